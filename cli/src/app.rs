@@ -76,6 +76,8 @@ pub enum Command {
     Rules(rules::Rules),
     /// Runs SQL queries against the data fusion service
     Sql(sql::Sql),
+    /// Live view of invocations, errors, queues and deployments
+    Top(top::Top),
     /// Download one of Restate's examples in this directory.
     #[clap(name = "example", alias = "examples")]
     Examples(examples::Examples),
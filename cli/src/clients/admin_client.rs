@@ -13,6 +13,8 @@
 use std::time::Duration;
 
 use anyhow::bail;
+use bytes::Bytes;
+use futures::Stream;
 use http::StatusCode;
 use serde::{Serialize, de::DeserializeOwned};
 use thiserror::Error;
@@ -102,6 +104,28 @@ where
     pub async fn into_text(self) -> Result<String, Error> {
         Ok(self.inner.text().await?)
     }
+
+    /// Returns the body as a stream of chunks, for responses too large to be buffered.
+    pub async fn into_bytes_stream(
+        self,
+    ) -> Result<impl Stream<Item = reqwest::Result<Bytes>>, Error> {
+        let http_status_code = self.inner.status();
+        let url = self.inner.url().clone();
+        if !http_status_code.is_success() {
+            let body = self.inner.text().await?;
+            info!("Response from {} ({})", url, http_status_code);
+            info!("  {}", body);
+            return Err(Error::Api(Box::new(ApiError {
+                http_status_code,
+                url,
+                body: serde_json::from_str(&body)?,
+            })));
+        }
+
+        debug!("Streaming response from {} ({})", url, http_status_code);
+        Ok(self.inner.bytes_stream())
+    }
+
     pub fn success_or_error(self) -> Result<StatusCode, Error> {
         let http_status_code = self.inner.status();
         let url = self.inner.url().clone();
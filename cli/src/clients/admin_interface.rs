@@ -17,6 +17,7 @@ use indicatif::ProgressBar;
 use restate_admin_rest_model::deployments::*;
 use restate_admin_rest_model::invocations::RestartAsNewInvocationResponse;
 use restate_admin_rest_model::kafka_clusters::*;
+use restate_admin_rest_model::query::{StateMutationsPlan, StateMutationsRequest};
 use restate_admin_rest_model::rules::*;
 use restate_admin_rest_model::services::*;
 use restate_admin_rest_model::subscriptions::*;
@@ -45,6 +46,19 @@ pub trait AdminClientInterface {
         name: &str,
         modify_service_request: ModifyServiceRequest,
     ) -> impl Future<Output = reqwest::Result<Envelope<ServiceMetadata>>> + Send + 'static;
+    fn get_traffic_split(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = reqwest::Result<Envelope<TrafficSplitResponse>>> + Send + 'static;
+    fn set_traffic_split(
+        &self,
+        name: &str,
+        traffic_split_request: TrafficSplitRequest,
+    ) -> impl Future<Output = reqwest::Result<Envelope<TrafficSplitResponse>>> + Send + 'static;
+    fn remove_traffic_split(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = reqwest::Result<Envelope<()>>> + Send + 'static;
     fn get_deployments(
         &self,
     ) -> impl Future<Output = reqwest::Result<Envelope<ListDeploymentsResponse>>> + Send + 'static;
@@ -58,6 +72,12 @@ pub trait AdminClientInterface {
         force: bool,
     ) -> impl Future<Output = reqwest::Result<Envelope<()>>> + Send + 'static;
 
+    fn drain_deployment(
+        &self,
+        id: &str,
+        auto_remove: bool,
+    ) -> impl Future<Output = reqwest::Result<Envelope<DetailedDeploymentResponse>>> + Send + 'static;
+
     fn discover_deployment(
         &self,
         body: RegisterDeploymentRequest,
@@ -100,6 +120,25 @@ pub trait AdminClientInterface {
         req: ModifyServiceStateRequest,
     ) -> impl Future<Output = reqwest::Result<Envelope<()>>> + Send + 'static;
 
+    /// Export the state of all the instances of a service, as newline-delimited JSON
+    fn export_state(
+        &self,
+        service: &str,
+    ) -> impl Future<Output = reqwest::Result<Envelope<()>>> + Send + 'static;
+
+    fn import_state(
+        &self,
+        service: &str,
+        req: ImportServiceStateRequest,
+    ) -> impl Future<Output = reqwest::Result<Envelope<ImportServiceStateResponse>>> + Send + 'static;
+
+    /// Plan and, unless it's a dry run, submit the state mutations of a DELETE or UPDATE
+    /// statement on the `state` table
+    fn state_mutations(
+        &self,
+        req: StateMutationsRequest,
+    ) -> impl Future<Output = reqwest::Result<Envelope<StateMutationsPlan>>> + Send + 'static;
+
     fn version(
         &self,
     ) -> impl Future<Output = reqwest::Result<Envelope<VersionInformation>>> + Send + 'static;
@@ -200,6 +239,33 @@ impl AdminClientInterface for AdminClient {
         self.run_with_body(reqwest::Method::PATCH, url, modify_service_request)
     }
 
+    fn get_traffic_split(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = reqwest::Result<Envelope<TrafficSplitResponse>>> + Send + 'static
+    {
+        let url = self.versioned_url(["services", name, "traffic-split"]);
+        self.run(reqwest::Method::GET, url)
+    }
+
+    fn set_traffic_split(
+        &self,
+        name: &str,
+        traffic_split_request: TrafficSplitRequest,
+    ) -> impl Future<Output = reqwest::Result<Envelope<TrafficSplitResponse>>> + Send + 'static
+    {
+        let url = self.versioned_url(["services", name, "traffic-split"]);
+        self.run_with_body(reqwest::Method::PUT, url, traffic_split_request)
+    }
+
+    fn remove_traffic_split(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = reqwest::Result<Envelope<()>>> + Send + 'static {
+        let url = self.versioned_url(["services", name, "traffic-split"]);
+        self.run(reqwest::Method::DELETE, url)
+    }
+
     fn get_deployments(
         &self,
     ) -> impl Future<Output = reqwest::Result<Envelope<ListDeploymentsResponse>>> + Send + 'static
@@ -228,6 +294,20 @@ impl AdminClientInterface for AdminClient {
         self.run(reqwest::Method::DELETE, url)
     }
 
+    fn drain_deployment(
+        &self,
+        id: &str,
+        auto_remove: bool,
+    ) -> impl Future<Output = reqwest::Result<Envelope<DetailedDeploymentResponse>>> + Send + 'static
+    {
+        let url = self.versioned_url(["deployments", id, "drain"]);
+        self.run_with_body(
+            reqwest::Method::POST,
+            url,
+            DrainDeploymentRequest { auto_remove },
+        )
+    }
+
     fn discover_deployment(
         &self,
         body: RegisterDeploymentRequest,
@@ -305,6 +385,32 @@ impl AdminClientInterface for AdminClient {
         self.run_with_body(reqwest::Method::POST, url, req)
     }
 
+    fn export_state(
+        &self,
+        service: &str,
+    ) -> impl Future<Output = reqwest::Result<Envelope<()>>> + Send + 'static {
+        let url = self.versioned_url(["services", service, "state"]);
+        self.run(reqwest::Method::GET, url)
+    }
+
+    fn import_state(
+        &self,
+        service: &str,
+        req: ImportServiceStateRequest,
+    ) -> impl Future<Output = reqwest::Result<Envelope<ImportServiceStateResponse>>> + Send + 'static
+    {
+        let url = self.versioned_url(["services", service, "state", "import"]);
+        self.run_with_body(reqwest::Method::POST, url, req)
+    }
+
+    fn state_mutations(
+        &self,
+        req: StateMutationsRequest,
+    ) -> impl Future<Output = reqwest::Result<Envelope<StateMutationsPlan>>> + Send + 'static {
+        let url = self.versioned_url(["query", "state-mutations"]);
+        self.run_with_body(reqwest::Method::POST, url, req)
+    }
+
     fn version(
         &self,
     ) -> impl Future<Output = reqwest::Result<Envelope<VersionInformation>>> + Send + 'static {
@@ -490,6 +596,7 @@ pub enum Deployment {
         metadata: HashMap<String, String>,
         sdk_version: Option<String>,
         auth: Option<restate_admin_rest_model::deployments::HttpAuth>,
+        drain: Option<DeploymentDrainResponse>,
     },
     Lambda {
         arn: LambdaARN,
@@ -500,6 +607,7 @@ pub enum Deployment {
         max_protocol_version: i32,
         metadata: HashMap<String, String>,
         sdk_version: Option<String>,
+        drain: Option<DeploymentDrainResponse>,
     },
 }
 
@@ -511,6 +619,13 @@ impl Deployment {
         }
     }
 
+    pub fn drain(&self) -> Option<&DeploymentDrainResponse> {
+        match self {
+            Self::Http { drain, .. } => drain.as_ref(),
+            Self::Lambda { drain, .. } => drain.as_ref(),
+        }
+    }
+
     pub fn from_deployment_response(
         deployment_response: DeploymentResponse,
     ) -> (DeploymentId, Self, Vec<ServiceNameRevPair>) {
@@ -528,6 +643,7 @@ impl Deployment {
                 metadata,
                 sdk_version,
                 auth,
+                drain,
                 ..
             } => (
                 id,
@@ -542,6 +658,7 @@ impl Deployment {
                     metadata,
                     sdk_version,
                     auth,
+                    drain,
                 },
                 services,
             ),
@@ -556,6 +673,7 @@ impl Deployment {
                 services,
                 metadata,
                 sdk_version,
+                drain,
                 ..
             } => (
                 id,
@@ -568,6 +686,7 @@ impl Deployment {
                     max_protocol_version,
                     metadata,
                     sdk_version,
+                    drain,
                 },
                 services,
             ),
@@ -591,6 +710,7 @@ impl Deployment {
                 metadata,
                 sdk_version,
                 auth,
+                drain,
                 ..
             } => (
                 id,
@@ -605,6 +725,7 @@ impl Deployment {
                     metadata,
                     sdk_version,
                     auth,
+                    drain,
                 },
                 services,
             ),
@@ -619,6 +740,7 @@ impl Deployment {
                 services,
                 metadata,
                 sdk_version,
+                drain,
                 ..
             } => (
                 id,
@@ -631,6 +753,7 @@ impl Deployment {
                     max_protocol_version,
                     metadata,
                     sdk_version,
+                    drain,
                 },
                 services,
             ),
@@ -0,0 +1,72 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use comfy_table::Table;
+
+use restate_cli_util::ui::console::{StyledTable, confirm_or_exit};
+use restate_cli_util::{c_println, c_success};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface, Deployment};
+use crate::ui::deployments::add_deployment_to_kv_table;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_drain")]
+pub struct Drain {
+    /// Remove the deployment automatically once no invocation is pinned to it anymore
+    #[clap(long)]
+    auto_remove: bool,
+    /// Deployment ID
+    deployment_id: String,
+}
+
+pub async fn run_drain(State(env): State<CliEnv>, opts: &Drain) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+
+    let deployment = client
+        .get_deployment(&opts.deployment_id)
+        .await?
+        .into_body()
+        .await?;
+    let (deployment_id, deployment, _) = Deployment::from_detailed_deployment_response(deployment);
+
+    let mut table = Table::new_styled();
+    table.add_kv_row("ID:", deployment_id);
+    add_deployment_to_kv_table(&deployment, &mut table);
+    c_println!("{}", table);
+    c_println!();
+
+    c_println!(
+        "New invocations won't be routed to this deployment anymore, invocations pinned to it \
+        will still complete on it."
+    );
+    if opts.auto_remove {
+        c_println!("The deployment will be removed once no invocation is pinned to it anymore.");
+    }
+    confirm_or_exit("Are you sure you want to drain this deployment?")?;
+
+    let deployment = client
+        .drain_deployment(&opts.deployment_id, opts.auto_remove)
+        .await?
+        .into_body()
+        .await?;
+    let (_, deployment, _) = Deployment::from_detailed_deployment_response(deployment);
+
+    c_println!();
+    c_success!("Deployment {} is draining", &opts.deployment_id);
+    if let Some(drain) = deployment.drain()
+        && let Some(pinned_invocations) = drain.pinned_invocations
+    {
+        c_println!("Pinned invocations: {pinned_invocations}");
+    }
+    Ok(())
+}
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 mod describe;
+mod drain;
 mod list;
 mod register;
 mod remove;
@@ -24,6 +25,8 @@ pub enum Deployments {
     Register(register::Register),
     /// Prints detailed information about a given deployment
     Describe(describe::Describe),
+    /// Stop routing new invocations to a deployment, optionally removing it once drained
+    Drain(drain::Drain),
     /// Remove a drained deployment
     Remove(remove::Remove),
 }
@@ -67,6 +67,14 @@ pub struct Patch {
     #[clap(long, alias = "abort_timeout", help = ABORT_TIMEOUT_EDIT_DESCRIPTION)]
     abort_timeout: Option<FriendlyDuration>,
 
+    /// Ratio of the invocations that are traced, between 0 and 1.
+    #[clap(long)]
+    tracing_sample_ratio: Option<f64>,
+
+    /// Emit a failure marker span for failed invocations that were not sampled.
+    #[clap(long)]
+    tracing_failure_markers: Option<bool>,
+
     /// Service name
     service: String,
 }
@@ -86,6 +94,8 @@ async fn patch(env: &CliEnv, opts: &Patch) -> Result<()> {
         journal_retention: opts.journal_retention.map(FriendlyDuration::to_std),
         inactivity_timeout: opts.inactivity_timeout.map(FriendlyDuration::to_std),
         abort_timeout: opts.abort_timeout.map(FriendlyDuration::to_std),
+        tracing_sample_ratio: opts.tracing_sample_ratio,
+        tracing_failure_markers: opts.tracing_failure_markers,
     };
 
     apply_service_configuration_patch(&opts.service, admin_client, modify_request).await
@@ -103,6 +113,8 @@ pub(super) async fn apply_service_configuration_patch(
         && modify_request.inactivity_timeout.is_none()
         && modify_request.journal_retention.is_none()
         && modify_request.abort_timeout.is_none()
+        && modify_request.tracing_sample_ratio.is_none()
+        && modify_request.tracing_failure_markers.is_none()
     {
         c_println!("No changes requested");
         return Ok(());
@@ -140,6 +152,12 @@ pub(super) async fn apply_service_configuration_patch(
     if let Some(abort_timeout) = &modify_request.abort_timeout {
         table.add_kv_row("Abort timeout:", abort_timeout.friendly().to_days_span());
     }
+    if let Some(tracing_sample_ratio) = &modify_request.tracing_sample_ratio {
+        table.add_kv_row("Tracing sample ratio:", tracing_sample_ratio);
+    }
+    if let Some(tracing_failure_markers) = &modify_request.tracing_failure_markers {
+        table.add_kv_row("Tracing failure markers:", tracing_failure_markers);
+    }
     c_println!("{table}");
     confirm_or_exit("Are you sure you want to apply these changes?")?;
 
@@ -12,6 +12,7 @@ mod config;
 mod describe;
 mod list;
 mod status;
+mod traffic_split;
 
 use cling::prelude::*;
 
@@ -28,4 +29,8 @@ pub enum Services {
     #[clap(name = "config", alias = "conf")]
     #[clap(subcommand)]
     Config(config::Config),
+    /// Split new invocations of a service between its deployments, e.g. for canary rollouts
+    #[clap(name = "traffic-split")]
+    #[clap(subcommand)]
+    TrafficSplit(traffic_split::TrafficSplit),
 }
@@ -0,0 +1,42 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+
+use restate_cli_util::ui::console::confirm_or_exit;
+use restate_cli_util::{c_println, c_success};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_clear")]
+pub struct Clear {
+    /// Service name
+    service: String,
+}
+
+pub async fn run_clear(State(env): State<CliEnv>, opts: &Clear) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+
+    c_println!(
+        "All new invocations of service {} will be routed to the deployment serving its latest \
+        revision.",
+        opts.service
+    );
+    confirm_or_exit("Are you sure you want to remove the traffic split?")?;
+
+    let result = client.remove_traffic_split(&opts.service).await?;
+    let _ = result.success_or_error()?;
+
+    c_success!("Traffic split of service {} removed", &opts.service);
+    Ok(())
+}
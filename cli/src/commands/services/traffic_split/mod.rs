@@ -0,0 +1,27 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+mod clear;
+mod set;
+mod show;
+
+use cling::prelude::*;
+
+#[derive(Run, Subcommand, Clone)]
+pub enum TrafficSplit {
+    /// Show how new invocations of a service are split between its deployments
+    #[clap(name = "show", alias = "get")]
+    Show(show::Show),
+    /// Route new invocations of a service between deployments proportionally to the given weights
+    Set(set::Set),
+    /// Remove the traffic split, routing new invocations to the latest deployment again
+    #[clap(name = "clear", alias = "rm")]
+    Clear(clear::Clear),
+}
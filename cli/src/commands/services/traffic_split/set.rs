@@ -0,0 +1,74 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::{Context, Result};
+use cling::prelude::*;
+
+use restate_admin_rest_model::services::{DeploymentWeightEntry, TrafficSplitRequest};
+use restate_cli_util::ui::console::confirm_or_exit;
+use restate_cli_util::{c_println, c_success};
+use restate_types::identifiers::DeploymentId;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+use super::show::traffic_split_table;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_set")]
+pub struct Set {
+    /// Service name
+    service: String,
+
+    /// Deployment weights, in the format `<DEPLOYMENT_ID>=<WEIGHT>`.
+    /// Deployments not listed receive no new invocations.
+    #[clap(required = true, value_parser = parse_deployment_weight)]
+    weights: Vec<DeploymentWeightEntry>,
+}
+
+fn parse_deployment_weight(s: &str) -> Result<DeploymentWeightEntry> {
+    let (deployment_id, weight) = s
+        .split_once('=')
+        .context("expected the format <DEPLOYMENT_ID>=<WEIGHT>")?;
+    Ok(DeploymentWeightEntry {
+        deployment_id: deployment_id
+            .trim()
+            .parse::<DeploymentId>()
+            .context("invalid deployment id")?,
+        weight: weight.trim().parse().context("invalid weight")?,
+    })
+}
+
+pub async fn run_set(State(env): State<CliEnv>, opts: &Set) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+
+    c_println!(
+        "New invocations of service {} will be routed between these deployments. Invocations \
+        already pinned to a deployment are not affected.",
+        opts.service
+    );
+    confirm_or_exit("Are you sure you want to apply this traffic split?")?;
+
+    let traffic_split = client
+        .set_traffic_split(
+            &opts.service,
+            TrafficSplitRequest {
+                weights: opts.weights.clone(),
+            },
+        )
+        .await?
+        .into_body()
+        .await?;
+
+    c_println!();
+    c_success!("Traffic split of service {} updated", &opts.service);
+    c_println!("{}", traffic_split_table(&traffic_split));
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Result;
+use cling::prelude::*;
+use comfy_table::Table;
+
+use restate_admin_rest_model::services::TrafficSplitResponse;
+use restate_cli_util::c_println;
+use restate_cli_util::ui::console::StyledTable;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_show")]
+pub struct Show {
+    /// Service name
+    service: String,
+}
+
+pub async fn run_show(State(env): State<CliEnv>, opts: &Show) -> Result<()> {
+    let client = AdminClient::new(&env).await?;
+    let traffic_split = client
+        .get_traffic_split(&opts.service)
+        .await?
+        .into_body()
+        .await?;
+
+    c_println!("{}", traffic_split_table(&traffic_split));
+    Ok(())
+}
+
+pub(super) fn traffic_split_table(traffic_split: &TrafficSplitResponse) -> Table {
+    let total_weight: u64 = traffic_split
+        .weights
+        .iter()
+        .map(|entry| u64::from(entry.weight))
+        .sum();
+
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec!["DEPLOYMENT", "WEIGHT", "SHARE"]);
+    for entry in &traffic_split.weights {
+        let share = if total_weight == 0 {
+            0.0
+        } else {
+            f64::from(entry.weight) * 100.0 / total_weight as f64
+        };
+        table.add_row(vec![
+            entry.deployment_id.to_string(),
+            entry.weight.to_string(),
+            format!("{share:.1}%"),
+        ]);
+    }
+    table
+}
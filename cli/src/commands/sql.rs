@@ -19,14 +19,18 @@ use cling::prelude::*;
 use comfy_table::Cell;
 use comfy_table::Table;
 
+use restate_admin_rest_model::query::{StateMutationsPlan, StateMutationsRequest};
 use restate_cli_util::c_eprintln;
 use restate_cli_util::c_println;
 use restate_cli_util::ui::console::Styled;
 use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::ui::console::confirm_or_exit;
 use restate_cli_util::ui::stylesheet::Style;
 use restate_cli_util::ui::watcher::Watch;
+use restate_cli_util::{c_success, c_title};
 
 use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
 
 #[derive(Run, Parser, Collect, Clone)]
 #[cling(run = "run_sql")]
@@ -44,9 +48,17 @@ pub struct Sql {
     /// Print result as json array instead of using the tabular format
     #[arg(long)]
     pub json: bool,
+
+    /// For DELETE and UPDATE statements on the `state` table, print the planned state mutations
+    /// without submitting them
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 pub async fn run_sql(State(env): State<CliEnv>, opts: &Sql) -> Result<()> {
+    if is_state_modification(&opts.query) {
+        return modify_state(&env, opts).await;
+    }
     opts.watch.run(|| run_query(&env, opts)).await
 }
 
@@ -114,3 +126,136 @@ async fn run_query(env: &CliEnv, sql_opts: &Sql) -> Result<()> {
     );
     Ok(())
 }
+
+async fn modify_state(env: &CliEnv, sql_opts: &Sql) -> Result<()> {
+    let client = AdminClient::new(env).await?;
+    let plan = client
+        .state_mutations(StateMutationsRequest {
+            query: sql_opts.query.clone(),
+            dry_run: true,
+        })
+        .await?
+        .into_body()
+        .await?;
+
+    if sql_opts.json {
+        c_println!("{}", serde_json::to_string_pretty(&plan)?);
+    } else if sql_opts.jsonl {
+        for mutation in &plan.mutations {
+            c_println!("{}", serde_json::to_string(mutation)?);
+        }
+    } else if !plan.mutations.is_empty() {
+        c_title!("ℹ️ ", "Planned state mutations");
+        c_println!("{}", changes_table(&plan));
+        c_println!();
+    }
+
+    if plan.mutations.is_empty() {
+        c_println!("No state entries match the statement.");
+        return Ok(());
+    }
+    if sql_opts.dry_run {
+        return Ok(());
+    }
+
+    c_println!(
+        "About to submit the state mutations of {} instance(s) to the system for processing. If there are ongoing invocations for an instance, its mutation will be enqueued to be processed after them.",
+        plan.mutations.len()
+    );
+    c_println!(
+        "The mutation of an instance is skipped if its state changes before the mutation is processed, re-run the statement to apply it."
+    );
+    confirm_or_exit("Are you sure?")?;
+
+    // The server plans the statement again and submits the resulting mutations
+    let submitted = client
+        .state_mutations(StateMutationsRequest {
+            query: sql_opts.query.clone(),
+            dry_run: false,
+        })
+        .await?
+        .into_body()
+        .await?;
+
+    c_println!();
+    c_success!(
+        "Submitted the state mutations of {} instance(s).",
+        submitted.mutations.len()
+    );
+
+    Ok(())
+}
+
+fn changes_table(plan: &StateMutationsPlan) -> Table {
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec![
+        "SERVICE",
+        "KEY",
+        "SCOPE",
+        "STATE KEY",
+        "OLD VALUE",
+        "NEW VALUE",
+    ]);
+    for mutation in &plan.mutations {
+        for change in &mutation.changes {
+            table.add_row(vec![
+                Cell::new(&mutation.service_name),
+                Cell::new(&mutation.request.object_key),
+                Cell::new(mutation.request.scope.as_deref().unwrap_or_default()),
+                Cell::new(&change.key),
+                Cell::new(display_value(change.old_value.as_deref())),
+                Cell::new(display_value(change.new_value.as_deref())),
+            ]);
+        }
+    }
+    table
+}
+
+fn display_value(value: Option<&[u8]>) -> String {
+    value
+        .map(|value| String::from_utf8_lossy(value).into_owned())
+        .unwrap_or_default()
+}
+
+/// Whether the statement is a DELETE or UPDATE, which the server only accepts on the `state` table
+fn is_state_modification(query: &str) -> bool {
+    let mut query = query.trim_start();
+    loop {
+        if let Some(rest) = query.strip_prefix("--") {
+            query = rest
+                .split_once('\n')
+                .map_or("", |(_, rest)| rest)
+                .trim_start();
+        } else if let Some(rest) = query.strip_prefix("/*") {
+            query = rest
+                .split_once("*/")
+                .map_or("", |(_, rest)| rest)
+                .trim_start();
+        } else {
+            break;
+        }
+    }
+
+    let keyword = query
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    keyword.eq_ignore_ascii_case("delete") || keyword.eq_ignore_ascii_case("update")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_state_modification;
+
+    #[test]
+    fn detects_state_modifications() {
+        assert!(is_state_modification("DELETE FROM state WHERE key = 'a'"));
+        assert!(is_state_modification("  update state SET value = ''"));
+        assert!(is_state_modification(
+            "-- fix\n/* corrupt key */ DELETE FROM state"
+        ));
+        assert!(!is_state_modification("SELECT * FROM state"));
+        assert!(!is_state_modification("-- DELETE\nSELECT 1"));
+        assert!(!is_state_modification(""));
+    }
+}
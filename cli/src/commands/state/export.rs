@@ -0,0 +1,105 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use bytes::BytesMut;
+use cling::prelude::*;
+use futures::StreamExt;
+
+use restate_admin_rest_model::services::ServiceStateEntry;
+use restate_cli_util::c_eprintln;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::commands::state::file_format::{ArrowStateWriter, StateFileFormat};
+
+/// Number of instances written in each record batch of Arrow files
+const ARROW_BATCH_SIZE: usize = 1024;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "export")]
+pub struct Export {
+    /// Service name
+    service: String,
+
+    /// File to write the exported state to, instead of stdout
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+
+    /// Format of the exported file
+    #[arg(long, value_enum, default_value_t)]
+    format: StateFileFormat,
+}
+
+pub async fn export(State(env): State<CliEnv>, opts: &Export) -> Result<()> {
+    let out: Box<dyn Write> = match &opts.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("Failed to create the file {}", path.display())
+            })?))
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let client = AdminClient::new(&env).await?;
+    let mut body = client
+        .export_state(&opts.service)
+        .await?
+        .into_bytes_stream()
+        .await?;
+
+    let mut instances = 0;
+    match opts.format {
+        StateFileFormat::Ndjson => {
+            let mut out = out;
+            while let Some(chunk) = body.next().await {
+                let chunk = chunk.context("Failed to read the exported state")?;
+                instances += chunk.iter().filter(|b| **b == b'\n').count();
+                out.write_all(&chunk)?;
+            }
+            out.flush()?;
+        }
+        StateFileFormat::Arrow => {
+            let mut writer = ArrowStateWriter::try_new(out)?;
+            let mut buffer = BytesMut::new();
+            let mut entries = Vec::with_capacity(ARROW_BATCH_SIZE);
+            while let Some(chunk) = body.next().await {
+                buffer.extend_from_slice(&chunk.context("Failed to read the exported state")?);
+                while let Some(newline) = buffer.iter().position(|b| *b == b'\n') {
+                    let line = buffer.split_to(newline + 1);
+                    entries.push(
+                        serde_json::from_slice::<ServiceStateEntry>(&line)
+                            .context("Unexpected state entry returned by the server")?,
+                    );
+                    if entries.len() == ARROW_BATCH_SIZE {
+                        instances += entries.len();
+                        writer.write(&entries)?;
+                        entries.clear();
+                    }
+                }
+            }
+            instances += entries.len();
+            writer.write(&entries)?;
+            writer.finish()?;
+        }
+    }
+
+    // Keep stdout clean, it might hold the exported state
+    c_eprintln!(
+        "Exported the state of {instances} instance(s) of {}",
+        opts.service
+    );
+
+    Ok(())
+}
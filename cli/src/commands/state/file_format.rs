@@ -0,0 +1,262 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Files produced by `restate state export` and consumed by `restate state import`.
+//!
+//! NDJSON files contain one [`ServiceStateEntry`] per line, as returned by the admin API. Arrow
+//! files use the IPC file format with one row per state key, the rows of an instance being
+//! adjacent.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Read, Seek, Write};
+use std::sync::{Arc, LazyLock};
+
+use anyhow::Context;
+use arrow::array::{Array, AsArray, BinaryBuilder, RecordBatch, StringBuilder};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use bytes::Bytes;
+use clap::ValueEnum;
+
+use restate_admin_rest_model::services::ServiceStateEntry;
+
+#[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum StateFileFormat {
+    /// One JSON object per instance, with base64 encoded values
+    #[default]
+    Ndjson,
+    /// Arrow IPC file, with one row per state key
+    Arrow,
+}
+
+static SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("scope", DataType::Utf8, true),
+        Field::new("object_key", DataType::Utf8, false),
+        Field::new("version", DataType::Utf8, false),
+        Field::new("key", DataType::Utf8, false),
+        Field::new("value", DataType::Binary, false),
+    ]))
+});
+
+/// Writes entries to an Arrow IPC file, one record batch per call to [`Self::write`].
+pub struct ArrowStateWriter<W: Write> {
+    writer: FileWriter<W>,
+}
+
+impl<W: Write> ArrowStateWriter<W> {
+    pub fn try_new(out: W) -> anyhow::Result<Self> {
+        Ok(Self {
+            writer: FileWriter::try_new(out, &SCHEMA)?,
+        })
+    }
+
+    pub fn write(&mut self, entries: &[ServiceStateEntry]) -> anyhow::Result<()> {
+        let mut scope = StringBuilder::new();
+        let mut object_key = StringBuilder::new();
+        let mut version = StringBuilder::new();
+        let mut key = StringBuilder::new();
+        let mut value = BinaryBuilder::new();
+
+        for entry in entries {
+            for (k, v) in &entry.state {
+                scope.append_option(entry.scope.as_deref());
+                object_key.append_value(&entry.object_key);
+                version.append_value(&entry.version);
+                key.append_value(k);
+                value.append_value(v);
+            }
+        }
+
+        let batch = RecordBatch::try_new(
+            SCHEMA.clone(),
+            vec![
+                Arc::new(scope.finish()),
+                Arc::new(object_key.finish()),
+                Arc::new(version.finish()),
+                Arc::new(key.finish()),
+                Arc::new(value.finish()),
+            ],
+        )?;
+        if batch.num_rows() > 0 {
+            self.writer.write(&batch)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        self.writer.finish()?;
+        Ok(())
+    }
+}
+
+/// Reads the entries of an NDJSON file one line at a time.
+pub fn read_ndjson(
+    reader: impl BufRead,
+) -> impl Iterator<Item = anyhow::Result<ServiceStateEntry>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter_map(|(line_number, line)| match line {
+            Ok(line) if line.trim().is_empty() => None,
+            Ok(line) => Some(
+                serde_json::from_str(&line)
+                    .with_context(|| format!("Invalid state entry at line {}", line_number + 1)),
+            ),
+            Err(err) => Some(Err(err).context("Failed to read the file")),
+        })
+}
+
+/// Reads an Arrow IPC file one record batch at a time, grouping the adjacent rows of the same
+/// instance into one entry.
+pub struct ArrowStateReader<R: Read + Seek> {
+    reader: FileReader<R>,
+    batch: Option<RecordBatch>,
+    row: usize,
+    current: Option<ServiceStateEntry>,
+}
+
+impl<R: Read + Seek> ArrowStateReader<R> {
+    pub fn try_new(reader: R) -> anyhow::Result<Self> {
+        let reader = FileReader::try_new(reader, None).context("Not a valid Arrow IPC file")?;
+        if reader.schema() != *SCHEMA {
+            anyhow::bail!(
+                "Unexpected schema of the Arrow file, expected {:?}",
+                SCHEMA.fields()
+            );
+        }
+
+        Ok(Self {
+            reader,
+            batch: None,
+            row: 0,
+            current: None,
+        })
+    }
+}
+
+impl<R: Read + Seek> Iterator for ArrowStateReader<R> {
+    type Item = anyhow::Result<ServiceStateEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(batch) = &self.batch else {
+                match self.reader.next()? {
+                    Ok(batch) => {
+                        self.batch = Some(batch);
+                        self.row = 0;
+                        continue;
+                    }
+                    Err(err) => return Some(Err(err).context("Failed to read the Arrow file")),
+                }
+            };
+            if self.row >= batch.num_rows() {
+                self.batch = None;
+                continue;
+            }
+            let row = self.row;
+            self.row += 1;
+
+            let scope = batch.column(0).as_string::<i32>();
+            let object_key = batch.column(1).as_string::<i32>();
+            let version = batch.column(2).as_string::<i32>();
+            let key = batch.column(3).as_string::<i32>();
+            let value = batch.column(4).as_binary::<i32>();
+
+            let row_scope = scope.is_valid(row).then(|| scope.value(row));
+            let row_object_key = object_key.value(row);
+
+            let is_same_instance = self.current.as_ref().is_some_and(|entry| {
+                entry.scope.as_deref() == row_scope && entry.object_key == row_object_key
+            });
+            let completed = if is_same_instance {
+                None
+            } else {
+                self.current.replace(ServiceStateEntry {
+                    object_key: row_object_key.to_owned(),
+                    scope: row_scope.map(str::to_owned),
+                    version: version.value(row).to_owned(),
+                    state: HashMap::new(),
+                })
+            };
+            self.current
+                .as_mut()
+                .expect("current entry is set")
+                .state
+                .insert(
+                    key.value(row).to_owned(),
+                    Bytes::copy_from_slice(value.value(row)),
+                );
+
+            if let Some(completed) = completed {
+                return Some(Ok(completed));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn entry(scope: Option<&str>, object_key: &str, state: &[(&str, &[u8])]) -> ServiceStateEntry {
+        ServiceStateEntry {
+            object_key: object_key.to_owned(),
+            scope: scope.map(str::to_owned),
+            version: format!("v-{object_key}"),
+            state: state
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), Bytes::copy_from_slice(v)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn arrow_roundtrip() {
+        let entries = vec![
+            entry(None, "a", &[("k1", b"1"), ("k2", b"2")]),
+            entry(Some("tenant"), "a", &[("k1", b"3")]),
+            entry(None, "b", &[("k1", b"")]),
+        ];
+
+        let mut buffer = Vec::new();
+        let mut writer = ArrowStateWriter::try_new(&mut buffer).unwrap();
+        writer.write(&entries[..2]).unwrap();
+        writer.write(&entries[2..]).unwrap();
+        writer.finish().unwrap();
+
+        let read = ArrowStateReader::try_new(Cursor::new(buffer))
+            .unwrap()
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(read, entries);
+    }
+
+    #[test]
+    fn ndjson_skips_empty_lines() {
+        let entries = vec![
+            entry(None, "a", &[("k1", b"1")]),
+            entry(Some("tenant"), "b", &[]),
+        ];
+        let mut ndjson = String::new();
+        for entry in &entries {
+            ndjson.push_str(&serde_json::to_string(entry).unwrap());
+            ndjson.push_str("\n\n");
+        }
+
+        let read = read_ndjson(Cursor::new(ndjson))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(read, entries);
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use cling::prelude::*;
+use comfy_table::{Cell, Table};
+
+use restate_admin_rest_model::services::{
+    ImportServiceStateRequest, ImportServiceStateResponse, STATE_IMPORT_MAX_BODY_SIZE,
+    STATE_IMPORT_MAX_ENTRIES, ServiceStateEntry,
+};
+use restate_cli_util::ui::console::{StyledTable, confirm_or_exit};
+use restate_cli_util::{c_println, c_success, c_title};
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface};
+use crate::commands::state::file_format::{ArrowStateReader, StateFileFormat, read_ndjson};
+
+/// Room left in each request for the fields other than the entries
+const REQUEST_OVERHEAD_BYTES: usize = 1024;
+/// Maximum number of skipped instances listed after the import
+const MAX_LISTED_SKIPPED: usize = 10;
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "import")]
+pub struct Import {
+    /// Service name
+    service: String,
+
+    /// File produced by `restate state export`
+    file: PathBuf,
+
+    /// Format of the file
+    #[arg(long, value_enum, default_value_t)]
+    format: StateFileFormat,
+
+    /// Replace the state of instances which already have some state. By default, these
+    /// instances are skipped.
+    #[arg(long)]
+    overwrite: bool,
+
+    /// Maximum number of instances sent to the server in each request. Requests are further
+    /// bounded by the server's request size limit.
+    #[arg(long, default_value_t = STATE_IMPORT_MAX_ENTRIES)]
+    batch_size: usize,
+}
+
+pub async fn import(State(env): State<CliEnv>, opts: &Import) -> Result<()> {
+    if opts.batch_size == 0 || opts.batch_size > STATE_IMPORT_MAX_ENTRIES {
+        bail!("--batch-size must be between 1 and {STATE_IMPORT_MAX_ENTRIES}");
+    }
+
+    let file = File::open(&opts.file)
+        .with_context(|| format!("Failed to open the file {}", opts.file.display()))?;
+    // The file is read while importing, so that only one batch is kept in memory
+    let entries: Box<dyn Iterator<Item = Result<ServiceStateEntry>>> = match opts.format {
+        StateFileFormat::Ndjson => Box::new(read_ndjson(BufReader::new(file))),
+        StateFileFormat::Arrow => Box::new(ArrowStateReader::try_new(file)?),
+    };
+
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec!["", ""]);
+    table.add_row(vec![Cell::new("Service"), Cell::new(&opts.service)]);
+    table.add_row(vec![Cell::new("File"), Cell::new(opts.file.display())]);
+    table.add_row(vec![Cell::new("Overwrite?"), Cell::new(opts.overwrite)]);
+
+    c_title!("ℹ️ ", "Import State");
+    c_println!("{table}");
+    c_println!();
+
+    c_println!("About to submit the state mutations to the system for processing.");
+    if opts.overwrite {
+        c_println!("The existing state of the imported instances will be replaced.");
+    } else {
+        c_println!("Instances which already have some state will be left untouched.");
+    }
+    c_println!(
+        "If there are ongoing invocations for an instance, its mutation will be enqueued to be processed after them."
+    );
+    c_println!();
+    confirm_or_exit("Are you sure?")?;
+
+    c_println!();
+
+    let client = AdminClient::new(&env).await?;
+    let max_batch_bytes = STATE_IMPORT_MAX_BODY_SIZE - REQUEST_OVERHEAD_BYTES;
+    let mut enqueued = 0;
+    let mut skipped = Vec::new();
+    let mut batch = Vec::with_capacity(opts.batch_size);
+    let mut batch_bytes = 0;
+    for entry in entries {
+        let entry = entry.with_context(|| {
+            format!("Failed to read the file, {enqueued} instance(s) were enqueued")
+        })?;
+        // Plus the separator between the entries
+        let entry_bytes = serde_json::to_vec(&entry)?.len() + 1;
+        if entry_bytes > max_batch_bytes {
+            bail!(
+                "The state of '{}' is larger than the request size limit of the server, \
+                 {enqueued} instance(s) were enqueued",
+                entry.object_key
+            );
+        }
+
+        if batch.len() == opts.batch_size || batch_bytes + entry_bytes > max_batch_bytes {
+            let response = submit(&client, opts, std::mem::take(&mut batch), enqueued).await?;
+            enqueued += response.enqueued;
+            skipped.extend(response.skipped);
+            batch_bytes = 0;
+        }
+        batch_bytes += entry_bytes;
+        batch.push(entry);
+    }
+    if !batch.is_empty() {
+        let response = submit(&client, opts, batch, enqueued).await?;
+        enqueued += response.enqueued;
+        skipped.extend(response.skipped);
+    }
+    if enqueued == 0 && skipped.is_empty() {
+        bail!("No state found in {}", opts.file.display());
+    }
+
+    c_success!("Enqueued the state of {enqueued} instance(s).");
+    if !skipped.is_empty() {
+        c_println!(
+            "Skipped {} instance(s) which already have some state, use --overwrite to replace it:",
+            skipped.len()
+        );
+        for entry in skipped.iter().take(MAX_LISTED_SKIPPED) {
+            match &entry.scope {
+                Some(scope) => c_println!(" - {} (scope {scope})", entry.object_key),
+                None => c_println!(" - {}", entry.object_key),
+            }
+        }
+        if skipped.len() > MAX_LISTED_SKIPPED {
+            c_println!(" ... and {} more", skipped.len() - MAX_LISTED_SKIPPED);
+        }
+    }
+
+    Ok(())
+}
+
+async fn submit(
+    client: &AdminClient,
+    opts: &Import,
+    entries: Vec<ServiceStateEntry>,
+    enqueued: usize,
+) -> Result<ImportServiceStateResponse> {
+    let req = ImportServiceStateRequest {
+        entries,
+        overwrite: opts.overwrite,
+    };
+    client
+        .import_state(&opts.service, req)
+        .await?
+        .into_body()
+        .await
+        .with_context(|| {
+            format!("Failed to import the state, {enqueued} instance(s) were enqueued")
+        })
+}
@@ -10,7 +10,8 @@
 
 use anyhow::Result;
 use cling::prelude::*;
-use comfy_table::Table;
+use comfy_table::{Cell, Table};
+use serde::Deserialize;
 use tracing::debug;
 
 use restate_cli_util::ui::console::StyledTable;
@@ -18,10 +19,29 @@ use restate_cli_util::ui::watcher::Watch;
 use restate_cli_util::{c_println, c_title};
 
 use crate::cli_env::CliEnv;
-use crate::clients::{AdminClient, AdminClientInterface};
+use crate::clients::{AdminClient, AdminClientInterface, DataFusionHttpClient};
 use crate::commands::kafkaclusters::utils as kc_shared;
 use crate::commands::subscriptions::kafka_cluster_from_source;
 
+#[derive(Deserialize)]
+struct ConsumerRow {
+    plain_node_id: String,
+    #[serde(default)]
+    topic: Option<String>,
+    #[serde(default)]
+    partition: Option<i32>,
+    #[serde(default)]
+    processed_offset: Option<u64>,
+    #[serde(default)]
+    committed_offset: Option<u64>,
+    #[serde(default)]
+    high_watermark: Option<u64>,
+    #[serde(default)]
+    lag: Option<u64>,
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
 #[derive(Run, Parser, Collect, Clone)]
 #[cling(run = "run_describe")]
 #[clap(visible_alias = "get")]
@@ -75,5 +95,69 @@ async fn describe(env: &CliEnv, opts: &Describe) -> Result<()> {
         c_println!("{}", kc_shared::render_properties_table(&sub.options));
     }
 
+    // Consumer progress is best-effort as well, e.g. older servers don't have the table.
+    match load_consumers(env, &sub.id.to_string()).await {
+        Ok(consumers) if !consumers.is_empty() => {
+            c_println!();
+            c_title!("📥", "Consumers");
+            c_println!("{}", render_consumers_table(consumers));
+        }
+        Ok(_) => {}
+        Err(e) => debug!(
+            "could not load Kafka consumers of subscription {}: {e}",
+            sub.id
+        ),
+    }
+
     Ok(())
 }
+
+async fn load_consumers(env: &CliEnv, subscription_id: &str) -> Result<Vec<ConsumerRow>> {
+    let client = DataFusionHttpClient::new(env).await?;
+    let rows = client
+        .run_json_query(format!(
+            "SELECT plain_node_id, topic, partition, processed_offset, committed_offset, \
+             high_watermark, lag, last_error \
+             FROM sys_kafka_consumer WHERE subscription_id = '{subscription_id}' \
+             ORDER BY topic, partition, plain_node_id"
+        ))
+        .await?;
+    Ok(rows)
+}
+
+fn render_consumers_table(consumers: Vec<ConsumerRow>) -> Table {
+    fn render_offset(offset: Option<u64>) -> String {
+        offset
+            .map(|o| o.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    let mut table = Table::new_styled();
+    table.set_styled_header(vec![
+        "NODE",
+        "TOPIC",
+        "PARTITION",
+        "PROCESSED",
+        "COMMITTED",
+        "HIGH WATERMARK",
+        "LAG",
+        "LAST ERROR",
+    ]);
+    for row in consumers {
+        table.add_row(vec![
+            Cell::new(row.plain_node_id),
+            Cell::new(row.topic.unwrap_or_else(|| "-".to_string())),
+            Cell::new(
+                row.partition
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            Cell::new(render_offset(row.processed_offset)),
+            Cell::new(render_offset(row.committed_offset)),
+            Cell::new(render_offset(row.high_watermark)),
+            Cell::new(render_offset(row.lag)),
+            Cell::new(row.last_error.unwrap_or_default()),
+        ]);
+    }
+    table
+}
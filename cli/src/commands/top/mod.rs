@@ -0,0 +1,316 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! `restate top`, a live view of the cluster refreshed from the SQL endpoint.
+
+mod screen;
+mod snapshot;
+mod view;
+
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use cling::prelude::*;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+use restate_admin_rest_model::version::AdminApiVersion;
+
+use crate::cli_env::CliEnv;
+use crate::clients::{AdminClient, AdminClientInterface, DataFusionHttpClient};
+
+use self::screen::Screen;
+use self::snapshot::{InvocationDetails, Snapshot};
+
+const MAX_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_top")]
+pub struct Top {
+    /// Refresh interval in seconds. Each refresh runs several aggregations over the system
+    /// tables, keep it conservative on large clusters.
+    #[clap(short = 'n', long, default_value = "10.0")]
+    interval: f32,
+
+    /// Maximum number of rows shown in the invocations, errors and vqueues panels
+    #[clap(long, default_value_t = 5)]
+    limit: usize,
+}
+
+pub async fn run_top(State(env): State<CliEnv>, opts: &Top) -> Result<()> {
+    let interval = match Duration::try_from_secs_f32(opts.interval) {
+        Ok(interval) if !interval.is_zero() && interval <= MAX_INTERVAL => interval,
+        _ => bail!(
+            "--interval must be a number of seconds between 0 and {}",
+            MAX_INTERVAL.as_secs()
+        ),
+    };
+
+    // Entered first, so that the output of the clients goes to the screen buffer
+    let screen = Screen::enter()?;
+    let client = AdminClient::new(&env).await?;
+    let sql_client = DataFusionHttpClient::from(client.clone());
+
+    let mut events = spawn_event_reader();
+    let mut app = App::new(opts.clone());
+
+    let mut refresh = tokio::time::interval(interval);
+    refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = refresh.tick() => app.refresh(&client, &sql_client).await,
+            event = events.recv() => {
+                let Some(event) = event else {
+                    // The terminal can no longer be read
+                    break;
+                };
+                if app.handle_event(event, &client, &sql_client).await.is_break() {
+                    break;
+                }
+            }
+        }
+        view::render(&app);
+        screen.draw()?;
+    }
+
+    Ok(())
+}
+
+/// Reads the terminal events on a dedicated thread, until the receiver is dropped.
+fn spawn_event_reader() -> mpsc::UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !tx.is_closed() {
+            match crossterm::event::poll(Duration::from_millis(100)) {
+                Ok(false) => {}
+                Ok(true) => match crossterm::event::read() {
+                    Ok(event) => {
+                        let _ = tx.send(event);
+                    }
+                    Err(_) => break,
+                },
+                Err(_) => break,
+            }
+        }
+    });
+    rx
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Pause,
+    Resume,
+    Kill,
+}
+
+impl Action {
+    fn verb(self) -> &'static str {
+        match self {
+            Action::Pause => "pause",
+            Action::Resume => "resume",
+            Action::Kill => "kill",
+        }
+    }
+}
+
+enum Page {
+    Overview,
+    Invocation {
+        id: String,
+        details: Option<Result<Box<InvocationDetails>>>,
+    },
+}
+
+/// Outcome of the last action, shown until the next key press.
+enum Notice {
+    Success(String),
+    Error(String),
+}
+
+struct App {
+    opts: Top,
+    snapshot: Option<Snapshot>,
+    /// The invocation selected in the invocations panel of the overview
+    selected: Option<String>,
+    page: Page,
+    /// Action waiting for the user to confirm it
+    pending: Option<(Action, String)>,
+    notice: Option<Notice>,
+}
+
+impl App {
+    fn new(opts: Top) -> Self {
+        Self {
+            opts,
+            snapshot: None,
+            selected: None,
+            page: Page::Overview,
+            pending: None,
+            notice: None,
+        }
+    }
+
+    async fn refresh(&mut self, client: &AdminClient, sql_client: &DataFusionHttpClient) {
+        let snapshot = Snapshot::fetch(client, sql_client, self.opts.limit).await;
+        if let Ok(invocations) = &snapshot.invocations {
+            let ids: Vec<&str> = invocations.iter().map(|inv| inv.id.as_str()).collect();
+            self.selected = move_selection(&ids, self.selected.as_deref(), 0).map(str::to_owned);
+        }
+        self.snapshot = Some(snapshot);
+
+        self.refresh_details(sql_client).await;
+    }
+
+    async fn refresh_details(&mut self, sql_client: &DataFusionHttpClient) {
+        if let Page::Invocation { id, details } = &mut self.page {
+            *details = Some(
+                InvocationDetails::fetch(sql_client, id, self.opts.limit)
+                    .await
+                    .map(Box::new),
+            );
+        }
+    }
+
+    async fn handle_event(
+        &mut self,
+        event: Event,
+        client: &AdminClient,
+        sql_client: &DataFusionHttpClient,
+    ) -> ControlFlow<()> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            // Redraw on resize
+            return ControlFlow::Continue(());
+        };
+
+        if code == KeyCode::Char('q')
+            || (code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL))
+        {
+            return ControlFlow::Break(());
+        }
+        self.notice = None;
+
+        if let Some((action, invocation_id)) = self.pending.take() {
+            if matches!(code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.notice = Some(run_action(client, action, &invocation_id).await);
+                self.refresh(client, sql_client).await;
+            }
+            return ControlFlow::Continue(());
+        }
+
+        match code {
+            KeyCode::Up | KeyCode::Down => {
+                if let Some(Ok(invocations)) = self.snapshot.as_ref().map(|s| &s.invocations) {
+                    let ids: Vec<&str> = invocations.iter().map(|inv| inv.id.as_str()).collect();
+                    let offset = if code == KeyCode::Up { -1 } else { 1 };
+                    self.selected =
+                        move_selection(&ids, self.selected.as_deref(), offset).map(str::to_owned);
+                }
+            }
+            KeyCode::Enter => {
+                if matches!(self.page, Page::Overview)
+                    && let Some(id) = self.selected.clone()
+                {
+                    self.page = Page::Invocation { id, details: None };
+                    self.refresh_details(sql_client).await;
+                }
+            }
+            KeyCode::Esc | KeyCode::Backspace => self.page = Page::Overview,
+            KeyCode::Char('p') => self.confirm(Action::Pause),
+            KeyCode::Char('r') => self.confirm(Action::Resume),
+            KeyCode::Char('k') => self.confirm(Action::Kill),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// The invocation the actions apply to: the one being shown, or the selected one.
+    fn target_invocation(&self) -> Option<&str> {
+        match &self.page {
+            Page::Overview => self.selected.as_deref(),
+            Page::Invocation { id, .. } => Some(id),
+        }
+    }
+
+    fn confirm(&mut self, action: Action) {
+        if let Some(id) = self.target_invocation() {
+            self.pending = Some((action, id.to_owned()));
+        }
+    }
+}
+
+async fn run_action(client: &AdminClient, action: Action, invocation_id: &str) -> Notice {
+    if action == Action::Pause && client.admin_api_version < AdminApiVersion::V3 {
+        return Notice::Error(
+            "Pausing invocations requires admin API version 3 or later (Restate server v1.6+)"
+                .to_owned(),
+        );
+    }
+
+    let result: Result<()> = async {
+        let envelope = match action {
+            Action::Pause => client.pause_invocation(invocation_id).await?,
+            Action::Resume => client.resume_invocation(invocation_id, None).await?,
+            Action::Kill => client.kill_invocation(invocation_id).await?,
+        };
+        envelope.success_or_error()?;
+        Ok(())
+    }
+    .await;
+    match result {
+        Ok(()) => Notice::Success(format!(
+            "Requested to {} invocation {invocation_id}",
+            action.verb()
+        )),
+        Err(err) => Notice::Error(format!(
+            "Failed to {} invocation {invocation_id}: {err}",
+            action.verb()
+        )),
+    }
+}
+
+/// Moves the selection by `offset` rows, staying within the list. If the selected invocation is
+/// no longer listed, the first one is selected.
+fn move_selection<'a>(ids: &[&'a str], selected: Option<&str>, offset: isize) -> Option<&'a str> {
+    let current = selected.and_then(|selected| ids.iter().position(|id| *id == selected));
+    let index = match current {
+        Some(current) => current
+            .saturating_add_signed(offset)
+            .min(ids.len().saturating_sub(1)),
+        None => 0,
+    };
+    ids.get(index).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_stays_within_the_list() {
+        let ids = ["inv_a", "inv_b", "inv_c"];
+
+        assert_eq!(move_selection(&ids, Some("inv_b"), 1), Some("inv_c"));
+        assert_eq!(move_selection(&ids, Some("inv_c"), 1), Some("inv_c"));
+        assert_eq!(move_selection(&ids, Some("inv_a"), -1), Some("inv_a"));
+        assert_eq!(move_selection(&ids, Some("inv_b"), 0), Some("inv_b"));
+        // The selected invocation is gone
+        assert_eq!(move_selection(&ids, Some("inv_z"), 0), Some("inv_a"));
+        assert_eq!(move_selection(&ids, None, 1), Some("inv_a"));
+        assert_eq!(move_selection(&[], Some("inv_a"), 0), None);
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::io::{self, Write};
+
+use crossterm::cursor::{self, MoveTo};
+use crossterm::style::{Print, ResetColor};
+use crossterm::terminal::{
+    self, BeginSynchronizedUpdate, Clear, ClearType, DisableLineWrap, EnableLineWrap,
+    EndSynchronizedUpdate, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+
+use restate_cli_util::ui::output::{self, Console};
+
+/// Full-screen terminal in raw mode, restored when dropped.
+///
+/// The console output is redirected to an in-memory buffer, which is drawn on each call to
+/// [`Screen::draw`].
+pub(super) struct Screen;
+
+impl Screen {
+    pub(super) fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        // From here on, the terminal is restored on drop
+        let screen = Self;
+        execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            DisableLineWrap,
+            cursor::Hide,
+            Clear(ClearType::All)
+        )?;
+
+        let console = Console::in_memory();
+        output::set_stdout(console.clone());
+        output::set_stderr(console);
+        Ok(screen)
+    }
+
+    /// Draws what was printed to the console since the last draw, truncated to fit the screen.
+    pub(super) fn draw(&self) -> io::Result<()> {
+        let Some(buffer) = output::stdout().take_buffer() else {
+            return Ok(());
+        };
+        let rows = usize::from(terminal::size()?.1);
+        let lines: Vec<&str> = buffer.lines().collect();
+
+        let mut lock = io::stdout().lock();
+        queue!(lock, BeginSynchronizedUpdate)?;
+        let shown = if lines.len() > rows {
+            // Keep the last row to tell the output was truncated
+            rows.saturating_sub(1)
+        } else {
+            lines.len()
+        };
+        for (row, line) in lines.iter().take(shown).enumerate() {
+            queue!(
+                lock,
+                MoveTo(0, row as u16),
+                Print(line),
+                ResetColor,
+                Clear(ClearType::UntilNewLine)
+            )?;
+        }
+        if shown < lines.len() {
+            queue!(
+                lock,
+                MoveTo(0, shown as u16),
+                Print("(output truncated to fit the screen, use --limit to show fewer rows)"),
+                Clear(ClearType::UntilNewLine)
+            )?;
+        } else if shown < rows {
+            queue!(
+                lock,
+                MoveTo(0, shown as u16),
+                Clear(ClearType::FromCursorDown)
+            )?;
+        }
+        queue!(lock, EndSynchronizedUpdate)?;
+        lock.flush()
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            EnableLineWrap,
+            cursor::Show,
+            ResetColor
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
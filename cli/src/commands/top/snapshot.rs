@@ -0,0 +1,294 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! The data shown by `restate top`, fetched from the SQL endpoint on every refresh.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+
+use restate_admin_rest_model::deployments::ServiceNameRevPair;
+use restate_types::identifiers::DeploymentId;
+use restate_types::schema::service::ServiceMetadata;
+
+use crate::clients::datafusion_helpers::{Invocation, find_active_invocations, get_invocation};
+use crate::clients::{AdminClient, AdminClientInterface, DataFusionHttpClient, Deployment};
+use crate::ui::deployments::{DeploymentStatus, calculate_deployment_status};
+
+/// Each panel is fetched independently, so that a failing query (e.g. a table missing on an
+/// older server) doesn't hide the other panels.
+pub struct Snapshot {
+    pub fetched_at: DateTime<Local>,
+    pub services: Result<Vec<ServiceInvocationCounts>>,
+    pub invocations: Result<Vec<Invocation>>,
+    pub errors: Result<Vec<ErrorSummary>>,
+    pub vqueues: Result<Vec<VQueueDepth>>,
+    pub deployments: Result<Vec<DeploymentSummary>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ServiceInvocationCounts {
+    pub service: String,
+    pub running: i64,
+    pub backing_off: i64,
+    pub paused: i64,
+    pub suspended: i64,
+    pub queued: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorSummary {
+    pub error_code: Option<i64>,
+    pub error_message: Option<String>,
+    pub occurrences: i64,
+    pub invocations: i64,
+    pub last_seen: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VQueueDepth {
+    pub id: String,
+    pub service_name: Option<String>,
+    pub lock_name: Option<String>,
+    pub is_paused: Option<bool>,
+    pub inbox: i64,
+    pub running: i64,
+    pub paused: i64,
+    pub suspended: i64,
+}
+
+pub struct DeploymentSummary {
+    pub id: DeploymentId,
+    pub deployment: Deployment,
+    pub services: Vec<ServiceNameRevPair>,
+    pub active_invocations: i64,
+    pub status: DeploymentStatus,
+}
+
+impl Snapshot {
+    pub async fn fetch(
+        client: &AdminClient,
+        sql_client: &DataFusionHttpClient,
+        limit: usize,
+    ) -> Self {
+        let (services, invocations, errors, vqueues, deployments) = tokio::join!(
+            fetch_service_counts(sql_client),
+            fetch_invocations(sql_client, limit),
+            fetch_errors(sql_client, limit),
+            fetch_vqueues(sql_client, limit),
+            fetch_deployments(client, sql_client),
+        );
+
+        Self {
+            fetched_at: Local::now(),
+            services,
+            invocations,
+            errors,
+            vqueues,
+            deployments,
+        }
+    }
+}
+
+async fn fetch_service_counts(
+    sql_client: &DataFusionHttpClient,
+) -> Result<Vec<ServiceInvocationCounts>> {
+    let query = "SELECT
+            target_service_name AS service,
+            SUM(CASE WHEN status = 'running' THEN 1 ELSE 0 END) AS running,
+            SUM(CASE WHEN status = 'backing-off' THEN 1 ELSE 0 END) AS backing_off,
+            SUM(CASE WHEN status = 'paused' THEN 1 ELSE 0 END) AS paused,
+            SUM(CASE WHEN status = 'suspended' THEN 1 ELSE 0 END) AS suspended,
+            SUM(CASE WHEN status IN ('pending', 'scheduled', 'ready') THEN 1 ELSE 0 END) AS queued
+        FROM sys_invocation
+        WHERE status != 'completed'
+        GROUP BY target_service_name
+        ORDER BY target_service_name";
+    Ok(sql_client
+        .run_json_query::<ServiceInvocationCounts>(query.to_owned())
+        .await?)
+}
+
+/// The invocations which can be drilled into, the ones needing attention first.
+async fn fetch_invocations(
+    sql_client: &DataFusionHttpClient,
+    limit: usize,
+) -> Result<Vec<Invocation>> {
+    let (invocations, _) = find_active_invocations(
+        sql_client,
+        "WHERE inv.status IN ('backing-off', 'paused', 'running')",
+        "ORDER BY CASE inv.status WHEN 'backing-off' THEN 0 WHEN 'paused' THEN 1 ELSE 2 END, \
+         inv.modified_at DESC, inv.id",
+        limit,
+    )
+    .await?;
+    Ok(invocations)
+}
+
+async fn fetch_errors(
+    sql_client: &DataFusionHttpClient,
+    limit: usize,
+) -> Result<Vec<ErrorSummary>> {
+    let query = format!(
+        "SELECT
+            json_get_int(event_json, 'error_code') AS error_code,
+            json_get_str(event_json, 'error_message') AS error_message,
+            COUNT(1) AS occurrences,
+            COUNT(DISTINCT id) AS invocations,
+            MAX(appended_at) AS last_seen
+        FROM sys_journal_events
+        WHERE event_type = 'TransientError'
+        GROUP BY 1, 2
+        ORDER BY occurrences DESC
+        LIMIT {limit}"
+    );
+    Ok(sql_client.run_json_query::<ErrorSummary>(query).await?)
+}
+
+async fn fetch_vqueues(
+    sql_client: &DataFusionHttpClient,
+    limit: usize,
+) -> Result<Vec<VQueueDepth>> {
+    let query = format!(
+        "WITH depths AS (SELECT
+            partition_key,
+            id,
+            SUM(CASE WHEN stage = 'inbox' THEN 1 ELSE 0 END) AS inbox,
+            SUM(CASE WHEN stage = 'running' THEN 1 ELSE 0 END) AS running,
+            SUM(CASE WHEN stage = 'paused' THEN 1 ELSE 0 END) AS paused,
+            SUM(CASE WHEN stage = 'suspended' THEN 1 ELSE 0 END) AS suspended
+        FROM sys_vqueues
+        WHERE stage != 'finished'
+        GROUP BY partition_key, id
+        ORDER BY inbox DESC, running DESC
+        LIMIT {limit})
+
+        SELECT
+            d.id,
+            m.service_name,
+            m.lock_name,
+            m.queue_is_paused AS is_paused,
+            d.inbox,
+            d.running,
+            d.paused,
+            d.suspended
+        FROM depths d
+        LEFT JOIN sys_vqueue_meta m ON m.partition_key = d.partition_key AND m.id = d.id
+        ORDER BY d.inbox DESC, d.running DESC"
+    );
+    Ok(sql_client.run_json_query::<VQueueDepth>(query).await?)
+}
+
+async fn fetch_deployments(
+    client: &AdminClient,
+    sql_client: &DataFusionHttpClient,
+) -> Result<Vec<DeploymentSummary>> {
+    #[derive(Deserialize)]
+    struct ActiveInvocationsRow {
+        deployment_id: String,
+        inv_count: i64,
+    }
+
+    let latest_services: HashMap<String, ServiceMetadata> = client
+        .get_services()
+        .await?
+        .into_body()
+        .await?
+        .services
+        .into_iter()
+        .map(|service| (service.name.clone(), service))
+        .collect();
+    let deployments = client
+        .get_deployments()
+        .await?
+        .into_body()
+        .await?
+        .deployments;
+
+    let active_invocations: HashMap<String, i64> = sql_client
+        .run_json_query::<ActiveInvocationsRow>(
+            "SELECT pinned_deployment_id AS deployment_id, COUNT(1) AS inv_count
+            FROM sys_invocation_status
+            WHERE pinned_deployment_id IS NOT NULL AND status != 'completed'
+            GROUP BY pinned_deployment_id"
+                .to_owned(),
+        )
+        .await?
+        .into_iter()
+        .map(|row| (row.deployment_id, row.inv_count))
+        .collect();
+
+    let mut summaries: Vec<_> = deployments
+        .into_iter()
+        .map(|deployment| {
+            let (id, deployment, services) = Deployment::from_deployment_response(deployment);
+            let active_invocations = active_invocations
+                .get(&id.to_string())
+                .copied()
+                .unwrap_or_default();
+            let status =
+                calculate_deployment_status(&id, &services, active_invocations, &latest_services);
+            DeploymentSummary {
+                id,
+                deployment,
+                services,
+                active_invocations,
+                status,
+            }
+        })
+        .collect();
+    // Newest first, as in `restate deployments list`
+    summaries.sort_unstable_by_key(|summary| std::cmp::Reverse(summary.deployment.created_at()));
+
+    Ok(summaries)
+}
+
+/// The invocation shown when drilling into the invocations panel.
+pub struct InvocationDetails {
+    /// `None` if the invocation is gone, e.g. completed and not retained
+    pub invocation: Option<Invocation>,
+    pub events: Result<Vec<JournalEvent>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JournalEvent {
+    pub appended_at: DateTime<Local>,
+    pub event_type: String,
+    pub error_message: Option<String>,
+}
+
+impl InvocationDetails {
+    pub async fn fetch(
+        sql_client: &DataFusionHttpClient,
+        invocation_id: &str,
+        limit: usize,
+    ) -> Result<Self> {
+        let (invocation, events) = tokio::join!(
+            get_invocation(sql_client, invocation_id),
+            sql_client.run_json_query::<JournalEvent>(format!(
+                "SELECT
+                    appended_at,
+                    event_type,
+                    json_get_str(event_json, 'error_message') AS error_message
+                FROM sys_journal_events
+                WHERE id = '{invocation_id}'
+                ORDER BY appended_at DESC
+                LIMIT {limit}"
+            )),
+        );
+
+        Ok(Self {
+            invocation: invocation?,
+            events: events.map_err(Into::into),
+        })
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Renders the pages of `restate top` to the console, which is buffered and drawn by the screen.
+
+use anyhow::Result;
+use comfy_table::{Attribute, Cell, Color, Table};
+use dialoguer::console::style;
+
+use restate_cli_util::ui::console::StyledTable;
+use restate_cli_util::ui::duration_to_human_rough;
+use restate_cli_util::{c_error, c_indent_table, c_println, c_success, c_title};
+
+use crate::ui::deployments::{
+    render_active_invocations, render_deployment_status, render_deployment_type,
+    render_deployment_url,
+};
+use crate::ui::invocations::{
+    add_invocation_to_kv_table, invocation_status, invocation_status_note,
+};
+
+use super::snapshot::{InvocationDetails, Snapshot};
+use super::{App, Notice, Page};
+
+/// Error messages are cut to keep one row per error
+const MAX_ERROR_MESSAGE_LENGTH: usize = 100;
+
+pub(super) fn render(app: &App) {
+    render_header(app);
+
+    match &app.page {
+        Page::Overview => match &app.snapshot {
+            Some(snapshot) => render_overview(snapshot, app.selected.as_deref()),
+            None => c_println!("Loading..."),
+        },
+        Page::Invocation { id, details } => match details {
+            Some(Ok(details)) => render_invocation(id, details),
+            Some(Err(err)) => c_error!("Failed to load invocation {id}: {err}"),
+            None => c_println!("Loading invocation {id}..."),
+        },
+    }
+}
+
+fn render_header(app: &App) {
+    let last_update = app
+        .snapshot
+        .as_ref()
+        .map(|snapshot| snapshot.fetched_at.format("%X").to_string())
+        .unwrap_or_else(|| "-".to_owned());
+    c_println!(
+        "{} {}",
+        style("restate top").bold(),
+        style(format!(
+            "- refreshing every {}s, last update {last_update}",
+            app.opts.interval
+        ))
+        .dim()
+    );
+
+    let keys = match app.page {
+        Page::Overview => "↑/↓ select  enter describe  p pause  r resume  k kill  q quit",
+        Page::Invocation { .. } => "esc back  p pause  r resume  k kill  q quit",
+    };
+    c_println!("{}", style(keys).dim());
+
+    if let Some((action, invocation_id)) = &app.pending {
+        c_println!(
+            "{}",
+            style(format!(
+                "Are you sure you want to {} invocation {invocation_id}? [y/N]",
+                action.verb()
+            ))
+            .yellow()
+            .bold()
+        );
+    } else {
+        match &app.notice {
+            Some(Notice::Success(msg)) => c_success!("{msg}"),
+            Some(Notice::Error(msg)) => c_error!("{msg}"),
+            None => c_println!(),
+        }
+    }
+}
+
+fn render_overview(snapshot: &Snapshot, selected: Option<&str>) {
+    c_title!("📷", "Invocations by Service");
+    render_panel(&snapshot.services, "No active invocations", |services| {
+        let mut table = Table::new_styled();
+        table.set_styled_header(vec![
+            "SERVICE",
+            "RUNNING",
+            "BACKING-OFF",
+            "PAUSED",
+            "SUSPENDED",
+            "QUEUED",
+        ]);
+        for service in services {
+            table.add_row(vec![
+                Cell::new(&service.service).add_attribute(Attribute::Bold),
+                Cell::new(service.running),
+                count_cell(service.backing_off, Color::Red),
+                count_cell(service.paused, Color::Yellow),
+                Cell::new(service.suspended),
+                Cell::new(service.queued),
+            ]);
+        }
+        table
+    });
+
+    c_title!("🚂", "Invocations");
+    render_panel(
+        &snapshot.invocations,
+        "No running, backing-off or paused invocations",
+        |invocations| {
+            let mut table = Table::new_styled();
+            table.set_styled_header(vec!["", "ID", "TARGET", "STATUS"]);
+            for inv in invocations {
+                let is_selected = selected == Some(inv.id.as_str());
+                let mut id = Cell::new(&inv.id);
+                if is_selected {
+                    id = id.add_attribute(Attribute::Reverse);
+                }
+                table.add_row(vec![
+                    Cell::new(if is_selected { "❯" } else { "" }),
+                    id,
+                    Cell::new(&inv.target),
+                    Cell::new(format!(
+                        "{}{}",
+                        invocation_status(inv.status),
+                        invocation_status_note(inv)
+                    )),
+                ]);
+            }
+            table
+        },
+    );
+
+    c_title!("🔥", "Top Errors");
+    render_panel(&snapshot.errors, "No transient errors", |errors| {
+        let mut table = Table::new_styled();
+        table.set_styled_header(vec!["CODE", "COUNT", "INVOCATIONS", "LAST SEEN", "MESSAGE"]);
+        for error in errors {
+            table.add_row(vec![
+                Cell::new(
+                    error
+                        .error_code
+                        .map(|code| code.to_string())
+                        .unwrap_or_default(),
+                ),
+                Cell::new(error.occurrences),
+                Cell::new(error.invocations),
+                Cell::new(
+                    error
+                        .last_seen
+                        .map(|last_seen| {
+                            duration_to_human_rough(
+                                chrono::Local::now().signed_duration_since(last_seen),
+                                chrono_humanize::Tense::Past,
+                            )
+                        })
+                        .unwrap_or_default(),
+                ),
+                Cell::new(truncate_message(
+                    error.error_message.as_deref().unwrap_or_default(),
+                ))
+                .fg(Color::Red),
+            ]);
+        }
+        table
+    });
+
+    c_title!("📨", "VQueues");
+    render_panel(&snapshot.vqueues, "No queued entries", |vqueues| {
+        let mut table = Table::new_styled();
+        table.set_styled_header(vec![
+            "VQUEUE",
+            "SERVICE",
+            "LOCK",
+            "INBOX",
+            "RUNNING",
+            "PAUSED",
+            "SUSPENDED",
+        ]);
+        for vqueue in vqueues {
+            let mut id = Cell::new(&vqueue.id);
+            if vqueue.is_paused == Some(true) {
+                id = Cell::new(format!("{} (paused)", vqueue.id)).fg(Color::Yellow);
+            }
+            table.add_row(vec![
+                id,
+                Cell::new(vqueue.service_name.as_deref().unwrap_or_default()),
+                Cell::new(vqueue.lock_name.as_deref().unwrap_or_default()),
+                Cell::new(vqueue.inbox),
+                Cell::new(vqueue.running),
+                count_cell(vqueue.paused, Color::Yellow),
+                Cell::new(vqueue.suspended),
+            ]);
+        }
+        table
+    });
+
+    c_title!("🚀", "Deployments");
+    render_panel(&snapshot.deployments, "No deployments", |deployments| {
+        let mut table = Table::new_styled();
+        table.set_styled_header(vec![
+            "DEPLOYMENT",
+            "TYPE",
+            "STATUS",
+            "ACTIVE INVOCATIONS",
+            "ID",
+            "SERVICES",
+        ]);
+        for summary in deployments {
+            table.add_row(vec![
+                Cell::new(render_deployment_url(&summary.deployment)),
+                Cell::new(render_deployment_type(&summary.deployment)),
+                render_deployment_status(summary.status),
+                render_active_invocations(summary.active_invocations),
+                Cell::new(summary.id),
+                Cell::new(
+                    summary
+                        .services
+                        .iter()
+                        .map(|service| format!("{} [{}]", service.name, service.revision))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            ]);
+        }
+        table
+    });
+}
+
+fn render_invocation(id: &str, details: &InvocationDetails) {
+    let Some(inv) = &details.invocation else {
+        c_println!("Invocation {id} is no longer active.");
+        return;
+    };
+
+    c_title!("📜", format!("Invocation {id}"));
+    let mut table = Table::new_styled();
+    table.add_kv_row(
+        "Created at:",
+        format!(
+            "{} ({})",
+            &inv.created_at,
+            duration_to_human_rough(
+                chrono::Local::now().signed_duration_since(inv.created_at),
+                chrono_humanize::Tense::Past
+            )
+        ),
+    );
+    add_invocation_to_kv_table(&mut table, inv);
+    c_indent_table!(0, table);
+
+    c_title!("🔥", "Recent Events");
+    render_panel(&details.events, "No events", |events| {
+        let mut table = Table::new_styled();
+        table.set_styled_header(vec!["APPENDED AT", "EVENT", "MESSAGE"]);
+        for event in events {
+            table.add_row(vec![
+                Cell::new(event.appended_at),
+                Cell::new(&event.event_type),
+                Cell::new(truncate_message(
+                    event.error_message.as_deref().unwrap_or_default(),
+                ))
+                .fg(Color::Red),
+            ]);
+        }
+        table
+    });
+}
+
+/// Renders the rows of a panel, or why they couldn't be loaded.
+fn render_panel<T>(rows: &Result<Vec<T>>, empty: &str, to_table: impl FnOnce(&[T]) -> Table) {
+    match rows {
+        Ok(rows) if rows.is_empty() => c_println!("{}", style(empty).dim()),
+        Ok(rows) => c_indent_table!(0, to_table(rows)),
+        Err(err) => c_println!("{}", style(format!("Failed to load: {err}")).red()),
+    }
+}
+
+fn count_cell(count: i64, color: Color) -> Cell {
+    if count > 0 {
+        Cell::new(count).fg(color)
+    } else {
+        Cell::new(count)
+    }
+}
+
+fn truncate_message(message: &str) -> String {
+    let first_line = message.lines().next().unwrap_or_default();
+    match first_line.char_indices().nth(MAX_ERROR_MESSAGE_LENGTH) {
+        Some((end, _)) => format!("{}…", &first_line[..end]),
+        None => first_line.to_owned(),
+    }
+}
@@ -209,6 +209,24 @@ pub fn add_deployment_to_kv_table(deployment: &Deployment, table: &mut Table) {
         );
     }
 
+    if let Some(drain) = deployment.drain() {
+        table.add_kv_row("Draining since:", drain.started_at.display());
+        if let Some(drained_at) = &drain.drained_at {
+            table.add_kv_row("Drained at:", drained_at.display());
+        }
+        table.add_kv_row(
+            "Pinned invocations:",
+            drain
+                .pinned_invocations
+                .map(|count| count.to_string())
+                .unwrap_or_else(|| "(unknown)".to_owned()),
+        );
+        table.add_kv_row(
+            "Remove when drained:",
+            if drain.auto_remove { "yes" } else { "no" },
+        );
+    }
+
     // Additional metadata is printed nicely when possible
     for (key, value) in metadata.iter() {
         match deployment::metadata::MetadataKey::try_from(key.as_str()) {
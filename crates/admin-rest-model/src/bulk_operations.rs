@@ -0,0 +1,159 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+use restate_types::identifiers::BulkOperationId;
+
+use crate::invocations::{FailedInvocationOperation, PatchDeploymentId};
+
+/// Maximum number of failed invocations reported in a bulk operation summary.
+pub const BULK_OPERATION_MAX_REPORTED_FAILURES: usize = 100;
+
+/// Operation applied to every invocation matched by a bulk operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum BulkOperationKind {
+    Kill,
+    Cancel,
+    Purge,
+    PurgeJournal,
+    Pause,
+    Resume,
+    RestartAsNew,
+}
+
+/// Request body to start a bulk operation.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct CreateBulkOperationRequest {
+    /// # Operation
+    ///
+    /// Operation to apply to every matched invocation.
+    pub operation: BulkOperationKind,
+    /// # Filter
+    ///
+    /// SQL predicate over the `sys_invocation` table selecting the invocations to operate on,
+    /// e.g. `status = 'paused' AND target_service_name = 'Greeter'`. It must be a single boolean
+    /// expression over the columns of `sys_invocation`, subqueries are not supported. Use `true`
+    /// to select all invocations.
+    pub filter: String,
+    /// # Limit
+    ///
+    /// Maximum number of invocations to operate on. By default, all matched invocations are
+    /// processed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    /// # Deployment
+    ///
+    /// Deployment to use for `resume` and `restart-as-new` operations. Use "Keep" to keep the
+    /// pinned deployment, "Latest" for latest, or a specific deployment ID. Defaults to "Keep"
+    /// for `resume` and "Latest" for `restart-as-new`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployment: Option<PatchDeploymentId>,
+}
+
+/// State of a bulk operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum BulkOperationState {
+    /// The operation is processing the matched invocations.
+    Running,
+    /// The operation was paused and can be resumed.
+    Paused,
+    /// The operation was cancelled before all matched invocations were processed.
+    Cancelled,
+    /// All matched invocations were processed.
+    Completed,
+    /// The operation stopped because of an error, see the `error` field.
+    Failed,
+}
+
+impl BulkOperationState {
+    /// Whether the operation stopped and won't make further progress.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            BulkOperationState::Cancelled
+                | BulkOperationState::Completed
+                | BulkOperationState::Failed
+        )
+    }
+}
+
+/// Progress and result summary of a bulk operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct BulkOperationResponse {
+    /// # Id
+    ///
+    /// Identifier of the bulk operation.
+    pub id: BulkOperationId,
+    /// # Operation
+    pub operation: BulkOperationKind,
+    /// # Filter
+    ///
+    /// SQL predicate used to select the invocations.
+    pub filter: String,
+    /// # State
+    pub state: BulkOperationState,
+    /// # Created at
+    ///
+    /// When the bulk operation was started.
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schema(value_type = String))]
+    pub created_at: humantime::Timestamp,
+    /// # Finished at
+    ///
+    /// When the bulk operation completed, failed or was cancelled.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schema(value_type = Option<String>))]
+    pub finished_at: Option<humantime::Timestamp>,
+    /// # Matched
+    ///
+    /// Number of invocations matched by the filter so far. The matching invocations are queried
+    /// in pages of 1000 while the operation runs.
+    pub matched: u64,
+    /// # Processed
+    ///
+    /// Number of invocations the operation was applied to, successfully or not.
+    pub processed: u64,
+    /// # Succeeded
+    pub succeeded: u64,
+    /// # Failed
+    pub failed: u64,
+    /// # Failures
+    ///
+    /// Details of the first failed invocations, up to 100 entries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<FailedInvocationOperation>,
+    /// # Error
+    ///
+    /// Reason why the operation failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// List of bulk operations.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+pub struct ListBulkOperationsResponse {
+    /// Running operations of this admin node, followed by the recent operations of the cluster,
+    /// newest first.
+    pub operations: Vec<BulkOperationResponse>,
+}
@@ -12,12 +12,13 @@ use http::{Uri, Version};
 use restate_serde_util::SerdeableHeaderHashMap;
 use restate_types::identifiers::ServiceRevision;
 use restate_types::identifiers::{DeploymentId, LambdaARN};
-use restate_types::schema::deployment::{EndpointLambdaCompression, ProtocolType};
+use restate_types::schema::deployment::{DeploymentDrain, EndpointLambdaCompression, ProtocolType};
 use restate_types::schema::info::SchemaInfo;
 use restate_types::schema::service::ServiceMetadata;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::HashMap;
+use std::time::SystemTime;
 
 /// HTTP authentication details.
 #[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
@@ -358,6 +359,12 @@ pub enum DeploymentResponse {
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         info: Vec<SchemaInfo>,
 
+        /// # Drain
+        ///
+        /// Set if the deployment is being drained, see `POST /deployments/{deployment}/drain`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        drain: Option<DeploymentDrainResponse>,
+
         /// # Authentication
         ///
         /// Per-deployment authentication, if configured.
@@ -429,6 +436,12 @@ pub enum DeploymentResponse {
         /// List of configuration/deprecation information related to this deployment.
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         info: Vec<SchemaInfo>,
+
+        /// # Drain
+        ///
+        /// Set if the deployment is being drained, see `POST /deployments/{deployment}/drain`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        drain: Option<DeploymentDrainResponse>,
     },
 }
 
@@ -514,6 +527,12 @@ pub enum DetailedDeploymentResponse {
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         info: Vec<SchemaInfo>,
 
+        /// # Drain
+        ///
+        /// Set if the deployment is being drained, see `POST /deployments/{deployment}/drain`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        drain: Option<DeploymentDrainResponse>,
+
         /// # Authentication
         ///
         /// Per-deployment authentication, if configured.
@@ -586,6 +605,12 @@ pub enum DetailedDeploymentResponse {
         /// List of configuration/deprecation information related to this deployment.
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         info: Vec<SchemaInfo>,
+
+        /// # Drain
+        ///
+        /// Set if the deployment is being drained, see `POST /deployments/{deployment}/drain`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        drain: Option<DeploymentDrainResponse>,
     },
 }
 
@@ -598,6 +623,63 @@ impl DetailedDeploymentResponse {
     }
 }
 
+/// Drain state of a deployment.
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeploymentDrainResponse {
+    /// # Started at
+    ///
+    /// When the deployment started draining.
+    #[serde(with = "serde_with::As::<serde_with::DisplayFromStr>")]
+    #[cfg_attr(feature = "schema", schema(value_type = String))]
+    pub started_at: humantime::Timestamp,
+
+    /// # Auto remove
+    ///
+    /// If true, the deployment is removed once no invocation is pinned to it anymore.
+    pub auto_remove: bool,
+
+    /// # Drained at
+    ///
+    /// When the drain checks observed that no invocation is pinned to this deployment anymore.
+    #[serde(
+        default,
+        with = "serde_with::As::<Option<serde_with::DisplayFromStr>>",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schema", schema(value_type = Option<String>))]
+    pub drained_at: Option<humantime::Timestamp>,
+
+    /// # Pinned invocations
+    ///
+    /// Number of in-flight invocations still pinned to this deployment. Computed on demand when
+    /// fetching a single deployment, not set when listing deployments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_invocations: Option<u64>,
+}
+
+impl From<DeploymentDrain> for DeploymentDrainResponse {
+    fn from(value: DeploymentDrain) -> Self {
+        Self {
+            started_at: SystemTime::from(value.started_at).into(),
+            auto_remove: value.auto_remove,
+            drained_at: value.drained_at.map(|t| SystemTime::from(t).into()),
+            pinned_invocations: None,
+        }
+    }
+}
+
+/// Request body to drain a deployment.
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DrainDeploymentRequest {
+    /// # Auto remove
+    ///
+    /// If true, the deployment is removed automatically once no invocation is pinned to it anymore.
+    #[serde(default)]
+    pub auto_remove: bool,
+}
+
 #[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
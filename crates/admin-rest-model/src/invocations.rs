@@ -88,7 +88,7 @@ pub struct BatchRestartAsNewRequest {
 }
 
 /// Information about a failed invocation operation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
 pub struct FailedInvocationOperation {
     /// The invocation ID that failed
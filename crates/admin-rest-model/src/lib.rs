@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub mod bulk_operations;
 pub mod deployments;
 pub mod handlers;
 pub mod invocations;
@@ -8,9 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use serde::Deserialize;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::services::ModifyServiceStateRequest;
+
 #[serde_as]
 #[derive(Debug, Deserialize)]
 #[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
@@ -18,3 +21,70 @@ pub struct QueryRequest {
     /// SQL query to run against the storage
     pub query: String,
 }
+
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateMutationsRequest {
+    /// `DELETE` or `UPDATE` statement on the `state` table
+    pub query: String,
+
+    /// # Dry run
+    ///
+    /// If set, the state mutations are only planned and returned, without submitting them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+/// The state mutations of a `DELETE` or `UPDATE` statement on the `state` table.
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateMutationsPlan {
+    /// # Mutations
+    ///
+    /// One mutation per affected Virtual Object or Workflow instance.
+    pub mutations: Vec<PlannedStateMutation>,
+
+    /// # Submitted
+    ///
+    /// Whether the mutations were submitted to the cluster. `false` for dry runs.
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlannedStateMutation {
+    /// # Service name
+    pub service_name: String,
+
+    /// # Request
+    ///
+    /// The new state of the instance, as submitted to the modify state endpoint of the service.
+    /// Its version is the one of the state the mutation was computed from, so the mutation is
+    /// skipped if the state of the instance changes before it is applied.
+    pub request: ModifyServiceStateRequest,
+
+    /// # Changes
+    ///
+    /// The affected keys, with their current and new values.
+    pub changes: Vec<StateChange>,
+}
+
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateChange {
+    /// # Key
+    pub key: String,
+
+    /// # Old value
+    ///
+    /// The current value of the key.
+    #[cfg_attr(feature = "schema", schema(value_type = Option<Vec<u8>>))]
+    pub old_value: Option<Bytes>,
+
+    /// # New value
+    ///
+    /// The value of the key after the mutation, absent if the key is deleted.
+    #[cfg_attr(feature = "schema", schema(value_type = Option<Vec<u8>>))]
+    pub new_value: Option<Bytes>,
+}
@@ -11,9 +11,13 @@
 use std::collections::HashMap;
 use std::time::Duration;
 
+use base64::Engine;
 use bytes::Bytes;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::serde_as;
 
+use restate_types::identifiers::DeploymentId;
+use restate_types::schema::deployment::{DeploymentWeight, TrafficSplit};
 use restate_types::schema::service::ServiceMetadata;
 use restate_util_time::FriendlyDuration;
 
@@ -91,10 +95,26 @@ pub struct ModifyServiceRequest {
     /// This overrides the default abort timeout set in invoker options.
     #[serde(default, with = "serde_with::As::<Option<FriendlyDuration>>")]
     pub abort_timeout: Option<Duration>,
+
+    /// # Tracing sample ratio
+    ///
+    /// Ratio of the invocations of this service that are traced, between 0 and 1. Only applies to invocations that start a new trace.
+    ///
+    /// This sets the `restate.tracing.sample-ratio` service metadata. Handler metadata takes precedence.
+    #[serde(default)]
+    pub tracing_sample_ratio: Option<f64>,
+
+    /// # Tracing failure markers
+    ///
+    /// If true, failed invocations that were not sampled emit a standalone failure marker span. The other spans of these invocations are not recorded.
+    ///
+    /// This sets the `restate.tracing.failure-markers` service metadata. Handler metadata takes precedence.
+    #[serde(default)]
+    pub tracing_failure_markers: Option<bool>,
 }
 
 #[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModifyServiceStateRequest {
     /// # Version
     ///
@@ -120,3 +140,184 @@ pub struct ModifyServiceStateRequest {
     #[cfg_attr(feature = "schema", schema(value_type = HashMap<String, Vec<u8>>))]
     pub new_state: HashMap<String, Bytes>,
 }
+
+/// The state of a single Virtual Object or Workflow instance.
+///
+/// The state export endpoint streams one entry per instance as newline-delimited JSON, the state
+/// import endpoint accepts the same entries.
+#[serde_as]
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceStateEntry {
+    /// # Service key
+    ///
+    /// The key of the Virtual Object or Workflow instance.
+    pub object_key: String,
+
+    /// # Scope
+    ///
+    /// The scope of the instance, if scoped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    /// # Version
+    ///
+    /// The version of `state`, computed like the version accepted by the modify state endpoint.
+    /// The import endpoint rejects entries whose state doesn't match this version.
+    pub version: String,
+
+    /// # State
+    ///
+    /// The state key-value pairs. Values are encoded as URL-safe base64.
+    #[serde_as(as = "HashMap<_, Base64Value>")]
+    #[cfg_attr(feature = "schema", schema(value_type = HashMap<String, String>))]
+    pub state: HashMap<String, Bytes>,
+}
+
+/// Encodes state values as URL-safe base64 strings, so that exports don't depend on the
+/// serialization format used by the service.
+struct Base64Value;
+
+impl serde_with::SerializeAs<Bytes> for Base64Value {
+    fn serialize_as<S: Serializer>(value: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&restate_base64_util::URL_SAFE.encode(value))
+    }
+}
+
+impl<'de> serde_with::DeserializeAs<'de, Bytes> for Base64Value {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        restate_base64_util::URL_SAFE
+            .decode(encoded)
+            .map(Bytes::from)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Maximum number of entries in a single state import request
+pub const STATE_IMPORT_MAX_ENTRIES: usize = 1000;
+
+/// Maximum size in bytes of the body of a single state import request
+pub const STATE_IMPORT_MAX_BODY_SIZE: usize = 32 * 1024 * 1024;
+
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportServiceStateRequest {
+    /// # Entries
+    ///
+    /// The state of the instances to import, as returned by the state export endpoint.
+    pub entries: Vec<ServiceStateEntry>,
+
+    /// # Overwrite
+    ///
+    /// If false, an entry is applied only if the instance has no state yet, otherwise it is
+    /// skipped. If true, the existing state of the instance is replaced.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportServiceStateResponse {
+    /// # Enqueued
+    ///
+    /// Number of entries which were enqueued to be applied by the cluster. The state mutations
+    /// are applied asynchronously.
+    pub enqueued: usize,
+
+    /// # Skipped
+    ///
+    /// Entries which were not enqueued because `overwrite` isn't set and their instance already
+    /// has some state. An instance which gets some state after this check is skipped as well when
+    /// its mutation is applied, without being listed here.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedServiceStateEntry>,
+}
+
+/// An entry of a state import which was skipped.
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedServiceStateEntry {
+    /// # Service key
+    ///
+    /// The key of the Virtual Object or Workflow instance.
+    pub object_key: String,
+
+    /// # Scope
+    ///
+    /// The scope of the instance, if scoped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Weighted routing of new invocations of a service between its deployments.
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficSplitRequest {
+    /// # Weights
+    ///
+    /// Relative weights of the deployments receiving new invocations of this service.
+    /// Every deployment must serve the service, and at least one weight must be positive.
+    /// Deployments not listed here, including deployments registered later, receive no new invocations.
+    pub weights: Vec<DeploymentWeightEntry>,
+}
+
+/// Traffic split configured for a service.
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficSplitResponse {
+    /// # Service name
+    pub service: String,
+
+    /// # Weights
+    ///
+    /// Relative weights of the deployments receiving new invocations of this service.
+    pub weights: Vec<DeploymentWeightEntry>,
+}
+
+impl TrafficSplitResponse {
+    pub fn new(service: String, traffic_split: TrafficSplit) -> Self {
+        Self {
+            service,
+            weights: traffic_split.weights.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "schema", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DeploymentWeightEntry {
+    /// # Deployment ID
+    pub deployment_id: DeploymentId,
+
+    /// # Weight
+    ///
+    /// Relative weight of this deployment. A weight of 0 keeps the deployment in the split without routing new invocations to it.
+    pub weight: u32,
+}
+
+impl From<DeploymentWeight> for DeploymentWeightEntry {
+    fn from(value: DeploymentWeight) -> Self {
+        Self {
+            deployment_id: value.deployment_id,
+            weight: value.weight,
+        }
+    }
+}
+
+impl From<DeploymentWeightEntry> for DeploymentWeight {
+    fn from(value: DeploymentWeightEntry) -> Self {
+        Self {
+            deployment_id: value.deployment_id,
+            weight: value.weight,
+        }
+    }
+}
+
+impl From<TrafficSplitRequest> for TrafficSplit {
+    fn from(value: TrafficSplitRequest) -> Self {
+        Self {
+            weights: value.weights.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -0,0 +1,797 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Query-driven bulk operations on invocations.
+//!
+//! A bulk operation selects invocations with a SQL predicate over `sys_invocation` and applies
+//! the same operation (kill, cancel, purge, ...) to each of them from a background task. The
+//! matching invocations are queried in pages ordered by id, so that only one page of ids is held
+//! in memory at a time.
+//!
+//! Operations run on the admin node which started them and can only be paused, resumed and
+//! cancelled there. Their progress is persisted in the metadata store after every page, so that
+//! it can be retrieved from every admin node. Operations are not resumed when their admin node
+//! stops: once the node is gone, the operation is reported as failed.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use datafusion::arrow::array::AsArray;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::{LogicalPlan, LogicalPlanBuilder};
+use datafusion::prelude::{col, lit};
+use futures::{StreamExt, future};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+use restate_admin_rest_model::bulk_operations::{
+    BULK_OPERATION_MAX_REPORTED_FAILURES, BulkOperationKind, BulkOperationResponse,
+    BulkOperationState,
+};
+use restate_admin_rest_model::invocations::{BATCH_OPERATION_MAX_SIZE, FailedInvocationOperation};
+use restate_core::{Metadata, ShutdownError, TaskCenter, TaskKind, my_node_id};
+use restate_metadata_store::{MetadataStoreClient, ReadError, ReadModifyWriteError};
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::GenerationalNodeId;
+use restate_types::identifiers::{BulkOperationId, InvocationId, PartitionProcessorRpcRequestId};
+use restate_types::invocation::client::{
+    self, CancelInvocationResponse, InvocationClient, KillInvocationResponse, PatchDeploymentId,
+    PauseInvocationResponse, PurgeInvocationResponse, ResumeInvocationResponse,
+};
+use restate_types::journal_v2::EntryIndex;
+use restate_types::metadata_store::keys::BULK_OPERATIONS_KEY;
+use restate_types::{Version, Versioned, flexbuffers_storage_encode_decode};
+
+/// Number of invocations a bulk operation operates on concurrently.
+const BULK_OPERATION_CONCURRENCY: usize = 64;
+/// Number of invocation ids queried at once.
+const BULK_OPERATION_PAGE_SIZE: usize = BATCH_OPERATION_MAX_SIZE;
+/// Number of operations kept in the metadata store.
+const MAX_RETAINED_OPERATIONS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+struct RunningOperation {
+    progress: Arc<Mutex<BulkOperationResponse>>,
+    control: watch::Sender<Control>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum StartBulkOperationError {
+    #[error(transparent)]
+    Shutdown(#[from] ShutdownError),
+    #[error("failed persisting the bulk operation: {0}")]
+    MetadataStore(#[from] ReadModifyWriteError<Infallible>),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum BulkOperationControlError {
+    #[error("bulk operation '{0}' not found")]
+    NotFound(BulkOperationId),
+    #[error("bulk operation '{0}' is {1}")]
+    Finished(BulkOperationId, BulkOperationState),
+    #[error("bulk operation '{0}' is running on node {1}")]
+    RunningOnOtherNode(BulkOperationId, GenerationalNodeId),
+    #[error(transparent)]
+    MetadataStore(#[from] ReadError),
+}
+
+/// Bulk operations of the cluster, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BulkOperationHistory {
+    version: Version,
+    operations: Vec<PersistedBulkOperation>,
+}
+
+impl Default for BulkOperationHistory {
+    fn default() -> Self {
+        Self {
+            version: Version::INVALID,
+            operations: Vec::new(),
+        }
+    }
+}
+
+impl Versioned for BulkOperationHistory {
+    fn version(&self) -> Version {
+        self.version
+    }
+}
+
+flexbuffers_storage_encode_decode!(BulkOperationHistory);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedBulkOperation {
+    /// Admin node running the operation.
+    owner: GenerationalNodeId,
+    operation: BulkOperationResponse,
+}
+
+impl PersistedBulkOperation {
+    /// Whether the operation stopped, either because it finished or because its admin node is
+    /// gone. Must only be called for operations which aren't running on this node.
+    fn is_stopped(&self) -> bool {
+        // Operations of this node are tracked as running until they are persisted as finished,
+        // hence unfinished operations of this node were started before it restarted.
+        self.operation.state.is_finished()
+            || self.owner == my_node_id()
+            || Metadata::with_current(|m| m.nodes_config_ref())
+                .find_node_by_id(self.owner)
+                .is_err()
+    }
+
+    /// Returns the operation, reported as failed if it was lost with its admin node.
+    fn into_response(self) -> BulkOperationResponse {
+        let is_lost = !self.operation.state.is_finished() && self.is_stopped();
+        let mut operation = self.operation;
+        if is_lost {
+            operation.state = BulkOperationState::Failed;
+            operation.error = Some(format!(
+                "the operation was lost because its admin node {} stopped",
+                self.owner
+            ));
+        }
+        operation
+    }
+}
+
+#[derive(Clone)]
+pub struct BulkOperations {
+    metadata_store_client: MetadataStoreClient,
+    running: Arc<Mutex<HashMap<BulkOperationId, RunningOperation>>>,
+}
+
+impl BulkOperations {
+    pub fn new(metadata_store_client: MetadataStoreClient) -> Self {
+        Self {
+            metadata_store_client,
+            running: Arc::default(),
+        }
+    }
+
+    /// Starts applying `operation` to all invocations returned by `invocations`, a plan over
+    /// `sys_invocation` as returned by [`QueryContext::filter_table`].
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn start<I>(
+        &self,
+        invocation_client: I,
+        query_context: QueryContext,
+        operation: BulkOperationKind,
+        filter: String,
+        invocations: LogicalPlan,
+        limit: Option<u64>,
+        deployment: Option<PatchDeploymentId>,
+    ) -> Result<BulkOperationResponse, StartBulkOperationError>
+    where
+        I: InvocationClient + Clone + Send + Sync + 'static,
+    {
+        let id = BulkOperationId::new();
+        let progress = BulkOperationResponse {
+            id,
+            operation,
+            filter,
+            state: BulkOperationState::Running,
+            created_at: SystemTime::now().into(),
+            finished_at: None,
+            matched: 0,
+            processed: 0,
+            succeeded: 0,
+            failed: 0,
+            failures: Vec::new(),
+            error: None,
+        };
+        // persist before starting, so that the operation is visible to all admin nodes
+        self.persist(progress.clone()).await?;
+
+        let progress = Arc::new(Mutex::new(progress));
+        let (control_tx, control_rx) = watch::channel(Control::Run);
+
+        // register before spawning, so that the task always finds its entry when finishing
+        self.running.lock().insert(
+            id,
+            RunningOperation {
+                progress: Arc::clone(&progress),
+                control: control_tx,
+            },
+        );
+
+        let task = BulkOperationTask {
+            id,
+            bulk_operations: self.clone(),
+            invocation_client,
+            query_context,
+            operation,
+            invocations,
+            limit,
+            deployment,
+            progress: Arc::clone(&progress),
+            control: control_rx,
+        };
+        let spawned = TaskCenter::spawn(
+            TaskKind::Disposable,
+            "bulk-invocation-operation",
+            async move {
+                task.run().await;
+                Ok(())
+            },
+        );
+
+        if let Err(err) = spawned {
+            self.running.lock().remove(&id);
+            return Err(err.into());
+        }
+
+        let snapshot = progress.lock().clone();
+        Ok(snapshot)
+    }
+
+    /// Returns the operation with the given id, if it is running on this node or was persisted.
+    pub(crate) async fn get(
+        &self,
+        id: BulkOperationId,
+    ) -> Result<Option<BulkOperationResponse>, ReadError> {
+        if let Some(running) = self.running.lock().get(&id) {
+            return Ok(Some(running.progress.lock().clone()));
+        }
+
+        Ok(self
+            .get_persisted(id)
+            .await?
+            .map(PersistedBulkOperation::into_response))
+    }
+
+    /// Lists the operations running on this node followed by the persisted operations, newest
+    /// first.
+    pub(crate) async fn list(&self) -> Result<Vec<BulkOperationResponse>, ReadError> {
+        let mut operations: Vec<_> = self
+            .running
+            .lock()
+            .values()
+            .map(|running| running.progress.lock().clone())
+            .collect();
+        // ids are ULIDs, hence sort by creation time
+        operations.sort_by(|a, b| b.id.cmp(&a.id));
+
+        let history = self.history().await?;
+        let persisted: Vec<_> = history
+            .operations
+            .into_iter()
+            .filter(|persisted| !operations.iter().any(|op| op.id == persisted.operation.id))
+            .map(PersistedBulkOperation::into_response)
+            .collect();
+        operations.extend(persisted);
+
+        Ok(operations)
+    }
+
+    pub(crate) async fn pause(
+        &self,
+        id: BulkOperationId,
+    ) -> Result<BulkOperationResponse, BulkOperationControlError> {
+        self.control(id, Control::Pause, BulkOperationState::Paused)
+            .await
+    }
+
+    pub(crate) async fn resume(
+        &self,
+        id: BulkOperationId,
+    ) -> Result<BulkOperationResponse, BulkOperationControlError> {
+        self.control(id, Control::Run, BulkOperationState::Running)
+            .await
+    }
+
+    /// Cancels the operation. Invocations which are being processed when cancelling are still
+    /// completed, the operation stops afterwards.
+    pub(crate) async fn cancel(
+        &self,
+        id: BulkOperationId,
+    ) -> Result<BulkOperationResponse, BulkOperationControlError> {
+        self.control(id, Control::Cancel, BulkOperationState::Cancelled)
+            .await
+    }
+
+    async fn control(
+        &self,
+        id: BulkOperationId,
+        control: Control,
+        state: BulkOperationState,
+    ) -> Result<BulkOperationResponse, BulkOperationControlError> {
+        {
+            let running = self.running.lock();
+            if let Some(running) = running.get(&id) {
+                let mut progress = running.progress.lock();
+                if progress.state.is_finished() {
+                    return Err(BulkOperationControlError::Finished(id, progress.state));
+                }
+                running.control.send_replace(control);
+                progress.state = state;
+                return Ok(progress.clone());
+            }
+        }
+
+        match self.get_persisted(id).await? {
+            Some(persisted) if persisted.is_stopped() => Err(BulkOperationControlError::Finished(
+                id,
+                persisted.into_response().state,
+            )),
+            Some(persisted) => Err(BulkOperationControlError::RunningOnOtherNode(
+                id,
+                persisted.owner,
+            )),
+            None => Err(BulkOperationControlError::NotFound(id)),
+        }
+    }
+
+    async fn finish(
+        &self,
+        id: BulkOperationId,
+        progress: &Mutex<BulkOperationResponse>,
+        result: Result<BulkOperationState, String>,
+    ) {
+        let summary = {
+            let mut progress = progress.lock();
+            match result {
+                Ok(state) => progress.state = state,
+                Err(err) => {
+                    progress.state = BulkOperationState::Failed;
+                    progress.error = Some(err);
+                }
+            }
+            progress.finished_at = Some(SystemTime::now().into());
+            progress.clone()
+        };
+        info!(
+            %id,
+            state = %summary.state,
+            matched = summary.matched,
+            succeeded = summary.succeeded,
+            failed = summary.failed,
+            "Bulk invocation operation finished"
+        );
+
+        match self.persist(summary).await {
+            Ok(()) => {
+                self.running.lock().remove(&id);
+            }
+            Err(err) => {
+                // keep the summary available on this node
+                warn!(%id, %err, "Failed to persist the summary of bulk invocation operation");
+            }
+        }
+    }
+
+    /// Stores the operation in the metadata store, replacing its previous progress.
+    async fn persist(
+        &self,
+        operation: BulkOperationResponse,
+    ) -> Result<(), ReadModifyWriteError<Infallible>> {
+        let owner = my_node_id();
+        self.metadata_store_client
+            .read_modify_write(BULK_OPERATIONS_KEY.clone(), |history| {
+                let mut history: BulkOperationHistory = history.unwrap_or_default();
+                history.version = history.version.next();
+                let persisted = PersistedBulkOperation {
+                    owner,
+                    operation: operation.clone(),
+                };
+                match history
+                    .operations
+                    .iter_mut()
+                    .find(|existing| existing.operation.id == operation.id)
+                {
+                    Some(existing) => *existing = persisted,
+                    None => {
+                        history.operations.insert(0, persisted);
+                        history.operations.truncate(MAX_RETAINED_OPERATIONS);
+                    }
+                }
+                Ok(history)
+            })
+            .await
+            .map(|_: BulkOperationHistory| ())
+    }
+
+    async fn get_persisted(
+        &self,
+        id: BulkOperationId,
+    ) -> Result<Option<PersistedBulkOperation>, ReadError> {
+        Ok(self
+            .history()
+            .await?
+            .operations
+            .into_iter()
+            .find(|persisted| persisted.operation.id == id))
+    }
+
+    async fn history(&self) -> Result<BulkOperationHistory, ReadError> {
+        Ok(self
+            .metadata_store_client
+            .get::<BulkOperationHistory>(BULK_OPERATIONS_KEY.clone())
+            .await?
+            .unwrap_or_default())
+    }
+}
+
+struct BulkOperationTask<I> {
+    id: BulkOperationId,
+    bulk_operations: BulkOperations,
+    invocation_client: I,
+    query_context: QueryContext,
+    operation: BulkOperationKind,
+    /// Plan returning the matching invocations.
+    invocations: LogicalPlan,
+    limit: Option<u64>,
+    deployment: Option<PatchDeploymentId>,
+    progress: Arc<Mutex<BulkOperationResponse>>,
+    control: watch::Receiver<Control>,
+}
+
+impl<I> BulkOperationTask<I>
+where
+    I: InvocationClient + Clone + Send + Sync + 'static,
+{
+    async fn run(mut self) {
+        info!(id = %self.id, operation = %self.operation, "Starting bulk invocation operation");
+        let result = self.process().await;
+        self.bulk_operations
+            .finish(self.id, &self.progress, result)
+            .await;
+    }
+
+    async fn process(&mut self) -> Result<BulkOperationState, String> {
+        // Pages are ordered by id and each page starts after the last id of the previous one.
+        // Invocations changed by the operation thus can't be matched twice.
+        let mut last_invocation_id = None;
+        let mut remaining = self.limit.unwrap_or(u64::MAX);
+
+        while remaining > 0 {
+            if !wait_until_running(&mut self.control).await {
+                return Ok(BulkOperationState::Cancelled);
+            }
+
+            let page_size = usize::try_from(remaining)
+                .unwrap_or(usize::MAX)
+                .min(BULK_OPERATION_PAGE_SIZE);
+            let invocation_ids = self.next_page(last_invocation_id, page_size).await?;
+            debug!(
+                id = %self.id,
+                matched = invocation_ids.len(),
+                "Queried page of bulk operation"
+            );
+            self.progress.lock().matched += invocation_ids.len() as u64;
+            remaining -= invocation_ids.len() as u64;
+
+            self.apply_page(&invocation_ids).await;
+
+            let progress = self.progress.lock().clone();
+            if let Err(err) = self.bulk_operations.persist(progress).await {
+                warn!(id = %self.id, %err, "Failed to persist the progress of bulk invocation operation");
+            }
+
+            if invocation_ids.len() < page_size {
+                break;
+            }
+            last_invocation_id = invocation_ids.last().copied();
+        }
+
+        Ok(BulkOperationState::Completed)
+    }
+
+    async fn next_page(
+        &self,
+        after: Option<InvocationId>,
+        page_size: usize,
+    ) -> Result<Vec<InvocationId>, String> {
+        let query_error = |err: &dyn Display| format!("failed querying invocations: {err}");
+
+        let mut page = LogicalPlanBuilder::from(self.invocations.clone());
+        if let Some(after) = after {
+            page = page
+                .filter(col("id").gt(lit(after.to_string())))
+                .map_err(|err| query_error(&err))?;
+        }
+        let page = page
+            .project([col("id")])
+            .and_then(|page| page.sort([col("id").sort(true, false)]))
+            .and_then(|page| page.limit(0, Some(page_size)))
+            .and_then(LogicalPlanBuilder::build)
+            .map_err(|err| query_error(&err))?;
+
+        let mut stream = self
+            .query_context
+            .execute_logical_plan(page)
+            .await
+            .map_err(|err| query_error(&err))?
+            .stream;
+        let mut invocation_ids = Vec::with_capacity(page_size);
+        while let Some(batch) = stream.next().await {
+            let batch = batch.map_err(|err| query_error(&err))?;
+            collect_invocation_ids(&batch, &mut invocation_ids)?;
+        }
+        Ok(invocation_ids)
+    }
+
+    async fn apply_page(&self, invocation_ids: &[InvocationId]) {
+        futures::stream::iter(invocation_ids.iter().copied())
+            .map(|invocation_id| {
+                let invocation_client = self.invocation_client.clone();
+                let deployment = self.deployment.clone();
+                let operation = self.operation;
+                async move {
+                    let result =
+                        apply(&invocation_client, operation, invocation_id, deployment).await;
+                    (invocation_id, result)
+                }
+            })
+            .buffer_unordered(BULK_OPERATION_CONCURRENCY)
+            .for_each(|(invocation_id, result)| {
+                let mut progress = self.progress.lock();
+                progress.processed += 1;
+                match result {
+                    Ok(()) => progress.succeeded += 1,
+                    Err(error) => {
+                        progress.failed += 1;
+                        if progress.failures.len() < BULK_OPERATION_MAX_REPORTED_FAILURES {
+                            progress.failures.push(FailedInvocationOperation {
+                                invocation_id,
+                                error,
+                            });
+                        }
+                    }
+                }
+                future::ready(())
+            })
+            .await;
+    }
+}
+
+/// Waits while the operation is paused. Returns `false` if the operation was cancelled.
+async fn wait_until_running(control: &mut watch::Receiver<Control>) -> bool {
+    loop {
+        let current = *control.borrow_and_update();
+        match current {
+            Control::Run => return true,
+            Control::Cancel => return false,
+            Control::Pause => {
+                if control.changed().await.is_err() {
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+fn collect_invocation_ids(
+    batch: &RecordBatch,
+    invocation_ids: &mut Vec<InvocationId>,
+) -> Result<(), String> {
+    if batch.num_columns() == 0 {
+        return Ok(());
+    }
+    let column = cast(batch.column(0), &DataType::Utf8)
+        .map_err(|err| format!("unexpected type of the invocation id column: {err}"))?;
+
+    for value in column.as_string::<i32>().iter().flatten() {
+        invocation_ids.push(
+            value
+                .parse()
+                .map_err(|err| format!("invalid invocation id '{value}': {err}"))?,
+        );
+    }
+    Ok(())
+}
+
+async fn apply<I: InvocationClient>(
+    invocation_client: &I,
+    operation: BulkOperationKind,
+    invocation_id: InvocationId,
+    deployment: Option<PatchDeploymentId>,
+) -> Result<(), String> {
+    let request_id = PartitionProcessorRpcRequestId::new();
+    let not_found = || format!("Invocation '{invocation_id}' not found");
+
+    match operation {
+        BulkOperationKind::Kill => {
+            match invocation_client
+                .kill_invocation(request_id, invocation_id)
+                .await
+                .map_err(|err| err.to_string())?
+            {
+                KillInvocationResponse::Ok => Ok(()),
+                KillInvocationResponse::NotFound => Err(not_found()),
+                KillInvocationResponse::AlreadyCompleted => Err(format!(
+                    "Invocation '{invocation_id}' was already completed"
+                )),
+            }
+        }
+        BulkOperationKind::Cancel => {
+            match invocation_client
+                .cancel_invocation(request_id, invocation_id)
+                .await
+                .map_err(|err| err.to_string())?
+            {
+                CancelInvocationResponse::Done | CancelInvocationResponse::Appended => Ok(()),
+                CancelInvocationResponse::NotFound => Err(not_found()),
+                CancelInvocationResponse::AlreadyCompleted => Err(format!(
+                    "Invocation '{invocation_id}' was already completed"
+                )),
+            }
+        }
+        BulkOperationKind::Purge | BulkOperationKind::PurgeJournal => {
+            let response = if operation == BulkOperationKind::Purge {
+                invocation_client
+                    .purge_invocation(request_id, invocation_id)
+                    .await
+            } else {
+                invocation_client
+                    .purge_journal(request_id, invocation_id)
+                    .await
+            };
+            match response.map_err(|err| err.to_string())? {
+                PurgeInvocationResponse::Ok => Ok(()),
+                PurgeInvocationResponse::NotFound => Err(not_found()),
+                PurgeInvocationResponse::NotCompleted => {
+                    Err(format!("Invocation '{invocation_id}' is not yet completed"))
+                }
+            }
+        }
+        BulkOperationKind::Pause => {
+            match invocation_client
+                .pause_invocation(request_id, invocation_id)
+                .await
+                .map_err(|err| err.to_string())?
+            {
+                PauseInvocationResponse::Accepted | PauseInvocationResponse::AlreadyPaused => {
+                    Ok(())
+                }
+                PauseInvocationResponse::NotFound => Err(not_found()),
+                PauseInvocationResponse::NotRunning => Err(format!(
+                    "Invocation '{invocation_id}' is not running, cannot be paused"
+                )),
+            }
+        }
+        BulkOperationKind::Resume => {
+            match invocation_client
+                .resume_invocation(
+                    request_id,
+                    invocation_id,
+                    deployment.unwrap_or(PatchDeploymentId::KeepPinned),
+                )
+                .await
+                .map_err(|err| err.to_string())?
+            {
+                ResumeInvocationResponse::Ok => Ok(()),
+                ResumeInvocationResponse::NotFound => Err(not_found()),
+                ResumeInvocationResponse::NotStarted => Err(format!(
+                    "Invocation '{invocation_id}' is either inboxed or scheduled, cannot be resumed"
+                )),
+                ResumeInvocationResponse::Completed => Err(format!(
+                    "Invocation '{invocation_id}' is completed, cannot be resumed"
+                )),
+                ResumeInvocationResponse::CannotChangeDeploymentId => Err(format!(
+                    "Cannot change deployment ID for invocation '{invocation_id}'"
+                )),
+                ResumeInvocationResponse::DeploymentNotFound => Err(format!(
+                    "Deployment not found when resuming invocation '{invocation_id}'"
+                )),
+                ResumeInvocationResponse::IncompatibleDeploymentId {
+                    pinned_protocol_version,
+                    deployment_id,
+                    supported_protocol_versions,
+                } => Err(format!(
+                    "Invocation '{invocation_id}' is running on protocol version '{pinned_protocol_version}', while the chosen deployment '{deployment_id}' supports the range {supported_protocol_versions:?}"
+                )),
+            }
+        }
+        BulkOperationKind::RestartAsNew => {
+            match invocation_client
+                .restart_as_new_invocation(
+                    request_id,
+                    invocation_id,
+                    EntryIndex::default(), // Always restart from the beginning
+                    deployment.unwrap_or(PatchDeploymentId::PinToLatest),
+                )
+                .await
+                .map_err(|err| err.to_string())?
+            {
+                client::RestartAsNewInvocationResponse::Ok { .. } => Ok(()),
+                client::RestartAsNewInvocationResponse::NotFound => Err(not_found()),
+                client::RestartAsNewInvocationResponse::StillRunning => {
+                    Err(format!("Invocation '{invocation_id}' is still running"))
+                }
+                client::RestartAsNewInvocationResponse::Unsupported => Err(format!(
+                    "Restarting invocation '{invocation_id}' is not supported"
+                )),
+                client::RestartAsNewInvocationResponse::MissingInput => Err(format!(
+                    "Invocation '{invocation_id}' cannot be restarted because the input is not available"
+                )),
+                client::RestartAsNewInvocationResponse::NotStarted => Err(format!(
+                    "Invocation '{invocation_id}' cannot be restarted because it's not running yet"
+                )),
+                client::RestartAsNewInvocationResponse::JournalIndexOutOfRange => Err(format!(
+                    "Journal index out of range for invocation '{invocation_id}'"
+                )),
+                client::RestartAsNewInvocationResponse::JournalCopyRangeInvalid => Err(format!(
+                    "Journal copy range invalid for invocation '{invocation_id}'"
+                )),
+                client::RestartAsNewInvocationResponse::CannotPatchDeploymentId => Err(format!(
+                    "Cannot change deployment ID for invocation '{invocation_id}'"
+                )),
+                client::RestartAsNewInvocationResponse::DeploymentNotFound => Err(format!(
+                    "Deployment not found when restarting invocation '{invocation_id}'"
+                )),
+                client::RestartAsNewInvocationResponse::IncompatibleDeploymentId {
+                    pinned_protocol_version,
+                    deployment_id,
+                    supported_protocol_versions,
+                } => Err(format!(
+                    "Invocation '{invocation_id}' is running on protocol version '{pinned_protocol_version}', while the chosen deployment '{deployment_id}' supports the range {supported_protocol_versions:?}"
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::StringArray;
+    use datafusion::arrow::datatypes::{Field, Schema};
+
+    use super::*;
+
+    #[test]
+    fn collects_invocation_ids_from_record_batches() {
+        let invocation_id = InvocationId::mock_random();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, true)]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(StringArray::from(vec![
+                Some(invocation_id.to_string()),
+                None,
+            ]))],
+        )
+        .unwrap();
+
+        let mut invocation_ids = Vec::new();
+        collect_invocation_ids(&batch, &mut invocation_ids).unwrap();
+        assert_eq!(invocation_ids, vec![invocation_id]);
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, true)]));
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(StringArray::from(vec!["not-an-id"]))])
+                .unwrap();
+        assert!(collect_invocation_ids(&batch, &mut invocation_ids).is_err());
+    }
+
+    #[tokio::test]
+    async fn paused_operation_waits_until_resumed_or_cancelled() {
+        let (tx, mut rx) = watch::channel(Control::Pause);
+        let waiting = tokio::spawn(async move { wait_until_running(&mut rx).await });
+        tx.send_replace(Control::Run);
+        assert!(waiting.await.unwrap());
+
+        let (tx, mut rx) = watch::channel(Control::Pause);
+        let waiting = tokio::spawn(async move { wait_until_running(&mut rx).await });
+        tx.send_replace(Control::Cancel);
+        assert!(!waiting.await.unwrap());
+    }
+}
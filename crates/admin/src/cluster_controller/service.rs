@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 mod cluster_controller_state;
+mod log_server_decommissioner;
 mod scheduler;
 mod scheduler_task;
 
@@ -65,6 +66,7 @@ use restate_types::{GenerationalNodeId, NodeId, Version};
 use crate::cluster_controller::cluster_state_refresher::ClusterStateRefresher;
 use crate::cluster_controller::grpc_svc_handler::ClusterCtrlSvcHandler;
 use crate::cluster_controller::service::cluster_controller_state::ClusterControllerState;
+pub(crate) use crate::cluster_controller::service::cluster_controller_state::leader_admin_node;
 
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum Error {
@@ -601,15 +603,7 @@ impl<T: TransportConnect> Service<T> {
                 extension,
                 response_tx,
             } => {
-                let membership_state = Metadata::with_current(|metadata| {
-                    metadata
-                        .partition_table_ref()
-                        .iter()
-                        .find(|(_, partition)| partition.log_id() == log_id)
-                        .map(|(partition_id, _)| *partition_id)
-                })
-                .map(|partition_id| self.replica_set_states.membership_state(partition_id))
-                .unwrap_or_default();
+                let membership_state = membership_state_of_log(&self.replica_set_states, log_id);
 
                 let bifrost = self.bifrost.clone();
 
@@ -617,6 +611,7 @@ impl<T: TransportConnect> Service<T> {
                 _ = TaskCenter::spawn(TaskKind::Disposable, "seal-and-extend", async move {
                     let result = SealAndExtendTask {
                         log_id,
+                        segment_index: extension.as_ref().and_then(|ext| ext.segment_index_to_seal),
                         extension,
                         min_version,
                         bifrost,
@@ -832,8 +827,27 @@ impl SealChainTask {
     }
 }
 
+/// Returns the membership state of the partition that writes to `log_id`, or the default if no
+/// partition uses this log.
+fn membership_state_of_log(
+    replica_set_states: &PartitionReplicaSetStates,
+    log_id: LogId,
+) -> MembershipState {
+    Metadata::with_current(|metadata| {
+        metadata
+            .partition_table_ref()
+            .iter()
+            .find(|(_, partition)| partition.log_id() == log_id)
+            .map(|(partition_id, _)| *partition_id)
+    })
+    .map(|partition_id| replica_set_states.membership_state(partition_id))
+    .unwrap_or_default()
+}
+
 struct SealAndExtendTask {
     log_id: LogId,
+    /// Segment index to seal. Last if None
+    segment_index: Option<SegmentIndex>,
     min_version: Version,
     extension: Option<ChainExtension>,
     bifrost: Bifrost,
@@ -842,10 +856,7 @@ struct SealAndExtendTask {
 
 impl SealAndExtendTask {
     async fn run(self) -> anyhow::Result<MaybeSealedSegment> {
-        let last_segment_index = self
-            .extension
-            .as_ref()
-            .and_then(|ext| ext.segment_index_to_seal);
+        let last_segment_index = self.segment_index;
 
         let (provider, params) = self.next_segment()?;
 
@@ -18,13 +18,35 @@ use tracing::info;
 
 use restate_core::network::TransportConnect;
 use restate_core::{TaskCenter, TaskId, TaskKind, my_node_id};
+use restate_types::GenerationalNodeId;
+use restate_types::cluster_state::ClusterState;
 use restate_types::identifiers::PartitionId;
 use restate_types::nodes_config::NodesConfiguration;
 
 use crate::cluster_controller::service::Service;
+use crate::cluster_controller::service::log_server_decommissioner::LogServerDecommissioner;
 use crate::cluster_controller::service::scheduler::Scheduler;
 use crate::cluster_controller::service::scheduler_task::SchedulerTask;
 
+/// The leading admin node is the alive admin node with the smallest PlainNodeID.
+pub(crate) fn leader_admin_node(
+    nodes_config: &NodesConfiguration,
+    cs: &ClusterState,
+) -> Option<GenerationalNodeId> {
+    let admin_nodes: Vec<_> = nodes_config
+        .get_admin_nodes()
+        .map(|c| c.current_generation)
+        .sorted()
+        .collect();
+    let states = cs.map_from_ids(admin_nodes.iter().map(Into::into));
+
+    admin_nodes
+        .iter()
+        .zip(states)
+        .filter_map(|(node_id, state)| state.is_alive().then_some(*node_id))
+        .next()
+}
+
 pub enum ClusterControllerState {
     Follower,
     Leader(Leader),
@@ -35,22 +57,9 @@ impl ClusterControllerState {
         &mut self,
         service: &Service<T>,
         nodes_config: &NodesConfiguration,
-        cs: &restate_types::cluster_state::ClusterState,
+        cs: &ClusterState,
     ) {
-        let maybe_leader = {
-            let admin_nodes: Vec<_> = nodes_config
-                .get_admin_nodes()
-                .map(|c| c.current_generation)
-                .sorted()
-                .collect();
-            let states = cs.map_from_ids(admin_nodes.iter().map(Into::into));
-
-            admin_nodes
-                .iter()
-                .zip(states)
-                .filter_map(|(node_id, state)| state.is_alive().then_some(*node_id))
-                .next()
-        };
+        let maybe_leader = leader_admin_node(nodes_config, cs);
 
         // A Cluster Controller is a leader if the node holds the smallest PlainNodeID
         let is_leader = match maybe_leader {
@@ -85,6 +94,7 @@ impl ClusterControllerState {
 
 pub struct Leader {
     scheduler_task: TaskId,
+    log_server_decommissioner_task: TaskId,
     sync_epoch_metadata_tx: mpsc::Sender<Vec<PartitionId>>,
 }
 
@@ -114,8 +124,22 @@ impl Leader {
         )
         .expect("failed to spawn scheduler task");
 
+        let log_server_decommissioner_task = TaskCenter::spawn_child(
+            TaskKind::SystemService,
+            "log-server-decommissioner",
+            LogServerDecommissioner::new(
+                service.bifrost.clone(),
+                service.networking.clone(),
+                service.metadata_writer.clone(),
+                service.replica_set_states.clone(),
+            )
+            .run(),
+        )
+        .expect("failed to spawn log-server decommissioner task");
+
         Self {
             scheduler_task,
+            log_server_decommissioner_task,
             sync_epoch_metadata_tx,
         }
     }
@@ -127,8 +151,12 @@ impl Leader {
     /// Stops the leader tasks to make sure that no other leader activity is running.
     async fn stop(self) {
         let scheduler_task = TaskCenter::cancel_task(self.scheduler_task);
+        let log_server_decommissioner_task =
+            TaskCenter::cancel_task(self.log_server_decommissioner_task);
 
-        // ignore if the task failed during cancellation
+        // ignore if the tasks failed during cancellation
         let _scheduler_task = OptionFuture::from(scheduler_task).await;
+        let _log_server_decommissioner_task =
+            OptionFuture::from(log_server_decommissioner_task).await;
     }
 }
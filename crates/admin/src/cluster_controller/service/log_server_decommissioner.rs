@@ -0,0 +1,516 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use metrics::gauge;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use restate_bifrost::Bifrost;
+use restate_bifrost::providers::replicated_loglet::scrubber::{ScrubFindings, Scrubber};
+use restate_core::network::{Networking, TransportConnect};
+use restate_core::{Metadata, MetadataWriter, cancellation_watcher};
+use restate_types::Versioned;
+use restate_types::logs::metadata::{InternalKind, Logs, ProviderKind, SegmentIndex};
+use restate_types::logs::{LogId, LogletId, Lsn};
+use restate_types::nodes_config::{NodesConfiguration, StorageState};
+use restate_types::partitions::state::PartitionReplicaSetStates;
+use restate_types::replicated_loglet::ReplicatedLogletParams;
+use restate_types::replication::NodeSet;
+use restate_util_time::DurationExt;
+
+use super::{SealAndExtendTask, membership_state_of_log};
+use crate::metric_definitions::LOG_SERVER_DECOMMISSIONER_STALLED_LOGLETS;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Number of consecutive rounds without progress after which evacuating a loglet is reported as
+/// stalled
+const MAX_STALLED_ROUNDS: u32 = 10;
+
+/// Moves the data of log-servers in [`StorageState::Draining`] to other log-servers.
+///
+/// Open loglets that include a draining node are reconfigured to a nodeset without it. The
+/// records of sealed loglets are copied to the remaining members of their nodesets and to
+/// replacement log-servers, after which the draining nodes are replaced in those nodesets. Once a
+/// draining node is no longer part of any nodeset, it's transitioned to
+/// [`StorageState::Disabled`].
+///
+/// Sealed loglets whose evacuation doesn't make progress for [`MAX_STALLED_ROUNDS`] rounds, for
+/// instance because there aren't enough writeable log-servers left, are reported as stalled.
+pub struct LogServerDecommissioner<T> {
+    bifrost: Bifrost,
+    metadata_writer: MetadataWriter,
+    replica_set_states: PartitionReplicaSetStates,
+    scrubber: Scrubber<T>,
+    /// Draining nodes that weren't part of any nodeset in the previous round
+    unreferenced: NodeSet,
+    /// Consecutive rounds in which evacuating a sealed loglet made no progress
+    stalled: HashMap<LogletId, u32>,
+}
+
+/// What needs to happen to move the data off the draining log-servers.
+#[derive(Debug, Default, PartialEq)]
+struct DecommissionPlan {
+    /// Open segments whose nodeset includes draining nodes
+    reconfigure: Vec<(LogId, SegmentIndex)>,
+    /// Sealed segments whose nodeset includes draining nodes
+    evacuate: Vec<SealedSegment>,
+    /// Draining nodes that are not part of any nodeset
+    unreferenced: NodeSet,
+}
+
+#[derive(Debug, PartialEq)]
+struct SealedSegment {
+    log_id: LogId,
+    segment_index: SegmentIndex,
+    base_lsn: Lsn,
+    tail_lsn: Lsn,
+    params: ReplicatedLogletParams,
+    /// The draining nodes of the segment's nodeset
+    evacuating: NodeSet,
+}
+
+impl<T: TransportConnect> LogServerDecommissioner<T> {
+    pub fn new(
+        bifrost: Bifrost,
+        networking: Networking<T>,
+        metadata_writer: MetadataWriter,
+        replica_set_states: PartitionReplicaSetStates,
+    ) -> Self {
+        Self {
+            bifrost,
+            metadata_writer,
+            replica_set_states,
+            // evacuation doesn't record findings, a throw-away registry is fine
+            scrubber: Scrubber::new(networking, ScrubFindings::default()),
+            unreferenced: NodeSet::default(),
+            stalled: HashMap::default(),
+        }
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let effective_interval = CHECK_INTERVAL.add_jitter(0.1);
+        let start_at = tokio::time::Instant::now() + effective_interval;
+        let mut check_interval = tokio::time::interval_at(start_at, effective_interval);
+        check_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        debug!("Starting log-server decommissioner");
+        let mut cancel = std::pin::pin!(cancellation_watcher());
+        loop {
+            tokio::select! {
+                _ = check_interval.tick() => {
+                    if let Err(err) = self.decommission_step().await {
+                        info!("Decommissioning log-servers failed: {err:#}");
+                    }
+                }
+                _ = &mut cancel => {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn decommission_step(&mut self) -> anyhow::Result<()> {
+        let (nodes_config, logs) =
+            Metadata::with_current(|m| (m.nodes_config_snapshot(), m.logs_snapshot()));
+        let plan = plan(&nodes_config, &logs)?;
+
+        for (log_id, segment_index) in plan.reconfigure {
+            info!(
+                %log_id,
+                %segment_index,
+                "Reconfiguring open loglet to move it off draining log-servers"
+            );
+            if let Err(err) = (SealAndExtendTask {
+                log_id,
+                segment_index: Some(segment_index),
+                min_version: logs.version(),
+                extension: None,
+                bifrost: self.bifrost.clone(),
+                membership_state: membership_state_of_log(&self.replica_set_states, log_id),
+            })
+            .run()
+            .await
+            {
+                warn!(%log_id, %segment_index, "Failed reconfiguring open loglet: {err:#}");
+            }
+        }
+
+        let mut stalled = HashMap::with_capacity(self.stalled.len());
+        for segment in plan.evacuate {
+            let loglet_id = segment.params.loglet_id;
+            let stalled_rounds = self.stalled.get(&loglet_id).copied().unwrap_or_default();
+            let summary = match self
+                .scrubber
+                .evacuate_loglet(
+                    &segment.params,
+                    &segment.evacuating,
+                    segment.base_lsn,
+                    segment.tail_lsn,
+                )
+                .await
+            {
+                Ok(summary) => summary,
+                Err(err) => {
+                    warn!(
+                        log_id = %segment.log_id,
+                        segment_index = %segment.segment_index,
+                        %loglet_id,
+                        "Cannot evacuate {} from sealed loglet: {err}",
+                        segment.evacuating,
+                    );
+                    stalled.insert(loglet_id, stalled_rounds.saturating_add(1));
+                    continue;
+                }
+            };
+
+            if !summary.is_complete() {
+                if summary.copied_records > 0 {
+                    stalled.insert(loglet_id, 0);
+                    debug!(
+                        log_id = %segment.log_id,
+                        segment_index = %segment.segment_index,
+                        %loglet_id,
+                        "Evacuating {} from sealed loglet is incomplete, will retry: {:?}",
+                        segment.evacuating,
+                        summary,
+                    );
+                    continue;
+                }
+                let stalled_rounds = stalled_rounds.saturating_add(1);
+                stalled.insert(loglet_id, stalled_rounds);
+                if stalled_rounds >= MAX_STALLED_ROUNDS {
+                    warn!(
+                        log_id = %segment.log_id,
+                        segment_index = %segment.segment_index,
+                        %loglet_id,
+                        "Evacuating {} from sealed loglet made no progress in the last {} rounds: {:?}",
+                        segment.evacuating,
+                        stalled_rounds,
+                        summary,
+                    );
+                } else {
+                    debug!(
+                        log_id = %segment.log_id,
+                        segment_index = %segment.segment_index,
+                        %loglet_id,
+                        "Evacuating {} from sealed loglet made no progress, will retry: {:?}",
+                        segment.evacuating,
+                        summary,
+                    );
+                }
+                continue;
+            }
+
+            match self
+                .bifrost
+                .admin()
+                .replace_in_sealed_nodeset(
+                    segment.log_id,
+                    segment.segment_index,
+                    segment.evacuating.clone(),
+                    summary.replacements.clone(),
+                )
+                .await
+            {
+                Ok(nodeset) => info!(
+                    log_id = %segment.log_id,
+                    segment_index = %segment.segment_index,
+                    %loglet_id,
+                    "Evacuated {} from sealed loglet after copying {} records, new nodeset is {}",
+                    segment.evacuating,
+                    summary.copied_records,
+                    nodeset,
+                ),
+                Err(err) => {
+                    warn!(
+                        log_id = %segment.log_id,
+                        segment_index = %segment.segment_index,
+                        %loglet_id,
+                        "Failed replacing {} with {} in the nodeset of sealed loglet: {err}",
+                        segment.evacuating,
+                        summary.replacements,
+                    );
+                    stalled.insert(loglet_id, stalled_rounds.saturating_add(1));
+                }
+            }
+        }
+        gauge!(LOG_SERVER_DECOMMISSIONER_STALLED_LOGLETS).set(
+            stalled
+                .values()
+                .filter(|rounds| **rounds >= MAX_STALLED_ROUNDS)
+                .count() as f64,
+        );
+        self.stalled = stalled;
+
+        // A loglet that was created based on an older nodes configuration might still add a
+        // draining node to a nodeset. Requiring the node to be unreferenced in two consecutive
+        // rounds gives those in-flight reconfigurations time to show up in the logs metadata.
+        let to_disable: NodeSet = plan
+            .unreferenced
+            .iter()
+            .filter(|node_id| self.unreferenced.contains(**node_id))
+            .copied()
+            .collect();
+        self.unreferenced = plan.unreferenced;
+
+        if !to_disable.is_empty() {
+            self.disable(&to_disable).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn disable(&self, nodes: &NodeSet) -> anyhow::Result<()> {
+        self.metadata_writer
+            .global_metadata()
+            .read_modify_write(|nodes_config: Option<Arc<NodesConfiguration>>| {
+                let mut nodes_config = nodes_config
+                    .context("Missing nodes configuration")?
+                    .as_ref()
+                    .clone();
+
+                for node_id in nodes.iter().copied() {
+                    let mut node = nodes_config.find_node_by_id(node_id)?.clone();
+                    // the operator might have changed their mind in the meantime
+                    if node.log_server_config.storage_state != StorageState::Draining {
+                        continue;
+                    }
+                    node.log_server_config.storage_state = StorageState::Disabled;
+                    nodes_config.upsert_node(node);
+                }
+
+                nodes_config.increment_version();
+                Ok::<_, anyhow::Error>(nodes_config)
+            })
+            .await?;
+
+        info!(
+            "Log-servers {} are not part of any nodeset anymore and have been disabled",
+            nodes
+        );
+        Ok(())
+    }
+}
+
+/// Finds the segments that still include draining log-servers in their nodeset.
+///
+/// Draining nodes are only reported as unreferenced if the default loglet provider is replicated
+/// and no local or in-memory loglet is in use, since those implicitly include every node.
+fn plan(nodes_config: &NodesConfiguration, logs: &Logs) -> anyhow::Result<DecommissionPlan> {
+    let draining: NodeSet = nodes_config
+        .iter()
+        .filter(|(_, config)| config.log_server_config.storage_state == StorageState::Draining)
+        .map(|(node_id, _)| node_id)
+        .collect();
+    if draining.is_empty() {
+        return Ok(DecommissionPlan::default());
+    }
+
+    let replicated_by_default =
+        logs.configuration().default_provider.kind() == ProviderKind::Replicated;
+    let mut uses_local_loglets = false;
+    let mut referenced = NodeSet::default();
+    let mut plan = DecommissionPlan::default();
+
+    for (log_id, chain) in logs.iter() {
+        for segment in chain.iter() {
+            match segment.config.kind {
+                InternalKind::Replicated => {}
+                InternalKind::Local | InternalKind::InMemory => {
+                    uses_local_loglets = true;
+                    continue;
+                }
+                InternalKind::Sealed => continue,
+            }
+
+            let params = ReplicatedLogletParams::deserialize_from(segment.config.params.as_bytes())
+                .with_context(|| {
+                    format!(
+                        "invalid replicated loglet params in segment {} of log {log_id}",
+                        segment.index()
+                    )
+                })?;
+            let evacuating: NodeSet = params
+                .nodeset
+                .iter()
+                .filter(|node_id| draining.contains(**node_id))
+                .copied()
+                .collect();
+            if evacuating.is_empty() {
+                continue;
+            }
+            referenced = referenced
+                .iter()
+                .chain(evacuating.iter())
+                .copied()
+                .collect();
+
+            match segment.tail_lsn {
+                None if replicated_by_default => {
+                    plan.reconfigure.push((*log_id, segment.index()));
+                }
+                None => {}
+                Some(tail_lsn) => plan.evacuate.push(SealedSegment {
+                    log_id: *log_id,
+                    segment_index: segment.index(),
+                    base_lsn: segment.base_lsn,
+                    tail_lsn,
+                    params,
+                    evacuating,
+                }),
+            }
+        }
+    }
+
+    if replicated_by_default && !uses_local_loglets {
+        plan.unreferenced = draining
+            .iter()
+            .filter(|node_id| !referenced.contains(**node_id))
+            .copied()
+            .collect();
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::num::NonZeroU8;
+
+    use googletest::prelude::*;
+
+    use restate_bifrost::providers::replicated_loglet::test_util::generate_logserver_node;
+    use restate_types::GenerationalNodeId;
+    use restate_types::logs::builder::LogsBuilder;
+    use restate_types::logs::metadata::{Chain, LogletParams, SealMetadata};
+    use restate_types::replication::ReplicationProperty;
+
+    fn nodes_config(draining: &[u32]) -> NodesConfiguration {
+        let mut nodes_config = NodesConfiguration::new_for_testing();
+        for id in 1..=4 {
+            let storage_state = if draining.contains(&id) {
+                StorageState::Draining
+            } else {
+                StorageState::ReadWrite
+            };
+            nodes_config.upsert_node(generate_logserver_node(id, storage_state));
+        }
+        nodes_config
+    }
+
+    fn params(log_id: LogId, segment_index: u32, nodeset: NodeSet) -> ReplicatedLogletParams {
+        ReplicatedLogletParams {
+            loglet_id: LogletId::new(log_id, SegmentIndex::from(segment_index)),
+            sequencer: GenerationalNodeId::new(1, 1),
+            replication: ReplicationProperty::new(NonZeroU8::new(2).unwrap()),
+            nodeset,
+        }
+    }
+
+    fn loglet_params(params: &ReplicatedLogletParams) -> LogletParams {
+        LogletParams::from(params.serialize().unwrap())
+    }
+
+    #[test]
+    fn nothing_to_do_without_draining_nodes() -> googletest::Result<()> {
+        let mut builder = LogsBuilder::default();
+        builder.add_log(
+            LogId::new(1),
+            Chain::new(
+                ProviderKind::Replicated,
+                loglet_params(&params(LogId::new(1), 0, NodeSet::from([1, 2, 3]))),
+            ),
+        )?;
+
+        let plan = plan(&nodes_config(&[]), &builder.build())?;
+        assert_that!(plan, eq(DecommissionPlan::default()));
+        Ok(())
+    }
+
+    #[test]
+    fn plans_reconfiguration_and_evacuation() -> googletest::Result<()> {
+        let log_1 = LogId::new(1);
+        let log_2 = LogId::new(2);
+        let sealed = params(log_1, 0, NodeSet::from([1, 2, 3]));
+
+        let mut builder = LogsBuilder::default();
+        // log-1 -> [sealed loglet with node 3, open loglet with node 3]
+        builder.add_log(
+            log_1,
+            Chain::new(ProviderKind::Replicated, loglet_params(&sealed)),
+        )?;
+        builder.chain(log_1).unwrap().append_segment(
+            Lsn::from(10),
+            ProviderKind::Replicated,
+            loglet_params(&params(log_1, 1, NodeSet::from([2, 3, 4]))),
+        )?;
+        // log-2 -> [open loglet without node 3]
+        builder.add_log(
+            log_2,
+            Chain::new(
+                ProviderKind::Replicated,
+                loglet_params(&params(log_2, 0, NodeSet::from([1, 2, 4]))),
+            ),
+        )?;
+
+        let plan = plan(&nodes_config(&[3]), &builder.build())?;
+        assert_that!(
+            plan.reconfigure,
+            elements_are![eq((log_1, SegmentIndex::from(1)))]
+        );
+        assert_that!(
+            plan.evacuate,
+            elements_are![eq(SealedSegment {
+                log_id: log_1,
+                segment_index: SegmentIndex::from(0),
+                base_lsn: Lsn::OLDEST,
+                tail_lsn: Lsn::from(10),
+                params: sealed,
+                evacuating: NodeSet::from([3]),
+            })]
+        );
+        // node 3 is still part of both loglets of log-1
+        assert_that!(plan.unreferenced, eq(NodeSet::default()));
+        Ok(())
+    }
+
+    #[test]
+    fn unreferenced_draining_nodes() -> googletest::Result<()> {
+        let log_1 = LogId::new(1);
+
+        let mut builder = LogsBuilder::default();
+        builder.add_log(
+            log_1,
+            Chain::new(
+                ProviderKind::Replicated,
+                loglet_params(&params(log_1, 0, NodeSet::from([1, 2, 3]))),
+            ),
+        )?;
+        builder
+            .chain(log_1)
+            .unwrap()
+            .seal(Lsn::from(10), &SealMetadata::default())?;
+
+        let plan = plan(&nodes_config(&[3, 4]), &builder.build())?;
+        assert_that!(plan.reconfigure, empty());
+        assert_that!(plan.evacuate.len(), eq(1));
+        assert_that!(plan.unreferenced, eq(NodeSet::from([4])));
+        Ok(())
+    }
+}
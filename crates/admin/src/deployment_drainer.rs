@@ -0,0 +1,311 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use anyhow::Context;
+use datafusion::arrow::array::AsArray;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, UInt64Type};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use itertools::Itertools;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use restate_core::{Metadata, TaskCenter, cancellation_watcher, my_node_id};
+use restate_storage_query_datafusion::context::QueryContext;
+use restate_types::identifiers::DeploymentId;
+use restate_types::schema::deployment::DeploymentDrain;
+use restate_types::schema::registry::{MetadataService, SchemaRegistry};
+use restate_util_time::DurationExt;
+
+use crate::cluster_controller::service::leader_admin_node;
+
+/// Periodically counts the in-flight invocations pinned to draining deployments, marks the
+/// deployments without pinned invocations as drained, and removes the drained deployments marked
+/// for automatic removal.
+///
+/// The drainer runs on every admin node, but only the leading admin node acts. The pinned
+/// invocation counts are kept in memory, only the drained transition is written to the schema.
+pub struct DeploymentDrainer<M, Discovery, Telemetry> {
+    schema_registry: SchemaRegistry<M, Discovery, Telemetry>,
+    query_context: QueryContext,
+    check_interval: Duration,
+    /// Draining deployments observed without pinned invocations by the previous check.
+    previously_empty: HashSet<DeploymentId>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum DrainAction {
+    MarkDrained,
+    Remove,
+}
+
+impl<M, Discovery, Telemetry> DeploymentDrainer<M, Discovery, Telemetry>
+where
+    M: MetadataService,
+{
+    pub fn new(
+        schema_registry: SchemaRegistry<M, Discovery, Telemetry>,
+        query_context: QueryContext,
+        check_interval: Duration,
+    ) -> Self {
+        Self {
+            schema_registry,
+            query_context,
+            check_interval,
+            previously_empty: HashSet::new(),
+        }
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let effective_interval = self.check_interval.add_jitter(0.1);
+        let start_at = tokio::time::Instant::now() + effective_interval;
+        let mut check_interval = tokio::time::interval_at(start_at, effective_interval);
+        check_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        debug!(
+            "Starting deployment drainer with check interval: {:?}",
+            self.check_interval
+        );
+        let mut cancel = std::pin::pin!(cancellation_watcher());
+        loop {
+            tokio::select! {
+                _ = check_interval.tick() => {
+                    if let Err(e) = self.check_draining_deployments().await {
+                        info!("Checking draining deployments failed: {e:#}");
+                    }
+                }
+                _ = &mut cancel => {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check_draining_deployments(&mut self) -> anyhow::Result<()> {
+        if !is_leader_admin_node() {
+            // another admin node takes care of draining deployments
+            self.previously_empty.clear();
+            return Ok(());
+        }
+
+        let draining: Vec<(DeploymentId, DeploymentDrain)> = self
+            .schema_registry
+            .list_deployments()
+            .into_iter()
+            .filter_map(|(deployment, _)| deployment.drain.map(|drain| (deployment.id, drain)))
+            .collect();
+        if draining.is_empty() {
+            self.previously_empty.clear();
+            return Ok(());
+        }
+
+        let pinned_invocations =
+            count_pinned_invocations(&self.query_context, draining.iter().map(|(id, _)| id))
+                .await?;
+
+        let mut empty = HashSet::with_capacity(draining.len());
+        for (deployment_id, drain) in draining {
+            let pinned = pinned_invocations
+                .get(&deployment_id)
+                .copied()
+                .unwrap_or_default();
+            if pinned == 0 {
+                empty.insert(deployment_id);
+            } else {
+                debug!(%deployment_id, "{pinned} invocations pinned to draining deployment");
+            }
+
+            let previously_empty = self.previously_empty.contains(&deployment_id);
+            match next_action(&drain, pinned, previously_empty) {
+                Some(DrainAction::Remove) => {
+                    info!(%deployment_id, "Removing drained deployment");
+                    if let Err(e) = self.schema_registry.delete_deployment(deployment_id).await
+                        && e.status_code() != http::StatusCode::NOT_FOUND
+                    {
+                        warn!(%deployment_id, "Failed removing drained deployment: {e}");
+                    }
+                }
+                Some(DrainAction::MarkDrained) => {
+                    info!(%deployment_id, "Deployment is drained");
+                    if let Err(e) = self
+                        .schema_registry
+                        .mark_deployment_drained(deployment_id)
+                        .await
+                        && e.status_code() != http::StatusCode::NOT_FOUND
+                    {
+                        warn!(%deployment_id, "Failed marking deployment as drained: {e}");
+                    }
+                }
+                None => {}
+            }
+        }
+        self.previously_empty = empty;
+
+        Ok(())
+    }
+}
+
+fn is_leader_admin_node() -> bool {
+    let nodes_config = Metadata::with_current(|m| m.nodes_config_snapshot());
+    let cs = TaskCenter::with_current(|tc| tc.cluster_state().clone());
+    leader_admin_node(&nodes_config, &cs) == Some(my_node_id())
+}
+
+/// Counts the in-flight invocations pinned to the given deployments. Deployments without pinned
+/// invocations are missing from the result.
+pub(crate) async fn count_pinned_invocations(
+    query_context: &QueryContext,
+    deployment_ids: impl Iterator<Item = &DeploymentId>,
+) -> anyhow::Result<HashMap<DeploymentId, u64>> {
+    let query = format!(
+        "SELECT pinned_deployment_id, COUNT(*) AS pinned_invocations \
+         FROM sys_invocation_status \
+         WHERE status != 'completed' AND pinned_deployment_id IN ({}) \
+         GROUP BY pinned_deployment_id",
+        deployment_ids.map(|id| format!("'{id}'")).join(", ")
+    );
+
+    let batches: Vec<RecordBatch> = query_context
+        .execute(&query)
+        .await?
+        .stream
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut pinned_invocations = HashMap::new();
+    for batch in batches {
+        collect_pinned_invocations(&batch, &mut pinned_invocations)?;
+    }
+    Ok(pinned_invocations)
+}
+
+fn collect_pinned_invocations(
+    batch: &RecordBatch,
+    pinned_invocations: &mut HashMap<DeploymentId, u64>,
+) -> anyhow::Result<()> {
+    let deployment_ids = cast(
+        batch
+            .column_by_name("pinned_deployment_id")
+            .context("Missing pinned_deployment_id column")?,
+        &DataType::Utf8,
+    )?;
+    let counts = cast(
+        batch
+            .column_by_name("pinned_invocations")
+            .context("Missing pinned_invocations column")?,
+        &DataType::UInt64,
+    )?;
+
+    for (deployment_id, count) in deployment_ids
+        .as_string::<i32>()
+        .iter()
+        .zip(counts.as_primitive::<UInt64Type>())
+    {
+        if let (Some(deployment_id), Some(count)) = (deployment_id, count) {
+            pinned_invocations.insert(deployment_id.parse()?, count);
+        }
+    }
+    Ok(())
+}
+
+/// A deployment is marked as drained, or removed, only after it was observed without pinned
+/// invocations by two consecutive checks, so that invocations pinned concurrently with the start of
+/// the drain are accounted for.
+fn next_action(
+    drain: &DeploymentDrain,
+    pinned_invocations: u64,
+    previously_empty: bool,
+) -> Option<DrainAction> {
+    if pinned_invocations > 0 || !(drain.is_drained() || previously_empty) {
+        None
+    } else if drain.auto_remove {
+        Some(DrainAction::Remove)
+    } else if !drain.is_drained() {
+        Some(DrainAction::MarkDrained)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{Field, Schema};
+
+    use restate_types::time::MillisSinceEpoch;
+
+    use super::*;
+
+    fn drain(auto_remove: bool, drained: bool) -> DeploymentDrain {
+        DeploymentDrain {
+            started_at: MillisSinceEpoch::now(),
+            auto_remove,
+            drained_at: drained.then(MillisSinceEpoch::now),
+        }
+    }
+
+    #[test]
+    fn removes_only_after_two_consecutive_empty_checks() {
+        assert_eq!(next_action(&drain(true, false), 0, false), None);
+        assert_eq!(
+            next_action(&drain(true, false), 0, true),
+            Some(DrainAction::Remove)
+        );
+        assert_eq!(next_action(&drain(true, false), 2, true), None);
+        assert_eq!(next_action(&drain(false, false), 0, false), None);
+        assert_eq!(
+            next_action(&drain(false, false), 0, true),
+            Some(DrainAction::MarkDrained)
+        );
+        assert_eq!(next_action(&drain(false, false), 3, true), None);
+    }
+
+    #[test]
+    fn drained_deployments_are_only_marked_once() {
+        assert_eq!(next_action(&drain(false, true), 0, true), None);
+        assert_eq!(next_action(&drain(false, true), 0, false), None);
+        // drained deployments marked for removal are removed even after a leader change
+        assert_eq!(
+            next_action(&drain(true, true), 0, false),
+            Some(DrainAction::Remove)
+        );
+    }
+
+    #[test]
+    fn collects_pinned_invocations_from_record_batches() {
+        let deployment_id = DeploymentId::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("pinned_deployment_id", DataType::Utf8, true),
+            Field::new("pinned_invocations", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec![Some(deployment_id.to_string())])),
+                Arc::new(Int64Array::from(vec![4])),
+            ],
+        )
+        .unwrap();
+
+        let mut pinned_invocations = HashMap::new();
+        collect_pinned_invocations(&batch, &mut pinned_invocations).unwrap();
+        assert_eq!(pinned_invocations, HashMap::from([(deployment_id, 4)]));
+    }
+}
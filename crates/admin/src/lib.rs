@@ -8,7 +8,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod bulk_operations;
 pub mod cluster_controller;
+mod deployment_drainer;
 mod error;
 #[cfg(feature = "metadata-api")]
 mod metadata_api;
@@ -18,6 +20,8 @@ mod rest_api;
 pub mod schema_registry_integration;
 pub mod service;
 mod state;
+mod state_dml;
+mod state_export;
 mod storage_accounting;
 #[cfg(feature = "serve-web-ui")]
 mod web_ui;
@@ -15,6 +15,8 @@ pub(crate) const USAGE_STATE_STORAGE_BYTE_SECONDS: &str =
     "restate.usage.state.storage_byte_seconds.total";
 pub(crate) const USAGE_STATE_SIZE_ACCOUNTING_QUERY_DURATION_SECONDS: &str =
     "restate.usage.state_size_accounting.query_duration_seconds";
+pub(crate) const LOG_SERVER_DECOMMISSIONER_STALLED_LOGLETS: &str =
+    "restate.log_server_decommissioner.stalled_loglets";
 
 pub(crate) fn describe_metrics() {
     describe_gauge!(
@@ -33,5 +35,11 @@ pub(crate) fn describe_metrics() {
         USAGE_STATE_SIZE_ACCOUNTING_QUERY_DURATION_SECONDS,
         Unit::Seconds,
         "Accounting query execution duration"
-    )
+    );
+
+    describe_gauge!(
+        LOG_SERVER_DECOMMISSIONER_STALLED_LOGLETS,
+        Unit::Count,
+        "Number of sealed loglets whose evacuation off draining log-servers makes no progress"
+    );
 }
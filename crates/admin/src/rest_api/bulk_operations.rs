@@ -0,0 +1,272 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use datafusion::error::DataFusionError;
+
+use restate_admin_rest_model::bulk_operations::{
+    BulkOperationKind, BulkOperationResponse, CreateBulkOperationRequest,
+    ListBulkOperationsResponse,
+};
+use restate_admin_rest_model::invocations::PatchDeploymentId;
+use restate_storage_query_datafusion::context::QueryError;
+use restate_types::identifiers::BulkOperationId;
+use restate_types::invocation::client::InvocationClient;
+
+use super::error::*;
+use crate::bulk_operations::{BulkOperationControlError, StartBulkOperationError};
+use crate::generate_meta_api_error;
+use crate::state::AdminServiceState;
+
+generate_meta_api_error!(CreateBulkOperationError: [InvalidFieldError, QueryServiceError, MetadataStoreError, ShuttingDownError]);
+
+/// Create a bulk operation
+///
+/// Applies the given operation to all the invocations matched by a SQL predicate over the
+/// `sys_invocation` table. The operation runs in the background on this node, use the returned
+/// id to follow its progress.
+#[utoipa::path(
+    post,
+    path = "/bulk-operations",
+    operation_id = "create_bulk_operation",
+    tag = "invocation",
+    responses(
+        (status = 202, description = "Bulk operation started", body = BulkOperationResponse),
+        CreateBulkOperationError
+    )
+)]
+pub async fn create_bulk_operation<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Json(request): Json<CreateBulkOperationRequest>,
+) -> Result<(StatusCode, Json<BulkOperationResponse>), CreateBulkOperationError>
+where
+    Invocations: InvocationClient + Clone + Send + Sync + 'static,
+{
+    let filter = request.filter.trim().to_owned();
+    if filter.is_empty() {
+        return Err(InvalidFieldError("filter", "must not be empty".to_owned()).into());
+    }
+
+    let deployment = match (request.operation, request.deployment) {
+        (BulkOperationKind::Resume | BulkOperationKind::RestartAsNew, deployment) => deployment
+            .map(PatchDeploymentId::into_client)
+            .transpose()
+            .map_err(|e| InvalidFieldError("deployment", e))?,
+        (_, None) => None,
+        (operation, Some(_)) => {
+            return Err(InvalidFieldError(
+                "deployment",
+                format!("cannot be set for '{operation}' operations"),
+            )
+            .into());
+        }
+    };
+
+    let Some(query_context) = state.query_context.as_ref() else {
+        return Err(
+            QueryServiceError("the query service is not enabled on this node".to_owned()).into(),
+        );
+    };
+
+    let invocations = query_context
+        .filter_table("sys_invocation", &filter)
+        .await
+        .and_then(|invocations| Ok(invocations.build()?))
+        .map_err(|err| match err {
+            QueryError::DataFusion(
+                err @ (DataFusionError::Plan(_)
+                | DataFusionError::SchemaError(_, _)
+                | DataFusionError::SQL(_, _)),
+            ) => CreateBulkOperationError::from(InvalidFieldError("filter", err.to_string())),
+            err => QueryServiceError(err.to_string()).into(),
+        })?;
+
+    let operation = state
+        .bulk_operations
+        .start(
+            state.invocation_client.clone(),
+            query_context.clone(),
+            request.operation,
+            filter,
+            invocations,
+            request.limit,
+            deployment,
+        )
+        .await
+        .map_err(|err| match err {
+            StartBulkOperationError::Shutdown(err) => {
+                CreateBulkOperationError::from(ShuttingDownError(err))
+            }
+            err @ StartBulkOperationError::MetadataStore(_) => {
+                MetadataStoreError(err.to_string()).into()
+            }
+        })?;
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
+
+generate_meta_api_error!(ListBulkOperationsError: [MetadataStoreError]);
+
+/// List bulk operations
+///
+/// Lists the bulk operations running on this node and the recent bulk operations of the
+/// cluster.
+#[utoipa::path(
+    get,
+    path = "/bulk-operations",
+    operation_id = "list_bulk_operations",
+    tag = "invocation",
+    responses(
+        (status = 200, description = "List of bulk operations", body = ListBulkOperationsResponse),
+        ListBulkOperationsError
+    )
+)]
+pub async fn list_bulk_operations<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+) -> Result<Json<ListBulkOperationsResponse>, ListBulkOperationsError> {
+    let operations = state
+        .bulk_operations
+        .list()
+        .await
+        .map_err(|err| MetadataStoreError(err.to_string()))?;
+
+    Ok(Json(ListBulkOperationsResponse { operations }))
+}
+
+generate_meta_api_error!(GetBulkOperationError: [InvalidFieldError, BulkOperationNotFoundError, MetadataStoreError]);
+
+/// Get bulk operation
+#[utoipa::path(
+    get,
+    path = "/bulk-operations/{bulk_operation_id}",
+    operation_id = "get_bulk_operation",
+    tag = "invocation",
+    params(
+        ("bulk_operation_id" = String, Path, description = "Bulk operation identifier."),
+    ),
+    responses(
+        (status = 200, description = "Bulk operation progress", body = BulkOperationResponse),
+        GetBulkOperationError
+    )
+)]
+pub async fn get_bulk_operation<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(bulk_operation_id): Path<String>,
+) -> Result<Json<BulkOperationResponse>, GetBulkOperationError> {
+    let id = parse_bulk_operation_id(&bulk_operation_id)?;
+
+    state
+        .bulk_operations
+        .get(id)
+        .await
+        .map_err(|err| MetadataStoreError(err.to_string()))?
+        .map(Json)
+        .ok_or_else(|| BulkOperationNotFoundError(bulk_operation_id).into())
+}
+
+generate_meta_api_error!(ControlBulkOperationError: [InvalidFieldError, BulkOperationNotFoundError, BulkOperationFinishedError, BulkOperationOnOtherNodeError, MetadataStoreError]);
+
+impl From<BulkOperationControlError> for ControlBulkOperationError {
+    fn from(value: BulkOperationControlError) -> Self {
+        match value {
+            BulkOperationControlError::NotFound(id) => {
+                BulkOperationNotFoundError(id.to_string()).into()
+            }
+            BulkOperationControlError::Finished(id, state) => {
+                BulkOperationFinishedError(id.to_string(), state.to_string()).into()
+            }
+            BulkOperationControlError::RunningOnOtherNode(id, node) => {
+                BulkOperationOnOtherNodeError(id.to_string(), node.to_string()).into()
+            }
+            BulkOperationControlError::MetadataStore(err) => {
+                MetadataStoreError(err.to_string()).into()
+            }
+        }
+    }
+}
+
+/// Pause a bulk operation
+///
+/// Invocations which are being processed are still completed, the operation stops afterwards
+/// until it is resumed.
+#[utoipa::path(
+    patch,
+    path = "/bulk-operations/{bulk_operation_id}/pause",
+    operation_id = "pause_bulk_operation",
+    tag = "invocation",
+    params(
+        ("bulk_operation_id" = String, Path, description = "Bulk operation identifier."),
+    ),
+    responses(
+        (status = 200, description = "Bulk operation paused", body = BulkOperationResponse),
+        ControlBulkOperationError
+    )
+)]
+pub async fn pause_bulk_operation<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(bulk_operation_id): Path<String>,
+) -> Result<Json<BulkOperationResponse>, ControlBulkOperationError> {
+    let id = parse_bulk_operation_id(&bulk_operation_id)?;
+    Ok(Json(state.bulk_operations.pause(id).await?))
+}
+
+/// Resume a bulk operation
+#[utoipa::path(
+    patch,
+    path = "/bulk-operations/{bulk_operation_id}/resume",
+    operation_id = "resume_bulk_operation",
+    tag = "invocation",
+    params(
+        ("bulk_operation_id" = String, Path, description = "Bulk operation identifier."),
+    ),
+    responses(
+        (status = 200, description = "Bulk operation resumed", body = BulkOperationResponse),
+        ControlBulkOperationError
+    )
+)]
+pub async fn resume_bulk_operation<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(bulk_operation_id): Path<String>,
+) -> Result<Json<BulkOperationResponse>, ControlBulkOperationError> {
+    let id = parse_bulk_operation_id(&bulk_operation_id)?;
+    Ok(Json(state.bulk_operations.resume(id).await?))
+}
+
+/// Cancel a bulk operation
+///
+/// Invocations which were already processed are not affected.
+#[utoipa::path(
+    patch,
+    path = "/bulk-operations/{bulk_operation_id}/cancel",
+    operation_id = "cancel_bulk_operation",
+    tag = "invocation",
+    params(
+        ("bulk_operation_id" = String, Path, description = "Bulk operation identifier."),
+    ),
+    responses(
+        (status = 200, description = "Bulk operation cancelled", body = BulkOperationResponse),
+        ControlBulkOperationError
+    )
+)]
+pub async fn cancel_bulk_operation<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(bulk_operation_id): Path<String>,
+) -> Result<Json<BulkOperationResponse>, ControlBulkOperationError> {
+    let id = parse_bulk_operation_id(&bulk_operation_id)?;
+    Ok(Json(state.bulk_operations.cancel(id).await?))
+}
+
+fn parse_bulk_operation_id(bulk_operation_id: &str) -> Result<BulkOperationId, InvalidFieldError> {
+    bulk_operation_id
+        .parse::<BulkOperationId>()
+        .map_err(|e| InvalidFieldError("bulk_operation_id", e.to_string()))
+}
@@ -17,10 +17,12 @@ use axum::response::IntoResponse;
 use axum::{Extension, Json};
 use http::{Method, Uri};
 use serde::Deserialize;
+use tracing::debug;
 
 use restate_admin_rest_model::deployments::*;
 use restate_admin_rest_model::version::AdminApiVersion;
 use restate_errors::warn_it;
+use restate_storage_query_datafusion::context::QueryContext;
 use restate_types::deployment::{HttpDeploymentAddress, LambdaDeploymentAddress};
 use restate_types::identifiers::{DeploymentId, InvalidLambdaARN, ServiceRevision};
 use restate_types::schema;
@@ -32,6 +34,7 @@ use restate_types::schema::registry::{
 use restate_types::schema::service::ServiceMetadata;
 
 use super::error::*;
+use crate::deployment_drainer::count_pinned_invocations;
 use crate::rest_api::ErrorDescriptionResponse;
 use crate::state::AdminServiceState;
 
@@ -216,7 +219,12 @@ where
         .get_deployment_and_services(deployment_id)
         .ok_or_else(|| MetaApiError::DeploymentNotFound(deployment_id))?;
 
-    Ok(to_detailed_deployment_response(deployment, services).into())
+    Ok(with_pinned_invocations(
+        state.query_context.as_ref(),
+        to_detailed_deployment_response(deployment, services),
+    )
+    .await
+    .into())
 }
 
 /// List deployments
@@ -453,6 +461,51 @@ where
     Ok(Json(to_detailed_deployment_response(deployment, services)))
 }
 
+/// Drain deployment
+///
+/// Stops routing new invocations to the deployment. Invocations already pinned to it keep running
+/// until they complete. The deployment must not serve the latest revision of any service, register
+/// a newer deployment first. If `auto_remove` is set, the deployment is removed once no invocation
+/// is pinned to it anymore.
+#[utoipa::path(
+    post,
+    path = "/deployments/{deployment}/drain",
+    operation_id = "drain_deployment",
+    tag = "deployment",
+    params(
+        ("deployment" = String, Path, description = "Deployment identifier"),
+    ),
+    request_body = DrainDeploymentRequest,
+    responses(
+        (status = 200, description = "Deployment is draining", body = DetailedDeploymentResponse),
+        MetaApiError
+    )
+)]
+pub async fn drain_deployment<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(deployment_id): Path<DeploymentId>,
+    payload: Option<Json<DrainDeploymentRequest>>,
+) -> Result<Json<DetailedDeploymentResponse>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    let DrainDeploymentRequest { auto_remove } = payload.map(|Json(p)| p).unwrap_or_default();
+
+    let (deployment, services) = state
+        .schema_registry
+        .drain_deployment(deployment_id, auto_remove)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(Json(
+        with_pinned_invocations(
+            state.query_context.as_ref(),
+            to_detailed_deployment_response(deployment, services),
+        )
+        .await,
+    ))
+}
+
 fn to_register_response(
     Deployment {
         id,
@@ -483,6 +536,7 @@ fn to_deployment_response(
         created_at,
         metadata,
         info,
+        drain,
         ..
     }: Deployment,
     services: Vec<(String, ServiceRevision)>,
@@ -509,6 +563,7 @@ fn to_deployment_response(
                 .map(|(name, revision)| ServiceNameRevPair { name, revision })
                 .collect(),
             info,
+            drain: drain.map(Into::into),
             auth: auth.map(Into::into),
         },
         DeploymentType::Lambda {
@@ -531,6 +586,7 @@ fn to_deployment_response(
                 .map(|(name, revision)| ServiceNameRevPair { name, revision })
                 .collect(),
             info,
+            drain: drain.map(Into::into),
         },
     }
 }
@@ -545,6 +601,7 @@ fn to_detailed_deployment_response(
         created_at,
         metadata,
         info,
+        drain,
         ..
     }: Deployment,
     services: Vec<ServiceMetadata>,
@@ -568,6 +625,7 @@ fn to_detailed_deployment_response(
             sdk_version,
             services,
             info,
+            drain: drain.map(Into::into),
             auth: auth.map(Into::into),
         },
         DeploymentType::Lambda {
@@ -587,10 +645,39 @@ fn to_detailed_deployment_response(
             sdk_version,
             services,
             info,
+            drain: drain.map(Into::into),
         },
     }
 }
 
+/// Fills in the number of invocations pinned to a draining deployment. The count is not part of
+/// the schema, hence it's computed on demand.
+async fn with_pinned_invocations(
+    query_context: Option<&QueryContext>,
+    mut response: DetailedDeploymentResponse,
+) -> DetailedDeploymentResponse {
+    let deployment_id = response.id();
+    let (DetailedDeploymentResponse::Http { drain, .. }
+    | DetailedDeploymentResponse::Lambda { drain, .. }) = &mut response;
+
+    if let (Some(drain), Some(query_context)) = (drain, query_context) {
+        match count_pinned_invocations(query_context, std::iter::once(&deployment_id)).await {
+            Ok(pinned_invocations) => {
+                drain.pinned_invocations = Some(
+                    pinned_invocations
+                        .get(&deployment_id)
+                        .copied()
+                        .unwrap_or_default(),
+                );
+            }
+            Err(e) => {
+                debug!(%deployment_id, "Failed counting pinned invocations: {e:#}");
+            }
+        }
+    }
+    response
+}
+
 #[inline]
 #[allow(clippy::result_large_err)]
 fn validate_uri(uri: &Uri) -> Result<(), MetaApiError> {
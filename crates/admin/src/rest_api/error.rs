@@ -262,6 +262,36 @@ pub(crate) struct RestartAsNewInvocationIncompatibleDeploymentIdError {
 }
 impl_meta_api_error!(RestartAsNewInvocationIncompatibleDeploymentIdError: BAD_REQUEST "The selected deployment id to restart as new the invocation doesn't support the currently pinned service protocol version.");
 
+#[derive(Debug, thiserror::Error)]
+#[error("The requested bulk operation '{0}' does not exist")]
+pub(crate) struct BulkOperationNotFoundError(pub(crate) String);
+impl_meta_api_error!(BulkOperationNotFoundError: NOT_FOUND);
+
+#[derive(Debug, thiserror::Error)]
+#[error("The bulk operation '{0}' is already {1}")]
+pub(crate) struct BulkOperationFinishedError(pub(crate) String, pub(crate) String);
+impl_meta_api_error!(BulkOperationFinishedError: CONFLICT "The bulk operation already finished, so it cannot be paused, resumed nor cancelled.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("The bulk operation '{0}' is running on node {1}")]
+pub(crate) struct BulkOperationOnOtherNodeError(pub(crate) String, pub(crate) String);
+impl_meta_api_error!(BulkOperationOnOtherNodeError: CONFLICT "The bulk operation runs on another admin node, it can only be paused, resumed or cancelled there.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("Cannot query the invocations. Reason: {0}")]
+pub(crate) struct QueryServiceError(pub(crate) String);
+impl_meta_api_error!(QueryServiceError: SERVICE_UNAVAILABLE "The query service, used to select the invocations, is not available on this node.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("Error when accessing the metadata store. Reason: {0}")]
+pub(crate) struct MetadataStoreError(pub(crate) String);
+impl_meta_api_error!(MetadataStoreError: SERVICE_UNAVAILABLE "Error when accessing the metadata store.");
+
+#[derive(Debug, thiserror::Error)]
+#[error("The node is shutting down")]
+pub(crate) struct ShuttingDownError(#[from] pub(crate) ShutdownError);
+impl_meta_api_error!(ShuttingDownError: SERVICE_UNAVAILABLE "The node is shutting down, retry on another node.");
+
 // --- Old Meta API errors. Please don't use these anymore.
 
 /// This error is used by handlers to propagate API errors,
@@ -283,6 +313,8 @@ pub enum MetaApiError {
     SubscriptionNotFound(SubscriptionId),
     #[error("The requested Kafka cluster '{0}' does not exist")]
     KafkaClusterNotFound(String),
+    #[error("The service '{0}' has no traffic split")]
+    TrafficSplitNotFound(String),
     #[error("Cannot {0} for service type {1}")]
     UnsupportedOperation(&'static str, ServiceType),
     #[error(transparent)]
@@ -295,6 +327,8 @@ pub enum MetaApiError {
     DeprecatedPutDeployment,
     #[error("bad scope: {0}")]
     BadScope(RestrictedValueError),
+    #[error("The query service is not available on this node")]
+    QueryServiceUnavailable,
 }
 
 impl IntoResponse for MetaApiError {
@@ -304,13 +338,15 @@ impl IntoResponse for MetaApiError {
             | MetaApiError::HandlerNotFound { .. }
             | MetaApiError::DeploymentNotFound(_)
             | MetaApiError::SubscriptionNotFound(_)
-            | MetaApiError::KafkaClusterNotFound(_) => StatusCode::NOT_FOUND,
+            | MetaApiError::KafkaClusterNotFound(_)
+            | MetaApiError::TrafficSplitNotFound(_) => StatusCode::NOT_FOUND,
             MetaApiError::InvalidField(_, _) | MetaApiError::UnsupportedOperation(_, _) => {
                 StatusCode::BAD_REQUEST
             }
             MetaApiError::Schema(error) => error.status_code(),
             MetaApiError::Conflict(_) => StatusCode::CONFLICT,
             MetaApiError::DeprecatedPutDeployment => StatusCode::METHOD_NOT_ALLOWED,
+            MetaApiError::QueryServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
         let body = Json(match &self {
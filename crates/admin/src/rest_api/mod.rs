@@ -10,6 +10,7 @@
 
 //! This module implements the Meta API endpoint.
 
+mod bulk_operations;
 mod cluster_health;
 mod deployments;
 mod error;
@@ -24,10 +25,12 @@ mod services;
 mod subscriptions;
 mod version;
 
+use axum::extract::DefaultBodyLimit;
 use serde::Serialize;
 use utoipa::OpenApi;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
+use restate_admin_rest_model::services::STATE_IMPORT_MAX_BODY_SIZE;
 use restate_core::network::TransportConnect;
 use restate_types::identifiers::PartitionKey;
 use restate_types::invocation::client::InvocationClient;
@@ -97,12 +100,23 @@ where
             .routes(routes!(deployments::get_deployment))
             .routes(routes!(deployments::delete_deployment))
             .routes(routes!(deployments::update_deployment))
+            .routes(routes!(deployments::drain_deployment))
             // Service endpoints
             .routes(routes!(services::list_services))
             .routes(routes!(services::get_service))
             .routes(routes!(services::get_service_openapi))
             .routes(routes!(services::modify_service))
             .routes(routes!(services::modify_service_state))
+            .routes(routes!(services::export_service_state))
+            .merge(
+                // State imports carry many instances, exceeding the default body limit
+                OpenApiRouter::new()
+                    .routes(routes!(services::import_service_state))
+                    .layer(DefaultBodyLimit::max(STATE_IMPORT_MAX_BODY_SIZE)),
+            )
+            .routes(routes!(services::get_service_traffic_split))
+            .routes(routes!(services::set_service_traffic_split))
+            .routes(routes!(services::delete_service_traffic_split))
             // Handler endpoints
             .routes(routes!(handlers::list_service_handlers))
             .routes(routes!(handlers::get_service_handler))
@@ -115,6 +129,13 @@ where
             .routes(routes!(invocations::restart_as_new_invocation))
             .routes(routes!(invocations::resume_invocation))
             .routes(routes!(invocations::pause_invocation))
+            // Bulk operation endpoints
+            .routes(routes!(bulk_operations::create_bulk_operation))
+            .routes(routes!(bulk_operations::list_bulk_operations))
+            .routes(routes!(bulk_operations::get_bulk_operation))
+            .routes(routes!(bulk_operations::pause_bulk_operation))
+            .routes(routes!(bulk_operations::resume_bulk_operation))
+            .routes(routes!(bulk_operations::cancel_bulk_operation))
             // Subscription endpoints
             .routes(routes!(subscriptions::create_subscription))
             .routes(routes!(subscriptions::list_subscriptions))
@@ -131,6 +152,7 @@ where
             .routes(routes!(rules::delete_rules))
             // Query endpoint
             .routes(routes!(query::query))
+            .routes(routes!(query::state_mutations))
     };
 
     let (router, api) = router.split_for_parts();
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -28,14 +29,24 @@ use http_body::Frame;
 use http_body_util::StreamBody;
 use parking_lot::Mutex;
 use serde::Serialize;
+use tracing::warn;
 
-use restate_admin_rest_model::query::QueryRequest;
+use restate_admin_rest_model::query::{QueryRequest, StateMutationsPlan, StateMutationsRequest};
 use restate_core::network::TransportConnect;
+use restate_ingestion_client::IngestionClient;
+use restate_storage_query_datafusion::context::StatementResult;
+use restate_types::Scope;
+use restate_types::identifiers::{PartitionId, PartitionKey, ServiceId, WithPartitionKey};
 use restate_types::invocation::client::InvocationClient;
+use restate_types::partitions::FindPartition;
 use restate_types::schema::registry::{DiscoveryClient, MetadataService, TelemetryClient};
+use restate_types::state_mut::ExternalStateMutation;
+use restate_wal_protocol::{Command, Envelope};
 
+use super::create_envelope_header;
 use crate::query_utils::{RecordBatchWriter, WriteRecordBatchStream};
 use crate::state::AdminServiceState;
+use crate::state_dml;
 
 const RETRY_AFTER_HEADER: &str = "Retry-After";
 
@@ -54,6 +65,10 @@ pub(crate) enum QueryError {
     Unavailable,
     #[error("Rate limited")]
     RateLimited(#[from] gardal::RateLimited),
+    #[error("Only DELETE and UPDATE statements on the state table can be planned")]
+    NotAStateMutation,
+    #[error("Failed submitting the state mutations to the cluster: {0}")]
+    Submission(String),
 }
 
 impl From<restate_storage_query_datafusion::context::QueryError> for QueryError {
@@ -75,10 +90,11 @@ impl IntoResponse for QueryError {
         let status_code = match &self {
             QueryError::Datafusion(datafusion::error::DataFusionError::Plan(_))
             | QueryError::Datafusion(datafusion::error::DataFusionError::SchemaError(_, _))
-            | QueryError::Datafusion(datafusion::error::DataFusionError::SQL(_, _)) => {
-                StatusCode::BAD_REQUEST
+            | QueryError::Datafusion(datafusion::error::DataFusionError::SQL(_, _))
+            | QueryError::NotAStateMutation => StatusCode::BAD_REQUEST,
+            QueryError::Datafusion(_) | QueryError::Submission(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
             }
-            QueryError::Datafusion(_) => StatusCode::INTERNAL_SERVER_ERROR,
             QueryError::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
             QueryError::RateLimited(e) => {
                 headers.insert(
@@ -167,6 +183,126 @@ where
         .into_response())
 }
 
+/// Modify state by using SQL.
+///
+/// Computes the state mutations of a `DELETE` or `UPDATE` statement on the `state` table and
+/// submits them to the cluster, one mutation per affected Virtual Object or Workflow instance.
+/// The statement itself is never applied by the query engine. Only the `value` column can be
+/// updated. Each mutation is applied asynchronously, after the ongoing invocations of its
+/// instance, and is skipped if the state of the instance changed since the mutation was planned.
+/// With `dry_run`, the mutations are only planned and returned.
+#[utoipa::path(
+    post,
+    path = "/query/state-mutations",
+    operation_id = "state_mutations",
+    tag = "introspection",
+    responses(
+        (status = 200, description = "Planned state mutations, not submitted (dry run)", body = StateMutationsPlan),
+        (status = 202, description = "State mutations submitted and will be applied asynchronously", body = StateMutationsPlan),
+        (status = 400, description = "Not a DELETE or UPDATE statement on the state table", body = QueryErrorBody),
+        (status = 500, description = "Datafusion error or the mutations could not be submitted", body = QueryErrorBody),
+        (status = 503, description = "Query service not available", body = QueryErrorBody),
+    )
+)]
+pub(crate) async fn state_mutations<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(mut state): State<
+        AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>,
+    >,
+    Json(StateMutationsRequest { query, dry_run }): Json<StateMutationsRequest>,
+) -> Result<(StatusCode, Json<StateMutationsPlan>), QueryError>
+where
+    Metadata: MetadataService + Send + Sync + Clone + 'static,
+    Discovery: DiscoveryClient + Send + Sync + Clone + 'static,
+    Telemetry: TelemetryClient + Send + Sync + Clone + 'static,
+    Invocations: InvocationClient + Send + Sync + Clone + 'static,
+    Transport: TransportConnect,
+{
+    let Some(query_context) = state.query_context.as_ref() else {
+        return Err(QueryError::Unavailable);
+    };
+
+    let StatementResult::StateDml(dml) = query_context.execute_statement(&query).await? else {
+        return Err(QueryError::NotAStateMutation);
+    };
+
+    let mut plan = state_dml::plan_state_dml(query_context, dml).await?;
+    if dry_run || plan.mutations.is_empty() {
+        return Ok((StatusCode::OK, Json(plan)));
+    }
+
+    submit_state_mutations(&mut state.ingestion_client, &plan).await?;
+    plan.submitted = true;
+
+    Ok((StatusCode::ACCEPTED, Json(plan)))
+}
+
+/// Appends the planned mutations as [`ExternalStateMutation`] commands, with one ingestion batch
+/// per partition, and waits until all of them are committed.
+async fn submit_state_mutations<Transport: TransportConnect>(
+    ingestion_client: &mut IngestionClient<Transport, Envelope>,
+    plan: &StateMutationsPlan,
+) -> Result<(), QueryError> {
+    let partition_table = ingestion_client.partition_table().pinned();
+    let mut batches: BTreeMap<PartitionId, (PartitionKey, Vec<Envelope>)> = BTreeMap::new();
+    for mutation in &plan.mutations {
+        let scope = mutation
+            .request
+            .scope
+            .as_deref()
+            .map(Scope::try_non_interned)
+            .transpose()
+            .map_err(|err| QueryError::Submission(err.to_string()))?;
+        let service_id = ServiceId::new(
+            scope,
+            mutation.service_name.clone(),
+            mutation.request.object_key.clone(),
+        );
+        let partition_key = service_id.partition_key();
+        let partition_id = partition_table
+            .find_partition_id(partition_key)
+            .map_err(|err| QueryError::Submission(err.to_string()))?;
+
+        let patch_state = ExternalStateMutation {
+            service_id,
+            version: mutation.request.version.clone(),
+            state: mutation
+                .request
+                .new_state
+                .iter()
+                .map(|(key, value)| (Bytes::from(key.clone()), value.clone()))
+                .collect(),
+        };
+        batches
+            .entry(partition_id)
+            .or_insert_with(|| (partition_key, Vec::new()))
+            .1
+            .push(Envelope::new(
+                create_envelope_header(partition_key),
+                Command::PatchState(patch_state),
+            ));
+    }
+    drop(partition_table);
+
+    let ingestions = batches
+        .into_values()
+        .map(|(partition_key, envelopes)| ingestion_client.ingest_batch(partition_key, envelopes))
+        .collect::<Vec<_>>();
+    let commits = futures::future::try_join_all(ingestions)
+        .await
+        .map_err(|err| {
+            warn!("Could not ingest state mutation commands: {err}");
+            QueryError::Submission(err.to_string())
+        })?;
+    futures::future::try_join_all(commits.into_iter().flatten())
+        .await
+        .map_err(|err| {
+            warn!("Could not ingest state mutation commands: {err}");
+            QueryError::Submission(err.to_string())
+        })?;
+
+    Ok(())
+}
+
 #[derive(Clone)]
 // unfortunately the json writer doesnt give a way to get a mutable reference to the underlying writer, so we need another pointer in to its buffer
 // we use a lock here to help make the writer send/sync, despite it being totally uncontended :(
@@ -8,12 +8,18 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::{BTreeMap, HashMap};
+
 use tracing::{debug, warn};
 
 use axum::Json;
 use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
+use futures::TryStreamExt;
 use http::StatusCode;
+use http_body::Frame;
+use http_body_util::StreamBody;
 
 use restate_admin_rest_model::services::ListServicesResponse;
 use restate_admin_rest_model::services::*;
@@ -21,16 +27,18 @@ use restate_core::TaskCenter;
 use restate_core::network::TransportConnect;
 use restate_errors::warn_it;
 use restate_types::config::Configuration;
-use restate_types::identifiers::{ServiceId, WithPartitionKey};
+use restate_types::identifiers::{PartitionId, PartitionKey, ServiceId, WithPartitionKey};
+use restate_types::partitions::FindPartition;
 use restate_types::schema::registry::MetadataService;
 use restate_types::schema::service::ServiceMetadata;
-use restate_types::state_mut::ExternalStateMutation;
+use restate_types::state_mut::{ExternalStateMutation, StateMutationVersion};
 use restate_types::{Scope, schema};
 use restate_wal_protocol::{Command, Envelope};
 
 use super::create_envelope_header;
 use super::error::*;
 use crate::state::AdminServiceState;
+use crate::state_export;
 
 /// List services
 ///
@@ -125,7 +133,7 @@ where
 
 /// Modify service configuration
 ///
-/// Updates the configuration of a registered service, such as public visibility, retention policies, and timeout settings.
+/// Updates the configuration of a registered service, such as public visibility, retention policies, timeout settings and tracing sampling.
 /// Note: Service re-discovery will update these settings based on the service endpoint configuration.
 #[utoipa::path(
     patch,
@@ -150,11 +158,20 @@ pub async fn modify_service<Metadata, Discovery, Telemetry, Invocations, Transpo
         journal_retention,
         inactivity_timeout,
         abort_timeout,
+        tracing_sample_ratio,
+        tracing_failure_markers,
     }): Json<ModifyServiceRequest>,
 ) -> Result<Json<ServiceMetadata>, MetaApiError>
 where
     Metadata: MetadataService,
 {
+    if tracing_sample_ratio.is_some_and(|ratio| !(0.0..=1.0).contains(&ratio)) {
+        return Err(MetaApiError::InvalidField(
+            "tracing_sample_ratio",
+            "must be a number between 0 and 1".to_owned(),
+        ));
+    }
+
     let modify_request = schema::registry::ModifyServiceRequest {
         public,
         idempotency_retention,
@@ -162,6 +179,8 @@ where
         workflow_completion_retention,
         inactivity_timeout,
         abort_timeout,
+        tracing_sample_ratio,
+        tracing_failure_markers,
     };
 
     if modify_request.public.is_none()
@@ -170,6 +189,8 @@ where
         && modify_request.workflow_completion_retention.is_none()
         && modify_request.inactivity_timeout.is_none()
         && modify_request.abort_timeout.is_none()
+        && modify_request.tracing_sample_ratio.is_none()
+        && modify_request.tracing_failure_markers.is_none()
     {
         // No need to do anything
         return get_service(State(state), Path(service_name)).await;
@@ -184,6 +205,107 @@ where
     Ok(response.into())
 }
 
+/// Get service traffic split
+///
+/// Returns the weights used to route new invocations of the service between its deployments.
+#[utoipa::path(
+    get,
+    path = "/services/{service}/traffic-split",
+    operation_id = "get_service_traffic_split",
+    tag = "service",
+    params(
+        ("service" = String, Path, description = "Fully qualified service name."),
+    ),
+    responses(
+        (status = 200, description = "Traffic split of the service", body = TrafficSplitResponse),
+        MetaApiError
+    )
+)]
+pub async fn get_service_traffic_split<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(service_name): Path<String>,
+) -> Result<Json<TrafficSplitResponse>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    if state.schema_registry.get_service(&service_name).is_none() {
+        return Err(MetaApiError::ServiceNotFound(service_name));
+    }
+
+    let Some(traffic_split) = state.schema_registry.get_traffic_split(&service_name) else {
+        return Err(MetaApiError::TrafficSplitNotFound(service_name));
+    };
+
+    Ok(TrafficSplitResponse::new(service_name, traffic_split).into())
+}
+
+/// Set service traffic split
+///
+/// Routes new invocations of the service between the given deployments, proportionally to their weights.
+/// Invocations already pinned to a deployment are not affected.
+/// Use this to roll out a new deployment to a fraction of the traffic, and to shift or roll back the traffic afterwards.
+#[utoipa::path(
+    put,
+    path = "/services/{service}/traffic-split",
+    operation_id = "set_service_traffic_split",
+    tag = "service",
+    params(
+        ("service" = String, Path, description = "Fully qualified service name."),
+    ),
+    responses(
+        (status = 200, description = "Traffic split updated successfully", body = TrafficSplitResponse),
+        MetaApiError
+    )
+)]
+pub async fn set_service_traffic_split<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(service_name): Path<String>,
+    Json(request): Json<TrafficSplitRequest>,
+) -> Result<Json<TrafficSplitResponse>, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    let traffic_split = state
+        .schema_registry
+        .set_traffic_split(service_name.clone(), request.into())
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(TrafficSplitResponse::new(service_name, traffic_split).into())
+}
+
+/// Remove service traffic split
+///
+/// Removes the traffic split of the service. New invocations are routed again to the deployment serving the latest revision of the service.
+#[utoipa::path(
+    delete,
+    path = "/services/{service}/traffic-split",
+    operation_id = "delete_service_traffic_split",
+    tag = "service",
+    params(
+        ("service" = String, Path, description = "Fully qualified service name."),
+    ),
+    responses(
+        (status = 202, description = "Traffic split removed successfully"),
+        MetaApiError
+    )
+)]
+pub async fn delete_service_traffic_split<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(service_name): Path<String>,
+) -> Result<StatusCode, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    state
+        .schema_registry
+        .remove_traffic_split(service_name)
+        .await
+        .inspect_err(|e| warn_it!(e))?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
 /// Modify service state
 ///
 /// Modifies the K/V state of a Virtual Object. For a detailed description of this API and how to use it, see the [state documentation](https://docs.restate.dev/operate/invocation#modifying-service-state).
@@ -275,3 +397,228 @@ where
         Ok(StatusCode::ACCEPTED)
     }
 }
+
+/// Export service state
+///
+/// Streams the K/V state of all the instances of a Virtual Object or Workflow as newline-delimited JSON, with one `ServiceStateEntry` per instance. The exported entries can be imported back with the state import API.
+#[utoipa::path(
+    get,
+    path = "/services/{service}/state",
+    operation_id = "export_service_state",
+    tag = "service",
+    params(
+        ("service" = String, Path, description = "Fully qualified service name."),
+    ),
+    responses(
+        (status = 200, description = "State of all the service instances",
+            content(("application/x-ndjson" = ServiceStateEntry))),
+        MetaApiError
+    )
+)]
+pub async fn export_service_state<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(state): State<AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>>,
+    Path(service_name): Path<String>,
+) -> Result<Response, MetaApiError>
+where
+    Metadata: MetadataService,
+{
+    let Some(svc) = state.schema_registry.get_service(&service_name) else {
+        return Err(MetaApiError::ServiceNotFound(service_name));
+    };
+    if !svc.ty.has_state() {
+        return Err(MetaApiError::UnsupportedOperation("export state", svc.ty));
+    }
+
+    let Some(query_context) = state.query_context.as_ref() else {
+        return Err(MetaApiError::QueryServiceUnavailable);
+    };
+
+    let query_result = query_context
+        .execute(&state_export::export_query(&service_name))
+        .await
+        .map_err(|err| {
+            warn!("Could not query the state of service {service_name}: {err}");
+            MetaApiError::Internal(format!("Failed reading the service state: {err}"))
+        })?;
+
+    Ok(Response::builder()
+        .header(http::header::CONTENT_TYPE, "application/x-ndjson")
+        .body(StreamBody::new(
+            state_export::export_stream(query_result.stream).map_ok(Frame::data),
+        ))
+        .expect("content-type header is correct")
+        .into_response())
+}
+
+/// Import service state
+///
+/// Imports the K/V state of instances of a Virtual Object or Workflow, as returned by the state export API. All the entries are validated against their `version` before any of them is applied. Unless `overwrite` is set, instances which already have some state are left untouched: their entries are skipped and listed in the response.
+#[utoipa::path(
+    post,
+    path = "/services/{service}/state/import",
+    operation_id = "import_service_state",
+    tag = "service",
+    params(
+        ("service" = String, Path, description = "Fully qualified service name."),
+    ),
+    responses(
+        (status = 202, description = "State import enqueued and will be applied asynchronously", body = ImportServiceStateResponse),
+        MetaApiError
+    )
+)]
+pub async fn import_service_state<Metadata, Discovery, Telemetry, Invocations, Transport>(
+    State(mut state): State<
+        AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transport>,
+    >,
+    Path(service_name): Path<String>,
+    Json(ImportServiceStateRequest { entries, overwrite }): Json<ImportServiceStateRequest>,
+) -> Result<(StatusCode, Json<ImportServiceStateResponse>), MetaApiError>
+where
+    Metadata: MetadataService,
+    Transport: TransportConnect,
+{
+    let Some(svc) = state.schema_registry.get_service(&service_name) else {
+        return Err(MetaApiError::ServiceNotFound(service_name));
+    };
+    if !svc.ty.has_state() {
+        return Err(MetaApiError::UnsupportedOperation("import state", svc.ty));
+    }
+    if entries.len() > STATE_IMPORT_MAX_ENTRIES {
+        return Err(MetaApiError::InvalidField(
+            "entries",
+            format!("at most {STATE_IMPORT_MAX_ENTRIES} entries can be imported at once"),
+        ));
+    }
+
+    // Without overwrite, the mutation applies only if the instance has no state yet
+    let expected_version =
+        (!overwrite).then(|| StateMutationVersion::from_user_state(&[]).into_inner());
+
+    // Validate all the entries first, so that a bad entry doesn't leave a partial import behind
+    let mut mutations = Vec::with_capacity(entries.len());
+    for ServiceStateEntry {
+        object_key,
+        scope,
+        version,
+        state,
+    } in entries
+    {
+        let state: HashMap<Bytes, Bytes> = state
+            .into_iter()
+            .map(|(k, v)| (Bytes::from(k), v))
+            .collect();
+        let kvs: Vec<_> = state.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        if StateMutationVersion::from_user_state(&kvs).into_inner() != version {
+            return Err(MetaApiError::InvalidField(
+                "entries",
+                format!("the version of the state of '{object_key}' doesn't match its content"),
+            ));
+        }
+
+        let service_scope = if let Some(scope) = &scope {
+            Some(Scope::try_non_interned(scope).map_err(MetaApiError::BadScope)?)
+        } else {
+            None
+        };
+
+        mutations.push((
+            SkippedServiceStateEntry {
+                object_key: object_key.clone(),
+                scope,
+            },
+            ExternalStateMutation {
+                service_id: ServiceId::new(service_scope, service_name.clone(), object_key),
+                version: expected_version.clone(),
+                state,
+            },
+        ));
+    }
+
+    // The partition processor skips the mutations of instances which have some state anyway,
+    // checking upfront lets the response report them.
+    let mut skipped = Vec::new();
+    if !overwrite && !mutations.is_empty() {
+        let Some(query_context) = state.query_context.as_ref() else {
+            return Err(MetaApiError::QueryServiceUnavailable);
+        };
+        let query = state_export::instances_with_state_query(
+            &service_name,
+            mutations.iter().map(|(entry, _)| entry.object_key.as_str()),
+        );
+        let query_result = query_context.execute(&query).await.map_err(|err| {
+            warn!("Could not query the state of service {service_name}: {err}");
+            MetaApiError::Internal(format!("Failed reading the service state: {err}"))
+        })?;
+        let instances_with_state = state_export::collect_instances(query_result.stream)
+            .await
+            .map_err(|err| {
+                warn!("Could not query the state of service {service_name}: {err}");
+                MetaApiError::Internal(format!("Failed reading the service state: {err}"))
+            })?;
+
+        mutations.retain(|(entry, _)| {
+            let has_state =
+                instances_with_state.contains(&(entry.scope.clone(), entry.object_key.clone()));
+            if has_state {
+                skipped.push(entry.clone());
+            }
+            !has_state
+        });
+    }
+
+    // One ingestion call per partition
+    let mut batches: BTreeMap<PartitionId, (PartitionKey, Vec<Envelope>)> = BTreeMap::new();
+    {
+        let partition_table = state.ingestion_client.partition_table().pinned();
+        for (_, mutation) in mutations {
+            let partition_key = mutation.service_id.partition_key();
+            let partition_id = partition_table
+                .find_partition_id(partition_key)
+                .map_err(|err| {
+                    warn!("Could not ingest state import commands: {err}");
+                    MetaApiError::Internal(
+                        "Failed sending state import commands to the cluster.".to_owned(),
+                    )
+                })?;
+            batches
+                .entry(partition_id)
+                .or_insert_with(|| (partition_key, Vec::new()))
+                .1
+                .push(Envelope::new(
+                    create_envelope_header(partition_key),
+                    Command::PatchState(mutation),
+                ));
+        }
+    }
+
+    let enqueued = batches.values().map(|(_, envelopes)| envelopes.len()).sum();
+    let ingestions = batches
+        .into_values()
+        .map(|(partition_key, envelopes)| {
+            state
+                .ingestion_client
+                .ingest_batch(partition_key, envelopes)
+        })
+        .collect::<Vec<_>>();
+    let commits = futures::future::try_join_all(ingestions)
+        .await
+        .map_err(|err| {
+            warn!("Could not ingest state import commands: {err}");
+            MetaApiError::Internal(
+                "Failed sending state import commands to the cluster.".to_owned(),
+            )
+        })?;
+    futures::future::try_join_all(commits.into_iter().flatten())
+        .await
+        .map_err(|err| {
+            warn!("Could not ingest state import commands: {err}");
+            MetaApiError::Internal(
+                "Failed sending state import commands to the cluster.".to_owned(),
+            )
+        })?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ImportServiceStateResponse { enqueued, skipped }),
+    ))
+}
@@ -22,8 +22,9 @@ use tower_http::trace::TraceLayer;
 use tracing::{Span, debug, info, info_span};
 
 use restate_admin_rest_model::version::AdminApiVersion;
+use restate_core::network::tls::TlsContext;
 use restate_core::network::{TransportConnect, net_util};
-use restate_core::{MetadataWriter, TaskCenter};
+use restate_core::{MetadataWriter, TaskCenter, TaskKind};
 use restate_limiter::rule_book::RuleBookObserver;
 use restate_metadata_store::MetadataStoreClient;
 use restate_service_client::HttpClient;
@@ -37,6 +38,7 @@ use restate_types::net::listener::Listeners;
 use restate_types::schema::registry::SchemaRegistry;
 use restate_util_time::DurationExt;
 
+use crate::deployment_drainer::DeploymentDrainer;
 use crate::rest_api::{MAX_ADMIN_API_VERSION, MIN_ADMIN_API_VERSION};
 use crate::schema_registry_integration::{MetadataService, TelemetryClient};
 use crate::{rest_api, state};
@@ -111,6 +113,19 @@ where
     ) -> anyhow::Result<()> {
         let opts = updateable_config.live_load();
 
+        if let Some(query_context) = &self.query_context {
+            TaskCenter::spawn(
+                TaskKind::Background,
+                "deployment-drainer",
+                DeploymentDrainer::new(
+                    self.schema_registry.clone(),
+                    query_context.clone(),
+                    opts.deployment_drain_check_interval.into(),
+                )
+                .run(),
+            )?;
+        }
+
         let rest_state = state::AdminServiceState::new(
             self.schema_registry,
             self.serdes_client,
@@ -217,13 +232,18 @@ where
             );
 
         let service = hyper_util::service::TowerToHyperService::new(router.into_service());
+        let tls = opts
+            .admin_listener_options()
+            .tls()
+            .map(TlsContext::for_listener)
+            .transpose()?;
 
         info!(
             "Admin API starting on: {}",
             TaskCenter::with_current(|tc| opts.advertised_address(tc.address_book()))
         );
 
-        net_util::run_hyper_server(self.listeners, service, || ())
+        net_util::run_hyper_server(self.listeners, service, tls, || ())
             .await
             .map_err(Into::into)
     }
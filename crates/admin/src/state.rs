@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use crate::bulk_operations::BulkOperations;
 use restate_core::network::TransportConnect;
 use restate_ingestion_client::IngestionClient;
 use restate_limiter::rule_book::RuleBookObserver;
@@ -30,6 +31,7 @@ pub struct AdminServiceState<Metadata, Discovery, Telemetry, Invocations, Transp
     // Some value if the query endpoint is activated
     pub query_context: Option<QueryContext>,
     pub rule_book_observer: Option<Arc<dyn RuleBookObserver>>,
+    pub bulk_operations: BulkOperations,
 }
 
 impl<Metadata, Discovery, Telemetry, Invocations, Transport>
@@ -46,6 +48,7 @@ where
         query_context: Option<QueryContext>,
         rule_book_observer: Option<Arc<dyn RuleBookObserver>>,
     ) -> Self {
+        let bulk_operations = BulkOperations::new(metadata_store_client.clone());
         Self {
             schema_registry,
             serdes_client,
@@ -54,6 +57,7 @@ where
             metadata_store_client,
             query_context,
             rule_book_observer,
+            bulk_operations,
         }
     }
 }
@@ -0,0 +1,305 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! `DELETE` and `UPDATE` statements on the `state` table.
+//!
+//! The query engine only returns the rows affected by such a statement. These are grouped per
+//! instance and applied to the current state of the instance, resulting in one
+//! [`PlannedStateMutation`] per instance. The statement itself is never applied: the state
+//! mutations endpoint submits the planned mutations to the partition processors. Each mutation
+//! carries the version of the state it was computed from, so it is skipped by the partition
+//! processor if the state changed in the meantime.
+
+use std::collections::{BTreeMap, HashMap};
+
+use bytes::Bytes;
+use datafusion::arrow::array::{Array, AsArray, LargeBinaryArray, LargeStringArray, RecordBatch};
+use datafusion::error::DataFusionError;
+use futures::StreamExt;
+
+use restate_admin_rest_model::query::{PlannedStateMutation, StateChange, StateMutationsPlan};
+use restate_admin_rest_model::services::ModifyServiceStateRequest;
+use restate_storage_query_datafusion::context::{QueryContext, QueryError, StateDml, StateDmlOp};
+use restate_types::state_mut::StateMutationVersion;
+
+/// Maximum number of instances a single statement can modify
+const MAX_AFFECTED_INSTANCES: usize = 10_000;
+
+/// Number of instances whose current state is read by a single query
+const STATE_LOOKUP_CHUNK_SIZE: usize = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Instance {
+    service_name: String,
+    service_key: String,
+    scope: Option<String>,
+}
+
+/// Changes to the state of an instance: the new value of each affected key, `None` if deleted.
+type Changes = BTreeMap<String, Option<Bytes>>;
+
+pub(crate) async fn plan_state_dml(
+    query_context: &QueryContext,
+    dml: StateDml,
+) -> Result<StateMutationsPlan, QueryError> {
+    let changes = collect_changes(dml).await?;
+
+    let mut instances_by_service: BTreeMap<&str, Vec<&Instance>> = BTreeMap::new();
+    for instance in changes.keys() {
+        instances_by_service
+            .entry(&instance.service_name)
+            .or_default()
+            .push(instance);
+    }
+
+    let mut current_state: HashMap<Instance, HashMap<String, Bytes>> = HashMap::new();
+    for (service_name, instances) in instances_by_service {
+        for chunk in instances.chunks(STATE_LOOKUP_CHUNK_SIZE) {
+            let service_keys = chunk
+                .iter()
+                .map(|instance| format!("'{}'", escape(&instance.service_key)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let sql = format!(
+                "SELECT scope, service_key, key, value FROM state \
+                 WHERE service_name = '{}' AND service_key IN ({service_keys})",
+                escape(service_name)
+            );
+
+            let mut stream = query_context.execute(&sql).await?.stream;
+            while let Some(batch) = stream.next().await {
+                let batch = batch?;
+                let scopes = string_column(&batch, "scope")?;
+                let service_keys = string_column(&batch, "service_key")?;
+                let keys = string_column(&batch, "key")?;
+                let values = binary_column(&batch, "value")?;
+
+                for row in 0..batch.num_rows() {
+                    let instance = Instance {
+                        service_name: service_name.to_owned(),
+                        service_key: service_keys.value(row).to_owned(),
+                        scope: scopes.is_valid(row).then(|| scopes.value(row).to_owned()),
+                    };
+                    // The lookup is by service key only, skip the instances of other scopes
+                    if changes.contains_key(&instance) {
+                        current_state.entry(instance).or_default().insert(
+                            keys.value(row).to_owned(),
+                            Bytes::copy_from_slice(values.value(row)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(build_plan(changes, current_state))
+}
+
+async fn collect_changes(dml: StateDml) -> Result<BTreeMap<Instance, Changes>, DataFusionError> {
+    let mut changes: BTreeMap<Instance, Changes> = BTreeMap::new();
+
+    let mut rows = dml.rows;
+    while let Some(batch) = rows.next().await {
+        let batch = batch?;
+        let scopes = string_column(&batch, "scope")?;
+        let service_names = string_column(&batch, "service_name")?;
+        let service_keys = string_column(&batch, "service_key")?;
+        let keys = string_column(&batch, "key")?;
+        let values = binary_column(&batch, "value")?;
+
+        for row in 0..batch.num_rows() {
+            let new_value = match dml.op {
+                StateDmlOp::Delete => None,
+                StateDmlOp::Update if values.is_null(row) => {
+                    return Err(DataFusionError::Plan(
+                        "the value of a state entry cannot be set to NULL, use DELETE instead"
+                            .to_owned(),
+                    ));
+                }
+                StateDmlOp::Update => Some(Bytes::copy_from_slice(values.value(row))),
+            };
+            let instance = Instance {
+                service_name: service_names.value(row).to_owned(),
+                service_key: service_keys.value(row).to_owned(),
+                scope: scopes.is_valid(row).then(|| scopes.value(row).to_owned()),
+            };
+
+            changes
+                .entry(instance)
+                .or_default()
+                .insert(keys.value(row).to_owned(), new_value);
+            if changes.len() > MAX_AFFECTED_INSTANCES {
+                return Err(DataFusionError::Plan(format!(
+                    "the statement modifies the state of more than {MAX_AFFECTED_INSTANCES} \
+                     instances, narrow down its WHERE clause"
+                )));
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Applies the changes to the current state of each instance. Instances without any current state
+/// are skipped: their state was cleared after the statement read it.
+fn build_plan(
+    changes: BTreeMap<Instance, Changes>,
+    mut current_state: HashMap<Instance, HashMap<String, Bytes>>,
+) -> StateMutationsPlan {
+    let mut mutations = Vec::with_capacity(changes.len());
+    for (instance, instance_changes) in changes {
+        let Some(current) = current_state.remove(&instance) else {
+            continue;
+        };
+
+        let kvs: Vec<_> = current
+            .iter()
+            .map(|(key, value)| (Bytes::from(key.clone()), value.clone()))
+            .collect();
+        let version = StateMutationVersion::from_user_state(&kvs).into_inner();
+
+        let mut new_state = current.clone();
+        let mut state_changes = Vec::with_capacity(instance_changes.len());
+        for (key, new_value) in instance_changes {
+            match &new_value {
+                Some(value) => new_state.insert(key.clone(), value.clone()),
+                None => new_state.remove(&key),
+            };
+            state_changes.push(StateChange {
+                old_value: current.get(&key).cloned(),
+                key,
+                new_value,
+            });
+        }
+
+        mutations.push(PlannedStateMutation {
+            service_name: instance.service_name,
+            request: ModifyServiceStateRequest {
+                version: Some(version),
+                object_key: instance.service_key,
+                scope: instance.scope,
+                new_state,
+            },
+            changes: state_changes,
+        });
+    }
+
+    StateMutationsPlan {
+        mutations,
+        submitted: false,
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+fn string_column<'a>(
+    batch: &'a RecordBatch,
+    name: &'static str,
+) -> Result<&'a LargeStringArray, DataFusionError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_string_opt::<i64>())
+        .ok_or_else(|| unexpected_column(name))
+}
+
+fn binary_column<'a>(
+    batch: &'a RecordBatch,
+    name: &'static str,
+) -> Result<&'a LargeBinaryArray, DataFusionError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_binary_opt::<i64>())
+        .ok_or_else(|| unexpected_column(name))
+}
+
+fn unexpected_column(name: &str) -> DataFusionError {
+    DataFusionError::Internal(format!("missing or unexpected type of column '{name}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(service_key: &str) -> Instance {
+        Instance {
+            service_name: "counter".to_owned(),
+            service_key: service_key.to_owned(),
+            scope: None,
+        }
+    }
+
+    fn state(kvs: &[(&str, &'static [u8])]) -> HashMap<String, Bytes> {
+        kvs.iter()
+            .map(|(key, value)| ((*key).to_owned(), Bytes::from_static(value)))
+            .collect()
+    }
+
+    #[test]
+    fn applies_changes_to_the_current_state() {
+        let changes = BTreeMap::from([
+            (instance("i1"), BTreeMap::from([("a".to_owned(), None)])),
+            (
+                instance("i2"),
+                BTreeMap::from([("a".to_owned(), Some(Bytes::from_static(b"3")))]),
+            ),
+        ]);
+        let i1_state = state(&[("a", b"1"), ("b", b"2")]);
+        let current_state = HashMap::from([
+            (instance("i1"), i1_state.clone()),
+            (instance("i2"), state(&[("a", b"1")])),
+        ]);
+
+        let plan = build_plan(changes, current_state);
+
+        assert_eq!(plan.mutations.len(), 2);
+        let i1 = &plan.mutations[0];
+        assert_eq!(i1.service_name, "counter");
+        assert_eq!(i1.request.object_key, "i1");
+        assert_eq!(i1.request.new_state, state(&[("b", b"2")]));
+        let kvs: Vec<_> = i1_state
+            .into_iter()
+            .map(|(key, value)| (Bytes::from(key), value))
+            .collect();
+        assert_eq!(
+            i1.request.version.as_deref(),
+            Some(StateMutationVersion::from_user_state(&kvs).as_str())
+        );
+        assert_eq!(i1.changes.len(), 1);
+        assert_eq!(i1.changes[0].old_value.as_deref(), Some(&b"1"[..]));
+        assert_eq!(i1.changes[0].new_value, None);
+
+        let i2 = &plan.mutations[1];
+        assert_eq!(i2.request.new_state, state(&[("a", b"3")]));
+        assert_eq!(i2.changes.len(), 1);
+        assert_eq!(i2.changes[0].old_value.as_deref(), Some(&b"1"[..]));
+        assert_eq!(i2.changes[0].new_value.as_deref(), Some(&b"3"[..]));
+    }
+
+    #[test]
+    fn skips_instances_without_state() {
+        let changes = BTreeMap::from([
+            (instance("i1"), BTreeMap::from([("a".to_owned(), None)])),
+            (
+                instance("i2"),
+                BTreeMap::from([("a".to_owned(), Some(Bytes::from_static(b"3")))]),
+            ),
+        ]);
+        // i2's state was cleared after the statement read it
+        let current_state = HashMap::from([(instance("i1"), state(&[("a", b"1")]))]);
+
+        let plan = build_plan(changes, current_state);
+
+        assert_eq!(plan.mutations.len(), 1);
+        assert_eq!(plan.mutations[0].request.object_key, "i1");
+        assert!(plan.mutations[0].request.new_state.is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Export of the state of Virtual Objects and Workflows.
+//!
+//! The state of all the instances of a service is read from the `state` table and streamed as
+//! newline-delimited JSON, with one [`ServiceStateEntry`] per instance. Only the state of the
+//! instance currently being assembled is kept in memory.
+//!
+//! The import looks up the instances which already have some state with the same table.
+
+use std::collections::{HashMap, HashSet};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use datafusion::arrow::array::{AsArray, LargeStringArray, RecordBatch};
+use datafusion::error::DataFusionError;
+use datafusion::execution::SendableRecordBatchStream;
+use futures::{Stream, StreamExt};
+use itertools::Itertools;
+
+use restate_admin_rest_model::services::ServiceStateEntry;
+use restate_types::state_mut::StateMutationVersion;
+
+/// Returns the query selecting the state of all the instances of `service_name`.
+pub(crate) fn export_query(service_name: &str) -> String {
+    // The rows of an instance must be adjacent, so that each instance is exported as a single
+    // entry. The partitions are scanned concurrently and their rows interleaved, hence this sorts
+    // all the state rows of the service. The sort is bounded by the query engine memory limit and
+    // spills to disk beyond it.
+    format!(
+        "SELECT scope, service_key, key, value FROM state WHERE service_name = '{}' \
+         ORDER BY partition_key, scope, service_key",
+        service_name.replace('\'', "''")
+    )
+}
+
+/// Returns the query selecting the instances of `service_name` among `object_keys` which have
+/// some state.
+pub(crate) fn instances_with_state_query<'a>(
+    service_name: &str,
+    object_keys: impl IntoIterator<Item = &'a str>,
+) -> String {
+    format!(
+        "SELECT DISTINCT scope, service_key FROM state WHERE service_name = '{}' \
+         AND service_key IN ({})",
+        service_name.replace('\'', "''"),
+        object_keys
+            .into_iter()
+            .map(|key| format!("'{}'", key.replace('\'', "''")))
+            .join(", ")
+    )
+}
+
+/// Collects the instances returned by the [`instances_with_state_query`], as
+/// `(scope, object_key)`.
+pub(crate) async fn collect_instances(
+    mut stream: SendableRecordBatchStream,
+) -> Result<HashSet<(Option<String>, String)>, DataFusionError> {
+    let mut instances = HashSet::new();
+    while let Some(batch) = stream.next().await {
+        push_instances(&batch?, &mut instances)?;
+    }
+    Ok(instances)
+}
+
+fn push_instances(
+    batch: &RecordBatch,
+    instances: &mut HashSet<(Option<String>, String)>,
+) -> Result<(), DataFusionError> {
+    let scopes = string_column(batch, "scope")?;
+    let object_keys = string_column(batch, "service_key")?;
+    for row in 0..batch.num_rows() {
+        instances.insert((
+            scopes.is_valid(row).then(|| scopes.value(row).to_owned()),
+            object_keys.value(row).to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Converts the result of the [`export_query`] into newline-delimited JSON.
+pub(crate) fn export_stream(
+    stream: SendableRecordBatchStream,
+) -> impl Stream<Item = Result<Bytes, DataFusionError>> + Send {
+    futures::stream::unfold(
+        Some((stream, StateExporter::default())),
+        |state| async move {
+            let (mut stream, mut exporter) = state?;
+            match stream.next().await {
+                Some(Ok(batch)) => {
+                    let lines = exporter.push(&batch);
+                    Some((lines, Some((stream, exporter))))
+                }
+                Some(Err(err)) => Some((Err(err), None)),
+                None => Some((exporter.finish(), None)),
+            }
+        },
+    )
+}
+
+struct Instance {
+    scope: Option<String>,
+    object_key: String,
+    state: HashMap<String, Bytes>,
+}
+
+#[derive(Default)]
+struct StateExporter {
+    current: Option<Instance>,
+}
+
+impl StateExporter {
+    /// Adds the rows of the batch, returning the entries of the instances which are complete.
+    fn push(&mut self, batch: &RecordBatch) -> Result<Bytes, DataFusionError> {
+        let scopes = string_column(batch, "scope")?;
+        let object_keys = string_column(batch, "service_key")?;
+        let keys = string_column(batch, "key")?;
+        let values = batch
+            .column_by_name("value")
+            .and_then(|column| column.as_binary_opt::<i64>())
+            .ok_or_else(|| unexpected_column("value"))?;
+
+        let mut lines = BytesMut::new();
+        for row in 0..batch.num_rows() {
+            let scope = scopes.is_valid(row).then(|| scopes.value(row));
+            let object_key = object_keys.value(row);
+
+            let is_same_instance = self.current.as_ref().is_some_and(|current| {
+                current.scope.as_deref() == scope && current.object_key == object_key
+            });
+            if !is_same_instance {
+                if let Some(previous) = self.current.take() {
+                    write_entry(&mut lines, previous)?;
+                }
+                self.current = Some(Instance {
+                    scope: scope.map(str::to_owned),
+                    object_key: object_key.to_owned(),
+                    state: HashMap::new(),
+                });
+            }
+
+            self.current
+                .as_mut()
+                .expect("current instance is set")
+                .state
+                .insert(
+                    keys.value(row).to_owned(),
+                    Bytes::copy_from_slice(values.value(row)),
+                );
+        }
+        Ok(lines.freeze())
+    }
+
+    /// Returns the entry of the last instance.
+    fn finish(&mut self) -> Result<Bytes, DataFusionError> {
+        let mut lines = BytesMut::new();
+        if let Some(last) = self.current.take() {
+            write_entry(&mut lines, last)?;
+        }
+        Ok(lines.freeze())
+    }
+}
+
+fn write_entry(lines: &mut BytesMut, instance: Instance) -> Result<(), DataFusionError> {
+    let kvs: Vec<(Bytes, Bytes)> = instance
+        .state
+        .iter()
+        .map(|(key, value)| (Bytes::copy_from_slice(key.as_bytes()), value.clone()))
+        .collect();
+    let entry = ServiceStateEntry {
+        object_key: instance.object_key,
+        scope: instance.scope,
+        version: StateMutationVersion::from_user_state(&kvs).into_inner(),
+        state: instance.state,
+    };
+
+    let mut writer = lines.writer();
+    serde_json::to_writer(&mut writer, &entry)
+        .map_err(|err| DataFusionError::External(err.into()))?;
+    writer.into_inner().put_u8(b'\n');
+    Ok(())
+}
+
+fn string_column<'a>(
+    batch: &'a RecordBatch,
+    name: &'static str,
+) -> Result<&'a LargeStringArray, DataFusionError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_string_opt::<i64>())
+        .ok_or_else(|| unexpected_column(name))
+}
+
+fn unexpected_column(name: &str) -> DataFusionError {
+    DataFusionError::Internal(format!("missing or unexpected type of column '{name}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{LargeBinaryArray, LargeStringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+
+    use super::*;
+
+    fn batch(rows: Vec<(Option<&str>, &str, &str, &[u8])>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("scope", DataType::LargeUtf8, true),
+            Field::new("service_key", DataType::LargeUtf8, false),
+            Field::new("key", DataType::LargeUtf8, false),
+            Field::new("value", DataType::LargeBinary, false),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(LargeStringArray::from(
+                    rows.iter().map(|row| row.0).collect::<Vec<_>>(),
+                )),
+                Arc::new(LargeStringArray::from(
+                    rows.iter().map(|row| row.1).collect::<Vec<_>>(),
+                )),
+                Arc::new(LargeStringArray::from(
+                    rows.iter().map(|row| row.2).collect::<Vec<_>>(),
+                )),
+                Arc::new(LargeBinaryArray::from(
+                    rows.iter().map(|row| row.3).collect::<Vec<_>>(),
+                )),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn parse(lines: &[Bytes]) -> Vec<ServiceStateEntry> {
+        lines
+            .iter()
+            .flat_map(|chunk| chunk.split(|b| *b == b'\n'))
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn groups_adjacent_rows_of_an_instance_across_batches() {
+        let mut exporter = StateExporter::default();
+        let first = exporter
+            .push(&batch(vec![
+                (None, "a", "k1", b"1"),
+                (None, "a", "k2", b"2"),
+                (None, "b", "k1", b"3"),
+            ]))
+            .unwrap();
+        let second = exporter
+            .push(&batch(vec![
+                (None, "b", "k2", b"4"),
+                (Some("tenant"), "b", "k1", b"5"),
+            ]))
+            .unwrap();
+        let last = exporter.finish().unwrap();
+
+        let entries = parse(&[first, second, last]);
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].object_key, "a");
+        assert_eq!(entries[0].scope, None);
+        assert_eq!(entries[0].state.len(), 2);
+
+        assert_eq!(entries[1].object_key, "b");
+        assert_eq!(entries[1].scope, None);
+        assert_eq!(entries[1].state["k2"], Bytes::from_static(b"4"));
+
+        assert_eq!(entries[2].object_key, "b");
+        assert_eq!(entries[2].scope.as_deref(), Some("tenant"));
+
+        let kvs: Vec<_> = entries[0]
+            .state
+            .iter()
+            .map(|(key, value)| (Bytes::from(key.clone()), value.clone()))
+            .collect();
+        assert_eq!(
+            entries[0].version,
+            StateMutationVersion::from_user_state(&kvs).into_inner()
+        );
+    }
+
+    #[test]
+    fn escapes_service_name_in_query() {
+        assert!(export_query("it's").contains("service_name = 'it''s'"));
+    }
+
+    #[test]
+    fn escapes_object_keys_in_instances_query() {
+        let query = instances_with_state_query("it's", ["a", "b'c"]);
+        assert!(query.contains("service_name = 'it''s'"));
+        assert!(query.contains("service_key IN ('a', 'b''c')"));
+    }
+
+    #[test]
+    fn collects_scoped_and_unscoped_instances() {
+        let mut instances = HashSet::new();
+        push_instances(
+            &batch(vec![
+                (None, "a", "k1", b"1"),
+                (Some("tenant"), "a", "k1", b"2"),
+            ]),
+            &mut instances,
+        )
+        .unwrap();
+
+        assert_eq!(
+            instances,
+            HashSet::from([
+                (None, "a".to_owned()),
+                (Some("tenant".to_owned()), "a".to_owned())
+            ])
+        );
+    }
+}
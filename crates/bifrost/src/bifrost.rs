@@ -31,6 +31,7 @@ use restate_types::logs::metadata::SealMetadata;
 use restate_types::logs::metadata::{LogletParams, Logs, SegmentIndex};
 use restate_types::logs::metadata::{MaybeSegment, ProviderKind, Segment};
 use restate_types::logs::{KeyFilter, LogId, Lsn, SequenceNumber, TailState};
+use restate_types::replication::NodeSet;
 use restate_types::storage::StorageEncode;
 
 use crate::appender::Appender;
@@ -572,6 +573,22 @@ impl BifrostInner {
         response_rx.await.map_err(|_| ShutdownError)?
     }
 
+    /// Replaces `remove` with `add` in the nodeset of the sealed replicated segment
+    /// `segment_index` of the given log. Returns the resulting nodeset.
+    pub async fn replace_in_sealed_nodeset(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        remove: NodeSet,
+        add: NodeSet,
+    ) -> std::result::Result<NodeSet, Error> {
+        let (response_rx, cmd) =
+            LogChainCommand::replace_in_sealed_nodeset(log_id, segment_index, remove, add);
+        let _ = self.watchdog.send(WatchdogCommand::ChainCommand(cmd));
+
+        response_rx.await.map_err(|_| ShutdownError)?
+    }
+
     // --- Helper functions --- //
     /// Get the provider for a given kind. A provider must be enabled and BifrostService **must**
     /// be started before calling this.
@@ -17,6 +17,7 @@ use restate_types::logs::metadata::{
     InternalKind, LogletParams, ProviderKind, SealMetadata, SegmentIndex,
 };
 use restate_types::logs::{LogId, Lsn, TailState};
+use restate_types::replication::NodeSet;
 
 use crate::bifrost::BifrostInner;
 use crate::error::AdminError;
@@ -175,6 +176,26 @@ impl<'a> BifrostAdmin<'a> {
         Ok(sealed_segment)
     }
 
+    /// Replaces `remove` with `add` in the nodeset of a sealed replicated segment.
+    ///
+    /// The caller is responsible for making sure that the records of the segment that haven't
+    /// been trimmed yet are sufficiently replicated on the resulting nodeset. Fails if the
+    /// resulting nodeset cannot satisfy the replication property of the segment. Returns the
+    /// resulting nodeset.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn replace_in_sealed_nodeset(
+        &self,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        remove: NodeSet,
+        add: NodeSet,
+    ) -> Result<NodeSet> {
+        self.inner.fail_if_shutting_down()?;
+        self.inner
+            .replace_in_sealed_nodeset(log_id, segment_index, remove, add)
+            .await
+    }
+
     pub async fn writeable_loglet(&self, log_id: LogId) -> Result<LogletWrapper> {
         self.inner.tail_loglet(log_id).await
     }
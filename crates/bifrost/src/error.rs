@@ -18,6 +18,7 @@ use restate_types::errors::MaybeRetryableError;
 use restate_types::logs::builder::BuilderError;
 use restate_types::logs::metadata::SegmentIndex;
 use restate_types::logs::{LogId, Lsn};
+use restate_types::replication::{NodeSet, ReplicationProperty};
 
 use crate::loglet::OperationError;
 
@@ -98,6 +99,12 @@ pub enum AdminError {
     ParamsSerde(#[from] Arc<serde_json::Error>),
     #[error("logs HLC clock error: {0}")]
     LogsHlcClock(clock::Error),
+    #[error("log {0} has no replicated segment with index {1}")]
+    UnknownSegment(LogId, SegmentIndex),
+    #[error("segment {1} of log {0} is not sealed")]
+    SegmentNotSealed(LogId, SegmentIndex),
+    #[error("nodeset {2} of segment {1} of log {0} cannot satisfy replication {3}")]
+    InsufficientNodeset(LogId, SegmentIndex, NodeSet, ReplicationProperty),
 }
 
 impl From<OperationError> for Error {
@@ -119,6 +126,15 @@ impl From<BuilderError> for AdminError {
             BuilderError::ParamsSerde(error) => AdminError::ParamsSerde(Arc::new(error)),
             BuilderError::SegmentConflict(lsn) => AdminError::SegmentConflict(lsn),
             BuilderError::HlcClock(err) => AdminError::LogsHlcClock(err),
+            BuilderError::UnknownSegment(log_id, segment_index) => {
+                AdminError::UnknownSegment(log_id, segment_index)
+            }
+            BuilderError::SegmentNotSealed(log_id, segment_index) => {
+                AdminError::SegmentNotSealed(log_id, segment_index)
+            }
+            BuilderError::InsufficientNodeset(log_id, segment_index, nodeset, replication) => {
+                AdminError::InsufficientNodeset(log_id, segment_index, nodeset, replication)
+            }
         }
     }
 }
@@ -13,13 +13,16 @@ use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, trace};
 
-use restate_core::{MetadataWriter, TaskCenter, TaskHandle, TaskKind, cancellation_token};
+use restate_core::{
+    Metadata, MetadataWriter, TaskCenter, TaskHandle, TaskKind, cancellation_token,
+};
 use restate_metadata_store::ReadModifyWriteError;
 use restate_types::logs::builder::{BuilderError, LogsBuilder};
 use restate_types::logs::metadata::{
     Chain, LogletParams, Logs, ProviderKind, SealMetadata, SegmentIndex,
 };
 use restate_types::logs::{LogId, Lsn, SequenceNumber};
+use restate_types::replication::NodeSet;
 
 use crate::Error;
 use crate::error::AdminError;
@@ -123,6 +126,28 @@ impl LogChainCommand {
         (rx, cmd)
     }
 
+    pub fn replace_in_sealed_nodeset(
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        remove: NodeSet,
+        add: NodeSet,
+    ) -> (oneshot::Receiver<Result<NodeSet, Error>>, Self) {
+        let (tx, rx) = oneshot::channel();
+        let cmd = Self {
+            log_id,
+            op: ChainOp::ReplaceInSealedNodeset {
+                segment_index,
+                remove,
+                add,
+                response: OpOutput {
+                    tx,
+                    staged_result: None,
+                },
+            },
+        };
+        (rx, cmd)
+    }
+
     pub fn trim_prefix(log_id: LogId, trim_point: Lsn) -> Self {
         Self {
             log_id,
@@ -135,6 +160,7 @@ impl LogChainCommand {
             ChainOp::Extend { response, .. } => response.fail(err),
             ChainOp::SealChain { response, .. } => response.fail(err),
             ChainOp::AddLog { response, .. } => response.fail(err),
+            ChainOp::ReplaceInSealedNodeset { response, .. } => response.fail(err),
             ChainOp::TrimPrefix { .. } => { /* do nothing */ }
         }
     }
@@ -144,6 +170,7 @@ impl LogChainCommand {
             ChainOp::Extend { response, .. } => response.complete(),
             ChainOp::SealChain { response, .. } => response.complete(),
             ChainOp::AddLog { response, .. } => response.complete(),
+            ChainOp::ReplaceInSealedNodeset { response, .. } => response.complete(),
             ChainOp::TrimPrefix { trim_point } => {
                 debug!(
                     "Log {} chain has been trimmed to trim-point {}",
@@ -179,6 +206,13 @@ enum ChainOp {
         #[debug(skip)]
         response: OpOutput<()>,
     },
+    ReplaceInSealedNodeset {
+        segment_index: SegmentIndex,
+        remove: NodeSet,
+        add: NodeSet,
+        #[debug(skip)]
+        response: OpOutput<NodeSet>,
+    },
     TrimPrefix {
         trim_point: Lsn,
     },
@@ -284,6 +318,20 @@ impl LogChainWriter {
                                     metadata,
                                 ));
                             }
+                            ChainOp::ReplaceInSealedNodeset {
+                                segment_index,
+                                ref remove,
+                                ref add,
+                                ref mut response,
+                            } => {
+                                response.stage_output(Self::replace_in_sealed_nodeset(
+                                    &mut builder,
+                                    cmd.log_id,
+                                    segment_index,
+                                    remove,
+                                    add,
+                                ));
+                            }
                             ChainOp::TrimPrefix { trim_point } => {
                                 // ignores the error if the log is unknown.
                                 let _ = Self::trim_prefix(&mut builder, cmd.log_id, trim_point);
@@ -376,6 +424,23 @@ impl LogChainWriter {
         Ok(lsn)
     }
 
+    fn replace_in_sealed_nodeset(
+        builder: &mut LogsBuilder,
+        log_id: LogId,
+        segment_index: SegmentIndex,
+        remove: &NodeSet,
+        add: &NodeSet,
+    ) -> Result<NodeSet, Error> {
+        let mut chain_builder = builder.chain(log_id).ok_or(Error::UnknownLogId(log_id))?;
+
+        let nodes_config = Metadata::with_current(|m| m.nodes_config_snapshot());
+        let nodeset = chain_builder
+            .replace_in_sealed_nodeset(segment_index, remove, add, &nodes_config)
+            .map_err(AdminError::from)?;
+
+        Ok(nodeset)
+    }
+
     fn trim_prefix(builder: &mut LogsBuilder, log_id: LogId, trim_point: Lsn) -> Result<(), Error> {
         let mut chain_builder = builder.chain(log_id).ok_or(Error::UnknownLogId(log_id))?;
 
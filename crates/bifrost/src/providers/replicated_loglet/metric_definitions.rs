@@ -36,6 +36,11 @@ pub(crate) const BIFROST_SEQ_APPEND_WAVE_DURATION: &str =
     "restate.bifrost.sequencer.append_wave_duration.seconds";
 pub(crate) const BIFROST_SEQ_RECORD_SIZE: &str = "restate.bifrost.sequencer.record_size_bytes";
 
+pub(crate) const BIFROST_SCRUBBER_CHECKED_RECORDS: &str =
+    "restate.bifrost.scrubber.checked_records.total";
+pub(crate) const BIFROST_SCRUBBER_FINDINGS: &str = "restate.bifrost.scrubber.findings.total";
+pub(crate) const BIFROST_SCRUBBER_REPAIRS: &str = "restate.bifrost.scrubber.repairs.total";
+
 pub(crate) fn describe_metrics() {
     describe_counter!(
         BIFROST_REPLICATED_READ_CACHE_HIT,
@@ -108,4 +113,22 @@ pub(crate) fn describe_metrics() {
         Unit::Bytes,
         "Distribution of record sizes sent by the sequencer"
     );
+
+    describe_counter!(
+        BIFROST_SCRUBBER_CHECKED_RECORDS,
+        Unit::Count,
+        "Number of records whose copies were compared by the scrubber"
+    );
+
+    describe_counter!(
+        BIFROST_SCRUBBER_FINDINGS,
+        Unit::Count,
+        "Number of corrupted or under-replicated records found by the scrubber, by kind"
+    );
+
+    describe_counter!(
+        BIFROST_SCRUBBER_REPAIRS,
+        Unit::Count,
+        "Number of record repairs attempted by the scrubber, by outcome"
+    );
 }
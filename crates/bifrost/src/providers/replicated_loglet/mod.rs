@@ -17,6 +17,7 @@ mod provider;
 mod read_path;
 mod remote_sequencer;
 pub mod replication;
+pub mod scrubber;
 pub mod sequencer;
 mod tasks;
 #[cfg(any(test, feature = "test-util"))]
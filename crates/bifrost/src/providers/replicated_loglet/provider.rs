@@ -35,6 +35,7 @@ use restate_types::replication::{
 use super::loglet::ReplicatedLoglet;
 use super::metric_definitions;
 use super::network::{SequencerDataRpcHandler, SequencerInfoRpcHandler};
+use super::scrubber::{ScrubFindings, Scrubber};
 use crate::Error;
 use crate::loglet::{Improvement, Loglet, LogletProvider, LogletProviderFactory, OperationError};
 use crate::providers::replicated_loglet::error::ReplicatedLogletError;
@@ -46,6 +47,7 @@ pub struct Factory<T> {
     data_request_pump: Buffered<SequencerDataService>,
     info_request_pump: Buffered<SequencerMetaService>,
     record_cache: RecordCache,
+    scrub_findings: ScrubFindings,
 }
 
 impl<T: TransportConnect> Factory<T> {
@@ -77,8 +79,15 @@ impl<T: TransportConnect> Factory<T> {
             data_request_pump,
             info_request_pump,
             record_cache,
+            scrub_findings: ScrubFindings::default(),
         }
     }
+
+    /// Findings of the background scrubber on this node. The handle stays valid after the
+    /// factory is consumed.
+    pub fn scrub_findings(&self) -> ScrubFindings {
+        self.scrub_findings.clone()
+    }
 }
 
 #[async_trait]
@@ -89,6 +98,11 @@ impl<T: TransportConnect> LogletProviderFactory for Factory<T> {
 
     async fn create(self: Box<Self>) -> Result<Arc<dyn LogletProvider>, OperationError> {
         metric_definitions::describe_metrics();
+        TaskCenter::spawn(
+            TaskKind::BifrostBackgroundLowPriority,
+            "loglet-scrubber",
+            Scrubber::new(self.networking.clone(), self.scrub_findings).run(),
+        )?;
         let provider = Arc::new(ReplicatedLogletProvider::new(
             self.networking,
             self.record_cache,
@@ -297,6 +311,18 @@ impl<T: TransportConnect> LogletProvider for ReplicatedLogletProvider<T> {
             return Ok(Improvement::None);
         }
 
+        // nodes that are being decommissioned must be moved out of the nodeset, even if that
+        // means running with a smaller nodeset.
+        if let Some(draining) = current_params.nodeset.iter().find(|node_id| {
+            nodes_config.find_node_by_id(**node_id).is_ok_and(|config| {
+                config.log_server_config.storage_state == StorageState::Draining
+            })
+        }) {
+            return Ok(Improvement::Possible {
+                reason: format!("log-server {draining} is draining"),
+            });
+        }
+
         if new_nodeset.len() < current_params.nodeset.len() {
             // a bigger nodeset is a better nodeset, we reject a smaller offer
             return Ok(Improvement::None);
@@ -0,0 +1,1520 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Background anti-entropy scrubber for replicated loglets.
+//!
+//! Every node periodically walks the loglets it is responsible for scrubbing (open and sealed
+//! segments alike) and compares the record digests reported by the members of the loglet's
+//! nodeset. Log-servers
+//! verify the checksum of each record while building the digest, which allows the scrubber to
+//! detect corrupted copies as well as records that lost their replication property. Damaged
+//! records are re-replicated from a healthy copy.
+//!
+//! A loglet is scrubbed by its sequencer node while that node is alive. Otherwise, which is the
+//! common case for sealed loglets whose sequencer is gone, one of the alive nodes of the cluster
+//! is picked deterministically from the loglet id (see [`scrubbing_node`]). Nodes can briefly
+//! disagree on liveness, in which case a loglet may be scrubbed twice, which is harmless.
+//!
+//! Scrubbing is throttled, so a pass over a long loglet can take a while. The progress within each
+//! loglet is persisted in a node-local cursor file, a pass resumes from where the previous one
+//! stopped and a loglet is only scrubbed from its beginning again once it was fully scrubbed.
+//!
+//! Findings are exported as metrics and kept in a bounded in-memory registry
+//! ([`ScrubFindings`]) for introspection.
+//!
+//! The same machinery is used to evacuate log-servers that are being decommissioned (see
+//! [`Scrubber::evacuate_loglet`]): copies held by the evacuated nodes are still used as sources
+//! but don't count towards the replication property anymore, which makes every record that
+//! relies on them under-replicated and gets it copied to the remaining nodes and to the
+//! replacement log-servers picked from the nodes configuration.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use metrics::counter;
+use parking_lot::Mutex;
+use rand::rng;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::{debug, info, trace, warn};
+
+use restate_core::network::{NetworkSender, Networking, Swimlane, TransportConnect};
+use restate_core::{Metadata, TaskCenter, TaskCenterFutureExt, cancellation_watcher};
+use restate_types::config::{Configuration, node_filepath};
+use restate_types::logs::{KeyFilter, LogletId, LogletOffset, Lsn, Record, SequenceNumber};
+use restate_types::net::log_server::{
+    Digest, GetDigest, GetRecords, LogServerRequestHeader, MaybeRecord, RecordStatus, Seal, Status,
+    Store, StoreFlags,
+};
+use restate_types::nodes_config::{NodesConfiguration, Role};
+use restate_types::replicated_loglet::{LogNodeSetExt, ReplicatedLogletParams};
+use restate_types::replication::{NodeSet, NodeSetChecker, ReplicationProperty};
+use restate_types::time::MillisSinceEpoch;
+use restate_types::{NodeId, PlainNodeId};
+use restate_util_time::DurationExt;
+
+use super::metric_definitions::{
+    BIFROST_SCRUBBER_CHECKED_RECORDS, BIFROST_SCRUBBER_FINDINGS, BIFROST_SCRUBBER_REPAIRS,
+};
+use super::replication::spread_selector::{SelectorStrategy, SpreadSelector};
+use crate::types::LsnExt;
+
+/// Number of offsets requested per digest round
+const SCRUB_CHUNK_SIZE: u32 = 1000;
+/// Number of findings kept in [`ScrubFindings`]
+const MAX_RETAINED_FINDINGS: usize = 1000;
+/// Name of the file in the node directory holding the scrub cursors
+const SCRUB_CURSORS_FILE_NAME: &str = "scrub-cursors.json";
+const TMP_SCRUB_CURSORS_FILE_NAME: &str = "scrub-cursors.json.tmp";
+/// Number of scrubbed chunks after which the scrub cursors are persisted
+const PERSIST_CURSORS_EVERY_CHUNKS: u32 = 100;
+/// Time after which changed scrub cursors are persisted. A restarted node rescans at most the
+/// chunks scrubbed since the cursors were last persisted.
+const PERSIST_CURSORS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The kind of damage the scrubber found on a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum FindingKind {
+    /// A copy of the record failed checksum verification on a log-server.
+    #[display("corrupted")]
+    Corrupted,
+    /// The record has fewer healthy copies than its replication property requires.
+    #[display("under-replicated")]
+    UnderReplicated,
+}
+
+/// What happened when the scrubber attempted to repair a damaged record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum RepairOutcome {
+    /// The record was restored from a healthy copy.
+    #[display("repaired")]
+    Repaired,
+    /// The repair was attempted but did not complete. It will be retried in the next pass.
+    #[display("failed")]
+    Failed,
+    /// No healthy copy of the record was found on any log-server.
+    #[display("unrepairable")]
+    Unrepairable,
+}
+
+/// A single record-level finding of the scrubber.
+#[derive(Debug, Clone)]
+pub struct ScrubFinding {
+    pub loglet_id: LogletId,
+    pub offset: LogletOffset,
+    pub kind: FindingKind,
+    /// The log-server holding the corrupted copy. Only set for [`FindingKind::Corrupted`].
+    pub node_id: Option<PlainNodeId>,
+    /// Number of log-servers that reported a healthy copy of the record.
+    pub healthy_copies: u32,
+    pub outcome: RepairOutcome,
+    pub detected_at: MillisSinceEpoch,
+}
+
+/// Bounded registry of the most recent scrubber findings on this node.
+///
+/// It is cheap to clone. Once full, the oldest findings are evicted first.
+#[derive(Clone, Debug, Default)]
+pub struct ScrubFindings {
+    inner: Arc<Mutex<VecDeque<ScrubFinding>>>,
+}
+
+impl ScrubFindings {
+    fn record(&self, finding: ScrubFinding) {
+        let mut guard = self.inner.lock();
+        if guard.len() >= MAX_RETAINED_FINDINGS {
+            guard.pop_front();
+        }
+        guard.push_back(finding);
+    }
+
+    /// Returns a snapshot of the retained findings, oldest first.
+    pub fn snapshot(&self) -> Vec<ScrubFinding> {
+        self.inner.lock().iter().cloned().collect()
+    }
+}
+
+/// Per-loglet scrub progress of this node, persisted in the node directory.
+///
+/// A cursor points at the next offset to scrub. Loglets without a cursor are scrubbed from
+/// their beginning.
+///
+/// To keep the number of file syncs low, the cursors are not persisted after every chunk but
+/// every [`PERSIST_CURSORS_EVERY_CHUNKS`] chunks or [`PERSIST_CURSORS_INTERVAL`], as well as at
+/// the end of a pass and on shutdown.
+#[derive(Debug)]
+struct ScrubCursors {
+    path: PathBuf,
+    cursors: BTreeMap<LogletId, LogletOffset>,
+    /// Number of changes since the cursors were last persisted
+    unpersisted_changes: u32,
+    last_persisted_at: Instant,
+}
+
+impl ScrubCursors {
+    fn load(path: PathBuf) -> Self {
+        let cursors = match std::fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|err| {
+                warn!(
+                    %err,
+                    "Could not decode the scrub cursors in {}, scrubbing all loglets from the start",
+                    path.display()
+                );
+                BTreeMap::default()
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::default(),
+            Err(err) => {
+                warn!(
+                    %err,
+                    "Could not read the scrub cursors from {}, scrubbing all loglets from the start",
+                    path.display()
+                );
+                BTreeMap::default()
+            }
+        };
+        Self {
+            path,
+            cursors,
+            unpersisted_changes: 0,
+            last_persisted_at: Instant::now(),
+        }
+    }
+
+    fn get(&self, loglet_id: LogletId) -> LogletOffset {
+        self.cursors
+            .get(&loglet_id)
+            .copied()
+            .unwrap_or(LogletOffset::OLDEST)
+    }
+
+    /// Forgets the cursors of loglets that don't exist anymore.
+    fn retain(&mut self, known_loglets: &HashSet<LogletId>) {
+        let len = self.cursors.len();
+        self.cursors
+            .retain(|loglet_id, _| known_loglets.contains(loglet_id));
+        if self.cursors.len() != len {
+            self.unpersisted_changes += 1;
+        }
+    }
+
+    /// Moves the cursor of the loglet to `next_offset`. `None` means the loglet was fully
+    /// scrubbed and the next pass starts from its beginning again.
+    ///
+    /// The cursors are persisted if enough chunks were scrubbed or enough time passed since they
+    /// were last persisted.
+    async fn advance(&mut self, loglet_id: LogletId, next_offset: Option<LogletOffset>) {
+        match next_offset {
+            Some(next_offset) => self.cursors.insert(loglet_id, next_offset),
+            None => self.cursors.remove(&loglet_id),
+        };
+        self.unpersisted_changes += 1;
+
+        if self.unpersisted_changes >= PERSIST_CURSORS_EVERY_CHUNKS
+            || self.last_persisted_at.elapsed() >= PERSIST_CURSORS_INTERVAL
+        {
+            self.persist().await;
+        }
+    }
+
+    /// Persists the cursors if they changed since they were last persisted.
+    async fn persist(&mut self) {
+        if self.unpersisted_changes == 0 {
+            return;
+        }
+        // retried with the next change if persisting fails
+        self.unpersisted_changes = 0;
+        self.last_persisted_at = Instant::now();
+
+        let path = self.path.clone();
+        let cursors = self.cursors.clone();
+        let result = tokio::task::spawn_blocking(move || write_scrub_cursors(&path, &cursors))
+            .await
+            .unwrap_or_else(|err| Err(std::io::Error::other(err)));
+        if let Err(err) = result {
+            warn!(%err, "Could not persist the scrub cursors to {}", self.path.display());
+        }
+    }
+}
+
+fn write_scrub_cursors(
+    path: &Path,
+    cursors: &BTreeMap<LogletId, LogletOffset>,
+) -> std::io::Result<()> {
+    let parent = path
+        .parent()
+        .expect("filepath should have parent directory");
+    let tmp_path = parent.join(TMP_SCRUB_CURSORS_FILE_NAME);
+    std::fs::create_dir_all(parent)?;
+
+    // write to a new file and then rename it
+    {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&tmp_path)?;
+        serde_json::to_writer(&file, cursors)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp_path, path)?;
+    // make sure the rename operation is persisted to disk
+    std::fs::File::open(parent)?.sync_all()
+}
+
+/// Picks the node that is responsible for scrubbing the loglet among the `alive` nodes.
+///
+/// The sequencer scrubs its loglet while it's alive. Otherwise, the responsibility is spread over
+/// the alive nodes by loglet id. `alive` must be sorted for all nodes to agree on the choice.
+fn scrubbing_node(params: &ReplicatedLogletParams, alive: &[PlainNodeId]) -> Option<PlainNodeId> {
+    let sequencer = params.sequencer.as_plain();
+    if alive.contains(&sequencer) {
+        return Some(sequencer);
+    }
+    if alive.is_empty() {
+        return None;
+    }
+    let index = *params.loglet_id % alive.len() as u64;
+    Some(alive[usize::try_from(index).expect("index fits into usize")])
+}
+
+/// Where the copies of a single offset live.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct OffsetCopies {
+    healthy: NodeSet,
+    corrupted: NodeSet,
+}
+
+/// The result of comparing the digests of a range of offsets.
+#[derive(Debug, Default)]
+struct Assessment {
+    /// Offsets with at least one corrupted copy, or that are not sufficiently replicated.
+    damaged: BTreeMap<LogletOffset, OffsetCopies>,
+    /// Number of offsets that were checked
+    checked: u32,
+    /// The highest global tail reported by the log-servers
+    global_tail: LogletOffset,
+    /// Where to continue from. `None` if the scrubber caught up with the global tail.
+    next_offset: Option<LogletOffset>,
+    /// Whether all nodes of the effective nodeset responded
+    all_responded: bool,
+}
+
+/// Progress of evacuating the records of a loglet off a set of log-servers.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EvacuationSummary {
+    /// The log-servers that take over the copies of the evacuated nodes. They are not part of
+    /// the loglet's nodeset yet.
+    pub replacements: NodeSet,
+    /// Number of offsets that were checked
+    pub checked_records: u64,
+    /// Number of records that were copied to the remaining nodes of the nodeset and to the
+    /// replacements
+    pub copied_records: u64,
+    /// Number of records that could not be copied
+    pub failed_records: u64,
+    /// Number of offset ranges that couldn't be checked because some log-servers didn't respond
+    pub unchecked_ranges: u64,
+    /// Number of replacements that couldn't be sealed
+    pub unsealed_replacements: u64,
+}
+
+impl EvacuationSummary {
+    /// Whether all records are replicated without relying on the evacuated nodes.
+    pub fn is_complete(&self) -> bool {
+        self.failed_records == 0 && self.unchecked_ranges == 0 && self.unsealed_replacements == 0
+    }
+}
+
+/// Why the records of a loglet cannot be evacuated off a set of log-servers.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum EvacuationError {
+    #[error(
+        "not enough writeable log-servers to replace {evacuating} in nodeset {nodeset} with \
+        replication {replication}"
+    )]
+    InsufficientLogServers {
+        nodeset: NodeSet,
+        evacuating: NodeSet,
+        replication: ReplicationProperty,
+    },
+}
+
+/// Compares the digests of `[from_offset..=to_offset]` received from the members of the
+/// loglet's effective nodeset.
+///
+/// Only committed records (below the highest known global tail, or `known_tail` if higher) are
+/// checked and trimmed prefixes are skipped. A record is considered under-replicated only if every
+/// node of the effective nodeset responded, otherwise a missing copy might just be on a node we
+/// didn't hear from. Copies on `evacuating` nodes don't count towards the replication property.
+fn assess(
+    params: &ReplicatedLogletParams,
+    evacuating: &NodeSet,
+    nodes_config: &NodesConfiguration,
+    from_offset: LogletOffset,
+    to_offset: LogletOffset,
+    known_tail: LogletOffset,
+    digests: &[(PlainNodeId, Digest)],
+) -> Assessment {
+    let digests: Vec<_> = digests
+        .iter()
+        .filter(|(_, digest)| digest.header.status == Status::Ok)
+        .collect();
+
+    let global_tail = digests
+        .iter()
+        .map(|(_, digest)| digest.header.known_global_tail)
+        .max()
+        .unwrap_or(LogletOffset::INVALID)
+        .max(known_tail);
+
+    // trimming is a loglet-wide decision, a single node reporting a trim gap is enough to skip
+    // over it.
+    let start = digests
+        .iter()
+        .flat_map(|(_, digest)| digest.entries.iter())
+        .filter(|entry| entry.status == RecordStatus::Trimmed)
+        .map(|entry| entry.to_offset.next())
+        .fold(from_offset, LogletOffset::max);
+
+    // exclusive
+    let end = to_offset.next().min(global_tail).max(start);
+
+    let mut copies: BTreeMap<LogletOffset, OffsetCopies> = (*start..*end)
+        .map(|offset| (LogletOffset::new(offset), OffsetCopies::default()))
+        .collect();
+    for (node_id, digest) in &digests {
+        for entry in digest.entries.iter().filter(|entry| !entry.is_empty()) {
+            for (_, offset_copies) in copies.range_mut(entry.from_offset..=entry.to_offset) {
+                match entry.status {
+                    RecordStatus::Exists => {
+                        offset_copies.healthy.insert(*node_id);
+                    }
+                    RecordStatus::Corrupted => {
+                        offset_copies.corrupted.insert(*node_id);
+                    }
+                    RecordStatus::Unknown | RecordStatus::Trimmed | RecordStatus::Archived => {}
+                }
+            }
+        }
+    }
+
+    let all_responded = params
+        .nodeset
+        .to_effective(nodes_config)
+        .iter()
+        .all(|node_id| digests.iter().any(|(peer, _)| peer == node_id));
+
+    let remaining_nodeset = remaining_nodeset(params, evacuating);
+    let mut checker = NodeSetChecker::new(&remaining_nodeset, nodes_config, &params.replication);
+    copies.retain(|_, offset_copies| {
+        if !offset_copies.corrupted.is_empty() {
+            return true;
+        }
+        if !all_responded {
+            return false;
+        }
+        checker.fill_with_default();
+        checker.set_attribute_on_each(
+            offset_copies
+                .healthy
+                .iter()
+                .copied()
+                .filter(|node_id| !evacuating.contains(*node_id)),
+            true,
+        );
+        !checker.check_write_quorum(|healthy| *healthy)
+    });
+
+    Assessment {
+        damaged: copies,
+        checked: end.saturating_sub(*start),
+        global_tail,
+        next_offset: (end < global_tail).then_some(end),
+        all_responded,
+    }
+}
+
+/// The nodeset of the loglet without the nodes that are being evacuated.
+fn remaining_nodeset(params: &ReplicatedLogletParams, evacuating: &NodeSet) -> NodeSet {
+    let mut nodeset = params.nodeset.clone();
+    nodeset.retain(|node_id| !evacuating.contains(*node_id));
+    nodeset
+}
+
+/// Picks the log-servers that take over the copies of the `evacuating` nodes of the loglet.
+///
+/// One writeable log-server outside of the nodeset is picked for each evacuated node, as long as
+/// there are enough of them. Like [`scrubbing_node`], the choice is spread over the candidates by
+/// loglet id and is stable as long as the nodes configuration doesn't change, so that retries
+/// copy records to the same nodes. Fails if the remaining nodeset together with the replacements
+/// can't satisfy the replication property of the loglet.
+fn select_replacements(
+    params: &ReplicatedLogletParams,
+    evacuating: &NodeSet,
+    nodes_config: &NodesConfiguration,
+) -> Result<NodeSet, EvacuationError> {
+    let mut candidates: Vec<PlainNodeId> = nodes_config
+        .iter_role(Role::LogServer)
+        .filter(|(node_id, config)| {
+            config.log_server_config.storage_state.should_write_to()
+                && !params.nodeset.contains(*node_id)
+        })
+        .map(|(node_id, _)| node_id)
+        .collect();
+    candidates.sort_unstable();
+    if !candidates.is_empty() {
+        let start = *params.loglet_id % candidates.len() as u64;
+        candidates.rotate_left(usize::try_from(start).expect("index fits into usize"));
+    }
+    let replacements: NodeSet = candidates.into_iter().take(evacuating.len()).collect();
+
+    let mut target_nodeset = remaining_nodeset(params, evacuating);
+    target_nodeset.extend(replacements.iter().copied());
+    let mut checker = NodeSetChecker::new(&target_nodeset, nodes_config, &params.replication);
+    checker.fill_with(true);
+    if !checker.check_write_quorum(|attr| *attr) {
+        return Err(EvacuationError::InsufficientLogServers {
+            nodeset: params.nodeset.clone(),
+            evacuating: evacuating.clone(),
+            replication: params.replication.clone(),
+        });
+    }
+    Ok(replacements)
+}
+
+/// Periodically scrubs the replicated loglets this node is responsible for.
+pub struct Scrubber<T> {
+    networking: Networking<T>,
+    findings: ScrubFindings,
+}
+
+impl<T: TransportConnect> Scrubber<T> {
+    pub fn new(networking: Networking<T>, findings: ScrubFindings) -> Self {
+        Self {
+            networking,
+            findings,
+        }
+    }
+
+    pub async fn run(self) -> anyhow::Result<()> {
+        debug!("Started the replicated loglet scrubber");
+        let mut cursors = ScrubCursors::load(node_filepath(SCRUB_CURSORS_FILE_NAME));
+        let mut cancel = std::pin::pin!(cancellation_watcher());
+        loop {
+            let interval: Duration = Configuration::pinned()
+                .bifrost
+                .replicated_loglet
+                .scrub_interval
+                .into();
+            tokio::select! {
+                _ = &mut cancel => return Ok(()),
+                _ = tokio::time::sleep(interval.add_jitter(0.1)) => {}
+            }
+
+            if Configuration::pinned()
+                .bifrost
+                .replicated_loglet
+                .disable_scrubber
+            {
+                continue;
+            }
+
+            let cancelled = tokio::select! {
+                _ = &mut cancel => true,
+                _ = self.scrub_pass(&mut cursors) => false,
+            };
+            // keep the progress of an interrupted pass
+            cursors.persist().await;
+            if cancelled {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn scrub_pass(&self, cursors: &mut ScrubCursors) {
+        let metadata = Metadata::current();
+        let my_node_id = metadata.my_node_id().as_plain();
+        let cluster_state = TaskCenter::with_current(|tc| tc.cluster_state().clone());
+        let mut alive: Vec<PlainNodeId> = cluster_state
+            .all()
+            .into_iter()
+            .filter(|(_, state)| state.is_alive())
+            .map(|(node_id, _)| node_id.as_plain())
+            .collect();
+        alive.sort_unstable();
+        alive.dedup();
+
+        let logs = metadata.logs_ref();
+        let known_loglets: HashSet<LogletId> = logs
+            .iter_replicated_loglets()
+            .map(|(loglet_id, _)| *loglet_id)
+            .collect();
+        cursors.retain(&known_loglets);
+
+        let loglets: Vec<ReplicatedLogletParams> = logs
+            .iter_replicated_loglets()
+            .filter(|(_, loglet_ref)| {
+                scrubbing_node(&loglet_ref.params, &alive) == Some(my_node_id)
+            })
+            .map(|(_, loglet_ref)| loglet_ref.params.clone())
+            .collect();
+        drop(logs);
+
+        debug!("Starting a scrub pass over {} loglets", loglets.len());
+        for params in loglets {
+            // the sequencer might have come back since we started the pass
+            if params.sequencer.as_plain() != my_node_id
+                && cluster_state.is_alive(NodeId::from(params.sequencer.as_plain()))
+            {
+                continue;
+            }
+            self.scrub_loglet(&params, cursors).await;
+        }
+    }
+
+    /// Copies every record of the sealed loglet that isn't sufficiently replicated without the
+    /// `evacuating` nodes onto the remaining nodes of its nodeset and onto replacement
+    /// log-servers picked from the nodes configuration.
+    ///
+    /// The segment spans `[base_lsn, tail_lsn)`. Once the returned summary is complete, the
+    /// `evacuating` nodes can be replaced with [`EvacuationSummary::replacements`] in the nodeset.
+    /// Unlike scrubbing, evacuation doesn't report findings. It's safe to retry until the
+    /// returned summary is complete. Fails if there aren't enough writeable log-servers to take
+    /// over the copies of the evacuated nodes.
+    pub async fn evacuate_loglet(
+        &self,
+        params: &ReplicatedLogletParams,
+        evacuating: &NodeSet,
+        base_lsn: Lsn,
+        tail_lsn: Lsn,
+    ) -> Result<EvacuationSummary, EvacuationError> {
+        let nodes_config = Metadata::with_current(|m| m.nodes_config_snapshot());
+        let replacements = select_replacements(params, evacuating, &nodes_config)?;
+
+        // the replacements take part in the evacuation as if they were members of the nodeset
+        // already. They must be sealed like the rest of the nodeset before they join it.
+        let mut target = params.clone();
+        target.nodeset.extend(replacements.iter().copied());
+        let unsealed_replacements = self.seal_replacements(params, &replacements).await;
+
+        let known_tail = tail_lsn.into_offset(base_lsn);
+        let summary = self
+            .process_loglet(
+                &target,
+                evacuating,
+                LogletOffset::OLDEST,
+                known_tail,
+                false,
+                None,
+            )
+            .await;
+        Ok(EvacuationSummary {
+            replacements,
+            unsealed_replacements,
+            ..summary
+        })
+    }
+
+    /// Seals the loglet on the `replacements`. Returns the number of replacements that couldn't
+    /// be sealed.
+    async fn seal_replacements(
+        &self,
+        params: &ReplicatedLogletParams,
+        replacements: &NodeSet,
+    ) -> u64 {
+        let mut unsealed = 0;
+        for node_id in replacements.iter().copied() {
+            let msg = Seal {
+                header: LogServerRequestHeader::new(params.loglet_id, LogletOffset::INVALID),
+                sequencer: params.sequencer,
+            };
+            match self
+                .networking
+                .call_rpc(
+                    node_id,
+                    Swimlane::BifrostData,
+                    msg,
+                    Some(params.loglet_id.into()),
+                    None,
+                )
+                .await
+            {
+                Ok(sealed) if sealed.header.status == Status::Ok => {}
+                Ok(sealed) => {
+                    debug!(
+                        loglet_id = %params.loglet_id,
+                        %node_id,
+                        "Could not seal the loglet on replacement log-server. Log server responded with status={:?}",
+                        sealed.header.status
+                    );
+                    unsealed += 1;
+                }
+                Err(err) => {
+                    debug!(
+                        loglet_id = %params.loglet_id,
+                        %node_id,
+                        %err,
+                        "Could not seal the loglet on replacement log-server. Network error",
+                    );
+                    unsealed += 1;
+                }
+            }
+        }
+        unsealed
+    }
+
+    async fn scrub_loglet(&self, params: &ReplicatedLogletParams, cursors: &mut ScrubCursors) {
+        let from_offset = cursors.get(params.loglet_id);
+        let summary = self
+            .process_loglet(
+                params,
+                &NodeSet::default(),
+                from_offset,
+                LogletOffset::INVALID,
+                true,
+                Some(cursors),
+            )
+            .await;
+        let total_damaged = summary.copied_records + summary.failed_records;
+
+        if total_damaged > 0 {
+            info!(
+                loglet_id = %params.loglet_id,
+                "Scrubbed {} records, found {} damaged records",
+                summary.checked_records,
+                total_damaged,
+            );
+        } else {
+            trace!(
+                loglet_id = %params.loglet_id,
+                "Scrubbed {} records, no damage found",
+                summary.checked_records,
+            );
+        }
+    }
+
+    async fn process_loglet(
+        &self,
+        params: &ReplicatedLogletParams,
+        evacuating: &NodeSet,
+        mut from_offset: LogletOffset,
+        known_tail: LogletOffset,
+        report_findings: bool,
+        mut cursors: Option<&mut ScrubCursors>,
+    ) -> EvacuationSummary {
+        let mut summary = EvacuationSummary::default();
+        loop {
+            let to_offset = LogletOffset::new(from_offset.saturating_add(SCRUB_CHUNK_SIZE - 1));
+            let nodes_config = Metadata::with_current(|m| m.nodes_config_snapshot());
+            let effective_nodeset = params.nodeset.to_effective(&nodes_config);
+
+            let digests = self
+                .get_digests(params, &effective_nodeset, from_offset, to_offset)
+                .await;
+            let assessment = assess(
+                params,
+                evacuating,
+                &nodes_config,
+                from_offset,
+                to_offset,
+                known_tail,
+                &digests,
+            );
+
+            counter!(BIFROST_SCRUBBER_CHECKED_RECORDS).increment(u64::from(assessment.checked));
+            summary.checked_records += u64::from(assessment.checked);
+            if !assessment.all_responded {
+                summary.unchecked_ranges += 1;
+            }
+
+            for (offset, copies) in &assessment.damaged {
+                let outcome = if report_findings {
+                    self.repair_record(
+                        params,
+                        &nodes_config,
+                        assessment.global_tail,
+                        *offset,
+                        copies,
+                    )
+                    .await
+                } else {
+                    self.try_repair_record(
+                        params,
+                        evacuating,
+                        &nodes_config,
+                        assessment.global_tail,
+                        *offset,
+                        copies,
+                    )
+                    .await
+                };
+                match outcome {
+                    RepairOutcome::Repaired => summary.copied_records += 1,
+                    RepairOutcome::Failed | RepairOutcome::Unrepairable => {
+                        summary.failed_records += 1
+                    }
+                }
+            }
+
+            if let Some(cursors) = cursors.as_deref_mut() {
+                cursors
+                    .advance(params.loglet_id, assessment.next_offset)
+                    .await;
+            }
+            let Some(next_offset) = assessment.next_offset else {
+                break;
+            };
+            from_offset = next_offset;
+
+            // rate limit the scrubbing to keep the load on log-servers in check
+            let records_per_second = Configuration::pinned()
+                .bifrost
+                .replicated_loglet
+                .scrub_records_per_second
+                .get();
+            tokio::time::sleep(Duration::from_secs_f64(
+                f64::from(assessment.checked) / f64::from(records_per_second),
+            ))
+            .await;
+        }
+
+        summary
+    }
+
+    async fn get_digests(
+        &self,
+        params: &ReplicatedLogletParams,
+        effective_nodeset: &NodeSet,
+        from_offset: LogletOffset,
+        to_offset: LogletOffset,
+    ) -> Vec<(PlainNodeId, Digest)> {
+        let timeout = Configuration::pinned()
+            .bifrost
+            .replicated_loglet
+            .rpc_timeout
+            .max_ms
+            .get();
+        let timeout = Duration::from_millis(u64::from(timeout));
+
+        let msg = GetDigest {
+            header: LogServerRequestHeader::new(params.loglet_id, LogletOffset::INVALID),
+            from_offset,
+            to_offset,
+            verify_checksums: true,
+        };
+
+        let mut requests = JoinSet::new();
+        for node_id in effective_nodeset.iter().copied() {
+            let networking = self.networking.clone();
+            let msg = msg.clone();
+            let loglet_id = params.loglet_id;
+            requests
+                .build_task()
+                .name("scrub-get-digest")
+                .spawn(
+                    async move {
+                        let result = networking
+                            .call_rpc(
+                                node_id,
+                                Swimlane::BifrostData,
+                                msg,
+                                Some(loglet_id.into()),
+                                Some(timeout),
+                            )
+                            .await;
+                        (node_id, result)
+                    }
+                    .in_current_tc(),
+                )
+                .expect("to spawn get-digest task");
+        }
+
+        let mut digests = Vec::with_capacity(effective_nodeset.len());
+        while let Some(Ok((node_id, result))) = requests.join_next().await {
+            match result {
+                Ok(digest) => digests.push((node_id, digest)),
+                Err(err) => {
+                    debug!(
+                        loglet_id = %params.loglet_id,
+                        %node_id,
+                        %err,
+                        "Could not get digest from log-server while scrubbing",
+                    );
+                }
+            }
+        }
+        digests
+    }
+
+    async fn repair_record(
+        &self,
+        params: &ReplicatedLogletParams,
+        nodes_config: &NodesConfiguration,
+        global_tail: LogletOffset,
+        offset: LogletOffset,
+        copies: &OffsetCopies,
+    ) -> RepairOutcome {
+        let outcome = self
+            .try_repair_record(
+                params,
+                &NodeSet::default(),
+                nodes_config,
+                global_tail,
+                offset,
+                copies,
+            )
+            .await;
+        counter!(BIFROST_SCRUBBER_REPAIRS, "outcome" => outcome.to_string()).increment(1);
+
+        let detected_at = MillisSinceEpoch::now();
+        let healthy_copies = u32::try_from(copies.healthy.len()).unwrap_or(u32::MAX);
+        let report = |kind: FindingKind, node_id: Option<PlainNodeId>| {
+            counter!(BIFROST_SCRUBBER_FINDINGS, "kind" => kind.to_string()).increment(1);
+            self.findings.record(ScrubFinding {
+                loglet_id: params.loglet_id,
+                offset,
+                kind,
+                node_id,
+                healthy_copies,
+                outcome,
+                detected_at,
+            });
+        };
+        for node_id in copies.corrupted.iter().copied() {
+            report(FindingKind::Corrupted, Some(node_id));
+        }
+        if copies.corrupted.is_empty() {
+            report(FindingKind::UnderReplicated, None);
+        }
+
+        match outcome {
+            RepairOutcome::Repaired => info!(
+                loglet_id = %params.loglet_id,
+                %offset,
+                "Repaired damaged record, healthy copies on {}, corrupted copies on {}",
+                copies.healthy,
+                copies.corrupted,
+            ),
+            RepairOutcome::Failed | RepairOutcome::Unrepairable => warn!(
+                loglet_id = %params.loglet_id,
+                %offset,
+                "Could not repair damaged record ({}), healthy copies on {}, corrupted copies on {}",
+                outcome,
+                copies.healthy,
+                copies.corrupted,
+            ),
+        }
+        outcome
+    }
+
+    async fn try_repair_record(
+        &self,
+        params: &ReplicatedLogletParams,
+        evacuating: &NodeSet,
+        nodes_config: &NodesConfiguration,
+        global_tail: LogletOffset,
+        offset: LogletOffset,
+        copies: &OffsetCopies,
+    ) -> RepairOutcome {
+        if copies.healthy.is_empty() {
+            return RepairOutcome::Unrepairable;
+        }
+
+        // corrupted copies get overwritten, on top of that we might need more copies to
+        // restore the replication property of the record. Copies on evacuated nodes are only
+        // used as a source.
+        let existing_copies: NodeSet = copies
+            .healthy
+            .iter()
+            .chain(copies.corrupted.iter())
+            .copied()
+            .filter(|node_id| !evacuating.contains(*node_id))
+            .collect();
+        let spread_selector = SpreadSelector::new(
+            remaining_nodeset(params, evacuating),
+            SelectorStrategy::Flood,
+            params.replication.clone(),
+        );
+        let fixups = match spread_selector.select_fixups(
+            &existing_copies,
+            &mut rng(),
+            nodes_config,
+            &NodeSet::default(),
+        ) {
+            Ok(fixups) => fixups,
+            Err(err) => {
+                debug!(
+                    loglet_id = %params.loglet_id,
+                    %offset,
+                    %err,
+                    "Cannot find enough writeable log-servers to repair the record",
+                );
+                return RepairOutcome::Failed;
+            }
+        };
+        let targets: NodeSet = copies
+            .corrupted
+            .iter()
+            .copied()
+            .filter(|node_id| !evacuating.contains(*node_id))
+            .chain(fixups.iter().copied())
+            .collect();
+
+        let Some(record) = self.read_record(params, global_tail, offset, copies).await else {
+            return RepairOutcome::Failed;
+        };
+
+        let msg = Store {
+            header: LogServerRequestHeader::new(params.loglet_id, global_tail),
+            timeout_at: None,
+            // Must be set to overwrite corrupted copies and to bypass the seal
+            flags: StoreFlags::IgnoreSeal,
+            first_offset: offset,
+            sequencer: params.sequencer,
+            known_archived: LogletOffset::INVALID,
+            payloads: vec![record].into(),
+        };
+
+        let mut stores = JoinSet::new();
+        for node_id in targets.iter().copied() {
+            let networking = self.networking.clone();
+            let msg = msg.clone();
+            let loglet_id = params.loglet_id;
+            stores
+                .build_task()
+                .name("scrub-repair-record")
+                .spawn(
+                    async move {
+                        let result = networking
+                            .call_rpc(
+                                node_id,
+                                Swimlane::BifrostData,
+                                msg,
+                                Some(loglet_id.into()),
+                                None,
+                            )
+                            .await;
+                        (node_id, result)
+                    }
+                    .in_current_tc(),
+                )
+                .expect("to spawn store task for repairing records");
+        }
+
+        let mut outcome = RepairOutcome::Repaired;
+        while let Some(Ok((node_id, result))) = stores.join_next().await {
+            match result {
+                Ok(stored) if stored.header.status == Status::Ok => {}
+                Ok(stored) => {
+                    debug!(
+                        loglet_id = %params.loglet_id,
+                        %node_id,
+                        %offset,
+                        "Could not store repaired record. Log server responded with status={:?}",
+                        stored.header.status
+                    );
+                    outcome = RepairOutcome::Failed;
+                }
+                Err(err) => {
+                    debug!(
+                        loglet_id = %params.loglet_id,
+                        %node_id,
+                        %offset,
+                        %err,
+                        "Could not store repaired record. Network error",
+                    );
+                    outcome = RepairOutcome::Failed;
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Reads the record at `offset` from the first healthy copy that responds.
+    async fn read_record(
+        &self,
+        params: &ReplicatedLogletParams,
+        global_tail: LogletOffset,
+        offset: LogletOffset,
+        copies: &OffsetCopies,
+    ) -> Option<Record> {
+        for node_id in copies.healthy.iter().copied() {
+            let msg = GetRecords {
+                header: LogServerRequestHeader::new(params.loglet_id, global_tail),
+                total_limit_in_bytes: None,
+                filter: KeyFilter::Any,
+                from_offset: offset,
+                to_offset: offset,
+            };
+            match self
+                .networking
+                .call_rpc(
+                    node_id,
+                    Swimlane::BifrostData,
+                    msg,
+                    Some(params.loglet_id.into()),
+                    None,
+                )
+                .await
+            {
+                Ok(records) if records.header.status == Status::Ok => {
+                    let record = records
+                        .records
+                        .into_iter()
+                        .find_map(|(record_offset, record)| match record {
+                            MaybeRecord::Data(record) if record_offset == offset => Some(record),
+                            _ => None,
+                        });
+                    if record.is_some() {
+                        return record;
+                    }
+                }
+                Ok(records) => {
+                    debug!(
+                        loglet_id = %params.loglet_id,
+                        %node_id,
+                        %offset,
+                        "Could not read healthy copy of the record. Log server responded with status={:?}",
+                        records.header.status
+                    );
+                }
+                Err(err) => {
+                    debug!(
+                        loglet_id = %params.loglet_id,
+                        %node_id,
+                        %offset,
+                        %err,
+                        "Could not read healthy copy of the record. Network error",
+                    );
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use googletest::prelude::*;
+
+    use restate_types::GenerationalNodeId;
+    use restate_types::logs::TailState;
+    use restate_types::net::log_server::DigestEntry;
+    use restate_types::nodes_config::StorageState;
+
+    use crate::providers::replicated_loglet::test_util::{
+        generate_logserver_node, generate_logserver_nodes_config,
+    };
+
+    fn params() -> ReplicatedLogletParams {
+        ReplicatedLogletParams {
+            loglet_id: LogletId::new_unchecked(1),
+            sequencer: GenerationalNodeId::new(1, 1),
+            replication: ReplicationProperty::new(2.try_into().unwrap()),
+            nodeset: NodeSet::from([1, 2, 3]),
+        }
+    }
+
+    fn digest(global_tail: u32, entries: &[(u32, u32, RecordStatus)]) -> Digest {
+        Digest::new(
+            TailState::Open(LogletOffset::new(global_tail)),
+            LogletOffset::new(global_tail),
+            entries
+                .iter()
+                .map(|(from, to, status)| DigestEntry {
+                    from_offset: LogletOffset::new(*from),
+                    to_offset: LogletOffset::new(*to),
+                    status: status.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn healthy_records() {
+        let params = params();
+        let nodes_config = generate_logserver_nodes_config(3, StorageState::ReadWrite);
+        let digests = [
+            (
+                PlainNodeId::new(1),
+                digest(11, &[(1, 10, RecordStatus::Exists)]),
+            ),
+            (
+                PlainNodeId::new(2),
+                digest(11, &[(1, 10, RecordStatus::Exists)]),
+            ),
+            (PlainNodeId::new(3), digest(11, &[])),
+        ];
+
+        let assessment = assess(
+            &params,
+            &NodeSet::default(),
+            &nodes_config,
+            LogletOffset::OLDEST,
+            LogletOffset::new(100),
+            LogletOffset::INVALID,
+            &digests,
+        );
+        assert_that!(assessment.damaged, empty());
+        assert_that!(assessment.checked, eq(10));
+        assert_that!(assessment.global_tail, eq(LogletOffset::new(11)));
+        assert_that!(assessment.next_offset, none());
+    }
+
+    #[test]
+    fn corrupted_and_under_replicated_records() {
+        let params = params();
+        let nodes_config = generate_logserver_nodes_config(3, StorageState::ReadWrite);
+        let digests = [
+            (
+                PlainNodeId::new(1),
+                digest(
+                    11,
+                    &[
+                        (1, 4, RecordStatus::Exists),
+                        (5, 5, RecordStatus::Corrupted),
+                        (6, 10, RecordStatus::Exists),
+                    ],
+                ),
+            ),
+            (
+                PlainNodeId::new(2),
+                digest(
+                    11,
+                    &[(1, 7, RecordStatus::Exists), (9, 10, RecordStatus::Exists)],
+                ),
+            ),
+            (
+                PlainNodeId::new(3),
+                digest(11, &[(1, 3, RecordStatus::Exists)]),
+            ),
+        ];
+
+        let assessment = assess(
+            &params,
+            &NodeSet::default(),
+            &nodes_config,
+            LogletOffset::OLDEST,
+            LogletOffset::new(100),
+            LogletOffset::INVALID,
+            &digests,
+        );
+        assert_that!(
+            assessment.damaged.keys().copied().collect::<Vec<_>>(),
+            elements_are![eq(LogletOffset::new(5)), eq(LogletOffset::new(8))]
+        );
+        assert_that!(
+            assessment.damaged.get(&LogletOffset::new(5)),
+            some(eq(&OffsetCopies {
+                healthy: NodeSet::from([2]),
+                corrupted: NodeSet::from([1]),
+            }))
+        );
+        assert_that!(
+            assessment.damaged.get(&LogletOffset::new(8)),
+            some(eq(&OffsetCopies {
+                healthy: NodeSet::from([1]),
+                corrupted: NodeSet::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn missing_response_hides_under_replication() {
+        let params = params();
+        let nodes_config = generate_logserver_nodes_config(3, StorageState::ReadWrite);
+        // node 3 didn't respond, it might have a copy of the records node 2 is missing
+        let digests = [
+            (
+                PlainNodeId::new(1),
+                digest(
+                    11,
+                    &[
+                        (1, 4, RecordStatus::Corrupted),
+                        (5, 10, RecordStatus::Exists),
+                    ],
+                ),
+            ),
+            (
+                PlainNodeId::new(2),
+                digest(11, &[(1, 5, RecordStatus::Exists)]),
+            ),
+        ];
+
+        let assessment = assess(
+            &params,
+            &NodeSet::default(),
+            &nodes_config,
+            LogletOffset::OLDEST,
+            LogletOffset::new(100),
+            LogletOffset::INVALID,
+            &digests,
+        );
+        // corrupted copies are always reported
+        assert_that!(assessment.damaged.len(), eq(4));
+        assert_that!(
+            assessment.damaged.keys().last(),
+            some(eq(&LogletOffset::new(4)))
+        );
+    }
+
+    #[test]
+    fn skips_trimmed_prefix_and_continues_to_global_tail() {
+        let params = params();
+        let nodes_config = generate_logserver_nodes_config(3, StorageState::ReadWrite);
+        let digests = [
+            (
+                PlainNodeId::new(1),
+                digest(
+                    2000,
+                    &[
+                        (1, 1500, RecordStatus::Trimmed),
+                        (1501, 1999, RecordStatus::Exists),
+                    ],
+                ),
+            ),
+            (
+                PlainNodeId::new(2),
+                digest(
+                    2000,
+                    &[
+                        (1, 1500, RecordStatus::Trimmed),
+                        (1501, 1999, RecordStatus::Exists),
+                    ],
+                ),
+            ),
+            (
+                PlainNodeId::new(3),
+                digest(1800, &[(1, 1500, RecordStatus::Trimmed)]),
+            ),
+        ];
+
+        // the whole chunk is trimmed, we jump to the trim point
+        let assessment = assess(
+            &params,
+            &NodeSet::default(),
+            &nodes_config,
+            LogletOffset::OLDEST,
+            LogletOffset::new(1000),
+            LogletOffset::INVALID,
+            &digests,
+        );
+        assert_that!(assessment.damaged, empty());
+        assert_that!(assessment.checked, eq(0));
+        assert_that!(assessment.global_tail, eq(LogletOffset::new(2000)));
+        assert_that!(assessment.next_offset, some(eq(LogletOffset::new(1501))));
+
+        let assessment = assess(
+            &params,
+            &NodeSet::default(),
+            &nodes_config,
+            LogletOffset::new(1001),
+            LogletOffset::new(2000),
+            LogletOffset::INVALID,
+            &digests,
+        );
+        assert_that!(assessment.damaged, empty());
+        assert_that!(assessment.checked, eq(499));
+        assert_that!(assessment.next_offset, none());
+    }
+
+    #[test]
+    fn copies_on_evacuated_nodes_do_not_count() {
+        let params = params();
+        let nodes_config = generate_logserver_nodes_config(3, StorageState::ReadWrite);
+        // node 3 is being evacuated and the sealed loglet ends at offset 11, even though the
+        // log-servers only know about a global tail of 6.
+        let digests = [
+            (
+                PlainNodeId::new(1),
+                digest(6, &[(1, 10, RecordStatus::Exists)]),
+            ),
+            (
+                PlainNodeId::new(2),
+                digest(6, &[(1, 5, RecordStatus::Exists)]),
+            ),
+            (
+                PlainNodeId::new(3),
+                digest(6, &[(6, 10, RecordStatus::Exists)]),
+            ),
+        ];
+
+        let assessment = assess(
+            &params,
+            &NodeSet::from([3]),
+            &nodes_config,
+            LogletOffset::OLDEST,
+            LogletOffset::new(100),
+            LogletOffset::new(11),
+            &digests,
+        );
+        assert_that!(assessment.all_responded, eq(true));
+        assert_that!(assessment.checked, eq(10));
+        assert_that!(assessment.global_tail, eq(LogletOffset::new(11)));
+        assert_that!(
+            assessment.damaged.keys().copied().collect::<Vec<_>>(),
+            elements_are![
+                eq(LogletOffset::new(6)),
+                eq(LogletOffset::new(7)),
+                eq(LogletOffset::new(8)),
+                eq(LogletOffset::new(9)),
+                eq(LogletOffset::new(10)),
+            ]
+        );
+        // the copy on the evacuated node can still be used as a source
+        assert_that!(
+            assessment.damaged.get(&LogletOffset::new(6)),
+            some(eq(&OffsetCopies {
+                healthy: NodeSet::from([1, 3]),
+                corrupted: NodeSet::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn scrubbing_node_selection() {
+        // loglet 1 sequenced by N1
+        let params = params();
+
+        // the sequencer scrubs its loglet while it's alive
+        let alive = [1, 2, 3].map(PlainNodeId::new);
+        assert_that!(
+            scrubbing_node(&params, &alive),
+            some(eq(PlainNodeId::new(1)))
+        );
+
+        // otherwise, it's picked by loglet id among the alive nodes
+        let alive = [2, 3].map(PlainNodeId::new);
+        assert_that!(
+            scrubbing_node(&params, &alive),
+            some(eq(PlainNodeId::new(3)))
+        );
+        let params = ReplicatedLogletParams {
+            loglet_id: LogletId::new_unchecked(2),
+            ..params
+        };
+        assert_that!(
+            scrubbing_node(&params, &alive),
+            some(eq(PlainNodeId::new(2)))
+        );
+
+        assert_that!(scrubbing_node(&params, &[]), none());
+    }
+
+    #[test]
+    fn replacement_selection() {
+        // loglet 1 on N1, N2, N3
+        let params = params();
+        let mut nodes_config = generate_logserver_nodes_config(5, StorageState::ReadWrite);
+        nodes_config.upsert_node(generate_logserver_node(3, StorageState::Draining));
+        nodes_config.upsert_node(generate_logserver_node(6, StorageState::Disabled));
+
+        // one writeable node outside of the nodeset per evacuated node, picked by loglet id
+        assert_that!(
+            select_replacements(&params, &NodeSet::from([3]), &nodes_config),
+            ok(eq(NodeSet::from([5])))
+        );
+        assert_that!(
+            select_replacements(&params, &NodeSet::from([1, 2, 3]), &nodes_config),
+            ok(eq(NodeSet::from([4, 5])))
+        );
+
+        // the remaining nodeset can't satisfy the replication property on its own
+        let nodes_config = generate_logserver_nodes_config(3, StorageState::ReadWrite);
+        assert_that!(
+            select_replacements(&params, &NodeSet::from([3]), &nodes_config),
+            ok(eq(NodeSet::default()))
+        );
+        assert_that!(
+            select_replacements(&params, &NodeSet::from([2, 3]), &nodes_config),
+            err(pat!(EvacuationError::InsufficientLogServers { .. }))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scrub_cursors_are_persisted() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(SCRUB_CURSORS_FILE_NAME);
+        let loglet_1 = LogletId::new_unchecked(1);
+        let loglet_2 = LogletId::new_unchecked(2);
+
+        let mut cursors = ScrubCursors::load(path.clone());
+        assert_that!(cursors.get(loglet_1), eq(LogletOffset::OLDEST));
+        cursors
+            .advance(loglet_1, Some(LogletOffset::new(1001)))
+            .await;
+        cursors
+            .advance(loglet_2, Some(LogletOffset::new(2001)))
+            .await;
+        // not every chunk is persisted
+        assert_that!(ScrubCursors::load(path.clone()).cursors, empty());
+
+        // the cursors are persisted once enough time passed
+        tokio::time::advance(PERSIST_CURSORS_INTERVAL).await;
+        cursors
+            .advance(loglet_2, Some(LogletOffset::new(3001)))
+            .await;
+
+        // a restarted scrubber resumes from the persisted cursors
+        let mut cursors = ScrubCursors::load(path.clone());
+        assert_that!(cursors.get(loglet_1), eq(LogletOffset::new(1001)));
+        assert_that!(cursors.get(loglet_2), eq(LogletOffset::new(3001)));
+
+        // ... or once enough chunks were scrubbed
+        let mut next_offset = LogletOffset::new(1001);
+        for _ in 1..PERSIST_CURSORS_EVERY_CHUNKS {
+            next_offset = LogletOffset::new(next_offset.saturating_add(SCRUB_CHUNK_SIZE));
+            cursors.advance(loglet_1, Some(next_offset)).await;
+        }
+        assert_that!(
+            ScrubCursors::load(path.clone()).get(loglet_1),
+            eq(LogletOffset::new(1001))
+        );
+        next_offset = LogletOffset::new(next_offset.saturating_add(SCRUB_CHUNK_SIZE));
+        cursors.advance(loglet_1, Some(next_offset)).await;
+        assert_that!(
+            ScrubCursors::load(path.clone()).get(loglet_1),
+            eq(next_offset)
+        );
+
+        // a fully scrubbed loglet starts over, a removed loglet is forgotten
+        cursors.advance(loglet_1, None).await;
+        cursors.retain(&HashSet::from([loglet_1]));
+        cursors.persist().await;
+
+        let cursors = ScrubCursors::load(path);
+        assert_that!(cursors.cursors, empty());
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_to_scrub_without_responses() {
+        let params = params();
+        let nodes_config = generate_logserver_nodes_config(3, StorageState::ReadWrite);
+        let assessment = assess(
+            &params,
+            &NodeSet::default(),
+            &nodes_config,
+            LogletOffset::OLDEST,
+            LogletOffset::new(1000),
+            LogletOffset::INVALID,
+            &[],
+        );
+        assert_that!(assessment.damaged, empty());
+        assert_that!(assessment.checked, eq(0));
+        assert_that!(assessment.next_offset, none());
+        assert_that!(assessment.all_responded, eq(false));
+    }
+}
@@ -143,6 +143,7 @@ impl<T: TransportConnect> RepairTail<T> {
                 ),
                 from_offset: self.digests.start_offset(),
                 to_offset: self.digests.target_tail().prev(),
+                verify_checksums: false,
             };
             get_digest_requests
                 .build_task()
@@ -34,8 +34,9 @@ use super::{
 };
 use crate::network::PeerMetadataVersion;
 use crate::network::connection::ConnectThrottle;
-use crate::network::handshake::{negotiate_protocol_version, wait_for_hello};
+use crate::network::handshake::{negotiate_protocol_version, verify_peer_identity, wait_for_hello};
 use crate::network::metric_definitions::{NETWORK_CONNECTION_CREATED, NETWORK_CONNECTION_DROPPED};
+use crate::network::tls::PeerIdentity;
 use crate::{Metadata, TaskId, TaskKind, my_node_id};
 
 #[derive(Copy, Clone, PartialOrd, PartialEq, Default)]
@@ -248,8 +249,23 @@ impl ConnectionManager {
 
     /// Accept a new incoming connection stream and register a network reactor task for it.
     pub async fn accept_incoming_connection<S>(
+        &self,
+        incoming: S,
+    ) -> Result<EgressStream, AcceptError>
+    where
+        S: Stream<Item = Message> + Unpin + Send + 'static,
+    {
+        self.accept_incoming_connection_with_identity(incoming, None)
+            .await
+    }
+
+    /// Like [`Self::accept_incoming_connection`], but additionally verifies that the TLS
+    /// certificate of the peer (if the connection was secured with mutual TLS) belongs to the node
+    /// announced in the Hello message.
+    pub async fn accept_incoming_connection_with_identity<S>(
         &self,
         mut incoming: S,
+        peer_identity: Option<PeerIdentity>,
     ) -> Result<EgressStream, AcceptError>
     where
         S: Stream<Item = Message> + Unpin + Send + 'static,
@@ -344,6 +360,10 @@ impl ConnectionManager {
             return Err(HandshakeError::Failed("cluster name mismatch".to_owned()).into());
         }
 
+        if let Some(peer_identity) = &peer_identity {
+            verify_peer_identity(peer_identity, peer_node_id, &nodes_config)?;
+        }
+
         let selected_protocol_version = negotiate_protocol_version(&hello)?;
         debug!(
             "Negotiated protocol version {:?} with client",
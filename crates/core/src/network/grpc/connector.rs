@@ -10,6 +10,7 @@
 
 use futures::Stream;
 use http::Uri;
+use hyper_util::client::legacy::connect::dns::GaiResolver;
 use hyper_util::rt::TokioIo;
 use tokio::io;
 use tokio::net::UnixStream;
@@ -24,8 +25,10 @@ use restate_types::net::address::{AdvertisedAddress, GrpcPort, ListenerPort, Pee
 use restate_types::net::connect_opts::GrpcConnectionOptions;
 
 use crate::network::grpc::DEFAULT_GRPC_COMPRESSION;
+use crate::network::net_util::http_connector;
 use crate::network::protobuf::core_node_svc::core_node_svc_client::CoreNodeSvcClient;
 use crate::network::protobuf::network::Message;
+use crate::network::tls::TlsConnector;
 use crate::network::transport_connector::find_node;
 use crate::network::{ConnectError, Destination, Swimlane, TransportConnect};
 use crate::{Metadata, TaskCenter, TaskKind};
@@ -120,7 +123,13 @@ fn create_channel<P: ListenerPort + GrpcPort>(
                 }
             }))
         }
-        PeerNetAddress::Http(_) => endpoint.connect_lazy()
+        PeerNetAddress::Http(_) => match &options.tls {
+            Some(tls) => {
+                let http = http_connector(&endpoint, GaiResolver::new());
+                endpoint.connect_with_connector_lazy(TlsConnector::new(http, tls.clone()))
+            }
+            None => endpoint.connect_lazy(),
+        },
     }
 }
 
@@ -23,6 +23,7 @@ use crate::network::protobuf::core_node_svc::core_node_svc_server::{
 };
 use crate::network::protobuf::core_node_svc::{RpcRequest, RpcResponse};
 use crate::network::protobuf::network::Message;
+use crate::network::tls::PeerIdentity;
 
 pub struct CoreNodeSvcHandler {
     connections: ConnectionManager,
@@ -68,6 +69,7 @@ impl CoreNodeSvc for CoreNodeSvcHandler {
         &self,
         request: Request<Streaming<Message>>,
     ) -> Result<Response<Self::CreateConnectionStream>, Status> {
+        let peer_identity = request.extensions().get::<PeerIdentity>().cloned();
         let incoming = request.into_inner();
         let transformed = incoming.map_while(|x| match x {
             Ok(msg) => Some(msg),
@@ -78,7 +80,7 @@ impl CoreNodeSvc for CoreNodeSvcHandler {
         });
         let output_stream = self
             .connections
-            .accept_incoming_connection(transformed)
+            .accept_incoming_connection_with_identity(transformed, peer_identity)
             .await?;
 
         // We map all responses to Ok, we never rely on sending tonic::Status errors explicitly.
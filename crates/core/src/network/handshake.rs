@@ -13,10 +13,13 @@ use std::time::Duration;
 use futures::Stream;
 use tokio_stream::StreamExt;
 
+use restate_types::GenerationalNodeId;
 use restate_types::net::{CURRENT_PROTOCOL_VERSION, ProtocolVersion};
+use restate_types::nodes_config::NodesConfiguration;
 
 use super::HandshakeError;
 use super::protobuf::network::{Header, Hello, Message, Welcome, message};
+use super::tls::PeerIdentity;
 
 pub async fn wait_for_hello<S>(
     incoming: &mut S,
@@ -84,6 +87,27 @@ pub fn negotiate_protocol_version(hello: &Hello) -> Result<ProtocolVersion, Hand
     Ok(selected_proto_version)
 }
 
+/// Verifies that the TLS certificate presented by the peer belongs to the node it claims to be in
+/// its Hello message, i.e. that the certificate is valid for the peer's configured node name.
+pub fn verify_peer_identity(
+    identity: &PeerIdentity,
+    peer_node_id: GenerationalNodeId,
+    nodes_config: &NodesConfiguration,
+) -> Result<(), HandshakeError> {
+    // Unknown peers are rejected; they'll retry once our nodes configuration has caught up.
+    let peer_config = nodes_config
+        .find_node_by_id(peer_node_id.as_plain())
+        .map_err(|err| {
+            HandshakeError::Failed(format!(
+                "cannot verify the TLS identity of peer {peer_node_id}: {err}"
+            ))
+        })?;
+
+    identity
+        .verify_node_name(&peer_config.name)
+        .map_err(HandshakeError::Failed)
+}
+
 pub async fn wait_for_welcome<S>(
     response_stream: &mut S,
     timeout: Duration,
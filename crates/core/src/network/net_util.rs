@@ -17,11 +17,14 @@ use std::time::Duration;
 
 use http::Uri;
 use hyper::body::{Body, Incoming};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::connect::dns::GaiResolver;
 use hyper_util::rt::TokioIo;
 use hyper_util::server::graceful::GracefulShutdown;
 use tokio::io;
 use tokio::net::UnixStream;
 use tokio::task::JoinHandle;
+use tokio_rustls::TlsAcceptor;
 use tokio_util::either::Either;
 use tonic::transport::{Channel, Endpoint};
 use tracing::{Instrument, Span, debug, error_span, info, instrument, trace};
@@ -33,6 +36,7 @@ use restate_types::net::address::{ListenerPort, PeerNetAddress};
 use restate_types::net::connect_opts::CommonClientConnectionOptions;
 use restate_types::net::listener::Listeners;
 
+use crate::network::tls::{TlsConnector, TlsContext, WithPeerIdentity};
 use crate::{ShutdownError, TaskCenter, TaskKind, cancellation_watcher};
 
 pub enum DNSResolution {
@@ -71,27 +75,39 @@ pub fn create_tonic_channel<
             }))
         }
         PeerNetAddress::Http(_) => {
-            match dns_resolution {
-                DNSResolution::Gai => endpoint.connect_lazy(),
-                DNSResolution::Headless => {
-                    // headless dns names need special consideration:
-                    // 1. We need to ensure all ips are used across retries
-                    // 2. The http connector will split the conn timeout between all resolved addresses, so we don't want too many
-                    let mut http = hyper_util::client::legacy::connect::HttpConnector::new_with_resolver(RandomAddressResolver);
-                    http.enforce_http(false);
-                    http.set_nodelay(endpoint.get_tcp_nodelay());
-                    http.set_keepalive(endpoint.get_tcp_keepalive());
-                    http.set_keepalive_interval(endpoint.get_tcp_keepalive_interval());
-                    http.set_keepalive_retries(endpoint.get_tcp_keepalive_retries());
-                    http.set_connect_timeout(endpoint.get_connect_timeout());
-
+            match (dns_resolution, options.tls()) {
+                (DNSResolution::Gai, None) => endpoint.connect_lazy(),
+                (DNSResolution::Gai, Some(tls)) => {
+                    let http = http_connector(&endpoint, GaiResolver::new());
+                    endpoint.connect_with_connector_lazy(TlsConnector::new(http, tls.clone()))
+                }
+                // headless dns names need special consideration:
+                // 1. We need to ensure all ips are used across retries
+                // 2. The http connector will split the conn timeout between all resolved addresses, so we don't want too many
+                (DNSResolution::Headless, None) => {
+                    let http = http_connector(&endpoint, RandomAddressResolver);
                     endpoint.connect_with_connector_lazy(http)
-                },
+                }
+                (DNSResolution::Headless, Some(tls)) => {
+                    let http = http_connector(&endpoint, RandomAddressResolver);
+                    endpoint.connect_with_connector_lazy(TlsConnector::new(http, tls.clone()))
+                }
             }
         }
     }
 }
 
+pub(crate) fn http_connector<R>(endpoint: &Endpoint, resolver: R) -> HttpConnector<R> {
+    let mut http = HttpConnector::new_with_resolver(resolver);
+    http.enforce_http(false);
+    http.set_nodelay(endpoint.get_tcp_nodelay());
+    http.set_keepalive(endpoint.get_tcp_keepalive());
+    http.set_keepalive_interval(endpoint.get_tcp_keepalive_interval());
+    http.set_keepalive_retries(endpoint.get_tcp_keepalive_retries());
+    http.set_connect_timeout(endpoint.get_connect_timeout());
+    http
+}
+
 fn apply_options<T: CommonClientConnectionOptions + Send + Sync + ?Sized>(
     endpoint: Endpoint,
     options: &T,
@@ -128,6 +144,7 @@ pub enum Error {
 pub async fn run_hyper_server<P: ListenerPort, S, B>(
     listeners: Listeners<P>,
     service: S,
+    tls: Option<Arc<TlsContext>>,
     on_stop: impl Fn(),
 ) -> Result<(), Error>
 where
@@ -150,8 +167,8 @@ where
         Span::current().record("server.port", socket_addr.port());
     }
 
-    info!("Server listening");
-    run_listener_loop(listeners, service, P::NAME).await?;
+    info!(tls = tls.is_some(), "Server listening");
+    run_listener_loop(listeners, service, tls, P::NAME).await?;
     on_stop();
 
     info!("Stopped listening");
@@ -162,6 +179,7 @@ where
 async fn run_listener_loop<P: ListenerPort, S, B>(
     mut listeners: Listeners<P>,
     service: S,
+    tls: Option<Arc<TlsContext>>,
     server_name: &'static str,
 ) -> Result<(), Error>
 where
@@ -203,47 +221,53 @@ where
                     .keep_alive_interval(Some(network_options.http2_keep_alive_interval.into()))
                     .keep_alive_timeout(network_options.http2_keep_alive_timeout.into());
 
-                match stream {
-                    Either::Left(tcp_stream) => {
+                match (stream, &tls) {
+                    (Either::Left(tcp_stream), Some(tls)) => {
+                        // TCP SOCKET (TLS)
+                        let acceptor = TlsAcceptor::from(tls.server_config());
+                        let handshake_timeout: Duration = network_options.handshake_timeout.into();
+                        let watcher = graceful_shutdown.watcher();
+                        let service = service.clone();
+                        TaskCenter::spawn(TaskKind::SocketHandler, task_name.clone(), async move {
+                            let tls_stream = match tokio::time::timeout(handshake_timeout, acceptor.accept(tcp_stream)).await {
+                                Ok(Ok(tls_stream)) => tls_stream,
+                                Ok(Err(e)) => {
+                                    debug!("TLS handshake failed: {e}");
+                                    return Ok(());
+                                }
+                                Err(_) => {
+                                    debug!("TLS handshake timed out");
+                                    return Ok(());
+                                }
+                            };
+                            let service = WithPeerIdentity::new(service, tls_stream.get_ref().1);
+                            let io = TokioIo::new(tls_stream);
+                            let connection = watcher.watch(builder.serve_connection(io, service).into_owned());
+                            trace!("New tls connection accepted");
+                            log_connection_outcome(connection.await);
+                            Ok(())
+                        }.instrument(socket_span))?;
+                    },
+                    (Either::Left(tcp_stream), None) => {
                         // TCP SOCKET
                         let io = TokioIo::new(tcp_stream);
                         let connection = graceful_shutdown.watch(builder
                             .serve_connection(io, service.clone()).into_owned());
                         TaskCenter::spawn(TaskKind::SocketHandler, task_name.clone(), async move {
                             trace!("New tcp connection accepted");
-                            if let Err(e) = connection.await {
-                                if let Some(hyper_error) = e.downcast_ref::<hyper::Error>() {
-                                    if hyper_error.is_incomplete_message() {
-                                        debug!("Connection closed before request completed");
-                                    }
-                                } else {
-                                    debug!("Connection terminated due to error: {e}");
-                                }
-                            } else {
-                                trace!("Connection completed cleanly");
-                            }
+                            log_connection_outcome(connection.await);
                             Ok(())
                         }.instrument(socket_span))?;
 
                     },
-                    Either::Right(unix_stream) => {
+                    (Either::Right(unix_stream), _) => {
                         // UNIX SOCKET
                         let io = TokioIo::new(unix_stream);
                         let connection = graceful_shutdown.watch(builder
                             .serve_connection(io, service.clone()).into_owned());
                         TaskCenter::spawn(TaskKind::SocketHandler, task_name.clone(), async move {
                             trace!("New uds connection accepted");
-                            if let Err(e) = connection.await {
-                                if let Some(hyper_error) = e.downcast_ref::<hyper::Error>() {
-                                    if hyper_error.is_incomplete_message() {
-                                        debug!("Connection closed before request completed");
-                                    }
-                                } else {
-                                    debug!("Connection terminated due to error: {e}");
-                                }
-                            } else {
-                                trace!("Connection completed cleanly");
-                            }
+                            log_connection_outcome(connection.await);
                             Ok(())
                         }.instrument(socket_span))?;
                     }
@@ -266,6 +290,20 @@ where
     Ok(())
 }
 
+fn log_connection_outcome(result: Result<(), GenericError>) {
+    if let Err(e) = result {
+        if let Some(hyper_error) = e.downcast_ref::<hyper::Error>() {
+            if hyper_error.is_incomplete_message() {
+                debug!("Connection closed before request completed");
+            }
+        } else {
+            debug!("Connection terminated due to error: {e}");
+        }
+    } else {
+        trace!("Connection completed cleanly");
+    }
+}
+
 #[derive(Clone, Default)]
 struct TaskCenterExecutor;
 
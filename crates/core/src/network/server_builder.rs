@@ -16,12 +16,14 @@ use tonic::service::Routes;
 use tower_http::trace::{DefaultOnFailure, TraceLayer};
 use tracing::{Level, debug};
 
+use restate_types::config::Configuration;
 use restate_types::health::HealthStatus;
 use restate_types::net::address::FabricPort;
 use restate_types::net::listener::{AddressBook, Listeners};
 use restate_types::protobuf::common::NodeRpcStatus;
 
 use super::net_util::run_hyper_server;
+use super::tls::TlsContext;
 
 pub struct NetworkServerBuilder {
     grpc_descriptors: Vec<&'static [u8]>,
@@ -78,6 +80,13 @@ impl NetworkServerBuilder {
     ) -> Result<(), anyhow::Error> {
         node_rpc_health.update(NodeRpcStatus::StartingUp);
 
+        let tls = Configuration::pinned()
+            .networking
+            .tls
+            .as_ref()
+            .map(TlsContext::get_or_load)
+            .transpose()?;
+
         // Trace layer for HTTP requests
         let http_span_factory = tower_http::trace::DefaultMakeSpan::new()
             .include_headers(true)
@@ -115,7 +124,7 @@ impl NetworkServerBuilder {
 
         node_rpc_health.update(NodeRpcStatus::Ready);
 
-        run_hyper_server(self.listeners, service, || {
+        run_hyper_server(self.listeners, service, tls, || {
             node_rpc_health.update(NodeRpcStatus::Stopping)
         })
         .await?;
@@ -0,0 +1,516 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Mutual TLS for the node-to-node message fabric and TLS for the HTTP listeners.
+//!
+//! Certificate material is loaded from the files configured in [`FabricTlsOptions`] (or
+//! [`ListenerTlsOptions`]) and shared by all connections using the same files. The files are checked for modifications at most once
+//! per `reload-interval`, when a connection is established, so rotated certificates are picked
+//! up by new connections without restarting the process.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime};
+
+use arc_swap::ArcSwap;
+use futures::future::BoxFuture;
+use http::Uri;
+use hyper_util::rt::TokioIo;
+use parking_lot::Mutex;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{ClientConfig, RootCertStore, ServerConfig, SupportedProtocolVersion};
+use tokio::net::TcpStream;
+use tracing::{info, warn};
+
+use restate_types::config::{FabricTlsOptions, ListenerTlsOptions, TlsVersion};
+use restate_types::errors::GenericError;
+
+static CONTEXTS: LazyLock<Mutex<HashMap<TlsSettings, Arc<TlsContext>>>> =
+    LazyLock::new(Default::default);
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    #[error("cannot read '{}': {source}", .path.display())]
+    Pem {
+        path: PathBuf,
+        #[source]
+        source: rustls::pki_types::pem::Error,
+    },
+    #[error("no certificates found in '{}'", .0.display())]
+    NoCertificates(PathBuf),
+    #[error(transparent)]
+    Rustls(#[from] rustls::Error),
+    #[error(transparent)]
+    ClientVerifier(#[from] rustls::server::VerifierBuilderError),
+}
+
+/// The certificate a peer presented during the TLS handshake.
+///
+/// It is attached as a request extension to every request received over a TLS connection and
+/// used to verify that the peer is the node it claims to be in the fabric handshake.
+#[derive(Debug, Clone)]
+pub struct PeerIdentity {
+    certificate: Arc<CertificateDer<'static>>,
+}
+
+impl PeerIdentity {
+    pub fn new(certificate: CertificateDer<'static>) -> Self {
+        Self {
+            certificate: Arc::new(certificate),
+        }
+    }
+
+    fn from_connection(connection: &rustls::ServerConnection) -> Option<Self> {
+        connection
+            .peer_certificates()
+            .and_then(|chain| chain.first())
+            .map(|certificate| Self::new(certificate.clone()))
+    }
+
+    /// Returns an error if the peer certificate is not valid for the given node name.
+    pub fn verify_node_name(&self, node_name: &str) -> Result<(), String> {
+        let server_name = ServerName::try_from(node_name)
+            .map_err(|_| format!("node name '{node_name}' is not a valid DNS name"))?;
+        let certificate = webpki::EndEntityCert::try_from(self.certificate.as_ref())
+            .map_err(|err| format!("cannot parse peer certificate: {err}"))?;
+        certificate
+            .verify_is_valid_for_subject_name(&server_name)
+            .map_err(|err| format!("peer certificate is not valid for node '{node_name}': {err}"))
+    }
+}
+
+struct TlsConfigs {
+    server: Arc<ServerConfig>,
+    client: Option<Arc<ClientConfig>>,
+}
+
+/// The certificate material a [`TlsContext`] is built from.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum TlsSettings {
+    Fabric(FabricTlsOptions),
+    Listener(ListenerTlsOptions),
+}
+
+impl TlsSettings {
+    fn files(&self) -> Vec<&Path> {
+        match self {
+            TlsSettings::Fabric(options) => {
+                vec![&options.ca_cert_path, &options.cert_path, &options.key_path]
+            }
+            TlsSettings::Listener(options) => [&options.cert_path, &options.key_path]
+                .into_iter()
+                .chain(&options.client_ca_cert_path)
+                .map(PathBuf::as_path)
+                .collect(),
+        }
+    }
+
+    fn cert_path(&self) -> &Path {
+        match self {
+            TlsSettings::Fabric(options) => &options.cert_path,
+            TlsSettings::Listener(options) => &options.cert_path,
+        }
+    }
+
+    fn reload_interval(&self) -> Duration {
+        match self {
+            TlsSettings::Fabric(options) => *options.reload_interval,
+            TlsSettings::Listener(options) => *options.reload_interval,
+        }
+    }
+
+    fn modification_times(&self) -> Vec<Option<SystemTime>> {
+        self.files()
+            .into_iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+
+    fn load(&self) -> Result<TlsConfigs, TlsError> {
+        match self {
+            TlsSettings::Fabric(options) => load_fabric_configs(options),
+            TlsSettings::Listener(options) => load_listener_configs(options),
+        }
+    }
+}
+
+struct ReloadState {
+    last_checked: Instant,
+    modified: Vec<Option<SystemTime>>,
+}
+
+/// TLS configurations built from certificate files that are reloaded when they change.
+pub struct TlsContext {
+    settings: TlsSettings,
+    configs: ArcSwap<TlsConfigs>,
+    reload_state: Mutex<ReloadState>,
+}
+
+impl TlsContext {
+    /// Returns the fabric context for the given options, loading the certificate material on
+    /// first use.
+    pub fn get_or_load(options: &FabricTlsOptions) -> Result<Arc<TlsContext>, TlsError> {
+        Self::get_or_load_settings(TlsSettings::Fabric(options.clone()))
+    }
+
+    /// Returns the context of an HTTP listener (ingress or admin API) for the given options,
+    /// loading the certificate material on first use.
+    pub fn for_listener(options: &ListenerTlsOptions) -> Result<Arc<TlsContext>, TlsError> {
+        Self::get_or_load_settings(TlsSettings::Listener(options.clone()))
+    }
+
+    fn get_or_load_settings(settings: TlsSettings) -> Result<Arc<TlsContext>, TlsError> {
+        let mut contexts = CONTEXTS.lock();
+        if let Some(context) = contexts.get(&settings) {
+            return Ok(Arc::clone(context));
+        }
+
+        let context = Arc::new(TlsContext {
+            reload_state: Mutex::new(ReloadState {
+                last_checked: Instant::now(),
+                modified: settings.modification_times(),
+            }),
+            configs: ArcSwap::from_pointee(settings.load()?),
+            settings: settings.clone(),
+        });
+        contexts.insert(settings, Arc::clone(&context));
+        Ok(context)
+    }
+
+    /// Configuration for accepting connections. For the fabric, clients must present a
+    /// certificate signed by the cluster CA.
+    pub fn server_config(&self) -> Arc<ServerConfig> {
+        self.maybe_reload();
+        Arc::clone(&self.configs.load().server)
+    }
+
+    /// Configuration for connecting to peers, presenting this node's certificate. Only fabric
+    /// contexts have a client configuration.
+    pub fn client_config(&self) -> Option<Arc<ClientConfig>> {
+        self.maybe_reload();
+        self.configs.load().client.clone()
+    }
+
+    /// Performs the server side of the TLS handshake on an accepted TCP connection.
+    pub async fn accept(
+        &self,
+        stream: TcpStream,
+    ) -> std::io::Result<tokio_rustls::server::TlsStream<TcpStream>> {
+        tokio_rustls::TlsAcceptor::from(self.server_config())
+            .accept(stream)
+            .await
+    }
+
+    fn maybe_reload(&self) {
+        let mut state = self.reload_state.lock();
+        if state.last_checked.elapsed() < self.settings.reload_interval() {
+            return;
+        }
+        state.last_checked = Instant::now();
+
+        let modified = self.settings.modification_times();
+        if modified == state.modified {
+            return;
+        }
+
+        match self.settings.load() {
+            Ok(configs) => {
+                info!(
+                    cert = %self.settings.cert_path().display(),
+                    "Reloaded TLS certificates"
+                );
+                state.modified = modified;
+                self.configs.store(Arc::new(configs));
+            }
+            Err(err) => {
+                // keep the previous certificates, the files might be in the middle of being
+                // replaced. We'll retry on the next check.
+                warn!(%err, "Failed to reload TLS certificates, keeping the previous ones");
+            }
+        }
+    }
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let pem_error = |source| TlsError::Pem {
+        path: path.to_owned(),
+        source,
+    };
+    let certificates = CertificateDer::pem_file_iter(path)
+        .map_err(pem_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(pem_error)?;
+    if certificates.is_empty() {
+        return Err(TlsError::NoCertificates(path.to_owned()));
+    }
+    Ok(certificates)
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, TlsError> {
+    PrivateKeyDer::from_pem_file(path).map_err(|source| TlsError::Pem {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn load_roots(path: &Path) -> Result<Arc<RootCertStore>, TlsError> {
+    let mut roots = RootCertStore::empty();
+    for certificate in load_certificates(path)? {
+        roots.add(certificate)?;
+    }
+    Ok(Arc::new(roots))
+}
+
+fn load_fabric_configs(options: &FabricTlsOptions) -> Result<TlsConfigs, TlsError> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let roots = load_roots(&options.ca_cert_path)?;
+    let cert_chain = load_certificates(&options.cert_path)?;
+    let key = load_private_key(&options.key_path)?;
+
+    let client_verifier =
+        WebPkiClientVerifier::builder_with_provider(Arc::clone(&roots), Arc::clone(&provider))
+            .build()?;
+    let mut server = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_protocol_versions(rustls::DEFAULT_VERSIONS)?
+        .with_client_cert_verifier(client_verifier)
+        .with_single_cert(cert_chain.clone(), key.clone_key())?;
+    server.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    let mut client = ClientConfig::builder_with_provider(provider)
+        .with_protocol_versions(rustls::DEFAULT_VERSIONS)?
+        .with_root_certificates(roots)
+        .with_client_auth_cert(cert_chain, key)?;
+    client.alpn_protocols = vec![b"h2".to_vec()];
+
+    Ok(TlsConfigs {
+        server: Arc::new(server),
+        client: Some(Arc::new(client)),
+    })
+}
+
+fn load_listener_configs(options: &ListenerTlsOptions) -> Result<TlsConfigs, TlsError> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let versions: &[&SupportedProtocolVersion] = match options.min_version {
+        TlsVersion::Tls12 => rustls::DEFAULT_VERSIONS,
+        TlsVersion::Tls13 => &[&rustls::version::TLS13],
+    };
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_protocol_versions(versions)?;
+    let builder = match &options.client_ca_cert_path {
+        Some(client_ca_cert_path) => builder.with_client_cert_verifier(
+            WebPkiClientVerifier::builder_with_provider(load_roots(client_ca_cert_path)?, provider)
+                .build()?,
+        ),
+        None => builder.with_no_client_auth(),
+    };
+    let mut server = builder.with_single_cert(
+        load_certificates(&options.cert_path)?,
+        load_private_key(&options.key_path)?,
+    )?;
+    server.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsConfigs {
+        server: Arc::new(server),
+        client: None,
+    })
+}
+
+/// Wraps a TCP connector and performs a TLS handshake on top of the established connection.
+///
+/// The certificate material is resolved when connecting, so misconfigured TLS surfaces as a
+/// connection error on the channel rather than a panic when creating it.
+#[derive(Clone)]
+pub struct TlsConnector<C> {
+    inner: C,
+    options: FabricTlsOptions,
+}
+
+impl<C> TlsConnector<C> {
+    pub fn new(inner: C, options: FabricTlsOptions) -> Self {
+        Self { inner, options }
+    }
+}
+
+impl<C> tower::Service<Uri> for TlsConnector<C>
+where
+    C: tower::Service<Uri, Response = TokioIo<TcpStream>>,
+    C::Error: Into<GenericError>,
+    C::Future: Send + 'static,
+{
+    type Response = TokioIo<tokio_rustls::client::TlsStream<TcpStream>>;
+    type Error = GenericError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let server_name = uri.host().map(|host| {
+            host.trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_owned()
+        });
+        let connect = self.inner.call(uri);
+        let options = self.options.clone();
+
+        Box::pin(async move {
+            let server_name = ServerName::try_from(server_name.ok_or("uri has no host")?)?;
+            let context = TlsContext::get_or_load(&options)?;
+            let tcp_stream = connect.await.map_err(Into::into)?.into_inner();
+            let client_config = context
+                .client_config()
+                .ok_or("fabric TLS context has no client configuration")?;
+            let tls_stream = tokio_rustls::TlsConnector::from(client_config)
+                .connect(server_name, tcp_stream)
+                .await?;
+            Ok(TokioIo::new(tls_stream))
+        })
+    }
+}
+
+/// Hyper service that attaches the [`PeerIdentity`] of a TLS connection to every request.
+#[derive(Clone)]
+pub(crate) struct WithPeerIdentity<S> {
+    inner: S,
+    identity: Option<PeerIdentity>,
+}
+
+impl<S> WithPeerIdentity<S> {
+    pub(crate) fn new(inner: S, connection: &rustls::ServerConnection) -> Self {
+        Self {
+            inner,
+            identity: PeerIdentity::from_connection(connection),
+        }
+    }
+}
+
+impl<S, B> hyper::service::Service<http::Request<B>> for WithPeerIdentity<S>
+where
+    S: hyper::service::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn call(&self, mut request: http::Request<B>) -> Self::Future {
+        if let Some(identity) = &self.identity {
+            request.extensions_mut().insert(identity.clone());
+        }
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rcgen::{
+        BasicConstraints, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+        KeyUsagePurpose,
+    };
+
+    fn write_certificates(dir: &Path, node_name: &str) -> FabricTlsOptions {
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let ca_key = KeyPair::generate().unwrap();
+        let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+
+        let mut params =
+            CertificateParams::new(vec![node_name.to_owned(), "localhost".to_owned()]).unwrap();
+        params.extended_key_usages = vec![
+            ExtendedKeyUsagePurpose::ServerAuth,
+            ExtendedKeyUsagePurpose::ClientAuth,
+        ];
+        let key = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key, &ca_cert, &ca_key).unwrap();
+
+        let options = FabricTlsOptions::new(
+            dir.join("ca.pem"),
+            dir.join(format!("{node_name}.pem")),
+            dir.join(format!("{node_name}.key")),
+        );
+        std::fs::write(&options.ca_cert_path, ca_cert.pem()).unwrap();
+        std::fs::write(&options.cert_path, cert.pem()).unwrap();
+        std::fs::write(&options.key_path, key.serialize_pem()).unwrap();
+        options
+    }
+
+    #[test]
+    fn peer_identity_matches_node_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = write_certificates(dir.path(), "node-1");
+
+        let chain = load_certificates(&options.cert_path).unwrap();
+        let identity = PeerIdentity::new(chain[0].clone());
+
+        assert!(identity.verify_node_name("node-1").is_ok());
+        assert!(identity.verify_node_name("node-2").is_err());
+    }
+
+    #[test]
+    fn load_and_share_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = write_certificates(dir.path(), "node-1");
+
+        let context = TlsContext::get_or_load(&options).unwrap();
+        assert_eq!(
+            context.server_config().alpn_protocols,
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+        assert_eq!(
+            context.client_config().unwrap().alpn_protocols,
+            vec![b"h2".to_vec()]
+        );
+
+        // same files resolve to the same context
+        let other = TlsContext::get_or_load(&options).unwrap();
+        assert!(Arc::ptr_eq(&context, &other));
+    }
+
+    #[test]
+    fn missing_files_fail_to_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = FabricTlsOptions::new(
+            dir.path().join("ca.pem"),
+            dir.path().join("node.pem"),
+            dir.path().join("node.key"),
+        );
+
+        assert!(matches!(
+            TlsContext::get_or_load(&options),
+            Err(TlsError::Pem { .. })
+        ));
+    }
+
+    #[test]
+    fn listener_context_without_client_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let fabric = write_certificates(dir.path(), "node-1");
+
+        let mut options = ListenerTlsOptions::new(fabric.cert_path, fabric.key_path);
+        options.min_version = TlsVersion::Tls13;
+        options.client_ca_cert_path = Some(fabric.ca_cert_path);
+
+        let context = TlsContext::for_listener(&options).unwrap();
+        assert!(context.client_config().is_none());
+        assert_eq!(
+            context.server_config().alpn_protocols,
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+
+use crate::KeyId;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("data is encrypted but encryption at rest is not configured")]
+    NotConfigured,
+    #[error("encryption key {0} is not present in the key ring")]
+    UnknownKey(KeyId),
+    #[error("invalid key material for key {0}, expected a 256-bit key")]
+    InvalidKey(KeyId),
+    #[error("malformed encrypted data: {0}")]
+    Malformed(&'static str),
+    #[error(
+        "failed to authenticate encrypted data; the data is corrupt or was not encrypted with this key"
+    )]
+    Authentication,
+    #[error("failed to generate a random nonce")]
+    Random,
+    #[error("invalid key file '{}': {reason}", path.display())]
+    KeyFile { path: PathBuf, reason: String },
+    #[error("failed to unwrap key {key_id} via KMS: {reason}")]
+    Kms { key_id: KeyId, reason: String },
+    #[error("encryption at rest can't be {0}d while the node is running, restart the node instead")]
+    RestartRequired(&'static str),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
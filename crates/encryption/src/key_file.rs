@@ -0,0 +1,235 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::Path;
+use std::process::Stdio;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::{EncryptionError, KeyId, KeyRing};
+
+/// Unwraps data keys that are stored encrypted by a key management service.
+#[async_trait]
+pub trait KmsClient: Send + Sync {
+    async fn unwrap_key(
+        &self,
+        key_id: KeyId,
+        kms_key_id: Option<&str>,
+        wrapped_key: &[u8],
+    ) -> Result<Vec<u8>, EncryptionError>;
+}
+
+/// [`KmsClient`] that delegates unwrapping to an external command, e.g. a cloud provider CLI.
+///
+/// The wrapped key is written to the command's stdin and the unwrapped key is read from its
+/// stdout.
+#[derive(Debug, Clone)]
+pub struct CommandKms {
+    command: Vec<String>,
+    output_base64: bool,
+}
+
+impl CommandKms {
+    pub fn new(command: Vec<String>, output_base64: bool) -> Self {
+        Self {
+            command,
+            output_base64,
+        }
+    }
+}
+
+#[async_trait]
+impl KmsClient for CommandKms {
+    async fn unwrap_key(
+        &self,
+        key_id: KeyId,
+        kms_key_id: Option<&str>,
+        wrapped_key: &[u8],
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let kms_error = |reason: String| EncryptionError::Kms { key_id, reason };
+
+        let Some((program, args)) = self.command.split_first() else {
+            return Err(kms_error("no unwrap command configured".to_owned()));
+        };
+
+        let mut command = tokio::process::Command::new(program);
+        command
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(kms_key_id) = kms_key_id {
+            command.env("RESTATE_KMS_KEY_ID", kms_key_id);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|err| kms_error(format!("failed to spawn '{program}': {err}")))?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin.write_all(wrapped_key).await?;
+        drop(stdin);
+
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(kms_error(format!(
+                "'{program}' exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        if self.output_base64 {
+            STANDARD
+                .decode(String::from_utf8_lossy(&output.stdout).trim())
+                .map_err(|err| kms_error(format!("output is not valid base64: {err}")))
+        } else {
+            Ok(output.stdout)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct KeyFile {
+    active_key: u32,
+    keys: Vec<KeyEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct KeyEntry {
+    id: u32,
+    /// Base64 encoded raw data key
+    #[serde(default)]
+    key: Option<String>,
+    /// Base64 encoded data key, encrypted by the KMS
+    #[serde(default)]
+    wrapped_key: Option<String>,
+    /// Identifier of the KMS master key that wrapped this data key
+    #[serde(default)]
+    kms_key_id: Option<String>,
+}
+
+/// Reads the key file at `path` and builds a [`KeyRing`] from it. Wrapped keys are unwrapped
+/// with `kms`, which is required if the file contains any wrapped keys.
+pub async fn load_key_ring(
+    path: &Path,
+    kms: Option<&dyn KmsClient>,
+) -> Result<KeyRing, EncryptionError> {
+    let key_file_error = |reason: String| EncryptionError::KeyFile {
+        path: path.to_owned(),
+        reason,
+    };
+
+    let content = tokio::fs::read_to_string(path).await?;
+    let key_file: KeyFile =
+        toml::from_str(&content).map_err(|err| key_file_error(err.to_string()))?;
+
+    let mut keys = Vec::with_capacity(key_file.keys.len());
+    for entry in key_file.keys {
+        let key_id = KeyId(entry.id);
+        if keys.iter().any(|(id, _)| *id == key_id) {
+            return Err(key_file_error(format!("duplicate key id {}", entry.id)));
+        }
+
+        let material = match (entry.key, entry.wrapped_key) {
+            (Some(key), None) => STANDARD
+                .decode(key.trim())
+                .map_err(|err| key_file_error(format!("key {}: {err}", entry.id)))?,
+            (None, Some(wrapped_key)) => {
+                let Some(kms) = kms else {
+                    return Err(key_file_error(format!(
+                        "key {} is wrapped but no KMS is configured",
+                        entry.id
+                    )));
+                };
+                let wrapped_key = STANDARD
+                    .decode(wrapped_key.trim())
+                    .map_err(|err| key_file_error(format!("key {}: {err}", entry.id)))?;
+                kms.unwrap_key(key_id, entry.kms_key_id.as_deref(), &wrapped_key)
+                    .await?
+            }
+            _ => {
+                return Err(key_file_error(format!(
+                    "key {} must set exactly one of 'key' or 'wrapped-key'",
+                    entry.id
+                )));
+            }
+        };
+        keys.push((key_id, material));
+    }
+
+    KeyRing::new(KeyId(key_file.active_key), keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    struct XorKms;
+
+    #[async_trait]
+    impl KmsClient for XorKms {
+        async fn unwrap_key(
+            &self,
+            _key_id: KeyId,
+            kms_key_id: Option<&str>,
+            wrapped_key: &[u8],
+        ) -> Result<Vec<u8>, EncryptionError> {
+            assert_eq!(kms_key_id, Some("master"));
+            Ok(wrapped_key.iter().map(|b| b ^ 0xFF).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn load_plain_and_wrapped_keys() -> Result<(), EncryptionError> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        write!(
+            file,
+            r#"
+active-key = 2
+
+[[keys]]
+id = 1
+key = "{}"
+
+[[keys]]
+id = 2
+wrapped-key = "{}"
+kms-key-id = "master"
+"#,
+            STANDARD.encode([1u8; 32]),
+            STANDARD.encode([0xFEu8; 32]),
+        )?;
+
+        let key_ring = load_key_ring(file.path(), Some(&XorKms)).await?;
+        assert_eq!(key_ring.active_key_id(), KeyId(2));
+        assert!(key_ring.contains(KeyId(1)));
+
+        // the unwrapped key 2 is [1u8; 32]
+        let expected = KeyRing::new(KeyId(2), [(KeyId(2), [1u8; 32])])?;
+        assert_eq!(expected.open(&key_ring.seal(b"data")?)?, b"data");
+
+        // wrapped keys require a KMS
+        assert!(matches!(
+            load_key_ring(file.path(), None).await,
+            Err(EncryptionError::KeyFile { .. })
+        ));
+
+        Ok(())
+    }
+}
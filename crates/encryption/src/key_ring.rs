@@ -0,0 +1,256 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::EncryptionError;
+
+/// Marker at the start of every sealed value, to reject values which were not produced by
+/// [`KeyRing::seal`]. Plaintext can start with the same bytes, so it must not be used to tell
+/// sealed values apart from plaintext ones.
+const MAGIC: [u8; 3] = [0xE5, b'N', b'C'];
+const ENVELOPE_V1: u8 = 1;
+
+/// Length of the authenticated part of the header: magic, version and key id.
+const AAD_LEN: usize = MAGIC.len() + size_of::<u8>() + size_of::<u32>();
+pub(crate) const HEADER_LEN: usize = AAD_LEN + NONCE_LEN;
+pub(crate) const TAG_LEN: usize = 16;
+
+/// Identifier of a data key. Stored alongside every sealed value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, derive_more::Display)]
+#[display("k{_0}")]
+pub struct KeyId(pub u32);
+
+/// A set of AES-256-GCM data keys of which one is used to encrypt new data.
+///
+/// Sealed value layout:
+///    [3 bytes]     Magic `0xE5 'N' 'C'`
+///    [1 byte]      Envelope version
+///    [4 bytes]     Key id (big-endian)
+///    [12 bytes]    Nonce
+///    [remaining]   Ciphertext followed by the 16 byte authentication tag
+///
+/// The magic, version and key id are authenticated as additional data.
+pub struct KeyRing {
+    active: KeyId,
+    keys: HashMap<KeyId, LessSafeKey>,
+    rng: SystemRandom,
+}
+
+impl std::fmt::Debug for KeyRing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never print key material
+        let mut key_ids: Vec<_> = self.keys.keys().collect();
+        key_ids.sort();
+        f.debug_struct("KeyRing")
+            .field("active", &self.active)
+            .field("keys", &key_ids)
+            .finish()
+    }
+}
+
+impl KeyRing {
+    /// Creates a new key ring. `active` must be one of the given keys.
+    pub fn new<K: AsRef<[u8]>>(
+        active: KeyId,
+        keys: impl IntoIterator<Item = (KeyId, K)>,
+    ) -> Result<Self, EncryptionError> {
+        let keys = keys
+            .into_iter()
+            .map(|(id, material)| {
+                UnboundKey::new(&AES_256_GCM, material.as_ref())
+                    .map(|key| (id, LessSafeKey::new(key)))
+                    .map_err(|_| EncryptionError::InvalidKey(id))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        if !keys.contains_key(&active) {
+            return Err(EncryptionError::UnknownKey(active));
+        }
+
+        Ok(Self {
+            active,
+            keys,
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// The key used to encrypt new data.
+    pub fn active_key_id(&self) -> KeyId {
+        self.active
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key_id: KeyId) -> bool {
+        self.keys.contains_key(&key_id)
+    }
+
+    /// Encrypts `plaintext` with the active key.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let key = self.key(self.active)?;
+        let nonce = self.random_nonce()?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + plaintext.len() + TAG_LEN);
+        out.extend_from_slice(&MAGIC);
+        out.push(ENVELOPE_V1);
+        out.extend_from_slice(&self.active.0.to_be_bytes());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(plaintext);
+
+        let (header, in_out) = out.split_at_mut(HEADER_LEN);
+        let tag = key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(&header[..AAD_LEN]),
+                in_out,
+            )
+            .map_err(|_| EncryptionError::Authentication)?;
+        out.extend_from_slice(tag.as_ref());
+
+        Ok(out)
+    }
+
+    /// Decrypts a value produced by [`KeyRing::seal`] with any key of this key ring.
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if !is_sealed(sealed) {
+            return Err(EncryptionError::Malformed("missing envelope header"));
+        }
+        if sealed.len() < HEADER_LEN + TAG_LEN {
+            return Err(EncryptionError::Malformed("value is too short"));
+        }
+
+        let key_id = KeyId(u32::from_be_bytes(
+            sealed[MAGIC.len() + 1..AAD_LEN].try_into().unwrap(),
+        ));
+        let key = self.key(key_id)?;
+        let nonce: [u8; NONCE_LEN] = sealed[AAD_LEN..HEADER_LEN].try_into().unwrap();
+
+        let mut buf = sealed[HEADER_LEN..].to_vec();
+        let plaintext_len = key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(&sealed[..AAD_LEN]),
+                &mut buf,
+            )
+            .map_err(|_| EncryptionError::Authentication)?
+            .len();
+        buf.truncate(plaintext_len);
+
+        Ok(buf)
+    }
+
+    pub(crate) fn key(&self, key_id: KeyId) -> Result<&LessSafeKey, EncryptionError> {
+        self.keys
+            .get(&key_id)
+            .ok_or(EncryptionError::UnknownKey(key_id))
+    }
+
+    pub(crate) fn fill_random(&self, dest: &mut [u8]) -> Result<(), EncryptionError> {
+        self.rng.fill(dest).map_err(|_| EncryptionError::Random)
+    }
+
+    fn random_nonce(&self) -> Result<[u8; NONCE_LEN], EncryptionError> {
+        let mut nonce = [0; NONCE_LEN];
+        self.fill_random(&mut nonce)?;
+        Ok(nonce)
+    }
+}
+
+/// Returns `true` if `data` starts with the envelope header written by [`KeyRing::seal`].
+pub(crate) fn is_sealed(data: &[u8]) -> bool {
+    data.len() > MAGIC.len() && data[..MAGIC.len()] == MAGIC && data[MAGIC.len()] == ENVELOPE_V1
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use super::*;
+
+    fn key_ring(active: u32) -> KeyRing {
+        KeyRing::new(
+            KeyId(active),
+            [(KeyId(1), [1u8; 32]), (KeyId(2), [2u8; 32])],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn seal_open_roundtrip() {
+        let key_ring = key_ring(1);
+        let sealed = key_ring.seal(b"hello world").unwrap();
+
+        assert!(is_sealed(&sealed));
+        assert_that!(sealed.len(), eq(HEADER_LEN + 11 + TAG_LEN));
+        assert_that!(key_ring.open(&sealed).unwrap(), eq(b"hello world".to_vec()));
+
+        // empty values are sealed as well
+        let sealed = key_ring.seal(b"").unwrap();
+        assert_that!(key_ring.open(&sealed).unwrap(), empty());
+    }
+
+    #[test]
+    fn rotated_key_ring_opens_old_values() {
+        let sealed = key_ring(1).seal(b"old").unwrap();
+
+        let rotated = key_ring(2);
+        assert_that!(rotated.open(&sealed).unwrap(), eq(b"old".to_vec()));
+        let sealed = rotated.seal(b"new").unwrap();
+        assert_eq!(&sealed[4..8], &[0, 0, 0, 2]);
+
+        let without_old_key = KeyRing::new(KeyId(2), [(KeyId(2), [2u8; 32])]).unwrap();
+        assert!(matches!(
+            without_old_key.open(&key_ring(1).seal(b"old").unwrap()),
+            Err(EncryptionError::UnknownKey(KeyId(1)))
+        ));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let key_ring = key_ring(1);
+        let mut sealed = key_ring.seal(b"hello world").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0x01;
+        assert!(matches!(
+            key_ring.open(&sealed),
+            Err(EncryptionError::Authentication)
+        ));
+
+        // swapping the key id invalidates the authenticated header
+        let mut sealed = key_ring.seal(b"hello world").unwrap();
+        sealed[7] = 2;
+        assert!(matches!(
+            key_ring.open(&sealed),
+            Err(EncryptionError::Authentication)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        assert!(matches!(
+            KeyRing::new(KeyId(1), [(KeyId(1), [0u8; 16])]),
+            Err(EncryptionError::InvalidKey(KeyId(1)))
+        ));
+        assert!(matches!(
+            KeyRing::new(KeyId(3), [(KeyId(1), [0u8; 32])]),
+            Err(EncryptionError::UnknownKey(KeyId(3)))
+        ));
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Encryption at rest for user data.
+//!
+//! Values are sealed with AES-256-GCM using data keys from a [`KeyRing`]. Each sealed value
+//! carries the id of the key that encrypted it, so that keys can be rotated by adding a new
+//! active key while older keys remain available for decryption. Data keys are either stored
+//! in the key file directly or wrapped by an external KMS ([`KmsClient`]), which makes this an
+//! envelope scheme: the KMS master key never leaves the KMS, and only wrapped data keys are
+//! kept next to the node.
+//!
+//! The process-wide key ring is installed once during node startup via
+//! [`install_from_options`]. On configuration changes, [`reload_from_options`] rotates the keys
+//! if the key configuration changed, but never enables or disables encryption at runtime.
+//! Storage layers call
+//! [`seal`] and [`open`], and record in their own format whether a value is sealed: a sealed
+//! value can't be reliably told apart from a plaintext value by its content.
+
+mod error;
+mod key_file;
+mod key_ring;
+mod stream;
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwapOption;
+use tracing::info;
+
+use restate_types::config::EncryptionOptions;
+
+pub use error::EncryptionError;
+pub use key_file::{CommandKms, KmsClient, load_key_ring};
+pub use key_ring::{KeyId, KeyRing};
+pub use stream::{open_file, seal_file};
+
+static KEY_RING: ArcSwapOption<KeyRing> = ArcSwapOption::const_empty();
+
+/// Key configuration the installed key ring was loaded from.
+static KEY_CONFIG: ArcSwapOption<KeyConfig> = ArcSwapOption::const_empty();
+
+/// Encryption options together with the content of the key file they point to.
+#[derive(Debug, PartialEq, Eq)]
+struct KeyConfig {
+    options: EncryptionOptions,
+    key_file_content: Option<String>,
+}
+
+impl KeyConfig {
+    async fn read(options: &EncryptionOptions) -> Result<Self, EncryptionError> {
+        let key_file_content = match &options.key_file {
+            Some(key_file) => Some(tokio::fs::read_to_string(key_file).await?),
+            None => None,
+        };
+
+        Ok(Self {
+            options: options.clone(),
+            key_file_content,
+        })
+    }
+}
+
+/// Installs (or removes, if `None`) the process-wide key ring.
+pub fn install_key_ring(key_ring: Option<Arc<KeyRing>>) {
+    KEY_RING.store(key_ring);
+}
+
+/// Returns the process-wide key ring if encryption at rest is enabled.
+pub fn key_ring() -> Option<Arc<KeyRing>> {
+    KEY_RING.load_full()
+}
+
+/// Whether newly written data is encrypted.
+pub fn is_enabled() -> bool {
+    KEY_RING.load().is_some()
+}
+
+/// Seals `plaintext` with the active key of the process-wide key ring. Returns `None` if
+/// encryption at rest is disabled, in which case the plaintext is stored as-is.
+pub fn seal(plaintext: &[u8]) -> Result<Option<Vec<u8>>, EncryptionError> {
+    KEY_RING
+        .load()
+        .as_ref()
+        .map(|key_ring| key_ring.seal(plaintext))
+        .transpose()
+}
+
+/// Opens a value previously produced by [`seal`]. The caller must know that the value is
+/// sealed, plaintext values fail to open.
+pub fn open(sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    match KEY_RING.load().as_ref() {
+        Some(key_ring) => key_ring.open(sealed),
+        None => Err(EncryptionError::NotConfigured),
+    }
+}
+
+/// Loads the key ring described by `options` and installs it as the process-wide key ring.
+/// Must be called once on node startup, before any storage is opened.
+///
+/// If `options` does not configure a key file, encryption at rest is disabled.
+pub async fn install_from_options(options: &EncryptionOptions) -> Result<(), EncryptionError> {
+    install(KeyConfig::read(options).await?).await
+}
+
+/// Reloads the process-wide key ring after a configuration change, if the encryption options or
+/// the content of the key file changed.
+///
+/// Encryption at rest can't be enabled or disabled at runtime, since the storage layers decide on
+/// startup how they store values. Such changes are rejected and require a restart.
+pub async fn reload_from_options(options: &EncryptionOptions) -> Result<(), EncryptionError> {
+    let key_config = KeyConfig::read(options).await?;
+    if KEY_CONFIG.load().as_deref() == Some(&key_config) {
+        return Ok(());
+    }
+
+    match (is_enabled(), options.is_enabled()) {
+        (true, false) => Err(EncryptionError::RestartRequired("disable")),
+        (false, true) => Err(EncryptionError::RestartRequired("enable")),
+        (false, false) => {
+            KEY_CONFIG.store(Some(Arc::new(key_config)));
+            Ok(())
+        }
+        (true, true) => install(key_config).await,
+    }
+}
+
+async fn install(key_config: KeyConfig) -> Result<(), EncryptionError> {
+    let options = &key_config.options;
+    let Some(key_file) = &options.key_file else {
+        install_key_ring(None);
+        KEY_CONFIG.store(Some(Arc::new(key_config)));
+        return Ok(());
+    };
+
+    let kms = (!options.kms_unwrap_command.is_empty()).then(|| {
+        CommandKms::new(
+            options.kms_unwrap_command.clone(),
+            options.kms_output_base64,
+        )
+    });
+
+    let key_ring = load_key_ring(key_file, kms.as_ref().map(|kms| kms as &dyn KmsClient)).await?;
+    info!(
+        active_key = %key_ring.active_key_id(),
+        num_keys = key_ring.len(),
+        "Loaded encryption at rest key ring from {}",
+        key_file.display()
+    );
+    install_key_ring(Some(Arc::new(key_ring)));
+    KEY_CONFIG.store(Some(Arc::new(key_config)));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+
+    use super::*;
+
+    fn write_key_file(file: &mut tempfile::NamedTempFile, active_key: u32) -> std::io::Result<()> {
+        let file = file.as_file_mut();
+        file.set_len(0)?;
+        std::io::Seek::rewind(file)?;
+        write!(file, "active-key = {active_key}\n")?;
+        for id in 1..=active_key {
+            write!(
+                file,
+                "[[keys]]\nid = {id}\nkey = \"{}\"\n",
+                STANDARD.encode([id as u8; 32])
+            )?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reload_rotates_keys_but_never_toggles_encryption() -> Result<(), EncryptionError> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        write_key_file(&mut file, 1)?;
+        let enabled = EncryptionOptions {
+            key_file: Some(file.path().to_owned()),
+            ..EncryptionOptions::default()
+        };
+        let disabled = EncryptionOptions::default();
+
+        // encryption can't be enabled at runtime
+        install_from_options(&disabled).await?;
+        reload_from_options(&disabled).await?;
+        assert!(matches!(
+            reload_from_options(&enabled).await,
+            Err(EncryptionError::RestartRequired(_))
+        ));
+        assert!(!is_enabled());
+
+        install_from_options(&enabled).await?;
+        assert_eq!(key_ring().unwrap().active_key_id(), KeyId(1));
+
+        // unchanged configuration keeps the installed key ring
+        let installed = key_ring().unwrap();
+        reload_from_options(&enabled).await?;
+        assert!(Arc::ptr_eq(&installed, &key_ring().unwrap()));
+
+        // a changed key file rotates the keys
+        write_key_file(&mut file, 2)?;
+        reload_from_options(&enabled).await?;
+        assert_eq!(key_ring().unwrap().active_key_id(), KeyId(2));
+
+        // encryption can't be disabled at runtime
+        assert!(matches!(
+            reload_from_options(&disabled).await,
+            Err(EncryptionError::RestartRequired(_))
+        ));
+        assert_eq!(key_ring().unwrap().active_key_id(), KeyId(2));
+
+        Ok(())
+    }
+}
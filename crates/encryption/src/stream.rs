@@ -0,0 +1,252 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Chunked encryption of files, used for snapshot archives which are too large to be sealed as a
+//! single value.
+//!
+//! File layout:
+//!    [4 bytes]      Magic `0xE5 'N' 'C' 'F'`
+//!    [1 byte]       Format version
+//!    [4 bytes]      Key id (big-endian)
+//!    [8 bytes]      Random nonce prefix
+//!    [...]          Chunks, each made of
+//!        [4 bytes]  Length of the sealed chunk (big-endian)
+//!        [...]      Ciphertext followed by the 16 byte authentication tag
+//!
+//! The nonce of a chunk is the nonce prefix followed by the chunk index (big-endian u32). The
+//! file header and a flag marking the last chunk are authenticated with every chunk, so that
+//! reordered, truncated or extended files are rejected.
+
+use std::path::Path;
+
+use ring::aead::{Aad, NONCE_LEN, Nonce};
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufWriter};
+
+use crate::key_ring::TAG_LEN;
+use crate::{EncryptionError, KeyId, KeyRing};
+
+const FILE_MAGIC: [u8; 4] = [0xE5, b'N', b'C', b'F'];
+const FILE_V1: u8 = 1;
+const NONCE_PREFIX_LEN: usize = NONCE_LEN - size_of::<u32>();
+const FILE_HEADER_LEN: usize = FILE_MAGIC.len() + 1 + size_of::<u32>() + NONCE_PREFIX_LEN;
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Encrypts the file at `src` with the active key of `key_ring` and writes the result to `dst`.
+/// Returns the size of the encrypted file.
+pub async fn seal_file(key_ring: &KeyRing, src: &Path, dst: &Path) -> Result<u64, EncryptionError> {
+    let key_id = key_ring.active_key_id();
+    let key = key_ring.key(key_id)?;
+    let mut nonce_prefix = [0; NONCE_PREFIX_LEN];
+    key_ring.fill_random(&mut nonce_prefix)?;
+
+    let mut header = Vec::with_capacity(FILE_HEADER_LEN);
+    header.extend_from_slice(&FILE_MAGIC);
+    header.push(FILE_V1);
+    header.extend_from_slice(&key_id.0.to_be_bytes());
+    header.extend_from_slice(&nonce_prefix);
+
+    let mut reader = File::open(src).await?;
+    let mut writer = BufWriter::new(File::create(dst).await?);
+    writer.write_all(&header).await?;
+    let mut written = header.len() as u64;
+
+    // A chunk shorter than the chunk size marks the end of the file. If the plaintext size is a
+    // multiple of the chunk size, an empty last chunk is written.
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE + TAG_LEN);
+    for index in 0u32.. {
+        read_chunk(&mut reader, &mut chunk).await?;
+        let is_last = chunk.len() < CHUNK_SIZE;
+
+        let tag = key
+            .seal_in_place_separate_tag(
+                chunk_nonce(&nonce_prefix, index),
+                chunk_aad(&header, is_last),
+                &mut chunk,
+            )
+            .map_err(|_| EncryptionError::Authentication)?;
+        chunk.extend_from_slice(tag.as_ref());
+
+        writer.write_u32(chunk.len() as u32).await?;
+        writer.write_all(&chunk).await?;
+        written += (size_of::<u32>() + chunk.len()) as u64;
+
+        if is_last {
+            break;
+        }
+    }
+
+    writer.flush().await?;
+    writer.into_inner().sync_all().await?;
+    Ok(written)
+}
+
+/// Decrypts a file produced by [`seal_file`] and writes the plaintext to `dst`. Returns the size
+/// of the decrypted file.
+pub async fn open_file(key_ring: &KeyRing, src: &Path, dst: &Path) -> Result<u64, EncryptionError> {
+    let mut reader = File::open(src).await?;
+
+    let mut header = [0; FILE_HEADER_LEN];
+    reader
+        .read_exact(&mut header)
+        .await
+        .map_err(|_| EncryptionError::Malformed("missing file header"))?;
+    if header[..FILE_MAGIC.len()] != FILE_MAGIC || header[FILE_MAGIC.len()] != FILE_V1 {
+        return Err(EncryptionError::Malformed("unsupported file header"));
+    }
+    let key_offset = FILE_MAGIC.len() + 1;
+    let key_id = KeyId(u32::from_be_bytes(
+        header[key_offset..key_offset + size_of::<u32>()]
+            .try_into()
+            .unwrap(),
+    ));
+    let key = key_ring.key(key_id)?;
+    let nonce_prefix: [u8; NONCE_PREFIX_LEN] =
+        header[key_offset + size_of::<u32>()..].try_into().unwrap();
+
+    let mut writer = BufWriter::new(File::create(dst).await?);
+    let mut written = 0;
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE + TAG_LEN);
+
+    for index in 0u32.. {
+        let len = reader
+            .read_u32()
+            .await
+            .map_err(|_| EncryptionError::Malformed("file is truncated"))?
+            as usize;
+        if len < TAG_LEN || len > CHUNK_SIZE + TAG_LEN {
+            return Err(EncryptionError::Malformed("invalid chunk length"));
+        }
+        chunk.resize(len, 0);
+        reader
+            .read_exact(&mut chunk)
+            .await
+            .map_err(|_| EncryptionError::Malformed("file is truncated"))?;
+
+        // only the last chunk is shorter than the chunk size
+        let is_last = len < CHUNK_SIZE + TAG_LEN;
+        let plaintext = key
+            .open_in_place(
+                chunk_nonce(&nonce_prefix, index),
+                chunk_aad(&header, is_last),
+                &mut chunk,
+            )
+            .map_err(|_| EncryptionError::Authentication)?;
+        writer.write_all(plaintext).await?;
+        written += plaintext.len() as u64;
+
+        if is_last {
+            break;
+        }
+    }
+
+    if reader.read_u8().await.is_ok() {
+        return Err(EncryptionError::Malformed(
+            "trailing data after the last chunk",
+        ));
+    }
+
+    writer.flush().await?;
+    writer.into_inner().sync_all().await?;
+    Ok(written)
+}
+
+async fn read_chunk(
+    reader: &mut (impl AsyncRead + Unpin),
+    buf: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    buf.clear();
+    while buf.len() < CHUNK_SIZE {
+        let read = (&mut *reader)
+            .take((CHUNK_SIZE - buf.len()) as u64)
+            .read_to_end(buf)
+            .await?;
+        if read == 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn chunk_nonce(nonce_prefix: &[u8; NONCE_PREFIX_LEN], index: u32) -> Nonce {
+    let mut nonce = [0; NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(nonce_prefix);
+    nonce[NONCE_PREFIX_LEN..].copy_from_slice(&index.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn chunk_aad(header: &[u8], is_last: bool) -> Aad<Vec<u8>> {
+    let mut aad = Vec::with_capacity(header.len() + 1);
+    aad.extend_from_slice(header);
+    aad.push(u8::from(is_last));
+    Aad::from(aad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_ring() -> KeyRing {
+        KeyRing::new(KeyId(7), [(KeyId(7), [7u8; 32])]).unwrap()
+    }
+
+    async fn roundtrip(size: usize) -> Result<(), EncryptionError> {
+        let dir = tempfile::tempdir()?;
+        let (plain, sealed, opened) = (
+            dir.path().join("plain"),
+            dir.path().join("sealed"),
+            dir.path().join("opened"),
+        );
+        let data: Vec<u8> = (0..size).map(|i| i as u8).collect();
+        tokio::fs::write(&plain, &data).await?;
+
+        let key_ring = key_ring();
+        let sealed_size = seal_file(&key_ring, &plain, &sealed).await?;
+        assert_eq!(sealed_size, tokio::fs::metadata(&sealed).await?.len());
+
+        let opened_size = open_file(&key_ring, &sealed, &opened).await?;
+        assert_eq!(opened_size, size as u64);
+        assert_eq!(tokio::fs::read(&opened).await?, data);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_roundtrip() -> Result<(), EncryptionError> {
+        roundtrip(0).await?;
+        roundtrip(1000).await?;
+        roundtrip(CHUNK_SIZE).await?;
+        roundtrip(2 * CHUNK_SIZE + 17).await
+    }
+
+    #[tokio::test]
+    async fn truncated_file_is_rejected() -> Result<(), EncryptionError> {
+        let dir = tempfile::tempdir()?;
+        let (plain, sealed, opened) = (
+            dir.path().join("plain"),
+            dir.path().join("sealed"),
+            dir.path().join("opened"),
+        );
+        tokio::fs::write(&plain, vec![1u8; 2 * CHUNK_SIZE]).await?;
+
+        let key_ring = key_ring();
+        seal_file(&key_ring, &plain, &sealed).await?;
+
+        // drop the trailing empty chunk, which leaves a file ending with a full chunk
+        let mut data = tokio::fs::read(&sealed).await?;
+        data.truncate(data.len() - size_of::<u32>() - TAG_LEN);
+        tokio::fs::write(&sealed, &data).await?;
+
+        assert!(matches!(
+            open_file(&key_ring, &sealed, &opened).await,
+            Err(EncryptionError::Malformed(_))
+        ));
+        Ok(())
+    }
+}
@@ -144,6 +144,68 @@ where
         IngestFuture::awaiting_permits(record, handle, acquire)
     }
 
+    /// Ingest a batch of records that all belong to the partition of `partition_key`.
+    ///
+    /// The records are handed over to the partition session in order, right after each other,
+    /// which lets the session send them to the partition processor in as few requests as
+    /// possible. Resolves to one [`RecordCommit`] per record, in the order of `records`, once all
+    /// of them were handed over. Fails without ingesting any record if one of them exceeds the
+    /// record size limit.
+    pub fn ingest_batch<R>(
+        &mut self,
+        partition_key: PartitionKey,
+        records: impl IntoIterator<Item = R>,
+    ) -> BoxFuture<'static, Result<Vec<RecordCommit>, IngestionError>>
+    where
+        R: Into<InputRecord<V>>,
+    {
+        let limit = self.manager.options().record_size_limit.get();
+        let mut batch = Vec::new();
+        for record in records {
+            let record = record.into().into_record(&mut self.arena);
+            if record.estimate_size() > limit {
+                let err = IngestionError::RecordMaxSizeExceeded {
+                    size: record.estimate_size(),
+                    limit,
+                };
+                return futures::future::ready(Err(err)).boxed();
+            }
+            batch.push(record);
+        }
+
+        let partition_id = match self
+            .partition_table
+            .pinned()
+            .find_partition_id(partition_key)
+        {
+            Ok(partition_id) => partition_id,
+            Err(err) => return futures::future::ready(Err(err.into())).boxed(),
+        };
+
+        let handle = self.manager.get(partition_id);
+        let permits = Arc::clone(&self.permits);
+        let memory_budget = self.memory_budget.get();
+
+        async move {
+            let mut commits = Vec::with_capacity(batch.len());
+            for record in batch {
+                // every record is handed over as soon as it got its permit, waiting for the
+                // permits of the whole batch could exceed the memory budget.
+                let budget = record.estimate_size().min(memory_budget);
+                let permit = Arc::clone(&permits)
+                    .acquire_many_owned(budget as u32)
+                    .await
+                    .map_err(|_| IngestionError::Closed("permits semaphore closed"))?;
+                let commit = handle
+                    .ingest(permit, record)
+                    .map_err(|_| IngestionError::Closed("partition session closed"))?;
+                commits.push(commit);
+            }
+            Ok(commits)
+        }
+        .boxed()
+    }
+
     /// Once closed, calls to ingest will return [`IngestionError::Closed`].
     /// Inflight records might still get committed.
     pub fn close(&self) {
@@ -404,6 +466,36 @@ mod test {
         commit.await.expect("to resolve");
     }
 
+    #[test(restate_core::test)]
+    async fn client_batch_of_records() {
+        let (mut incoming, mut client) = init_env(1024).await;
+        let mut buf = BytesMut::new();
+
+        let commits = client
+            .ingest_batch(0, ["r0", "r1", "r2"].into_iter().map(InputRecord::from_str))
+            .await
+            .unwrap();
+        assert_that!(commits, len(eq(3)));
+
+        // all records of the batch are sent together, in order
+        let msg = must_next(&mut incoming).await;
+        let (rx, body) = msg.split();
+        assert_that!(
+            body.records,
+            elements_are![
+                eq(InputRecord::from_str("r0").into_record(&mut buf)),
+                eq(InputRecord::from_str("r1").into_record(&mut buf)),
+                eq(InputRecord::from_str("r2").into_record(&mut buf)),
+            ]
+        );
+
+        rx.send(ResponseStatus::Ack.into());
+
+        for commit in commits {
+            commit.await.expect("to resolve");
+        }
+    }
+
     #[test(restate_core::test)]
     async fn client_single_record_retry() {
         let (mut incoming, mut client) = init_env(10).await;
@@ -0,0 +1,390 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+use bytes::Bytes;
+use bytestring::ByteString;
+use futures::future;
+use http::request::Parts;
+use http::{Method, Request, Response, StatusCode, header};
+use http_body_util::{BodyExt, Full, Limited};
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use tracing::{debug, trace};
+use ulid::Ulid;
+
+use restate_core::Metadata;
+use restate_types::errors::GenericError;
+use restate_types::identifiers::{
+    InvocationId, PartitionId, PartitionKey, WithInvocationId, WithPartitionKey,
+};
+use restate_types::invocation::client::SubmittedInvocationNotification;
+use restate_types::invocation::{
+    Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
+    SpanRelation, WorkflowHandlerType,
+};
+use restate_types::nodes_config::ClusterFeature;
+use restate_types::partition_table::FindPartition;
+use restate_types::schema::invocation_target::{
+    DeploymentStatus, InvocationTargetResolver, is_reserved_idempotency_key,
+};
+
+use super::service_handler::SendResponse;
+use super::tracing::prepare_tracing_span;
+use super::{APPLICATION_JSON, Handler, HandlerError};
+use crate::RequestDispatcher;
+use crate::metric_definitions::{INGRESS_REQUESTS, REQUEST_COMPLETED, REQUEST_FAILED};
+
+/// Maximum number of items of a single batch.
+pub(crate) const MAX_BATCH_ITEMS: usize = 1000;
+/// Maximum size of the body of a single batch. The ingress request size limit applies as well.
+const MAX_BATCH_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+const NDJSON_CONTENT_TYPES: [&str; 3] = [
+    "application/x-ndjson",
+    "application/jsonl",
+    "application/x-jsonlines",
+];
+
+/// Item of a `POST /restate/batch/send` request.
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BatchSendItem {
+    /// `{service}/{handler}`
+    target: String,
+    /// Key of the virtual object or workflow.
+    #[serde(default)]
+    key: Option<String>,
+    /// Input of the handler, sent with content type `application/json`.
+    #[serde(default)]
+    body: Option<serde_json::Value>,
+    #[serde(default)]
+    idempotency_key: Option<String>,
+    #[serde_as(as = "Option<restate_util_time::FriendlyDuration>")]
+    #[serde(default)]
+    delay: Option<Duration>,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+pub(crate) struct BatchSendResponse {
+    /// One result per item, in the order of the request.
+    pub(crate) results: Vec<BatchSendResult>,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(untagged)]
+pub(crate) enum BatchSendResult {
+    Sent(SendResponse),
+    Failed { error: String },
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    /// Sends all the items of the batch, replying with the outcome of each item.
+    ///
+    /// The items are grouped by partition, and the items of each partition are appended with a
+    /// single ingestion call in the order of the batch, hence items targeting the same virtual
+    /// object or workflow are enqueued in that order. Partitions are submitted concurrently.
+    pub(crate) async fn handle_batch_send<B: http_body::Body>(
+        self,
+        req: Request<B>,
+    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    where
+        <B as http_body::Body>::Error: Into<GenericError>,
+    {
+        let (parts, body) = req.into_parts();
+        if parts.method != Method::POST {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+
+        let body = Limited::new(body, MAX_BATCH_BODY_SIZE)
+            .collect()
+            .await
+            .map_err(|e| HandlerError::Body(e.into()))?
+            .to_bytes();
+        let items = parse_batch_items(&parts, &body)?;
+        debug!("Processing batch of {} send requests", items.len());
+
+        let mut results: Vec<Option<BatchSendResult>> = Vec::with_capacity(items.len());
+        let mut partitions: BTreeMap<PartitionId, (PartitionKey, Vec<(usize, InvocationRequest)>)> =
+            BTreeMap::new();
+        {
+            let schemas = self.schemas.pinned();
+            let partition_table = Metadata::with_current(|m| m.partition_table_ref());
+            for (idx, item) in items.into_iter().enumerate() {
+                let prepared =
+                    self.prepare_batch_item(&*schemas, &parts, item)
+                        .and_then(|request| {
+                            let partition_id = partition_table
+                                .find_partition_id(request.partition_key())
+                                .map_err(|_| HandlerError::Unavailable)?;
+                            Ok((partition_id, request))
+                        });
+                match prepared {
+                    Ok((partition_id, request)) => {
+                        partitions
+                            .entry(partition_id)
+                            .or_insert_with(|| (request.partition_key(), Vec::new()))
+                            .1
+                            .push((idx, request));
+                        results.push(None);
+                    }
+                    Err(e) => results.push(Some(BatchSendResult::Failed {
+                        error: e.to_string(),
+                    })),
+                }
+            }
+        }
+
+        let dispatcher = &self.dispatcher;
+        let sent = future::join_all(partitions.into_values().map(|(partition_key, requests)| {
+            send_partition_batch(dispatcher, partition_key, requests)
+        }))
+        .await;
+        for (idx, result) in sent.into_iter().flatten() {
+            results[idx] = Some(result);
+        }
+
+        trace!("Complete external HTTP batch send request successfully");
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, APPLICATION_JSON)
+            .body(Full::new(
+                serde_json::to_vec(&BatchSendResponse {
+                    results: results
+                        .into_iter()
+                        .map(|r| r.expect("every item must have a result"))
+                        .collect(),
+                })
+                .unwrap()
+                .into(),
+            ))
+            .unwrap())
+    }
+
+    fn prepare_batch_item(
+        &self,
+        schemas: &Schemas,
+        parts: &Parts,
+        item: BatchSendItem,
+    ) -> Result<InvocationRequest, HandlerError> {
+        let Some((service_name, handler_name)) = item
+            .target
+            .split_once('/')
+            .filter(|(s, h)| !s.is_empty() && !h.is_empty() && !h.contains('/'))
+        else {
+            return Err(HandlerError::BadBatchTarget(item.target.clone()));
+        };
+
+        let invocation_target_meta = schemas
+            .resolve_latest_invocation_target(service_name, handler_name)
+            .ok_or_else(|| {
+                HandlerError::ServiceHandlerNotFound(
+                    service_name.to_owned(),
+                    handler_name.to_owned(),
+                )
+            })?;
+        if !invocation_target_meta.public {
+            return Err(HandlerError::PrivateService);
+        }
+        if let DeploymentStatus::Deprecated(dp_id) = invocation_target_meta.deployment_status {
+            return Err(HandlerError::DeploymentDeprecated(
+                service_name.to_owned(),
+                dp_id,
+            ));
+        }
+
+        let mut idempotency_key = item.idempotency_key.map(ByteString::from);
+        if idempotency_key
+            .as_deref()
+            .is_some_and(is_reserved_idempotency_key)
+        {
+            return Err(HandlerError::ReservedIdempotencyKey);
+        }
+        if idempotency_key.is_some()
+            && invocation_target_meta.target_ty
+                == InvocationTargetType::Workflow(WorkflowHandlerType::Workflow)
+        {
+            return Err(HandlerError::UnsupportedIdempotencyKey);
+        }
+        // Same as for single sends, see handle_service_request
+        if idempotency_key.is_none()
+            && self
+                .cluster_features
+                .contains(ClusterFeature::ControlledIdempotentSharding)
+            && matches!(
+                invocation_target_meta.target_ty,
+                InvocationTargetType::Service | InvocationTargetType::VirtualObject(_)
+            )
+        {
+            idempotency_key = Some(Ulid::new().to_string().into());
+        }
+
+        let invocation_target = match (invocation_target_meta.target_ty, item.key) {
+            (InvocationTargetType::Service, _) => {
+                InvocationTarget::service(service_name, handler_name)
+            }
+            (InvocationTargetType::VirtualObject(handler_ty), Some(key)) => {
+                InvocationTarget::virtual_object(service_name, key, handler_name, handler_ty)
+            }
+            (InvocationTargetType::Workflow(handler_ty), Some(key)) => {
+                InvocationTarget::workflow(service_name, key, handler_name, handler_ty)
+            }
+            (_, None) => return Err(HandlerError::MissingBatchKey(item.target.clone())),
+        };
+
+        let (content_type, body) = match item.body {
+            Some(value) => (
+                Some(APPLICATION_JSON),
+                Bytes::from(serde_json::to_vec(&value).expect("serializing a JSON value")),
+            ),
+            None => (None, Bytes::new()),
+        };
+        invocation_target_meta.input_rules.validate(
+            content_type
+                .as_ref()
+                .map(|ct| ct.to_str().expect("static content type")),
+            &body,
+        )?;
+
+        let invocation_id = InvocationId::generate(&invocation_target, idempotency_key.as_deref());
+        let ingress_span_context = prepare_tracing_span(
+            &invocation_id,
+            &invocation_target,
+            &invocation_target_meta.tracing_sampling,
+            parts,
+        );
+
+        let mut invocation_request_header =
+            InvocationRequestHeader::initialize(invocation_id, invocation_target);
+        invocation_request_header.with_related_span(SpanRelation::parent(ingress_span_context));
+        invocation_request_header
+            .with_retention(invocation_target_meta.compute_retention(idempotency_key.is_some()));
+        invocation_request_header.idempotency_key = idempotency_key;
+        invocation_request_header.execution_time =
+            item.delay.map(|d| SystemTime::now() + d).map(Into::into);
+        if let Some(content_type) = content_type {
+            invocation_request_header.headers = vec![Header::new(
+                header::CONTENT_TYPE.as_str(),
+                content_type.to_str().expect("static content type"),
+            )];
+        }
+
+        Ok(InvocationRequest::new(invocation_request_header, body))
+    }
+}
+
+/// Appends the items of a single partition with one ingestion call, and maps the outcome of each
+/// item to its result.
+async fn send_partition_batch<Dispatcher: RequestDispatcher>(
+    dispatcher: &Dispatcher,
+    partition_key: PartitionKey,
+    requests: Vec<(usize, InvocationRequest)>,
+) -> Vec<(usize, BatchSendResult)> {
+    let items: Vec<_> = requests
+        .iter()
+        .map(|(idx, request)| {
+            (
+                *idx,
+                request.invocation_id(),
+                request.header.execution_time,
+                request.header.target.service_name().to_string(),
+            )
+        })
+        .collect();
+    let outcomes = dispatcher
+        .send_batch(
+            partition_key,
+            requests.into_iter().map(|(_, request)| request).collect(),
+        )
+        .await;
+
+    items
+        .into_iter()
+        .zip(outcomes)
+        .map(
+            |((idx, invocation_id, execution_time, service_name), outcome)| {
+                let (status, result) = match outcome {
+                    // The invocation is appended to the log without waiting for the partition
+                    // processor, hence it's reported as accepted.
+                    Ok(()) => (
+                        REQUEST_COMPLETED,
+                        BatchSendResult::Sent(SendResponse::new(
+                            invocation_id,
+                            SubmittedInvocationNotification {
+                                request_id: Default::default(),
+                                execution_time,
+                                is_new_invocation: true,
+                            },
+                        )),
+                    ),
+                    Err(e) => (
+                        REQUEST_FAILED,
+                        BatchSendResult::Failed {
+                            error: HandlerError::from(e).to_string(),
+                        },
+                    ),
+                };
+                counter!(
+                    INGRESS_REQUESTS,
+                    "status" => status,
+                    "rpc.service" => service_name,
+                    "rpc.type" => "batch_send",
+                )
+                .increment(1);
+                (idx, result)
+            },
+        )
+        .collect()
+}
+
+/// Parses the batch items, either as a JSON array or as newline delimited JSON, depending on the
+/// content type. Empty lines of newline delimited JSON are skipped.
+fn parse_batch_items(parts: &Parts, body: &Bytes) -> Result<Vec<BatchSendItem>, HandlerError> {
+    let is_ndjson = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .is_some_and(|ct| NDJSON_CONTENT_TYPES.iter().any(|t| ct.starts_with(t)));
+
+    let items: Vec<BatchSendItem> = if is_ndjson {
+        let lines: Vec<_> = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.trim_ascii().is_empty())
+            .collect();
+        if lines.len() > MAX_BATCH_ITEMS {
+            return Err(HandlerError::BatchTooLarge(MAX_BATCH_ITEMS));
+        }
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                serde_json::from_slice(line)
+                    .map_err(|e| HandlerError::BadBatchBody(format!("item {idx}: {e}")))
+            })
+            .collect::<Result<_, _>>()?
+    } else {
+        serde_json::from_slice(body).map_err(|e| HandlerError::BadBatchBody(e.to_string()))?
+    };
+
+    if items.len() > MAX_BATCH_ITEMS {
+        return Err(HandlerError::BatchTooLarge(MAX_BATCH_ITEMS));
+    }
+    Ok(items)
+}
@@ -0,0 +1,116 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use http::HeaderMap;
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::Full;
+
+/// Body of the responses produced by the ingress [`Handler`](super::Handler).
+///
+/// This is either a [`Full`] body, which can optionally be followed by trailers as required by
+/// gRPC, or a stream of chunks, e.g. for server-sent events.
+#[derive(Debug, Default)]
+pub(crate) struct ResponseBody {
+    inner: Inner,
+    trailers: Option<HeaderMap>,
+}
+
+enum Inner {
+    Full(Full<Bytes>),
+    Stream(BoxStream<'static, Bytes>),
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Inner::Full(Full::default())
+    }
+}
+
+impl fmt::Debug for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inner::Full(full) => f.debug_tuple("Full").field(full).finish(),
+            Inner::Stream(_) => f.write_str("Stream"),
+        }
+    }
+}
+
+impl ResponseBody {
+    pub(crate) fn with_trailers(data: Bytes, trailers: HeaderMap) -> Self {
+        Self {
+            inner: Inner::Full(Full::new(data)),
+            trailers: Some(trailers),
+        }
+    }
+
+    pub(crate) fn from_stream(stream: impl Stream<Item = Bytes> + Send + 'static) -> Self {
+        Self {
+            inner: Inner::Stream(stream.boxed()),
+            trailers: None,
+        }
+    }
+}
+
+impl From<Full<Bytes>> for ResponseBody {
+    fn from(inner: Full<Bytes>) -> Self {
+        Self {
+            inner: Inner::Full(inner),
+            trailers: None,
+        }
+    }
+}
+
+impl From<Bytes> for ResponseBody {
+    fn from(value: Bytes) -> Self {
+        Full::new(value).into()
+    }
+}
+
+impl Body for ResponseBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let frame = match &mut this.inner {
+            Inner::Full(full) => ready!(Pin::new(full).poll_frame(cx)),
+            Inner::Stream(stream) => ready!(stream.poll_next_unpin(cx)).map(|d| Ok(Frame::data(d))),
+        };
+        match frame {
+            Some(frame) => Poll::Ready(Some(frame)),
+            None => Poll::Ready(this.trailers.take().map(|t| Ok(Frame::trailers(t)))),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.inner {
+            Inner::Full(full) => full.is_end_stream() && self.trailers.is_none(),
+            Inner::Stream(_) => false,
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.inner {
+            Inner::Full(full) => full.size_hint(),
+            Inner::Stream(_) => SizeHint::default(),
+        }
+    }
+}
@@ -57,7 +57,7 @@ pub(crate) enum HandlerError {
     #[error("bad path: {0}")]
     BadPath(String),
     #[error(
-        "bad path, expected /restate/call/:service/:handler, /restate/send/:service/:handler, /restate/scope/:scope/call/:service/:handler, /restate/attach/:invocation_id, /restate/output/:invocation_id, or /restate/lookup"
+        "bad path, expected /restate/call/:service/:handler, /restate/send/:service/:handler, /restate/scope/:scope/call/:service/:handler, /restate/attach/:invocation_id, /restate/output/:invocation_id, /restate/lookup, or /restate/batch/send"
     )]
     BadRestateApiPath,
     #[error("limit-key requires a scope to be set")]
@@ -102,6 +102,8 @@ pub(crate) enum HandlerError {
         "cannot use the idempotency key with workflow handlers. The handler invocation will already be idempotent by the workflow key itself."
     )]
     UnsupportedIdempotencyKey,
+    #[error("bad idempotency key, the prefix 'restate-response-cache:' is reserved")]
+    ReservedIdempotencyKey,
     #[error("bad awakeable id '{0}': {1}")]
     BadAwakeableId(String, IdDecodeError),
     #[error("bad invocation id '{0}': {1}")]
@@ -112,6 +114,24 @@ pub(crate) enum HandlerError {
     DispatcherError(#[from] RequestDispatcherError),
     #[error("bad scope value: {0}")]
     BadScopeValue(RestrictedValueError),
+    #[error("bad path, expected /:package.Service/:Method")]
+    BadRpcPath,
+    #[error(
+        "missing the key of the virtual object or workflow, it must be set with the 'x-restate-key' metadata"
+    )]
+    MissingRpcKey,
+    #[error("bad request message: {0}")]
+    BadRpcMessage(&'static str),
+    #[error("unsupported encoding '{0}', only 'identity' is supported")]
+    UnsupportedRpcEncoding(String),
+    #[error("bad batch body: {0}")]
+    BadBatchBody(String),
+    #[error("bad target '{0}', expected :service/:handler")]
+    BadBatchTarget(String),
+    #[error("the target '{0}' is a virtual object or workflow, but no key was provided")]
+    MissingBatchKey(String),
+    #[error("the batch has more than {0} items")]
+    BatchTooLarge(usize),
 }
 
 // IMPORTANT! If you touch this, please update crates/types/src/schema/openapi.rs too
@@ -152,6 +172,7 @@ impl HandlerError {
             | HandlerError::BadWorkflowPath
             | HandlerError::InputValidation(_)
             | HandlerError::UnsupportedIdempotencyKey
+            | HandlerError::ReservedIdempotencyKey
             | HandlerError::UnsupportedGetOutput
             | HandlerError::DeploymentDeprecated(_, _)
             | HandlerError::BadRestateApiPath
@@ -160,7 +181,15 @@ impl HandlerError {
             | HandlerError::BadScopeValue(_)
             | HandlerError::BadPath(_)
             | HandlerError::ScopeRequiresVQueues
-            | HandlerError::ScopedVirtualObjectNotSupported => StatusCode::BAD_REQUEST,
+            | HandlerError::ScopedVirtualObjectNotSupported
+            | HandlerError::BadRpcPath
+            | HandlerError::MissingRpcKey
+            | HandlerError::BadRpcMessage(_)
+            | HandlerError::BadBatchBody(_)
+            | HandlerError::BadBatchTarget(_)
+            | HandlerError::MissingBatchKey(_) => StatusCode::BAD_REQUEST,
+            HandlerError::UnsupportedRpcEncoding(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            HandlerError::BatchTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
             HandlerError::DispatcherError(_) => {
                 // TODO add more distinctions between different dispatcher errors (unavailable, etc)
                 StatusCode::INTERNAL_SERVER_ERROR
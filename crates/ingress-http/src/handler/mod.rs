@@ -9,13 +9,17 @@
 // by the Apache License, Version 2.0.
 
 mod awakeables;
+mod batch;
+mod body;
 mod error;
 mod health;
 mod invocation;
 mod lookup;
 mod path_parsing;
 mod responses;
+mod rpc;
 mod service_handler;
+mod sse;
 #[cfg(test)]
 mod tests;
 mod tracing;
@@ -29,7 +33,6 @@ use enumset::EnumSet;
 use error::HandlerError;
 use futures::FutureExt;
 use futures::future::BoxFuture;
-use http_body_util::Full;
 use hyper::http::HeaderValue;
 use hyper::{Request, Response};
 use serde::Deserialize;
@@ -46,9 +49,11 @@ use restate_types::schema::service::ServiceMetadataResolver;
 use restate_util_string::{ReString, RestrictedValue};
 
 use super::*;
+use crate::handler::body::ResponseBody;
 use crate::handler::path_parsing::{
     AwakeableRequestType, InvocationRequestType, ServiceRequestType, WorkflowRequestType,
 };
+use crate::handler::rpc::RpcProtocol;
 
 const APPLICATION_JSON: HeaderValue = HeaderValue::from_static("application/json");
 
@@ -69,6 +74,20 @@ enum RequestType {
     OutputByTarget,
     /// `POST /restate/lookup`
     Lookup,
+    /// `POST /restate/batch/send`
+    BatchSend,
+}
+
+impl RequestType {
+    /// Whether the request attaches to an invocation, waiting for its output.
+    fn is_attach(&self) -> bool {
+        matches!(
+            self,
+            RequestType::Attach(_)
+                | RequestType::Invocation(InvocationRequestType::Attach(_))
+                | RequestType::Workflow(WorkflowRequestType::Attach(..))
+        )
+    }
 }
 
 #[derive(Clone)]
@@ -98,7 +117,7 @@ where
     <Body as http_body::Body>::Data: Send + 'static,
     <Body as http_body::Body>::Error: Into<GenericError>,
 {
-    type Response = Response<Full<Bytes>>;
+    type Response = Response<ResponseBody>;
     type Error = Infallible;
     type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -107,7 +126,26 @@ where
     }
 
     fn call(&mut self, req: Request<Body>) -> Self::Future {
-        let res = self.parse_path(req.uri());
+        if let Some(protocol) = RpcProtocol::detect(req.headers()) {
+            return self
+                .clone()
+                .handle_rpc(req, protocol)
+                .map(Ok::<_, Infallible>)
+                .boxed();
+        }
+
+        let res = match self.parse_path(req.uri()) {
+            Ok(request_type)
+                if request_type.is_attach() && sse::accepts_event_stream(req.headers()) =>
+            {
+                return self
+                    .clone()
+                    .handle_attach_events(req, request_type)
+                    .map(|r| Ok::<_, Infallible>(r.unwrap_or_else(|e| e.into_response())))
+                    .boxed();
+            }
+            res => res,
+        };
 
         let mut this = self.clone();
         async move {
@@ -143,9 +181,10 @@ where
                 RequestType::AttachByTarget => this.handle_attach_by_target(req).await,
                 RequestType::OutputByTarget => this.handle_output_by_target(req).await,
                 RequestType::Lookup => this.handle_lookup(req).await,
+                RequestType::BatchSend => this.handle_batch_send(req).await,
             }
         }
-        .map(|r| Ok::<_, Infallible>(r.unwrap_or_else(|e| e.into_response())))
+        .map(|r| Ok::<_, Infallible>(r.map_or_else(|e| e.into_response(), |r| r.map(Into::into))))
         .boxed()
     }
 }
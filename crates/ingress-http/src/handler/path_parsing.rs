@@ -241,6 +241,7 @@ impl ServiceRequestType {
 ///   - `attach/{invocation_id}` or `output/{invocation_id}`
 ///   - `attach` or `output` (POST with body describing the target)
 ///   - `lookup`
+///   - `batch/send`
 fn parse_restate_api_verb<'a, Schemas>(
     verb: &str,
     mut path_parts: impl Iterator<Item = &'a str>,
@@ -285,6 +286,10 @@ where
             }
             Ok(RequestType::Lookup)
         }
+        "batch" => match (path_parts.next(), path_parts.next()) {
+            (Some("send"), None) => Ok(RequestType::BatchSend),
+            _ => Err(HandlerError::BadRestateApiPath),
+        },
         _ => Err(HandlerError::NotFound),
     }
 }
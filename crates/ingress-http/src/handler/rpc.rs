@@ -0,0 +1,517 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! gRPC and Connect unary requests.
+//!
+//! Requests to `/{package.Service}/{Method}` are mapped onto the handler `Method` of the Restate
+//! service `package.Service`, or `Service` if no service with the fully qualified name exists.
+//! The request message is passed as is to the handler, and the handler output is returned as
+//! response message. Restate specific options are provided as request metadata:
+//!
+//! * `x-restate-key`: the key of the virtual object or workflow.
+//! * `idempotency-key`: the idempotency key of the invocation.
+//! * `x-restate-delay`: send the invocation without waiting for its completion, executing it
+//!   after the given delay. The response is an empty message.
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use http::{
+    HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode, Uri, header,
+};
+use http_body_util::{BodyExt, Full};
+use serde::{Deserialize, Serialize};
+
+use restate_types::ServiceName;
+use restate_types::errors::GenericError;
+use restate_types::schema::invocation_target::InvocationTargetResolver;
+
+use super::body::ResponseBody;
+use super::path_parsing::{InvokeType, ServiceRequestType, TargetType};
+use super::{APPLICATION_JSON, Handler, HandlerError};
+use crate::RequestDispatcher;
+
+const X_RESTATE_KEY: HeaderName = HeaderName::from_static("x-restate-key");
+const X_RESTATE_DELAY: HeaderName = HeaderName::from_static("x-restate-delay");
+const GRPC_STATUS: HeaderName = HeaderName::from_static("grpc-status");
+const GRPC_MESSAGE: HeaderName = HeaderName::from_static("grpc-message");
+const GRPC_ENCODING: HeaderName = HeaderName::from_static("grpc-encoding");
+const CONNECT_PROTOCOL_VERSION: HeaderName = HeaderName::from_static("connect-protocol-version");
+
+const APPLICATION_GRPC: &str = "application/grpc";
+const APPLICATION_PROTO: HeaderValue = HeaderValue::from_static("application/proto");
+const IDENTITY_ENCODING: &str = "identity";
+/// Length of the prefix of the gRPC messages: compressed flag and message length.
+const GRPC_FRAME_HEADER_LEN: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RpcCodec {
+    Proto,
+    Json,
+}
+
+impl RpcCodec {
+    fn content_type(self) -> HeaderValue {
+        match self {
+            RpcCodec::Proto => APPLICATION_PROTO,
+            RpcCodec::Json => APPLICATION_JSON,
+        }
+    }
+
+    /// The encoding of a message with all the fields set to their default value.
+    fn empty_message(self) -> Bytes {
+        match self {
+            RpcCodec::Proto => Bytes::new(),
+            RpcCodec::Json => Bytes::from_static(b"{}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RpcProtocol {
+    Grpc(RpcCodec),
+    Connect(RpcCodec),
+}
+
+impl RpcProtocol {
+    /// Detects gRPC requests by their content type, and Connect unary requests by the
+    /// `connect-protocol-version` header. Returns `None` for any other request.
+    pub(crate) fn detect(headers: &HeaderMap) -> Option<Self> {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|ct| ct.to_str().ok())
+            .unwrap_or_default();
+
+        if let Some(subtype) = content_type.strip_prefix(APPLICATION_GRPC) {
+            return match subtype {
+                "" | "+proto" => Some(RpcProtocol::Grpc(RpcCodec::Proto)),
+                "+json" => Some(RpcProtocol::Grpc(RpcCodec::Json)),
+                // gRPC-Web, or a codec we don't know about
+                _ => None,
+            };
+        }
+
+        if headers.contains_key(CONNECT_PROTOCOL_VERSION) {
+            return Some(RpcProtocol::Connect(
+                if content_type.starts_with("application/json") {
+                    RpcCodec::Json
+                } else {
+                    RpcCodec::Proto
+                },
+            ));
+        }
+
+        None
+    }
+
+    fn codec(self) -> RpcCodec {
+        match self {
+            RpcProtocol::Grpc(codec) | RpcProtocol::Connect(codec) => codec,
+        }
+    }
+
+    fn content_type(self) -> HeaderValue {
+        match self {
+            RpcProtocol::Grpc(RpcCodec::Proto) => {
+                HeaderValue::from_static("application/grpc+proto")
+            }
+            RpcProtocol::Grpc(RpcCodec::Json) => HeaderValue::from_static("application/grpc+json"),
+            RpcProtocol::Connect(codec) => codec.content_type(),
+        }
+    }
+
+    fn check_encoding(self, headers: &HeaderMap) -> Result<(), HandlerError> {
+        let encoding_header = match self {
+            RpcProtocol::Grpc(_) => GRPC_ENCODING,
+            RpcProtocol::Connect(_) => header::CONTENT_ENCODING,
+        };
+        match headers.get(&encoding_header) {
+            None => Ok(()),
+            Some(encoding) if encoding == IDENTITY_ENCODING => Ok(()),
+            Some(encoding) => Err(HandlerError::UnsupportedRpcEncoding(
+                String::from_utf8_lossy(encoding.as_bytes()).into_owned(),
+            )),
+        }
+    }
+
+    fn decode_request(self, body: Bytes) -> Result<Bytes, HandlerError> {
+        match self {
+            RpcProtocol::Grpc(_) => decode_grpc_message(body),
+            RpcProtocol::Connect(_) => Ok(body),
+        }
+    }
+
+    /// Converts the response of the HTTP ingress into the response of this protocol.
+    async fn encode_response(self, response: Response<Full<Bytes>>) -> Response<ResponseBody> {
+        let (mut parts, body) = response.into_parts();
+        let body = body
+            .collect()
+            .await
+            .expect("full bodies are infallible")
+            .to_bytes();
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts
+            .headers
+            .insert(header::CONTENT_TYPE, self.content_type());
+
+        if parts.status.is_success() {
+            // Sends are acknowledged with an empty message, the invocation id is in the headers
+            let message = if parts.status == StatusCode::ACCEPTED {
+                self.codec().empty_message()
+            } else {
+                body
+            };
+            parts.status = StatusCode::OK;
+
+            return match self {
+                RpcProtocol::Grpc(_) => {
+                    let mut trailers = HeaderMap::new();
+                    trailers.insert(GRPC_STATUS, RpcCode::Ok.grpc_status());
+                    Response::from_parts(
+                        parts,
+                        ResponseBody::with_trailers(encode_grpc_message(message), trailers),
+                    )
+                }
+                RpcProtocol::Connect(_) => Response::from_parts(parts, message.into()),
+            };
+        }
+
+        let code = RpcCode::from_http_status(parts.status);
+        let message = serde_json::from_slice::<ErrorMessage>(&body)
+            .map(|e| e.message)
+            .unwrap_or_else(|_| {
+                parts
+                    .status
+                    .canonical_reason()
+                    .unwrap_or_default()
+                    .to_owned()
+            });
+
+        match self {
+            RpcProtocol::Grpc(_) => {
+                // Trailers-only response
+                parts.status = StatusCode::OK;
+                parts.headers.insert(GRPC_STATUS, code.grpc_status());
+                if let Ok(message) = HeaderValue::try_from(urlencoding::encode(&message).as_ref()) {
+                    parts.headers.insert(GRPC_MESSAGE, message);
+                }
+                Response::from_parts(parts, ResponseBody::default())
+            }
+            RpcProtocol::Connect(_) => {
+                parts.status = code.connect_http_status();
+                parts.headers.insert(header::CONTENT_TYPE, APPLICATION_JSON);
+                let error = ConnectError {
+                    code: code.connect_name(),
+                    message,
+                };
+                Response::from_parts(
+                    parts,
+                    Bytes::from(
+                        serde_json::to_vec(&error)
+                            .expect("Serializing ConnectError should not fail"),
+                    )
+                    .into(),
+                )
+            }
+        }
+    }
+}
+
+/// Subset of the status codes of gRPC and Connect used by the ingress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RpcCode {
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    Unauthenticated = 16,
+}
+
+impl RpcCode {
+    fn from_http_status(status: StatusCode) -> Self {
+        match status.as_u16() {
+            400 => RpcCode::InvalidArgument,
+            401 => RpcCode::Unauthenticated,
+            403 => RpcCode::PermissionDenied,
+            404 => RpcCode::NotFound,
+            405 | 415 | 501 => RpcCode::Unimplemented,
+            408 | 504 => RpcCode::DeadlineExceeded,
+            409 => RpcCode::Aborted,
+            412 => RpcCode::FailedPrecondition,
+            413 | 429 => RpcCode::ResourceExhausted,
+            499 => RpcCode::Cancelled,
+            470 | 503 => RpcCode::Unavailable,
+            500 => RpcCode::Internal,
+            _ => RpcCode::Unknown,
+        }
+    }
+
+    fn grpc_status(self) -> HeaderValue {
+        HeaderValue::from(self as u16)
+    }
+
+    fn connect_name(self) -> &'static str {
+        match self {
+            RpcCode::Ok => "ok",
+            RpcCode::Cancelled => "canceled",
+            RpcCode::Unknown => "unknown",
+            RpcCode::InvalidArgument => "invalid_argument",
+            RpcCode::DeadlineExceeded => "deadline_exceeded",
+            RpcCode::NotFound => "not_found",
+            RpcCode::PermissionDenied => "permission_denied",
+            RpcCode::ResourceExhausted => "resource_exhausted",
+            RpcCode::FailedPrecondition => "failed_precondition",
+            RpcCode::Aborted => "aborted",
+            RpcCode::Unimplemented => "unimplemented",
+            RpcCode::Internal => "internal",
+            RpcCode::Unavailable => "unavailable",
+            RpcCode::Unauthenticated => "unauthenticated",
+        }
+    }
+
+    /// See <https://connectrpc.com/docs/protocol#error-codes>
+    fn connect_http_status(self) -> StatusCode {
+        match self {
+            RpcCode::Ok => StatusCode::OK,
+            RpcCode::Cancelled => StatusCode::from_u16(499).unwrap(),
+            RpcCode::InvalidArgument | RpcCode::FailedPrecondition => StatusCode::BAD_REQUEST,
+            RpcCode::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+            RpcCode::NotFound => StatusCode::NOT_FOUND,
+            RpcCode::PermissionDenied => StatusCode::FORBIDDEN,
+            RpcCode::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+            RpcCode::Aborted => StatusCode::CONFLICT,
+            RpcCode::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+            RpcCode::Unknown | RpcCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+            RpcCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            RpcCode::Unauthenticated => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// Both the invocation errors and the ingress errors carry a message.
+#[derive(Deserialize)]
+struct ErrorMessage {
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ConnectError {
+    code: &'static str,
+    message: String,
+}
+
+fn decode_grpc_message(mut body: Bytes) -> Result<Bytes, HandlerError> {
+    if body.len() < GRPC_FRAME_HEADER_LEN {
+        return Err(HandlerError::BadRpcMessage("missing message"));
+    }
+    let compressed = body.get_u8();
+    let len = body.get_u32() as usize;
+    if compressed != 0 {
+        return Err(HandlerError::UnsupportedRpcEncoding(
+            "compressed message".to_owned(),
+        ));
+    }
+    if body.len() != len {
+        return Err(HandlerError::BadRpcMessage(
+            "expected exactly one length-prefixed message",
+        ));
+    }
+    Ok(body)
+}
+
+fn encode_grpc_message(message: Bytes) -> Bytes {
+    let mut buf = BytesMut::with_capacity(GRPC_FRAME_HEADER_LEN + message.len());
+    buf.put_u8(0);
+    buf.put_u32(message.len() as u32);
+    buf.put(message);
+    buf.freeze()
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    pub(crate) async fn handle_rpc<B: http_body::Body>(
+        self,
+        req: Request<B>,
+        protocol: RpcProtocol,
+    ) -> Response<ResponseBody>
+    where
+        <B as http_body::Body>::Error: Into<GenericError>,
+    {
+        let response = self
+            .handle_rpc_request(req, protocol)
+            .await
+            .unwrap_or_else(|e| e.into_response());
+        protocol.encode_response(response).await
+    }
+
+    /// Translates the request into a request of the HTTP ingress API, and processes it.
+    async fn handle_rpc_request<B: http_body::Body>(
+        self,
+        req: Request<B>,
+        protocol: RpcProtocol,
+    ) -> Result<Response<Full<Bytes>>, HandlerError>
+    where
+        <B as http_body::Body>::Error: Into<GenericError>,
+    {
+        let (mut parts, body) = req.into_parts();
+        if parts.method != Method::POST {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+        protocol.check_encoding(&parts.headers)?;
+        let service_request = self.parse_rpc_target(&parts.uri, &parts.headers)?;
+
+        let body = body
+            .collect()
+            .await
+            .map_err(|e| HandlerError::Body(e.into()))?
+            .to_bytes();
+        let message = protocol.decode_request(body)?;
+
+        // Empty messages are passed as requests without body, as accepted by handlers without input
+        if message.is_empty() {
+            parts.headers.remove(header::CONTENT_TYPE);
+        } else {
+            parts
+                .headers
+                .insert(header::CONTENT_TYPE, protocol.codec().content_type());
+        }
+        if let Some(delay) = parts.headers.get(X_RESTATE_DELAY) {
+            let delay = delay
+                .to_str()
+                .map_err(|e| HandlerError::BadHeader(X_RESTATE_DELAY, e))?;
+            // The service handler reads the delay from the query
+            parts.uri = Uri::try_from(format!(
+                "{}?delay={}",
+                parts.uri.path(),
+                urlencoding::encode(delay)
+            ))
+            .map_err(|e| HandlerError::BadDelayDuration(e.to_string()))?;
+        }
+
+        self.handle_service_request(
+            Request::from_parts(parts, Full::new(message)),
+            service_request,
+        )
+        .await
+    }
+
+    fn parse_rpc_target(
+        &self,
+        uri: &Uri,
+        headers: &HeaderMap,
+    ) -> Result<ServiceRequestType, HandlerError> {
+        let mut segments = uri.path().split('/').skip(1);
+        let (Some(service), Some(method), None) =
+            (segments.next(), segments.next(), segments.next())
+        else {
+            return Err(HandlerError::BadRpcPath);
+        };
+
+        let schemas = self.schemas.pinned();
+        // Fall back to the service name without package
+        let (service_name, service_type) = match schemas.resolve_latest_service_type(service) {
+            Some(service_type) => (service, service_type),
+            None => service
+                .rsplit_once('.')
+                .and_then(|(_, name)| {
+                    schemas
+                        .resolve_latest_service_type(name)
+                        .map(|service_type| (name, service_type))
+                })
+                .ok_or_else(|| HandlerError::ServiceNotFound(service.to_owned()))?,
+        };
+
+        let target = if service_type.is_keyed() {
+            let key = headers
+                .get(X_RESTATE_KEY)
+                .ok_or(HandlerError::MissingRpcKey)?
+                .to_str()
+                .map_err(|e| HandlerError::BadHeader(X_RESTATE_KEY, e))?;
+            TargetType::Keyed {
+                key: key.to_owned(),
+            }
+        } else {
+            TargetType::Unkeyed
+        };
+
+        Ok(ServiceRequestType {
+            name: ServiceName::new(service_name),
+            handler: method.to_owned(),
+            target,
+            invoke_ty: if headers.contains_key(X_RESTATE_DELAY) {
+                InvokeType::Send
+            } else {
+                InvokeType::Call
+            },
+            scope: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grpc_message_round_trip() {
+        let message = Bytes::from_static(b"\x0a\x05hello");
+        let frame = encode_grpc_message(message.clone());
+        assert_eq!(frame.len(), GRPC_FRAME_HEADER_LEN + message.len());
+        assert_eq!(decode_grpc_message(frame).unwrap(), message);
+    }
+
+    #[test]
+    fn grpc_message_rejects_bad_frames() {
+        assert!(decode_grpc_message(Bytes::from_static(b"\x00\x00")).is_err());
+        // truncated message
+        assert!(decode_grpc_message(Bytes::from_static(b"\x00\x00\x00\x00\x02a")).is_err());
+        // compressed message
+        assert!(decode_grpc_message(Bytes::from_static(b"\x01\x00\x00\x00\x01a")).is_err());
+    }
+
+    #[test]
+    fn detect_protocol() {
+        let detect = |headers: &[(&'static str, &'static str)]| {
+            let mut map = HeaderMap::new();
+            for (k, v) in headers {
+                map.insert(*k, HeaderValue::from_static(v));
+            }
+            RpcProtocol::detect(&map)
+        };
+
+        assert_eq!(
+            detect(&[("content-type", "application/grpc")]),
+            Some(RpcProtocol::Grpc(RpcCodec::Proto))
+        );
+        assert_eq!(
+            detect(&[("content-type", "application/grpc+json")]),
+            Some(RpcProtocol::Grpc(RpcCodec::Json))
+        );
+        assert_eq!(detect(&[("content-type", "application/grpc-web")]), None);
+        assert_eq!(
+            detect(&[
+                ("content-type", "application/json"),
+                ("connect-protocol-version", "1")
+            ]),
+            Some(RpcProtocol::Connect(RpcCodec::Json))
+        );
+        assert_eq!(detect(&[("content-type", "application/json")]), None);
+    }
+}
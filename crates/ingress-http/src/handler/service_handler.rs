@@ -26,14 +26,18 @@ use restate_types::Scope;
 use restate_types::config::Configuration;
 use restate_types::errors::GenericError;
 use restate_types::identifiers::{InvocationId, WithInvocationId};
+use restate_types::invocation::client::{
+    GetInvocationOutputResponse, SubmittedInvocationNotification,
+};
 use restate_types::invocation::{
-    Header, InvocationRequest, InvocationRequestHeader, InvocationTarget, InvocationTargetType,
-    SpanRelation, WorkflowHandlerType,
+    Header, InvocationQuery, InvocationRequest, InvocationRequestHeader, InvocationTarget,
+    InvocationTargetType, SpanRelation, WorkflowHandlerType,
 };
 use restate_types::limit_key::LimitKey;
 use restate_types::nodes_config::ClusterFeature;
 use restate_types::schema::invocation_target::{
     DeploymentStatus, InvocationTargetMetadata, InvocationTargetResolver,
+    is_reserved_idempotency_key, response_cache_idempotency_key,
 };
 use restate_types::time::MillisSinceEpoch;
 use restate_util_string::{ReString, RestateString};
@@ -44,7 +48,10 @@ use super::tracing::prepare_tracing_span;
 use super::{APPLICATION_JSON, Handler};
 use crate::RequestDispatcher;
 use crate::handler::responses::{IDEMPOTENCY_EXPIRES, X_RESTATE_ID};
-use crate::metric_definitions::{INGRESS_REQUEST_DURATION, INGRESS_REQUESTS, REQUEST_COMPLETED};
+use crate::metric_definitions::{
+    INGRESS_REQUEST_DURATION, INGRESS_REQUESTS, INGRESS_RESPONSE_CACHE, REQUEST_COMPLETED,
+    RESPONSE_CACHE_HIT, RESPONSE_CACHE_MISS,
+};
 
 pub(crate) const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
 const LIMIT_KEY_HEADER: HeaderName = HeaderName::from_static("x-restate-limit-key");
@@ -74,6 +81,34 @@ pub(crate) struct SendResponse {
     status: SendStatus,
 }
 
+impl SendResponse {
+    pub(crate) fn new(
+        invocation_id: InvocationId,
+        notification: SubmittedInvocationNotification,
+    ) -> Self {
+        Self {
+            invocation_id,
+            execution_time: notification
+                .execution_time
+                .and_then(|m| {
+                    if m == MillisSinceEpoch::UNIX_EPOCH {
+                        // Ignore
+                        None
+                    } else {
+                        Some(m)
+                    }
+                })
+                .map(SystemTime::from)
+                .map(Into::into),
+            status: if notification.is_new_invocation {
+                SendStatus::Accepted
+            } else {
+                SendStatus::PreviouslyAccepted
+            },
+        }
+    }
+}
+
 impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
 where
     Schemas: InvocationTargetResolver + Clone + Send + Sync + 'static,
@@ -128,12 +163,22 @@ where
             return Err(HandlerError::UnsupportedIdempotencyKey);
         }
 
+        // Calls without idempotency key to pure handlers get their response cached.
+        // The idempotency key is derived from the input once the body is collected.
+        let response_cache_retention =
+            if idempotency_key.is_none() && matches!(invoke_ty, InvokeType::Call) {
+                invocation_target_meta.compute_response_cache_retention()
+            } else {
+                None
+            };
+
         // Inject a new random idempotency key for
         // calls that have no idempotency keys only
         // if the `controlled-idempotent-sharding` is enabled.
-        if self
-            .cluster_features
-            .contains(ClusterFeature::ControlledIdempotentSharding)
+        if response_cache_retention.is_none()
+            && self
+                .cluster_features
+                .contains(ClusterFeature::ControlledIdempotentSharding)
             && matches!(
                 invocation_target_meta.target_ty,
                 InvocationTargetType::Service | InvocationTargetType::VirtualObject(_)
@@ -144,8 +189,9 @@ where
         }
 
         // Compute retention values
-        let invocation_retention =
-            invocation_target_meta.compute_retention(idempotency_key.is_some());
+        let invocation_retention = response_cache_retention
+            .unwrap_or_else(|| invocation_target_meta.compute_retention(idempotency_key.is_some()));
+        let tracing_sampling = invocation_target_meta.tracing_sampling;
 
         // Parse scope from path
         let scope = if let Some(scope) = scope {
@@ -203,19 +249,9 @@ where
         }
         .with_scope(scope);
 
-        let invocation_id = InvocationId::generate(&invocation_target, idempotency_key.as_deref());
         let invoke_ty_str = invoke_ty.as_static_str();
 
         let result = async move {
-            let ingress_span_context =
-                prepare_tracing_span(&invocation_id, &invocation_target, &req);
-
-            debug!(
-                restate.invocation.id = %invocation_id,
-                restate.invocation.target = %invocation_target.short(),
-                "Processing invocation request"
-            );
-
             let (parts, body) = req.into_parts();
 
             // Check HTTP Method
@@ -231,6 +267,25 @@ where
                 .to_bytes();
             trace!(rpc.request = ?body);
 
+            if response_cache_retention.is_some() {
+                idempotency_key = Some(response_cache_idempotency_key(&body));
+            }
+            let invocation_id =
+                InvocationId::generate(&invocation_target, idempotency_key.as_deref());
+
+            let ingress_span_context = prepare_tracing_span(
+                &invocation_id,
+                &invocation_target,
+                &tracing_sampling,
+                &parts,
+            );
+
+            debug!(
+                restate.invocation.id = %invocation_id,
+                restate.invocation.target = %invocation_target.short(),
+                "Processing invocation request"
+            );
+
             // Validate content-type and body
             invocation_target_meta.input_rules.validate(
                 parts
@@ -274,6 +329,14 @@ where
                     if delay.is_some() {
                         return Err(HandlerError::UnsupportedDelay);
                     }
+                    if response_cache_retention.is_some() {
+                        return Self::handle_cached_service_call(
+                            Arc::new(InvocationRequest::new(invocation_request_header, body)),
+                            invocation_target_meta,
+                            self.dispatcher,
+                        )
+                        .await;
+                    }
                     Self::handle_service_call(
                         Arc::new(InvocationRequest::new(invocation_request_header, body)),
                         invocation_target_meta,
@@ -327,6 +390,51 @@ where
         Self::reply_with_invocation_response(response, move |_| Ok(invocation_target_metadata))
     }
 
+    /// Reply with the cached response if a previous invocation with the same input completed,
+    /// otherwise call the handler. The response is then cached for the retention of the invocation.
+    async fn handle_cached_service_call(
+        invocation_request: Arc<InvocationRequest>,
+        invocation_target_metadata: InvocationTargetMetadata,
+        dispatcher: Dispatcher,
+    ) -> Result<Response<Full<Bytes>>, HandlerError> {
+        let invocation_id = invocation_request.invocation_id();
+        let service_name = invocation_request.header.target.service_name().to_string();
+
+        match dispatcher
+            .get_invocation_output(InvocationQuery::Invocation(invocation_id))
+            .instrument(trace_span!("Looking up cached response"))
+            .await
+        {
+            Ok(GetInvocationOutputResponse::Ready(output)) => {
+                counter!(
+                    INGRESS_RESPONSE_CACHE,
+                    "result" => RESPONSE_CACHE_HIT,
+                    "rpc.service" => service_name,
+                )
+                .increment(1);
+                return Self::reply_with_invocation_response(output, move |_| {
+                    Ok(invocation_target_metadata)
+                });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                // The call below will attach to the existing invocation anyway, if any.
+                debug!(
+                    restate.invocation.id = %invocation_id,
+                    "Failed to look up cached response: {e}"
+                );
+            }
+        }
+
+        counter!(
+            INGRESS_RESPONSE_CACHE,
+            "result" => RESPONSE_CACHE_MISS,
+            "rpc.service" => service_name,
+        )
+        .increment(1);
+        Self::handle_service_call(invocation_request, invocation_target_metadata, dispatcher).await
+    }
+
     async fn handle_service_send(
         invocation_request: Arc<InvocationRequest>,
         dispatcher: Dispatcher,
@@ -342,28 +450,9 @@ where
             .header(header::CONTENT_TYPE, APPLICATION_JSON)
             .header(X_RESTATE_ID, invocation_id.to_string())
             .body(Full::new(
-                serde_json::to_vec(&SendResponse {
-                    invocation_id,
-                    execution_time: response
-                        .execution_time
-                        .and_then(|m| {
-                            if m == MillisSinceEpoch::UNIX_EPOCH {
-                                // Ignore
-                                None
-                            } else {
-                                Some(m)
-                            }
-                        })
-                        .map(SystemTime::from)
-                        .map(Into::into),
-                    status: if response.is_new_invocation {
-                        SendStatus::Accepted
-                    } else {
-                        SendStatus::PreviouslyAccepted
-                    },
-                })
-                .unwrap()
-                .into(),
+                serde_json::to_vec(&SendResponse::new(invocation_id, response))
+                    .unwrap()
+                    .into(),
             ))
             .unwrap())
     }
@@ -433,6 +522,9 @@ fn parse_idempotency(headers: &HeaderMap) -> Result<Option<ByteString>, HandlerE
     } else {
         return Ok(None);
     };
+    if is_reserved_idempotency_key(&idempotency_key) {
+        return Err(HandlerError::ReservedIdempotencyKey);
+    }
 
     Ok(Some(idempotency_key))
 }
@@ -0,0 +1,341 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::DateTime;
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt, stream};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode, header};
+use http_body_util::BodyExt;
+use serde::Serialize;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tracing::debug;
+
+use restate_types::errors::InvocationError;
+use restate_types::identifiers::{InvocationId, ServiceId};
+use restate_types::invocation::InvocationQuery;
+use restate_types::invocation::client::{
+    AttachInvocationResponse, GetInvocationProgressResponse, InvocationProgress,
+    InvocationProgressStatus,
+};
+use restate_types::schema::invocation_target::InvocationTargetResolver;
+use restate_types::time::MillisSinceEpoch;
+
+use super::body::ResponseBody;
+use super::path_parsing::{InvocationRequestType, WorkflowRequestType};
+use super::responses::X_RESTATE_ID;
+use super::{Handler, HandlerError, RequestType};
+use crate::{RequestDispatcher, RequestDispatcherError};
+
+const TEXT_EVENT_STREAM: HeaderValue = HeaderValue::from_static("text/event-stream");
+/// Disables response buffering in nginx and compatible proxies.
+const X_ACCEL_BUFFERING: HeaderName = HeaderName::from_static("x-accel-buffering");
+
+/// Interval between keep-alive comments, to prevent proxies from closing idle connections.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Interval between reads of the invocation progress.
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Returns true if the client accepts `text/event-stream` responses.
+pub(crate) fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|media_range| media_range.split(';').next())
+        .any(|media_type| media_type.trim().eq_ignore_ascii_case("text/event-stream"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum EventStatus {
+    Scheduled,
+    Inboxed,
+    Running,
+    Retrying,
+    Suspended,
+    Paused,
+    Completed,
+}
+
+/// Data of the `status` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(test, derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StatusEvent {
+    pub(crate) status: EventStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scheduled_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) attempts: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_failure: Option<InvocationError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) next_retry_at: Option<String>,
+}
+
+impl From<InvocationProgress> for StatusEvent {
+    fn from(progress: InvocationProgress) -> Self {
+        Self {
+            status: match progress.status {
+                InvocationProgressStatus::Scheduled => EventStatus::Scheduled,
+                InvocationProgressStatus::Inboxed => EventStatus::Inboxed,
+                InvocationProgressStatus::Running => EventStatus::Running,
+                InvocationProgressStatus::BackingOff => EventStatus::Retrying,
+                InvocationProgressStatus::Suspended => EventStatus::Suspended,
+                InvocationProgressStatus::Paused => EventStatus::Paused,
+                InvocationProgressStatus::Completed => EventStatus::Completed,
+            },
+            scheduled_at: progress.execution_time.map(to_rfc3339),
+            attempts: progress.attempts,
+            last_failure: progress.last_failure,
+            next_retry_at: progress.next_retry_at.map(to_rfc3339),
+        }
+    }
+}
+
+fn to_rfc3339(time: MillisSinceEpoch) -> String {
+    DateTime::from_timestamp_millis(time.as_u64() as i64)
+        .expect("conversion to chrono should work")
+        .to_rfc3339()
+}
+
+/// Formats a server-sent event, splitting multi-line data over several `data` fields.
+fn event(name: &str, data: &str) -> Bytes {
+    let mut buf = String::with_capacity(name.len() + data.len() + 16);
+    buf.push_str("event: ");
+    buf.push_str(name);
+    buf.push('\n');
+    for line in data.split('\n') {
+        buf.push_str("data: ");
+        buf.push_str(line.strip_suffix('\r').unwrap_or(line));
+        buf.push('\n');
+    }
+    buf.push('\n');
+    Bytes::from(buf)
+}
+
+fn interval(period: Duration) -> Interval {
+    let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+struct EventStreamState<Schemas, Dispatcher> {
+    handler: Handler<Schemas, Dispatcher>,
+    invocation_id: InvocationId,
+    /// `None` once the output event was emitted.
+    attach: Option<BoxFuture<'static, Result<AttachInvocationResponse, RequestDispatcherError>>>,
+    /// `None` if the progress cannot be read, e.g. because the partition leader runs an older
+    /// version.
+    last_status: Option<StatusEvent>,
+    keep_alive: Interval,
+    progress_poll: Interval,
+}
+
+impl<Schemas, Dispatcher> Handler<Schemas, Dispatcher>
+where
+    Schemas: InvocationTargetResolver + Clone + Send + Sync + 'static,
+    Dispatcher: RequestDispatcher + Clone + Send + Sync + 'static,
+{
+    /// Attaches to the invocation replying with server-sent events: a `status` event whenever
+    /// the status of the invocation changes, keep-alive comments while waiting, and finally
+    /// either an `output` or an `error` event.
+    pub(super) async fn handle_attach_events<B: http_body::Body>(
+        self,
+        req: Request<B>,
+        request_type: RequestType,
+    ) -> Result<Response<ResponseBody>, HandlerError> {
+        if req.method() != Method::GET {
+            return Err(HandlerError::MethodNotAllowed);
+        }
+        let invocation_query = match request_type {
+            RequestType::Attach(invocation_id) => InvocationQuery::Invocation(invocation_id),
+            RequestType::Invocation(InvocationRequestType::Attach(invocation_target_type)) => {
+                Self::convert_to_invocation_query(invocation_target_type)?
+            }
+            RequestType::Workflow(WorkflowRequestType::Attach(name, key)) => {
+                InvocationQuery::Workflow(ServiceId::new(None, name.as_str(), key.as_str()))
+            }
+            _ => unreachable!("checked by RequestType::is_attach"),
+        };
+        let invocation_id = invocation_query.to_invocation_id();
+
+        // Read the progress before replying, so we can still fail with the right status code
+        let last_status = match self.dispatcher.get_invocation_progress(invocation_id).await {
+            Ok(GetInvocationProgressResponse::NotFound) => {
+                return Err(HandlerError::InvocationNotFound);
+            }
+            Ok(GetInvocationProgressResponse::Ready(progress)) => Some(progress.into()),
+            Err(e) => {
+                debug!(
+                    restate.invocation.id = %invocation_id,
+                    "Cannot read the invocation progress, no status events will be sent: {e}"
+                );
+                None
+            }
+        };
+
+        let dispatcher = self.dispatcher.clone();
+        let attach = async move { dispatcher.attach_invocation(invocation_query).await }.boxed();
+        let first_event = last_status
+            .as_ref()
+            .map(|status| event("status", &serde_json::to_string(status).unwrap()));
+
+        let state = EventStreamState {
+            handler: self,
+            invocation_id,
+            attach: Some(attach),
+            last_status,
+            keep_alive: interval(KEEP_ALIVE_INTERVAL),
+            progress_poll: interval(PROGRESS_POLL_INTERVAL),
+        };
+        let events = stream::iter(first_event).chain(stream::unfold(state, Self::next_event));
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, TEXT_EVENT_STREAM)
+            .header(header::CACHE_CONTROL, "no-cache")
+            .header(X_ACCEL_BUFFERING, "no")
+            .header(X_RESTATE_ID, invocation_id.to_string())
+            .body(ResponseBody::from_stream(events))
+            .unwrap())
+    }
+
+    async fn next_event(
+        mut state: EventStreamState<Schemas, Dispatcher>,
+    ) -> Option<(Bytes, EventStreamState<Schemas, Dispatcher>)> {
+        let mut attach = state.attach.take()?;
+        loop {
+            tokio::select! {
+                biased;
+                result = &mut attach => {
+                    let event = state.handler.output_event(result).await;
+                    return Some((event, state));
+                }
+                _ = state.keep_alive.tick() => {
+                    state.attach = Some(attach);
+                    return Some((Bytes::from_static(b": keep-alive\n\n"), state));
+                }
+                _ = state.progress_poll.tick(), if state.last_status.is_some() => {
+                    if let Some(event) = state.poll_status().await {
+                        state.attach = Some(attach);
+                        return Some((event, state));
+                    }
+                }
+            }
+        }
+    }
+
+    async fn output_event(
+        &self,
+        result: Result<AttachInvocationResponse, RequestDispatcherError>,
+    ) -> Bytes {
+        let response = match result {
+            Ok(AttachInvocationResponse::Ready(output)) => {
+                Self::reply_with_invocation_response(output, |invocation_target| {
+                    self.schemas
+                        .pinned()
+                        .resolve_latest_invocation_target(
+                            invocation_target.service_name(),
+                            invocation_target.handler_name(),
+                        )
+                        .ok_or(HandlerError::NotFound)
+                })
+            }
+            Ok(AttachInvocationResponse::NotFound) => Err(HandlerError::InvocationNotFound),
+            Ok(AttachInvocationResponse::NotSupported) => Err(HandlerError::NotImplemented),
+            Err(e) => Err(e.into()),
+        }
+        .unwrap_or_else(|e| e.into_response());
+
+        let (parts, body) = response.into_parts();
+        let Ok(body) = body.collect().await;
+        let body = body.to_bytes();
+        match (parts.status.is_success(), std::str::from_utf8(&body)) {
+            (true, Ok(output)) => event("output", output),
+            (true, Err(_)) => event(
+                "error",
+                &serde_json::json!({
+                    "message": "the output is not valid UTF-8, read it from the output endpoint"
+                })
+                .to_string(),
+            ),
+            // Error responses are JSON
+            (false, error) => event("error", error.unwrap_or_default()),
+        }
+    }
+}
+
+impl<Schemas, Dispatcher: RequestDispatcher> EventStreamState<Schemas, Dispatcher> {
+    /// Returns the `status` event if the status changed since the last poll.
+    async fn poll_status(&mut self) -> Option<Bytes> {
+        let status: StatusEvent = match self
+            .handler
+            .dispatcher
+            .get_invocation_progress(self.invocation_id)
+            .await
+        {
+            Ok(GetInvocationProgressResponse::Ready(progress)) => progress.into(),
+            // Completed and purged in the meantime, the attach will tell
+            Ok(GetInvocationProgressResponse::NotFound) => return None,
+            Err(e) => {
+                debug!(
+                    restate.invocation.id = %self.invocation_id,
+                    "Failed to read the invocation progress: {e}"
+                );
+                return None;
+            }
+        };
+        // The output event will follow
+        if status.status == EventStatus::Completed || self.last_status.as_ref() == Some(&status) {
+            return None;
+        }
+        let event = event("status", &serde_json::to_string(&status).unwrap());
+        self.last_status = Some(status);
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_header() {
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_event_stream(&headers));
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert!(!accepts_event_stream(&headers));
+
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/json, Text/Event-Stream;q=0.9"),
+        );
+        assert!(accepts_event_stream(&headers));
+    }
+
+    #[test]
+    fn multi_line_event() {
+        assert_eq!(
+            event("output", "{\r\n  \"a\": 1\n}"),
+            Bytes::from_static(b"event: output\ndata: {\ndata:   \"a\": 1\ndata: }\n\n")
+        );
+        assert_eq!(
+            event("output", ""),
+            Bytes::from_static(b"event: output\ndata: \n\n")
+        );
+    }
+}
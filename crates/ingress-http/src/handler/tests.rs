@@ -26,19 +26,26 @@ use tracing_test::traced_test;
 
 use super::ConnectInfo;
 use super::Handler;
+use super::batch::{BatchSendResponse, BatchSendResult, MAX_BATCH_ITEMS};
+use super::body::ResponseBody;
 use super::health::HealthResponse;
 use super::lookup::LookupResponse;
 use super::mocks::*;
 use super::service_handler::*;
-use crate::MockRequestDispatcher;
+use super::sse::{EventStatus, StatusEvent};
 use crate::handler::responses::X_RESTATE_ID;
+use crate::{MockRequestDispatcher, RequestDispatcherError};
 use restate_core::TestCoreEnv;
 use restate_test_util::{assert, assert_eq};
 use restate_types::config::{Configuration, set_current_config};
-use restate_types::identifiers::{IdempotencyId, InvocationId, ServiceId, WithInvocationId};
+use restate_types::errors::InvocationError;
+use restate_types::identifiers::{
+    IdempotencyId, InvocationId, ServiceId, WithInvocationId, WithPartitionKey,
+};
 use restate_types::invocation::client::{
-    AttachInvocationResponse, GetInvocationOutputResponse, InvocationOutput,
-    InvocationOutputResponse, SubmittedInvocationNotification,
+    AttachInvocationResponse, GetInvocationOutputResponse, GetInvocationProgressResponse,
+    InvocationOutput, InvocationOutputResponse, InvocationProgress, InvocationProgressStatus,
+    SubmittedInvocationNotification,
 };
 use restate_types::invocation::{
     InvocationQuery, InvocationTarget, InvocationTargetType, VirtualObjectHandlerType,
@@ -48,7 +55,7 @@ use restate_types::live::Live;
 use restate_types::net::address::SocketAddress;
 use restate_types::schema::invocation_target::{
     InputContentType, InputRules, InputValidationRule, InvocationTargetMetadata,
-    OutputContentTypeRule, OutputRules,
+    OutputContentTypeRule, OutputRules, response_cache_idempotency_key,
 };
 
 #[restate_core::test]
@@ -547,6 +554,182 @@ async fn idempotency_key_and_send_with_different_invocation_id() {
     assert_eq!(send_response.invocation_id, expected_invocation_id);
 }
 
+fn cached_greeter_schemas() -> MockSchemas {
+    MockSchemas::default().with_service_and_target(
+        "greeter.Greeter",
+        "greet",
+        InvocationTargetMetadata {
+            response_cache_ttl: Some(Duration::from_secs(60)),
+            ..InvocationTargetMetadata::mock(InvocationTargetType::Service)
+        },
+    )
+}
+
+fn greeting_request(person: &str) -> Request<Full<Bytes>> {
+    hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&GreetingRequest {
+                person: person.to_string(),
+            })
+            .unwrap(),
+        )))
+        .unwrap()
+}
+
+fn greeting_output(invocation_id: InvocationId, greeting: &str) -> InvocationOutput {
+    InvocationOutput {
+        request_id: Default::default(),
+        invocation_id: Some(invocation_id),
+        completion_expiry_time: None,
+        response: InvocationOutputResponse::Success(
+            InvocationTarget::service("greeter.Greeter", "greet"),
+            serde_json::to_vec(&GreetingResponse {
+                greeting: greeting.to_string(),
+            })
+            .unwrap()
+            .into(),
+        ),
+    }
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn cached_call_miss_invokes_with_input_derived_idempotency_key() {
+    let greeting_req = serde_json::to_vec(&GreetingRequest {
+        person: "Francesco".to_string(),
+    })
+    .unwrap();
+    let expected_idempotency_key = response_cache_idempotency_key(&greeting_req);
+    let expected_invocation_id = InvocationId::generate(
+        &InvocationTarget::service("greeter.Greeter", "greet"),
+        Some(&*expected_idempotency_key),
+    );
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_get_invocation_output()
+        .return_once(move |invocation_query| {
+            assert_eq!(
+                invocation_query,
+                InvocationQuery::Invocation(expected_invocation_id)
+            );
+            ready(Ok(GetInvocationOutputResponse::NotFound)).boxed()
+        });
+    mock_dispatcher
+        .expect_call()
+        .return_once(move |invocation_request| {
+            assert_eq!(invocation_request.invocation_id(), expected_invocation_id);
+            assert_eq!(
+                invocation_request.header.idempotency_key,
+                Some(expected_idempotency_key)
+            );
+            assert_eq!(
+                invocation_request.header.completion_retention_duration(),
+                Duration::from_secs(60)
+            );
+
+            ready(Ok(greeting_output(
+                invocation_request.invocation_id(),
+                "Igal",
+            )))
+            .boxed()
+        });
+
+    let response = handle_with_schemas_and_dispatcher(
+        greeting_request("Francesco"),
+        cached_greeter_schemas(),
+        mock_dispatcher,
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    let response_value: GreetingResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(response_value.greeting, "Igal");
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn cached_call_hit_replies_without_invoking() {
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_get_invocation_output()
+        .return_once(|invocation_query| {
+            ready(Ok(GetInvocationOutputResponse::Ready(greeting_output(
+                invocation_query.to_invocation_id(),
+                "Igal",
+            ))))
+            .boxed()
+        });
+    mock_dispatcher.expect_call().never();
+
+    let response = handle_with_schemas_and_dispatcher(
+        greeting_request("Francesco"),
+        cached_greeter_schemas(),
+        mock_dispatcher,
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_, response_body) = response.into_parts();
+    let response_bytes = response_body.collect().await.unwrap().to_bytes();
+    let response_value: GreetingResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(response_value.greeting, "Igal");
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn cached_call_with_idempotency_key_bypasses_cache() {
+    let mut req = greeting_request("Francesco");
+    req.headers_mut()
+        .insert(IDEMPOTENCY_KEY, HeaderValue::from_static("123456"));
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher.expect_get_invocation_output().never();
+    mock_dispatcher
+        .expect_call()
+        .return_once(|invocation_request| {
+            assert_eq!(
+                invocation_request.header.idempotency_key,
+                Some(ByteString::from_static("123456"))
+            );
+            ready(Ok(greeting_output(
+                invocation_request.invocation_id(),
+                "Igal",
+            )))
+            .boxed()
+        });
+
+    let response =
+        handle_with_schemas_and_dispatcher(req, cached_greeter_schemas(), mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn reserved_idempotency_key_is_rejected() {
+    let mut req = greeting_request("Francesco");
+    req.headers_mut().insert(
+        IDEMPOTENCY_KEY,
+        HeaderValue::from_static("restate-response-cache:abc"),
+    );
+
+    // The request must not be able to write into the response cache
+    let response = handle_with_schemas_and_dispatcher(
+        req,
+        cached_greeter_schemas(),
+        MockRequestDispatcher::default(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn attach_with_invocation_id() {
@@ -1189,7 +1372,7 @@ pub async fn handle_with_schemas_and_dispatcher<B: http_body::Body + Send + 'sta
     mut req: Request<B>,
     schemas: MockSchemas,
     dispatcher: MockRequestDispatcher,
-) -> Response<Full<Bytes>>
+) -> Response<ResponseBody>
 where
     <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     <B as http_body::Body>::Data: Send + Sync + 'static,
@@ -1208,7 +1391,7 @@ where
 pub async fn handle<B: http_body::Body + Send + 'static>(
     req: Request<B>,
     mock_request_dispatcher: MockRequestDispatcher,
-) -> Response<Full<Bytes>>
+) -> Response<ResponseBody>
 where
     <B as http_body::Body>::Error: std::error::Error + Send + Sync + 'static,
     <B as http_body::Body>::Data: Send + Sync + 'static,
@@ -1220,7 +1403,7 @@ async fn handle_with_size_limit<B>(
     mut req: Request<B>,
     size_limit: usize,
     dispatcher: MockRequestDispatcher,
-) -> Response<LimitResponseBody<Full<Bytes>>>
+) -> Response<LimitResponseBody<ResponseBody>>
 where
     B: http_body::Body + Send + 'static,
     <B as http_body::Body>::Data: Send + Sync + 'static,
@@ -1284,6 +1467,143 @@ async fn attach_with_id_path() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[restate_core::test]
+#[traced_test]
+async fn attach_event_stream() {
+    let invocation_id = InvocationId::mock_random();
+
+    let req = hyper::Request::builder()
+        .uri(format!("http://localhost/restate/attach/{invocation_id}"))
+        .method(Method::GET)
+        .header(http::header::ACCEPT, "text/event-stream")
+        .body(Empty::<Bytes>::new())
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_get_invocation_progress()
+        .return_once(move |actual_invocation_id| {
+            assert_eq!(invocation_id, actual_invocation_id);
+            ready(Ok(GetInvocationProgressResponse::Ready(
+                InvocationProgress {
+                    status: InvocationProgressStatus::BackingOff,
+                    execution_time: None,
+                    attempts: Some(2),
+                    last_failure: Some(InvocationError::internal("boom")),
+                    next_retry_at: None,
+                },
+            )))
+            .boxed()
+        });
+    mock_dispatcher
+        .expect_attach_invocation()
+        .return_once(move |actual_invocation_query| {
+            assert_eq!(
+                InvocationQuery::Invocation(invocation_id),
+                actual_invocation_query
+            );
+            ready(Ok(AttachInvocationResponse::Ready(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_id),
+                completion_expiry_time: None,
+                response: InvocationOutputResponse::Success(
+                    InvocationTarget::service("greeter.Greeter", "greet"),
+                    Bytes::from_static(b"{\"greeting\":\"Igal\"}"),
+                ),
+            })))
+            .boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "text/event-stream"
+    );
+    assert_eq!(
+        response.headers().get(X_RESTATE_ID).unwrap(),
+        invocation_id.to_string().as_str()
+    );
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = std::str::from_utf8(&body).unwrap();
+    let (status, output) = body.split_once("\n\n").unwrap();
+    let status: StatusEvent =
+        serde_json::from_str(status.strip_prefix("event: status\ndata: ").unwrap()).unwrap();
+    assert_eq!(status.status, EventStatus::Retrying);
+    assert_eq!(status.attempts, Some(2));
+    assert_eq!(status.last_failure, Some(InvocationError::internal("boom")));
+    assert_eq!(output, "event: output\ndata: {\"greeting\":\"Igal\"}\n\n");
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn attach_event_stream_failure() {
+    let workflow_id = ServiceId::new(None, "greeter.Workflow", "my-key");
+
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/workflow/greeter.Workflow/my-key/attach")
+        .method(Method::GET)
+        .header(http::header::ACCEPT, "text/event-stream")
+        .body(Empty::<Bytes>::new())
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    // Leaders not supporting the progress don't prevent from attaching
+    mock_dispatcher
+        .expect_get_invocation_progress()
+        .return_once(|_| ready(Err(anyhow::anyhow!("unsupported").into())).boxed());
+    mock_dispatcher
+        .expect_attach_invocation()
+        .return_once(move |actual_invocation_query| {
+            assert_eq!(
+                InvocationQuery::Workflow(workflow_id),
+                actual_invocation_query
+            );
+            ready(Ok(AttachInvocationResponse::Ready(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: None,
+                completion_expiry_time: None,
+                response: InvocationOutputResponse::Failure(InvocationError::internal("boom")),
+            })))
+            .boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = std::str::from_utf8(&body).unwrap();
+    let error: InvocationError = serde_json::from_str(
+        body.strip_prefix("event: error\ndata: ")
+            .unwrap()
+            .trim_end(),
+    )
+    .unwrap();
+    assert_eq!(error, InvocationError::internal("boom"));
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn attach_event_stream_not_found() {
+    let invocation_id = InvocationId::mock_random();
+
+    let req = hyper::Request::builder()
+        .uri(format!("http://localhost/restate/attach/{invocation_id}"))
+        .method(Method::GET)
+        .header(http::header::ACCEPT, "text/event-stream")
+        .body(Empty::<Bytes>::new())
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_get_invocation_progress()
+        .return_once(|_| ready(Ok(GetInvocationProgressResponse::NotFound)).boxed());
+
+    let response = handle(req, mock_dispatcher).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
 #[restate_core::test]
 #[traced_test]
 async fn output_with_id_path() {
@@ -1589,3 +1909,436 @@ async fn scoped_virtual_object_allowed_when_flag_on() {
     .await;
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+fn grpc_frame(message: &[u8]) -> Bytes {
+    let mut frame = vec![0];
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(message);
+    frame.into()
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn grpc_call_virtual_object() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.GreeterObject/greet")
+        .method(Method::POST)
+        .header("content-type", "application/grpc")
+        .header("te", "trailers")
+        .header("x-restate-key", "my-key")
+        .body(Full::new(grpc_frame(b"\x0a\x09Francesco")))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_call()
+        .return_once(|invocation_request| {
+            assert_eq!(
+                invocation_request.header.target.service_name(),
+                "greeter.GreeterObject"
+            );
+            assert_eq!(invocation_request.header.target.key().unwrap(), &"my-key");
+            assert_eq!(invocation_request.header.target.handler_name(), "greet");
+            assert_eq!(
+                invocation_request.body,
+                Bytes::from_static(b"\x0a\x09Francesco")
+            );
+
+            Box::pin(ready(Ok(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_request.invocation_id()),
+                completion_expiry_time: None,
+                response: InvocationOutputResponse::Success(
+                    invocation_request.header.target.clone(),
+                    Bytes::from_static(b"\x0a\x04Igal"),
+                ),
+            })))
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let (parts, response_body) = response.into_parts();
+    assert!(parts.headers.contains_key(X_RESTATE_ID));
+    assert_eq!(
+        parts.headers.get("content-type").unwrap(),
+        "application/grpc+proto"
+    );
+    let collected = response_body.collect().await.unwrap();
+    assert_eq!(
+        collected.trailers().unwrap().get("grpc-status").unwrap(),
+        "0"
+    );
+    assert_eq!(collected.to_bytes(), grpc_frame(b"\x0a\x04Igal"));
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn grpc_send_with_delay_and_unqualified_service_name() {
+    let mock_schemas = MockSchemas::default().with_service_and_target(
+        "Greeter",
+        "greet",
+        InvocationTargetMetadata::mock(InvocationTargetType::Service),
+    );
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.v1.Greeter/greet")
+        .method(Method::POST)
+        .header("content-type", "application/grpc+proto")
+        .header("x-restate-delay", "1m")
+        .body(Full::new(grpc_frame(b"")))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_send()
+        .return_once(|invocation_request| {
+            assert_eq!(invocation_request.header.target.service_name(), "Greeter");
+            assert!(invocation_request.header.execution_time.is_some());
+
+            ready(Ok(SubmittedInvocationNotification {
+                request_id: Default::default(),
+                execution_time: None,
+                is_new_invocation: true,
+            }))
+            .boxed()
+        });
+
+    let response = handle_with_schemas_and_dispatcher(req, mock_schemas, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key(X_RESTATE_ID));
+    let collected = response.into_body().collect().await.unwrap();
+    assert_eq!(
+        collected.trailers().unwrap().get("grpc-status").unwrap(),
+        "0"
+    );
+    assert_eq!(collected.to_bytes(), grpc_frame(b""));
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn grpc_errors_are_trailers_only() {
+    // Virtual objects require the key metadata
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.GreeterObject/greet")
+        .method(Method::POST)
+        .header("content-type", "application/grpc")
+        .body(Full::new(grpc_frame(b"")))
+        .unwrap();
+
+    let response = handle(req, MockRequestDispatcher::default()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("grpc-status").unwrap(), "3");
+    assert!(response.headers().contains_key("grpc-message"));
+
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Unknown/greet")
+        .method(Method::POST)
+        .header("content-type", "application/grpc")
+        .body(Full::new(grpc_frame(b"")))
+        .unwrap();
+
+    let response = handle(req, MockRequestDispatcher::default()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("grpc-status").unwrap(), "5");
+    let collected = response.into_body().collect().await.unwrap();
+    assert!(collected.trailers().is_none());
+    assert!(collected.to_bytes().is_empty());
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn connect_unary_call() {
+    let greeting_req = GreetingRequest {
+        person: "Francesco".to_string(),
+    };
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .header("connect-protocol-version", "1")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&greeting_req).unwrap(),
+        )))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_call()
+        .return_once(|invocation_request| {
+            let greeting_req: GreetingRequest =
+                serde_json::from_slice(&invocation_request.body).unwrap();
+            assert_eq!(&greeting_req.person, "Francesco");
+
+            Box::pin(ready(Ok(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_request.invocation_id()),
+                completion_expiry_time: None,
+                response: InvocationOutputResponse::Success(
+                    invocation_request.header.target.clone(),
+                    serde_json::to_vec(&GreetingResponse {
+                        greeting: "Igal".to_string(),
+                    })
+                    .unwrap()
+                    .into(),
+                ),
+            })))
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let response_value: GreetingResponse = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(response_value.greeting, "Igal");
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn connect_unary_call_failure() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/greeter.Greeter/greet")
+        .method(Method::POST)
+        .header("content-type", "application/proto")
+        .header("connect-protocol-version", "1")
+        .body(Full::new(Bytes::new()))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_call()
+        .return_once(|invocation_request| {
+            Box::pin(ready(Ok(InvocationOutput {
+                request_id: Default::default(),
+                invocation_id: Some(invocation_request.invocation_id()),
+                completion_expiry_time: None,
+                response: InvocationOutputResponse::Failure(InvocationError::new(
+                    404u16,
+                    "no greeting",
+                )),
+            })))
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let error: serde_json::Value = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(error["code"], "not_found");
+    assert_eq!(error["message"], "no greeting");
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn batch_send_reports_per_item_results() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/batch/send")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&serde_json::json!([
+                {"target": "greeter.Greeter/greet", "body": {"person": "Francesco"}},
+                {"target": "greeter.GreeterObject/greet"},
+                {"target": "greeter.Unknown/greet"},
+                {"target": "greeter.GreeterObject/greet", "key": "my-key", "idempotencyKey": "123"},
+            ]))
+            .unwrap(),
+        )))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_send_batch()
+        .times(1..=2)
+        .returning(|_, invocation_requests| {
+            for invocation_request in &invocation_requests {
+                if invocation_request.header.target.service_name() == "greeter.Greeter" {
+                    let greeting_req: GreetingRequest =
+                        serde_json::from_slice(&invocation_request.body).unwrap();
+                    assert_eq!(&greeting_req.person, "Francesco");
+                } else {
+                    assert_eq!(invocation_request.header.target.key().unwrap(), &"my-key");
+                    assert_eq!(
+                        invocation_request.header.idempotency_key.as_deref(),
+                        Some("123")
+                    );
+                }
+            }
+
+            ready(invocation_requests.iter().map(|_| Ok(())).collect()).boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let BatchSendResponse { results } = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(results.len(), 4);
+    assert!(let BatchSendResult::Sent(_) = &results[0]);
+    assert!(let BatchSendResult::Failed { .. } = &results[1]);
+    assert!(let BatchSendResult::Failed { .. } = &results[2]);
+    assert!(let BatchSendResult::Sent(_) = &results[3]);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn batch_send_rejects_reserved_idempotency_key() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/batch/send")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&serde_json::json!([
+                {"target": "greeter.Greeter/greet", "idempotencyKey": "restate-response-cache:abc"},
+            ]))
+            .unwrap(),
+        )))
+        .unwrap();
+
+    let response = handle(req, MockRequestDispatcher::default()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let BatchSendResponse { results } = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(let BatchSendResult::Failed { .. } = &results[0]);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn batch_send_ndjson_with_delay() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/batch/send")
+        .method(Method::POST)
+        .header("content-type", "application/x-ndjson")
+        .body(Full::new(Bytes::from_static(
+            b"{\"target\": \"greeter.GreeterObject/greet\", \"key\": \"a\", \"delay\": \"10s\"}\n\
+              \n\
+              {\"target\": \"greeter.GreeterObject/greet\", \"key\": \"b\"}\n",
+        )))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_send_batch()
+        .times(1..=2)
+        .returning(|_, invocation_requests| {
+            for invocation_request in &invocation_requests {
+                let key = invocation_request.header.target.key().unwrap().to_string();
+                assert_eq!(
+                    invocation_request.header.execution_time.is_some(),
+                    key == "a"
+                );
+            }
+
+            ready(invocation_requests.iter().map(|_| Ok(())).collect()).boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let BatchSendResponse { results } = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(
+        results
+            .iter()
+            .all(|r| matches!(r, BatchSendResult::Sent(_)))
+    );
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn batch_send_appends_items_of_a_partition_with_one_call() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/batch/send")
+        .method(Method::POST)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&serde_json::json!([
+                {"target": "greeter.GreeterObject/greet", "key": "my-key", "body": {"person": "1"}},
+                {"target": "greeter.GreeterObject/greet", "key": "my-key", "body": {"person": "2"}},
+                {"target": "greeter.GreeterObject/greet", "key": "my-key", "body": {"person": "3"}},
+            ]))
+            .unwrap(),
+        )))
+        .unwrap();
+
+    let mut mock_dispatcher = MockRequestDispatcher::default();
+    mock_dispatcher
+        .expect_send_batch()
+        .times(1)
+        .returning(|partition_key, invocation_requests| {
+            let persons: Vec<_> = invocation_requests
+                .iter()
+                .map(|invocation_request| {
+                    assert_eq!(invocation_request.partition_key(), partition_key);
+                    serde_json::from_slice::<GreetingRequest>(&invocation_request.body)
+                        .unwrap()
+                        .person
+                })
+                .collect();
+            // items are appended in the order of the batch
+            assert_eq!(persons, ["1", "2", "3"]);
+
+            ready(vec![
+                Ok(()),
+                Err(RequestDispatcherError::Internal(anyhow::anyhow!(
+                    "not committed"
+                ))),
+                Ok(()),
+            ])
+            .boxed()
+        });
+
+    let response = handle(req, mock_dispatcher).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let response_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let BatchSendResponse { results } = serde_json::from_slice(&response_bytes).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(let BatchSendResult::Sent(_) = &results[0]);
+    assert!(let BatchSendResult::Failed { .. } = &results[1]);
+    assert!(let BatchSendResult::Sent(_) = &results[2]);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn batch_send_rejects_malformed_batch() {
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/batch/send")
+        .method(Method::POST)
+        .header("content-type", "application/x-ndjson")
+        .body(Full::new(Bytes::from_static(
+            b"{\"target\": \"greeter.Greeter/greet\"}\nnot json\n",
+        )))
+        .unwrap();
+
+    // Nothing is sent if any item cannot be parsed
+    let response = handle(req, MockRequestDispatcher::default()).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[restate_core::test]
+#[traced_test]
+async fn batch_send_rejects_too_many_items() {
+    let body = "{\"target\": \"greeter.Greeter/greet\"}\n".repeat(MAX_BATCH_ITEMS + 1);
+    let req = hyper::Request::builder()
+        .uri("http://localhost/restate/batch/send")
+        .method(Method::POST)
+        .header("content-type", "application/x-ndjson")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap();
+
+    // Nothing is sent if the batch exceeds the limit
+    let response = handle(req, MockRequestDispatcher::default()).await;
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -10,26 +10,28 @@
 
 use super::ConnectInfo;
 
-use http::Request;
+use http::request::Parts;
 use opentelemetry::global::ObjectSafeSpan;
 use opentelemetry::trace::{SpanContext, TraceContextExt};
 use restate_tracing_instrumentation as instrumentation;
 use restate_types::identifiers::InvocationId;
 use restate_types::invocation::{InvocationTarget, SpanRelation};
+use restate_types::schema::invocation_target::TracingSampling;
 
-pub(crate) fn prepare_tracing_span<B>(
+pub(crate) fn prepare_tracing_span(
     invocation_id: &InvocationId,
     invocation_target: &InvocationTarget,
-    req: &Request<B>,
+    tracing_sampling: &TracingSampling,
+    parts: &Parts,
 ) -> SpanContext {
-    let connect_info: &ConnectInfo = req
-        .extensions()
+    let connect_info: &ConnectInfo = parts
+        .extensions
         .get()
         .expect("Should have been injected by the previous layer");
     let (client_addr, client_port) = (connect_info.address(), connect_info.port());
 
-    let tracing_context: &opentelemetry::Context = req
-        .extensions()
+    let tracing_context: &opentelemetry::Context = parts
+        .extensions
         .get()
         .expect("Should have been injected by the previous layer");
 
@@ -43,6 +45,14 @@ pub(crate) fn prepare_tracing_span<B>(
         SpanRelation::None
     };
 
+    let attributes = instrumentation::ingress_span_attributes(
+        tracing_sampling,
+        parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+    );
+
     let span = if let Some(port) = client_port {
         instrumentation::info_invocation_span!(
             relation = relation,
@@ -52,7 +62,8 @@ pub(crate) fn prepare_tracing_span<B>(
             tags = (
                 client.socket.address = client_addr,
                 client.socket.port = port as i64
-            )
+            ),
+            attributes = attributes
         )
     } else {
         // unix sockets connections don't have a port, address is also likely "anonymous"
@@ -61,7 +72,8 @@ pub(crate) fn prepare_tracing_span<B>(
             prefix = "ingress",
             id = invocation_id,
             target = invocation_target,
-            tags = (client.socket.address = client_addr)
+            tags = (client.socket.address = client_addr),
+            attributes = attributes
         )
     };
 
@@ -22,10 +22,10 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 
-use restate_types::identifiers::InvocationId;
+use restate_types::identifiers::{InvocationId, PartitionKey};
 use restate_types::invocation::client::{
-    AttachInvocationResponse, GetInvocationOutputResponse, InvocationOutput,
-    SubmittedInvocationNotification,
+    AttachInvocationResponse, GetInvocationOutputResponse, GetInvocationProgressResponse,
+    InvocationOutput, SubmittedInvocationNotification,
 };
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use restate_types::journal_v2::Signal;
@@ -73,6 +73,15 @@ pub trait RequestDispatcher {
         invocation_request: Arc<InvocationRequest>,
     ) -> impl Future<Output = Result<SubmittedInvocationNotification, RequestDispatcherError>> + Send;
 
+    /// Send batch: append invocations that all belong to the partition of `partition_key` with a
+    /// single ingestion call, in the given order, and wait until they are committed to the log.
+    /// Returns one result per invocation, in the same order.
+    fn send_batch(
+        &self,
+        partition_key: PartitionKey,
+        invocation_requests: Vec<InvocationRequest>,
+    ) -> impl Future<Output = Vec<Result<(), RequestDispatcherError>>> + Send;
+
     /// Call: append invocation and wait for its response
     fn call(
         &self,
@@ -91,6 +100,12 @@ pub trait RequestDispatcher {
         invocation_query: InvocationQuery,
     ) -> impl Future<Output = Result<GetInvocationOutputResponse, RequestDispatcherError>> + Send;
 
+    /// Get the current progress of an invocation, without blocking when it's still running.
+    fn get_invocation_progress(
+        &self,
+        invocation_id: InvocationId,
+    ) -> impl Future<Output = Result<GetInvocationProgressResponse, RequestDispatcherError>> + Send;
+
     /// Send invocation response (for awakeables).
     /// **NOTE:** This works only for targeting invocations using Journal Table V1/Service Protocol <= V3.
     fn send_invocation_response(
@@ -300,6 +315,14 @@ mod mocks {
             MockRequestDispatcher::send(self, invocation_request)
         }
 
+        fn send_batch(
+            &self,
+            partition_key: PartitionKey,
+            invocation_requests: Vec<InvocationRequest>,
+        ) -> impl Future<Output = Vec<Result<(), RequestDispatcherError>>> + Send {
+            MockRequestDispatcher::send_batch(self, partition_key, invocation_requests)
+        }
+
         fn call(
             &self,
             invocation_request: Arc<InvocationRequest>,
@@ -323,6 +346,14 @@ mod mocks {
             MockRequestDispatcher::get_invocation_output(self, invocation_query)
         }
 
+        fn get_invocation_progress(
+            &self,
+            invocation_id: InvocationId,
+        ) -> impl Future<Output = Result<GetInvocationProgressResponse, RequestDispatcherError>> + Send
+        {
+            MockRequestDispatcher::get_invocation_progress(self, invocation_id)
+        }
+
         fn send_invocation_response(
             &self,
             invocation_response: InvocationResponse,
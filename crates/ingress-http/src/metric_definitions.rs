@@ -14,15 +14,22 @@ use metrics::{Unit, describe_counter, describe_histogram};
 
 pub const HTTP_CONNECTION_CREATED: &str = "restate.ingress.http.connection_created.total";
 pub const HTTP_CONNECTION_DROPPED: &str = "restate.ingress.http.connection_dropped.total";
+pub const HTTP_TLS_HANDSHAKE_FAILED: &str = "restate.ingress.http.tls_handshake_failed.total";
 
 pub const INGRESS_REQUESTS: &str = "restate.ingress.requests.total";
 // values of label `status` in INGRESS_REQUEST
 pub const REQUEST_ADMITTED: &str = "admitted";
 pub const REQUEST_COMPLETED: &str = "completed";
+pub const REQUEST_FAILED: &str = "failed";
 pub const REQUEST_RATE_LIMITED: &str = "rate-limited";
 
 pub const INGRESS_REQUEST_DURATION: &str = "restate.ingress.request_duration.seconds";
 
+pub const INGRESS_RESPONSE_CACHE: &str = "restate.ingress.response_cache.total";
+// values of label `result` in INGRESS_RESPONSE_CACHE
+pub const RESPONSE_CACHE_HIT: &str = "hit";
+pub const RESPONSE_CACHE_MISS: &str = "miss";
+
 pub(crate) fn describe_metrics() {
     describe_counter!(
         INGRESS_REQUESTS,
@@ -34,6 +41,11 @@ pub(crate) fn describe_metrics() {
         Unit::Seconds,
         "Total latency of Ingress request processing in seconds"
     );
+    describe_counter!(
+        INGRESS_RESPONSE_CACHE,
+        Unit::Count,
+        "Number of ingress calls to pure handlers answered from the response cache (hit) or by invoking the handler (miss)"
+    );
 
     describe_counter!(
         HTTP_CONNECTION_CREATED,
@@ -46,4 +58,10 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of ingress incoming connections dropped"
     );
+
+    describe_counter!(
+        HTTP_TLS_HANDSHAKE_FAILED,
+        Unit::Count,
+        "Number of ingress incoming connections that failed or timed out the TLS handshake"
+    );
 }
@@ -10,37 +10,47 @@
 
 use super::{RequestDispatcher, RequestDispatcherError};
 
-use restate_types::identifiers::{InvocationId, PartitionProcessorRpcRequestId, WithInvocationId};
+use restate_core::network::TransportConnect;
+use restate_ingestion_client::IngestionClient;
+use restate_types::identifiers::{
+    InvocationId, PartitionKey, PartitionProcessorRpcRequestId, WithInvocationId, WithPartitionKey,
+};
 use restate_types::invocation::client::{
-    AttachInvocationResponse, GetInvocationOutputResponse, InvocationClient, InvocationClientError,
-    InvocationOutput, SubmittedInvocationNotification,
+    AttachInvocationResponse, GetInvocationOutputResponse, GetInvocationProgressResponse,
+    InvocationClient, InvocationClientError, InvocationOutput, SubmittedInvocationNotification,
+};
+use restate_types::invocation::{
+    InvocationQuery, InvocationRequest, InvocationResponse, ServiceInvocation,
 };
-use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use restate_types::journal_v2::Signal;
 use restate_types::retries::RetryPolicy;
+use restate_wal_protocol::{Command, Destination, Envelope, Header, Source};
 use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{Instrument, debug_span, trace};
 
-pub struct InvocationClientRequestDispatcher<IC> {
+pub struct InvocationClientRequestDispatcher<IC, T> {
     invocation_client: IC,
+    ingestion_client: IngestionClient<T, Envelope>,
     retry_policy: RetryPolicy,
 }
 
-impl<IC: Clone> Clone for InvocationClientRequestDispatcher<IC> {
+impl<IC: Clone, T: Clone> Clone for InvocationClientRequestDispatcher<IC, T> {
     fn clone(&self) -> Self {
         InvocationClientRequestDispatcher {
             invocation_client: self.invocation_client.clone(),
+            ingestion_client: self.ingestion_client.clone(),
             retry_policy: self.retry_policy.clone(),
         }
     }
 }
 
-impl<IC> InvocationClientRequestDispatcher<IC> {
-    pub fn new(invocation_client: IC) -> Self {
+impl<IC, T> InvocationClientRequestDispatcher<IC, T> {
+    pub fn new(invocation_client: IC, ingestion_client: IngestionClient<T, Envelope>) -> Self {
         Self {
             invocation_client,
+            ingestion_client,
             // TODO figure out how to tune this?
             retry_policy: RetryPolicy::fixed_delay(Duration::from_millis(50), None),
         }
@@ -74,9 +84,10 @@ impl<IC> InvocationClientRequestDispatcher<IC> {
     }
 }
 
-impl<IC> RequestDispatcher for InvocationClientRequestDispatcher<IC>
+impl<IC, T> RequestDispatcher for InvocationClientRequestDispatcher<IC, T>
 where
     IC: InvocationClient + Clone + Send + Sync + 'static,
+    T: TransportConnect,
 {
     async fn send(
         &self,
@@ -95,6 +106,63 @@ where
         .await
     }
 
+    async fn send_batch(
+        &self,
+        partition_key: PartitionKey,
+        invocation_requests: Vec<InvocationRequest>,
+    ) -> Vec<Result<(), RequestDispatcherError>> {
+        let len = invocation_requests.len();
+        // Same as appending an invocation which replies once appended, see the partition
+        // processor's rpc handler. Duplicates of idempotent invocations are deduplicated when
+        // the partition processor applies them.
+        let envelopes = invocation_requests.into_iter().map(|invocation_request| {
+            let service_invocation = ServiceInvocation::from_request(
+                invocation_request,
+                restate_types::invocation::Source::ingress(PartitionProcessorRpcRequestId::new()),
+            );
+            let header = Header {
+                source: Source::Ingress {},
+                dest: Destination::Processor {
+                    partition_key: service_invocation.partition_key(),
+                    dedup: None,
+                },
+            };
+            Envelope::new(header, Command::Invoke(Box::new(service_invocation)))
+        });
+
+        let commits = match self
+            .ingestion_client
+            .clone()
+            .ingest_batch(partition_key, envelopes)
+            .instrument(debug_span!("send invocation batch", %partition_key, len))
+            .await
+        {
+            Ok(commits) => commits,
+            Err(err) => {
+                trace!("Ingesting invocation batch failed: {err}");
+                return (0..len)
+                    .map(|_| {
+                        Err(RequestDispatcherError::Internal(anyhow::anyhow!(
+                            "failed to ingest invocation: {err}"
+                        )))
+                    })
+                    .collect();
+            }
+        };
+
+        futures::future::join_all(commits)
+            .await
+            .into_iter()
+            .map(|commit| {
+                commit.map_err(|err| {
+                    RequestDispatcherError::Internal(anyhow::anyhow!(
+                        "invocation was not committed: {err}"
+                    ))
+                })
+            })
+            .collect()
+    }
+
     async fn call(
         &self,
         invocation_request: Arc<InvocationRequest>,
@@ -135,6 +203,20 @@ where
         .await
     }
 
+    async fn get_invocation_progress(
+        &self,
+        invocation_id: InvocationId,
+    ) -> Result<GetInvocationProgressResponse, RequestDispatcherError> {
+        let request_id = PartitionProcessorRpcRequestId::default();
+        // Not retried, callers poll the progress periodically anyway
+        Ok(self
+            .invocation_client
+            .get_invocation_progress(request_id, invocation_id)
+            .instrument(debug_span!("get invocation progress", %request_id, %invocation_id))
+            .await
+            .map_err(|e| e.into_inner())?)
+    }
+
     async fn send_invocation_response(
         &self,
         invocation_response: InvocationResponse,
@@ -11,6 +11,7 @@
 use std::convert::Infallible;
 use std::future::Future;
 use std::num::NonZeroU32;
+use std::sync::Arc;
 use std::time::Duration;
 
 use codederror::CodedError;
@@ -32,8 +33,9 @@ use tower_http::trace::TraceLayer;
 use tracing::{Span, debug, info, info_span, instrument};
 
 use restate_core::network::hyper_error_status;
+use restate_core::network::tls::TlsContext;
 use restate_core::{TaskCenter, TaskCenterFutureExt, cancellation_token, task_center};
-use restate_types::config::IngressOptions;
+use restate_types::config::{Configuration, IngressOptions, ListenerTlsOptions};
 use restate_types::errors::GenericError;
 use restate_types::health::HealthStatus;
 use restate_types::live::Live;
@@ -46,7 +48,9 @@ use restate_util_time::DurationExt;
 
 use super::*;
 use crate::handler::Handler;
-use crate::metric_definitions::{HTTP_CONNECTION_CREATED, HTTP_CONNECTION_DROPPED};
+use crate::metric_definitions::{
+    HTTP_CONNECTION_CREATED, HTTP_CONNECTION_DROPPED, HTTP_TLS_HANDSHAKE_FAILED,
+};
 
 #[derive(Debug, thiserror::Error, CodedError)]
 pub enum IngressServerError {
@@ -60,6 +64,7 @@ pub struct HyperServerIngress<Schemas, Dispatcher> {
     concurrency_limit: usize,
     request_size_limit: usize,
     http2_max_concurrent_streams: Option<NonZeroU32>,
+    tls: Option<ListenerTlsOptions>,
 
     // Parameters to build the layers
     schemas: Live<Schemas>,
@@ -86,6 +91,7 @@ where
             ingress_options.concurrent_api_requests_limit(),
             ingress_options.request_size_limit().get(),
             ingress_options.http2_max_concurrent_streams(),
+            ingress_options.ingress_listener_options().tls().cloned(),
             schemas,
             dispatcher,
             health,
@@ -103,6 +109,7 @@ where
         concurrency_limit: usize,
         request_size_limit: usize,
         http2_max_concurrent_streams: Option<NonZeroU32>,
+        tls: Option<ListenerTlsOptions>,
         schemas: Live<Schemas>,
         dispatcher: Dispatcher,
         health: HealthStatus<IngressStatus>,
@@ -114,6 +121,7 @@ where
             concurrency_limit,
             request_size_limit,
             http2_max_concurrent_streams,
+            tls,
             schemas,
             dispatcher,
             health,
@@ -132,11 +140,14 @@ where
             concurrency_limit,
             request_size_limit,
             http2_max_concurrent_streams,
+            tls,
             schemas,
             dispatcher,
             health,
         } = self;
 
+        let tls = tls.as_ref().map(TlsContext::for_listener).transpose()?;
+
         // Prepare the handler
         let service = ServiceBuilder::new()
             .layer(
@@ -219,8 +230,20 @@ where
             tokio::select! {
                 res = listeners.accept() => {
                     let (stream, peer_addr) = res?;
-                    match stream {
-                        Either::Left(tcp_stream) => {
+                    match (stream, &tls) {
+                        (Either::Left(tcp_stream), Some(tls)) => {
+                            Self::handle_tls_connection(
+                                tls.clone(),
+                                tcp_stream,
+                                peer_addr,
+                                service.clone(),
+                                http2_max_concurrent_streams,
+                                shutdown.child_token(),
+                                force_shutdown.child_token(),
+                                &mut inflight,
+                            );
+                        }
+                        (Either::Left(tcp_stream), None) => {
                             Self::handle_connection(
                                 tcp_stream,
                                 peer_addr,
@@ -231,7 +254,7 @@ where
                                 &mut inflight,
                             )?;
                         }
-                        Either::Right(unix_stream) => {
+                        (Either::Right(unix_stream), _) => {
                             Self::handle_connection(
                                 unix_stream,
                                 peer_addr,
@@ -273,6 +296,72 @@ where
         return Ok(());
     }
 
+    /// Completes the TLS handshake off the accept loop and then serves the connection like a
+    /// plaintext one.
+    #[allow(clippy::too_many_arguments)]
+    fn handle_tls_connection<T, F, B>(
+        tls: Arc<TlsContext>,
+        stream: tokio::net::TcpStream,
+        remote_peer: SocketAddress,
+        handler: T,
+        http2_max_concurrent_streams: Option<NonZeroU32>,
+        drain: CancellationToken,
+        force_shutdown: CancellationToken,
+        inflight: &mut TaskTracker,
+    ) where
+        F: Send,
+        B: http_body::Body + Send + 'static,
+        <B as http_body::Body>::Data: Send + 'static,
+        <B as http_body::Body>::Error: Into<GenericError>,
+        T: tower::Service<
+                Request<Incoming>,
+                Response = Response<B>,
+                Error = Infallible,
+                Future = F,
+            > + Clone
+            + Send
+            + 'static,
+    {
+        let mut tracker = inflight.clone();
+        inflight.spawn(
+            async move {
+                // Same bound as for the TLS handshakes of the admin listener, so idle sockets
+                // don't linger.
+                let handshake_timeout: Duration =
+                    Configuration::pinned().networking.handshake_timeout.into();
+                let tls_stream = tokio::select! {
+                    res = tokio::time::timeout(handshake_timeout, tls.accept(stream)) => match res {
+                        Ok(Ok(tls_stream)) => tls_stream,
+                        Ok(Err(err)) => {
+                            counter!(HTTP_TLS_HANDSHAKE_FAILED).increment(1);
+                            debug!("TLS handshake failed: {err}");
+                            return;
+                        }
+                        Err(_) => {
+                            counter!(HTTP_TLS_HANDSHAKE_FAILED).increment(1);
+                            debug!("TLS handshake timed out");
+                            return;
+                        }
+                    },
+                    _ = drain.cancelled() => return,
+                };
+
+                if let Err(err) = Self::handle_connection(
+                    tls_stream,
+                    remote_peer,
+                    handler,
+                    http2_max_concurrent_streams,
+                    drain,
+                    force_shutdown,
+                    &mut tracker,
+                ) {
+                    debug!("Error when serving the connection: {err:?}");
+                }
+            }
+            .in_current_tc(),
+        );
+    }
+
     fn handle_connection<S, T, F, B>(
         stream: S,
         remote_peer: SocketAddress,
@@ -517,6 +606,7 @@ mod tests {
             Semaphore::MAX_PERMITS,
             10 * 1024 * 1024, // 10MB
             None,
+            None,
             Live::from_value(mock_schemas()),
             Arc::new(mock_request_dispatcher),
             health.ingress_status(),
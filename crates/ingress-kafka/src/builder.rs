@@ -29,7 +29,9 @@ use restate_types::invocation::{Header, InvocationTarget, ServiceInvocation, Spa
 use restate_types::limit_key::LimitKey;
 use restate_types::live::Live;
 use restate_types::schema::Schema;
-use restate_types::schema::invocation_target::{DeploymentStatus, InvocationTargetResolver};
+use restate_types::schema::invocation_target::{
+    DeploymentStatus, InvocationTargetResolver, TracingSampling,
+};
 use restate_types::schema::subscriptions::{EventInvocationTargetTemplate, Sink, Subscription};
 use restate_util_string::{ReString, RestateString, RestrictedValueError};
 use restate_wal_protocol::{Command, Destination, Envelope, Source};
@@ -317,6 +319,7 @@ impl InvocationBuilder {
         let ingress_span_context = prepare_tracing_span(
             &invocation_id,
             &invocation_target,
+            &target.tracing_sampling,
             &headers,
             consumer_group_id,
             topic,
@@ -352,6 +355,7 @@ struct KafkaPartitionKeySeed<'a> {
 pub(crate) fn prepare_tracing_span(
     invocation_id: &InvocationId,
     invocation_target: &InvocationTarget,
+    tracing_sampling: &TracingSampling,
     headers: &[restate_types::invocation::Header],
     consumer_group_name: &str,
     topic: &str,
@@ -379,6 +383,12 @@ pub(crate) fn prepare_tracing_span(
             messaging.kafka.offset = offset,
             messaging.source.partition.id = partition,
             messaging.source.name = topic.to_owned()
+        ),
+        attributes = restate_tracing_instrumentation::ingress_span_attributes(
+            tracing_sampling,
+            headers
+                .iter()
+                .map(|header| (&*header.name, &*header.value))
         )
     );
 
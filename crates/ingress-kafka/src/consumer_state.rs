@@ -0,0 +1,217 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use rdkafka::Statistics;
+
+use restate_types::identifiers::SubscriptionId;
+
+/// Progress of a single Kafka topic-partition consumed by a subscription on this node.
+#[derive(Debug, Clone)]
+pub struct KafkaConsumerState {
+    pub subscription_id: SubscriptionId,
+    pub consumer_group: String,
+    /// `None` if the consumer of the subscription has no partitions assigned.
+    pub topic: Option<String>,
+    /// `None` if the consumer of the subscription has no partitions assigned.
+    pub partition: Option<i32>,
+    /// Offset of the last message that was ingested into Restate.
+    pub processed_offset: Option<i64>,
+    /// Last offset committed to the consumer group, as reported by librdkafka statistics.
+    pub committed_offset: Option<i64>,
+    /// High watermark of the partition, as reported by librdkafka statistics.
+    pub high_watermark: Option<i64>,
+    /// Number of messages the consumer group is behind the high watermark.
+    pub lag: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct TopicPartitionProgress {
+    processed_offset: Option<i64>,
+    committed_offset: Option<i64>,
+    high_watermark: Option<i64>,
+    lag: Option<i64>,
+    last_error: Option<String>,
+}
+
+#[derive(Debug)]
+struct SubscriptionConsumerState {
+    consumer_group: String,
+    /// Error which made the consumer of the subscription fail as a whole.
+    last_error: Option<String>,
+    partitions: BTreeMap<(String, i32), TopicPartitionProgress>,
+}
+
+/// Registry of the progress of the Kafka consumers running on this node.
+///
+/// Entries are added when a subscription's consumer starts and removed when the subscription
+/// is stopped. Partitions are tracked between their assignment and revocation. Errors are
+/// retained across restarts of a failed consumer, so that they remain visible while the
+/// consumer is backing off.
+#[derive(Debug, Clone, Default)]
+pub struct KafkaConsumerStates {
+    inner: Arc<Mutex<HashMap<SubscriptionId, SubscriptionConsumerState>>>,
+}
+
+impl KafkaConsumerStates {
+    /// Returns a snapshot with one entry per assigned topic-partition. Subscriptions whose
+    /// consumer has no partitions assigned are reported with a single entry without partition.
+    pub fn snapshot(&self) -> Vec<KafkaConsumerState> {
+        let guard = self.inner.lock();
+        let mut result = Vec::with_capacity(guard.len());
+        for (subscription_id, state) in guard.iter() {
+            if state.partitions.is_empty() {
+                result.push(KafkaConsumerState {
+                    subscription_id: *subscription_id,
+                    consumer_group: state.consumer_group.clone(),
+                    topic: None,
+                    partition: None,
+                    processed_offset: None,
+                    committed_offset: None,
+                    high_watermark: None,
+                    lag: None,
+                    last_error: state.last_error.clone(),
+                });
+                continue;
+            }
+
+            for ((topic, partition), progress) in &state.partitions {
+                result.push(KafkaConsumerState {
+                    subscription_id: *subscription_id,
+                    consumer_group: state.consumer_group.clone(),
+                    topic: Some(topic.clone()),
+                    partition: Some(*partition),
+                    processed_offset: progress.processed_offset,
+                    committed_offset: progress.committed_offset,
+                    high_watermark: progress.high_watermark,
+                    lag: progress.lag,
+                    last_error: progress
+                        .last_error
+                        .clone()
+                        .or_else(|| state.last_error.clone()),
+                });
+            }
+        }
+        result
+    }
+
+    pub(crate) fn consumer_started(&self, subscription_id: SubscriptionId, consumer_group: &str) {
+        let mut guard = self.inner.lock();
+        let state = guard
+            .entry(subscription_id)
+            .or_insert_with(|| SubscriptionConsumerState {
+                consumer_group: consumer_group.to_owned(),
+                last_error: None,
+                partitions: BTreeMap::new(),
+            });
+        // partitions are re-assigned to the new consumer
+        state.consumer_group = consumer_group.to_owned();
+        state.partitions.clear();
+    }
+
+    pub(crate) fn remove(&self, subscription_id: SubscriptionId) {
+        self.inner.lock().remove(&subscription_id);
+    }
+
+    pub(crate) fn partition_assigned(
+        &self,
+        subscription_id: SubscriptionId,
+        topic: &str,
+        partition: i32,
+    ) {
+        if let Some(state) = self.inner.lock().get_mut(&subscription_id) {
+            state
+                .partitions
+                .insert((topic.to_owned(), partition), Default::default());
+        }
+    }
+
+    pub(crate) fn partition_revoked(
+        &self,
+        subscription_id: SubscriptionId,
+        topic: &str,
+        partition: i32,
+    ) {
+        if let Some(state) = self.inner.lock().get_mut(&subscription_id) {
+            state.partitions.remove(&(topic.to_owned(), partition));
+        }
+    }
+
+    pub(crate) fn record_processed(
+        &self,
+        subscription_id: SubscriptionId,
+        topic: &str,
+        partition: i32,
+        offset: i64,
+    ) {
+        self.update_partition(subscription_id, topic, partition, |progress| {
+            progress.processed_offset = Some(offset);
+        });
+    }
+
+    pub(crate) fn record_partition_error(
+        &self,
+        subscription_id: SubscriptionId,
+        topic: &str,
+        partition: i32,
+        error: &crate::Error,
+    ) {
+        self.update_partition(subscription_id, topic, partition, |progress| {
+            progress.last_error = Some(error.to_string());
+        });
+    }
+
+    pub(crate) fn record_error(&self, subscription_id: SubscriptionId, error: impl ToString) {
+        if let Some(state) = self.inner.lock().get_mut(&subscription_id) {
+            state.last_error = Some(error.to_string());
+        }
+    }
+
+    pub(crate) fn update_statistics(&self, subscription_id: SubscriptionId, stats: &Statistics) {
+        let mut guard = self.inner.lock();
+        let Some(state) = guard.get_mut(&subscription_id) else {
+            return;
+        };
+
+        for (topic, topic_stats) in &stats.topics {
+            for (partition, partition_stats) in &topic_stats.partitions {
+                // statistics include partitions which aren't assigned to this consumer
+                let Some(progress) = state.partitions.get_mut(&(topic.clone(), *partition)) else {
+                    continue;
+                };
+                // librdkafka reports negative values for unknown offsets
+                progress.committed_offset =
+                    Some(partition_stats.committed_offset).filter(|o| *o >= 0);
+                progress.high_watermark = Some(partition_stats.hi_offset).filter(|o| *o >= 0);
+                progress.lag = Some(partition_stats.consumer_lag).filter(|o| *o >= 0);
+            }
+        }
+    }
+
+    fn update_partition(
+        &self,
+        subscription_id: SubscriptionId,
+        topic: &str,
+        partition: i32,
+        f: impl FnOnce(&mut TopicPartitionProgress),
+    ) {
+        let mut guard = self.inner.lock();
+        if let Some(progress) = guard
+            .get_mut(&subscription_id)
+            .and_then(|state| state.partitions.get_mut(&(topic.to_owned(), partition)))
+        {
+            f(progress);
+        }
+    }
+}
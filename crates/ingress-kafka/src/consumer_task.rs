@@ -42,6 +42,7 @@ use tracing::{debug, instrument, trace, warn};
 
 use crate::Error;
 use crate::builder::EnvelopeBuilder;
+use crate::consumer_state::KafkaConsumerStates;
 use crate::metric_definitions::{KAFKA_INGRESS_CONSUMER_LAG, KAFKA_INGRESS_REQUESTS};
 
 type MessageConsumer<T> = StreamConsumer<RebalanceContext<T>>;
@@ -52,6 +53,7 @@ pub struct ConsumerTask<T> {
     topics: Vec<String>,
     ingestion: IngestionClient<T, Envelope>,
     builder: EnvelopeBuilder,
+    states: KafkaConsumerStates,
 }
 
 impl<T> ConsumerTask<T>
@@ -63,12 +65,14 @@ where
         topics: Vec<String>,
         ingestion: IngestionClient<T, Envelope>,
         builder: EnvelopeBuilder,
+        states: KafkaConsumerStates,
     ) -> Self {
         Self {
             client_config,
             topics,
             ingestion,
             builder,
+            states,
         }
     }
 
@@ -85,6 +89,9 @@ where
             "Starting consumer for topics {:?} with configuration {:?}",
             self.topics, self.client_config
         );
+        let subscription_id = self.builder.subscription().id();
+        self.states
+            .consumer_started(subscription_id, &consumer_group_id);
 
         let (failures_tx, failures_rx) = mpsc::unbounded_channel();
 
@@ -96,6 +103,7 @@ where
             ingestion: self.ingestion.clone(),
             builder: self.builder.clone(),
             consumer_group_id,
+            states: self.states.clone(),
         };
         let consumer: Arc<MessageConsumer<T>> =
             Arc::new(self.client_config.create_with_context(rebalance_context)?);
@@ -111,7 +119,7 @@ where
 
         let mut failures_rx = std::pin::pin!(failures_rx);
 
-        tokio::select! {
+        let result = tokio::select! {
             // we have to poll the main consumer for callbacks to be processed, but we expect to only see messages on the partitioned queues
             res = consumer.recv() => {
                 match res {
@@ -128,7 +136,12 @@ where
             _ = &mut rx => {
                  Ok(())
             }
+        };
+
+        if let Err(err) = &result {
+            self.states.record_error(subscription_id, err);
         }
+        result
     }
 }
 
@@ -170,6 +183,7 @@ struct RebalanceContext<T: TransportConnect> {
     ingestion: IngestionClient<T, Envelope>,
     builder: EnvelopeBuilder,
     consumer_group_id: String,
+    states: KafkaConsumerStates,
 }
 
 impl<T> ClientContext for RebalanceContext<T>
@@ -177,6 +191,8 @@ where
     T: TransportConnect,
 {
     fn stats(&self, statistics: Statistics) {
+        self.states
+            .update_statistics(self.builder.subscription().id(), &statistics);
         for topic in statistics.topics {
             for partition in topic.1.partitions {
                 let lag = partition.1.consumer_lag as f64;
@@ -249,6 +265,7 @@ where
                                 Arc::clone(&consumer),
                                 self.consumer_group_id.clone(),
                                 self.failures_tx.clone(),
+                                self.states.clone(),
                             );
 
                             if let Ok(task_handle) = self.task_center_handle.spawn_unmanaged(
@@ -256,6 +273,11 @@ where
                                 "kafka-partition-ingest",
                                 task.run(),
                             ) {
+                                self.states.partition_assigned(
+                                    self.builder.subscription().id(),
+                                    &partition.0,
+                                    partition.1,
+                                );
                                 topic_partition_tasks.insert(partition, AbortOnDrop(task_handle));
                             } else {
                                 // shutting down
@@ -280,6 +302,11 @@ where
                         partition = %partition.1,
                         "Revoked kafka partition"
                     );
+                    self.states.partition_revoked(
+                        self.builder.subscription().id(),
+                        &partition.0,
+                        partition.1,
+                    );
 
                     match topic_partition_tasks.remove(&partition) {
                         Some(task_id) => {
@@ -331,6 +358,7 @@ where
     consumer: Arc<MessageConsumer<T>>,
     consumer_group_id: String,
     failed: mpsc::UnboundedSender<Error>,
+    states: KafkaConsumerStates,
 }
 
 impl<T, C> TopicPartitionConsumptionTask<T, C>
@@ -346,6 +374,7 @@ where
         consumer: Arc<MessageConsumer<T>>,
         consumer_group_id: String,
         failed: mpsc::UnboundedSender<Error>,
+        states: KafkaConsumerStates,
     ) -> Self {
         Self {
             ingestion,
@@ -355,6 +384,7 @@ where
             consumer,
             consumer_group_id,
             failed,
+            states,
         }
     }
 
@@ -364,11 +394,17 @@ where
             Err(err) => err,
             Ok(_) => Error::UnexpectedConsumptionTaskExited {
                 subscription: self.builder.subscription().id().to_string(),
-                topic: self.topic_partition.0,
+                topic: self.topic_partition.0.clone(),
                 partition: self.topic_partition.1,
             },
         };
 
+        self.states.record_partition_error(
+            self.builder.subscription().id(),
+            &self.topic_partition.0,
+            self.topic_partition.1,
+            &err,
+        );
         _ = self.failed.send(err);
     }
 
@@ -511,6 +547,12 @@ where
                     );
 
                     self.consumer.store_offset(&self.topic_partition.0, self.topic_partition.1, offset)?;
+                    self.states.record_processed(
+                        self.builder.subscription().id(),
+                        &self.topic_partition.0,
+                        self.topic_partition.1,
+                        offset,
+                    );
                 },
                 Some(received) = consumer_stream.next() => {
                     let msg = received?;
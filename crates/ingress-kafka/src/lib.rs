@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 mod builder;
+mod consumer_state;
 mod consumer_task;
 mod metric_definitions;
 mod subscription_controller;
@@ -59,4 +60,5 @@ pub enum Error {
     },
 }
 
+pub use consumer_state::{KafkaConsumerState, KafkaConsumerStates};
 pub use subscription_controller::Service;
@@ -27,6 +27,7 @@ use restate_types::schema::subscriptions::{Source, Subscription};
 
 use super::*;
 use crate::builder::EnvelopeBuilder;
+use crate::consumer_state::KafkaConsumerStates;
 use crate::subscription_controller::task_orchestrator::TaskOrchestrator;
 
 // For simplicity of the current implementation, this currently lives in this module
@@ -34,6 +35,7 @@ use crate::subscription_controller::task_orchestrator::TaskOrchestrator;
 pub struct Service<T> {
     ingestion: IngestionClient<T, Envelope>,
     schema: Live<Schema>,
+    consumer_states: KafkaConsumerStates,
 
     commands_tx: SubscriptionCommandSender,
     commands_rx: SubscriptionCommandReceiver,
@@ -50,6 +52,7 @@ where
         Service {
             ingestion,
             schema,
+            consumer_states: KafkaConsumerStates::default(),
             commands_tx,
             commands_rx,
         }
@@ -59,6 +62,11 @@ where
         self.commands_tx.clone()
     }
 
+    /// Progress of the Kafka consumers run by this service.
+    pub fn consumer_states(&self) -> &KafkaConsumerStates {
+        &self.consumer_states
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         let shutdown = cancellation_watcher();
         tokio::pin!(shutdown);
@@ -101,6 +109,8 @@ where
         let mut client_config = rdkafka::ClientConfig::new();
         // enabling probing for the ca certificates if the user does not specify anything else
         client_config.set("https.ca.location", "probe");
+        // statistics feed the consumer lag metric and the sys_kafka_consumer table
+        client_config.set("statistics.interval.ms", "10000");
 
         let Source::Kafka { topic, .. } = subscription.source();
 
@@ -127,6 +137,7 @@ where
             vec![topic.to_string()],
             self.ingestion.clone(),
             EnvelopeBuilder::new(subscription.clone(), self.schema.clone()),
+            self.consumer_states.clone(),
         );
 
         task_orchestrator.start(subscription_id, consumer_task, kafka_cluster, subscription);
@@ -138,6 +149,7 @@ where
         task_orchestrator: &mut TaskOrchestrator<T>,
     ) {
         task_orchestrator.stop(subscription_id);
+        self.consumer_states.remove(subscription_id);
     }
 
     fn handle_update_subscriptions(
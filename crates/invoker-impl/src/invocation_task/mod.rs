@@ -502,11 +502,13 @@ where
                 )
             } else {
                 // We can choose the freshest deployment for the latest revision
-                // of the registered service.
+                // of the registered service, unless the service has a traffic split.
                 let deployment = shortcircuit!(
                     schemas
-                        .resolve_latest_deployment_for_service(
-                            self.invocation_target.service_name()
+                        .resolve_deployment_for_new_invocation(
+                            self.invocation_target.service_name(),
+                            self.invocation_target.handler_name(),
+                            &self.invocation_id,
                         )
                         .ok_or(InvokerError::NoDeploymentForService)
                 );
@@ -78,8 +78,8 @@ use crate::invocation_state_machine::OnTaskError;
 use crate::invocation_task::InvocationTask;
 use crate::invocation_task::{InvocationTaskOutput, InvocationTaskOutputInner};
 use crate::metric_definitions::{
-    INVOKER_ENQUEUE, INVOKER_INVOCATION_TASKS, TASK_OP_COMPLETED, TASK_OP_FAILED, TASK_OP_STARTED,
-    TASK_OP_SUSPENDED,
+    INVOKER_DEPLOYMENT_INVOCATION_TASKS, INVOKER_ENQUEUE, INVOKER_INVOCATION_TASKS,
+    TASK_OP_COMPLETED, TASK_OP_FAILED, TASK_OP_STARTED, TASK_OP_SUSPENDED,
 };
 use crate::status_store::InvocationStatusStore;
 
@@ -1105,6 +1105,12 @@ where
                 "partition_id" => self.invoker_id_label.clone()
             )
             .increment(1);
+            counter!(
+                INVOKER_DEPLOYMENT_INVOCATION_TASKS,
+                "status" => TASK_OP_COMPLETED,
+                "deployment_id" => ism.attempt_deployment_id().to_string()
+            )
+            .increment(1);
             trace!(
                 restate.invocation.target = %ism.invocation_target,
                 "Invocation task closed correctly");
@@ -1562,6 +1568,12 @@ where
         mut ism: InvocationStateMachine,
     ) {
         let attempt_deployment_id = ism.attempt_deployment_id();
+        counter!(
+            INVOKER_DEPLOYMENT_INVOCATION_TASKS,
+            "status" => TASK_OP_FAILED,
+            "deployment_id" => attempt_deployment_id.to_string()
+        )
+        .increment(1);
 
         // Call handle_task_error with a closure that registers the timer.
         // We need to capture the duration for logging and status updates.
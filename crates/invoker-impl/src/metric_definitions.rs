@@ -12,6 +12,8 @@ use metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
 
 pub const INVOKER_ENQUEUE: &str = "restate.invoker.enqueue.total";
 pub const INVOKER_INVOCATION_TASKS: &str = "restate.invoker.invocation_tasks.total";
+pub const INVOKER_DEPLOYMENT_INVOCATION_TASKS: &str =
+    "restate.invoker.deployment_invocation_tasks.total";
 pub const INVOKER_CONCURRENCY_SLOTS_ACQUIRED: &str = "restate.invoker.concurrency_slots.acquired";
 pub const INVOKER_CONCURRENCY_SLOTS_RELEASED: &str = "restate.invoker.concurrency_slots.released";
 pub const INVOKER_CONCURRENCY_LIMIT: &str = "restate.invoker.concurrency_limit";
@@ -46,6 +48,12 @@ pub(crate) fn describe_metrics() {
         "Invocation task operation"
     );
 
+    describe_counter!(
+        INVOKER_DEPLOYMENT_INVOCATION_TASKS,
+        Unit::Count,
+        "Completed and failed invocation tasks per deployment"
+    );
+
     describe_counter!(
         INVOKER_CONCURRENCY_SLOTS_ACQUIRED,
         Unit::Count,
@@ -0,0 +1,362 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Linearizability checker for histories of operations on registers.
+//!
+//! Workers record every operation with [`History::invoke`] before sending it to the cluster,
+//! and its outcome with [`History::complete`] once they observed it. [`History::check`] then
+//! searches for a linearization of the operations on every register, following the algorithm
+//! of Wing & Gong with the memoization of Lowe. Since linearizability is a local property, the
+//! registers are checked independently of each other.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Operation on a register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation<V> {
+    Read,
+    Write(V),
+}
+
+/// Observed outcome of an [`Operation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<V> {
+    /// The read returned the value, `None` if the register was never written.
+    Read(Option<V>),
+    Written,
+    /// The operation definitely did not take effect, e.g. because it was rejected.
+    Failed,
+    /// It is unknown whether the operation took effect, e.g. because it timed out. Operations
+    /// which are never completed are treated the same way.
+    Unknown,
+}
+
+/// Identifies an invoked operation in its [`History`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationId(usize);
+
+#[derive(Debug, thiserror::Error)]
+#[error("history of register {key} is not linearizable: {operations:#?}")]
+pub struct LinearizabilityError {
+    pub key: String,
+    /// The recorded operations on the register, in the order of their invocation.
+    pub operations: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Entry<K, V> {
+    key: K,
+    operation: Operation<V>,
+    invoked: Instant,
+    completed: Option<(Instant, Outcome<V>)>,
+}
+
+/// Concurrent history of the operations of a workload on a set of registers.
+#[derive(Debug)]
+pub struct History<K, V> {
+    entries: Mutex<Vec<Entry<K, V>>>,
+}
+
+impl<K, V> Default for History<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::default(),
+        }
+    }
+}
+
+impl<K, V> History<K, V>
+where
+    K: Ord + Clone + Debug,
+    V: PartialEq + Debug,
+{
+    /// Record the invocation of an operation. Call this before sending the operation.
+    pub fn invoke(&self, key: K, operation: Operation<V>) -> OperationId {
+        let mut entries = self.entries.lock().unwrap();
+        entries.push(Entry {
+            key,
+            operation,
+            invoked: Instant::now(),
+            completed: None,
+        });
+        OperationId(entries.len() - 1)
+    }
+
+    /// Record the outcome of an operation. Call this after the outcome was observed.
+    pub fn complete(&self, id: OperationId, outcome: Outcome<V>) {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = &mut entries[id.0];
+        assert!(entry.completed.is_none(), "operation completed twice");
+        entry.completed = Some((Instant::now(), outcome));
+    }
+
+    /// Number of operations recorded so far.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check that the history is linearizable, assuming that every register is initially unset.
+    pub fn check(&self) -> Result<(), LinearizabilityError> {
+        let entries = self.entries.lock().unwrap();
+
+        let mut registers: BTreeMap<&K, Vec<&Entry<K, V>>> = BTreeMap::new();
+        for entry in entries.iter() {
+            registers.entry(&entry.key).or_default().push(entry);
+        }
+
+        for (key, entries) in registers {
+            if !is_linearizable(&entries) {
+                return Err(LinearizabilityError {
+                    key: format!("{key:?}"),
+                    operations: entries.iter().map(|entry| describe(entry)).collect(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn describe<K, V: Debug>(entry: &Entry<K, V>) -> String {
+    match &entry.completed {
+        Some((completed, outcome)) => format!(
+            "{:?} -> {outcome:?} in {:?}",
+            entry.operation,
+            completed.duration_since(entry.invoked)
+        ),
+        None => format!("{:?} -> Unknown", entry.operation),
+    }
+}
+
+/// Operation of a single register, prepared for the search.
+struct Op<'a, V> {
+    invoked: Instant,
+    /// `None` if the operation might take effect at any time after its invocation.
+    completed: Option<Instant>,
+    kind: OpKind<'a, V>,
+    /// Whether the operation must be part of the linearization.
+    required: bool,
+}
+
+enum OpKind<'a, V> {
+    Read(Option<&'a V>),
+    Write(&'a V),
+}
+
+fn is_linearizable<K, V: PartialEq>(entries: &[&Entry<K, V>]) -> bool {
+    let mut ops: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| {
+            let completed = entry.completed.as_ref();
+            let kind = match (&entry.operation, completed.map(|(_, outcome)| outcome)) {
+                (_, Some(Outcome::Failed)) => return None,
+                (Operation::Read, Some(Outcome::Read(value))) => OpKind::Read(value.as_ref()),
+                // reads which didn't return a value have no effect
+                (Operation::Read, _) => return None,
+                (Operation::Write(value), _) => OpKind::Write(value),
+            };
+            let required = completed.is_some_and(|(_, outcome)| outcome != &Outcome::Unknown);
+
+            Some(Op {
+                invoked: entry.invoked,
+                completed: completed
+                    .filter(|_| required)
+                    .map(|(completed, _)| *completed),
+                kind,
+                required,
+            })
+        })
+        .collect();
+    ops.sort_by_key(|op| op.invoked);
+
+    let required = ops.iter().filter(|op| op.required).count();
+
+    /// State of the search: the linearized operations and the index of the write which set
+    /// the current value of the register.
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct State {
+        linearized: Vec<bool>,
+        value: Option<usize>,
+    }
+
+    struct Frame {
+        state: State,
+        required: usize,
+        /// Earliest completion of the operations which are not linearized yet.
+        horizon: Option<Instant>,
+        next_candidate: usize,
+    }
+
+    let horizon = |linearized: &[bool]| {
+        ops.iter()
+            .zip(linearized)
+            .filter(|(_, linearized)| !**linearized)
+            .filter_map(|(op, _)| op.completed)
+            .min()
+    };
+
+    let initial = State {
+        linearized: vec![false; ops.len()],
+        value: None,
+    };
+    let mut visited = HashSet::from([initial.clone()]);
+    let mut stack = vec![Frame {
+        horizon: horizon(&initial.linearized),
+        state: initial,
+        required: 0,
+        next_candidate: 0,
+    }];
+
+    while let Some(frame) = stack.last_mut() {
+        if frame.required == required {
+            return true;
+        }
+
+        // an operation can be linearized next if no other pending operation completed before
+        // it was invoked
+        let candidate = (frame.next_candidate..ops.len())
+            .take_while(|&candidate| frame.horizon.is_none_or(|h| ops[candidate].invoked <= h))
+            .find(|&candidate| !frame.state.linearized[candidate]);
+        let Some(candidate) = candidate else {
+            stack.pop();
+            continue;
+        };
+        frame.next_candidate = candidate + 1;
+
+        let value = match ops[candidate].kind {
+            OpKind::Write(_) => Some(candidate),
+            OpKind::Read(read) => {
+                let current = frame.state.value.map(|write| match ops[write].kind {
+                    OpKind::Write(value) => value,
+                    OpKind::Read(_) => unreachable!("register values are set by writes"),
+                });
+                if current != read {
+                    continue;
+                }
+                frame.state.value
+            }
+        };
+
+        let mut state = frame.state.clone();
+        state.linearized[candidate] = true;
+        state.value = value;
+
+        if visited.insert(state.clone()) {
+            let required = frame.required + usize::from(ops[candidate].required);
+            stack.push(Frame {
+                horizon: horizon(&state.linearized),
+                state,
+                required,
+                next_candidate: 0,
+            });
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    fn op(history: &History<&'static str, u32>, operation: Operation<u32>, outcome: Outcome<u32>) {
+        let id = history.invoke("key", operation);
+        history.complete(id, outcome);
+        // make sure that the next operation is invoked after this one completed
+        sleep(Duration::from_millis(1));
+    }
+
+    #[test]
+    fn sequential_history() {
+        let history = History::default();
+        op(&history, Operation::Read, Outcome::Read(None));
+        op(&history, Operation::Write(1), Outcome::Written);
+        op(&history, Operation::Read, Outcome::Read(Some(1)));
+        op(&history, Operation::Write(2), Outcome::Written);
+        op(&history, Operation::Read, Outcome::Read(Some(2)));
+
+        assert!(history.check().is_ok());
+    }
+
+    #[test]
+    fn stale_read() {
+        let history = History::default();
+        op(&history, Operation::Write(1), Outcome::Written);
+        op(&history, Operation::Write(2), Outcome::Written);
+        op(&history, Operation::Read, Outcome::Read(Some(1)));
+
+        let err = history.check().unwrap_err();
+        assert_eq!(err.key, "\"key\"");
+        assert_eq!(err.operations.len(), 3);
+    }
+
+    #[test]
+    fn concurrent_operations() {
+        let history = History::default();
+        let write_1 = history.invoke("key", Operation::Write(1));
+        let write_2 = history.invoke("key", Operation::Write(2));
+        let read_2 = history.invoke("key", Operation::Read);
+        history.complete(read_2, Outcome::Read(Some(2)));
+        let read_1 = history.invoke("key", Operation::Read);
+        history.complete(read_1, Outcome::Read(Some(1)));
+        history.complete(write_2, Outcome::Written);
+        history.complete(write_1, Outcome::Written);
+
+        // write 2, read 2, write 1, read 1
+        assert!(history.check().is_ok());
+
+        sleep(Duration::from_millis(1));
+        op(&history, Operation::Read, Outcome::Read(Some(2)));
+
+        // read 2 can't be linearized after read 1, since write 2 can't happen twice
+        assert!(history.check().is_err());
+    }
+
+    #[test]
+    fn failed_and_unknown_writes() {
+        let history = History::default();
+        op(&history, Operation::Write(1), Outcome::Written);
+        op(&history, Operation::Write(2), Outcome::Failed);
+        op(&history, Operation::Read, Outcome::Read(Some(1)));
+        op(&history, Operation::Write(3), Outcome::Unknown);
+        op(&history, Operation::Read, Outcome::Read(Some(1)));
+        // the unknown write might take effect later
+        op(&history, Operation::Read, Outcome::Read(Some(3)));
+
+        assert!(history.check().is_ok());
+
+        op(&history, Operation::Read, Outcome::Read(Some(2)));
+
+        // the failed write must not take effect
+        assert!(history.check().is_err());
+    }
+
+    #[test]
+    fn registers_are_independent() {
+        let history = History::default();
+        let write_a = history.invoke("a", Operation::Write(1));
+        history.complete(write_a, Outcome::Written);
+        sleep(Duration::from_millis(1));
+        let read_b = history.invoke("b", Operation::Read);
+        history.complete(read_b, Outcome::Read(None));
+
+        assert!(history.check().is_ok());
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Fault injection for Jepsen-style tests of local clusters.
+//!
+//! A cluster started with chaos enabled routes all node-to-node fabric traffic through proxies,
+//! one per pair of nodes and direction, which can drop connections between specific nodes and
+//! delay the traffic on them. Nodes advertise their fabric address as a path relative to the
+//! working directory of the connecting node, which is its base dir, so that every node reaches
+//! every other node through a proxy of its own. Together with pausing nodes ([`StartedNode::pause`]), filling their disks
+//! and skewing their clocks, this allows to test the behaviour of the cluster under faults.
+//! Use [`linearizability::History`] to record the operations of a workload running against the
+//! cluster and to check that the observed history is linearizable.
+//!
+//! [`StartedNode::pause`]: crate::node::StartedNode::pause
+
+pub mod linearizability;
+mod proxy;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::net::UnixListener;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use restate_types::net::address::{AdvertisedAddress, FabricPort, ListenerPort};
+
+/// Environment variable to set the path of `libfaketime.so.1`, which is needed to skew clocks.
+const LIBFAKETIME_ENV: &str = "LOCAL_CLUSTER_RUNNER_LIBFAKETIME";
+const LIBFAKETIME_PATHS: &[&str] = &[
+    "/usr/lib/x86_64-linux-gnu/faketime/libfaketime.so.1",
+    "/usr/lib/aarch64-linux-gnu/faketime/libfaketime.so.1",
+    "/usr/lib/faketime/libfaketime.so.1",
+    "/usr/local/lib/faketime/libfaketime.so.1",
+];
+/// Directory in the base dir of a node with the sockets of the proxies to the other nodes.
+const PROXY_DIR: &str = "chaos-fabric";
+const FAKETIME_FILE: &str = "chaos-faketime";
+const BALLAST_FILE: &str = "chaos-ballast";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChaosError {
+    #[error("Failed to start the fabric proxy of link {0}: {1}")]
+    StartProxy(String, io::Error),
+    #[error("Node {0} was not started with chaos enabled")]
+    UnknownNode(String),
+    #[error(
+        "Skewing clocks requires libfaketime, set {LIBFAKETIME_ENV} to the path of \
+        libfaketime.so.1"
+    )]
+    LibfaketimeNotFound,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Injects faults into the nodes of a cluster which was started with chaos enabled.
+pub struct Chaos {
+    base_dir: PathBuf,
+    libfaketime: Option<PathBuf>,
+    faults: watch::Sender<NetworkFaults>,
+    proxies: Mutex<Proxies>,
+}
+
+#[derive(Default)]
+struct Proxies {
+    nodes: BTreeSet<String>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Chaos {
+    pub(crate) fn new(base_dir: &Path) -> Self {
+        let libfaketime = std::env::var_os(LIBFAKETIME_ENV)
+            .map(PathBuf::from)
+            .or_else(|| {
+                LIBFAKETIME_PATHS
+                    .iter()
+                    .map(PathBuf::from)
+                    .find(|path| path.exists())
+            });
+        if libfaketime.is_none() {
+            warn!("libfaketime was not found, skewing clocks of nodes won't be supported");
+        }
+
+        Self {
+            base_dir: base_dir.to_owned(),
+            libfaketime,
+            faults: watch::Sender::new(NetworkFaults::default()),
+            proxies: Mutex::default(),
+        }
+    }
+
+    /// Starts the fabric proxies between the node and all registered nodes, including itself,
+    /// if the node isn't registered yet, and returns the address nodes should use to connect
+    /// to it.
+    pub(crate) fn register_node(
+        &self,
+        node_name: &str,
+    ) -> Result<AdvertisedAddress<FabricPort>, ChaosError> {
+        let mut proxies = self.proxies.lock().unwrap();
+        if proxies.nodes.insert(node_name.to_owned()) {
+            for other in proxies.nodes.clone() {
+                let task = self.start_proxy(Link::new(&other, node_name))?;
+                proxies.tasks.push(task);
+                if other != node_name {
+                    let task = self.start_proxy(Link::new(node_name, &other))?;
+                    proxies.tasks.push(task);
+                }
+            }
+        }
+
+        Ok(self.fabric_address(node_name))
+    }
+
+    /// Starts the proxy through which `link.from` connects to the fabric of `link.to`.
+    fn start_proxy(&self, link: Link) -> Result<JoinHandle<()>, ChaosError> {
+        let proxy_dir = self.node_dir(&link.from).join(PROXY_DIR);
+        let proxy_socket = proxy_dir.join(proxy_socket_name(&link.to));
+
+        let listener = std::fs::create_dir_all(&proxy_dir)
+            .and_then(|()| match std::fs::remove_file(&proxy_socket) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => UnixListener::bind(&proxy_socket),
+            })
+            .map_err(|err| ChaosError::StartProxy(link.to_string(), err))?;
+
+        let target = self.node_dir(&link.to).join(FabricPort::UDS_NAME);
+        Ok(tokio::spawn(proxy::run(
+            link,
+            listener,
+            target,
+            self.faults.subscribe(),
+        )))
+    }
+
+    /// Address of the fabric of the node, relative to the base dir of the connecting node. It
+    /// resolves to the proxy through which the connecting node reaches the node.
+    pub(crate) fn fabric_address(&self, node_name: &str) -> AdvertisedAddress<FabricPort> {
+        AdvertisedAddress::new_uds(Path::new(PROXY_DIR).join(proxy_socket_name(node_name)))
+    }
+
+    /// Environment variables which preload libfaketime into the node process, if available.
+    /// The clock of the node follows the offset in its faketime file, which is checked once
+    /// per second. Monotonic clocks are not affected.
+    pub(crate) fn faketime_env(&self, node_name: &str) -> io::Result<Vec<(String, String)>> {
+        let Some(libfaketime) = &self.libfaketime else {
+            return Ok(Vec::new());
+        };
+
+        let faketime_file = self.node_dir(node_name).join(FAKETIME_FILE);
+        if !faketime_file.exists() {
+            std::fs::write(&faketime_file, "+0")?;
+        }
+
+        Ok(vec![
+            ("LD_PRELOAD".to_owned(), libfaketime.display().to_string()),
+            (
+                "FAKETIME_TIMESTAMP_FILE".to_owned(),
+                faketime_file.display().to_string(),
+            ),
+            ("FAKETIME_CACHE_DURATION".to_owned(), "1".to_owned()),
+            ("FAKETIME_DONT_FAKE_MONOTONIC".to_owned(), "1".to_owned()),
+        ])
+    }
+
+    /// Drop all connections `from` opens to `to`, and refuse new ones. Connections which `to`
+    /// opens to `from` are not affected.
+    pub fn block(&self, from: &str, to: &str) {
+        info!("Blocking connections from node {from} to node {to}");
+        self.faults.send_modify(|faults| {
+            faults.blocked.insert(Link::new(from, to));
+        });
+    }
+
+    /// Drop all connections between the nodes on either side, in both directions.
+    pub fn partition(&self, side_a: &[&str], side_b: &[&str]) {
+        info!("Partitioning nodes {side_a:?} from nodes {side_b:?}");
+        self.faults.send_modify(|faults| {
+            for a in side_a {
+                for b in side_b {
+                    faults.blocked.insert(Link::new(a, b));
+                    faults.blocked.insert(Link::new(b, a));
+                }
+            }
+        });
+    }
+
+    /// Drop all connections between the node and every other node of the cluster.
+    pub fn isolate(&self, node_name: &str) {
+        let others: Vec<_> = self
+            .proxies
+            .lock()
+            .unwrap()
+            .nodes
+            .iter()
+            .filter(|name| *name != node_name)
+            .cloned()
+            .collect();
+        let others: Vec<_> = others.iter().map(String::as_str).collect();
+        self.partition(&[node_name], &others);
+    }
+
+    /// Delay the traffic between two nodes by `latency`, in both directions.
+    pub fn delay(&self, a: &str, b: &str, latency: Duration) {
+        info!("Delaying traffic between node {a} and node {b} by {latency:?}");
+        self.faults.send_modify(|faults| {
+            faults.latency.insert(Link::new(a, b), latency);
+            faults.latency.insert(Link::new(b, a), latency);
+        });
+    }
+
+    /// Remove all network faults.
+    pub fn heal(&self) {
+        info!("Healing all network faults");
+        self.faults.send_replace(NetworkFaults::default());
+    }
+
+    /// Allocate a ballast file in the base dir of the node, which takes up all but `keep_free`
+    /// bytes of the available space of its file system. Returns the size of the ballast.
+    ///
+    /// Consider placing the base dir on a size-limited file system, such as a tmpfs.
+    pub fn fill_disk(&self, node_name: &str, keep_free: u64) -> Result<u64, ChaosError> {
+        self.free_disk(node_name)?;
+
+        let node_dir = self.node_dir(node_name);
+        if !node_dir.exists() {
+            return Err(ChaosError::UnknownNode(node_name.to_owned()));
+        }
+
+        let stat = nix::sys::statvfs::statvfs(node_dir.as_path()).map_err(io::Error::from)?;
+        #[allow(clippy::unnecessary_cast)]
+        let available = stat.blocks_available() as u64 * stat.fragment_size() as u64;
+        let size = available.saturating_sub(keep_free);
+
+        info!("Filling disk of node {node_name} with {size} bytes");
+        let ballast = std::fs::File::create(node_dir.join(BALLAST_FILE))?;
+        if size > 0 {
+            allocate(&ballast, size)?;
+        }
+
+        Ok(size)
+    }
+
+    /// Remove the ballast file allocated by [`Chaos::fill_disk`].
+    pub fn free_disk(&self, node_name: &str) -> Result<(), ChaosError> {
+        match std::fs::remove_file(self.node_dir(node_name).join(BALLAST_FILE)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Offset the wall clock of the node by `offset_millis`, which might be negative. The offset
+    /// is picked up by the node within a second, and stays in place across restarts. Use an
+    /// offset of zero to reset the clock.
+    pub fn skew_clock(&self, node_name: &str, offset_millis: i64) -> Result<(), ChaosError> {
+        if self.libfaketime.is_none() {
+            return Err(ChaosError::LibfaketimeNotFound);
+        }
+
+        let faketime_file = self.node_dir(node_name).join(FAKETIME_FILE);
+        if !faketime_file.exists() {
+            return Err(ChaosError::UnknownNode(node_name.to_owned()));
+        }
+
+        info!("Skewing clock of node {node_name} by {offset_millis}ms");
+        let sign = if offset_millis < 0 { '-' } else { '+' };
+        let offset = offset_millis.unsigned_abs();
+        std::fs::write(
+            faketime_file,
+            format!("{sign}{}.{:03}", offset / 1000, offset % 1000),
+        )?;
+
+        Ok(())
+    }
+
+    fn node_dir(&self, node_name: &str) -> PathBuf {
+        self.base_dir.join(node_name)
+    }
+}
+
+impl Drop for Chaos {
+    fn drop(&mut self) {
+        for proxy in &self.proxies.get_mut().unwrap().tasks {
+            proxy.abort();
+        }
+    }
+}
+
+fn proxy_socket_name(node_name: &str) -> String {
+    format!("{node_name}.sock")
+}
+
+#[cfg(target_os = "linux")]
+fn allocate(file: &std::fs::File, size: u64) -> io::Result<()> {
+    let size = i64::try_from(size).map_err(io::Error::other)?;
+    nix::fcntl::posix_fallocate(file, 0, size).map_err(io::Error::from)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn allocate(_file: &std::fs::File, _size: u64) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "filling disks is only supported on Linux",
+    ))
+}
+
+/// Direction of a connection between two nodes, from the node which opened it to the node
+/// which accepted it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, derive_more::Display)]
+#[display("{from} -> {to}")]
+struct Link {
+    from: String,
+    to: String,
+}
+
+impl Link {
+    fn new(from: &str, to: &str) -> Self {
+        Self {
+            from: from.to_owned(),
+            to: to.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct NetworkFaults {
+    blocked: HashSet<Link>,
+    latency: HashMap<Link, Duration>,
+}
+
+impl NetworkFaults {
+    fn is_blocked(&self, link: &Link) -> bool {
+        self.blocked.contains(link)
+    }
+
+    fn latency(&self, link: &Link) -> Duration {
+        self.latency.get(link).copied().unwrap_or_default()
+    }
+}
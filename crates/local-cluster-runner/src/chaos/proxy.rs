@@ -0,0 +1,140 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Proxy in front of the fabric socket of a node which applies the [`NetworkFaults`] to the
+//! connections another node opens to it. Every pair of nodes has a dedicated proxy per
+//! direction, so the proxy knows the [`Link`] of every connection it accepts.
+
+use std::io;
+use std::path::PathBuf;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use super::{Link, NetworkFaults};
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+pub(super) async fn run(
+    link: Link,
+    listener: UnixListener,
+    target: PathBuf,
+    faults: watch::Receiver<NetworkFaults>,
+) {
+    // dropping the set aborts the open connections once the proxy is stopped
+    let mut connections = JoinSet::new();
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                match accepted {
+                    Ok((stream, _)) => {
+                        connections.spawn(proxy_connection(
+                            link.clone(),
+                            stream,
+                            target.clone(),
+                            faults.clone(),
+                        ));
+                    }
+                    Err(err) => {
+                        warn!("Fabric proxy of link {link} failed to accept connection: {err}");
+                    }
+                }
+            }
+            Some(_) = connections.join_next() => {}
+        }
+    }
+}
+
+async fn proxy_connection(
+    link: Link,
+    inbound: UnixStream,
+    target: PathBuf,
+    mut faults: watch::Receiver<NetworkFaults>,
+) {
+    let is_blocked = |faults: &NetworkFaults| faults.is_blocked(&link);
+
+    if is_blocked(&faults.borrow_and_update()) {
+        // refuse the connection by closing it right away
+        return;
+    }
+
+    let outbound = match UnixStream::connect(&target).await {
+        Ok(outbound) => outbound,
+        Err(err) => {
+            debug!(
+                "Fabric proxy failed to connect to {}: {err}",
+                target.display()
+            );
+            return;
+        }
+    };
+
+    let (inbound_read, inbound_write) = inbound.into_split();
+    let (outbound_read, outbound_write) = outbound.into_split();
+
+    let forward = futures::future::try_join(
+        forward(inbound_read, outbound_write, &link, faults.clone()),
+        forward(outbound_read, inbound_write, &link, faults.clone()),
+    );
+    let blocked = async {
+        while faults.changed().await.is_ok() {
+            if is_blocked(&faults.borrow_and_update()) {
+                return;
+            }
+        }
+        futures::future::pending().await
+    };
+
+    tokio::select! {
+        _ = forward => {}
+        () = blocked => {
+            debug!("Dropping fabric connection {link}");
+        }
+    }
+}
+
+/// Copies the bytes from the reader to the writer, delaying every chunk by the latency of the
+/// link at the time it was read.
+async fn forward(
+    mut reader: OwnedReadHalf,
+    mut writer: OwnedWriteHalf,
+    link: &Link,
+    faults: watch::Receiver<NetworkFaults>,
+) -> io::Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let read = async move {
+        let mut buf = vec![0; BUFFER_SIZE];
+        loop {
+            let len = reader.read(&mut buf).await?;
+            if len == 0 {
+                return io::Result::Ok(());
+            }
+            let latency = faults.borrow().latency(link);
+            // the writer stops only if the connection is gone
+            let _ = tx.send((Instant::now() + latency, buf[..len].to_vec()));
+        }
+    };
+    let write = async move {
+        while let Some((deadline, chunk)) = rx.recv().await {
+            tokio::time::sleep_until(deadline).await;
+            writer.write_all(&chunk).await?;
+        }
+        writer.shutdown().await
+    };
+
+    futures::future::try_join(read, write).await.map(drop)
+}
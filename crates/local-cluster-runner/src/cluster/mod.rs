@@ -30,7 +30,9 @@ use restate_types::{
 };
 use restate_types::{errors::GenericError, nodes_config::Role};
 
+use crate::chaos::Chaos;
 use crate::node::{HealthCheck, HealthError, NodeSpec, NodeStartError, StartedNode};
+use crate::tls::{CertificateError, TestCertificates};
 
 #[derive(Debug, Serialize, Deserialize, TypedBuilder)]
 pub struct Cluster {
@@ -41,20 +43,30 @@ pub struct Cluster {
     #[builder(setter(into), default = default_base_dir())]
     #[serde(default = "default_base_dir")]
     base_dir: MaybeTempDir,
+    /// Enable mutual TLS on the node-to-node fabric. A throwaway cluster CA is generated in
+    /// `<base-dir>/tls` and every node gets a certificate issued for its node name.
+    #[builder(default)]
+    #[serde(default)]
+    fabric_tls: bool,
+    /// Enable fault injection via [`StartedCluster::chaos`]. The fabric traffic between nodes
+    /// is routed through proxies, and libfaketime is preloaded into the nodes if available.
+    #[builder(default)]
+    #[serde(default)]
+    chaos: bool,
 }
 
-impl<C, N> ClusterBuilder<(C, N, ())> {
+impl<C, N, T, X> ClusterBuilder<(C, N, (), T, X)> {
     /// Use a tempdir as the basedir; this will be removed on Cluster/StartedCluster drop.
     /// You may set LOCAL_CLUSTER_RUNNER_RETAIN_TEMPDIR=true to instead log it out and retain
     /// it, and use LOCAL_CLUSTER_RUNNER_TEMPDIR to set the base dir.
     /// dir_name is the subdirectory's name inside the base dir, and used as node's base dir.
-    pub fn temp_base_dir(self, dir_name: &str) -> ClusterBuilder<(C, N, (MaybeTempDir,))> {
+    pub fn temp_base_dir(self, dir_name: &str) -> ClusterBuilder<(C, N, (MaybeTempDir,), T, X)> {
         let maybe_temp_dir = MaybeTempDir::new(&dir_name);
 
         let base_dir = (maybe_temp_dir,);
-        let (cluster_name, nodes, ()) = self.fields;
+        let (cluster_name, nodes, (), fabric_tls, chaos) = self.fields;
         ClusterBuilder {
-            fields: (cluster_name, nodes, base_dir),
+            fields: (cluster_name, nodes, base_dir, fabric_tls, chaos),
             phantom: self.phantom,
         }
     }
@@ -80,6 +92,20 @@ pub enum ClusterStartError {
     CreateMetadataClient(GenericError),
     #[error("Clusters must have at least one node")]
     NoNodes,
+    #[error("Failed to generate fabric TLS certificates: {0}")]
+    Certificates(#[from] CertificateError),
+}
+
+/// Address other nodes use to connect to the fabric of the node.
+fn fabric_address(
+    base_dir: &Path,
+    chaos: Option<&Chaos>,
+    node_name: &str,
+) -> AdvertisedAddress<FabricPort> {
+    match chaos {
+        Some(chaos) => chaos.fabric_address(node_name),
+        None => AdvertisedAddress::with_node_base_dir(&base_dir.join(node_name)),
+    }
 }
 
 impl Cluster {
@@ -88,7 +114,9 @@ impl Cluster {
         let Self {
             cluster_name,
             base_dir,
-            nodes,
+            mut nodes,
+            fabric_tls,
+            chaos,
         } = self;
 
         if nodes.is_empty() {
@@ -100,6 +128,14 @@ impl Cluster {
                 .map_err(ClusterStartError::CreateDirectory)?;
         }
 
+        let certificates = if fabric_tls {
+            Some(TestCertificates::generate(base_dir.as_path().join("tls"))?)
+        } else {
+            None
+        };
+
+        let chaos = chaos.then(|| Chaos::new(base_dir.as_path()));
+
         let mut started_nodes = Vec::with_capacity(nodes.len());
 
         info!(
@@ -114,16 +150,25 @@ impl Cluster {
         let metadata_server_addresses: Vec<_> = nodes
             .iter()
             .filter_map(|node| {
-                node.has_role(Role::MetadataServer).then_some(
-                    AdvertisedAddress::with_node_base_dir(
-                        &base_dir.as_path().join(node.node_name()),
-                    ),
-                )
+                node.has_role(Role::MetadataServer)
+                    .then(|| fabric_address(base_dir.as_path(), chaos.as_ref(), node.node_name()))
             })
             .collect();
 
+        // register all nodes upfront, so that the proxies between them exist once they start
+        if let Some(chaos) = &chaos {
+            for (i, node) in nodes.iter_mut().enumerate() {
+                node.set_chaos(chaos)
+                    .map_err(|err| ClusterStartError::NodeStartError(i, err.into()))?;
+            }
+        }
+
         for (i, mut node) in nodes.into_iter().enumerate() {
             node.set_metadata_servers(&metadata_server_addresses);
+            if let Some(certificates) = &certificates {
+                node.set_fabric_tls(certificates)
+                    .map_err(|err| ClusterStartError::NodeStartError(i, err.into()))?;
+            }
             let node = node
                 .start_clustered(base_dir.as_path(), &cluster_name)
                 .await
@@ -135,6 +180,8 @@ impl Cluster {
             clock_guard,
             cluster_name,
             base_dir,
+            certificates,
+            chaos,
             nodes: started_nodes,
         })
     }
@@ -145,6 +192,8 @@ pub struct StartedCluster {
     clock_guard: restate_clock::ClockUpkeep,
     cluster_name: String,
     base_dir: MaybeTempDir,
+    certificates: Option<TestCertificates>,
+    chaos: Option<Chaos>,
     pub nodes: Vec<StartedNode>,
 }
 
@@ -153,13 +202,13 @@ impl StartedCluster {
         self.base_dir.as_path()
     }
 
+    /// Fabric addresses of the metadata servers, as seen by the nodes of the cluster.
     pub fn collect_metadata_server_addresses(&self) -> Vec<AdvertisedAddress<FabricPort>> {
         self.nodes
             .iter()
             .filter_map(|node| {
-                node.has_role(Role::MetadataServer).then_some(
-                    AdvertisedAddress::with_node_base_dir(&self.base_dir().join(node.node_name())),
-                )
+                node.has_role(Role::MetadataServer)
+                    .then(|| fabric_address(self.base_dir(), self.chaos.as_ref(), node.node_name()))
             })
             .collect()
     }
@@ -168,6 +217,17 @@ impl StartedCluster {
         &self.cluster_name
     }
 
+    /// The cluster CA, if the cluster was started with fabric TLS enabled. Use it to issue
+    /// certificates for additional clients, such as `restatectl`.
+    pub fn certificates(&self) -> Option<&TestCertificates> {
+        self.certificates.as_ref()
+    }
+
+    /// The fault injector, if the cluster was started with chaos enabled.
+    pub fn chaos(&self) -> Option<&Chaos> {
+        self.chaos.as_ref()
+    }
+
     /// Send a SIGKILL to every node in the cluster
     pub async fn kill(&mut self) -> io::Result<()> {
         future::try_join_all(self.nodes.iter_mut().map(|n| n.kill()))
@@ -245,6 +305,12 @@ impl StartedCluster {
     pub async fn expand(&mut self, mut node: NodeSpec) -> Result<(), NodeStartError> {
         let addresses = self.collect_metadata_server_addresses();
         node.set_metadata_servers(&addresses);
+        if let Some(certificates) = &self.certificates {
+            node.set_fabric_tls(certificates)?;
+        }
+        if let Some(chaos) = &self.chaos {
+            node.set_chaos(chaos)?;
+        }
         self.nodes.push(
             node.start_clustered(self.base_dir.as_path(), self.cluster_name.clone())
                 .await?,
@@ -22,8 +22,10 @@ use rand::RngExt;
 use tokio::signal::unix::SignalKind;
 use tracing::info;
 
+pub mod chaos;
 pub mod cluster;
 pub mod node;
+pub mod tls;
 
 /// Used to store marker files of "used" ports to avoid conflicts
 ///
@@ -71,6 +71,9 @@ use restate_types::{
     nodes_config::{NodesConfiguration, Role},
 };
 
+use crate::chaos::{Chaos, ChaosError};
+use crate::tls::{CertificateError, TestCertificates};
+
 #[derive(Debug, Clone, Serialize, Deserialize, TypedBuilder)]
 pub struct NodeSpec {
     #[builder(mutators(
@@ -100,6 +103,11 @@ pub struct NodeSpec {
     /// like SIGINT directly.
     #[builder(default = false)]
     isolate_process_group: bool,
+    /// If true, the process runs in the base dir of the node, against which relative socket
+    /// paths, such as the fabric addresses of a chaos-enabled cluster, are resolved.
+    #[builder(default = false)]
+    #[serde(default)]
+    run_in_node_dir: bool,
     #[builder(default)]
     #[serde(skip)]
     searcher: Searcher,
@@ -123,6 +131,10 @@ pub enum NodeStartError {
     DumpConfig(GenericError),
     #[error("Failed to spawn restate-server: {0}")]
     SpawnError(io::Error),
+    #[error("Failed to issue fabric TLS certificate: {0}")]
+    Certificates(#[from] CertificateError),
+    #[error("Failed to prepare node for fault injection: {0}")]
+    Chaos(#[from] ChaosError),
 }
 
 impl NodeSpec {
@@ -195,6 +207,32 @@ impl NodeSpec {
         nodes
     }
 
+    /// Enables mutual TLS on the node-to-node fabric using a certificate issued for this node.
+    pub fn set_fabric_tls(
+        &mut self,
+        certificates: &TestCertificates,
+    ) -> Result<(), CertificateError> {
+        let tls = certificates.issue(self.node_name())?;
+        self.base_config.networking.tls = Some(tls.clone());
+        self.base_config.common.metadata_client.tls = Some(tls);
+        Ok(())
+    }
+
+    /// Routes the fabric traffic between this node and the other nodes through the proxies of
+    /// `chaos`, and preloads libfaketime if available, so that faults can be injected into the
+    /// node.
+    pub fn set_chaos(&mut self, chaos: &Chaos) -> Result<(), ChaosError> {
+        let node_name = self.node_name().to_owned();
+        let fabric_address = chaos.register_node(&node_name)?;
+        self.base_config
+            .common
+            .set_fabric_advertised_address(fabric_address);
+
+        self.run_in_node_dir = true;
+        self.env.extend(chaos.faketime_env(&node_name)?);
+        Ok(())
+    }
+
     pub fn set_metadata_servers(&mut self, all_servers: &[AdvertisedAddress<FabricPort>]) {
         if let MetadataClientKind::Replicated { addresses } =
             &mut self.base_config.common.metadata_client.kind
@@ -212,7 +250,8 @@ impl NodeSpec {
         base_dir: impl Into<PathBuf>,
         cluster_name: impl Into<String>,
     ) -> Result<StartedNode, NodeStartError> {
-        let base_dir = base_dir.into();
+        // the process might not run in the working directory of the harness
+        let base_dir = std::path::absolute(base_dir.into()).map_err(NodeStartError::Absolute)?;
 
         self.base_config.common.set_base_dir(base_dir);
         self.base_config.common.set_cluster_name(cluster_name);
@@ -230,6 +269,7 @@ impl NodeSpec {
             inherit_env,
             env,
             isolate_process_group,
+            run_in_node_dir,
             searcher,
         } = &self;
 
@@ -303,6 +343,10 @@ impl NodeSpec {
             cmd.process_group(0);
         }
 
+        if *run_in_node_dir {
+            cmd.current_dir(&node_base_dir);
+        }
+
         let mut child = cmd.spawn().map_err(NodeStartError::SpawnError)?;
         let pid = child.id().expect("child to have a pid");
 
@@ -558,6 +602,39 @@ impl StartedNode {
         }
     }
 
+    /// Send a SIGSTOP to the current process, if it is running. The node stops responding
+    /// until it is resumed, while its connections stay open.
+    pub fn pause(&self) -> io::Result<()> {
+        self.signal(nix::sys::signal::SIGSTOP)
+    }
+
+    /// Send a SIGCONT to the current process, if it is running, to resume it after a pause.
+    pub fn resume(&self) -> io::Result<()> {
+        self.signal(nix::sys::signal::SIGCONT)
+    }
+
+    fn signal(&self, signal: nix::sys::signal::Signal) -> io::Result<()> {
+        match self.status {
+            StartedNodeStatus::Exited(_) => Ok(()),
+            StartedNodeStatus::Failed(kind) => Err(kind.into()),
+            StartedNodeStatus::Running { pid, .. } => {
+                info!(
+                    "Sending {signal} to node {} (pid {})",
+                    self.node_name(),
+                    pid
+                );
+                match nix::sys::signal::kill(
+                    nix::unistd::Pid::from_raw(pid.try_into().expect("pid_t = i32")),
+                    signal,
+                ) {
+                    // ignore "no such process"
+                    Err(nix::errno::Errno::ESRCH) | Ok(()) => Ok(()),
+                    Err(errno) => Err(io::Error::from_raw_os_error(errno as i32)),
+                }
+            }
+        }
+    }
+
     /// Send a SIGTERM to the current process, if it is running
     pub fn terminate(&self) -> io::Result<()> {
         match self.status {
@@ -578,7 +655,8 @@ impl StartedNode {
                         Ok(())
                     }
                     Err(errno) => Err(io::Error::from_raw_os_error(errno as i32)),
-                    _ => Ok(()),
+                    // a paused node handles the SIGTERM only once it is resumed
+                    _ => self.resume(),
                 }
             }
         }
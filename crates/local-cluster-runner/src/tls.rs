@@ -0,0 +1,109 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa,
+    KeyPair, KeyUsagePurpose, SanType,
+};
+
+use restate_types::config::FabricTlsOptions;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CertificateError {
+    #[error("failed to generate certificate: {0}")]
+    Generate(#[from] rcgen::Error),
+    #[error("failed to write certificate: {0}")]
+    Write(#[from] io::Error),
+}
+
+/// A throwaway cluster CA that issues node certificates for testing fabric mutual TLS.
+///
+/// All files are written as PEM into the given directory: `ca.pem` for the CA certificate and
+/// `<node-name>.pem`/`<node-name>.key` for every issued node certificate.
+pub struct TestCertificates {
+    dir: PathBuf,
+    ca_cert: Certificate,
+    ca_key: KeyPair,
+}
+
+impl TestCertificates {
+    pub fn generate(dir: impl Into<PathBuf>) -> Result<Self, CertificateError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        let mut params = CertificateParams::new(Vec::<String>::new())?;
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Restate local cluster CA");
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![
+            KeyUsagePurpose::KeyCertSign,
+            KeyUsagePurpose::CrlSign,
+            KeyUsagePurpose::DigitalSignature,
+        ];
+        let ca_key = KeyPair::generate()?;
+        let ca_cert = params.self_signed(&ca_key)?;
+        std::fs::write(dir.join("ca.pem"), ca_cert.pem())?;
+
+        Ok(Self {
+            dir,
+            ca_cert,
+            ca_key,
+        })
+    }
+
+    pub fn ca_cert_path(&self) -> PathBuf {
+        self.dir.join("ca.pem")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Issues a certificate for the given node and returns the matching fabric TLS options. The
+    /// certificate is valid for the node name (which peers verify during the handshake) as well
+    /// as for `localhost` and the loopback addresses.
+    pub fn issue(&self, node_name: &str) -> Result<FabricTlsOptions, CertificateError> {
+        let mut params =
+            CertificateParams::new(vec![node_name.to_owned(), "localhost".to_owned()])?;
+        params
+            .distinguished_name
+            .push(DnType::CommonName, node_name);
+        params.subject_alt_names.extend([
+            SanType::IpAddress(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            SanType::IpAddress(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+        ]);
+        params.key_usages = vec![
+            KeyUsagePurpose::DigitalSignature,
+            KeyUsagePurpose::KeyEncipherment,
+        ];
+        params.extended_key_usages = vec![
+            ExtendedKeyUsagePurpose::ServerAuth,
+            ExtendedKeyUsagePurpose::ClientAuth,
+        ];
+
+        let key = KeyPair::generate()?;
+        let cert = params.signed_by(&key, &self.ca_cert, &self.ca_key)?;
+
+        let options = FabricTlsOptions::new(
+            self.ca_cert_path(),
+            self.dir.join(format!("{node_name}.pem")),
+            self.dir.join(format!("{node_name}.key")),
+        );
+        std::fs::write(&options.cert_path, cert.pem())?;
+        std::fs::write(&options.key_path, key.serialize_pem())?;
+
+        Ok(options)
+    }
+}
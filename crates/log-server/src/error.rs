@@ -10,6 +10,9 @@
 
 use codederror::CodedError;
 
+use std::path::PathBuf;
+
+use restate_types::config::LogStoreBackend;
 use restate_types::errors::GenericError;
 
 #[derive(Debug, thiserror::Error, CodedError)]
@@ -17,6 +20,18 @@ pub enum LogServerBuildError {
     #[error("unknown")]
     #[code(unknown)]
     Unknown,
+    #[error(
+        "log-server.storage-backend is '{configured:?}', but the data directory {} of the \
+        '{other:?}' backend exists. Data is not migrated between backends, remove the \
+        directory to switch the backend",
+        data_dir.display()
+    )]
+    #[code(unknown)]
+    StorageBackendMismatch {
+        configured: LogStoreBackend,
+        other: LogStoreBackend,
+        data_dir: PathBuf,
+    },
     #[error("failed building log server: {0}")]
     #[code(unknown)]
     Other(GenericError),
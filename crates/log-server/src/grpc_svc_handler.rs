@@ -82,6 +82,7 @@ where
             },
             from_offset: request.from_offset.into(),
             to_offset: request.to_offset.into(),
+            verify_checksums: request.verify_checksums,
         };
 
         let digest = self
@@ -29,6 +29,10 @@ pub mod protobuf;
 pub mod rocksdb_logstore;
 #[cfg(not(feature = "expose-internals"))]
 mod rocksdb_logstore;
+#[cfg(feature = "expose-internals")]
+pub mod segment_logstore;
+#[cfg(not(feature = "expose-internals"))]
+mod segment_logstore;
 mod service;
 mod tasks;
 
@@ -10,11 +10,12 @@
 
 use std::time::Duration;
 
+use bytes::BytesMut;
 use metrics::counter;
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::WatchStream;
-use tracing::{debug, info, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
 use restate_clock::WallClock;
 use restate_clock::time::MillisSinceEpoch;
@@ -37,6 +38,7 @@ use crate::metric_definitions::{
     LOG_SERVER_LOGLET_STARTED, LOG_SERVER_LOGLET_STOPPED, LOG_SERVER_STORE_BYTES,
     LOG_SERVER_STORE_RECORDS,
 };
+use crate::rocksdb_logstore::record_format::encode_records;
 use crate::tasks::{
     OnComplete, SealStorageTask, StoreStorageTask, SyncGlobalTailStorageTask, TrimStorageTask,
 };
@@ -92,6 +94,8 @@ pub struct LogletWorker<S: LogStore> {
     pending: PendingWaiters,
     last_request: MillisSinceEpoch,
     last_periodically_synced_global_tail: LogletOffset,
+    /// Scratch buffer to encode records into their on-disk format
+    encode_scratch: BytesMut,
 }
 
 impl<S: LogStore> LogletWorker<S> {
@@ -119,6 +123,7 @@ impl<S: LogStore> LogletWorker<S> {
             pending: PendingWaiters::default(),
             last_request: WallClock::recent_ms(),
             last_periodically_synced_global_tail: global_tail,
+            encode_scratch: BytesMut::new(),
         };
 
         let (data_tx, data_rx) = ShardSender::new();
@@ -547,6 +552,23 @@ impl<S: LogStore> LogletWorker<S> {
             }
         }
 
+        // Encode before enqueueing, so that a store whose records can't be sealed is rejected on
+        // its own without advancing the staging local tail. The sequencer retries it.
+        let records = match encode_records(&body.payloads, &mut self.encode_scratch) {
+            Ok(records) => records,
+            Err(err) => {
+                error!(
+                    loglet_id = %self.loglet_id,
+                    "Cannot encode records [{}..{}] for storage, dropping the store: {err}",
+                    body.first_offset,
+                    last_offset,
+                );
+                update_store_stats(count, bytes, "dropped");
+                task.on_complete(local_tail, known_global_tail, Status::Dropped);
+                return;
+            }
+        };
+
         // Remember the sequencer if this is the first store we've seen.
         if self.known_sequencer.is_none() {
             self.known_sequencer = Some(body.sequencer);
@@ -558,7 +580,7 @@ impl<S: LogStore> LogletWorker<S> {
         // tail watch after durable commit.
         if self
             .writer
-            .enqueue_store(body.first_offset, last_offset, body.payloads, task)
+            .enqueue_store(body.first_offset, last_offset, records, task)
         {
             // Advance staging_local_tail on successful enqueue so we don't advance it artificially
             self.staging_local_tail = std::cmp::max(self.staging_local_tail, last_offset.next());
@@ -956,7 +978,6 @@ mod tests {
 
     use super::*;
     use googletest::prelude::*;
-    use test_log::test;
 
     use restate_core::{MetadataBuilder, TaskCenter};
     use restate_rocksdb::RocksDbManager;
@@ -964,10 +985,11 @@ mod tests {
 
     use crate::metadata::LogletStateMap;
     use crate::rocksdb_logstore::{RocksDbLogStore, RocksDbLogStoreBuilder};
+    use crate::segment_logstore::{SegmentLogStore, SegmentLogStoreBuilder};
 
     use super::LogletWorker;
 
-    async fn setup() -> Result<RocksDbLogStore> {
+    async fn setup_rocksdb() -> Result<RocksDbLogStore> {
         RocksDbManager::init();
         let metadata_builder = MetadataBuilder::default();
         assert!(TaskCenter::try_set_global_metadata(
@@ -978,9 +1000,66 @@ mod tests {
         Ok(builder.start(Default::default()).await?)
     }
 
-    #[test(restate_core::test(start_paused = true))]
-    async fn simple_store_flow() -> Result<()> {
-        let log_store = setup().await?;
+    async fn setup_segment_file() -> Result<SegmentLogStore> {
+        let metadata_builder = MetadataBuilder::default();
+        assert!(TaskCenter::try_set_global_metadata(
+            metadata_builder.to_metadata()
+        ));
+        // create logstore.
+        let builder = SegmentLogStoreBuilder::create().await?;
+        Ok(builder.start(Default::default()).await?)
+    }
+
+    /// Runs each of the given tests against every log-store backend
+    macro_rules! log_store_tests {
+        ($($name:ident),* $(,)?) => {
+            mod rocksdb {
+                use googletest::prelude::*;
+                use test_log::test;
+
+                use restate_core::TaskCenter;
+                use restate_rocksdb::RocksDbManager;
+
+                $(
+                    #[test(restate_core::test(start_paused = true))]
+                    async fn $name() -> Result<()> {
+                        let log_store = super::setup_rocksdb().await?;
+                        super::$name(log_store).await?;
+                        TaskCenter::shutdown_node("test completed", 0).await;
+                        RocksDbManager::get().shutdown().await;
+                        Ok(())
+                    }
+                )*
+            }
+
+            mod segment_file {
+                use googletest::prelude::*;
+                use test_log::test;
+
+                use restate_core::TaskCenter;
+
+                $(
+                    #[test(restate_core::test(start_paused = true))]
+                    async fn $name() -> Result<()> {
+                        let log_store = super::setup_segment_file().await?;
+                        super::$name(log_store).await?;
+                        TaskCenter::shutdown_node("test completed", 0).await;
+                        Ok(())
+                    }
+                )*
+            }
+        };
+    }
+
+    log_store_tests!(
+        simple_store_flow,
+        store_and_seal,
+        repair_store,
+        simple_get_records_flow,
+        trim_basics,
+    );
+
+    async fn simple_store_flow<S: LogStore>(log_store: S) -> Result<()> {
         const SEQUENCER: GenerationalNodeId = GenerationalNodeId::new(1, 1);
         const LOGLET: LogletId = LogletId::new_unchecked(1);
         let loglet_state_map = LogletStateMap::default();
@@ -1035,15 +1114,10 @@ mod tests {
         assert_that!(stored.status, eq(Status::Ok));
         assert_that!(stored.local_tail, eq(LogletOffset::new(5)));
 
-        TaskCenter::shutdown_node("test completed", 0).await;
-        RocksDbManager::get().shutdown().await;
-
         Ok(())
     }
 
-    #[test(restate_core::test(start_paused = true))]
-    async fn store_and_seal() -> Result<()> {
-        let log_store = setup().await?;
+    async fn store_and_seal<S: LogStore>(log_store: S) -> Result<()> {
         const SEQUENCER: GenerationalNodeId = GenerationalNodeId::new(1, 1);
         const LOGLET: LogletId = LogletId::new_unchecked(1);
         let loglet_state_map = LogletStateMap::default();
@@ -1156,14 +1230,10 @@ mod tests {
         assert_that!(info.trim_point, eq(LogletOffset::INVALID));
         assert_that!(info.sealed, eq(true));
 
-        TaskCenter::shutdown_node("test completed", 0).await;
-        RocksDbManager::get().shutdown().await;
         Ok(())
     }
 
-    #[test(restate_core::test(start_paused = true))]
-    async fn repair_store() -> Result<()> {
-        let log_store = setup().await?;
+    async fn repair_store<S: LogStore>(log_store: S) -> Result<()> {
         const SEQUENCER: GenerationalNodeId = GenerationalNodeId::new(1, 1);
         const PEER: GenerationalNodeId = GenerationalNodeId::new(2, 2);
         const LOGLET: LogletId = LogletId::new_unchecked(1);
@@ -1297,14 +1367,10 @@ mod tests {
         assert_that!(info.local_tail, eq(LogletOffset::new(18)));
         assert_that!(info.trim_point, eq(LogletOffset::INVALID));
         assert_that!(info.sealed, eq(true));
-        TaskCenter::shutdown_node("test completed", 0).await;
-        RocksDbManager::get().shutdown().await;
         Ok(())
     }
 
-    #[test(restate_core::test(start_paused = true))]
-    async fn simple_get_records_flow() -> Result<()> {
-        let log_store = setup().await?;
+    async fn simple_get_records_flow<S: LogStore>(log_store: S) -> Result<()> {
         const SEQUENCER: GenerationalNodeId = GenerationalNodeId::new(1, 1);
         const LOGLET: LogletId = LogletId::new_unchecked(1);
         let loglet_state_map = LogletStateMap::default();
@@ -1494,15 +1560,10 @@ mod tests {
             }
         }
 
-        TaskCenter::shutdown_node("test completed", 0).await;
-        RocksDbManager::get().shutdown().await;
-
         Ok(())
     }
 
-    #[test(restate_core::test(start_paused = true))]
-    async fn trim_basics() -> Result<()> {
-        let log_store = setup().await?;
+    async fn trim_basics<S: LogStore>(log_store: S) -> Result<()> {
         const SEQUENCER: GenerationalNodeId = GenerationalNodeId::new(1, 1);
         const LOGLET: LogletId = LogletId::new_unchecked(1);
         let loglet_state_map = LogletStateMap::default();
@@ -1680,8 +1741,6 @@ mod tests {
         assert_that!(loglet_state.trim_point(), eq(LogletOffset::new(6)));
         assert_that!(loglet_state.local_tail().offset(), eq(LogletOffset::new(7)));
 
-        TaskCenter::shutdown_node("test completed", 0).await;
-        RocksDbManager::get().shutdown().await;
         Ok(())
     }
 }
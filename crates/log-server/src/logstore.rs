@@ -17,9 +17,10 @@ use tokio::sync::SetOnce;
 use restate_bifrost::loglet::OperationError;
 use restate_types::health::{HealthStatus, LogServerStatus};
 use restate_types::logs::{LogletId, LogletOffset};
-use restate_types::net::log_server::{Digest, GetDigest, GetRecords, Payloads, Records};
+use restate_types::net::log_server::{Digest, GetDigest, GetRecords, Records};
 
 use crate::metadata::{LogStoreMarker, LogletState};
+use crate::rocksdb_logstore::record_format::EncodedRecord;
 use crate::tasks::{SealStorageTask, StoreStorageTask, SyncGlobalTailStorageTask, TrimStorageTask};
 
 pub type Result<T, E = OperationError> = std::result::Result<T, E>;
@@ -153,14 +154,15 @@ pub trait LogStore: Clone + Send + 'static {
 /// corresponding field on [`LogletState`] (local-tail watch for stores,
 /// seal flag for seals, trim-point watch for trims) or the store state.
 pub trait LogletWriter: Send + 'static {
-    /// Enqueues a store batch for writing. The writer derives the committed
-    /// offset from the store message and advances the registered loglet's tail
-    /// watch after the batch is durably committed.
+    /// Enqueues a store batch for writing. The records are already encoded in their on-disk
+    /// format, so that a record which can't be encoded fails its store before it is enqueued.
+    /// The writer derives the committed offset from the store message and advances the
+    /// registered loglet's tail watch after the batch is durably committed.
     fn enqueue_store(
         &mut self,
         first_offset: LogletOffset,
         last_offset: LogletOffset,
-        payloads: Payloads,
+        records: Vec<EncodedRecord>,
         task: StoreStorageTask,
     ) -> bool;
 
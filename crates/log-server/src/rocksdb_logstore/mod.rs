@@ -21,12 +21,13 @@ mod metadata_merge;
 #[cfg(feature = "expose-internals")]
 pub mod record_format;
 #[cfg(not(feature = "expose-internals"))]
-mod record_format;
+pub(crate) mod record_format;
 mod store;
 mod writer;
 
 pub use self::builder::RocksDbLogStoreBuilder;
 pub use self::store::RocksDbLogStore;
+pub(crate) use self::store::block_in_place;
 pub(crate) use error::*;
 
 pub const DATA_CF: &str = "data";
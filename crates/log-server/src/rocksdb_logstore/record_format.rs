@@ -12,9 +12,11 @@ use bitflags::bitflags;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use restate_clock::UniqueTimestamp;
+use restate_encryption::{EncryptionError, KeyRing};
 use restate_types::logs::{KeyFilter, Keys, MatchKeyQuery, Record};
 use restate_types::storage::PolyBytes;
 use restate_types::time::NanosSinceEpoch;
+use xxhash_rust::xxh3::xxh3_64;
 
 #[derive(Debug, derive_more::TryFrom, Eq, PartialEq, Ord, PartialOrd)]
 #[try_from(repr)]
@@ -38,15 +40,33 @@ bitflags! {
         /// This allows future versions to add extra header fields without breaking backwards
         /// compatibility.
         const ExtendedHeader = 0b00000000_00000010;
+        /// The payload is sealed with the node's data encryption keys (see
+        /// [`restate_encryption::KeyRing`]). Header fields and keys stay in plaintext so that
+        /// filtering and trimming work without decrypting records.
+        const Encrypted = 0b00000000_00000100;
+        /// The extended header starts with an xxh3 checksum (u64) of the payload as stored on
+        /// disk (after sealing, if encrypted). Requires `ExtendedHeader`.
+        const Checksum = 0b00000000_00001000;
     }
 }
 
+/// Size of the extended header written by this version, it only holds the payload checksum.
+const EXTENDED_HEADER_SIZE: u8 = size_of::<u64>() as u8;
+
 #[derive(Debug, thiserror::Error)]
 #[error("Record decode error: {0}")]
 pub enum RecordDecodeError {
     UnsupportedFormatVersion(u8),
     InvalidRecordTimestamp(#[from] restate_clock::Error),
     UnsupportedKeyStyle(u8),
+    Encryption(#[from] EncryptionError),
+    #[error("record header is truncated")]
+    Truncated,
+    #[error("checksum mismatch, expected {expected:#018x} but payload hashes to {actual:#018x}")]
+    ChecksumMismatch {
+        expected: u64,
+        actual: u64,
+    },
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, derive_more::TryFrom)]
@@ -84,6 +104,15 @@ impl<'a> DataRecordDecoder<'a> {
         self.buffer.len()
     }
 
+    /// Verifies the payload against the checksum stored in the record header. Records written
+    /// before checksums were introduced carry none and always pass.
+    pub fn verify_checksum(&self) -> Result<(), RecordDecodeError> {
+        let mut buf = self.buffer;
+        let (flags, _) = read_flags_and_created_at(&mut buf)?;
+        read_extended_header(flags, &mut buf)?;
+        Ok(())
+    }
+
     /// Reads the record's `created_at` timestamp without consuming the decoder.
     #[cfg(any(test, feature = "test-util"))]
     pub fn created_at(&self) -> Result<NanosSinceEpoch, RecordDecodeError> {
@@ -91,21 +120,43 @@ impl<'a> DataRecordDecoder<'a> {
         read_flags_and_created_at(&mut buf).map(|(_, created_at)| created_at)
     }
 
-    pub fn decode(mut self) -> Result<Record, RecordDecodeError> {
-        let (flags, created_at) = read_flags_and_created_at(&mut self.buffer)?;
+    pub fn decode(self) -> Result<Record, RecordDecodeError> {
+        self.decode_with_key_ring(restate_encryption::key_ring().as_deref())
+    }
 
-        if flags.contains(RecordFlags::ExtendedHeader) {
-            let extra_header_bytes = self.buffer.get_u8();
-            // We skip the extra header bytes since we don't know how to decode them.
-            self.buffer.advance(extra_header_bytes as usize);
-        }
+    fn decode_with_key_ring(
+        mut self,
+        key_ring: Option<&KeyRing>,
+    ) -> Result<Record, RecordDecodeError> {
+        let (flags, created_at) = read_flags_and_created_at(&mut self.buffer)?;
+        read_extended_header(flags, &mut self.buffer)?;
 
-        let body = PolyBytes::Bytes(Bytes::copy_from_slice(self.buffer.chunk()));
+        let body = if flags.contains(RecordFlags::Encrypted) {
+            let key_ring = key_ring.ok_or(EncryptionError::NotConfigured)?;
+            PolyBytes::Bytes(Bytes::from(key_ring.open(self.buffer.chunk())?))
+        } else {
+            PolyBytes::Bytes(Bytes::copy_from_slice(self.buffer.chunk()))
+        };
 
         Ok(Record::from_parts(created_at, self.keys, body))
     }
 }
 
+/// A record in its on-disk format, as produced by [`DataRecordEncoder::encode_to_disk_format`].
+pub type EncodedRecord = bytes::buf::Chain<Bytes, Bytes>;
+
+/// Encodes the records of a store into their on-disk format. Fails if any of the records can't
+/// be sealed, in which case none of them must be stored.
+pub fn encode_records(
+    payloads: &[Record],
+    scratch: &mut BytesMut,
+) -> Result<Vec<EncodedRecord>, EncryptionError> {
+    payloads
+        .iter()
+        .map(|payload| DataRecordEncoder::from(payload).encode_to_disk_format(scratch))
+        .collect()
+}
+
 #[derive(derive_more::From)]
 pub struct DataRecordEncoder<'a>(&'a Record);
 
@@ -121,13 +172,34 @@ impl DataRecordEncoder<'_> {
     ///    [2 bytes]       Flags (see `RecordFlags` enum)
     ///    [8 bytes]       `created_at` timestamp
     ///    [1 byte]        [If Flags::ExtendedHeader] The number of extra bytes occupied by future header fields.
+    ///      * [8 bytes]   [If Flags::Checksum] xxh3 checksum of the payload
     ///    [...]           Additional header fields
-    ///    [remaining]     Serialized Payload
+    ///    [remaining]     Serialized Payload, sealed if Flags::Encrypted is set
     #[tracing::instrument(skip_all)]
-    pub fn encode_to_disk_format(self, scratch: &mut BytesMut) -> bytes::buf::Chain<Bytes, Bytes> {
-        // header is 1 + 1 + 8 + 8 + 2 + 8 = 28 bytes at most
-        scratch.reserve(self.header_size());
+    pub fn encode_to_disk_format(
+        self,
+        scratch: &mut BytesMut,
+    ) -> Result<bytes::buf::Chain<Bytes, Bytes>, EncryptionError> {
+        self.encode_with_key_ring(scratch, restate_encryption::key_ring().as_deref())
+    }
+
+    fn encode_with_key_ring(
+        self,
+        scratch: &mut BytesMut,
+        key_ring: Option<&KeyRing>,
+    ) -> Result<bytes::buf::Chain<Bytes, Bytes>, EncryptionError> {
         let (created_at, body, keys) = (self.0.created_at(), self.0.body(), self.0.keys());
+        // body first, the header carries its checksum
+        let body_bytes = body
+            .encode_to_bytes(scratch)
+            .expect("Encoding is infallible");
+        let body_bytes = match key_ring {
+            Some(key_ring) => Bytes::from(key_ring.seal(&body_bytes)?),
+            None => body_bytes,
+        };
+
+        // header is 1 + 1 + 8 + 8 + 2 + 8 + 1 + 8 = 37 bytes at most
+        scratch.reserve(self.header_size());
 
         // Write the format version
         scratch.put_u8(RecordFormat::CustomV1 as u8);
@@ -150,18 +222,20 @@ impl DataRecordEncoder<'_> {
             }
         }
         // flags
-        let flags = RecordFlags::default();
+        let mut flags = RecordFlags::ExtendedHeader | RecordFlags::Checksum;
+        if key_ring.is_some() {
+            flags |= RecordFlags::Encrypted;
+        }
         scratch.put_u16_le(flags.bits());
 
         // created_at
         scratch.put_u64_le(created_at.as_u64());
+        // extended header
+        scratch.put_u8(EXTENDED_HEADER_SIZE);
+        scratch.put_u64_le(xxh3_64(&body_bytes));
         let header_bytes = scratch.split().freeze();
-        // body
-        let body_bytes = body
-            .encode_to_bytes(scratch)
-            .expect("Encoding is infallible");
 
-        header_bytes.chain(body_bytes)
+        Ok(header_bytes.chain(body_bytes))
     }
 
     pub const fn header_size(&self) -> usize {
@@ -182,7 +256,9 @@ impl DataRecordEncoder<'_> {
         // flags
         size_of::<u16>() +
         // created_at
-        size_of::<u64>()
+        size_of::<u64>() +
+        // extended header length and checksum
+        size_of::<u8>() + EXTENDED_HEADER_SIZE as usize
     }
 }
 
@@ -200,6 +276,34 @@ fn read_flags_and_created_at<B: Buf>(
     Ok((flags, created_at))
 }
 
+// Skips over the extended header (if any), verifying the payload checksum when the header
+// carries one. On return, the buffer is positioned at the payload.
+fn read_extended_header(flags: RecordFlags, buf: &mut &[u8]) -> Result<(), RecordDecodeError> {
+    if !flags.contains(RecordFlags::ExtendedHeader) {
+        return Ok(());
+    }
+    let extra_header_bytes = buf.get_u8() as usize;
+    let bytes = *buf;
+    if bytes.len() < extra_header_bytes {
+        return Err(RecordDecodeError::Truncated);
+    }
+    let (mut extended_header, payload) = bytes.split_at(extra_header_bytes);
+    *buf = payload;
+
+    if flags.contains(RecordFlags::Checksum) {
+        if extended_header.len() < size_of::<u64>() {
+            return Err(RecordDecodeError::Truncated);
+        }
+        let expected = extended_header.get_u64_le();
+        let actual = xxh3_64(payload);
+        if expected != actual {
+            return Err(RecordDecodeError::ChecksumMismatch { expected, actual });
+        }
+    }
+    // We skip the remaining extra header bytes since we don't know how to decode them.
+    Ok(())
+}
+
 // Reads KeyStyle and extract the keys from the buffer
 fn read_keys<B: Buf>(buf: &mut B) -> Result<Keys, RecordDecodeError> {
     let key_style = buf.get_u8();
@@ -360,4 +464,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn encrypted_record_roundtrip() -> googletest::Result<()> {
+        let key_ring = KeyRing::new(
+            restate_encryption::KeyId(1),
+            [(restate_encryption::KeyId(1), [1u8; 32])],
+        )?;
+        let record = Record::from_parts(
+            NanosSinceEpoch::from(1_000_000_000),
+            Keys::Single(42),
+            PolyBytes::Bytes(Bytes::from_static(b"secret payload")),
+        );
+
+        let mut scratch = BytesMut::new();
+        let mut encoded =
+            DataRecordEncoder::from(&record).encode_with_key_ring(&mut scratch, Some(&key_ring))?;
+        let encoded = encoded.copy_to_bytes(encoded.remaining());
+        assert!(
+            !encoded
+                .windows(b"secret payload".len())
+                .any(|w| w == b"secret payload")
+        );
+
+        // keys are readable without the key ring
+        let decoder = DataRecordDecoder::new(&encoded)?;
+        assert_that!(decoder.matches_key_query(&KeyFilter::Include(42)), eq(true));
+        assert!(matches!(
+            decoder.decode_with_key_ring(None),
+            Err(RecordDecodeError::Encryption(
+                EncryptionError::NotConfigured
+            ))
+        ));
+
+        let decoded = DataRecordDecoder::new(&encoded)?.decode_with_key_ring(Some(&key_ring))?;
+        assert_that!(decoded.keys(), eq(&Keys::Single(42)));
+        let body_bytes = decoded
+            .body()
+            .encode_to_bytes(&mut BytesMut::new())
+            .unwrap();
+        assert_that!(body_bytes.as_ref(), eq(b"secret payload".as_slice()));
+        Ok(())
+    }
+
+    #[test]
+    fn checksum_detects_corruption() -> googletest::Result<()> {
+        let record = Record::from_parts(
+            NanosSinceEpoch::from(1_000_000_000),
+            Keys::Single(42),
+            PolyBytes::Bytes(Bytes::from_static(b"test payload")),
+        );
+
+        let mut scratch = BytesMut::new();
+        let mut encoded =
+            DataRecordEncoder::from(&record).encode_with_key_ring(&mut scratch, None)?;
+        let encoded = encoded.copy_to_bytes(encoded.remaining());
+
+        let decoder = DataRecordDecoder::new(&encoded)?;
+        assert_that!(decoder.verify_checksum(), ok(anything()));
+        let decoded = decoder.decode()?;
+        assert_that!(decoded.keys(), eq(&Keys::Single(42)));
+
+        // flip a bit in the payload
+        let mut corrupted = encoded.to_vec();
+        *corrupted.last_mut().unwrap() ^= 0x01;
+
+        let decoder = DataRecordDecoder::new(&corrupted)?;
+        assert!(matches!(
+            decoder.verify_checksum(),
+            Err(RecordDecodeError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            decoder.decode(),
+            Err(RecordDecodeError::ChecksumMismatch { .. })
+        ));
+        Ok(())
+    }
 }
@@ -8,11 +8,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::cmp::Ordering;
 use std::sync::Arc;
 
 use rocksdb::{BoundColumnFamily, DB, ReadOptions, WriteBatch, WriteOptions};
-use tracing::{error, trace};
+use tracing::{error, trace, warn};
 
 use restate_bifrost::loglet::OperationError;
 use restate_rocksdb::{IoMode, Priority, RocksDb};
@@ -21,12 +20,12 @@ use restate_types::config::Configuration;
 use restate_types::logs::{LogletId, LogletOffset, SequenceNumber, TailState};
 use restate_types::net::log_server::{
     Digest, DigestEntry, Gap, GetDigest, GetRecords, LogServerResponseHeader, MaybeRecord,
-    Payloads, RecordStatus, Records, Status,
+    RecordStatus, Records, Status,
 };
 use restate_types::protobuf::common::LogServerStatus;
 
 use super::keys::{KeyPrefixKind, MARKER_KEY, MetadataKey};
-use super::record_format::DataRecordDecoder;
+use super::record_format::{DataRecordDecoder, EncodedRecord};
 use super::writer::RocksDbLogWriterHandle;
 use super::{DATA_CF, METADATA_CF, RocksDbLogStoreError};
 
@@ -103,13 +102,13 @@ impl LogletWriter for RocksDbLogletWriter {
         &mut self,
         first_offset: LogletOffset,
         last_offset: LogletOffset,
-        payloads: Payloads,
+        records: Vec<EncodedRecord>,
         task: StoreStorageTask,
     ) -> bool {
         match self.inner {
             LogletWriterState::Active {
                 ref writer_handle, ..
-            } => writer_handle.enqueue_put_records(first_offset, last_offset, payloads, task),
+            } => writer_handle.enqueue_put_records(first_offset, last_offset, records, task),
             LogletWriterState::Disabled => false,
         }
     }
@@ -547,45 +546,51 @@ impl LogStore for RocksDbLogStore {
                 let loaded_key =
                     DataRecordKey::from_slice(&mut iterator.key().expect("log record exists"));
                 let offset = loaded_key.offset();
-                match offset.cmp(&read_pointer) {
-                    Ordering::Greater => {
-                        // We found a record but it's beyond what we expect as next (local gap)
-                        // close the entry if we had one
+                if offset < read_pointer {
+                    panic!("Bad logic, iterator is going backward!");
+                }
+                if offset > read_to {
+                    // We found a record but it's beyond the range we are interested in.
+                    break;
+                }
+
+                let status = if msg.verify_checksums {
+                    let value = iterator.value().expect("log record exists");
+                    match DataRecordDecoder::new(value).and_then(|d| d.verify_checksum()) {
+                        Ok(()) => RecordStatus::Exists,
+                        Err(err) => {
+                            warn!(
+                                %loglet_id,
+                                %offset,
+                                %err,
+                                "Record failed checksum verification"
+                            );
+                            RecordStatus::Corrupted
+                        }
+                    }
+                } else {
+                    RecordStatus::Exists
+                };
+
+                match current_open_entry.as_mut() {
+                    // extend the open entry if this record is the next one and has the same status
+                    Some(open) if offset == read_pointer && open.status == status => {
+                        open.to_offset = offset;
+                    }
+                    _ => {
+                        // We found a record that's beyond what we expect as next (local gap), or
+                        // its status differs. Close the entry if we had one and open a new one.
                         if let Some(open) = current_open_entry.take() {
                             entries.push(open);
                         }
-                        if offset > read_to {
-                            // we are done.
-                            break;
-                        }
-
-                        // open a new entry
                         current_open_entry = Some(DigestEntry {
                             from_offset: offset,
                             to_offset: offset,
-                            status: RecordStatus::Exists,
+                            status,
                         });
-                        // reset the pointer
-                        read_pointer = offset.next();
-                    }
-                    Ordering::Equal => {
-                        if let Some(open) = current_open_entry.as_mut() {
-                            open.to_offset = read_pointer;
-                        } else {
-                            // open a new entry
-                            current_open_entry = Some(DigestEntry {
-                                from_offset: offset,
-                                to_offset: offset,
-                                status: RecordStatus::Exists,
-                            });
-                        }
-                        read_pointer = read_pointer.next();
-                    }
-                    Ordering::Less => {
-                        // offset < read_pointer?
-                        panic!("Bad logic, iterator is going backward!");
                     }
                 }
+                read_pointer = offset.next();
                 iterator.next();
             }
 
@@ -625,22 +630,28 @@ where
 
 #[cfg(test)]
 mod tests {
+    use bytes::BytesMut;
     use googletest::prelude::*;
     use test_log::test;
 
-    use restate_core::TaskCenter;
+    use restate_core::network::ServiceMessage;
+    use restate_core::{MetadataBuilder, TaskCenter};
     use restate_memory::MemoryLease;
     use restate_rocksdb::RocksDbManager;
-    use restate_types::logs::{LogletId, LogletOffset, Record, SequenceNumber};
+    use restate_types::logs::{KeyFilter, LogletId, LogletOffset, Record, SequenceNumber};
     use restate_types::net::log_server::{
-        DigestEntry, GetDigest, LogServerRequestHeader, RecordStatus, Status, Store, StoreFlags,
+        Digest, DigestEntry, GetDigest, GetRecords, LogServerRequestHeader, RecordStatus, Records,
+        Seal, Sealed, Status, Store, StoreFlags, Stored,
     };
     use restate_types::{GenerationalNodeId, PlainNodeId};
 
     use super::RocksDbLogStore;
+    use crate::loglet_worker::{LogletWorker, LogletWorkerHandle};
     use crate::logstore::{LogStore, LogletWriter};
-    use crate::metadata::{LogStoreMarker, LogletState};
+    use crate::metadata::{LogStoreMarker, LogletState, LogletStateMap};
     use crate::rocksdb_logstore::RocksDbLogStoreBuilder;
+    use crate::rocksdb_logstore::keys::DataRecordKey;
+    use crate::rocksdb_logstore::record_format::encode_records;
     use crate::tasks::StoreStorageTask;
 
     async fn setup() -> Result<RocksDbLogStore> {
@@ -679,7 +690,8 @@ mod tests {
             writer.enqueue_store(
                 store_msg.first_offset,
                 store_msg.last_offset().unwrap(),
-                store_msg.payloads,
+                encode_records(&store_msg.payloads, &mut BytesMut::new())
+                    .expect("records can be encoded"),
                 task,
             ),
             "writer channel open"
@@ -827,6 +839,7 @@ mod tests {
             header: LogServerRequestHeader::new(loglet_id_1, LogletOffset::new(10)),
             from_offset: LogletOffset::new(5),
             to_offset: LogletOffset::new(200),
+            verify_checksums: false,
         };
         let digest = log_store.get_records_digest(msg, &state).await?;
         assert_that!(digest.header.status, eq(Status::Ok));
@@ -886,6 +899,7 @@ mod tests {
             header: LogServerRequestHeader::new(loglet_id_1, LogletOffset::new(200)),
             from_offset: LogletOffset::new(1),
             to_offset: LogletOffset::new(200),
+            verify_checksums: false,
         };
         let digest = log_store.get_records_digest(msg, &state).await?;
         assert_that!(digest.header.status, eq(Status::Ok));
@@ -933,6 +947,7 @@ mod tests {
             // start from 5 this time
             from_offset: LogletOffset::new(5),
             to_offset: LogletOffset::new(200),
+            verify_checksums: false,
         };
         let digest = log_store.get_records_digest(msg, &state).await?;
         assert_that!(digest.header.status, eq(Status::Ok));
@@ -966,6 +981,197 @@ mod tests {
             ]
         );
 
+        // Scenario 3.
+        // Flip a bit in the payload of offset 12 and ask for checksum verification.
+        {
+            let db = log_store.rocksdb.inner().as_raw_db();
+            let data_cf = log_store.data_cf();
+            let key = DataRecordKey::new(loglet_id_1, LogletOffset::new(12)).to_binary_array();
+            let mut value = db.get_cf(&data_cf, key)?.expect("record exists");
+            *value.last_mut().unwrap() ^= 0x01;
+            db.put_cf(&data_cf, key, value)?;
+        }
+        let msg = GetDigest {
+            header: LogServerRequestHeader::new(loglet_id_1, LogletOffset::new(200)),
+            from_offset: LogletOffset::new(5),
+            to_offset: LogletOffset::new(200),
+            verify_checksums: true,
+        };
+        let digest = log_store.get_records_digest(msg, &state).await?;
+        assert_that!(digest.header.status, eq(Status::Ok));
+        // we expect [5..11] T, [12..12] C, [13..13] X, [18..18] X
+        assert_that!(
+            digest.entries,
+            elements_are![
+                eq(DigestEntry {
+                    from_offset: 5.into(),
+                    to_offset: 11.into(),
+                    status: RecordStatus::Trimmed,
+                }),
+                eq(DigestEntry {
+                    from_offset: 12.into(),
+                    to_offset: 12.into(),
+                    status: RecordStatus::Corrupted,
+                }),
+                eq(DigestEntry {
+                    from_offset: 13.into(),
+                    to_offset: 13.into(),
+                    status: RecordStatus::Exists,
+                }),
+                eq(DigestEntry {
+                    from_offset: 18.into(),
+                    to_offset: 18.into(),
+                    status: RecordStatus::Exists,
+                }),
+            ]
+        );
+
+        TaskCenter::shutdown_node("test completed", 0).await;
+        RocksDbManager::get().shutdown().await;
+        Ok(())
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn repair_store_overwrites_corrupted_record() -> Result<()> {
+        const SEQUENCER: GenerationalNodeId = GenerationalNodeId::new(1, 1);
+        const PEER: GenerationalNodeId = GenerationalNodeId::new(2, 2);
+        const LOGLET: LogletId = LogletId::new_unchecked(1);
+
+        assert!(TaskCenter::try_set_global_metadata(
+            MetadataBuilder::default().to_metadata()
+        ));
+        let log_store = setup().await?;
+        let loglet_state_map = LogletStateMap::default();
+        let loglet_state = loglet_state_map.get_or_load(LOGLET, &log_store).await?;
+        let worker = LogletWorker::start(LOGLET, log_store.clone(), loglet_state)?;
+
+        // offsets 1, 2, 3
+        let (store, store_reply) = ServiceMessage::fake_rpc(
+            Store {
+                header: LogServerRequestHeader::new(LOGLET, LogletOffset::INVALID),
+                timeout_at: None,
+                sequencer: SEQUENCER,
+                known_archived: LogletOffset::INVALID,
+                first_offset: LogletOffset::OLDEST,
+                flags: StoreFlags::empty(),
+                payloads: vec![
+                    Record::from("record1"),
+                    Record::from("record2"),
+                    Record::from("record3"),
+                ]
+                .into(),
+            },
+            Some(LOGLET.into()),
+            SEQUENCER,
+            None,
+        );
+        worker.data_tx().send(store);
+        let stored: Stored = store_reply.await?;
+        assert_that!(stored.status, eq(Status::Ok));
+        assert_that!(stored.local_tail, eq(LogletOffset::new(4)));
+
+        let (seal, seal_reply) = ServiceMessage::fake_rpc(
+            Seal {
+                header: LogServerRequestHeader::new(LOGLET, LogletOffset::INVALID),
+                sequencer: SEQUENCER,
+            },
+            Some(LOGLET.into()),
+            SEQUENCER,
+            None,
+        );
+        worker.meta_tx().send(seal);
+        let sealed: Sealed = seal_reply.await?;
+        assert_that!(sealed.status, eq(Status::Ok));
+
+        // Flip a bit in the payload of offset 2 behind the worker's back.
+        {
+            let db = log_store.rocksdb.inner().as_raw_db();
+            let data_cf = log_store.data_cf();
+            let key = DataRecordKey::new(LOGLET, LogletOffset::new(2)).to_binary_array();
+            let mut value = db.get_cf(&data_cf, key)?.expect("record exists");
+            *value.last_mut().unwrap() ^= 0x01;
+            db.put_cf(&data_cf, key, value)?;
+        }
+
+        let digest = |worker: &LogletWorkerHandle| {
+            let (msg, reply) = ServiceMessage::fake_rpc(
+                GetDigest {
+                    header: LogServerRequestHeader::new(LOGLET, LogletOffset::new(4)),
+                    from_offset: LogletOffset::OLDEST,
+                    to_offset: LogletOffset::new(3),
+                    verify_checksums: true,
+                },
+                Some(LOGLET.into()),
+                PEER,
+                None,
+            );
+            worker.meta_tx().send(msg);
+            reply
+        };
+
+        let digest_before: Digest = digest(&worker).await?;
+        assert_that!(
+            digest_before.entries,
+            contains(eq(DigestEntry {
+                from_offset: 2.into(),
+                to_offset: 2.into(),
+                status: RecordStatus::Corrupted,
+            }))
+        );
+
+        // The scrubber's repair is a store with IgnoreSeal, coming from a peer that is not the
+        // sequencer, for an offset below the (sealed) local tail.
+        let (repair, repair_reply) = ServiceMessage::fake_rpc(
+            Store {
+                header: LogServerRequestHeader::new(LOGLET, LogletOffset::new(4)),
+                timeout_at: None,
+                sequencer: SEQUENCER,
+                known_archived: LogletOffset::INVALID,
+                first_offset: LogletOffset::new(2),
+                flags: StoreFlags::IgnoreSeal,
+                payloads: vec![Record::from("record2")].into(),
+            },
+            Some(LOGLET.into()),
+            PEER,
+            None,
+        );
+        worker.data_tx().send(repair);
+        let stored: Stored = repair_reply.await?;
+        assert_that!(stored.status, eq(Status::Ok));
+        assert_that!(stored.sealed, eq(true));
+        assert_that!(stored.local_tail, eq(LogletOffset::new(4)));
+
+        let digest_after: Digest = digest(&worker).await?;
+        assert_that!(
+            digest_after.entries,
+            elements_are![eq(DigestEntry {
+                from_offset: 1.into(),
+                to_offset: 3.into(),
+                status: RecordStatus::Exists,
+            })]
+        );
+
+        let (get_records, get_records_reply) = ServiceMessage::fake_rpc(
+            GetRecords {
+                header: LogServerRequestHeader::new(LOGLET, LogletOffset::new(4)),
+                filter: KeyFilter::Any,
+                total_limit_in_bytes: None,
+                from_offset: LogletOffset::new(2),
+                to_offset: LogletOffset::new(2),
+            },
+            Some(LOGLET.into()),
+            PEER,
+            None,
+        );
+        worker.data_tx().send(get_records);
+        let mut records: Records = get_records_reply.await?;
+        assert_that!(records.status, eq(Status::Ok));
+        assert_that!(records.records.len(), eq(1));
+        let (offset, record) = records.records.pop().unwrap();
+        assert_that!(offset, eq(LogletOffset::new(2)));
+        let original: String = record.try_unwrap_data().unwrap().decode().unwrap();
+        assert_that!(original, eq("record2"));
+
         TaskCenter::shutdown_node("test completed", 0).await;
         RocksDbManager::get().shutdown().await;
         Ok(())
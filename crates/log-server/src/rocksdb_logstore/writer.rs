@@ -12,7 +12,6 @@ use std::io::IoSlice;
 use std::sync::Arc;
 
 use ahash::HashMap;
-use bytes::BytesMut;
 use metrics::{Histogram, histogram};
 use rocksdb::{BoundColumnFamily, WriteBatch};
 use smallvec::SmallVec;
@@ -24,10 +23,10 @@ use restate_rocksdb::{IoMode, Priority, RocksDb, RocksError};
 use restate_types::GenerationalNodeId;
 use restate_types::config::{Configuration, LogServerOptions};
 use restate_types::logs::{LogletId, LogletOffset, SequenceNumber};
-use restate_types::net::log_server::{Payloads, Status};
+use restate_types::net::log_server::Status;
 
 use super::keys::{DataRecordKey, KeyPrefixKind, MetadataKey};
-use super::record_format::DataRecordEncoder;
+use super::record_format::EncodedRecord;
 use super::{DATA_CF, METADATA_CF};
 
 use crate::logstore::{LogStoreState, WriteDisableReason};
@@ -115,7 +114,7 @@ struct WriteCommand {
 
 enum DataUpdate {
     StoreBatch {
-        payloads: Payloads,
+        records: Vec<EncodedRecord>,
         first_offset: LogletOffset,
         last_offset: LogletOffset,
     },
@@ -183,7 +182,6 @@ impl LogStoreWriterBuilder {
                     data_cf,
                     metadata_cf,
                     state_map: HashMap::default(),
-                    arena: BytesMut::default(),
                     log_store_state: self.log_store_state.clone(),
                     write_size_histogram: histogram!(LOG_SERVER_WRITE_BATCH_SIZE_BYTES),
                 };
@@ -301,7 +299,6 @@ struct LogStoreWriter<'a> {
     /// Registered loglet states. The writer uses these to advance tail watches
     /// after durable commit.
     state_map: HashMap<LogletId, LogletState>,
-    arena: BytesMut,
     /// Store-level state shared with loglet workers. The writer sets the
     /// disable reason on failsafe to unblock all store waiters.
     log_store_state: LogStoreState,
@@ -374,7 +371,7 @@ impl LogStoreWriter<'_> {
 
                 match data_update {
                     Some(DataUpdate::StoreBatch {
-                        payloads,
+                        records,
                         first_offset,
                         last_offset,
                         ..
@@ -388,10 +385,9 @@ impl LogStoreWriter<'_> {
                         Self::process_store_message(
                             loglet_id,
                             first_offset,
-                            payloads,
+                            records,
                             &self.data_cf,
                             &mut batch.write_batch,
-                            &mut self.arena,
                         );
                     }
                     Some(DataUpdate::TrimLogRecords { trim_point }) => {
@@ -411,16 +407,13 @@ impl LogStoreWriter<'_> {
     fn process_store_message(
         loglet_id: LogletId,
         first_offset: LogletOffset,
-        payloads: Payloads,
+        records: Vec<EncodedRecord>,
         data_cf: &Arc<BoundColumnFamily>,
         write_batch: &mut WriteBatch,
-        arena: &mut BytesMut,
     ) {
         let mut offset = first_offset;
-        for payload in payloads.iter() {
+        for value_bytes in records {
             let key_bytes = DataRecordKey::new(loglet_id, offset).to_binary_array();
-            let encoder = DataRecordEncoder::from(payload);
-            let value_bytes = encoder.encode_to_disk_format(arena);
             // Shortcut: we know that the chain is 2 slices wide, todo is to introduce an
             // IoBufQueue that can be used in ropes of owned byte slices like this case.
             let dst = [
@@ -639,7 +632,7 @@ impl RocksDbLogWriterHandle {
         &self,
         first_offset: LogletOffset,
         last_offset: LogletOffset,
-        payloads: Payloads,
+        records: Vec<EncodedRecord>,
         task: StoreStorageTask,
     ) -> bool {
         let metadata_update = task
@@ -647,7 +640,7 @@ impl RocksDbLogWriterHandle {
             .map(|sequencer| MetadataUpdate::SetSequencer { sequencer });
         trace!(loglet_id = %task.loglet_id(), "Sending store [{first_offset}..{last_offset}] to writer");
         let data_update = DataUpdate::StoreBatch {
-            payloads,
+            records,
             first_offset,
             last_offset,
         };
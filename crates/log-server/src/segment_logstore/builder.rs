@@ -0,0 +1,67 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tracing::info;
+
+use restate_core::ShutdownError;
+use restate_types::config::Configuration;
+use restate_types::health::HealthStatus;
+use restate_types::protobuf::common::LogServerStatus;
+
+use super::segments::SegmentCatalog;
+use super::writer::SegmentLogStoreWriterBuilder;
+use super::{SegmentLogStore, SegmentLogStoreError};
+use crate::logstore::LogStoreState;
+
+#[derive(Clone)]
+pub struct SegmentLogStoreBuilder {
+    base_dir: PathBuf,
+}
+
+impl SegmentLogStoreBuilder {
+    pub async fn create() -> Result<Self, SegmentLogStoreError> {
+        let opts = &Configuration::pinned().log_server;
+        if opts.in_memory {
+            return Err(SegmentLogStoreError::InMemoryUnsupported);
+        }
+
+        let base_dir = opts.segment_data_dir();
+        if !opts.read_only {
+            std::fs::create_dir_all(&base_dir)?;
+        }
+        info!(
+            "Using segment-file log-store at {}, segments are rolled at {} bytes",
+            base_dir.display(),
+            opts.segment_max_size(),
+        );
+
+        Ok(Self { base_dir })
+    }
+
+    pub async fn start(
+        self,
+        health_status: HealthStatus<LogServerStatus>,
+    ) -> Result<SegmentLogStore, ShutdownError> {
+        let SegmentLogStoreBuilder { base_dir } = self;
+        let store_state = LogStoreState::new(health_status);
+        let catalog = Arc::new(SegmentCatalog::new(base_dir));
+        let writer_handle =
+            SegmentLogStoreWriterBuilder::new(catalog.clone(), store_state.clone()).start()?;
+
+        Ok(SegmentLogStore {
+            catalog,
+            store_state,
+            writer_handle,
+        })
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use restate_bifrost::loglet::OperationError;
+use restate_core::ShutdownError;
+use restate_types::errors::MaybeRetryableError;
+
+use crate::rocksdb_logstore::record_format::RecordDecodeError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SegmentLogStoreError {
+    #[error("log-store is on read-only mode")]
+    ReadOnly,
+    #[error("segment-file log-store does not support in-memory mode")]
+    InMemoryUnsupported,
+    #[error("corrupted frame in segment file {} at position {position}", path.display())]
+    CorruptedFrame { path: PathBuf, position: u64 },
+    #[error("corrupted loglet metadata file {}", .0.display())]
+    CorruptedMeta(PathBuf),
+    #[error(transparent)]
+    Decode(#[from] RecordDecodeError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonDecode(#[from] serde_json::Error),
+    #[error(transparent)]
+    Shutdown(#[from] ShutdownError),
+}
+
+impl MaybeRetryableError for SegmentLogStoreError {
+    fn retryable(&self) -> bool {
+        match self {
+            Self::ReadOnly => false,
+            Self::InMemoryUnsupported => false,
+            Self::CorruptedFrame { .. } => false,
+            Self::CorruptedMeta(_) => false,
+            Self::Decode(_) => false,
+            Self::Io(_) => true,
+            Self::JsonDecode(_) => false,
+            Self::Shutdown(_) => false,
+        }
+    }
+}
+
+impl From<SegmentLogStoreError> for OperationError {
+    fn from(value: SegmentLogStoreError) -> Self {
+        match value {
+            SegmentLogStoreError::Shutdown(e) => OperationError::Shutdown(e),
+            e => OperationError::Other(Arc::new(e)),
+        }
+    }
+}
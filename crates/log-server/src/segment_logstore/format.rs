@@ -0,0 +1,299 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! On-disk layout of the segment-file log-store.
+//!
+//! ```text
+//! <segment-data-dir>/
+//!   marker                        LogStoreMarker (json)
+//!   loglet-<loglet_id>/
+//!     meta                        LogletMeta
+//!     00000000000000000001.segment
+//!     00000000000000000002.segment
+//! ```
+//!
+//! A segment file starts with [`SEGMENT_MAGIC`] followed by a sequence of frames. Each frame
+//! carries a single record in the same format used by the RocksDB log-store.
+
+use std::fs::File;
+use std::io::Write;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+use bytes::{Buf, BufMut, BytesMut};
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+
+use restate_types::GenerationalNodeId;
+use restate_types::logs::{LogletId, LogletOffset};
+
+use crate::rocksdb_logstore::record_format::EncodedRecord;
+
+pub const MARKER_FILE: &str = "marker";
+pub const LOGLET_META_FILE: &str = "meta";
+const SEGMENT_FILE_EXTENSION: &str = "segment";
+
+/// Written at the start of every segment file. The last byte is the format version.
+pub const SEGMENT_MAGIC: [u8; 8] = *b"RSTSEG\0\x01";
+pub const SEGMENT_HEADER_SIZE: u64 = SEGMENT_MAGIC.len() as u64;
+
+/// Frame layout, all integers are little-endian.
+///
+///    [4 bytes]   Length of the record
+///    [4 bytes]   Loglet offset of the record
+///    [8 bytes]   xxh3 checksum of the record, seeded with the offset
+///    [length]    Record in the log-store's on-disk record format
+pub const FRAME_HEADER_SIZE: usize = 16;
+
+pub fn loglet_dir_name(loglet_id: LogletId) -> String {
+    format!("loglet-{loglet_id}")
+}
+
+pub fn segment_file_name(segment_id: u64) -> String {
+    format!("{segment_id:020}.{SEGMENT_FILE_EXTENSION}")
+}
+
+/// Returns the segment id if `file_name` names a segment file
+pub fn parse_segment_file_name(file_name: &str) -> Option<u64> {
+    let (id, extension) = file_name.split_once('.')?;
+    if extension != SEGMENT_FILE_EXTENSION {
+        return None;
+    }
+    id.parse().ok()
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FrameHeader {
+    pub len: u32,
+    pub offset: LogletOffset,
+    pub checksum: u64,
+}
+
+impl FrameHeader {
+    pub fn decode(mut buf: &[u8]) -> Self {
+        debug_assert!(buf.len() >= FRAME_HEADER_SIZE);
+        Self {
+            len: buf.get_u32_le(),
+            offset: LogletOffset::new(buf.get_u32_le()),
+            checksum: buf.get_u64_le(),
+        }
+    }
+
+    /// Total size of the frame on disk
+    pub fn frame_len(&self) -> u64 {
+        FRAME_HEADER_SIZE as u64 + u64::from(self.len)
+    }
+
+    pub fn verify(&self, record: &[u8]) -> bool {
+        record.len() == self.len as usize && checksum(self.offset, record) == self.checksum
+    }
+}
+
+fn checksum(offset: LogletOffset, record: &[u8]) -> u64 {
+    xxh3_64_with_seed(record, u64::from(*offset))
+}
+
+/// Appends a frame holding the encoded `record` to `buf` and returns the size of the frame.
+pub fn encode_frame(buf: &mut BytesMut, offset: LogletOffset, record: EncodedRecord) -> u32 {
+    let start = buf.len();
+    // header is filled in once the record is in place
+    buf.put_bytes(0, FRAME_HEADER_SIZE);
+    buf.put(record);
+    let record_len = u32::try_from(buf.len() - start - FRAME_HEADER_SIZE)
+        .expect("records are smaller than 4GiB");
+    let checksum = checksum(offset, &buf[start + FRAME_HEADER_SIZE..]);
+    let mut header = &mut buf[start..start + FRAME_HEADER_SIZE];
+    header.put_u32_le(record_len);
+    header.put_u32_le(*offset);
+    header.put_u64_le(checksum);
+    FRAME_HEADER_SIZE as u32 + record_len
+}
+
+/// Reads the frame header at `position`. Returns `None` if the file ends before the header.
+pub fn read_frame_header(file: &File, position: u64) -> std::io::Result<Option<FrameHeader>> {
+    let mut buf = [0; FRAME_HEADER_SIZE];
+    match file.read_exact_at(&mut buf, position) {
+        Ok(()) => Ok(Some(FrameHeader::decode(&buf))),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads the record of the frame described by `header` that starts at `position`.
+pub fn read_frame_record(
+    file: &File,
+    position: u64,
+    header: &FrameHeader,
+) -> std::io::Result<Vec<u8>> {
+    let mut record = vec![0; header.len as usize];
+    file.read_exact_at(&mut record, position + FRAME_HEADER_SIZE as u64)?;
+    Ok(record)
+}
+
+/// Durable per-loglet metadata, the equivalent of the RocksDB log-store's metadata column
+/// family.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct LogletMeta {
+    pub sequencer: Option<GenerationalNodeId>,
+    pub trim_point: Option<LogletOffset>,
+    pub known_global_tail: Option<LogletOffset>,
+    pub sealed: bool,
+}
+
+const LOGLET_META_VERSION: u8 = 1;
+const META_SEALED: u8 = 1;
+const META_HAS_SEQUENCER: u8 = 1 << 1;
+const META_HAS_TRIM_POINT: u8 = 1 << 2;
+const META_HAS_GLOBAL_TAIL: u8 = 1 << 3;
+
+impl LogletMeta {
+    /// Layout, all integers are big-endian.
+    ///
+    ///    [1 byte]    Format version
+    ///    [1 byte]    Flags
+    ///    [8 bytes]   Sequencer
+    ///    [4 bytes]   Trim point
+    ///    [4 bytes]   Known global tail
+    ///    [8 bytes]   xxh3 checksum of the preceding bytes
+    pub fn encode(&self) -> BytesMut {
+        let mut buf = BytesMut::with_capacity(26);
+        let mut flags = 0;
+        if self.sealed {
+            flags |= META_SEALED;
+        }
+        if self.sequencer.is_some() {
+            flags |= META_HAS_SEQUENCER;
+        }
+        if self.trim_point.is_some() {
+            flags |= META_HAS_TRIM_POINT;
+        }
+        if self.known_global_tail.is_some() {
+            flags |= META_HAS_GLOBAL_TAIL;
+        }
+        buf.put_u8(LOGLET_META_VERSION);
+        buf.put_u8(flags);
+        self.sequencer
+            .unwrap_or(GenerationalNodeId::INVALID)
+            .encode(&mut buf);
+        self.trim_point
+            .unwrap_or(LogletOffset::INVALID)
+            .encode(&mut buf);
+        self.known_global_tail
+            .unwrap_or(LogletOffset::INVALID)
+            .encode(&mut buf);
+        let checksum = xxhash_rust::xxh3::xxh3_64(&buf);
+        buf.put_u64(checksum);
+        buf
+    }
+
+    /// Returns `None` if the value is truncated, corrupted or of an unknown version.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let (mut body, mut checksum) = data.split_at_checked(data.len().checked_sub(8)?)?;
+        if body.len() != 18 || xxhash_rust::xxh3::xxh3_64(body) != checksum.get_u64() {
+            return None;
+        }
+        if body.get_u8() != LOGLET_META_VERSION {
+            return None;
+        }
+        let flags = body.get_u8();
+        let sequencer = GenerationalNodeId::decode(&mut body);
+        let trim_point = LogletOffset::decode(&mut body);
+        let known_global_tail = LogletOffset::decode(&mut body);
+        Some(Self {
+            sequencer: (flags & META_HAS_SEQUENCER != 0).then_some(sequencer),
+            trim_point: (flags & META_HAS_TRIM_POINT != 0).then_some(trim_point),
+            known_global_tail: (flags & META_HAS_GLOBAL_TAIL != 0).then_some(known_global_tail),
+            sealed: flags & META_SEALED != 0,
+        })
+    }
+}
+
+/// Replaces `dir/name` with `contents` such that readers observe either the old or the new
+/// file, never a partially written one.
+pub fn write_file_atomically(dir: &Path, name: &str, contents: &[u8]) -> std::io::Result<()> {
+    let tmp_path = dir.join(format!("{name}.tmp"));
+    let mut tmp = File::create(&tmp_path)?;
+    tmp.write_all(contents)?;
+    tmp.sync_all()?;
+    std::fs::rename(&tmp_path, dir.join(name))?;
+    sync_dir(dir)
+}
+
+/// Makes creation, removal and renames of files in `dir` durable.
+pub fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use googletest::prelude::*;
+
+    use restate_types::GenerationalNodeId;
+    use restate_types::logs::{LogletOffset, Record};
+
+    use super::*;
+    use crate::rocksdb_logstore::record_format::DataRecordEncoder;
+
+    #[test]
+    fn segment_file_names() {
+        let name = segment_file_name(42);
+        assert_that!(name, eq("00000000000000000042.segment"));
+        assert_that!(parse_segment_file_name(&name), some(eq(42)));
+        assert_that!(parse_segment_file_name("meta"), none());
+        assert_that!(parse_segment_file_name("meta.tmp"), none());
+        assert_that!(parse_segment_file_name("abc.segment"), none());
+    }
+
+    #[test]
+    fn frame_roundtrip() {
+        let mut buf = BytesMut::new();
+        let mut scratch = BytesMut::new();
+        let record = DataRecordEncoder::from(&Record::from("a sample record".to_owned()))
+            .encode_to_disk_format(&mut scratch)
+            .unwrap();
+        let frame_len = encode_frame(&mut buf, LogletOffset::new(7), record);
+        assert_that!(frame_len as usize, eq(buf.len()));
+
+        let header = FrameHeader::decode(&buf);
+        assert_that!(header.offset, eq(LogletOffset::new(7)));
+        assert_that!(header.frame_len(), eq(u64::from(frame_len)));
+        assert!(header.verify(&buf[FRAME_HEADER_SIZE..]));
+
+        // the checksum covers the offset
+        let moved = FrameHeader {
+            offset: LogletOffset::new(8),
+            ..header
+        };
+        assert!(!moved.verify(&buf[FRAME_HEADER_SIZE..]));
+
+        // and the record
+        *buf.last_mut().unwrap() ^= 0x01;
+        assert!(!header.verify(&buf[FRAME_HEADER_SIZE..]));
+    }
+
+    #[test]
+    fn loglet_meta_roundtrip() {
+        let empty = LogletMeta::default();
+        assert_that!(LogletMeta::decode(&empty.encode()), some(eq(&empty)));
+
+        let meta = LogletMeta {
+            sequencer: Some(GenerationalNodeId::new(5, 213)),
+            trim_point: Some(LogletOffset::new(10)),
+            known_global_tail: Some(LogletOffset::new(20)),
+            sealed: true,
+        };
+        let mut encoded = meta.encode();
+        assert_that!(LogletMeta::decode(&encoded), some(eq(&meta)));
+
+        // truncated or corrupted values are rejected
+        assert_that!(LogletMeta::decode(&encoded[..encoded.len() - 1]), none());
+        encoded[3] ^= 0x01;
+        assert_that!(LogletMeta::decode(&encoded), none());
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Append-only segment-file log-store.
+//!
+//! Records of each loglet are appended to size-bounded segment files and located through a
+//! sparse in-memory index that is rebuilt on startup. Trimming deletes whole segments.
+
+mod builder;
+mod error;
+mod format;
+mod segments;
+mod store;
+mod writer;
+
+pub use self::builder::SegmentLogStoreBuilder;
+pub use self::store::SegmentLogStore;
+pub(crate) use error::*;
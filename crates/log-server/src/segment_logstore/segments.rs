@@ -0,0 +1,727 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ahash::HashMap;
+use parking_lot::{Mutex, RwLock};
+use tracing::{debug, warn};
+
+use restate_types::config::Configuration;
+use restate_types::logs::{LogletId, LogletOffset, SequenceNumber};
+
+use super::SegmentLogStoreError;
+use super::format::{
+    LOGLET_META_FILE, LogletMeta, SEGMENT_HEADER_SIZE, SEGMENT_MAGIC, loglet_dir_name,
+    parse_segment_file_name, read_frame_header, read_frame_record, segment_file_name, sync_dir,
+    write_file_atomically,
+};
+
+/// Location of a range of consecutive offsets that are stored back-to-back in a segment file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct IndexRun {
+    pub segment_id: u64,
+    /// Position of the frame of the run's first offset
+    pub position: u64,
+    /// Position right after the frame of `last_offset`
+    pub end_position: u64,
+    pub last_offset: LogletOffset,
+}
+
+struct SegmentInfo {
+    /// The highest offset ever written to this segment, even if a newer copy of it exists
+    /// in a later segment.
+    max_offset: LogletOffset,
+}
+
+struct ActiveSegment {
+    id: u64,
+    file: Arc<File>,
+    len: u64,
+}
+
+/// Files written by a write batch that must be made durable before the batch is
+/// acknowledged.
+#[derive(Default)]
+pub struct PendingSync {
+    files: Vec<Arc<File>>,
+    dirs: Vec<PathBuf>,
+}
+
+impl PendingSync {
+    pub fn sync(&mut self) -> std::io::Result<()> {
+        for file in self.files.drain(..) {
+            file.sync_data()?;
+        }
+        for dir in self.dirs.drain(..) {
+            sync_dir(&dir)?;
+        }
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+        self.dirs.clear();
+    }
+}
+
+/// Segments and sparse offset index of a single loglet.
+///
+/// The index is rebuilt from the segment files when the loglet is first accessed. Stores may
+/// overwrite offsets below the local tail (e.g. sequencer retries or repairs), those land in
+/// the active segment and replace the older copy in the index.
+pub struct LogletSegments {
+    dir: PathBuf,
+    dir_exists: bool,
+    meta: LogletMeta,
+    segments: BTreeMap<u64, SegmentInfo>,
+    next_segment_id: u64,
+    /// Keyed by the first offset of each run. Runs never overlap.
+    index: BTreeMap<LogletOffset, IndexRun>,
+    active: Option<ActiveSegment>,
+}
+
+impl LogletSegments {
+    fn load(
+        dir: PathBuf,
+        index_interval: u32,
+        read_only: bool,
+    ) -> Result<Self, SegmentLogStoreError> {
+        let mut this = Self {
+            dir,
+            dir_exists: false,
+            meta: LogletMeta::default(),
+            segments: BTreeMap::new(),
+            next_segment_id: 1,
+            index: BTreeMap::new(),
+            active: None,
+        };
+
+        let entries = match std::fs::read_dir(&this.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(this),
+            Err(e) => return Err(e.into()),
+        };
+        this.dir_exists = true;
+
+        let mut segment_ids = Vec::new();
+        for entry in entries {
+            if let Some(id) = entry?
+                .file_name()
+                .to_str()
+                .and_then(parse_segment_file_name)
+            {
+                segment_ids.push(id);
+            }
+        }
+        segment_ids.sort_unstable();
+
+        let meta_path = this.dir.join(LOGLET_META_FILE);
+        this.meta = match std::fs::read(&meta_path) {
+            Ok(data) => LogletMeta::decode(&data)
+                .ok_or_else(|| SegmentLogStoreError::CorruptedMeta(meta_path))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => LogletMeta::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let last_segment = segment_ids.last().copied();
+        for segment_id in segment_ids {
+            let is_last = Some(segment_id) == last_segment;
+            this.recover_segment(segment_id, is_last, index_interval, read_only)?;
+            this.next_segment_id = segment_id + 1;
+        }
+        debug!(
+            "Loaded {} segments of {} with {} index runs",
+            this.segments.len(),
+            this.dir.display(),
+            this.index.len()
+        );
+        Ok(this)
+    }
+
+    /// Scans the frames of a segment and adds them to the index. Segments are fsynced before
+    /// the next one is created, therefore only the last segment can have a torn tail, which
+    /// is truncated. A torn tail consists of invalid frames after the last valid one, an invalid
+    /// frame that is followed by a valid one is corruption of acknowledged data and fails the
+    /// recovery.
+    fn recover_segment(
+        &mut self,
+        segment_id: u64,
+        is_last: bool,
+        index_interval: u32,
+        read_only: bool,
+    ) -> Result<(), SegmentLogStoreError> {
+        let path = self.segment_path(segment_id);
+        let file = File::open(&path)?;
+        let file_len = file.metadata()?.len();
+
+        if file_len < SEGMENT_HEADER_SIZE {
+            if !is_last {
+                return Err(SegmentLogStoreError::CorruptedFrame { path, position: 0 });
+            }
+            // crashed while creating the segment
+            warn!("Removing incomplete segment file {}", path.display());
+            if !read_only {
+                std::fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        let mut magic = [0; SEGMENT_MAGIC.len()];
+        file.read_exact_at(&mut magic, 0)?;
+        if magic != SEGMENT_MAGIC {
+            return Err(SegmentLogStoreError::CorruptedFrame { path, position: 0 });
+        }
+
+        let mut position = SEGMENT_HEADER_SIZE;
+        let mut max_offset = LogletOffset::INVALID;
+        while position < file_len {
+            let header = match read_frame_header(&file, position)? {
+                Some(header) if position + header.frame_len() <= file_len => {
+                    if !is_last || header.verify(&read_frame_record(&file, position, &header)?) {
+                        Some(header)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            };
+            let Some(header) = header else {
+                if !is_last || has_valid_frame_after(&file, position, file_len)? {
+                    return Err(SegmentLogStoreError::CorruptedFrame { path, position });
+                }
+                warn!(
+                    "Truncating torn tail of segment file {} at position {position}, {} bytes \
+                     were never acknowledged",
+                    path.display(),
+                    file_len - position,
+                );
+                if !read_only {
+                    let file = OpenOptions::new().write(true).open(&path)?;
+                    file.set_len(position)?;
+                    file.sync_all()?;
+                }
+                break;
+            };
+            self.index_frame(
+                header.offset,
+                segment_id,
+                position,
+                header.frame_len(),
+                index_interval,
+            )?;
+            max_offset = max_offset.max(header.offset);
+            position += header.frame_len();
+        }
+
+        self.segments.insert(segment_id, SegmentInfo { max_offset });
+        Ok(())
+    }
+
+    pub fn meta(&self) -> &LogletMeta {
+        &self.meta
+    }
+
+    pub fn meta_mut(&mut self) -> &mut LogletMeta {
+        &mut self.meta
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn segment_path(&self, segment_id: u64) -> PathBuf {
+        self.dir.join(segment_file_name(segment_id))
+    }
+
+    /// The offset after the highest offset stored in this loglet
+    pub fn local_tail(&self) -> Option<LogletOffset> {
+        self.index
+            .last_key_value()
+            .map(|(_, run)| run.last_offset.next())
+    }
+
+    /// Returns the runs that hold offsets in `from..=to`, ordered by offset. The first run
+    /// might start before `from`.
+    pub fn runs_in(&self, from: LogletOffset, to: LogletOffset) -> Vec<(LogletOffset, IndexRun)> {
+        let mut runs = Vec::new();
+        if from > to {
+            return runs;
+        }
+        if let Some((start, run)) = self.index.range(..from).next_back()
+            && run.last_offset >= from
+        {
+            runs.push((*start, *run));
+        }
+        runs.extend(
+            self.index
+                .range(from..=to)
+                .map(|(start, run)| (*start, *run)),
+        );
+        runs
+    }
+
+    /// Whether records exist beyond `offset`
+    pub fn has_records_after(&self, offset: LogletOffset) -> bool {
+        self.index
+            .last_key_value()
+            .is_some_and(|(_, run)| run.last_offset > offset)
+    }
+
+    /// Appends encoded frames to the active segment, rolling it whenever it exceeds
+    /// `max_segment_size`. The written files are added to `pending` and must be synced before
+    /// the frames are acknowledged.
+    pub fn append(
+        &mut self,
+        frames: &[u8],
+        frame_lens: &[(LogletOffset, u32)],
+        max_segment_size: u64,
+        index_interval: u32,
+        pending: &mut PendingSync,
+    ) -> Result<(), SegmentLogStoreError> {
+        let mut buf_position = 0;
+        let mut next_frame = 0;
+        while next_frame < frame_lens.len() {
+            let first_frame_len = u64::from(frame_lens[next_frame].1);
+            let active = self.active_segment(first_frame_len, max_segment_size, pending)?;
+
+            // fill up the active segment with as many frames as fit, but at least one.
+            let mut chunk_len = first_frame_len;
+            let mut chunk_end = next_frame + 1;
+            while let Some((_, len)) = frame_lens.get(chunk_end)
+                && active.len + chunk_len + u64::from(*len) <= max_segment_size
+            {
+                chunk_len += u64::from(*len);
+                chunk_end += 1;
+            }
+
+            let segment_id = active.id;
+            let mut position = active.len;
+            let chunk = &frames[buf_position..buf_position + chunk_len as usize];
+            active.file.write_all_at(chunk, position)?;
+            active.len += chunk_len;
+            if !pending
+                .files
+                .last()
+                .is_some_and(|file| Arc::ptr_eq(file, &active.file))
+            {
+                pending.files.push(Arc::clone(&active.file));
+            }
+
+            for (offset, len) in &frame_lens[next_frame..chunk_end] {
+                let len = u64::from(*len);
+                self.index_frame(*offset, segment_id, position, len, index_interval)?;
+                let info = self
+                    .segments
+                    .get_mut(&segment_id)
+                    .expect("segment is known");
+                info.max_offset = info.max_offset.max(*offset);
+                position += len;
+            }
+            buf_position += chunk_len as usize;
+            next_frame = chunk_end;
+        }
+        Ok(())
+    }
+
+    fn active_segment(
+        &mut self,
+        frame_len: u64,
+        max_segment_size: u64,
+        pending: &mut PendingSync,
+    ) -> Result<&mut ActiveSegment, SegmentLogStoreError> {
+        let roll = self.active.as_ref().is_none_or(|active| {
+            active.len > SEGMENT_HEADER_SIZE && active.len + frame_len > max_segment_size
+        });
+        if roll {
+            if let Some(previous) = self.active.take() {
+                // Only the last segment of a loglet is allowed to have a torn tail
+                previous.file.sync_data()?;
+            }
+            self.ensure_dir()?;
+            let segment_id = self.next_segment_id;
+            let path = self.segment_path(segment_id);
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path)?;
+            file.write_all_at(&SEGMENT_MAGIC, 0)?;
+            debug!("Created segment file {}", path.display());
+
+            self.next_segment_id += 1;
+            self.segments.insert(
+                segment_id,
+                SegmentInfo {
+                    max_offset: LogletOffset::INVALID,
+                },
+            );
+            pending.dirs.push(self.dir.clone());
+            self.active = Some(ActiveSegment {
+                id: segment_id,
+                file: Arc::new(file),
+                len: SEGMENT_HEADER_SIZE,
+            });
+        }
+        Ok(self.active.as_mut().expect("active segment exists"))
+    }
+
+    /// Durably replaces the loglet's metadata file with the current metadata
+    pub fn persist_meta(&mut self) -> Result<(), SegmentLogStoreError> {
+        self.ensure_dir()?;
+        write_file_atomically(&self.dir, LOGLET_META_FILE, &self.meta.encode())?;
+        Ok(())
+    }
+
+    fn ensure_dir(&mut self) -> std::io::Result<()> {
+        if !self.dir_exists {
+            std::fs::create_dir_all(&self.dir)?;
+            if let Some(parent) = self.dir.parent() {
+                sync_dir(parent)?;
+            }
+            self.dir_exists = true;
+        }
+        Ok(())
+    }
+
+    /// Drops all offsets at or below `trim_point` from the index and returns the paths of the
+    /// segments that only hold trimmed records. The caller is responsible for deleting them
+    /// after the new trim point is durable.
+    pub fn trim(&mut self, trim_point: LogletOffset) -> Vec<PathBuf> {
+        self.index.retain(|_, run| run.last_offset > trim_point);
+
+        let trimmed: Vec<u64> = self
+            .segments
+            .iter()
+            .filter(|(_, info)| info.max_offset <= trim_point)
+            .map(|(segment_id, _)| *segment_id)
+            .collect();
+        trimmed
+            .into_iter()
+            .map(|segment_id| {
+                self.segments.remove(&segment_id);
+                if self
+                    .active
+                    .as_ref()
+                    .is_some_and(|active| active.id == segment_id)
+                {
+                    self.active = None;
+                }
+                self.segment_path(segment_id)
+            })
+            .collect()
+    }
+
+    /// Records that the frame of `offset` is at `position` in `segment_id`, replacing any
+    /// older copy of the same offset.
+    fn index_frame(
+        &mut self,
+        offset: LogletOffset,
+        segment_id: u64,
+        position: u64,
+        frame_len: u64,
+        index_interval: u32,
+    ) -> Result<(), SegmentLogStoreError> {
+        self.evict(offset)?;
+
+        // extend the preceding run if the frame directly follows it in the same segment
+        if let Some((start, run)) = self.index.range_mut(..offset).next_back()
+            && run.last_offset.next() == offset
+            && run.segment_id == segment_id
+            && run.end_position == position
+            && *run.last_offset - **start + 1 < index_interval
+        {
+            run.last_offset = offset;
+            run.end_position += frame_len;
+            return Ok(());
+        }
+
+        self.index.insert(
+            offset,
+            IndexRun {
+                segment_id,
+                position,
+                end_position: position + frame_len,
+                last_offset: offset,
+            },
+        );
+        Ok(())
+    }
+
+    /// Removes `offset` from the index, splitting the run that covers it.
+    fn evict(&mut self, offset: LogletOffset) -> Result<(), SegmentLogStoreError> {
+        let Some((&start, &run)) = self.index.range(..=offset).next_back() else {
+            return Ok(());
+        };
+        if run.last_offset < offset {
+            return Ok(());
+        }
+        self.index.remove(&start);
+
+        let path = self.segment_path(run.segment_id);
+        let file = File::open(&path)?;
+        let position = seek_frame(&file, &path, run.position, *offset - *start)?;
+        if start < offset {
+            self.index.insert(
+                start,
+                IndexRun {
+                    end_position: position,
+                    last_offset: offset.prev(),
+                    ..run
+                },
+            );
+        }
+        if run.last_offset > offset {
+            let header = read_frame_header(&file, position)?
+                .ok_or(SegmentLogStoreError::CorruptedFrame { path, position })?;
+            self.index.insert(
+                offset.next(),
+                IndexRun {
+                    position: position + header.frame_len(),
+                    ..run
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns the position of the frame that is `skip` frames after the one at `position`.
+pub fn seek_frame(
+    file: &File,
+    path: &Path,
+    mut position: u64,
+    skip: u32,
+) -> Result<u64, SegmentLogStoreError> {
+    for _ in 0..skip {
+        let header = read_frame_header(file, position)?.ok_or_else(|| {
+            SegmentLogStoreError::CorruptedFrame {
+                path: path.to_owned(),
+                position,
+            }
+        })?;
+        position += header.frame_len();
+    }
+    Ok(position)
+}
+
+/// Lazily loaded segments of all loglets of this log-store
+pub struct SegmentCatalog {
+    base_dir: PathBuf,
+    loglets: Mutex<HashMap<LogletId, Arc<RwLock<LogletSegments>>>>,
+}
+
+impl SegmentCatalog {
+    pub fn new(base_dir: PathBuf) -> Self {
+        Self {
+            base_dir,
+            loglets: Mutex::default(),
+        }
+    }
+
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// Returns the segments of the given loglet, recovering them from disk on first access.
+    ///
+    /// The lock is held during recovery so that a torn tail is never truncated while the
+    /// writer appends to the same loglet.
+    pub fn get_or_load(
+        &self,
+        loglet_id: LogletId,
+    ) -> Result<Arc<RwLock<LogletSegments>>, SegmentLogStoreError> {
+        let mut loglets = self.loglets.lock();
+        if let Some(segments) = loglets.get(&loglet_id) {
+            return Ok(Arc::clone(segments));
+        }
+        let opts = &Configuration::pinned().log_server;
+        let segments = Arc::new(RwLock::new(LogletSegments::load(
+            self.base_dir.join(loglet_dir_name(loglet_id)),
+            opts.segment_index_interval.get(),
+            opts.read_only,
+        )?));
+        loglets.insert(loglet_id, Arc::clone(&segments));
+        Ok(segments)
+    }
+}
+
+/// Whether a valid frame follows the invalid frame at `position`, following the lengths in the
+/// frame headers until the end of the file.
+fn has_valid_frame_after(file: &File, position: u64, file_len: u64) -> std::io::Result<bool> {
+    let mut position = position;
+    let mut is_first = true;
+    while let Some(header) = read_frame_header(file, position)? {
+        if position + header.frame_len() > file_len {
+            break;
+        }
+        if !is_first && header.verify(&read_frame_record(file, position, &header)?) {
+            return Ok(true);
+        }
+        is_first = false;
+        position += header.frame_len();
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use googletest::prelude::*;
+
+    use restate_types::logs::{LogletOffset, Record, SequenceNumber};
+
+    use super::*;
+    use crate::rocksdb_logstore::record_format::DataRecordEncoder;
+    use crate::segment_logstore::format::encode_frame;
+
+    fn append(
+        segments: &mut LogletSegments,
+        offsets: impl IntoIterator<Item = u32>,
+        max_segment_size: u64,
+    ) -> Result<()> {
+        let mut frames = BytesMut::new();
+        let mut scratch = BytesMut::new();
+        let mut frame_lens = Vec::new();
+        for offset in offsets {
+            let offset = LogletOffset::new(offset);
+            let record = Record::from(format!("record-{offset}"));
+            let record = DataRecordEncoder::from(&record).encode_to_disk_format(&mut scratch)?;
+            frame_lens.push((offset, encode_frame(&mut frames, offset, record)));
+        }
+        let mut pending = PendingSync::default();
+        segments.append(&frames, &frame_lens, max_segment_size, 4, &mut pending)?;
+        pending.sync()?;
+        Ok(())
+    }
+
+    fn run_bounds(segments: &LogletSegments) -> Vec<(u32, u32)> {
+        segments
+            .index
+            .iter()
+            .map(|(start, run)| (**start, *run.last_offset))
+            .collect()
+    }
+
+    #[test]
+    fn sparse_index_and_overwrites() -> Result<()> {
+        let base_dir = tempfile::tempdir()?;
+        let dir = base_dir.path().join("loglet");
+        let mut segments = LogletSegments::load(dir.clone(), 4, false)?;
+        assert_that!(segments.local_tail(), none());
+
+        append(&mut segments, 1..=10, u64::MAX)?;
+        // runs are capped by the index interval
+        assert_that!(
+            run_bounds(&segments),
+            elements_are![eq((1, 4)), eq((5, 8)), eq((9, 10))]
+        );
+        assert_that!(segments.local_tail(), some(eq(LogletOffset::new(11))));
+
+        // overwrite 6 and 7, the run covering them is split
+        append(&mut segments, 6..=7, u64::MAX)?;
+        assert_that!(
+            run_bounds(&segments),
+            elements_are![eq((1, 4)), eq((5, 5)), eq((6, 7)), eq((8, 8)), eq((9, 10))]
+        );
+        assert_that!(
+            segments
+                .runs_in(LogletOffset::new(7), LogletOffset::new(8))
+                .len(),
+            eq(2)
+        );
+
+        // the index is rebuilt with the same runs from disk
+        drop(segments);
+        let segments = LogletSegments::load(dir, 4, false)?;
+        assert_that!(
+            run_bounds(&segments),
+            elements_are![eq((1, 4)), eq((5, 5)), eq((6, 7)), eq((8, 8)), eq((9, 10))]
+        );
+        Ok(())
+    }
+
+    /// Writes three frames of equal length to the first segment of a new loglet, returns the
+    /// path of the segment and the length of the frames.
+    fn write_three_frames(dir: &Path) -> Result<(PathBuf, u64)> {
+        let mut segments = LogletSegments::load(dir.to_owned(), 4, false)?;
+        append(&mut segments, 1..=3, u64::MAX)?;
+        let path = segments.segment_path(1);
+        let frame_len = (std::fs::metadata(&path)?.len() - SEGMENT_HEADER_SIZE) / 3;
+        Ok((path, frame_len))
+    }
+
+    fn flip_byte(path: &Path, position: u64) -> Result<()> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut byte = [0];
+        file.read_exact_at(&mut byte, position)?;
+        file.write_all_at(&[!byte[0]], position)?;
+        Ok(())
+    }
+
+    #[test]
+    fn truncate_only_torn_tail() -> Result<()> {
+        let base_dir = tempfile::tempdir()?;
+
+        // a corrupted last frame is a torn tail
+        let dir = base_dir.path().join("torn");
+        let (path, frame_len) = write_three_frames(&dir)?;
+        let last_frame = SEGMENT_HEADER_SIZE + 2 * frame_len;
+        flip_byte(&path, last_frame + frame_len - 1)?;
+        let segments = LogletSegments::load(dir, 4, false)?;
+        assert_that!(segments.local_tail(), some(eq(LogletOffset::new(3))));
+        assert_that!(std::fs::metadata(&path)?.len(), eq(last_frame));
+
+        // a corrupted frame followed by a valid one is not
+        let dir = base_dir.path().join("corrupted");
+        let (path, frame_len) = write_three_frames(&dir)?;
+        flip_byte(&path, SEGMENT_HEADER_SIZE + frame_len - 1)?;
+        assert_that!(
+            LogletSegments::load(dir, 4, false)
+                .err()
+                .map(|err| err.to_string()),
+            some(contains_substring("corrupted frame"))
+        );
+        assert_that!(
+            std::fs::metadata(&path)?.len(),
+            eq(SEGMENT_HEADER_SIZE + 3 * frame_len)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn roll_and_trim_segments() -> Result<()> {
+        let base_dir = tempfile::tempdir()?;
+        let dir = base_dir.path().join("loglet");
+        let mut segments = LogletSegments::load(dir.clone(), 4, false)?;
+
+        // tiny segments hold a single frame each
+        append(&mut segments, 1..=5, 1)?;
+        assert_that!(segments.segments.len(), eq(5));
+
+        let trimmed = segments.trim(LogletOffset::new(3));
+        assert_that!(trimmed.len(), eq(3));
+        for path in trimmed {
+            std::fs::remove_file(path)?;
+        }
+        assert_that!(run_bounds(&segments), elements_are![eq((4, 4)), eq((5, 5))]);
+
+        // new segments never reuse the ids of deleted ones
+        append(&mut segments, [6], 1)?;
+        assert_that!(
+            segments.segments.keys().copied().collect::<Vec<_>>(),
+            elements_are![eq(4), eq(5), eq(6)]
+        );
+        Ok(())
+    }
+}
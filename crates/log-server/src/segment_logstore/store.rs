@@ -0,0 +1,899 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tracing::warn;
+
+use restate_bifrost::loglet::OperationError;
+use restate_types::config::Configuration;
+use restate_types::logs::{LogletId, LogletOffset, SequenceNumber, TailState};
+use restate_types::net::log_server::{
+    Digest, DigestEntry, Gap, GetDigest, GetRecords, LogServerResponseHeader, MaybeRecord,
+    RecordStatus, Records, Status,
+};
+
+use super::SegmentLogStoreError;
+use super::format::{
+    FrameHeader, MARKER_FILE, read_frame_header, read_frame_record, segment_file_name,
+    write_file_atomically,
+};
+use super::segments::{SegmentCatalog, seek_frame};
+use super::writer::SegmentLogWriterHandle;
+
+use crate::logstore::{LogStore, LogStoreState, LogletWriter};
+use crate::metadata::{LogStoreMarker, LogletState};
+use crate::rocksdb_logstore::block_in_place;
+use crate::rocksdb_logstore::record_format::{DataRecordDecoder, EncodedRecord};
+use crate::tasks::{
+    OnComplete, SealStorageTask, StoreStorageTask, SyncGlobalTailStorageTask, TrimStorageTask,
+};
+
+#[derive(Clone)]
+pub struct SegmentLogStore {
+    pub(super) catalog: Arc<SegmentCatalog>,
+    pub(super) store_state: LogStoreState,
+    pub(super) writer_handle: SegmentLogWriterHandle,
+}
+
+/// Per-loglet write handle for the segment-file log store.
+///
+/// Automatically registers the loglet with the writer on creation and unregisters when
+/// dropped.
+pub struct SegmentLogletWriter {
+    inner: LogletWriterState,
+}
+
+impl Drop for SegmentLogletWriter {
+    fn drop(&mut self) {
+        match self.inner {
+            LogletWriterState::Active {
+                loglet_id,
+                ref loglet_state,
+                ref writer_handle,
+            } => {
+                // Best-effort: the writer may already be gone during shutdown.
+                let _ = writer_handle.unregister_loglet(loglet_id, loglet_state.clone());
+            }
+            LogletWriterState::Disabled => {}
+        }
+    }
+}
+
+enum LogletWriterState {
+    Active {
+        loglet_id: LogletId,
+        loglet_state: LogletState,
+        writer_handle: SegmentLogWriterHandle,
+    },
+    Disabled,
+}
+
+impl LogletWriter for SegmentLogletWriter {
+    fn enqueue_store(
+        &mut self,
+        first_offset: LogletOffset,
+        last_offset: LogletOffset,
+        records: Vec<EncodedRecord>,
+        task: StoreStorageTask,
+    ) -> bool {
+        match self.inner {
+            LogletWriterState::Active {
+                ref writer_handle, ..
+            } => writer_handle.enqueue_put_records(first_offset, last_offset, records, task),
+            LogletWriterState::Disabled => false,
+        }
+    }
+
+    fn enqueue_seal(&mut self, mut task: SealStorageTask) -> bool {
+        match self.inner {
+            LogletWriterState::Active {
+                ref writer_handle, ..
+            } => writer_handle.enqueue_seal(task),
+            LogletWriterState::Disabled => {
+                task.on_complete(
+                    TailState::invalid(),
+                    LogletOffset::INVALID,
+                    Status::Disabled,
+                );
+                false
+            }
+        }
+    }
+
+    fn enqueue_trim(&mut self, task: TrimStorageTask) -> bool {
+        match self.inner {
+            LogletWriterState::Active {
+                ref writer_handle, ..
+            } => writer_handle.enqueue_trim(task),
+            LogletWriterState::Disabled => false,
+        }
+    }
+
+    fn set_known_global_tail(&mut self, task: SyncGlobalTailStorageTask) {
+        if let LogletWriterState::Active {
+            ref writer_handle, ..
+        } = self.inner
+        {
+            writer_handle.enqueue_set_global_tail(task);
+        }
+    }
+
+    fn close(&mut self) {
+        self.inner = LogletWriterState::Disabled;
+    }
+}
+
+impl LogStore for SegmentLogStore {
+    type Writer = SegmentLogletWriter;
+
+    async fn load_marker(&self) -> Result<Option<LogStoreMarker>, OperationError> {
+        let path = self.catalog.base_dir().join(MARKER_FILE);
+        let value = match std::fs::read(&path) {
+            Ok(value) => value,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(SegmentLogStoreError::from(e).into()),
+        };
+        let marker = LogStoreMarker::from_slice(value).map_err(SegmentLogStoreError::from)?;
+        Ok(Some(marker))
+    }
+
+    fn state(&self) -> &LogStoreState {
+        &self.store_state
+    }
+
+    async fn store_marker(&self, marker: LogStoreMarker) -> Result<(), OperationError> {
+        if Configuration::pinned().log_server.read_only {
+            return Err(SegmentLogStoreError::ReadOnly.into());
+        }
+
+        block_in_place(|| {
+            write_file_atomically(self.catalog.base_dir(), MARKER_FILE, &marker.to_bytes())
+        })
+        .map_err(SegmentLogStoreError::from)?;
+        Ok(())
+    }
+
+    async fn load_loglet_state(&self, loglet_id: LogletId) -> Result<LogletState, OperationError> {
+        let segments = block_in_place(|| self.catalog.get_or_load(loglet_id))?;
+        let segments = segments.read();
+        let meta = segments.meta();
+
+        let trim_point = meta.trim_point.unwrap_or(LogletOffset::INVALID);
+        let mut local_tail = segments.local_tail().unwrap_or(LogletOffset::OLDEST);
+        // If the loglet is trimmed (all records were removed) and we know the trim_point, then we
+        // use the trim_point.next() as the local_tail.
+        if trim_point >= local_tail {
+            local_tail = trim_point.next();
+        }
+        // We can only trim records that are known to be globally committed, let's use that trim
+        // point to adjust our known_global_tail
+        let known_global_tail = meta
+            .known_global_tail
+            .unwrap_or(LogletOffset::OLDEST)
+            .max(trim_point.next());
+
+        Ok(LogletState::new(
+            meta.sequencer,
+            local_tail,
+            meta.sealed,
+            trim_point,
+            known_global_tail,
+        ))
+    }
+
+    fn new_loglet_writer(
+        &self,
+        loglet_id: LogletId,
+        loglet_state: &LogletState,
+    ) -> SegmentLogletWriter {
+        if self
+            .writer_handle
+            .register_loglet(loglet_id, loglet_state.clone())
+        {
+            SegmentLogletWriter {
+                inner: LogletWriterState::Active {
+                    loglet_id,
+                    loglet_state: loglet_state.clone(),
+                    writer_handle: self.writer_handle.clone(),
+                },
+            }
+        } else {
+            SegmentLogletWriter {
+                inner: LogletWriterState::Disabled,
+            }
+        }
+    }
+
+    async fn read_records(
+        &self,
+        msg: GetRecords,
+        loglet_state: &LogletState,
+    ) -> Result<Records, OperationError> {
+        Ok(block_in_place(|| {
+            self.read_records_blocking(msg, loglet_state)
+        })?)
+    }
+
+    async fn get_records_digest(
+        &self,
+        msg: GetDigest,
+        loglet_state: &LogletState,
+    ) -> Result<Digest, OperationError> {
+        Ok(block_in_place(|| {
+            self.get_records_digest_blocking(msg, loglet_state)
+        })?)
+    }
+}
+
+impl SegmentLogStore {
+    fn read_records_blocking(
+        &self,
+        msg: GetRecords,
+        loglet_state: &LogletState,
+    ) -> Result<Records, SegmentLogStoreError> {
+        // Same order of operations as the RocksDB log-store: clip to the local tail first and
+        // re-check the trim point whenever offsets are missing to avoid silent data loss.
+        let local_tail = loglet_state.local_tail();
+        let trim_point = loglet_state.trim_point();
+
+        let read_from = msg.from_offset.max(trim_point.next());
+        let read_to = msg.to_offset.min(local_tail.offset().prev());
+
+        let mut size_budget = msg.total_limit_in_bytes.unwrap_or(usize::MAX);
+
+        // why +1? to have enough room for the initial trim_gap if we have one.
+        let mut records = Vec::with_capacity(
+            usize::try_from(read_to.saturating_sub(*read_from)).expect("no overflow") + 1,
+        );
+
+        // Issue a trim gap until the known head
+        if read_from > msg.from_offset {
+            records.push((
+                msg.from_offset,
+                MaybeRecord::TrimGap(Gap {
+                    to: read_from.prev_unchecked(),
+                }),
+            ));
+        }
+
+        let segments = self.catalog.get_or_load(msg.header.loglet_id)?;
+        let (mut files, runs, has_more) = {
+            let segments = segments.read();
+            (
+                SegmentFiles::new(segments.dir()),
+                segments.runs_in(read_from, read_to),
+                segments.has_records_after(read_to),
+            )
+        };
+
+        // read_pointer points to the next offset we should attempt to read (or expect to read)
+        let mut read_pointer = read_from;
+        let mut first_record_inserted = false;
+        let mut budget_exhausted = false;
+        let mut runs = runs.into_iter().peekable();
+
+        'runs: while let Some(&(start, run)) = runs.peek() {
+            let first = start.max(read_pointer);
+            let last = run.last_offset.min(read_to);
+            if first > last {
+                runs.next();
+                continue;
+            }
+
+            // We skipped offsets, either because we don't have copies for those offsets or
+            // because they got trimmed in the meantime. The segment might also be gone
+            // already if it only held trimmed records.
+            let file = files.open(run.segment_id)?;
+            if first > read_pointer || file.is_none() {
+                let potentially_different_trim_point = loglet_state.trim_point();
+                if potentially_different_trim_point >= first {
+                    // drop the set of accumulated records and start over with a a fresh trim-gap
+                    records.clear();
+                    records.push((
+                        msg.from_offset,
+                        MaybeRecord::TrimGap(Gap {
+                            to: potentially_different_trim_point,
+                        }),
+                    ));
+                    read_pointer = potentially_different_trim_point.next();
+                    continue;
+                }
+            }
+            let Some((file, path)) = file else {
+                return Err(SegmentLogStoreError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("segment {} of untrimmed records is missing", run.segment_id),
+                )));
+            };
+
+            let mut position = seek_frame(file, path, run.position, *first - *start)?;
+            let mut offset = first;
+            loop {
+                let (header, record) = read_frame(file, path, position, offset)?;
+                let decoder = DataRecordDecoder::new(&record)?;
+                if !decoder.matches_key_query(&msg.filter) {
+                    records.push((offset, MaybeRecord::FilteredGap(Gap { to: offset })));
+                } else {
+                    if first_record_inserted && size_budget < decoder.size() {
+                        // we have reached the limit
+                        read_pointer = offset;
+                        budget_exhausted = true;
+                        break 'runs;
+                    }
+                    first_record_inserted = true;
+                    size_budget = size_budget.saturating_sub(decoder.size());
+                    records.push((offset, MaybeRecord::Data(decoder.decode()?)));
+                }
+
+                read_pointer = offset.next();
+                position += header.frame_len();
+                if offset >= last {
+                    break;
+                }
+                offset = offset.next();
+            }
+            runs.next();
+        }
+
+        // Like an iterator that found a record beyond what we want to read
+        if !budget_exhausted && read_pointer <= read_to && has_more {
+            read_pointer = read_to.next();
+        }
+
+        Ok(Records {
+            header: LogServerResponseHeader::new(local_tail, loglet_state.known_global_tail()),
+            next_offset: read_pointer,
+            records,
+        })
+    }
+
+    fn get_records_digest_blocking(
+        &self,
+        msg: GetDigest,
+        loglet_state: &LogletState,
+    ) -> Result<Digest, SegmentLogStoreError> {
+        let loglet_id = msg.header.loglet_id;
+        // If we are reading beyond the tail, the first thing we do is to clip to the
+        // local_tail.
+        let local_tail = loglet_state.local_tail();
+        let trim_point = loglet_state.trim_point();
+
+        // inclusive
+        let read_from = msg.from_offset.max(trim_point.next());
+        let read_to = msg.to_offset.min(local_tail.offset().prev());
+
+        // allocate for near-worst-case.
+        let mut entries = Vec::with_capacity(
+            usize::try_from(read_to.saturating_sub(*read_from)).expect("no overflow") + 1,
+        );
+
+        // Issue a trim gap until the known head
+        if read_from > msg.from_offset {
+            entries.push(DigestEntry {
+                from_offset: msg.from_offset,
+                to_offset: read_from.prev_unchecked(),
+                status: RecordStatus::Trimmed,
+            });
+        }
+
+        let segments = self.catalog.get_or_load(loglet_id)?;
+        let (mut files, runs) = {
+            let segments = segments.read();
+            (
+                SegmentFiles::new(segments.dir()),
+                segments.runs_in(read_from, read_to),
+            )
+        };
+
+        // read_pointer points to the next offset we expect to find
+        let mut read_pointer = read_from;
+        let mut current_open_entry: Option<DigestEntry> = None;
+
+        for (start, run) in runs {
+            let first = start.max(read_pointer);
+            let last = run.last_offset.min(read_to);
+            if first > last {
+                continue;
+            }
+            // Records of deleted segments were trimmed after we started
+            let Some((file, path)) = files.open(run.segment_id)? else {
+                continue;
+            };
+
+            let mut position = seek_frame(file, path, run.position, *first - *start)?;
+            let mut offset = first;
+            loop {
+                let (header, status) = if msg.verify_checksums {
+                    match read_frame(file, path, position, offset).and_then(|(header, record)| {
+                        DataRecordDecoder::new(&record)?.verify_checksum()?;
+                        Ok(header)
+                    }) {
+                        Ok(header) => (header, RecordStatus::Exists),
+                        Err(err) => {
+                            warn!(
+                                %loglet_id,
+                                %offset,
+                                %err,
+                                "Record failed checksum verification"
+                            );
+                            // the header is needed to move on to the next frame
+                            (
+                                read_frame_header(file, position)?
+                                    .filter(|header| header.offset == offset)
+                                    .ok_or_else(|| corrupted_frame(path, position))?,
+                                RecordStatus::Corrupted,
+                            )
+                        }
+                    }
+                } else {
+                    let header = read_frame_header(file, position)?
+                        .filter(|header| header.offset == offset)
+                        .ok_or_else(|| corrupted_frame(path, position))?;
+                    (header, RecordStatus::Exists)
+                };
+
+                match current_open_entry.as_mut() {
+                    // extend the open entry if this record is the next one and has the same status
+                    Some(open) if offset == read_pointer && open.status == status => {
+                        open.to_offset = offset;
+                    }
+                    _ => {
+                        // We found a record that's beyond what we expect as next (local gap), or
+                        // its status differs. Close the entry if we had one and open a new one.
+                        if let Some(open) = current_open_entry.take() {
+                            entries.push(open);
+                        }
+                        current_open_entry = Some(DigestEntry {
+                            from_offset: offset,
+                            to_offset: offset,
+                            status,
+                        });
+                    }
+                }
+                read_pointer = offset.next();
+                position += header.frame_len();
+                if offset >= last {
+                    break;
+                }
+                offset = offset.next();
+            }
+        }
+
+        if let Some(last) = current_open_entry.take() {
+            // close the last entry
+            entries.push(last);
+        }
+
+        Ok(Digest {
+            header: LogServerResponseHeader::new(local_tail, loglet_state.known_global_tail()),
+            entries,
+        })
+    }
+}
+
+/// Reads and verifies the frame of `offset` at `position`.
+fn read_frame(
+    file: &File,
+    path: &Path,
+    position: u64,
+    offset: LogletOffset,
+) -> Result<(FrameHeader, Vec<u8>), SegmentLogStoreError> {
+    let header = read_frame_header(file, position)?
+        .filter(|header| header.offset == offset)
+        .ok_or_else(|| corrupted_frame(path, position))?;
+    let record = read_frame_record(file, position, &header)?;
+    if !header.verify(&record) {
+        return Err(corrupted_frame(path, position));
+    }
+    Ok((header, record))
+}
+
+fn corrupted_frame(path: &Path, position: u64) -> SegmentLogStoreError {
+    SegmentLogStoreError::CorruptedFrame {
+        path: path.to_owned(),
+        position,
+    }
+}
+
+/// The segment file that is currently being read. Runs are ordered by offset, and
+/// consecutive runs are mostly in the same segment.
+struct SegmentFiles {
+    dir: PathBuf,
+    current: Option<(u64, Option<(File, PathBuf)>)>,
+}
+
+impl SegmentFiles {
+    fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_owned(),
+            current: None,
+        }
+    }
+
+    /// Returns `None` if the segment has been deleted
+    fn open(&mut self, segment_id: u64) -> Result<Option<(&File, &Path)>, SegmentLogStoreError> {
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|(id, _)| *id != segment_id)
+        {
+            let path = self.dir.join(segment_file_name(segment_id));
+            let file = match File::open(&path) {
+                Ok(file) => Some((file, path)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+            self.current = Some((segment_id, file));
+        }
+        Ok(self
+            .current
+            .as_ref()
+            .and_then(|(_, file)| file.as_ref())
+            .map(|(file, path)| (file, path.as_path())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use std::os::unix::fs::FileExt;
+
+    use bytes::BytesMut;
+    use googletest::prelude::*;
+    use test_log::test;
+
+    use restate_core::TaskCenter;
+    use restate_core::network::Reciprocal;
+    use restate_memory::MemoryLease;
+    use restate_types::config::{Configuration, set_current_config};
+    use restate_types::logs::{KeyFilter, LogletId, LogletOffset, Record, SequenceNumber};
+    use restate_types::net::log_server::{
+        DigestEntry, GetDigest, GetRecords, LogServerRequestHeader, MaybeRecord, RecordStatus,
+        Status, Store, StoreFlags,
+    };
+    use restate_types::{GenerationalNodeId, PlainNodeId};
+    use restate_util_bytecount::NonZeroByteCount;
+
+    use super::SegmentLogStore;
+    use crate::logstore::{LogStore, LogletWriter};
+    use crate::metadata::{LogStoreMarker, LogletState};
+    use crate::rocksdb_logstore::record_format::encode_records;
+    use crate::segment_logstore::SegmentLogStoreBuilder;
+    use crate::segment_logstore::format::{loglet_dir_name, read_frame_header, segment_file_name};
+    use crate::segment_logstore::segments::seek_frame;
+    use crate::tasks::{StoreStorageTask, TrimStorageTask};
+
+    async fn setup() -> Result<SegmentLogStore> {
+        let builder = SegmentLogStoreBuilder::create().await?;
+        Ok(builder.start(Default::default()).await?)
+    }
+
+    fn store_msg(loglet_id: LogletId, first_offset: u32, payloads: Vec<Record>) -> Store {
+        Store {
+            header: LogServerRequestHeader::new(loglet_id, LogletOffset::new(first_offset)),
+            timeout_at: None,
+            sequencer: GenerationalNodeId::new(5, 213),
+            known_archived: LogletOffset::INVALID,
+            first_offset: LogletOffset::new(first_offset),
+            flags: StoreFlags::empty(),
+            payloads: payloads.into(),
+        }
+    }
+
+    /// Helper: enqueue a store through the loglet's writer and wait for it to be durably
+    /// committed.
+    async fn enqueue_store_and_wait(
+        log_store: &SegmentLogStore,
+        loglet_state: &LogletState,
+        store_msg: Store,
+    ) -> Result<()> {
+        let loglet_id = store_msg.header.loglet_id;
+        let committed_up_to = store_msg.last_offset().expect("non-empty store").next();
+        let mut writer = log_store.new_loglet_writer(loglet_id, loglet_state);
+        let (reciprocal, _rx) = Reciprocal::mock();
+        let mut task = StoreStorageTask::new(loglet_id, MemoryLease::unlinked(), reciprocal);
+        task.set_sequencer(store_msg.sequencer);
+        assert!(
+            writer.enqueue_store(
+                store_msg.first_offset,
+                store_msg.last_offset().unwrap(),
+                encode_records(&store_msg.payloads, &mut BytesMut::new())
+                    .expect("records can be encoded"),
+                task,
+            ),
+            "writer channel open"
+        );
+        loglet_state
+            .get_local_tail_watch()
+            .wait_for_offset(committed_up_to)
+            .await
+            .map_err(|_| {
+                restate_bifrost::loglet::OperationError::Shutdown(restate_core::ShutdownError)
+            })?;
+        Ok(())
+    }
+
+    fn segment_files(loglet_id: LogletId) -> Result<Vec<String>> {
+        let dir = Configuration::pinned()
+            .log_server
+            .segment_data_dir()
+            .join(loglet_dir_name(loglet_id));
+        let mut names = std::fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .filter(|name: &std::io::Result<String>| {
+                name.as_ref()
+                    .map_or(true, |name| name.ends_with(".segment"))
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        names.sort();
+        Ok(names)
+    }
+
+    fn get_records(loglet_id: LogletId, from: u32, to: u32) -> GetRecords {
+        GetRecords {
+            header: LogServerRequestHeader::new(loglet_id, LogletOffset::INVALID),
+            total_limit_in_bytes: None,
+            filter: KeyFilter::Any,
+            from_offset: LogletOffset::new(from),
+            to_offset: LogletOffset::new(to),
+        }
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn log_store_marker() -> Result<()> {
+        let log_store = setup().await?;
+
+        let marker = log_store.load_marker().await?;
+        assert!(marker.is_none());
+        let marker = LogStoreMarker::new(PlainNodeId::new(111));
+        log_store.store_marker(marker.clone()).await?;
+
+        let marker_again = log_store.load_marker().await?;
+        assert_that!(marker_again, some(eq(marker)));
+
+        // unconditionally store again.
+        let marker = LogStoreMarker::new(PlainNodeId::new(999));
+        log_store.store_marker(marker.clone()).await?;
+        let marker_again = log_store.load_marker().await?;
+        assert_that!(marker_again, some(eq(marker)));
+
+        TaskCenter::shutdown_node("test completed", 0).await;
+        Ok(())
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn load_loglet_state_and_recover() -> Result<()> {
+        let log_store = setup().await?;
+        let loglet_id = LogletId::new_unchecked(88);
+
+        let state = log_store.load_loglet_state(loglet_id).await?;
+        assert!(!state.is_sealed());
+        assert_that!(state.local_tail().offset(), eq(LogletOffset::OLDEST));
+        assert_that!(state.trim_point(), eq(LogletOffset::INVALID));
+        assert!(state.sequencer().is_none());
+
+        let payloads = vec![Record::from("a sample record".to_owned()); 3];
+        enqueue_store_and_wait(
+            &log_store,
+            &state,
+            store_msg(loglet_id, 1, payloads.clone()),
+        )
+        .await?;
+        // a retried store overwrites offset 2 and 3
+        enqueue_store_and_wait(&log_store, &state, store_msg(loglet_id, 2, payloads)).await?;
+
+        // a fresh store (e.g. after restart) recovers the state from the segment files
+        let recovered = setup().await?;
+        let state = recovered.load_loglet_state(loglet_id).await?;
+        assert_that!(state.local_tail().offset(), eq(LogletOffset::new(5)));
+        assert_that!(
+            state.sequencer(),
+            some(eq(&GenerationalNodeId::new(5, 213)))
+        );
+
+        // simulate a torn write at the end of the last segment
+        let segment_path = Configuration::pinned()
+            .log_server
+            .segment_data_dir()
+            .join(loglet_dir_name(loglet_id))
+            .join(segment_file_name(1));
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&segment_path)?;
+        let len = file.metadata()?.len();
+        file.write_all_at(&[0xAB; 11], len)?;
+        drop(file);
+
+        let recovered = setup().await?;
+        let state = recovered.load_loglet_state(loglet_id).await?;
+        assert_that!(state.local_tail().offset(), eq(LogletOffset::new(5)));
+        // the torn tail was truncated
+        assert_that!(std::fs::metadata(&segment_path)?.len(), eq(len));
+
+        let records = recovered
+            .read_records(get_records(loglet_id, 1, 100), &state)
+            .await?;
+        assert_that!(records.next_offset, eq(LogletOffset::new(5)));
+        assert_that!(records.records.len(), eq(4));
+
+        TaskCenter::shutdown_node("test completed", 0).await;
+        Ok(())
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn trim_deletes_segments() -> Result<()> {
+        // segments are at least 1MiB, each of them holds two of these records
+        let mut config = Configuration::default();
+        config.log_server.segment_max_size = NonZeroByteCount::new(NonZeroUsize::MIN);
+        set_current_config(config);
+        let log_store = setup().await?;
+        let loglet_id = LogletId::new_unchecked(89);
+        let mut state = log_store.load_loglet_state(loglet_id).await?;
+
+        let record = Record::from("x".repeat(400 * 1024));
+        for offset in 1..=8 {
+            enqueue_store_and_wait(
+                &log_store,
+                &state,
+                store_msg(loglet_id, offset, vec![record.clone()]),
+            )
+            .await?;
+        }
+        assert_that!(segment_files(loglet_id)?.len(), eq(4));
+
+        // trim through the loglet writer like the loglet worker does
+        let trim_point = LogletOffset::new(5);
+        state.update_trim_point(trim_point);
+        let mut writer = log_store.new_loglet_writer(loglet_id, &state);
+        let (reciprocal, rx) = Reciprocal::mock();
+        assert!(writer.enqueue_trim(TrimStorageTask::new(loglet_id, trim_point, reciprocal)));
+        let trimmed = rx.recv().await;
+        assert_that!(trimmed.status, eq(Status::Ok));
+
+        // segments with records up to 2 and 4 are gone
+        assert_that!(
+            segment_files(loglet_id)?,
+            elements_are![eq(&segment_file_name(3)), eq(&segment_file_name(4))]
+        );
+
+        let records = log_store
+            .read_records(get_records(loglet_id, 1, 100), &state)
+            .await?;
+        assert_that!(records.next_offset, eq(LogletOffset::new(9)));
+        let offsets: Vec<_> = records.records.iter().map(|(offset, _)| **offset).collect();
+        assert_that!(offsets, elements_are![eq(1), eq(6), eq(7), eq(8)]);
+        assert!(matches!(
+            records.records[0].1,
+            MaybeRecord::TrimGap(ref gap) if gap.to == trim_point
+        ));
+
+        // the trim point survives a restart
+        let recovered = setup().await?;
+        let state = recovered.load_loglet_state(loglet_id).await?;
+        assert_that!(state.trim_point(), eq(trim_point));
+        assert_that!(state.local_tail().offset(), eq(LogletOffset::new(9)));
+
+        TaskCenter::shutdown_node("test completed", 0).await;
+        Ok(())
+    }
+
+    #[test(restate_core::test(start_paused = true))]
+    async fn digest() -> Result<()> {
+        let log_store = setup().await?;
+        let loglet_id = LogletId::new_unchecked(90);
+        let mut state = log_store.load_loglet_state(loglet_id).await?;
+
+        let payloads = vec![Record::from("a sample record".to_owned())];
+        for offset in [5, 7, 10, 11, 12, 13, 18] {
+            enqueue_store_and_wait(
+                &log_store,
+                &state,
+                store_msg(loglet_id, offset, payloads.clone()),
+            )
+            .await?;
+        }
+        // the store does not fill the gaps of the local tail, set it to 20 manually
+        state
+            .get_local_tail_watch()
+            .notify_offset_update(LogletOffset::new(20));
+        state.update_trim_point(LogletOffset::new(11));
+
+        // Flip a bit in the record of offset 12
+        {
+            let segments = log_store.catalog.get_or_load(loglet_id)?;
+            let segments = segments.read();
+            let (start, run) = segments.runs_in(LogletOffset::new(12), LogletOffset::new(12))[0];
+            let path = segments.segment_path(run.segment_id);
+            let file = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&path)?;
+            let position = seek_frame(&file, &path, run.position, 12 - *start)?;
+            let header = read_frame_header(&file, position)?.unwrap();
+            let last = position + header.frame_len() - 1;
+            let mut last_byte = [0];
+            file.read_exact_at(&mut last_byte, last)?;
+            file.write_all_at(&[last_byte[0] ^ 0x01], last)?;
+        }
+
+        let msg = GetDigest {
+            header: LogServerRequestHeader::new(loglet_id, LogletOffset::new(200)),
+            from_offset: LogletOffset::new(5),
+            to_offset: LogletOffset::new(200),
+            verify_checksums: false,
+        };
+        let digest = log_store.get_records_digest(msg.clone(), &state).await?;
+        assert_that!(digest.header.status, eq(Status::Ok));
+        assert_that!(digest.header.local_tail, eq(LogletOffset::new(20)));
+        // we expect [5..11] T, [12..13] X, [18..18] X
+        assert_that!(
+            digest.entries,
+            elements_are![
+                eq(DigestEntry {
+                    from_offset: 5.into(),
+                    to_offset: 11.into(),
+                    status: RecordStatus::Trimmed,
+                }),
+                eq(DigestEntry {
+                    from_offset: 12.into(),
+                    to_offset: 13.into(),
+                    status: RecordStatus::Exists,
+                }),
+                eq(DigestEntry {
+                    from_offset: 18.into(),
+                    to_offset: 18.into(),
+                    status: RecordStatus::Exists,
+                }),
+            ]
+        );
+
+        let digest = log_store
+            .get_records_digest(
+                GetDigest {
+                    verify_checksums: true,
+                    ..msg
+                },
+                &state,
+            )
+            .await?;
+        // we expect [5..11] T, [12..12] C, [13..13] X, [18..18] X
+        assert_that!(
+            digest.entries,
+            elements_are![
+                eq(DigestEntry {
+                    from_offset: 5.into(),
+                    to_offset: 11.into(),
+                    status: RecordStatus::Trimmed,
+                }),
+                eq(DigestEntry {
+                    from_offset: 12.into(),
+                    to_offset: 12.into(),
+                    status: RecordStatus::Corrupted,
+                }),
+                eq(DigestEntry {
+                    from_offset: 13.into(),
+                    to_offset: 13.into(),
+                    status: RecordStatus::Exists,
+                }),
+                eq(DigestEntry {
+                    from_offset: 18.into(),
+                    to_offset: 18.into(),
+                    status: RecordStatus::Exists,
+                }),
+            ]
+        );
+
+        TaskCenter::shutdown_node("test completed", 0).await;
+        Ok(())
+    }
+}
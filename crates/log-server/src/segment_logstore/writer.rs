@@ -0,0 +1,537 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ahash::HashMap;
+use bytes::BytesMut;
+use metrics::{Histogram, histogram};
+use smallvec::SmallVec;
+use tokio::sync::mpsc;
+use tracing::{debug, error, trace, warn};
+
+use restate_core::{ShutdownError, TaskCenter, TaskKind, cancellation_token};
+use restate_types::GenerationalNodeId;
+use restate_types::config::{Configuration, LogServerOptions};
+use restate_types::logs::{LogletId, LogletOffset, SequenceNumber};
+use restate_types::net::log_server::Status;
+
+use super::SegmentLogStoreError;
+use super::format::encode_frame;
+use super::segments::{PendingSync, SegmentCatalog};
+
+use crate::logstore::{LogStoreState, WriteDisableReason};
+use crate::metadata::LogletState;
+use crate::metric_definitions::LOG_SERVER_WRITE_BATCH_SIZE_BYTES;
+use crate::rocksdb_logstore::block_in_place;
+use crate::rocksdb_logstore::record_format::EncodedRecord;
+use crate::tasks::{
+    SealStorageTask, StoreStorageTask, SyncGlobalTailStorageTask, TrimStorageTask, WriteStorageTask,
+};
+
+/// Commands sent to the [`SegmentLogStoreWriter`] over the mpsc channel.
+enum LogStoreWriteCommand {
+    Write {
+        task: Box<dyn WriteStorageTask>,
+        op: WriteOp,
+    },
+    /// Register a loglet's state so the writer can advance its tail watch.
+    Register {
+        loglet_id: LogletId,
+        loglet_state: LogletState,
+    },
+    /// Unregister a loglet's state. Only removes the entry if the watch in the
+    /// map is the same instance (identity check via `same_watch()`).
+    Unregister {
+        loglet_id: LogletId,
+        loglet_state: LogletState,
+    },
+}
+
+enum WriteOp {
+    Store {
+        records: Vec<EncodedRecord>,
+        first_offset: LogletOffset,
+        last_offset: LogletOffset,
+        sequencer: Option<GenerationalNodeId>,
+    },
+    Seal,
+    Trim {
+        trim_point: LogletOffset,
+    },
+    SetGlobalTail {
+        known_global_tail: LogletOffset,
+    },
+}
+
+impl std::fmt::Debug for LogStoreWriteCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogStoreWriteCommand::Write { op, .. } => match op {
+                WriteOp::Store {
+                    first_offset,
+                    last_offset,
+                    sequencer,
+                    ..
+                } => {
+                    write!(f, "Write(Store[{first_offset}..{last_offset}]")?;
+                    if sequencer.is_some() {
+                        write!(f, "+SetSequencer")?;
+                    }
+                    write!(f, ")")
+                }
+                WriteOp::Seal => write!(f, "Write(Seal)"),
+                WriteOp::Trim { trim_point } => write!(f, "Write(Trim[..={trim_point}])"),
+                WriteOp::SetGlobalTail { known_global_tail } => {
+                    write!(f, "Write(UpdateGlobalTail[{known_global_tail}])")
+                }
+            },
+            LogStoreWriteCommand::Register { loglet_id, .. } => {
+                write!(f, "Register(loglet={loglet_id})")
+            }
+            LogStoreWriteCommand::Unregister { loglet_id, .. } => {
+                write!(f, "Unregister(loglet={loglet_id})")
+            }
+        }
+    }
+}
+
+pub(crate) struct SegmentLogStoreWriterBuilder {
+    catalog: Arc<SegmentCatalog>,
+    /// Store-level state shared with loglet workers. The writer sets the
+    /// disable reason on failsafe to unblock all store waiters.
+    log_store_state: LogStoreState,
+}
+
+impl SegmentLogStoreWriterBuilder {
+    pub(crate) fn new(catalog: Arc<SegmentCatalog>, log_store_state: LogStoreState) -> Self {
+        Self {
+            catalog,
+            log_store_state,
+        }
+    }
+
+    /// Must be called from task_center context
+    pub fn start(self) -> Result<SegmentLogWriterHandle, ShutdownError> {
+        // The channel is bounded by the ability to acquire memory for those writes via the memory
+        // pool.
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        TaskCenter::spawn(
+            TaskKind::LogStoreWriter,
+            "log-server-segment-writer",
+            async move {
+                let mut writer = SegmentLogStoreWriter {
+                    catalog: self.catalog,
+                    state_map: HashMap::default(),
+                    pending_sync: PendingSync::default(),
+                    write_size_histogram: histogram!(LOG_SERVER_WRITE_BATCH_SIZE_BYTES),
+                    log_store_state: self.log_store_state.clone(),
+                };
+                debug!("Start running SegmentLogStoreWriter");
+                let mut config = Configuration::live();
+                let mut batch = Batch::default();
+                let cancel = cancellation_token();
+                let mut draining = false;
+
+                loop {
+                    tokio::select! {
+                        biased;
+                        () = cancel.cancelled(), if !draining => {
+                            draining = true;
+                            rx.close();
+                            continue;
+                        }
+                        Some(cmd) = rx.recv() => {
+                            writer.handle_command(cmd, &mut batch);
+                        }
+                        else => {
+                            // the channel is closed and drained, we are done.
+                            break;
+                        }
+                    }
+
+                    let config = &config.live_load().log_server;
+                    let batch_bytes_limit = config.write_batch_commit_bytes();
+
+                    // Opportunistically drain queued commands into the same batch.
+                    while batch.size_in_bytes < batch_bytes_limit
+                        && config
+                            .write_batch_commit_count
+                            .is_none_or(|c| batch.len < c.get())
+                        && let Ok(cmd) = rx.try_recv()
+                    {
+                        writer.handle_command(cmd, &mut batch);
+                    }
+
+                    if !writer.commit(config, &mut batch) {
+                        // the store is disabled, will drop the rest of the commands.
+                        break;
+                    }
+                }
+
+                if self.log_store_state.accepting_writes() {
+                    // write the final batch
+                    if !batch.is_empty() {
+                        writer.commit(&config.live_load().log_server, &mut batch);
+                    }
+                    debug!("Segment log-store writer shutdown complete");
+                    self.log_store_state
+                        .disable_writes(WriteDisableReason::Shutdown);
+                } else {
+                    // guaranteed that it's initialized
+                    let reason = self.log_store_state.wait_disabled().await;
+                    error!(
+                        "Writes to log-server have been disabled until the node is manually restarted and \
+                         the underlying reason has been resolved. Reason: {reason}",
+                    );
+                }
+                Ok(())
+            },
+        )?;
+        Ok(SegmentLogWriterHandle { tx })
+    }
+}
+
+#[derive(Default)]
+struct LogletBatch {
+    /// Encoded frames of all stores of this loglet in the batch
+    frames: BytesMut,
+    frame_lens: Vec<(LogletOffset, u32)>,
+    /// max committed offset for the current batch.
+    max_offset: Option<LogletOffset>,
+    sequencer: Option<GenerationalNodeId>,
+    trim_point: Option<LogletOffset>,
+    known_global_tail: Option<LogletOffset>,
+    notify_seal: bool,
+    tasks: SmallVec<[Box<dyn WriteStorageTask>; 2]>,
+    unregister: bool,
+}
+
+#[derive(Default)]
+struct Batch {
+    loglets: HashMap<LogletId, LogletBatch>,
+    /// Number of write operations in the batch
+    len: usize,
+    size_in_bytes: usize,
+}
+
+impl Batch {
+    fn clear(&mut self) {
+        self.loglets.clear();
+        self.len = 0;
+        self.size_in_bytes = 0;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+struct SegmentLogStoreWriter {
+    catalog: Arc<SegmentCatalog>,
+    /// Registered loglet states. The writer uses these to advance tail watches
+    /// after durable commit.
+    state_map: HashMap<LogletId, LogletState>,
+    pending_sync: PendingSync,
+    write_size_histogram: Histogram,
+    /// Store-level state shared with loglet workers. The writer sets the
+    /// disable reason on failsafe to unblock all store waiters.
+    log_store_state: LogStoreState,
+}
+
+impl SegmentLogStoreWriter {
+    fn handle_command(&mut self, command: LogStoreWriteCommand, batch: &mut Batch) {
+        trace!("SegmentLogStoreWriter: {command:?}");
+        match command {
+            LogStoreWriteCommand::Register {
+                loglet_id,
+                loglet_state,
+            } => {
+                if let Some(batch_loglet) = batch.loglets.get_mut(&loglet_id) {
+                    batch_loglet.unregister = false;
+                } else {
+                    self.state_map.insert(loglet_id, loglet_state);
+                }
+            }
+            LogStoreWriteCommand::Unregister {
+                loglet_id,
+                loglet_state,
+            } => {
+                // Same identity check as the RocksDB writer, a stale unregister must not
+                // remove a fresh registration.
+                if let Some(existing) = self.state_map.get(&loglet_id)
+                    && existing
+                        .get_local_tail_watch()
+                        .same_watch(&loglet_state.get_local_tail_watch())
+                {
+                    if let Some(batch_loglet) = batch.loglets.get_mut(&loglet_id) {
+                        // mark this loglet for unregistration at the end of the batch
+                        batch_loglet.unregister = true;
+                    } else {
+                        self.state_map.remove(&loglet_id);
+                    }
+                }
+            }
+            LogStoreWriteCommand::Write { mut task, op } => {
+                let loglet_id = task.loglet_id();
+                let loglet_batch = batch.loglets.entry(loglet_id).or_default();
+                task.on_start();
+                loglet_batch.tasks.push(task);
+                batch.len += 1;
+
+                match op {
+                    WriteOp::Store {
+                        records,
+                        first_offset,
+                        last_offset,
+                        sequencer,
+                    } => {
+                        loglet_batch.max_offset = loglet_batch.max_offset.max(Some(last_offset));
+                        if sequencer.is_some() {
+                            loglet_batch.sequencer = sequencer;
+                        }
+                        let mut offset = first_offset;
+                        for record in records {
+                            let frame_len = encode_frame(&mut loglet_batch.frames, offset, record);
+                            loglet_batch.frame_lens.push((offset, frame_len));
+                            batch.size_in_bytes += frame_len as usize;
+                            offset = offset.next();
+                        }
+                    }
+                    WriteOp::Seal => {
+                        loglet_batch.notify_seal = true;
+                    }
+                    WriteOp::Trim { trim_point } => {
+                        loglet_batch.trim_point = loglet_batch.trim_point.max(Some(trim_point));
+                    }
+                    WriteOp::SetGlobalTail { known_global_tail } => {
+                        loglet_batch.known_global_tail =
+                            loglet_batch.known_global_tail.max(Some(known_global_tail));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes the batch to the segment files and syncs them. Returns true if the batch was
+    /// committed successfully.
+    fn commit(&mut self, opts: &LogServerOptions, batch: &mut Batch) -> bool {
+        if batch.is_empty() {
+            return true;
+        }
+        self.write_size_histogram.record(batch.size_in_bytes as f64);
+
+        let result = if opts.read_only {
+            Err(SegmentLogStoreError::ReadOnly)
+        } else {
+            block_in_place(|| self.write_batch(opts, batch))
+        };
+
+        match result {
+            Ok(trimmed_segments) => {
+                for (loglet_id, loglet_batch) in batch.loglets.drain() {
+                    let Some(loglet_state) = self.state_map.get(&loglet_id) else {
+                        error!(
+                            "Storage task completed after the loglet was unregistered from log-server writer."
+                        );
+                        // panic in debug mode.
+                        debug_assert!(
+                            false,
+                            "Storage task completed after the loglet {loglet_id} was unregistered from log-server writer."
+                        );
+                        continue;
+                    };
+
+                    // Advance tail watches — one notification per loglet with the max offset.
+                    if let Some(max_offset) = loglet_batch.max_offset {
+                        loglet_state
+                            .local_tail_watch()
+                            .notify(loglet_batch.notify_seal, max_offset.next());
+                    } else if loglet_batch.notify_seal {
+                        loglet_state.local_tail_watch().notify_seal();
+                    }
+
+                    let local_tail = loglet_state.local_tail();
+                    for mut task in loglet_batch.tasks {
+                        task.on_complete(local_tail, loglet_state.known_global_tail(), Status::Ok);
+                    }
+
+                    if loglet_batch.unregister {
+                        self.state_map.remove(&loglet_id);
+                    }
+                }
+                batch.clear();
+
+                // Readers observe the new trim point before the segments disappear, those
+                // that already started reading them retry after re-checking the trim point.
+                block_in_place(|| {
+                    for path in trimmed_segments {
+                        match std::fs::remove_file(&path) {
+                            Ok(()) => trace!("Removed trimmed segment {}", path.display()),
+                            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                            Err(e) => {
+                                warn!("Failed to remove trimmed segment {}: {e}", path.display())
+                            }
+                        }
+                    }
+                });
+                true
+            }
+            Err(e) => {
+                // Disable writes to unblock all store waiters and to prevent future writes until
+                // the node has been manually restarted.
+                error!("Cannot write to log-server segment files: {e}");
+                let reason = if matches!(e, SegmentLogStoreError::ReadOnly) {
+                    WriteDisableReason::Manual
+                } else {
+                    WriteDisableReason::Error(e.into())
+                };
+                self.log_store_state.disable_writes(reason);
+                self.pending_sync.clear();
+                batch.clear();
+                // Stops the writer
+                false
+            }
+        }
+    }
+
+    /// Appends the records of the batch and persists metadata changes. Returns the segments
+    /// that became obsolete by trims in this batch.
+    fn write_batch(
+        &mut self,
+        opts: &LogServerOptions,
+        batch: &Batch,
+    ) -> Result<Vec<PathBuf>, SegmentLogStoreError> {
+        let max_segment_size = opts.segment_max_size() as u64;
+        let index_interval = opts.segment_index_interval.get();
+        let mut trimmed_segments = Vec::new();
+
+        for (loglet_id, loglet_batch) in &batch.loglets {
+            let segments = self.catalog.get_or_load(*loglet_id)?;
+            let mut segments = segments.write();
+
+            if !loglet_batch.frame_lens.is_empty() {
+                segments.append(
+                    &loglet_batch.frames,
+                    &loglet_batch.frame_lens,
+                    max_segment_size,
+                    index_interval,
+                    &mut self.pending_sync,
+                )?;
+            }
+
+            let meta = segments.meta_mut();
+            let mut meta_changed = false;
+            if let Some(sequencer) = loglet_batch.sequencer
+                && meta.sequencer != Some(sequencer)
+            {
+                meta.sequencer = Some(sequencer);
+                meta_changed = true;
+            }
+            if loglet_batch.notify_seal && !meta.sealed {
+                meta.sealed = true;
+                meta_changed = true;
+            }
+            if loglet_batch.trim_point > meta.trim_point {
+                meta.trim_point = loglet_batch.trim_point;
+                meta_changed = true;
+            }
+            if loglet_batch.known_global_tail > meta.known_global_tail {
+                meta.known_global_tail = loglet_batch.known_global_tail;
+                meta_changed = true;
+            }
+            if meta_changed {
+                segments.persist_meta()?;
+            }
+
+            if let Some(trim_point) = segments.meta().trim_point
+                && loglet_batch.trim_point.is_some()
+            {
+                trimmed_segments.extend(segments.trim(trim_point));
+            }
+        }
+
+        // One fsync per written segment file for the entire batch
+        self.pending_sync.sync()?;
+        Ok(trimmed_segments)
+    }
+}
+
+#[derive(Clone)]
+pub struct SegmentLogWriterHandle {
+    tx: mpsc::UnboundedSender<LogStoreWriteCommand>,
+}
+
+impl SegmentLogWriterHandle {
+    /// Registers a loglet's state with the writer so it can advance the tail
+    /// watch after durable commits.
+    pub fn register_loglet(&self, loglet_id: LogletId, loglet_state: LogletState) -> bool {
+        self.tx
+            .send(LogStoreWriteCommand::Register {
+                loglet_id,
+                loglet_state,
+            })
+            .is_ok()
+    }
+
+    /// Unregisters a loglet's state. Uses identity check (`same_watch()`) to
+    /// prevent a stale unregister from removing a fresh registration.
+    pub fn unregister_loglet(&self, loglet_id: LogletId, loglet_state: LogletState) -> bool {
+        self.tx
+            .send(LogStoreWriteCommand::Unregister {
+                loglet_id,
+                loglet_state,
+            })
+            .is_ok()
+    }
+
+    pub fn enqueue_seal(&self, task: SealStorageTask) -> bool {
+        self.send_write(Box::new(task), WriteOp::Seal)
+    }
+
+    pub fn enqueue_put_records(
+        &self,
+        first_offset: LogletOffset,
+        last_offset: LogletOffset,
+        records: Vec<EncodedRecord>,
+        task: StoreStorageTask,
+    ) -> bool {
+        trace!(loglet_id = %task.loglet_id(), "Sending store [{first_offset}..{last_offset}] to writer");
+        let op = WriteOp::Store {
+            records,
+            first_offset,
+            last_offset,
+            sequencer: task.sequencer(),
+        };
+        self.send_write(Box::new(task), op)
+    }
+
+    pub fn enqueue_trim(&self, task: TrimStorageTask) -> bool {
+        let op = WriteOp::Trim {
+            trim_point: task.trim_point(),
+        };
+        self.send_write(Box::new(task), op)
+    }
+
+    pub fn enqueue_set_global_tail(&self, task: SyncGlobalTailStorageTask) {
+        let op = WriteOp::SetGlobalTail {
+            known_global_tail: task.global_tail(),
+        };
+        let _ = self.send_write(Box::new(task), op);
+    }
+
+    fn send_write(&self, task: Box<dyn WriteStorageTask>, op: WriteOp) -> bool {
+        self.tx
+            .send(LogStoreWriteCommand::Write { task, op })
+            .is_ok()
+    }
+}
@@ -19,7 +19,7 @@ use restate_core::network::{MessageRouterBuilder, NetworkServerBuilder};
 use restate_core::{Metadata, MetadataWriter, TaskCenter, TaskKind};
 use restate_metadata_store::{ReadWriteError, RetryError, retry_on_retryable_error};
 use restate_types::GenerationalNodeId;
-use restate_types::config::Configuration;
+use restate_types::config::{Configuration, LogStoreBackend};
 use restate_types::health::HealthStatus;
 use restate_types::live::Live;
 use restate_types::metadata_store::keys::NODES_CONFIG_KEY;
@@ -33,13 +33,20 @@ use crate::metadata::{ActiveWorkerMap, LogStoreMarker, LogletStateMap};
 use crate::metric_definitions::describe_metrics;
 use crate::network::RequestPump;
 use crate::rocksdb_logstore::{RocksDbLogStore, RocksDbLogStoreBuilder};
+use crate::segment_logstore::{SegmentLogStore, SegmentLogStoreBuilder};
+
+/// The log-store selected by `log-server.storage-backend`
+enum ConfiguredLogStore {
+    RocksDb(RocksDbLogStore),
+    SegmentFile(SegmentLogStore),
+}
 
 pub struct LogServerService {
     health_status: HealthStatus<LogServerStatus>,
     metadata: Metadata,
     request_processor: RequestPump,
     state_map: LogletStateMap,
-    log_store: RocksDbLogStore,
+    log_store: ConfiguredLogStore,
     active_worker_map: ActiveWorkerMap,
 }
 
@@ -57,18 +64,70 @@ impl LogServerService {
         // What do we need to create the log-server?
         //
         // 1. A log-store
-        let log_store_builder = RocksDbLogStoreBuilder::create()
-            .await
-            .map_err(LogServerBuildError::other)?;
-
         // 2. Fire up the log store.
-        let log_store = log_store_builder
-            .start(health_status.clone())
-            .await
-            .map_err(LogServerBuildError::other)?;
+        let config = updateable_config.live_load();
+        let (other, data_dir) = config.log_server.other_backend_data_dir();
+        if data_dir.exists() {
+            return Err(LogServerBuildError::StorageBackendMismatch {
+                configured: config.log_server.storage_backend,
+                other,
+                data_dir,
+            });
+        }
 
+        let (log_store, state_map) = match config.log_server.storage_backend {
+            LogStoreBackend::RocksDb => {
+                let log_store = RocksDbLogStoreBuilder::create()
+                    .await
+                    .map_err(LogServerBuildError::other)?
+                    .start(health_status.clone())
+                    .await
+                    .map_err(LogServerBuildError::other)?;
+                let state_map =
+                    Self::register_log_store(&log_store, &health_status, config, server_builder)
+                        .await?;
+                (ConfiguredLogStore::RocksDb(log_store), state_map)
+            }
+            LogStoreBackend::SegmentFile => {
+                let log_store = SegmentLogStoreBuilder::create()
+                    .await
+                    .map_err(LogServerBuildError::other)?
+                    .start(health_status.clone())
+                    .await
+                    .map_err(LogServerBuildError::other)?;
+                let state_map =
+                    Self::register_log_store(&log_store, &health_status, config, server_builder)
+                        .await?;
+                (ConfiguredLogStore::SegmentFile(log_store), state_map)
+            }
+        };
+
+        let request_processor = RequestPump::new(router_builder);
+        let active_worker_map = ActiveWorkerMap::default();
+
+        Ok(Self {
+            health_status,
+            metadata,
+            request_processor,
+            state_map,
+            log_store,
+            active_worker_map,
+        })
+    }
+
+    /// Loads the state of all known loglets and registers the log-server grpc service on top
+    /// of the given log-store.
+    async fn register_log_store<S>(
+        log_store: &S,
+        health_status: &HealthStatus<LogServerStatus>,
+        config: &Configuration,
+        server_builder: &mut NetworkServerBuilder,
+    ) -> Result<LogletStateMap, LogServerBuildError>
+    where
+        S: LogStore + Clone + Sync + Send + 'static,
+    {
         // Might fetch all known loglets from disk
-        let state_map = LogletStateMap::load_all(&log_store)
+        let state_map = LogletStateMap::load_all(log_store)
             .await
             .map_err(LogServerBuildError::other)?;
 
@@ -76,7 +135,7 @@ impl LogServerService {
         server_builder.register_grpc_service(
             TonicServiceFilter::new(
                 LogServerSvcHandler::new(log_store.clone(), state_map.clone())
-                    .into_server(&updateable_config.live_load().networking),
+                    .into_server(&config.networking),
                 StatusInRange::new(
                     health_status.clone(),
                     LogServerStatus::Ready,
@@ -86,17 +145,7 @@ impl LogServerService {
             crate::protobuf::FILE_DESCRIPTOR_SET,
         );
 
-        let request_processor = RequestPump::new(router_builder);
-        let active_worker_map = ActiveWorkerMap::default();
-
-        Ok(Self {
-            health_status,
-            metadata,
-            request_processor,
-            state_map,
-            log_store,
-            active_worker_map,
-        })
+        Ok(state_map)
     }
 
     /// Returns a handle to the active loglet worker map for external introspection.
@@ -109,16 +158,56 @@ impl LogServerService {
         &self.state_map
     }
 
-    pub async fn start(self, mut metadata_writer: MetadataWriter) -> anyhow::Result<()> {
+    pub async fn start(self, metadata_writer: MetadataWriter) -> anyhow::Result<()> {
         let LogServerService {
             health_status,
             metadata,
             request_processor: request_pump,
             state_map,
-            mut log_store,
+            log_store,
             active_worker_map,
         } = self;
 
+        match log_store {
+            ConfiguredLogStore::RocksDb(log_store) => {
+                Self::run(
+                    health_status,
+                    metadata,
+                    request_pump,
+                    state_map,
+                    log_store,
+                    active_worker_map,
+                    metadata_writer,
+                )
+                .await
+            }
+            ConfiguredLogStore::SegmentFile(log_store) => {
+                Self::run(
+                    health_status,
+                    metadata,
+                    request_pump,
+                    state_map,
+                    log_store,
+                    active_worker_map,
+                    metadata_writer,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn run<S>(
+        health_status: HealthStatus<LogServerStatus>,
+        metadata: Metadata,
+        request_pump: RequestPump,
+        state_map: LogletStateMap,
+        mut log_store: S,
+        active_worker_map: ActiveWorkerMap,
+        mut metadata_writer: MetadataWriter,
+    ) -> anyhow::Result<()>
+    where
+        S: LogStore + Clone + Sync + Send + 'static,
+    {
         // Run log-store checks and self-provision if needed.
         let storage_state =
             Self::provision_node(&metadata, &mut log_store, &mut metadata_writer).await?;
@@ -218,7 +307,9 @@ impl LogServerService {
                     "This node's storage-state is marked as `disabled`, log-server role cannot run on disabled nodes"
                 ));
             }
-            StorageState::ReadWrite | StorageState::ReadOnly if maybe_marker.is_none() => {
+            StorageState::ReadWrite | StorageState::ReadOnly | StorageState::Draining
+                if maybe_marker.is_none() =>
+            {
                 // Mark as data loss
                 warn!(
                     "Detected data loss for log-server of my node {}. The log-server marker is missing, storage-state was {}, will transition into `data-loss`",
@@ -238,7 +329,10 @@ impl LogServerService {
                     my_node_id.as_plain(),
                 ));
             }
-            StorageState::ReadWrite | StorageState::ReadOnly | StorageState::Gone => {}
+            StorageState::ReadWrite
+            | StorageState::ReadOnly
+            | StorageState::Draining
+            | StorageState::Gone => {}
         }
 
         debug!("My storage state: {:?}", my_storage_state);
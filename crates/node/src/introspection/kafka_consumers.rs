@@ -0,0 +1,112 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Local scanner implementation for the `sys_kafka_consumer` DataFusion table.
+//!
+//! This scanner reads the [`KafkaConsumerStates`] of the Kafka subscriptions running on
+//! this node and produces Arrow record batches for fan-out SQL queries.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use futures::stream;
+
+use restate_core::Metadata;
+use restate_ingress_kafka::{KafkaConsumerState, KafkaConsumerStates};
+use restate_storage_query_datafusion::Scan;
+use restate_storage_query_datafusion::kafka_consumer::SysKafkaConsumerBuilder;
+use restate_storage_query_datafusion::table_util::Builder;
+use restate_types::GenerationalNodeId;
+
+/// Creates a local scanner for `sys_kafka_consumer` from the state of the Kafka consumers.
+pub(crate) fn create_local_scanner(
+    states: KafkaConsumerStates,
+    metadata: Metadata,
+) -> Arc<dyn Scan> {
+    Arc::new(KafkaConsumersScanner { states, metadata })
+}
+
+struct KafkaConsumersScanner {
+    states: KafkaConsumerStates,
+    metadata: Metadata,
+}
+
+impl Debug for KafkaConsumersScanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("KafkaConsumersScanner")
+    }
+}
+
+impl Scan for KafkaConsumersScanner {
+    fn scan(
+        &self,
+        projection: SchemaRef,
+        _filters: &[Expr],
+        _batch_size: usize,
+        limit: Option<usize>,
+    ) -> SendableRecordBatchStream {
+        let snapshot = self.states.snapshot();
+        let my_node_id = self.metadata.my_node_id();
+        let schema = projection.clone();
+
+        let fut = async move {
+            let mut builder = SysKafkaConsumerBuilder::new(schema.clone());
+
+            for (count, state) in snapshot.iter().enumerate() {
+                if limit.is_some_and(|l| count >= l) {
+                    break;
+                }
+                append_row(&mut builder, my_node_id, state);
+            }
+
+            builder.finish()
+        };
+
+        Box::pin(RecordBatchStreamAdapter::new(projection, stream::once(fut)))
+    }
+}
+
+fn append_row(
+    builder: &mut SysKafkaConsumerBuilder,
+    node_id: GenerationalNodeId,
+    state: &KafkaConsumerState,
+) {
+    let mut row = builder.row();
+
+    row.fmt_plain_node_id(node_id.as_plain());
+    row.fmt_gen_node_id(node_id);
+    row.fmt_subscription_id(state.subscription_id);
+    row.consumer_group(&state.consumer_group);
+    if let Some(topic) = &state.topic {
+        row.topic(topic);
+    }
+    if let Some(partition) = state.partition {
+        row.partition(partition);
+    }
+    if let Some(offset) = state.processed_offset {
+        row.processed_offset(offset as u64);
+    }
+    if let Some(offset) = state.committed_offset {
+        row.committed_offset(offset as u64);
+    }
+    if let Some(offset) = state.high_watermark {
+        row.high_watermark(offset as u64);
+    }
+    if let Some(lag) = state.lag {
+        row.lag(lag as u64);
+    }
+    if let Some(error) = &state.last_error {
+        row.last_error(error);
+    }
+}
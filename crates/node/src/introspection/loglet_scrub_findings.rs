@@ -0,0 +1,98 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Local scanner implementation for the `sys_loglet_scrub_findings` DataFusion table.
+//!
+//! This scanner reads the [`ScrubFindings`] snapshot of the local replicated loglet
+//! scrubber and produces Arrow record batches for fan-out SQL queries.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use futures::stream;
+
+use restate_bifrost::providers::replicated_loglet::scrubber::{ScrubFinding, ScrubFindings};
+use restate_core::Metadata;
+use restate_storage_query_datafusion::Scan;
+use restate_storage_query_datafusion::loglet_scrub_finding::SysLogletScrubFindingsBuilder;
+use restate_storage_query_datafusion::table_util::Builder;
+use restate_types::GenerationalNodeId;
+
+/// Creates a local scanner for `sys_loglet_scrub_findings` from the findings of the
+/// replicated loglet scrubber.
+pub(crate) fn create_local_scanner(findings: ScrubFindings, metadata: Metadata) -> Arc<dyn Scan> {
+    Arc::new(LogletScrubFindingsScanner { findings, metadata })
+}
+
+struct LogletScrubFindingsScanner {
+    findings: ScrubFindings,
+    metadata: Metadata,
+}
+
+impl Debug for LogletScrubFindingsScanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LogletScrubFindingsScanner")
+    }
+}
+
+impl Scan for LogletScrubFindingsScanner {
+    fn scan(
+        &self,
+        projection: SchemaRef,
+        _filters: &[Expr],
+        _batch_size: usize,
+        limit: Option<usize>,
+    ) -> SendableRecordBatchStream {
+        let snapshot = self.findings.snapshot();
+        let my_node_id = self.metadata.my_node_id();
+        let schema = projection.clone();
+
+        let fut = async move {
+            let mut builder = SysLogletScrubFindingsBuilder::new(schema.clone());
+
+            for (count, finding) in snapshot.iter().enumerate() {
+                if limit.is_some_and(|l| count >= l) {
+                    break;
+                }
+                append_row(&mut builder, my_node_id, finding);
+            }
+
+            builder.finish()
+        };
+
+        Box::pin(RecordBatchStreamAdapter::new(projection, stream::once(fut)))
+    }
+}
+
+fn append_row(
+    builder: &mut SysLogletScrubFindingsBuilder,
+    node_id: GenerationalNodeId,
+    finding: &ScrubFinding,
+) {
+    let mut row = builder.row();
+
+    row.fmt_plain_node_id(node_id.as_plain());
+    row.fmt_gen_node_id(node_id);
+    row.log_id(u32::from(finding.loglet_id.log_id()));
+    row.segment_index(u32::from(finding.loglet_id.segment_index()));
+    row.fmt_loglet_id(finding.loglet_id);
+    row.offset(u32::from(finding.offset));
+    row.fmt_kind(finding.kind);
+    if let Some(replica) = finding.node_id {
+        row.fmt_replica_node_id(replica);
+    }
+    row.healthy_copies(finding.healthy_copies);
+    row.fmt_repair_outcome(finding.outcome);
+    row.detected_at(finding.detected_at.as_u64() as i64);
+}
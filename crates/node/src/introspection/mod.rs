@@ -15,4 +15,6 @@
 //! batches for fan-out SQL queries.
 
 pub(crate) mod bifrost_read_streams;
+pub(crate) mod kafka_consumers;
+pub(crate) mod loglet_scrub_findings;
 pub(crate) mod loglet_workers;
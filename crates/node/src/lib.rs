@@ -133,6 +133,10 @@ pub enum BuildError {
     #[code(unknown)]
     MetadataStoreClient(anyhow::Error),
 
+    #[error("failed to load encryption keys: {0}")]
+    #[code(unknown)]
+    Encryption(#[from] restate_encryption::EncryptionError),
+
     #[error("building metadata store failed: {0}")]
     #[code(unknown)]
     MetadataStore(#[from] anyhow::Error),
@@ -181,6 +185,10 @@ impl Node {
 
         let is_provisioned = marker.provisioned();
 
+        // Must happen before any storage is opened, so that nothing is written in plaintext
+        // once encryption at rest is configured.
+        restate_encryption::install_from_options(&config.encryption).await?;
+
         // If MetadataServerKind::Local and Role::MetadataServer are configured,
         // we use an in-memory client, ignoring the rest of the client config.
         // Client kind defaults to MetadataClientKind::Replicated, so we turn a
@@ -245,6 +253,7 @@ impl Node {
             record_cache.clone(),
             &mut router_builder,
         );
+        let scrub_findings = replicated_loglet_factory.scrub_findings();
 
         let bifrost_svc = BifrostService::new(metadata_manager.writer());
 
@@ -357,6 +366,27 @@ impl Node {
             remote_scanner_manager.register_node_scanner("bifrost_read_streams", local_scanner);
         }
 
+        // Register sys_kafka_consumer scanner if the worker role is present, since the workers
+        // run the Kafka subscriptions.
+        if let Some(worker_role) = &worker_role {
+            let local_scanner = introspection::kafka_consumers::create_local_scanner(
+                worker_role.kafka_consumer_states(),
+                metadata.clone(),
+            );
+            remote_scanner_manager.register_node_scanner("sys_kafka_consumer", local_scanner);
+        }
+
+        // Register sys_loglet_scrub_findings scanner — every node scrubs the loglets it
+        // sequences.
+        {
+            let local_scanner = introspection::loglet_scrub_findings::create_local_scanner(
+                scrub_findings,
+                metadata.clone(),
+            );
+            remote_scanner_manager
+                .register_node_scanner("sys_loglet_scrub_findings", local_scanner);
+        }
+
         // Register config scanner — available on every node.
         if !Configuration::pinned().common.disable_config_sql_table {
             let local_scanner = restate_storage_query_datafusion::config::create_scanner(
@@ -390,6 +420,7 @@ impl Node {
                 metadata.updateable_schema(),
                 metadata.updateable_partition_table(),
                 PartitionRouting::new(replica_set_states.clone(), tc.clone()),
+                ingestion_client.clone(),
             ))
         } else {
             None
@@ -18,6 +18,7 @@ use restate_core::network::Networking;
 use restate_core::network::TransportConnect;
 use restate_core::{MetadataWriter, TaskCenter, TaskKind};
 use restate_ingestion_client::IngestionClient;
+use restate_ingress_kafka::KafkaConsumerStates;
 use restate_partition_store::PartitionStoreManager;
 use restate_storage_query_datafusion::context::QueryContext;
 use restate_storage_query_datafusion::remote_query_scanner_manager::RemoteScannerManager;
@@ -86,6 +87,10 @@ where
         self.worker.rule_book_cache_handle()
     }
 
+    pub fn kafka_consumer_states(&self) -> KafkaConsumerStates {
+        self.worker.kafka_consumer_states()
+    }
+
     pub fn start(self) -> anyhow::Result<()> {
         TaskCenter::spawn(TaskKind::WorkerRole, "worker-service", async {
             self.worker.run().await
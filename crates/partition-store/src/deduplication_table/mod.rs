@@ -8,16 +8,23 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use restate_rocksdb::RocksDbReadPerfGuard;
-use restate_storage_api::Result;
+use std::ops::ControlFlow;
+
+use restate_rocksdb::{Priority, RocksDbReadPerfGuard};
 use restate_storage_api::deduplication_table::{
-    DedupSequenceNumber, ProducerId, ReadDeduplicationTable, WriteDeduplicationTable,
+    DedupSequenceNumber, ProducerId, ReadDeduplicationTable, ScanDeduplicationTable,
+    WriteDeduplicationTable,
 };
+use restate_storage_api::protobuf_types::PartitionStoreProtobufValue;
+use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::PartitionId;
 
 use crate::TableKind::Deduplication;
-use crate::keys::{KeyKind, define_table_key};
-use crate::{PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess};
+use crate::keys::{DecodeTableKey, KeyKind, define_table_key};
+use crate::{
+    PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
+    break_on_err,
+};
 
 define_table_key!(
     Deduplication,
@@ -56,6 +63,27 @@ impl ReadDeduplicationTable for PartitionStore {
     }
 }
 
+impl ScanDeduplicationTable for PartitionStore {
+    fn for_each_dedup_sequence_number<
+        F: FnMut((ProducerId, DedupSequenceNumber)) -> ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each(
+            "df-dedup",
+            Priority::Low,
+            TableScan::Prefix(DeduplicationKey::builder().partition_id(self.partition_id().into())),
+            move |(mut key, mut value)| {
+                let key = break_on_err(DeduplicationKey::deserialize_from(&mut key))?;
+                let sequence_number = break_on_err(DedupSequenceNumber::decode(&mut value))?;
+                f((key.producer_id, sequence_number)).map_break(Ok)
+            },
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
+}
+
 impl ReadDeduplicationTable for PartitionStoreTransaction<'_> {
     async fn get_dedup_sequence_number(
         &mut self,
@@ -0,0 +1,109 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Encryption at rest of user payloads stored in the partition store.
+//!
+//! Only values which carry user data (state values and journal entries) are sealed. Keys and
+//! internal bookkeeping values stay in plaintext so that range scans and merge operators keep
+//! working.
+//!
+//! Sealed values start with the [`StorageCodecKind::Encrypted`] tag, in place of the codec tag of
+//! the plaintext value. Whether a value is sealed is thus recorded in the value itself, values
+//! written while encryption was disabled are read as-is.
+
+use std::borrow::Cow;
+
+use restate_encryption::{EncryptionError, KeyRing};
+use restate_storage_api::{Result, StorageError};
+use restate_types::storage::StorageCodecKind;
+
+/// Seals a value which starts with a [`StorageCodecKind`] tag. Returns `None` if encryption at
+/// rest is disabled, in which case the value is stored as-is.
+#[inline]
+pub(crate) fn seal_encoded_value(value: &[u8]) -> Result<Option<Vec<u8>>> {
+    seal_encoded_value_with_key_ring(restate_encryption::key_ring().as_deref(), value)
+        .map_err(|err| StorageError::Generic(err.into()))
+}
+
+/// Opens a value written with [`seal_encoded_value`]. Values which aren't tagged with
+/// [`StorageCodecKind::Encrypted`] are returned unchanged.
+#[inline]
+pub(crate) fn open_encoded_value(value: &[u8]) -> Result<Cow<'_, [u8]>> {
+    match value.split_first() {
+        Some((&tag, sealed)) if tag == u8::from(StorageCodecKind::Encrypted) => {
+            restate_encryption::open(sealed)
+                .map(Cow::Owned)
+                .map_err(|err| StorageError::Generic(err.into()))
+        }
+        _ => Ok(Cow::Borrowed(value)),
+    }
+}
+
+fn seal_encoded_value_with_key_ring(
+    key_ring: Option<&KeyRing>,
+    value: &[u8],
+) -> std::result::Result<Option<Vec<u8>>, EncryptionError> {
+    let Some(key_ring) = key_ring else {
+        return Ok(None);
+    };
+    let sealed = key_ring.seal(value)?;
+    let mut tagged = Vec::with_capacity(1 + sealed.len());
+    tagged.push(u8::from(StorageCodecKind::Encrypted));
+    tagged.extend_from_slice(&sealed);
+    Ok(Some(tagged))
+}
+
+#[cfg(test)]
+mod tests {
+    use restate_encryption::KeyId;
+
+    use super::*;
+
+    #[test]
+    fn sealed_values_are_tagged() {
+        let key_ring = KeyRing::new(KeyId(1), [(KeyId(1), [1u8; 32])]).unwrap();
+        let value = [u8::from(StorageCodecKind::Protobuf), 1, 2, 3];
+
+        let sealed = seal_encoded_value_with_key_ring(Some(&key_ring), &value)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sealed[0], u8::from(StorageCodecKind::Encrypted));
+        assert_eq!(key_ring.open(&sealed[1..]).unwrap(), value);
+
+        assert!(
+            seal_encoded_value_with_key_ring(None, &value)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn plaintext_values_are_not_opened() {
+        // Plaintext looking like a sealed envelope stays readable without a key ring
+        let value = [
+            u8::from(StorageCodecKind::Custom),
+            0xE5,
+            b'N',
+            b'C',
+            1,
+            0,
+            0,
+            0,
+        ];
+        assert!(matches!(
+            open_encoded_value(&value).unwrap(),
+            Cow::Borrowed(opened) if opened == value
+        ));
+        assert!(matches!(
+            open_encoded_value(&[]).unwrap(),
+            Cow::Borrowed(opened) if opened.is_empty()
+        ));
+    }
+}
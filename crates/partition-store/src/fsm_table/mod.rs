@@ -20,7 +20,7 @@ use restate_storage_api::fsm_table::{
 use restate_storage_api::protobuf_types::{PartitionStoreProtobufValue, ProtobufStorageWrapper};
 use restate_storage_api::{Result, StorageError};
 use restate_types::SemanticRestateVersion;
-use restate_types::identifiers::PartitionId;
+use restate_types::identifiers::{PartitionId, PartitionKey};
 use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
 use restate_types::partitions::StorageVersion;
@@ -101,6 +101,11 @@ pub(crate) mod fsm_variable {
     /// `VersionBarrierCommand` entries carrying feature changes.
     /// *Since v1.7.0*
     pub(crate) const STATE_MACHINE_FEATURES: u64 = 10;
+
+    /// Last partition key whose state values were tagged by the migration to
+    /// `StorageVersion::TaggedStateValues`. Removed once the migration completes.
+    /// *Since v1.7.0*
+    pub(crate) const TAGGED_STATE_VALUES_CURSOR: u64 = 11;
 }
 
 fn get<T: PartitionStoreProtobufValue, S: StorageAccess>(
@@ -179,14 +184,58 @@ pub(crate) fn append_storage_version_to_wb(
     wb: &mut rocksdb::WriteBatch,
     partition_id: PartitionId,
     version: StorageVersion,
+) -> Result<()> {
+    append_sequence_number_to_wb(
+        cf_handle,
+        wb,
+        partition_id,
+        fsm_variable::STORAGE_VERSION,
+        SequenceNumber::from(version as u64),
+    )
+}
+
+pub(crate) fn get_tagged_state_values_cursor(db: &PartitionDb) -> Result<Option<PartitionKey>> {
+    get_proto_from_partition_db::<SequenceNumber>(db, fsm_variable::TAGGED_STATE_VALUES_CURSOR)
+        .map(|cursor| cursor.map(u64::from))
+}
+
+/// Append a put of the tagged state values migration cursor to `wb`, or its removal if
+/// `cursor` is `None`.
+pub(crate) fn append_tagged_state_values_cursor_to_wb(
+    cf_handle: &std::sync::Arc<rocksdb::BoundColumnFamily<'_>>,
+    wb: &mut rocksdb::WriteBatch,
+    partition_id: PartitionId,
+    cursor: Option<PartitionKey>,
+) -> Result<()> {
+    match cursor {
+        Some(cursor) => append_sequence_number_to_wb(
+            cf_handle,
+            wb,
+            partition_id,
+            fsm_variable::TAGGED_STATE_VALUES_CURSOR,
+            SequenceNumber::from(cursor),
+        ),
+        None => {
+            let key = create_key(partition_id, fsm_variable::TAGGED_STATE_VALUES_CURSOR);
+            wb.delete_cf(cf_handle, key.to_bytes());
+            Ok(())
+        }
+    }
+}
+
+fn append_sequence_number_to_wb(
+    cf_handle: &std::sync::Arc<rocksdb::BoundColumnFamily<'_>>,
+    wb: &mut rocksdb::WriteBatch,
+    partition_id: PartitionId,
+    state_id: u64,
+    value: SequenceNumber,
 ) -> Result<()> {
     use bytes::BytesMut;
     use restate_types::storage::StorageCodec;
 
-    let key = create_key(partition_id, fsm_variable::STORAGE_VERSION);
+    let key = create_key(partition_id, state_id);
     let key_buffer = key.to_bytes();
 
-    let value = SequenceNumber::from(version as u64);
     let mut value_buffer = BytesMut::new();
     StorageCodec::encode(
         &ProtobufStorageWrapper::<<SequenceNumber as PartitionStoreProtobufValue>::ProtobufType>(
@@ -31,6 +31,7 @@ use restate_types::journal_v2::{CompletionId, EntryMetadata, NotificationId};
 use restate_types::storage::{StoredRawEntry, StoredRawEntryHeader};
 
 use crate::TableKind::Journal;
+use crate::encryption::open_encoded_value;
 use crate::keys::{DecodeTableKey, EncodeTableKey, KeyKind, define_table_key};
 use crate::owned_iter::OwnedIterator;
 use crate::{PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan, break_on_err};
@@ -107,9 +108,10 @@ impl<'a, DB: DBAccess> JournalEntryIter<'a, DB> {
 /// Decodes a V2 journal key/value pair from raw byte slices.
 fn decode_journal_entry_v2(k: &[u8], v: &[u8]) -> Result<(EntryIndex, StoredRawEntry)> {
     let mut k = k;
-    let mut v = v;
     let index = JournalKey::deserialize_from(&mut k)?.journal_index;
-    let entry = StoredEntry::decode(&mut v).map_err(|e| StorageError::Generic(e.into()))?;
+    let v = open_encoded_value(v)?;
+    let entry =
+        StoredEntry::decode(&mut v.as_ref()).map_err(|e| StorageError::Generic(e.into()))?;
     Ok((index, entry.0))
 }
 
@@ -167,7 +169,7 @@ fn put_journal_entry<S: StorageAccess>(
         }
     }
 
-    storage.put_kv_proto(
+    storage.put_kv_proto_sealed(
         write_journal_entry_key(invocation_id, journal_index),
         &StoredEntry(journal_entry.clone()),
     )
@@ -179,7 +181,7 @@ fn get_journal_entry<S: StorageAccess>(
     journal_index: u32,
 ) -> Result<Option<StoredRawEntry>> {
     let key = write_journal_entry_key(invocation_id, journal_index);
-    let opt: Option<StoredEntry> = storage.get_value_proto(key)?;
+    let opt: Option<StoredEntry> = storage.get_value_proto_sealed(key)?;
     Ok(opt.map(|e| e.0))
 }
 
@@ -218,8 +220,8 @@ async fn get_journal_entry_budgeted<S: StorageAccess>(
             if raw_size <= lease.size() {
                 // Lease already covers (or exceeds) the value — shrink and decode.
                 lease.shrink(lease.size() - raw_size);
-                let mut slice = pinned.as_ref();
-                let entry = StoredEntry::decode(&mut slice)
+                let value = open_encoded_value(pinned.as_ref())?;
+                let entry = StoredEntry::decode(&mut value.as_ref())
                     .map_err(|e| BudgetedReadError::Storage(StorageError::Generic(e.into())))?;
                 return Ok(Some((entry.0, lease)));
             }
@@ -228,8 +230,8 @@ async fn get_journal_entry_budgeted<S: StorageAccess>(
             let deficit = raw_size - lease.size();
             if let Some(extra) = budget.try_reserve(deficit) {
                 lease.merge(extra);
-                let mut slice = pinned.as_ref();
-                let entry = StoredEntry::decode(&mut slice)
+                let value = open_encoded_value(pinned.as_ref())?;
+                let entry = StoredEntry::decode(&mut value.as_ref())
                     .map_err(|e| BudgetedReadError::Storage(StorageError::Generic(e.into())))?;
                 return Ok(Some((entry.0, lease)));
             }
@@ -454,7 +456,7 @@ fn get_command_by_completion_id<S: StorageAccess>(
     // Now access the entry
     let journal_index = opt.unwrap().0;
     let key = write_journal_entry_key(&invocation_id, journal_index);
-    let opt: Option<StoredEntry> = storage.get_value_proto(key)?;
+    let opt: Option<StoredEntry> = storage.get_value_proto_sealed(key)?;
     if opt.is_none() {
         return Ok(None);
     }
@@ -601,10 +603,11 @@ impl ScanJournalTable for PartitionStore {
             "df-v2-journal",
             Priority::Low,
             scan,
-            move |(mut key, mut value)| {
+            move |(mut key, value)| {
                 let journal_key = break_on_err(JournalKey::deserialize_from(&mut key))?;
+                let value = break_on_err(open_encoded_value(value))?;
                 let journal_entry = break_on_err(
-                    StoredEntry::decode(&mut value)
+                    StoredEntry::decode(&mut value.as_ref())
                         .map_err(|err| StorageError::Conversion(err.into())),
                 )?;
 
@@ -10,6 +10,7 @@
 
 pub mod deduplication_table;
 mod durable_lsn_tracking;
+mod encryption;
 pub mod error;
 pub mod fsm_table;
 pub mod inbox_table;
@@ -11,6 +11,7 @@
 pub mod migrate_to_locks_table;
 mod migrate_to_scoped_promise_table;
 mod migrate_to_scoped_state_table;
+mod migrate_to_tagged_state_values;
 
 use std::num::NonZeroU16;
 use std::sync::Arc;
@@ -31,7 +32,7 @@ use restate_types::sharding::KeyRange;
 use restate_types::sharding::subsharding::ShardPlan;
 use restate_util_time::DurationExt;
 
-use crate::fsm_table::append_storage_version_to_wb;
+use crate::fsm_table::{append_storage_version_to_wb, append_tagged_state_values_cursor_to_wb};
 use crate::{PartitionDb, PartitionStore, Result};
 
 use self::migrate_to_scoped_promise_table::{
@@ -40,6 +41,7 @@ use self::migrate_to_scoped_promise_table::{
 use self::migrate_to_scoped_state_table::{
     append_delete_state_data, migrate_to_scoped_state_table,
 };
+use self::migrate_to_tagged_state_values::migrate_to_tagged_state_values;
 
 /// Migration error
 #[derive(Debug, thiserror::Error)]
@@ -144,8 +146,47 @@ async fn do_migration(
             new_storage_version
         }
         StorageVersion::ScopedStateAndPromise => {
+            let start = Instant::now();
+            let key_range = storage.partition_key_range();
+            let partition_id = storage.partition_id();
+            let partition_db = storage.partition_db().clone();
+            let mut ctx = MigrationContext::new(config, &partition_db, key_range, cancel);
+            let new_storage_version = StorageVersion::TaggedStateValues;
+
+            migrate_to_tagged_state_values(&mut ctx)?;
+
+            // Final step: drop the migration cursor + bump storage version in a single
+            // `WriteBatch`, see the doc-comment on `do_migration`.
+            let cf_handle = partition_db.cf_handle().clone();
+            let mut wb = WriteBatch::default();
+            append_tagged_state_values_cursor_to_wb(&cf_handle, &mut wb, partition_id, None)?;
+            append_storage_version_to_wb(&cf_handle, &mut wb, partition_id, new_storage_version)?;
+
+            ctx.fail_if_cancelled()?;
+            let mut opts = rocksdb::WriteOptions::default();
+            opts.disable_wal(true);
+            partition_db
+                .rocksdb()
+                .write_batch(
+                    "tagged-state-values-migration",
+                    Priority::High,
+                    IoMode::Default,
+                    opts,
+                    wb,
+                )
+                .await
+                .context("failed to commit tagged-state-values final write batch")
+                .map_err(StorageError::Generic)?;
+            debug!(
+                %partition_id,
+                "Finalized tagged state values migration in {}", start.elapsed().friendly()
+            );
+
+            new_storage_version
+        }
+        StorageVersion::TaggedStateValues => {
             // Latest version, nothing further to do.
-            StorageVersion::ScopedStateAndPromise
+            StorageVersion::TaggedStateValues
         }
     };
 
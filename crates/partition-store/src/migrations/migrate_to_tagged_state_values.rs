@@ -0,0 +1,132 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use bytes::BufMut;
+use rocksdb::WriteBatch;
+use tracing::debug;
+
+use restate_storage_api::StorageError;
+use restate_types::sharding::{KeyRange, PartitionKey};
+use restate_types::storage::StorageCodecKind;
+
+use crate::fsm_table::{append_tagged_state_values_cursor_to_wb, get_tagged_state_values_cursor};
+use crate::keys::KeyKind;
+use crate::scan::{PhysicalScan, TableScan};
+use crate::state_table::ScopedStateKey;
+
+use super::{MigrationContext, MigrationError};
+
+/// Size of the write batches above which they are committed.
+const BATCH_SIZE_BYTES: usize = 1024 * 1024;
+
+/// Rewrites every value of the scoped state table with a leading [`StorageCodecKind::Custom`]
+/// tag. Runs after the scoped state migration, so the unscoped state table is empty.
+///
+/// Values are rewritten in place, so running this twice over the same value would tag it twice.
+/// Each write batch thus also records the last partition key it covers, and an interrupted
+/// migration resumes after it. A batch always holds all the entries of its partition keys.
+///
+/// We use direct rocksdb access because no async operations are needed.
+pub fn migrate_to_tagged_state_values(
+    ctx: &mut MigrationContext<'_>,
+) -> Result<(), MigrationError> {
+    let rocks = ctx.partition_db.rocksdb();
+    let cf_handle = ctx.partition_db.cf_handle().clone();
+    let partition_id = ctx.partition_db.partition().id();
+
+    let start = match get_tagged_state_values_cursor(ctx.partition_db)? {
+        Some(cursor) if cursor >= ctx.key_range.end() => return Ok(()),
+        Some(cursor) => {
+            debug!(%partition_id, "Resuming state values tagging after partition key {cursor}");
+            cursor + 1
+        }
+        None => ctx.key_range.start(),
+    };
+    let mut counter = 0;
+
+    // The iterator reads from an implicit snapshot, so it doesn't see the rewritten values.
+    let mut iterator = ctx.partition_db.scan(
+        PhysicalScan::from(
+            TableScan::ScanPartitionKeyRange::<ScopedStateKey>(KeyRange::new(
+                start,
+                ctx.key_range.end(),
+            )),
+            &mut ctx.arena,
+        ),
+        rocksdb::ReadOptions::default(),
+    )?;
+    iterator.seek_to_first();
+
+    let mut wb = WriteBatch::with_capacity_bytes(BATCH_SIZE_BYTES);
+    let mut last_partition_key = None;
+
+    let mut opts = rocksdb::WriteOptions::default();
+    // We disable WAL since bifrost is our durable distributed log.
+    opts.disable_wal(true);
+
+    let commit = |wb: &mut WriteBatch, cursor: PartitionKey| -> Result<(), MigrationError> {
+        append_tagged_state_values_cursor_to_wb(&cf_handle, wb, partition_id, Some(cursor))?;
+        rocks
+            .inner()
+            .write_batch(wb, &opts)
+            .context("failed to write batch")
+            .map_err(StorageError::Generic)?;
+        wb.clear();
+        Ok(())
+    };
+
+    while iterator.valid() {
+        ctx.fail_if_cancelled()?;
+        // safe to unwrap because the iterator is valid
+        let (key, value) = iterator.item().unwrap();
+        let partition_key: PartitionKey = {
+            let mut key = key;
+            let kind = KeyKind::deserialize(&mut key)?;
+            debug_assert_eq!(kind, KeyKind::ScopedState);
+            crate::keys::deserialize(&mut key)?
+        };
+
+        // Only cut batches between partition keys, the cursor covers whole partition keys
+        if let Some(last_partition_key) = last_partition_key
+            && last_partition_key != partition_key
+            && wb.size_in_bytes() >= BATCH_SIZE_BYTES
+        {
+            commit(&mut wb, last_partition_key)?;
+        }
+
+        ctx.arena.clear();
+        ctx.arena.put_u8(StorageCodecKind::Custom.into());
+        ctx.arena.put_slice(value);
+        wb.put_cf(&cf_handle, key, &ctx.arena);
+        last_partition_key = Some(partition_key);
+        counter += 1;
+
+        iterator.next();
+    }
+
+    // ensures we didn't stop because of an iterator error
+    iterator
+        .status()
+        .context("iterating over state entries")
+        .map_err(StorageError::Generic)?;
+
+    if let Some(last_partition_key) = last_partition_key {
+        commit(&mut wb, last_partition_key)?;
+    }
+
+    debug!(%partition_id, "Finished tagging {} state values", counter);
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "../tests/migrations_test/migrate_to_tagged_state_values.rs"]
+mod tests;
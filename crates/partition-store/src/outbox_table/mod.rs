@@ -8,18 +8,21 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::ops::RangeInclusive;
+use std::ops::{ControlFlow, RangeInclusive};
 
-use restate_rocksdb::RocksDbReadPerfGuard;
-use restate_storage_api::Result;
-use restate_storage_api::outbox_table::{OutboxMessage, ReadOutboxTable, WriteOutboxTable};
+use restate_rocksdb::{Priority, RocksDbReadPerfGuard};
+use restate_storage_api::outbox_table::{
+    OutboxMessage, ReadOutboxTable, ScanOutboxTable, WriteOutboxTable,
+};
 use restate_storage_api::protobuf_types::PartitionStoreProtobufValue;
+use restate_storage_api::{Result, StorageError};
 use restate_types::identifiers::PartitionId;
 
 use crate::TableKind::Outbox;
 use crate::keys::{DecodeTableKey, KeyKind, define_table_key};
 use crate::{
     PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
+    break_on_err,
 };
 
 define_table_key!(
@@ -135,6 +138,26 @@ impl ReadOutboxTable for PartitionStore {
     }
 }
 
+impl ScanOutboxTable for PartitionStore {
+    fn for_each_outbox_message<
+        F: FnMut((u64, OutboxMessage)) -> ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        self.iterator_for_each(
+            "df-outbox",
+            Priority::Low,
+            TableScan::Prefix(OutboxKey::builder().partition_id(self.partition_id().into())),
+            move |(key, value)| {
+                let message = break_on_err(decode_key_value(key, value))?;
+                f(message).map_break(Ok)
+            },
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
+}
+
 impl ReadOutboxTable for PartitionStoreTransaction<'_> {
     async fn get_outbox_head_seq_number(&mut self) -> Result<Option<u64>> {
         get_outbox_head_seq_number(self, self.partition_id())
@@ -25,7 +25,7 @@ use tracing::{debug, info, instrument, warn};
 
 use restate_core::ShutdownError;
 use restate_rocksdb::configuration::{CfConfigurator, DbConfigurator};
-use restate_rocksdb::{DbName, RocksDb, RocksError};
+use restate_rocksdb::{DbName, OpenMode, RocksDb, RocksError};
 use restate_storage_api::StorageError;
 use restate_types::config::Configuration;
 use restate_types::logs::Lsn;
@@ -527,6 +527,7 @@ pub struct RocksConfigurator<T> {
     memory_budget: Arc<MemoryBudget>,
     shared_state: Arc<crate::SharedState>,
     use_multi_db_layout: bool,
+    open_mode: OpenMode,
     _marker: PhantomData<T>,
 }
 
@@ -536,6 +537,7 @@ impl<T> Clone for RocksConfigurator<T> {
             memory_budget: self.memory_budget.clone(),
             shared_state: self.shared_state.clone(),
             use_multi_db_layout: self.use_multi_db_layout,
+            open_mode: self.open_mode,
             _marker: PhantomData,
         }
     }
@@ -546,11 +548,13 @@ impl<T> RocksConfigurator<T> {
         memory_budget: Arc<MemoryBudget>,
         psm_state: Arc<crate::SharedState>,
         use_multi_db_layout: bool,
+        open_mode: OpenMode,
     ) -> Self {
         Self {
             memory_budget,
             shared_state: psm_state,
             use_multi_db_layout,
+            open_mode,
             _marker: PhantomData,
         }
     }
@@ -560,6 +564,10 @@ impl<T> RocksConfigurator<T> {
 pub struct AllDataCf;
 
 impl DbConfigurator for RocksConfigurator<AllDataCf> {
+    fn get_db_open_mode(&self) -> OpenMode {
+        self.open_mode
+    }
+
     fn get_db_options(
         &self,
         db_name: &DbName,
@@ -27,7 +27,7 @@ use tokio::sync::oneshot;
 use tokio::sync::watch;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::CancellationToken;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 
 use restate_core::ShutdownError;
 use restate_rocksdb::{IoMode, IterAction, Priority, RocksDb, RocksError};
@@ -45,6 +45,7 @@ use restate_types::storage::StorageEncode;
 
 use restate_types::partitions::StorageVersion;
 
+use crate::encryption::{open_encoded_value, seal_encoded_value};
 use crate::fsm_table::put_storage_version;
 use crate::fsm_table::{
     get_locally_durable_lsn, get_storage_version_from_partition_db, is_jc_orphan_cleanup_done,
@@ -638,8 +639,12 @@ impl PartitionStore {
         // the flag we leave the partition at `V1_5` so a downgrade to a
         // pre-`ScopedStateAndPromise` binary stays possible. With the flag
         // enabled we migrate the unscoped state and promise tables into their
-        // scoped variants and bump to `ScopedStateAndPromise`.
-        let target = if config
+        // scoped variants and bump to `ScopedStateAndPromise`. Encrypting state
+        // values requires them to be tagged, so configuring encryption at rest
+        // migrates up to `TaggedStateValues`.
+        let target = if config.encryption.is_enabled() {
+            StorageVersion::TaggedStateValues
+        } else if config
             .common
             .experimental
             .is_migrate_scoped_tables_enabled()
@@ -675,6 +680,14 @@ impl PartitionStore {
                     storage_version, target
                 );
             }
+            if target == StorageVersion::TaggedStateValues {
+                warn!(
+                    "Encryption at rest is configured, migrating the partition store to {:?}. \
+                    Once migrated, the partition store can't be opened by Restate versions older \
+                    than v1.7.3 anymore.",
+                    target
+                );
+            }
             storage_version =
                 run_migrations_up_to(storage_version, target, self, cancel, config).await?;
         }
@@ -1115,6 +1128,33 @@ pub(crate) trait StorageAccess {
         )
     }
 
+    /// Like [`StorageAccess::put_kv_proto`] but seals the encoded value if encryption at rest
+    /// is enabled. Values written this way must be read with
+    /// [`StorageAccess::get_value_proto_sealed`].
+    #[inline]
+    fn put_kv_proto_sealed<K: EncodeTableKey, V: PartitionStoreProtobufValue + Clone + 'static>(
+        &mut self,
+        key: K,
+        value: &V,
+    ) -> Result<()> {
+        let key_buffer = self.cleared_key_buffer_mut(key.serialized_length());
+        key.serialize_to(key_buffer);
+        let key_buffer = key_buffer.split();
+
+        let value_buffer = self.cleared_value_buffer_mut(0);
+        StorageCodec::encode(
+            &ProtobufStorageWrapper::<V::ProtobufType>(value.clone().into()),
+            value_buffer,
+        )
+        .map_err(|e| StorageError::Generic(e.into()))?;
+        let value_buffer = value_buffer.split();
+
+        match seal_encoded_value(&value_buffer)? {
+            Some(sealed) => self.put_cf(K::TABLE, key_buffer, sealed),
+            None => self.put_cf(K::TABLE, key_buffer, value_buffer),
+        }
+    }
+
     #[inline]
     fn put_kv_storage_codec<K: EncodeTableKey, V: StorageEncode + 'static>(
         &mut self,
@@ -1158,6 +1198,27 @@ pub(crate) trait StorageAccess {
             .map_err(|err| StorageError::Conversion(err.into()))
     }
 
+    /// Reads a value written by [`StorageAccess::put_kv_proto_sealed`].
+    #[inline]
+    fn get_value_proto_sealed<K, V>(&mut self, key: K) -> Result<Option<V>>
+    where
+        K: EncodeTableKey,
+        V: PartitionStoreProtobufValue,
+        <<V as PartitionStoreProtobufValue>::ProtobufType as TryInto<V>>::Error:
+            Into<anyhow::Error>,
+    {
+        let mut buf = self.cleared_key_buffer_mut(key.serialized_length());
+        key.serialize_to(&mut buf);
+        let buf = buf.split();
+
+        self.get(K::TABLE, &buf)?
+            .map(|value| {
+                let value = open_encoded_value(value.as_ref())?;
+                V::decode(&mut value.as_ref())
+            })
+            .transpose()
+    }
+
     #[inline]
     fn get_value_storage_codec<K, V>(&mut self, key: K) -> Result<Option<V>>
     where
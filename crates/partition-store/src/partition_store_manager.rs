@@ -16,7 +16,9 @@ use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use tokio::sync::Mutex as AsyncMutex;
 use tracing::{debug, error, info, instrument, warn};
 
-use restate_rocksdb::{CfPrefixPattern, DbSpecBuilder, RocksDb, RocksDbManager, RocksError};
+use restate_rocksdb::{
+    CfPrefixPattern, DbSpecBuilder, OpenMode, RocksDb, RocksDbManager, RocksError,
+};
 use restate_storage_api::fsm_table::ReadFsmTable;
 use restate_types::config::Configuration;
 use restate_types::identifiers::{PartitionId, SnapshotId};
@@ -128,10 +130,21 @@ pub struct PartitionStoreManager {
     db_cache: AsyncMutex<HashMap<restate_rocksdb::DbName, Weak<RocksDb>>>,
     memory_controller: MemoryController,
     use_multi_db_layout: bool,
+    open_mode: OpenMode,
 }
 
 impl PartitionStoreManager {
     pub async fn create(use_multi_db_layout: bool) -> Result<Arc<Self>, BuildError> {
+        Self::create_with_open_mode(use_multi_db_layout, OpenMode::ReadWrite).await
+    }
+
+    /// Creates a manager that opens the partition store databases in the given mode.
+    ///
+    /// [`OpenMode::ReadOnly`] is meant for offline tooling inspecting a stopped node's data.
+    pub async fn create_with_open_mode(
+        use_multi_db_layout: bool,
+        open_mode: OpenMode,
+    ) -> Result<Arc<Self>, BuildError> {
         crate::metric_definitions::describe_metrics();
 
         // Start the memory controller, how do we know when db is dropped?
@@ -146,6 +159,7 @@ impl PartitionStoreManager {
             db_cache: Default::default(),
             memory_controller,
             use_multi_db_layout,
+            open_mode,
         });
 
         Ok(psm)
@@ -164,6 +178,7 @@ impl PartitionStoreManager {
             self.memory_controller.memory_budget.clone(),
             Arc::clone(&self.state),
             self.use_multi_db_layout,
+            self.open_mode,
         );
 
         let db_spec = DbSpecBuilder::new(
@@ -75,6 +75,11 @@ pub struct PartitionSnapshotMetadata {
     /// The RocksDB SST files comprising the snapshot.
     #[serde_as(as = "Vec<SnapshotSstFile>")]
     pub files: Vec<LiveFile>,
+
+    /// Whether the snapshot files were sealed with the data encryption keys before upload. The
+    /// file sizes listed in [`Self::files`] are the plaintext sizes.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
 }
 
 impl PartitionSnapshotMetadata {
@@ -125,6 +130,8 @@ struct PartitionSnapshotMetadataShadow {
     pub db_comparator_name: String,
     #[serde_as(as = "Vec<SnapshotSstFile>")]
     pub files: Vec<LiveFile>,
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 impl From<PartitionSnapshotMetadataShadow> for PartitionSnapshotMetadata {
@@ -147,6 +154,7 @@ impl From<PartitionSnapshotMetadataShadow> for PartitionSnapshotMetadata {
             min_applied_lsn: value.min_applied_lsn,
             db_comparator_name: value.db_comparator_name,
             files: value.files,
+            encrypted: value.encrypted,
         }
     }
 }
@@ -514,18 +514,34 @@ impl SnapshotRepository {
         local_snapshot_path: &Path,
         progress: &mut SnapshotUploadProgress,
     ) -> Result<(), PutSnapshotError> {
+        let key_ring = if snapshot.encrypted {
+            Some(
+                restate_encryption::key_ring()
+                    .context("snapshot must be encrypted but no encryption keys are configured")
+                    .map_err(|e| PutSnapshotError::from(e, progress.clone()))?,
+            )
+        } else {
+            None
+        };
+
         let mut buf = BytesMut::new();
         for file in &snapshot.files {
             let filename = strip_leading_slash(&file.name);
             let key = self.snapshot_file_path(snapshot, filename);
+            let local_path = local_snapshot_path.join(filename);
 
-            let put_result = put_snapshot_object(
-                local_snapshot_path.join(filename).as_path(),
-                &key,
-                &self.object_store,
-                &mut buf,
-            )
-            .await
+            let put_result = if let Some(key_ring) = &key_ring {
+                let sealed_path = sealed_file_path(&local_path);
+                let result = async {
+                    restate_encryption::seal_file(key_ring, &local_path, &sealed_path).await?;
+                    put_snapshot_object(&sealed_path, &key, &self.object_store, &mut buf).await
+                }
+                .await;
+                let _ = tokio::fs::remove_file(&sealed_path).await;
+                result
+            } else {
+                put_snapshot_object(&local_path, &key, &self.object_store, &mut buf).await
+            }
             .map_err(|e| PutSnapshotError::from(e, progress.clone()))?;
 
             debug!(etag = %put_result.e_tag.unwrap_or_default(), %key, "Put snapshot object completed");
@@ -796,6 +812,17 @@ impl SnapshotRepository {
         )?;
         debug!(path = %snapshot_dir.path().display(), "Downloading snapshot");
 
+        let key_ring = if snapshot_metadata.encrypted {
+            Some(restate_encryption::key_ring().with_context(|| {
+                format!(
+                    "snapshot {} is encrypted but no encryption keys are configured",
+                    snapshot_metadata.snapshot_id
+                )
+            })?)
+        } else {
+            None
+        };
+
         let directory = snapshot_dir.path().to_string_lossy().to_string();
         let concurrency_limiter = Arc::new(Semaphore::new(DOWNLOAD_CONCURRENCY_LIMIT));
         let mut downloads = JoinSet::new();
@@ -814,6 +841,7 @@ impl SnapshotRepository {
             let object_store = Arc::clone(&self.object_store);
             let snapshot_id = snapshot_metadata.snapshot_id;
             let snapshot_filename = filename.to_owned();
+            let key_ring = key_ring.clone();
 
             let handle = downloads.build_task().name(filename).spawn(async move {
                 let _permit = concurrency_limiter.acquire().await?;
@@ -826,15 +854,28 @@ impl SnapshotRepository {
                         .into_stream(),
                 );
 
+                let download_path = if key_ring.is_some() {
+                    sealed_file_path(&local_path)
+                } else {
+                    local_path.clone()
+                };
                 let mut snapshot_file =
-                    tokio::fs::File::create_new(&local_path).await.map_err(|e| {
-                        anyhow!("Failed to create local partition {partition_id} snapshot file {local_path:?}: {e}")
+                    tokio::fs::File::create_new(&download_path).await.map_err(|e| {
+                        anyhow!("Failed to create local partition {partition_id} snapshot file {download_path:?}: {e}")
                     })?;
-                let size = io::copy(&mut file_data, &mut snapshot_file)
+                let mut size = io::copy(&mut file_data, &mut snapshot_file)
                     .await
                     .map_err(|e| anyhow!("Failed to download snapshot object {:?}: {}", key, e))?;
                 snapshot_file.shutdown().await?;
 
+                if let Some(key_ring) = key_ring {
+                    let result = restate_encryption::open_file(&key_ring, &download_path, &local_path).await;
+                    let _ = tokio::fs::remove_file(&download_path).await;
+                    size = result.map_err(|e| {
+                        anyhow!("Failed to decrypt partition {partition_id} snapshot {snapshot_id} file {key:?}: {e}")
+                    })?;
+                }
+
                 if size != expected_size as u64 {
                     return Err(anyhow!("Downloaded partition {partition_id} snapshot {snapshot_id} component file {:?} has unexpected size: expected: {}, actual: {}", snapshot_filename, expected_size, size));
                 }
@@ -1108,6 +1149,13 @@ async fn put_snapshot_object(
     }
 }
 
+/// Path of the temporary sealed copy of a snapshot file, next to the plaintext file.
+fn sealed_file_path(path: &Path) -> PathBuf {
+    let mut sealed = path.as_os_str().to_owned();
+    sealed.push(".sealed");
+    PathBuf::from(sealed)
+}
+
 async fn abort_tasks<T: 'static>(mut join_set: JoinSet<T>) {
     join_set.abort_all();
     while join_set.join_next().await.is_some() {}
@@ -1386,6 +1434,7 @@ mod tests {
                 smallest_seqno: 0,
                 largest_seqno: 0,
             }],
+            encrypted: false,
         }
     }
 
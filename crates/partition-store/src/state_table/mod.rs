@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::borrow::Cow;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -26,10 +27,12 @@ use restate_storage_api::state_table::{ReadStateTable, ScanStateTable, WriteStat
 use restate_storage_api::{BudgetedReadError, Result, StorageError};
 use restate_types::identifiers::{PartitionKey, ServiceId, WithPartitionKey};
 use restate_types::sharding::KeyRange;
+use restate_types::storage::StorageCodecKind;
 use restate_types::{Scope, ServiceName};
 use restate_util_string::ReString;
 
 use crate::TableKind::State;
+use crate::encryption::{open_encoded_value, seal_encoded_value};
 use crate::keys::{DecodeTableKey, KeyKind, define_table_key};
 use crate::{
     PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
@@ -88,11 +91,15 @@ fn user_state_key_from_slice(mut key: &[u8]) -> Result<Bytes> {
 /// to move forward. Also implements [`Iterator`] for convenience.
 pub struct StateEntryIter<'a, DB: DBAccess> {
     iter: DBRawIteratorWithThreadMode<'a, DB>,
+    storage_version: StorageVersion,
 }
 
 impl<'a, DB: DBAccess> StateEntryIter<'a, DB> {
-    fn new(iter: DBRawIteratorWithThreadMode<'a, DB>) -> Self {
-        Self { iter }
+    fn new(iter: DBRawIteratorWithThreadMode<'a, DB>, storage_version: StorageVersion) -> Self {
+        Self {
+            iter,
+            storage_version,
+        }
     }
 
     /// Returns the raw `(key, value)` byte slices at the current iterator
@@ -122,7 +129,7 @@ impl<DB: DBAccess> Iterator for StateEntryIter<'_, DB> {
             Ok(item) => item,
             Err(e) => return Some(Err(e)),
         };
-        let result = decode_user_state_key_value(k, v);
+        let result = decode_user_state_key_value(self.storage_version, k, v);
         self.advance();
         Some(result)
     }
@@ -137,6 +144,56 @@ fn use_scoped_state(storage_version: StorageVersion, service_id: &ServiceId) ->
     storage_version.is_scope_migrated() || service_id.scope.is_some()
 }
 
+/// Encodes a user state value as stored in the state table.
+///
+/// Since [`StorageVersion::TaggedStateValues`], values start with a [`StorageCodecKind`] tag:
+/// [`StorageCodecKind::Custom`] followed by the user bytes, or [`StorageCodecKind::Encrypted`]
+/// if they are sealed. Before, values are the plain user bytes and are never sealed, since a
+/// sealed value couldn't be told apart from user bytes.
+fn encode_state_value(storage_version: StorageVersion, value: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if !storage_version.has_tagged_state_values() {
+        return Ok(Cow::Borrowed(value));
+    }
+    let mut tagged = Vec::with_capacity(1 + value.len());
+    tagged.push(u8::from(StorageCodecKind::Custom));
+    tagged.extend_from_slice(value);
+    Ok(Cow::Owned(seal_encoded_value(&tagged)?.unwrap_or(tagged)))
+}
+
+/// Decodes a user state value written by [`encode_state_value`].
+fn decode_state_value(storage_version: StorageVersion, value: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if !storage_version.has_tagged_state_values() {
+        return Ok(Cow::Borrowed(value));
+    }
+    match open_encoded_value(value)? {
+        Cow::Borrowed(value) => untag_state_value(value).map(Cow::Borrowed),
+        Cow::Owned(mut value) => {
+            untag_state_value(&value)?;
+            value.drain(..1);
+            Ok(Cow::Owned(value))
+        }
+    }
+}
+
+fn decode_state_value_to_bytes(storage_version: StorageVersion, value: &[u8]) -> Result<Bytes> {
+    Ok(match decode_state_value(storage_version, value)? {
+        Cow::Borrowed(value) => Bytes::copy_from_slice(value),
+        Cow::Owned(value) => Bytes::from(value),
+    })
+}
+
+fn untag_state_value(value: &[u8]) -> Result<&[u8]> {
+    match value.split_first() {
+        Some((&tag, user_value)) if tag == u8::from(StorageCodecKind::Custom) => Ok(user_value),
+        Some((&tag, _)) => Err(StorageError::Conversion(anyhow::anyhow!(
+            "unexpected state value tag {tag:#x}"
+        ))),
+        None => Err(StorageError::Conversion(anyhow::anyhow!(
+            "state value is missing its tag"
+        ))),
+    }
+}
+
 fn put_user_state<S: StorageAccess>(
     storage: &mut S,
     storage_version: StorageVersion,
@@ -144,6 +201,7 @@ fn put_user_state<S: StorageAccess>(
     state_key: &Bytes,
     state_value: impl AsRef<[u8]>,
 ) -> Result<()> {
+    let state_value = encode_state_value(storage_version, state_value.as_ref())?;
     if use_scoped_state(storage_version, service_id) {
         //todo(tillrohrmann) remove once ServiceId carries the right types
         let service_name = ServiceName::new(service_id.service_name.as_ref());
@@ -262,10 +320,16 @@ fn get_user_state<S: StorageAccess>(
             .into_complete()
             .expect("key to be complete");
 
-        storage.get_kv_raw(key, move |_k, v| Ok(v.map(Bytes::copy_from_slice)))
+        storage.get_kv_raw(key, move |_k, v| {
+            v.map(|v| decode_state_value_to_bytes(storage_version, v))
+                .transpose()
+        })
     } else {
         let key = write_state_entry_key(service_id, state_key);
-        storage.get_kv_raw(key, move |_k, v| Ok(v.map(Bytes::copy_from_slice)))
+        storage.get_kv_raw(key, move |_k, v| {
+            v.map(|v| decode_state_value_to_bytes(storage_version, v))
+                .transpose()
+        })
     }
 }
 
@@ -289,7 +353,7 @@ fn get_all_user_states_for_service<'a, S: StorageAccess>(
             .service_key(&service_key);
 
         let iter = storage.iterator_from(TableScan::Prefix(key))?;
-        Ok(StateEntryIter::new(iter))
+        Ok(StateEntryIter::new(iter, storage_version))
     } else {
         let key = StateKey::builder()
             .partition_key(service_id.partition_key())
@@ -297,7 +361,7 @@ fn get_all_user_states_for_service<'a, S: StorageAccess>(
             .service_key(service_id.key.clone());
 
         let iter = storage.iterator_from(TableScan::Prefix(key))?;
-        Ok(StateEntryIter::new(iter))
+        Ok(StateEntryIter::new(iter, storage_version))
     }
 }
 
@@ -351,10 +415,11 @@ impl ScanStateTable for PartitionStore {
         // (needed because iterator_for_each requires 'static closures).
         // No contention: scans are awaited sequentially.
         let f = Arc::new(parking_lot::Mutex::new(f));
+        let storage_version = self.storage_version();
 
         // Only scan the legacy unscoped table while we may still hold data there.
         // After migration the range was deleted, so the scoped scan covers everything.
-        let unscoped = if self.storage_version().is_scope_migrated() {
+        let unscoped = if storage_version.is_scope_migrated() {
             None
         } else {
             let f_unscoped = Arc::clone(&f);
@@ -368,7 +433,8 @@ impl ScanStateTable for PartitionStore {
                         let (partition_key, service_name, service_key, state_key) = row_key.split();
                         let service_id =
                             ServiceId::from_parts(partition_key, service_name, service_key);
-                        f_unscoped.lock()((service_id, state_key, value)).map_break(Ok)
+                        let value = break_on_err(decode_state_value(storage_version, value))?;
+                        f_unscoped.lock()((service_id, state_key, &value)).map_break(Ok)
                     },
                 )
                 .map_err(|_| StorageError::OperationalError)?,
@@ -390,7 +456,8 @@ impl ScanStateTable for PartitionStore {
                         ByteString::from(service_name.as_str()),
                         ByteString::from(service_key.as_str()),
                     );
-                    f_scoped.lock()((service_id, state_key, value)).map_break(Ok)
+                    let value = break_on_err(decode_state_value(storage_version, value))?;
+                    f_scoped.lock()((service_id, state_key, &value)).map_break(Ok)
                 },
             )
             .map_err(|_| StorageError::OperationalError)?;
@@ -521,6 +588,7 @@ impl<DB: DBAccess + Send> BudgetedStateStream<'_, DB> {
         budget: &mut LocalMemoryPool,
         lease: &mut LocalMemoryLease,
     ) -> TryProduce {
+        let storage_version = iter.storage_version;
         let (k, v) = match iter.peek_item() {
             Some(Ok(item)) => item,
             Some(Err(e)) => return TryProduce::Ready(Err(e.into())),
@@ -532,7 +600,7 @@ impl<DB: DBAccess + Send> BudgetedStateStream<'_, DB> {
         // Fast path 1: existing lease already covers the entry.
         if raw_size <= lease.size() {
             // Decode copies data out of the iterator, releasing the borrow.
-            let result = decode_user_state_key_value(k, v);
+            let result = decode_user_state_key_value(storage_version, k, v);
             iter.advance();
             return TryProduce::Ready(
                 result
@@ -545,7 +613,7 @@ impl<DB: DBAccess + Send> BudgetedStateStream<'_, DB> {
         let deficit = raw_size - lease.size();
         if let Some(extra) = budget.try_reserve(deficit) {
             lease.merge(extra);
-            let result = decode_user_state_key_value(k, v);
+            let result = decode_user_state_key_value(storage_version, k, v);
             iter.advance();
             return TryProduce::Ready(
                 result
@@ -647,9 +715,13 @@ fn budgeted_state_stream<'a, DB: DBAccess + Send>(
     }
 }
 
-fn decode_user_state_key_value(k: &[u8], v: &[u8]) -> Result<(Bytes, Bytes)> {
+fn decode_user_state_key_value(
+    storage_version: StorageVersion,
+    k: &[u8],
+    v: &[u8],
+) -> Result<(Bytes, Bytes)> {
     let user_key = user_state_key_from_slice(k)?;
-    let user_value = Bytes::copy_from_slice(v);
+    let user_value = decode_state_value_to_bytes(storage_version, v)?;
     Ok((user_key, user_value))
 }
 
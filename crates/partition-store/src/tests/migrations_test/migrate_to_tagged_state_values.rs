@@ -0,0 +1,129 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+
+use bytes::{Bytes, BytesMut};
+use tokio_util::sync::CancellationToken;
+
+use restate_rocksdb::RocksDbManager;
+use restate_storage_api::Transaction;
+use restate_storage_api::fsm_table::WriteFsmTable;
+use restate_storage_api::state_table::WriteStateTable;
+use restate_types::config::{Configuration, set_current_config};
+use restate_types::identifiers::{PartitionId, PartitionKey, WithPartitionKey};
+use restate_types::logs::Lsn;
+use restate_types::partitions::{Partition, StorageVersion};
+use restate_types::sharding::KeyRange;
+use restate_types::storage::StorageCodecKind;
+
+use crate::PartitionStoreManager;
+use crate::fsm_table::get_tagged_state_values_cursor;
+use crate::migrations::MigrationContext;
+use crate::migrations::tests::distinct_service_ids;
+use crate::scan::{PhysicalScan, TableScan};
+use crate::state_table::ScopedStateKey;
+
+use super::migrate_to_tagged_state_values;
+
+#[restate_core::test]
+async fn migrate_to_tagged_state_values_tags_every_value_once() {
+    let mut config = Configuration::default();
+    config.common.experimental.set_migrate_scoped_tables(true);
+    set_current_config(config);
+    RocksDbManager::init();
+    let manager = PartitionStoreManager::create(true)
+        .await
+        .expect("DB storage creation succeeds");
+    let mut rocksdb = manager
+        .open(
+            &Partition::new(PartitionId::MIN, KeyRange::new(0, PartitionKey::MAX - 1)),
+            None,
+        )
+        .await
+        .expect("DB storage creation succeeds");
+    // Empty partition, initialized at ScopedStateAndPromise
+    rocksdb
+        .verify_and_run_migrations(CancellationToken::new(), &Configuration::pinned())
+        .await
+        .expect("verify fresh");
+    assert_eq!(
+        rocksdb.storage_version(),
+        StorageVersion::ScopedStateAndPromise
+    );
+
+    let service_ids = distinct_service_ids(3);
+    let values = [
+        Bytes::from_static(b"v1"),
+        Bytes::new(),
+        // Plaintext which looks like a sealed envelope
+        Bytes::from_static(&[0xE5, b'N', b'C', 1, 0, 0, 0, 1]),
+    ];
+    let mut txn = rocksdb.transaction();
+    for service_id in &service_ids {
+        for (idx, value) in values.iter().enumerate() {
+            txn.put_user_state(service_id, &Bytes::from(format!("k{idx}")), value)
+                .expect("state write should succeed");
+        }
+    }
+    txn.put_applied_lsn(Lsn::from(1)).expect("lsn write");
+    txn.commit().await.expect("commit should succeed");
+
+    let config = Configuration::default();
+    let mut ctx = MigrationContext::new(
+        &config,
+        rocksdb.partition_db(),
+        rocksdb.partition_key_range(),
+        CancellationToken::new(),
+    );
+    migrate_to_tagged_state_values(&mut ctx).expect("migration should succeed");
+    let last_partition_key = service_ids
+        .iter()
+        .map(|service_id| service_id.partition_key())
+        .max();
+    assert_eq!(
+        get_tagged_state_values_cursor(rocksdb.partition_db()).expect("cursor read"),
+        last_partition_key
+    );
+
+    // Resuming after the cursor doesn't tag the values again
+    migrate_to_tagged_state_values(&mut ctx).expect("resumed migration should succeed");
+
+    let mut arena = BytesMut::new();
+    let mut iter = rocksdb
+        .partition_db()
+        .scan(
+            PhysicalScan::from(
+                TableScan::ScanPartitionKeyRange::<ScopedStateKey>(rocksdb.partition_key_range()),
+                &mut arena,
+            ),
+            rocksdb::ReadOptions::default(),
+        )
+        .expect("scan should start");
+    iter.seek_to_first();
+    let mut observed: HashMap<Bytes, usize> = HashMap::new();
+    while iter.valid() {
+        let (_, value) = iter.item().expect("iterator should be valid");
+        let (&tag, user_value) = value.split_first().expect("value is tagged");
+        assert_eq!(tag, u8::from(StorageCodecKind::Custom));
+        *observed
+            .entry(Bytes::copy_from_slice(user_value))
+            .or_default() += 1;
+        iter.next();
+    }
+    iter.status().expect("scan should not error");
+
+    assert_eq!(observed.len(), values.len());
+    for value in &values {
+        assert_eq!(observed.get(value), Some(&service_ids.len()));
+    }
+
+    RocksDbManager::get().shutdown().await;
+}
@@ -324,3 +324,80 @@ async fn flag_on_writes_post_migration_land_in_scoped() {
 
     RocksDbManager::get().shutdown().await;
 }
+
+#[restate_core::test]
+async fn encryption_migrates_to_tagged_state_values() {
+    with_migrate_scoped_tables(true);
+    RocksDbManager::init();
+    let manager = PartitionStoreManager::create(true)
+        .await
+        .expect("manager create");
+    let mut store = manager
+        .open(
+            &Partition::new(PartitionId::MIN, KeyRange::new(0, PartitionKey::MAX - 1)),
+            None,
+        )
+        .await
+        .expect("open");
+
+    // Seed state at ScopedStateAndPromise, without tags
+    let cancel = CancellationToken::new();
+    store
+        .verify_and_run_migrations(cancel, &Configuration::pinned())
+        .await
+        .expect("verify fresh");
+    let service_id = ServiceId::new(None, "svc", "k");
+    // Plaintext which looks like a sealed envelope must stay readable
+    let looks_sealed = Bytes::from_static(&[0xE5, b'N', b'C', 1, 0, 0, 0, 1]);
+    {
+        let mut txn = store.transaction();
+        txn.put_user_state(&service_id, &Bytes::from_static(b"key"), &looks_sealed)
+            .expect("write state");
+        txn.put_applied_lsn(Lsn::from(1)).expect("lsn write");
+        txn.commit().await.expect("commit");
+    }
+
+    // Configuring encryption at rest tags the state values. The key file is only read by the
+    // node, the partition store only checks that encryption is configured.
+    let mut config = Configuration::default();
+    config.common.experimental.set_migrate_scoped_tables(true);
+    config.encryption.key_file = Some("keys.toml".into());
+    set_current_config(config);
+    let cancel = CancellationToken::new();
+    store
+        .verify_and_run_migrations(cancel, &Configuration::pinned())
+        .await
+        .expect("verify with encryption");
+
+    assert_eq!(store.storage_version(), StorageVersion::TaggedStateValues);
+    assert_eq!(
+        crate::fsm_table::get_tagged_state_values_cursor(store.partition_db())
+            .expect("cursor read"),
+        None
+    );
+    assert_eq!(
+        store
+            .get_user_state(&service_id, &Bytes::from_static(b"key"))
+            .await
+            .expect("get state"),
+        Some(looks_sealed.clone())
+    );
+
+    // Values written after the migration read back as well
+    {
+        let mut txn = store.transaction();
+        txn.put_user_state(&service_id, &Bytes::from_static(b"other"), b"value")
+            .expect("write state");
+        txn.commit().await.expect("commit");
+    }
+    assert_eq!(
+        store
+            .get_user_state(&service_id, &Bytes::from_static(b"other"))
+            .await
+            .expect("get state"),
+        Some(Bytes::from_static(b"value"))
+    );
+    assert_eq!(count_scoped_state(&store), 2);
+
+    RocksDbManager::get().shutdown().await;
+}
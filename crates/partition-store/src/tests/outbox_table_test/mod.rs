@@ -13,6 +13,12 @@ use super::mock_random_service_invocation;
 use crate::PartitionStore;
 use restate_storage_api::Transaction;
 use restate_storage_api::outbox_table::{OutboxMessage, ReadOutboxTable, WriteOutboxTable};
+use restate_types::errors::InvocationError;
+use restate_types::identifiers::InvocationId;
+use restate_types::invocation::InvocationTarget;
+use restate_types::invocation::events::{InvocationEvent, InvocationEventKind};
+use restate_types::journal_events::{Event, PausedEvent};
+use restate_types::time::MillisSinceEpoch;
 
 fn mock_outbox_message() -> OutboxMessage {
     OutboxMessage::ServiceInvocation(mock_random_service_invocation())
@@ -61,6 +67,34 @@ pub(crate) async fn verify_outbox_is_empty_after_truncation<T: ReadOutboxTable>(
     assert_eq!(result, None);
 }
 
+pub(crate) async fn verify_invocation_events_roundtrip<T: ReadOutboxTable + WriteOutboxTable>(
+    txn: &mut T,
+) {
+    let kinds = [
+        InvocationEventKind::Completed,
+        InvocationEventKind::Failed(InvocationError::new(500u16, "boom")),
+        InvocationEventKind::JournalEvent(Event::Paused(PausedEvent { last_failure: None }).into()),
+    ];
+
+    for (seq_no, kind) in (10..).zip(kinds) {
+        let message = OutboxMessage::InvocationEvent(Box::new(InvocationEvent {
+            invocation_id: InvocationId::mock_random(),
+            invocation_target: InvocationTarget::mock_service(),
+            timestamp: MillisSinceEpoch::new(1_000),
+            kind,
+        }));
+        txn.put_outbox_message(seq_no, &message).unwrap();
+
+        let stored = txn
+            .get_outbox_message(seq_no)
+            .await
+            .expect("should not fail");
+        assert_eq!(stored, Some(message));
+
+        txn.truncate_outbox(seq_no..=seq_no).unwrap();
+    }
+}
+
 pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
     let mut txn = rocksdb.transaction();
     verify_outbox_head_seq_number(&mut txn, None).await;
@@ -80,6 +114,12 @@ pub(crate) async fn run_tests(mut rocksdb: PartitionStore) {
     txn.commit().await.expect("should not fail");
     drop(txn);
 
+    let mut txn = rocksdb.transaction();
+    verify_outbox_is_empty_after_truncation(&mut txn).await;
+    verify_invocation_events_roundtrip(&mut txn).await;
+    txn.commit().await.expect("should not fail");
+    drop(txn);
+
     let mut txn = rocksdb.transaction();
     verify_outbox_is_empty_after_truncation(&mut txn).await;
 }
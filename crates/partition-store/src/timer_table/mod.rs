@@ -8,23 +8,26 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::ops::{ControlFlow, RangeBounds};
+
 use futures::Stream;
 use futures_util::stream;
 
-use restate_rocksdb::RocksDbReadPerfGuard;
-use restate_storage_api::Result;
+use restate_rocksdb::{Priority, RocksDbReadPerfGuard};
 use restate_storage_api::protobuf_types::PartitionStoreProtobufValue;
 use restate_storage_api::timer_table::{
-    ReadTimerTable, Timer, TimerKey, TimerKeyKind, WriteTimerTable,
+    ReadTimerTable, ScanTimerTable, Timer, TimerKey, TimerKeyKind, WriteTimerTable,
 };
-use restate_types::identifiers::{InvocationUuid, PartitionId};
+use restate_storage_api::{Result, StorageError};
+use restate_types::identifiers::{InvocationUuid, PartitionId, WithPartitionKey};
+use restate_types::sharding::KeyRange;
 
 use crate::TableKind::Timers;
 use crate::TableScanIterationDecision::Emit;
 use crate::keys::{DecodeTableKey, KeyKind, define_table_key};
 use crate::{
     PaddedPartitionId, PartitionStore, PartitionStoreTransaction, StorageAccess, TableScan,
-    TableScanIterationDecision,
+    TableScanIterationDecision, break_on_err,
 };
 
 define_table_key!(
@@ -185,6 +188,29 @@ impl ReadTimerTable for PartitionStore {
     }
 }
 
+impl ScanTimerTable for PartitionStore {
+    fn for_each_timer<F: FnMut((TimerKey, Timer)) -> ControlFlow<()> + Send + Sync + 'static>(
+        &self,
+        range: KeyRange,
+        mut f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send> {
+        // timers are keyed by partition id, the key range can only be applied on the values
+        self.iterator_for_each(
+            "df-timers",
+            Priority::Low,
+            TableScan::Prefix(TimersKey::builder().partition_id(self.partition_id().into())),
+            move |(key, value)| {
+                let (timer_key, timer) = break_on_err(decode_seq_timer_key_value(key, value))?;
+                if !range.contains(&timer.partition_key()) {
+                    return ControlFlow::Continue(());
+                }
+                f((timer_key, timer)).map_break(Ok)
+            },
+        )
+        .map_err(|_| StorageError::OperationalError)
+    }
+}
+
 impl ReadTimerTable for PartitionStoreTransaction<'_> {
     fn next_timers_greater_than(
         &mut self,
@@ -88,6 +88,9 @@ pub enum StorageCodecKind {
     /// it is up to your implementation to decide how (or if) to use them, and how the final
     /// byte representation is constructed.
     Custom = 7,
+    /// A value sealed by the encryption at rest. The tag is followed by the sealed envelope of
+    /// another value, which carries its own codec once opened.
+    Encrypted = 8,
 }
 
 #[cfg(feature = "bilrost")]
@@ -150,6 +153,7 @@ impl TryFrom<u8> for StorageCodecKind {
             5 => Ok(Self::Json),
             6 => Ok(Self::Bilrost),
             7 => Ok(Self::Custom),
+            8 => Ok(Self::Encrypted),
             value => Err(StorageDecodeError::ReadingCodec(format_restring!(
                 "unknown discriminant '{value}'"
             ))),
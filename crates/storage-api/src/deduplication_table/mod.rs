@@ -147,6 +147,19 @@ pub trait ReadDeduplicationTable {
     ) -> impl Future<Output = Result<Option<DedupSequenceNumber>>> + Send;
 }
 
+pub trait ScanDeduplicationTable {
+    /// Visits the latest sequence number of every producer known to the partition.
+    fn for_each_dedup_sequence_number<
+        F: FnMut((ProducerId, DedupSequenceNumber)) -> std::ops::ControlFlow<()>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        &self,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+}
+
 pub trait WriteDeduplicationTable {
     fn put_dedup_seq_number(
         &mut self,
@@ -11,6 +11,7 @@
 use std::ops::RangeInclusive;
 
 use restate_types::identifiers::{PartitionKey, WithPartitionKey};
+use restate_types::invocation::events::InvocationEvent;
 use restate_types::invocation::{
     AttachInvocationRequest, InvocationResponse, InvocationTermination, NotifySignalRequest,
     ServiceInvocation,
@@ -36,6 +37,9 @@ pub enum OutboxMessage {
 
     /// Notify signal request
     NotifySignal(NotifySignalRequest),
+
+    /// Invocation lifecycle event to deliver to the configured event sink
+    InvocationEvent(Box<InvocationEvent>),
 }
 
 impl PartitionStoreProtobufValue for OutboxMessage {
@@ -50,6 +54,7 @@ impl WithPartitionKey for OutboxMessage {
             OutboxMessage::InvocationTermination(it) => it.invocation_id.partition_key(),
             OutboxMessage::AttachInvocation(ai) => ai.partition_key(),
             OutboxMessage::NotifySignal(sig) => sig.partition_key(),
+            OutboxMessage::InvocationEvent(event) => event.invocation_id.partition_key(),
         }
     }
 }
@@ -68,6 +73,17 @@ pub trait ReadOutboxTable {
     ) -> impl Future<Output = Result<Option<OutboxMessage>>> + Send;
 }
 
+pub trait ScanOutboxTable {
+    /// Visits the messages of the partition's outbox that haven't been shuffled yet, ordered by
+    /// their sequence number.
+    fn for_each_outbox_message<
+        F: FnMut((u64, OutboxMessage)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+}
+
 pub trait WriteOutboxTable {
     fn put_outbox_message(
         &mut self,
@@ -99,8 +99,10 @@ pub mod v1 {
         use restate_types::identifiers::{
             PartitionProcessorRpcRequestId, WithInvocationId, WithPartitionKey,
         };
+        use restate_types::invocation::events::{InvocationEvent, InvocationEventKind};
         use restate_types::invocation::{InvocationTermination, TerminationFlavor};
         use restate_types::journal::enriched::AwakeableEnrichmentResult;
+        use restate_types::journal_events::raw::RawEvent;
         use restate_types::journal_v2::raw::RawNotificationResultVariant;
         use restate_types::journal_v2::{
             CombinatorType, EntryMetadata, NotificationId, SignalId, UnresolvedFuture,
@@ -109,7 +111,7 @@ pub mod v1 {
         use restate_types::service_protocol::ServiceProtocolVersion;
         use restate_types::time::MillisSinceEpoch;
         use restate_types::vqueues::VQueueId;
-        use restate_types::{GenerationalNodeId, LimitKey, Scope, journal_v2};
+        use restate_types::{GenerationalNodeId, LimitKey, Scope, journal_events, journal_v2};
         use restate_util_string::RestateString;
 
         use super::dedup_sequence_number::Variant;
@@ -3541,6 +3543,83 @@ pub mod v1 {
             }
         }
 
+        impl From<InvocationEvent> for outbox_message::InvocationEvent {
+            fn from(value: InvocationEvent) -> Self {
+                let InvocationEvent {
+                    invocation_id,
+                    invocation_target,
+                    timestamp,
+                    kind,
+                } = value;
+
+                Self {
+                    invocation_id: Some(InvocationId::from(invocation_id)),
+                    invocation_target: Some(InvocationTarget::from(invocation_target)),
+                    timestamp: timestamp.as_u64(),
+                    kind: Some(match kind {
+                        InvocationEventKind::Completed => {
+                            outbox_message::invocation_event::Kind::Completed(())
+                        }
+                        InvocationEventKind::Failed(err) => {
+                            outbox_message::invocation_event::Kind::Failed(err.into())
+                        }
+                        InvocationEventKind::JournalEvent(event) => {
+                            let (event_type, content) = event.into_inner();
+                            outbox_message::invocation_event::Kind::JournalEvent(
+                                outbox_message::invocation_event::JournalEvent {
+                                    event_type: event_type as u32,
+                                    content,
+                                },
+                            )
+                        }
+                    }),
+                }
+            }
+        }
+
+        impl TryFrom<outbox_message::InvocationEvent> for InvocationEvent {
+            type Error = ConversionError;
+
+            fn try_from(value: outbox_message::InvocationEvent) -> Result<Self, Self::Error> {
+                let outbox_message::InvocationEvent {
+                    invocation_id,
+                    invocation_target,
+                    timestamp,
+                    kind,
+                } = value;
+
+                Ok(Self {
+                    invocation_id: restate_types::identifiers::InvocationId::try_from(
+                        expect_or_fail!(invocation_id)?,
+                    )?,
+                    invocation_target: restate_types::invocation::InvocationTarget::try_from(
+                        expect_or_fail!(invocation_target)?,
+                    )?,
+                    timestamp: MillisSinceEpoch::new(timestamp),
+                    kind: match expect_or_fail!(kind)? {
+                        outbox_message::invocation_event::Kind::Completed(_) => {
+                            InvocationEventKind::Completed
+                        }
+                        outbox_message::invocation_event::Kind::Failed(failure) => {
+                            InvocationEventKind::Failed(failure.try_into()?)
+                        }
+                        outbox_message::invocation_event::Kind::JournalEvent(
+                            outbox_message::invocation_event::JournalEvent {
+                                event_type,
+                                content,
+                            },
+                        ) => InvocationEventKind::JournalEvent(RawEvent::new(
+                            u8::try_from(event_type)
+                                .ok()
+                                .and_then(journal_events::EventType::from_repr)
+                                .unwrap_or(journal_events::EventType::Unknown),
+                            content,
+                        )),
+                    },
+                })
+            }
+        }
+
         impl TryFrom<OutboxMessage> for crate::outbox_table::OutboxMessage {
             type Error = ConversionError;
 
@@ -3597,6 +3676,11 @@ pub mod v1 {
                     outbox_message::OutboxMessage::NotifySignal(notify_signal) => {
                         crate::outbox_table::OutboxMessage::NotifySignal(notify_signal.try_into()?)
                     }
+                    outbox_message::OutboxMessage::InvocationEvent(invocation_event) => {
+                        crate::outbox_table::OutboxMessage::InvocationEvent(Box::new(
+                            invocation_event.try_into()?,
+                        ))
+                    }
                 };
 
                 Ok(result)
@@ -3660,6 +3744,9 @@ pub mod v1 {
                     crate::outbox_table::OutboxMessage::NotifySignal(notify_signal) => {
                         outbox_message::OutboxMessage::NotifySignal(notify_signal.into())
                     }
+                    crate::outbox_table::OutboxMessage::InvocationEvent(invocation_event) => {
+                        outbox_message::OutboxMessage::InvocationEvent((*invocation_event).into())
+                    }
                 };
 
                 OutboxMessage {
@@ -3680,20 +3767,7 @@ pub mod v1 {
                         restate_types::invocation::ResponseResult::Success(success.value)
                     }
                     response_result::ResponseResult::ResponseFailure(failure) => {
-                        // we should be able to turn the incoming Bytes into a String without a copy
-                        let failure_message = Vec::<u8>::from(failure.failure_message);
-                        let failure_message = String::from_utf8(failure_message)
-                            .map_err(ConversionError::invalid_data)?;
-                        restate_types::invocation::ResponseResult::Failure(
-                            InvocationError::new(failure.failure_code, failure_message)
-                                .with_metadata_vec(
-                                    failure
-                                        .failure_metadata
-                                        .into_iter()
-                                        .map(|m| (m.key, m.value))
-                                        .collect(),
-                                ),
-                        )
+                        restate_types::invocation::ResponseResult::Failure(failure.try_into()?)
                     }
                 };
 
@@ -3701,6 +3775,26 @@ pub mod v1 {
             }
         }
 
+        impl TryFrom<response_result::ResponseFailure> for InvocationError {
+            type Error = ConversionError;
+
+            fn try_from(failure: response_result::ResponseFailure) -> Result<Self, Self::Error> {
+                // we should be able to turn the incoming Bytes into a String without a copy
+                let failure_message = Vec::<u8>::from(failure.failure_message);
+                let failure_message =
+                    String::from_utf8(failure_message).map_err(ConversionError::invalid_data)?;
+                Ok(
+                    InvocationError::new(failure.failure_code, failure_message).with_metadata_vec(
+                        failure
+                            .failure_metadata
+                            .into_iter()
+                            .map(|m| (m.key, m.value))
+                            .collect(),
+                    ),
+                )
+            }
+        }
+
         impl From<restate_types::invocation::ResponseResult> for ResponseResult {
             fn from(value: restate_types::invocation::ResponseResult) -> Self {
                 let response_result = match value {
@@ -3710,17 +3804,7 @@ pub mod v1 {
                         )
                     }
                     restate_types::invocation::ResponseResult::Failure(err) => {
-                        response_result::ResponseResult::ResponseFailure(
-                            response_result::ResponseFailure {
-                                failure_code: err.code().into(),
-                                failure_message: Bytes::copy_from_slice(err.message().as_ref()),
-                                failure_metadata: err
-                                    .metadata
-                                    .into_iter()
-                                    .map(|(key, value)| FailureMetadata { key, value })
-                                    .collect(),
-                            },
-                        )
+                        response_result::ResponseResult::ResponseFailure(err.into())
                     }
                 };
 
@@ -3730,6 +3814,20 @@ pub mod v1 {
             }
         }
 
+        impl From<InvocationError> for response_result::ResponseFailure {
+            fn from(err: InvocationError) -> Self {
+                response_result::ResponseFailure {
+                    failure_code: err.code().into(),
+                    failure_message: Bytes::copy_from_slice(err.message().as_ref()),
+                    failure_metadata: err
+                        .metadata
+                        .into_iter()
+                        .map(|(key, value)| FailureMetadata { key, value })
+                        .collect(),
+                }
+            }
+        }
+
         impl TryFrom<Timer> for crate::timer_table::Timer {
             type Error = ConversionError;
 
@@ -14,6 +14,7 @@ use futures::Stream;
 
 use restate_types::identifiers::{InvocationId, InvocationUuid, PartitionKey, WithPartitionKey};
 use restate_types::invocation::ServiceInvocation;
+use restate_types::sharding::KeyRange;
 use restate_types::time::MillisSinceEpoch;
 
 use crate::Result;
@@ -269,6 +270,18 @@ pub trait ReadTimerTable {
     ) -> Result<impl Stream<Item = Result<(TimerKey, Timer)>> + Send>;
 }
 
+pub trait ScanTimerTable {
+    /// Visits the timers of the partition whose target falls into `range`, ordered by their
+    /// wake up time.
+    fn for_each_timer<
+        F: FnMut((TimerKey, Timer)) -> std::ops::ControlFlow<()> + Send + Sync + 'static,
+    >(
+        &self,
+        range: KeyRange,
+        f: F,
+    ) -> Result<impl Future<Output = Result<()>> + Send>;
+}
+
 pub trait WriteTimerTable {
     fn put_timer(&mut self, timer_key: &TimerKey, timer: &Timer) -> Result<()>;
 
@@ -17,15 +17,21 @@ use codederror::CodedError;
 use tokio::sync::watch;
 use tracing::warn;
 
+use datafusion::arrow::datatypes::DataType;
 use datafusion::catalog::TableProvider;
+use datafusion::common::tree_node::TreeNode;
 use datafusion::error::DataFusionError;
 use datafusion::execution::SessionStateBuilder;
 use datafusion::execution::TaskContext;
 use datafusion::execution::context::SQLOptions;
 use datafusion::execution::runtime_env::RuntimeEnvBuilder;
+use datafusion::logical_expr::{Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder, WriteOp};
 use datafusion::physical_plan::{ExecutionPlan, SendableRecordBatchStream, execute_stream};
-use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion::prelude::{SessionConfig, SessionContext, col};
 use datafusion::sql::TableReference;
+use datafusion::sql::sqlparser::dialect::PostgreSqlDialect;
+use datafusion::sql::sqlparser::parser::Parser;
+use datafusion::sql::sqlparser::tokenizer::Token;
 
 use restate_core::{Metadata, TaskCenter};
 use restate_limiter::rule_book::RuleBookObserver;
@@ -42,10 +48,12 @@ use restate_types::partition_table::Partition;
 use restate_types::partitions::state::PartitionReplicaSetStates;
 use restate_types::schema::deployment::DeploymentResolver;
 use restate_types::schema::service::ServiceMetadataResolver;
+use restate_types::schema::subscriptions::SubscriptionResolver;
 use restate_worker_api::invoker::StatusHandle;
 use restate_worker_api::{SchedulerStatusEntry, UserLimitCounterEntry};
 
 use crate::empty_invoker_status_handle::EmptyInvokerStatusHandle;
+use crate::invocation_history::InvocationArchive;
 use crate::node_fan_out::NodeWarnings;
 use crate::remote_query_scanner_manager::RemoteScannerManager;
 
@@ -223,12 +231,20 @@ where
             SchedulerStatus = SchedulerStatusEntry,
             UserLimitCounter = UserLimitCounterEntry,
         >,
-    D: DeploymentResolver + ServiceMetadataResolver + Send + Sync + Debug + Clone + 'static,
+    D: DeploymentResolver
+        + ServiceMetadataResolver
+        + SubscriptionResolver
+        + Send
+        + Sync
+        + Debug
+        + Clone
+        + 'static,
 {
     async fn register(&self, ctx: &QueryContext) -> Result<(), BuildError> {
         // ----- non partitioned tables -----
         crate::deployment::register_self(ctx, self.schemas.clone())?;
         crate::service::register_self(ctx, self.schemas.clone())?;
+        crate::subscription::register_self(ctx, self.schemas.clone())?;
         crate::rules::register_self(
             ctx,
             self.metadata_store_client.clone(),
@@ -298,6 +314,30 @@ where
             self.partition_store_manager.clone(),
             &self.remote_scanner_manager,
         )?;
+        crate::timer::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::outbox::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::idempotency::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::dedup::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
         crate::promise::register_self(
             ctx,
             self.partition_selector.clone(),
@@ -326,6 +366,22 @@ where
 
         ctx.datafusion_context.sql(SYS_INVOCATION_VIEW).await?;
 
+        // A misconfigured archive must not make the other tables unavailable.
+        let archive_options = Configuration::pinned().worker.invocation_archive.clone();
+        let invocation_archive = match archive_options {
+            Some(options) => InvocationArchive::create(&options)
+                .await
+                .inspect_err(|err| {
+                    warn!(
+                        "Failed to access the invocation archive, archived invocations won't be \
+                         queryable: {err:#}"
+                    )
+                })
+                .ok(),
+            None => None,
+        };
+        crate::invocation_history::register_self(ctx, invocation_archive.as_ref()).await?;
+
         Ok(())
     }
 }
@@ -386,6 +442,18 @@ impl RegisterTable for ClusterTables {
             self.remote_scanner_manager.clone(),
             None, // local scanner is registered separately by the node
         )?;
+        crate::loglet_scrub_finding::register_self(
+            ctx,
+            metadata.clone(),
+            self.remote_scanner_manager.clone(),
+            None, // local scanner is registered separately by the node
+        )?;
+        crate::kafka_consumer::register_self(
+            ctx,
+            metadata.clone(),
+            self.remote_scanner_manager.clone(),
+            None, // local scanner is registered separately if this node is also a worker
+        )?;
 
         if !Configuration::pinned().common.disable_config_sql_table {
             crate::config::register_self(
@@ -487,6 +555,30 @@ where
             self.partition_store_manager.clone(),
             &self.remote_scanner_manager,
         )?;
+        crate::timer::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::outbox::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::idempotency::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
+        crate::dedup::register_self(
+            ctx,
+            self.partition_selector.clone(),
+            self.partition_store_manager.clone(),
+            &self.remote_scanner_manager,
+        )?;
         crate::promise::register_self(
             ctx,
             self.partition_selector.clone(),
@@ -553,7 +645,14 @@ impl QueryContext {
             >,
         >,
         schemas: Live<
-            impl DeploymentResolver + ServiceMetadataResolver + Send + Sync + Debug + Clone + 'static,
+            impl DeploymentResolver
+            + ServiceMetadataResolver
+            + SubscriptionResolver
+            + Send
+            + Sync
+            + Debug
+            + Clone
+            + 'static,
         >,
         remote_scanner_manager: RemoteScannerManager,
         metadata_store_client: MetadataStoreClient,
@@ -655,13 +754,119 @@ impl QueryContext {
     }
 
     pub async fn execute(&self, sql: &str) -> Result<QueryResult, QueryError> {
+        let plan = self.plan(sql).await?;
+        self.execute_plan(plan).await
+    }
+
+    /// Like [`Self::execute`], but additionally accepts `DELETE` and `UPDATE` statements on the
+    /// `state` table. These are not applied: the rows they affect are returned instead, so that
+    /// the caller can turn them into state mutations.
+    pub async fn execute_statement(&self, sql: &str) -> Result<StatementResult, QueryError> {
+        let plan = self.plan(sql).await?;
+        let LogicalPlan::Dml(dml) = plan else {
+            return Ok(StatementResult::Query(self.execute_plan(plan).await?));
+        };
+        if dml.table_name.table() != "state" {
+            // rejected by the sql options
+            return Ok(StatementResult::Query(
+                self.execute_plan(LogicalPlan::Dml(dml)).await?,
+            ));
+        }
+
+        let op = match dml.op {
+            WriteOp::Delete => StateDmlOp::Delete,
+            WriteOp::Update => {
+                verify_state_update(&dml.input)?;
+                StateDmlOp::Update
+            }
+            op => {
+                return Err(DataFusionError::Plan(format!(
+                    "{op} is not supported on the state table, only DELETE and UPDATE are"
+                ))
+                .into());
+            }
+        };
+
+        // For updates, the input of the statement already computes the new values
+        let rows = LogicalPlanBuilder::from(Arc::unwrap_or_clone(dml.input))
+            .project(
+                ["scope", "service_name", "service_key", "key", "value"]
+                    .into_iter()
+                    .map(col),
+            )?
+            .build()?;
+
+        Ok(StatementResult::StateDml(StateDml {
+            op,
+            rows: self.execute_plan(rows).await?.stream,
+        }))
+    }
+
+    /// Plans a scan of `table` filtered by `predicate`, which must be a single boolean SQL
+    /// expression over the columns of `table`. The predicate is parsed on its own rather than
+    /// spliced into a query, so it can't change the rest of the plan.
+    pub async fn filter_table(
+        &self,
+        table: &str,
+        predicate: &str,
+    ) -> Result<LogicalPlanBuilder, QueryError> {
+        if let Some(limiter) = self.rate_limiter.as_ref() {
+            limiter.try_consume_one()?;
+        }
+
+        let invalid_predicate =
+            |err: &dyn std::fmt::Display| DataFusionError::Plan(format!("invalid filter: {err}"));
+        let dialect = PostgreSqlDialect {};
+        let mut parser = Parser::new(&dialect)
+            .try_with_sql(predicate)
+            .map_err(|err| invalid_predicate(&err))?;
+        parser
+            .parse_expr()
+            .and_then(|_| parser.expect_token(&Token::EOF))
+            .map_err(|err| invalid_predicate(&err))?;
+
+        let scan = self.datafusion_context.table(table).await?;
+        let schema = scan.schema().clone();
+        let expr = self
+            .datafusion_context
+            .state()
+            .create_logical_expr(predicate, &schema)?;
+        if expr.exists(|expr| {
+            Ok(matches!(
+                expr,
+                Expr::ScalarSubquery(_) | Expr::InSubquery(_) | Expr::Exists(_)
+            ))
+        })? {
+            return Err(invalid_predicate(&"subqueries are not supported").into());
+        }
+        let data_type = expr.get_type(&schema)?;
+        if data_type != DataType::Boolean {
+            return Err(invalid_predicate(&format!(
+                "expected a boolean expression, got {data_type}"
+            ))
+            .into());
+        }
+
+        Ok(LogicalPlanBuilder::from(scan.into_unoptimized_plan()).filter(expr)?)
+    }
+
+    /// Executes a plan built by the caller, e.g. on top of [`Self::filter_table`]. Unlike
+    /// [`Self::execute`], this is not subject to the rate limit.
+    pub async fn execute_logical_plan(&self, plan: LogicalPlan) -> Result<QueryResult, QueryError> {
+        self.execute_plan(plan).await
+    }
+
+    async fn plan(&self, sql: &str) -> Result<LogicalPlan, QueryError> {
         if let Some(limiter) = self.rate_limiter.as_ref() {
             limiter.try_consume_one()?;
         }
 
         let state = self.datafusion_context.state();
         let statement = state.sql_to_statement(sql, &datafusion::config::Dialect::PostgreSQL)?;
-        let plan = state.statement_to_plan(statement).await?;
+        Ok(state.statement_to_plan(statement).await?)
+    }
+
+    async fn execute_plan(&self, plan: LogicalPlan) -> Result<QueryResult, QueryError> {
         self.sql_options.verify_plan(&plan)?;
         let df = self.datafusion_context.execute_logical_plan(plan).await?;
 
@@ -697,6 +902,49 @@ pub struct QueryResult {
     pub node_warnings: Vec<NodeWarnings>,
 }
 
+/// Result of [`QueryContext::execute_statement`].
+pub enum StatementResult {
+    Query(QueryResult),
+    StateDml(StateDml),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateDmlOp {
+    Delete,
+    Update,
+}
+
+/// A `DELETE` or `UPDATE` statement on the `state` table.
+pub struct StateDml {
+    pub op: StateDmlOp,
+    /// The affected rows, with the columns `scope`, `service_name`, `service_key`, `key` and
+    /// `value`. For updates, `value` holds the new value.
+    pub rows: SendableRecordBatchStream,
+}
+
+/// Only the `value` column can be assigned by `UPDATE` statements on the `state` table, the
+/// other columns are either part of the key or derived from the value.
+fn verify_state_update(input: &LogicalPlan) -> Result<(), DataFusionError> {
+    let LogicalPlan::Projection(projection) = input else {
+        return Err(DataFusionError::Plan(
+            "unexpected plan for the UPDATE statement".to_owned(),
+        ));
+    };
+    for (expr, field) in projection.expr.iter().zip(projection.schema.fields()) {
+        let is_unchanged = matches!(
+            expr.clone().unalias(),
+            Expr::Column(column) if column.name == *field.name()
+        );
+        if field.name() != "value" && !is_unchanged {
+            return Err(DataFusionError::Plan(format!(
+                "only the 'value' column of the state table can be updated, not '{}'",
+                field.name()
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Walks the physical plan tree and collects [`NodeWarnings`] handles from
 /// any [`NodeFanOutExecutionPlan`] nodes found.
 fn collect_node_warnings(plan: &Arc<dyn ExecutionPlan>) -> Vec<NodeWarnings> {
@@ -0,0 +1,59 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_storage_api::deduplication_table::{
+    DedupSequenceNumber, EpochSequenceNumber, ProducerId,
+};
+use restate_types::identifiers::PartitionId;
+
+use super::schema::SysDedupBuilder;
+
+#[inline]
+pub(crate) fn append_dedup_row(
+    builder: &mut SysDedupBuilder,
+    partition_id: PartitionId,
+    producer_id: ProducerId,
+    dedup_sequence_number: DedupSequenceNumber,
+) {
+    let mut row = builder.row();
+    row.partition_id(partition_id.into());
+
+    if producer_id.is_self_producer() {
+        row.producer_kind("self");
+    } else {
+        match producer_id {
+            ProducerId::Partition(partition_id) => {
+                row.producer_kind("partition");
+                row.fmt_producer_id(partition_id);
+            }
+            ProducerId::Producer(producer_id) => {
+                row.producer_kind("producer");
+                row.fmt_producer_id(u128::from(producer_id));
+            }
+            ProducerId::Other(producer_id) => {
+                row.producer_kind("other");
+                row.producer_id(producer_id);
+            }
+        }
+    }
+
+    match dedup_sequence_number {
+        DedupSequenceNumber::Sn(sequence_number) => {
+            row.sequence_number(sequence_number);
+        }
+        DedupSequenceNumber::Esn(EpochSequenceNumber {
+            leader_epoch,
+            sequence_number,
+        }) => {
+            row.sequence_number(sequence_number);
+            row.leader_epoch(leader_epoch.into());
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_dedup(
+    /// The partition which tracks the sequence number.
+    partition_id: DataType::UInt32,
+
+    /// The kind of producer. Either `partition` for messages shuffled from another partition,
+    /// `self` for messages proposed by the partition's own leader, `producer` for messages
+    /// produced by an ingress or `other` for any other named producer.
+    producer_kind: DataType::LargeUtf8,
+
+    /// The identifier of the producer. The partition id for producers of kind `partition`.
+    producer_id: DataType::LargeUtf8,
+
+    /// The last sequence number seen from the producer. Messages with a lower or equal sequence
+    /// number are discarded as duplicates.
+    sequence_number: DataType::UInt64,
+
+    /// The leader epoch of the producer when the sequence number was recorded. Only present for
+    /// producers which fence off messages of older leader epochs.
+    leader_epoch: DataType::UInt64,
+));
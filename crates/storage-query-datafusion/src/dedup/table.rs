@@ -0,0 +1,88 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use restate_partition_store::{PartitionStore, PartitionStoreManager};
+use restate_storage_api::StorageError;
+use restate_storage_api::deduplication_table::{
+    DedupSequenceNumber, ProducerId, ScanDeduplicationTable,
+};
+use restate_types::identifiers::PartitionId;
+use restate_types::sharding::KeyRange;
+
+use crate::context::{QueryContext, SelectPartitions};
+use crate::dedup::row::append_dedup_row;
+use crate::dedup::schema::SysDedupBuilder;
+use crate::filter::FirstMatchingPartitionKeyExtractor;
+use crate::partition_store_scanner::{LocalPartitionsScanner, ScanLocalPartition};
+use crate::remote_query_scanner_manager::RemoteScannerManager;
+use crate::table_providers::{PartitionedTableProvider, ScanPartition};
+
+const NAME: &str = "sys_dedup";
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    partition_selector: impl SelectPartitions,
+    partition_store_manager: Arc<PartitionStoreManager>,
+    remote_scanner_manager: &RemoteScannerManager,
+) -> datafusion::common::Result<()> {
+    let local_partition_scanner = Arc::new(LocalPartitionsScanner::new(
+        partition_store_manager,
+        DedupScanner,
+    )) as Arc<dyn ScanPartition>;
+
+    let table = PartitionedTableProvider::new(
+        partition_selector,
+        SysDedupBuilder::schema(),
+        Vec::new(),
+        remote_scanner_manager.create_distributed_scanner(NAME, local_partition_scanner),
+        FirstMatchingPartitionKeyExtractor::default(),
+    );
+    ctx.register_partitioned_table(NAME, Arc::new(table))
+}
+
+#[derive(Debug, Clone)]
+struct DedupScanner;
+
+impl ScanLocalPartition for DedupScanner {
+    type Builder = SysDedupBuilder;
+    type Item<'a> = (PartitionId, ProducerId, DedupSequenceNumber);
+    type ConversionError = std::convert::Infallible;
+    type Filter = KeyRange;
+
+    fn for_each_row<
+        F: for<'a> FnMut(
+                Self::Item<'a>,
+            ) -> std::ops::ControlFlow<Result<(), Self::ConversionError>>
+            + Send
+            + Sync
+            + 'static,
+    >(
+        partition_store: &PartitionStore,
+        // sequence numbers are tracked per partition id, hence all of them are scanned
+        _range: KeyRange,
+        mut f: F,
+    ) -> Result<impl Future<Output = restate_storage_api::Result<()>> + Send, StorageError> {
+        let partition_id = partition_store.partition_id();
+        partition_store.for_each_dedup_sequence_number(move |(producer_id, sequence_number)| {
+            f((partition_id, producer_id, sequence_number)).map_break(Result::unwrap)
+        })
+    }
+
+    fn append_row<'a>(
+        row_builder: &mut Self::Builder,
+        (partition_id, producer_id, sequence_number): Self::Item<'a>,
+    ) -> Result<(), Self::ConversionError> {
+        append_dedup_row(row_builder, partition_id, producer_id, sequence_number);
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{LargeStringArray, UInt64Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::Transaction;
+use restate_storage_api::deduplication_table::{
+    DedupSequenceNumber, EpochSequenceNumber, ProducerId, WriteDeduplicationTable,
+};
+use restate_types::identifiers::{LeaderEpoch, PartitionId};
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_dedup_sequence_numbers() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    tx.put_dedup_seq_number(
+        ProducerId::Partition(PartitionId::from(7)),
+        &DedupSequenceNumber::Sn(42),
+    )
+    .unwrap();
+    tx.put_dedup_seq_number(
+        ProducerId::self_producer().clone(),
+        &DedupSequenceNumber::Esn(EpochSequenceNumber {
+            leader_epoch: LeaderEpoch::from(3),
+            sequence_number: 10,
+        }),
+    )
+    .unwrap();
+    tx.commit().await.unwrap();
+    drop(tx);
+
+    let records = engine
+        .execute("SELECT * FROM sys_dedup ORDER BY producer_kind")
+        .await
+        .unwrap()
+        .stream
+        .collect::<Vec<datafusion::common::Result<RecordBatch>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "producer_kind" => LargeStringArray: eq("partition"),
+                    "producer_id" => LargeStringArray: eq("7"),
+                    "sequence_number" => UInt64Array: eq(42),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "producer_kind" => LargeStringArray: eq("self"),
+                    "sequence_number" => UInt64Array: eq(10),
+                    "leader_epoch" => UInt64Array: eq(3),
+                }
+            )
+        )
+    );
+}
@@ -39,4 +39,22 @@ pub(crate) fn append_deployment_row(
     );
     row.max_service_protocol_version(deployment.supported_protocol_versions.end().unsigned_abs());
     row.services(service_names.into_iter().map(|s| Some(s)));
+
+    match deployment.drain {
+        None => {
+            row.status("active");
+        }
+        Some(drain) => {
+            row.status(if drain.is_drained() {
+                "drained"
+            } else {
+                "draining"
+            });
+            row.draining_since(drain.started_at.as_u64() as i64);
+            row.auto_remove(drain.auto_remove);
+            if let Some(drained_at) = drain.drained_at {
+                row.drained_at(drained_at.as_u64() as i64);
+            }
+        }
+    }
 }
@@ -32,5 +32,19 @@ define_table!(sys_deployment(
     max_service_protocol_version: DataType::UInt32,
 
     /// List of service names registered by this deployment.
-    services: LargeUtf8List
+    services: LargeUtf8List,
+
+    /// The status of the deployment. Either `active`, `draining` or `drained`. A deployment is
+    /// `drained` once no in-flight invocation is pinned to it anymore.
+    status: DataType::LargeUtf8,
+
+    /// If the deployment is draining, timestamp indicating when the drain started.
+    draining_since: TimestampMillisecond,
+
+    /// If the deployment is draining, whether it is removed automatically once drained.
+    auto_remove: DataType::Boolean,
+
+    /// If the deployment is drained, timestamp indicating when no in-flight invocation was pinned
+    /// to it anymore.
+    drained_at: TimestampMillisecond
 ));
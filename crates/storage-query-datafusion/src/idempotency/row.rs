@@ -0,0 +1,51 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_storage_api::protobuf_types::v1::lazy::InvocationStatusV2Lazy;
+use restate_types::errors::ConversionError;
+use restate_types::identifiers::{InvocationId, WithPartitionKey};
+
+use super::schema::SysIdempotencyBuilder;
+
+#[inline]
+pub(crate) fn append_idempotency_row<'a>(
+    builder: &mut SysIdempotencyBuilder,
+    invocation_id: InvocationId,
+    invocation_status: &'a InvocationStatusV2Lazy<'a>,
+) -> Result<(), ConversionError> {
+    let Some(idempotency_key) = invocation_status.idempotency_key()? else {
+        // only invocations with an idempotency key are part of this table
+        return Ok(());
+    };
+
+    let mut row = builder.row();
+    row.partition_key(invocation_id.partition_key());
+    row.idempotency_key(idempotency_key);
+
+    if let Some(invocation_target) = invocation_status.invocation_target()? {
+        if row.is_service_name_defined() {
+            row.fmt_service_name(invocation_target.service_name());
+        }
+        if row.is_service_key_defined()
+            && let Some(key) = invocation_target.key()?
+        {
+            row.service_key(key);
+        }
+        if row.is_service_handler_defined() {
+            row.service_handler(invocation_target.handler_name());
+        }
+    }
+
+    if row.is_invocation_id_defined() {
+        row.fmt_invocation_id(invocation_id);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use bytestring::ByteString;
+use datafusion::arrow::array::LargeStringArray;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+
+use restate_storage_api::Transaction;
+use restate_storage_api::invocation_status_table::{
+    CompletedInvocation, InvocationStatus, WriteInvocationStatusTable,
+};
+use restate_types::identifiers::InvocationId;
+use restate_types::invocation::InvocationTarget;
+
+use crate::mocks::*;
+use crate::row;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_idempotency_keys() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_id = InvocationId::mock_random();
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let mut tx = engine.partition_store().transaction();
+    tx.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Completed(CompletedInvocation {
+            invocation_target: invocation_target.clone(),
+            idempotency_key: Some(ByteString::from_static("my-idempotency-key")),
+            ..CompletedInvocation::mock_neo()
+        }),
+    )
+    .unwrap();
+    // invocations without idempotency key are not part of the table
+    tx.put_invocation_status(
+        &InvocationId::mock_random(),
+        &InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+    )
+    .unwrap();
+    tx.commit().await.unwrap();
+    drop(tx);
+
+    let records = engine
+        .execute("SELECT * FROM sys_idempotency")
+        .await
+        .unwrap()
+        .stream
+        .collect::<Vec<datafusion::common::Result<RecordBatch>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_eq!(records.num_rows(), 1);
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "service_name" => LargeStringArray: eq(invocation_target.service_name().to_string()),
+                "service_key" => LargeStringArray: eq(invocation_target.key().unwrap().to_string()),
+                "service_handler" => LargeStringArray: eq(invocation_target.handler_name().to_string()),
+                "idempotency_key" => LargeStringArray: eq("my-idempotency-key"),
+                "invocation_id" => LargeStringArray: eq(invocation_id.to_string()),
+            }
+        ))
+    );
+}
@@ -0,0 +1,192 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Context;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::parquet::arrow::ArrowWriter;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+use tracing::info;
+use url::Url;
+
+use restate_object_store_util::create_object_store_client;
+use restate_storage_api::protobuf_types::v1::lazy::InvocationStatusV2Lazy;
+use restate_types::config::InvocationArchiveOptions;
+use restate_types::errors::ConversionError;
+use restate_types::identifiers::{InvocationId, JournalEntryId};
+use restate_types::storage::StoredRawEntry;
+use restate_types::time::MillisSinceEpoch;
+
+use super::{INVOCATIONS_DIR, JOURNALS_DIR};
+use crate::invocation_status::row::append_invocation_status_row;
+use crate::invocation_status::schema::SysInvocationStatusBuilder;
+use crate::journal::row::append_journal_row_v2;
+use crate::journal::schema::SysJournalBuilder;
+use crate::table_util::Builder;
+
+/// Object store location the completed invocations are archived to.
+#[derive(Debug, Clone)]
+pub struct InvocationArchive {
+    object_store: Arc<dyn ObjectStore>,
+    destination: Url,
+    prefix: Path,
+}
+
+impl InvocationArchive {
+    pub async fn create(options: &InvocationArchiveOptions) -> anyhow::Result<Self> {
+        let mut destination = Url::parse(&options.destination)
+            .context("Failed parsing invocation archive destination URL")?;
+        // Prevent passing configuration options to object_store via the destination URL.
+        destination
+            .query()
+            .inspect(|params| info!("Invocation archive destination parameters ignored: {params}"));
+        destination.set_query(None);
+
+        let prefix = Path::from(destination.path());
+        let object_store = create_object_store_client(
+            destination.clone(),
+            &options.object_store,
+            &options.object_store_retry_policy,
+        )
+        .await?;
+
+        Ok(Self {
+            object_store,
+            destination,
+            prefix,
+        })
+    }
+
+    pub(super) fn object_store(&self) -> &Arc<dyn ObjectStore> {
+        &self.object_store
+    }
+
+    /// URL of the directory containing the archived files of the given kind.
+    pub(super) fn url(&self, dir: &str) -> Url {
+        let mut url = self.destination.clone();
+        url.set_path(&format!("{}/{dir}/", self.prefix));
+        url
+    }
+
+    /// Writes the batch to the archive, as one Parquet file called `file_name` per
+    /// [`ArchivePartition`]. `file_name` must be unique across all writers of the archive.
+    pub async fn write(
+        &self,
+        batch: InvocationArchiveBatch,
+        file_name: &str,
+    ) -> anyhow::Result<()> {
+        for (partition, builder) in batch.invocations {
+            let path = partition.path(&self.prefix.child(INVOCATIONS_DIR), file_name);
+            self.put(&path, builder.finish()?).await?;
+        }
+        for (partition, builder) in batch.journals {
+            let path = partition.path(&self.prefix.child(JOURNALS_DIR), file_name);
+            self.put(&path, builder.finish()?).await?;
+        }
+        Ok(())
+    }
+
+    async fn put(&self, path: &Path, batch: RecordBatch) -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        self.object_store
+            .put(path, PutPayload::from(buf))
+            .await
+            .with_context(|| format!("Failed writing archive file {path}"))?;
+        Ok(())
+    }
+}
+
+/// Hive-style partition of the archive an invocation is written to, based on the completion date
+/// (UTC) and the target service of the invocation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ArchivePartition {
+    date: String,
+    service: String,
+}
+
+impl ArchivePartition {
+    /// Returns `None` if the invocation is not completed.
+    pub fn from_invocation_status(
+        invocation_status: &InvocationStatusV2Lazy<'_>,
+    ) -> Result<Option<Self>, ConversionError> {
+        let Some(completed_time) = invocation_status.inner.completed_transition_time else {
+            return Ok(None);
+        };
+        let Some(invocation_target) = invocation_status.invocation_target()? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            date: MillisSinceEpoch::new(completed_time)
+                .into_timestamp()
+                .strftime("%Y-%m-%d")
+                .to_string(),
+            service: invocation_target.service_name().to_string(),
+        }))
+    }
+
+    fn path(&self, base: &Path, file_name: &str) -> Path {
+        base.child(format!("date={}", self.date))
+            .child(format!("service={}", self.service))
+            .child(file_name)
+    }
+}
+
+/// Rows to be written to the [`InvocationArchive`].
+#[derive(Default)]
+pub struct InvocationArchiveBatch {
+    invocations: BTreeMap<ArchivePartition, SysInvocationStatusBuilder>,
+    journals: BTreeMap<ArchivePartition, SysJournalBuilder>,
+}
+
+impl InvocationArchiveBatch {
+    pub fn append_invocation(
+        &mut self,
+        partition: &ArchivePartition,
+        invocation_id: InvocationId,
+        invocation_status: &InvocationStatusV2Lazy<'_>,
+    ) -> Result<(), ConversionError> {
+        let builder = self
+            .invocations
+            .entry(partition.clone())
+            .or_insert_with(|| {
+                SysInvocationStatusBuilder::new(SysInvocationStatusBuilder::schema())
+            });
+        append_invocation_status_row(builder, invocation_id, invocation_status)
+    }
+
+    pub fn append_journal_entry(
+        &mut self,
+        partition: &ArchivePartition,
+        journal_entry_id: JournalEntryId,
+        entry: StoredRawEntry,
+    ) {
+        let builder = self
+            .journals
+            .entry(partition.clone())
+            .or_insert_with(|| SysJournalBuilder::new(SysJournalBuilder::schema()));
+        append_journal_row_v2(builder, journal_entry_id, entry);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invocations.is_empty() && self.journals.is_empty()
+    }
+
+    pub fn num_invocations(&self) -> usize {
+        self.invocations.values().map(Builder::num_rows).sum()
+    }
+}
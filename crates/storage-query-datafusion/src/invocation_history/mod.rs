@@ -0,0 +1,28 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Archive of completed invocations.
+//!
+//! The partition leaders write the completed invocations, and optionally their journals, to
+//! Parquet files on an object store before removing them. The archived files use the schemas of
+//! `sys_invocation_status` and `sys_journal` respectively, and are exposed together with the live
+//! tables by the `sys_invocation_history` and `sys_journal_history` tables.
+
+mod archive;
+mod table;
+
+pub use archive::{ArchivePartition, InvocationArchive, InvocationArchiveBatch};
+pub(crate) use table::register_self;
+
+const INVOCATIONS_DIR: &str = "invocations";
+const JOURNALS_DIR: &str = "journals";
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,104 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::dataframe::DataFrame;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::listing::{
+    ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+};
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::SessionContext;
+use datafusion::prelude::{col, lit};
+
+use super::{INVOCATIONS_DIR, InvocationArchive, JOURNALS_DIR};
+use crate::context::QueryContext;
+use crate::invocation_status::schema::SysInvocationStatusBuilder;
+use crate::journal::schema::SysJournalBuilder;
+
+const ARCHIVED_COLUMN: &str = "archived";
+
+/// Registers `sys_invocation_history` and `sys_journal_history`, which contain the rows of
+/// `sys_invocation_status` and `sys_journal` together with the archived ones, if an archive is
+/// configured. Must be called after the live tables have been registered.
+pub(crate) async fn register_self(
+    ctx: &QueryContext,
+    archive: Option<&InvocationArchive>,
+) -> Result<(), DataFusionError> {
+    register_history_table(
+        ctx,
+        "sys_invocation_history",
+        "sys_invocation_status",
+        SysInvocationStatusBuilder::schema(),
+        archive.map(|archive| (archive, INVOCATIONS_DIR)),
+    )
+    .await?;
+    register_history_table(
+        ctx,
+        "sys_journal_history",
+        "sys_journal",
+        SysJournalBuilder::schema(),
+        archive.map(|archive| (archive, JOURNALS_DIR)),
+    )
+    .await
+}
+
+async fn register_history_table(
+    ctx: &QueryContext,
+    name: &str,
+    live_table: &str,
+    schema: SchemaRef,
+    archive: Option<(&InvocationArchive, &str)>,
+) -> Result<(), DataFusionError> {
+    let columns: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| col(field.name()))
+        .collect();
+
+    let mut history = ctx
+        .as_ref()
+        .table(live_table)
+        .await?
+        .select(columns.clone())?
+        .with_column(ARCHIVED_COLUMN, lit(false))?;
+
+    if let Some((archive, dir)) = archive {
+        let archived = read_archive(ctx.as_ref(), archive, dir, schema)?
+            .select(columns)?
+            .with_column(ARCHIVED_COLUMN, lit(true))?;
+        history = history.union(archived)?;
+    }
+
+    ctx.register_non_partitioned_table(name, history.into_view())
+}
+
+/// Reads the archived files of the given kind, which use the given schema.
+pub(super) fn read_archive(
+    session: &SessionContext,
+    archive: &InvocationArchive,
+    dir: &str,
+    schema: SchemaRef,
+) -> Result<DataFrame, DataFusionError> {
+    let url = archive.url(dir);
+    session.register_object_store(&url, Arc::clone(archive.object_store()));
+
+    // The files are listed recursively, so we don't need to declare the date and service
+    // directories as partition columns.
+    let options =
+        ListingOptions::new(Arc::new(ParquetFormat::default())).with_file_extension(".parquet");
+    let config = ListingTableConfig::new(ListingTableUrl::parse(url.as_str())?)
+        .with_listing_options(options)
+        .with_schema(schema);
+
+    session.read_table(Arc::new(ListingTable::try_new(config)?))
+}
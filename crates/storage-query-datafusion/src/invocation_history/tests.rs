@@ -0,0 +1,169 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{BooleanArray, LargeStringArray};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::execution::context::SessionContext;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use parking_lot::Mutex;
+
+use restate_storage_api::Transaction;
+use restate_storage_api::invocation_status_table::{
+    CompletedInvocation, InvocationStatus, ScanInvocationStatusTable, WriteInvocationStatusTable,
+};
+use restate_types::config::InvocationArchiveOptions;
+use restate_types::errors::ConversionError;
+use restate_types::identifiers::InvocationId;
+use restate_types::invocation::InvocationTarget;
+use restate_types::retries::RetryPolicy;
+
+use super::table::read_archive;
+use super::{ArchivePartition, INVOCATIONS_DIR, InvocationArchive, InvocationArchiveBatch};
+use crate::invocation_status::schema::SysInvocationStatusBuilder;
+use crate::mocks::*;
+use crate::row;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn history_contains_live_invocations() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_id = InvocationId::mock_random();
+    let mut tx = engine.partition_store().transaction();
+    tx.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Completed(CompletedInvocation::mock_neo()),
+    )
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    let records = engine
+        .execute("SELECT id, archived FROM sys_invocation_history")
+        .await
+        .unwrap()
+        .stream
+        .collect::<Vec<datafusion::common::Result<RecordBatch>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        row!(
+            0,
+            {
+                "id" => LargeStringArray: eq(invocation_id.to_string()),
+                "archived" => BooleanArray: eq(false),
+            }
+        )
+    );
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn archive_round_trip() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let invocation_id = InvocationId::mock_random();
+    let invocation_target = InvocationTarget::mock_service();
+    let completed_invocation = CompletedInvocation {
+        invocation_target: invocation_target.clone(),
+        ..CompletedInvocation::mock_neo()
+    };
+    let completed_on = completed_invocation
+        .timestamps
+        .completed_transition_time()
+        .unwrap();
+    let mut tx = engine.partition_store().transaction();
+    tx.put_invocation_status(
+        &invocation_id,
+        &InvocationStatus::Completed(completed_invocation),
+    )
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    // Collect the invocation like the cleaner does
+    let batch = Arc::new(Mutex::new(InvocationArchiveBatch::default()));
+    let archived = engine
+        .partition_store()
+        .filter_map_invocation_status_lazy({
+            let batch = Arc::clone(&batch);
+            move |(invocation_id, invocation_status)| {
+                let partition = ArchivePartition::from_invocation_status(invocation_status)?
+                    .expect("invocation is completed");
+                batch
+                    .lock()
+                    .append_invocation(&partition, invocation_id, invocation_status)?;
+                Result::<_, ConversionError>::Ok(Some(()))
+            }
+        })
+        .unwrap()
+        .count()
+        .await;
+    assert_eq!(archived, 1);
+    let batch = std::mem::take(&mut *batch.lock());
+    assert_eq!(batch.num_invocations(), 1);
+
+    let archive_dir = tempfile::tempdir().unwrap();
+    let archive = InvocationArchive::create(&InvocationArchiveOptions {
+        destination: format!("file://{}", archive_dir.path().display()),
+        include_journal: false,
+        max_invocations_per_file: NonZeroUsize::new(10).unwrap(),
+        object_store: Default::default(),
+        object_store_retry_policy: RetryPolicy::None,
+    })
+    .await
+    .unwrap();
+    archive.write(batch, "0-1.parquet").await.unwrap();
+
+    let expected_file = archive_dir
+        .path()
+        .join(INVOCATIONS_DIR)
+        .join(format!(
+            "date={}",
+            completed_on.into_timestamp().strftime("%Y-%m-%d")
+        ))
+        .join(format!("service={}", invocation_target.service_name()))
+        .join("0-1.parquet");
+    assert!(
+        expected_file.exists(),
+        "{} is missing",
+        expected_file.display()
+    );
+
+    let records = read_archive(
+        &SessionContext::new(),
+        &archive,
+        INVOCATIONS_DIR,
+        SysInvocationStatusBuilder::schema(),
+    )
+    .unwrap()
+    .select_columns(&["id", "target_service_name", "status"])
+    .unwrap()
+    .collect()
+    .await
+    .unwrap()
+    .remove(0);
+
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "id" => LargeStringArray: eq(invocation_id.to_string()),
+                "target_service_name" => LargeStringArray: eq(invocation_target.service_name().to_string()),
+                "status" => LargeStringArray: eq("completed"),
+            }
+        ))
+    );
+}
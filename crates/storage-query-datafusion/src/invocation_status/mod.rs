@@ -8,7 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-mod row;
+pub(crate) mod row;
 pub(crate) mod schema;
 mod table;
 
@@ -0,0 +1,42 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use datafusion::arrow::datatypes::DataType;
+
+use crate::table_macro::*;
+
+define_table!(
+    /// Progress of the Kafka consumers of the subscriptions, per topic-partition.
+    sys_kafka_consumer(
+        /// The PlainNodeId of the node running the consumer.
+        plain_node_id: DataType::Utf8,
+        /// Current known generation ID of the node.
+        gen_node_id: DataType::Utf8,
+        /// The ID of the subscription.
+        subscription_id: DataType::Utf8,
+        /// The Kafka consumer group of the subscription.
+        consumer_group: DataType::Utf8,
+        /// The consumed topic. Not set if no partitions are assigned to the consumer.
+        topic: DataType::Utf8,
+        /// The consumed topic partition. Not set if no partitions are assigned to the consumer.
+        partition: DataType::Int32,
+        /// Offset of the last message that was ingested into Restate.
+        processed_offset: DataType::UInt64,
+        /// Last offset committed to the consumer group.
+        committed_offset: DataType::UInt64,
+        /// Offset of the next message that will be produced to the partition.
+        high_watermark: DataType::UInt64,
+        /// Number of messages the consumer group is behind the high watermark.
+        lag: DataType::UInt64,
+        /// The last error of the consumer, if any. Errors are retained while the consumer is
+        /// being restarted.
+        last_error: DataType::Utf8,
+    )
+);
@@ -14,18 +14,24 @@ pub mod remote_query_scanner_server;
 
 pub mod bifrost_read_stream;
 pub mod config;
+mod dedup;
 mod deployment;
+mod idempotency;
 mod inbox;
+pub mod invocation_history;
 mod invocation_state;
 mod invocation_status;
 mod journal;
 mod journal_events;
+pub mod kafka_consumer;
 mod keyed_service_status;
 mod locks;
 mod log;
+pub mod loglet_scrub_finding;
 pub mod loglet_worker;
 mod node;
 pub mod node_fan_out;
+mod outbox;
 mod partition;
 mod partition_replica_set;
 mod partition_state;
@@ -37,10 +43,12 @@ mod scheduler_status;
 mod service;
 mod state;
 mod statistics;
+mod subscription;
 #[cfg(feature = "table_docs")]
 pub mod table_docs;
 mod table_macro;
 mod table_providers;
+mod timer;
 mod user_limits;
 mod vqueue_entry_status;
 mod vqueue_meta;
@@ -0,0 +1,41 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use datafusion::arrow::datatypes::DataType;
+
+use crate::table_macro::*;
+
+define_table!(
+    /// Damaged records found by the replicated loglet scrubber on each node in the cluster.
+    sys_loglet_scrub_findings(
+        /// The PlainNodeId of the node that ran the scrubber.
+        plain_node_id: DataType::Utf8,
+        /// Current known generation ID of the node.
+        gen_node_id: DataType::Utf8,
+        /// The log the loglet belongs to.
+        log_id: DataType::UInt32,
+        /// The segment index of the loglet within the log.
+        segment_index: DataType::UInt32,
+        /// The loglet ID.
+        loglet_id: DataType::Utf8,
+        /// The loglet offset of the damaged record.
+        offset: DataType::UInt32,
+        /// Kind of damage: `corrupted` or `under-replicated`.
+        kind: DataType::Utf8,
+        /// The log-server holding the corrupted copy. Only set if `kind` is `corrupted`.
+        replica_node_id: DataType::Utf8,
+        /// Number of log-servers that held a healthy copy of the record.
+        healthy_copies: DataType::UInt32,
+        /// Result of the repair attempt: `repaired`, `failed` or `unrepairable`.
+        repair_outcome: DataType::Utf8,
+        /// When the damage was detected.
+        detected_at: TimestampMillisecond,
+    )
+);
@@ -0,0 +1,45 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use restate_core::Metadata;
+
+use crate::context::QueryContext;
+use crate::node_fan_out::{AllNodeLocator, NodeFanOutTableProvider};
+use crate::remote_query_scanner_manager::RemoteScannerManager;
+use crate::table_providers::Scan;
+
+use super::schema::SysLogletScrubFindingsBuilder;
+
+pub(crate) const TABLE_NAME: &str = "sys_loglet_scrub_findings";
+
+/// Registers the `sys_loglet_scrub_findings` fan-out table in the query context.
+///
+/// Loglets are scrubbed by the node running their sequencer, which can be any node of the
+/// cluster, so this table fans out to all nodes.
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    metadata: Metadata,
+    remote_scanner_manager: RemoteScannerManager,
+    local_scanner: Option<Arc<dyn Scan>>,
+) -> datafusion::common::Result<()> {
+    let schema = SysLogletScrubFindingsBuilder::schema();
+
+    let table = NodeFanOutTableProvider::new(
+        schema,
+        Arc::new(AllNodeLocator::new(metadata)),
+        remote_scanner_manager,
+        local_scanner,
+        TABLE_NAME,
+    );
+
+    ctx.register_non_partitioned_table(TABLE_NAME, Arc::new(table))
+}
@@ -28,15 +28,19 @@ use restate_types::NodeId;
 use restate_types::config::QueryEngineOptions;
 use restate_types::deployment::{DeploymentAddress, Headers};
 use restate_types::errors::GenericError;
-use restate_types::identifiers::{DeploymentId, PartitionId, ServiceRevision};
+use restate_types::identifiers::{DeploymentId, PartitionId, ServiceRevision, SubscriptionId};
 use restate_types::live::Live;
 use restate_types::net::address::{AdvertisedAddress, HttpIngressPort};
 use restate_types::net::remote_query_scanner::RemoteQueryScannerOpen;
 use restate_types::partition_table::Partition;
+use restate_types::schema::Redaction;
 use restate_types::schema::deployment::test_util::MockDeploymentMetadataRegistry;
 use restate_types::schema::deployment::{Deployment, DeploymentResolver};
 use restate_types::schema::service::test_util::MockServiceMetadataResolver;
 use restate_types::schema::service::{ServiceMetadata, ServiceMetadataResolver};
+use restate_types::schema::subscriptions::{
+    ListSubscriptionFilter, Subscription, SubscriptionResolver,
+};
 use restate_types::sharding::KeyRange;
 use restate_worker_api::invoker::{InvocationStatusReport, StatusHandle};
 use restate_worker_api::{SchedulerStatusEntry, UserLimitCounterEntry};
@@ -70,6 +74,7 @@ impl StatusHandle for MockStatusHandle {
 pub(crate) struct MockSchemas(
     pub(crate) MockServiceMetadataResolver,
     pub(crate) MockDeploymentMetadataRegistry,
+    pub(crate) Vec<Subscription>,
 );
 
 impl ServiceMetadataResolver for MockSchemas {
@@ -94,6 +99,24 @@ impl ServiceMetadataResolver for MockSchemas {
     }
 }
 
+impl SubscriptionResolver for MockSchemas {
+    fn get_subscription(&self, id: SubscriptionId, _: Redaction) -> Option<Subscription> {
+        self.2.iter().find(|s| s.id() == id).cloned()
+    }
+
+    fn list_subscriptions(
+        &self,
+        filters: &[ListSubscriptionFilter],
+        _: Redaction,
+    ) -> Vec<Subscription> {
+        self.2
+            .iter()
+            .filter(|s| filters.iter().all(|f| f.matches(s)))
+            .cloned()
+            .collect()
+    }
+}
+
 impl PartitionLeaderStatusHandle for MockStatusHandle {
     type SchedulerStatus = SchedulerStatusEntry;
     type SchedulerStatusIterator = std::iter::Empty<Self::SchedulerStatus>;
@@ -197,6 +220,7 @@ impl MockQueryEngine {
         >,
         schemas: impl DeploymentResolver
         + ServiceMetadataResolver
+        + SubscriptionResolver
         + Send
         + Sync
         + Debug
@@ -253,6 +277,22 @@ impl MockQueryEngine {
     ) -> Result<crate::context::QueryResult, crate::context::QueryError> {
         self.2.execute(sql.as_ref()).await
     }
+
+    pub async fn execute_statement(
+        &self,
+        sql: impl AsRef<str> + Send,
+    ) -> Result<crate::context::StatementResult, crate::context::QueryError> {
+        self.2.execute_statement(sql.as_ref()).await
+    }
+
+    pub async fn filter_table(
+        &self,
+        table: &str,
+        predicate: &str,
+    ) -> Result<crate::context::QueryResult, crate::context::QueryError> {
+        let plan = self.2.filter_table(table, predicate).await?.build()?;
+        self.2.execute_logical_plan(plan).await
+    }
 }
 
 // --- Matchers for rows
@@ -0,0 +1,80 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_storage_api::outbox_table::OutboxMessage;
+use restate_types::identifiers::{PartitionId, WithPartitionKey};
+use restate_types::invocation::{InvocationQuery, InvocationTarget};
+
+use super::schema::{SysOutboxBuilder, SysOutboxRowBuilder};
+
+#[inline]
+pub(crate) fn append_outbox_row(
+    builder: &mut SysOutboxBuilder,
+    partition_id: PartitionId,
+    sequence_number: u64,
+    message: OutboxMessage,
+) {
+    let mut row = builder.row();
+    row.partition_id(partition_id.into());
+    row.sequence_number(sequence_number);
+    row.target_partition_key(message.partition_key());
+
+    match message {
+        OutboxMessage::ServiceInvocation(service_invocation) => {
+            row.kind("service_invocation");
+            if row.is_target_id_defined() {
+                row.fmt_target_id(service_invocation.invocation_id);
+            }
+            fill_invocation_target(&mut row, &service_invocation.invocation_target);
+        }
+        OutboxMessage::ServiceResponse(response) => {
+            row.kind("service_response");
+            if row.is_target_id_defined() {
+                row.fmt_target_id(response.target.caller_id);
+            }
+        }
+        OutboxMessage::InvocationTermination(termination) => {
+            row.kind("invocation_termination");
+            if row.is_target_id_defined() {
+                row.fmt_target_id(termination.invocation_id);
+            }
+        }
+        OutboxMessage::AttachInvocation(request) => {
+            row.kind("attach_invocation");
+            if row.is_target_id_defined()
+                && let InvocationQuery::Invocation(invocation_id) = request.invocation_query
+            {
+                row.fmt_target_id(invocation_id);
+            }
+        }
+        OutboxMessage::NotifySignal(request) => {
+            row.kind("notify_signal");
+            if row.is_target_id_defined() {
+                row.fmt_target_id(request.invocation_id);
+            }
+        }
+        OutboxMessage::InvocationEvent(event) => {
+            row.kind("invocation_event");
+            if row.is_target_id_defined() {
+                row.fmt_target_id(event.invocation_id);
+            }
+            fill_invocation_target(&mut row, &event.invocation_target);
+        }
+    }
+}
+
+#[inline]
+fn fill_invocation_target(row: &mut SysOutboxRowBuilder, invocation_target: &InvocationTarget) {
+    row.target_service_name(invocation_target.service_name());
+    if let Some(key) = invocation_target.key() {
+        row.target_service_key(key);
+    }
+    row.target_handler_name(invocation_target.handler_name());
+}
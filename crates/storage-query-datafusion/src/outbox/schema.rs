@@ -0,0 +1,46 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_outbox(
+    /// The partition whose outbox contains the message.
+    partition_id: DataType::UInt32,
+
+    /// Sequence number in the outbox.
+    sequence_number: DataType::UInt64,
+
+    /// The kind of message. Either `service_invocation`, `service_response`,
+    /// `invocation_termination`, `attach_invocation`, `notify_signal` or `invocation_event`.
+    kind: DataType::LargeUtf8,
+
+    /// Partition key of the recipient of the message. The message will be delivered to the
+    /// partition owning this key.
+    target_partition_key: DataType::UInt64,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the invocation the message
+    /// is addressed to. Not present for `attach_invocation` messages that don't query an
+    /// invocation id.
+    target_id: DataType::LargeUtf8,
+
+    /// The name of the invoked service. Only present for `service_invocation` and
+    /// `invocation_event` messages.
+    target_service_name: DataType::LargeUtf8,
+
+    /// The key of the invoked virtual object/workflow. Only present for `service_invocation` and
+    /// `invocation_event` messages.
+    target_service_key: DataType::LargeUtf8,
+
+    /// The name of the invoked handler. Only present for `service_invocation` and
+    /// `invocation_event` messages.
+    target_handler_name: DataType::LargeUtf8,
+));
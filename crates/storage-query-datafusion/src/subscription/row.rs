@@ -0,0 +1,51 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+
+use super::schema::SysSubscriptionBuilder;
+
+use restate_types::schema::subscriptions::{
+    EventInvocationTargetTemplate, Sink, Source, Subscription,
+};
+
+#[inline]
+pub(crate) fn append_subscription_row(
+    builder: &mut SysSubscriptionBuilder,
+    subscription: Subscription,
+) {
+    let mut row = builder.row();
+    row.fmt_id(subscription.id());
+    row.fmt_source(subscription.source());
+    match subscription.source() {
+        Source::Kafka { cluster, topic } => {
+            row.source_cluster(cluster);
+            row.source_topic(topic);
+        }
+    }
+    row.fmt_sink(subscription.sink());
+    let Sink::Invocation {
+        event_invocation_target_template,
+    } = subscription.sink();
+    match event_invocation_target_template {
+        EventInvocationTargetTemplate::Service { name, handler }
+        | EventInvocationTargetTemplate::VirtualObject { name, handler, .. }
+        | EventInvocationTargetTemplate::Workflow { name, handler, .. } => {
+            row.sink_service(name);
+            row.sink_handler(handler);
+        }
+    }
+    if row.is_options_defined()
+        && let Ok(options) =
+            serde_json::to_string(&subscription.metadata().iter().collect::<BTreeMap<_, _>>())
+    {
+        row.options(options);
+    }
+}
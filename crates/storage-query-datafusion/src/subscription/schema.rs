@@ -0,0 +1,39 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_subscription(
+    /// The ID of the subscription.
+    id: DataType::LargeUtf8,
+
+    /// The source of the events, e.g. `kafka://my-cluster/my-topic`.
+    source: DataType::LargeUtf8,
+
+    /// The name of the Kafka cluster the events are consumed from.
+    source_cluster: DataType::LargeUtf8,
+
+    /// The Kafka topic the events are consumed from.
+    source_topic: DataType::LargeUtf8,
+
+    /// The sink of the events, e.g. `service://MyService/myHandler`.
+    sink: DataType::LargeUtf8,
+
+    /// The name of the service the events are delivered to.
+    sink_service: DataType::LargeUtf8,
+
+    /// The name of the handler the events are delivered to.
+    sink_handler: DataType::LargeUtf8,
+
+    /// The options of the subscription, as JSON object. Sensitive values are redacted.
+    options: DataType::LargeUtf8,
+));
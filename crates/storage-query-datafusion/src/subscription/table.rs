@@ -0,0 +1,96 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchReceiverStream;
+use restate_types::live::Live;
+use tokio::sync::mpsc::Sender;
+
+use restate_types::schema::Redaction;
+use restate_types::schema::subscriptions::{Subscription, SubscriptionResolver};
+
+use super::schema::SysSubscriptionBuilder;
+use crate::context::QueryContext;
+use crate::statistics::{RowEstimate, TableStatisticsBuilder};
+use crate::subscription::row::append_subscription_row;
+use crate::table_providers::{GenericTableProvider, Scan};
+use crate::table_util::Builder;
+
+pub(crate) fn register_self(
+    ctx: &QueryContext,
+    resolver: Live<impl SubscriptionResolver + Send + Sync + 'static>,
+) -> datafusion::common::Result<()> {
+    let schema = SysSubscriptionBuilder::schema();
+    let statistics = TableStatisticsBuilder::new(schema)
+        .with_num_rows_estimate(RowEstimate::Tiny)
+        .with_primary_key("id");
+    let subscription_table = GenericTableProvider::new(
+        SysSubscriptionBuilder::schema(),
+        Arc::new(SubscriptionScanner(resolver)),
+    )
+    .with_statistics(statistics.build());
+
+    ctx.register_non_partitioned_table("sys_subscription", Arc::new(subscription_table))
+}
+
+#[derive(Clone, derive_more::Debug)]
+#[debug("SubscriptionScanner")]
+struct SubscriptionScanner<SR>(Live<SR>);
+
+impl<SR: SubscriptionResolver + Sync + Send + 'static> Scan for SubscriptionScanner<SR> {
+    fn scan(
+        &self,
+        projection: SchemaRef,
+        _filters: &[Expr],
+        batch_size: usize,
+        _limit: Option<usize>,
+    ) -> SendableRecordBatchStream {
+        let schema = projection.clone();
+        let mut stream_builder = RecordBatchReceiverStream::builder(projection, 16);
+        let tx = stream_builder.tx();
+
+        let rows = self.0.pinned().list_subscriptions(&[], Redaction::Yes);
+        stream_builder.spawn(async move {
+            for_each_state(schema, tx, rows, batch_size).await;
+            Ok(())
+        });
+        stream_builder.build()
+    }
+}
+
+async fn for_each_state(
+    schema: SchemaRef,
+    tx: Sender<datafusion::common::Result<RecordBatch>>,
+    rows: Vec<Subscription>,
+    batch_size: usize,
+) {
+    let mut builder = SysSubscriptionBuilder::new(schema.clone());
+    for subscription in rows {
+        append_subscription_row(&mut builder, subscription);
+        if builder.num_rows() >= batch_size {
+            let batch = builder.finish_and_new();
+            if tx.send(batch).await.is_err() {
+                // not sure what to do here?
+                // the other side has hung up on us.
+                // we probably don't want to panic, is it will cause the entire process to exit
+                return;
+            }
+        }
+    }
+    if !builder.empty() {
+        let result = builder.finish();
+        let _ = tx.send(result).await;
+    }
+}
@@ -9,9 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use crate::{
-    deployment, inbox, invocation_state, invocation_status, journal, journal_events,
-    keyed_service_status, promise, scheduler_status, service, state, vqueue_entry_status,
-    vqueue_meta, vqueues,
+    dedup, deployment, idempotency, inbox, invocation_state, invocation_status, journal,
+    journal_events, keyed_service_status, outbox, promise, scheduler_status, service, state,
+    subscription, timer, vqueue_entry_status, vqueue_meta, vqueues,
 };
 use std::borrow::Cow;
 
@@ -19,15 +19,20 @@ use std::borrow::Cow;
 /// this array. This will ensure that the table docs will be included in the automatic
 /// table docs generation process.
 pub const ALL_TABLE_DOCS: &[StaticTableDocs] = &[
+    dedup::schema::TABLE_DOCS,
     deployment::schema::TABLE_DOCS,
+    idempotency::schema::TABLE_DOCS,
     inbox::schema::TABLE_DOCS,
     journal::schema::TABLE_DOCS,
     journal_events::schema::TABLE_DOCS,
     keyed_service_status::schema::TABLE_DOCS,
+    outbox::schema::TABLE_DOCS,
     promise::schema::TABLE_DOCS,
     scheduler_status::schema::TABLE_DOCS,
     service::schema::TABLE_DOCS,
     state::schema::TABLE_DOCS,
+    subscription::schema::TABLE_DOCS,
+    timer::schema::TABLE_DOCS,
     vqueue_entry_status::schema::TABLE_DOCS,
     vqueue_meta::schema::TABLE_DOCS,
     vqueues::schema::TABLE_DOCS,
@@ -268,3 +273,41 @@ pub fn sys_invocation_table_docs() -> OwnedTableDocs {
         columns,
     }
 }
+
+pub fn sys_invocation_history_table_docs() -> OwnedTableDocs {
+    history_table_docs(
+        "sys_invocation_history",
+        "The invocations of `sys_invocation_status`, together with the completed invocations \
+         which were archived before being removed.",
+        &invocation_status::schema::TABLE_DOCS,
+    )
+}
+
+pub fn sys_journal_history_table_docs() -> OwnedTableDocs {
+    history_table_docs(
+        "sys_journal_history",
+        "The journal entries of `sys_journal`, together with the journal entries which were \
+         archived before being removed.",
+        &journal::schema::TABLE_DOCS,
+    )
+}
+
+fn history_table_docs(
+    name: &'static str,
+    description: &'static str,
+    live_table_docs: &StaticTableDocs,
+) -> OwnedTableDocs {
+    // We need to compile this manually, due to the fact that it's a view.
+    let mut columns = live_table_docs.columns.to_vec();
+    columns.push(TableColumn {
+        name: "archived",
+        column_type: "Boolean",
+        description: "Whether the row was read from the invocation archive.",
+    });
+
+    OwnedTableDocs {
+        name: Cow::Borrowed(name),
+        description: Cow::Borrowed(description),
+        columns,
+    }
+}
@@ -8,13 +8,14 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::time::{Duration, SystemTime};
 
 use bytes::Bytes;
 use datafusion::arrow::array::{
-    ArrayRef, DurationMillisecondArray, LargeStringArray, ListArray, TimestampMillisecondArray,
-    UInt32Array, UInt64Array,
+    ArrayRef, DurationMillisecondArray, LargeBinaryArray, LargeStringArray, ListArray,
+    TimestampMillisecondArray, UInt32Array, UInt64Array,
 };
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::StreamExt;
@@ -31,11 +32,16 @@ use restate_storage_api::vqueue_table::stats::WaitStats;
 use restate_types::Scope;
 use restate_types::errors::InvocationError;
 use restate_types::identifiers::InvocationUuid;
-use restate_types::identifiers::{DeploymentId, InvocationId, PartitionKey, ServiceId};
+use restate_types::identifiers::{
+    DeploymentId, InvocationId, PartitionKey, ServiceId, SubscriptionId,
+};
 use restate_types::invocation::InvocationTarget;
 use restate_types::journal::EntryType;
 use restate_types::journal_v2::NotificationId;
 use restate_types::journal_v2::UnresolvedFuture;
+use restate_types::schema::subscriptions::{
+    EventInvocationTargetTemplate, Sink, Source, Subscription,
+};
 use restate_types::service_protocol::ServiceProtocolVersion;
 use restate_types::sharding::KeyRange;
 use restate_types::vqueues::EntryId;
@@ -49,7 +55,7 @@ use restate_worker_api::{
 };
 use strum::IntoDiscriminant;
 
-use crate::context::PartitionLeaderStatusHandle;
+use crate::context::{PartitionLeaderStatusHandle, StateDmlOp, StatementResult};
 use crate::mocks::*;
 use crate::row;
 
@@ -974,3 +980,186 @@ async fn query_state_with_service_key_filter() {
         row!(0, { "service_name" => LargeStringArray: eq("unscoped") })
     );
 }
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn plan_state_dml() {
+    let i1 = ServiceId::new(None, "counter", "i1");
+    let i2 = ServiceId::new(None, "counter", "i2");
+
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    tx.put_user_state(&i1, &Bytes::from_static(b"a"), b"1")
+        .unwrap();
+    tx.put_user_state(&i1, &Bytes::from_static(b"b"), b"2")
+        .unwrap();
+    tx.put_user_state(&i2, &Bytes::from_static(b"a"), b"3")
+        .unwrap();
+    tx.commit().await.unwrap();
+    drop(tx);
+
+    let StatementResult::StateDml(delete) = engine
+        .execute_statement("DELETE FROM state WHERE key = 'a' AND service_key = 'i1'")
+        .await
+        .unwrap()
+    else {
+        panic!("expected a state DML statement");
+    };
+    assert_eq!(delete.op, StateDmlOp::Delete);
+    let deleted = delete
+        .rows
+        .collect::<Vec<datafusion::common::Result<RecordBatch>>>()
+        .await
+        .remove(0)
+        .unwrap();
+    assert_eq!(deleted.num_rows(), 1);
+    assert_that!(
+        deleted,
+        row!(0, {
+            "service_key" => LargeStringArray: eq("i1"),
+            "key" => LargeStringArray: eq("a"),
+        })
+    );
+
+    let StatementResult::StateDml(update) = engine
+        .execute_statement("UPDATE state SET value = CAST('4' AS BYTEA) WHERE service_key = 'i2'")
+        .await
+        .unwrap()
+    else {
+        panic!("expected a state DML statement");
+    };
+    assert_eq!(update.op, StateDmlOp::Update);
+    let updated = update
+        .rows
+        .collect::<Vec<datafusion::common::Result<RecordBatch>>>()
+        .await
+        .remove(0)
+        .unwrap();
+    assert_eq!(updated.num_rows(), 1);
+    assert_that!(
+        updated,
+        row!(0, {
+            "key" => LargeStringArray: eq("a"),
+            "value" => LargeBinaryArray: eq(b"4".to_vec()),
+        })
+    );
+
+    // Only the value can be updated
+    assert!(
+        engine
+            .execute_statement("UPDATE state SET key = 'c'")
+            .await
+            .is_err()
+    );
+    // Other tables stay read-only
+    assert!(
+        engine
+            .execute_statement("DELETE FROM sys_invocation_status")
+            .await
+            .is_err()
+    );
+    // DML is only accepted through execute_statement
+    assert!(engine.execute("DELETE FROM state").await.is_err());
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn filter_table_accepts_a_single_predicate() {
+    let engine = MockQueryEngine::create().await;
+
+    let result = engine
+        .filter_table(
+            "sys_invocation",
+            "status = 'paused' AND target_service_name = 'Greeter'",
+        )
+        .await
+        .unwrap();
+    let rows: usize = result
+        .stream
+        .map(|batch| batch.unwrap().num_rows())
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .sum();
+    assert_eq!(rows, 0);
+
+    for predicate in [
+        // escapes the predicate
+        "true) OR (true",
+        "true; DELETE FROM state",
+        "true LIMIT 1",
+        // not a boolean
+        "target_service_name",
+        // not a column of the table
+        "unknown_column = 1",
+        // subqueries
+        "id IN (SELECT id FROM sys_invocation_status)",
+    ] {
+        assert!(
+            engine
+                .filter_table("sys_invocation", predicate)
+                .await
+                .is_err(),
+            "{predicate}"
+        );
+    }
+}
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn query_sys_subscription() {
+    let subscription = Subscription::new(
+        SubscriptionId::new(),
+        Source::Kafka {
+            cluster: "my-cluster".to_string(),
+            topic: "orders".to_string(),
+        },
+        Sink::Invocation {
+            event_invocation_target_template: EventInvocationTargetTemplate::Service {
+                name: "Orders".to_string(),
+                handler: "process".to_string(),
+            },
+        },
+        HashMap::from([
+            ("group.id".to_string(), "orders-consumer".to_string()),
+            ("auto.offset.reset".to_string(), "earliest".to_string()),
+        ]),
+    );
+
+    let engine = MockQueryEngine::create_with(
+        MockStatusHandle::default(),
+        MockSchemas(
+            Default::default(),
+            Default::default(),
+            vec![subscription.clone()],
+        ),
+    )
+    .await;
+
+    let records = engine
+        .execute("SELECT * FROM sys_subscription")
+        .await
+        .unwrap()
+        .stream
+        .collect::<Vec<datafusion::common::Result<RecordBatch>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(row!(
+            0,
+            {
+                "id" => LargeStringArray: eq(subscription.id().to_string()),
+                "source" => LargeStringArray: eq("kafka://my-cluster/orders"),
+                "source_cluster" => LargeStringArray: eq("my-cluster"),
+                "source_topic" => LargeStringArray: eq("orders"),
+                "sink" => LargeStringArray: eq("service://Orders/process"),
+                "sink_service" => LargeStringArray: eq("Orders"),
+                "sink_handler" => LargeStringArray: eq("process"),
+                "options" => LargeStringArray: eq(
+                    r#"{"auto.offset.reset":"earliest","group.id":"orders-consumer"}"#
+                ),
+            }
+        ))
+    );
+}
@@ -0,0 +1,47 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use restate_storage_api::timer_table::{Timer, TimerKey};
+use restate_types::identifiers::WithPartitionKey;
+
+use super::schema::SysTimerBuilder;
+
+#[inline]
+pub(crate) fn append_timer_row(builder: &mut SysTimerBuilder, timer_key: TimerKey, timer: Timer) {
+    let mut row = builder.row();
+    row.partition_key(timer.partition_key());
+    row.fire_at(timer_key.timestamp as i64);
+
+    if row.is_id_defined() {
+        row.fmt_id(timer.invocation_id());
+    }
+
+    match timer {
+        Timer::Invoke(service_invocation) => {
+            row.kind("invoke");
+            let target = &service_invocation.invocation_target;
+            row.target_service_name(target.service_name());
+            if let Some(key) = target.key() {
+                row.target_service_key(key);
+            }
+            row.target_handler_name(target.handler_name());
+        }
+        Timer::NeoInvoke(_) => {
+            row.kind("invoke");
+        }
+        Timer::CompleteJournalEntry(_, journal_index) => {
+            row.kind("complete_journal_entry");
+            row.journal_index(journal_index);
+        }
+        Timer::CleanInvocationStatus(_) => {
+            row.kind("clean_invocation_status");
+        }
+    }
+}
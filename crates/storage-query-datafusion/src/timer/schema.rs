@@ -0,0 +1,47 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::table_macro::*;
+
+use datafusion::arrow::datatypes::DataType;
+
+define_table!(sys_timer(
+    /// Internal column that is used for partitioning the services invocations. Can be ignored.
+    partition_key: DataType::UInt64,
+
+    /// Timestamp at which the timer fires.
+    fire_at: TimestampMillisecond,
+
+    /// The kind of timer. Either `invoke` for a delayed invocation,
+    /// `complete_journal_entry` for a sleep or a timeout of an awaited entry, or
+    /// `clean_invocation_status` for the removal of a completed invocation once its retention
+    /// expired.
+    kind: DataType::LargeUtf8,
+
+    /// [Invocation ID](/operate/invocation#invocation-identifier) of the invocation the timer
+    /// belongs to.
+    id: DataType::LargeUtf8,
+
+    /// The index of the journal entry completed by this timer. Only present for timers of kind
+    /// `complete_journal_entry`.
+    journal_index: DataType::UInt32,
+
+    /// The name of the invoked service. Only present for delayed invocations scheduled before
+    /// Restate 1.2.
+    target_service_name: DataType::LargeUtf8,
+
+    /// The key of the invoked virtual object/workflow. Only present for delayed invocations
+    /// scheduled before Restate 1.2.
+    target_service_key: DataType::LargeUtf8,
+
+    /// The name of the invoked handler. Only present for delayed invocations scheduled before
+    /// Restate 1.2.
+    target_handler_name: DataType::LargeUtf8,
+));
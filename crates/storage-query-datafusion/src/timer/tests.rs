@@ -0,0 +1,68 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::mocks::*;
+use crate::row;
+use datafusion::arrow::array::{LargeStringArray, UInt32Array};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use googletest::all;
+use googletest::prelude::{assert_that, eq};
+use restate_storage_api::Transaction;
+use restate_storage_api::timer_table::{Timer, WriteTimerTable};
+use restate_types::identifiers::InvocationId;
+use restate_types::invocation::InvocationTarget;
+
+#[restate_core::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_timers() {
+    let mut engine = MockQueryEngine::create().await;
+
+    let mut tx = engine.partition_store().transaction();
+    let invocation_target = InvocationTarget::mock_virtual_object();
+    let invocation_id_1 = InvocationId::mock_generate(&invocation_target);
+    let invocation_id_2 = InvocationId::mock_generate(&invocation_target);
+    let (timer_key, timer) = Timer::neo_invoke(2000, invocation_id_1);
+    tx.put_timer(&timer_key, &timer).unwrap();
+    let (timer_key, timer) = Timer::complete_journal_entry(1000, invocation_id_2, 3);
+    tx.put_timer(&timer_key, &timer).unwrap();
+    tx.commit().await.unwrap();
+    drop(tx);
+
+    let records = engine
+        .execute("SELECT * FROM sys_timer ORDER BY fire_at")
+        .await
+        .unwrap()
+        .stream
+        .collect::<Vec<datafusion::common::Result<RecordBatch>>>()
+        .await
+        .remove(0)
+        .unwrap();
+
+    assert_that!(
+        records,
+        all!(
+            row!(
+                0,
+                {
+                    "id" => LargeStringArray: eq(invocation_id_2.to_string()),
+                    "kind" => LargeStringArray: eq("complete_journal_entry"),
+                    "journal_index" => UInt32Array: eq(3),
+                }
+            ),
+            row!(
+                1,
+                {
+                    "id" => LargeStringArray: eq(invocation_id_1.to_string()),
+                    "kind" => LargeStringArray: eq("invoke"),
+                }
+            )
+        )
+    );
+}
@@ -12,7 +12,8 @@ use std::sync::OnceLock;
 
 use http::HeaderMap;
 use opentelemetry_otlp::{
-    Protocol, SpanExporter as OTelSpanExporter, WithExportConfig, WithHttpConfig, WithTonicConfig,
+    LogExporter, MetricExporter, Protocol, SpanExporter as OTelSpanExporter, WithExportConfig,
+    WithHttpConfig, WithTonicConfig,
 };
 use tonic::metadata::MetadataMap;
 use tonic::transport::{Channel, ClientTlsConfig};
@@ -28,6 +29,40 @@ pub fn set_global_node_id(node_id: GenerationalNodeId) {
         .expect("Global NodeId is not set")
 }
 
+/// The span, metric and log exporters of `opentelemetry_otlp` have separate builder types which
+/// share the same configuration methods.
+macro_rules! build_exporter {
+    ($builder:expr, $exporter:ty) => {
+        match $builder {
+            ExporterBuilder::Tonic {
+                metadata,
+                channel,
+                protocol,
+            } => Ok(<$exporter>::builder()
+                .with_tonic()
+                .with_channel(channel.clone())
+                .with_metadata(metadata.clone())
+                .with_protocol(*protocol)
+                .build()
+                .map_err(|e| super::bad_endpoint(format!("build gRPC exporter: {e}")))?),
+
+            ExporterBuilder::Http {
+                client,
+                headers,
+                protocol,
+                endpoint,
+            } => Ok(<$exporter>::builder()
+                .with_http()
+                .with_http_client(client.clone())
+                .with_protocol(*protocol)
+                .with_headers(headers.clone())
+                .with_endpoint(endpoint.to_string())
+                .build()
+                .map_err(|e| super::bad_endpoint(format!("build HTTP exporter: {e}")))?),
+        }
+    };
+}
+
 #[derive(Debug, Clone)]
 pub enum ExporterBuilder {
     Tonic {
@@ -134,33 +169,17 @@ impl ExporterBuilder {
         Ok(builder)
     }
 
-    pub fn build(&self) -> Result<opentelemetry_otlp::SpanExporter, super::Error> {
-        match self {
-            ExporterBuilder::Tonic {
-                metadata,
-                channel,
-                protocol,
-            } => Ok(OTelSpanExporter::builder()
-                .with_tonic()
-                .with_channel(channel.clone())
-                .with_metadata(metadata.clone())
-                .with_protocol(*protocol)
-                .build()
-                .map_err(|e| super::bad_endpoint(format!("build gRPC exporter: {e}")))?),
+    pub fn build(&self) -> Result<OTelSpanExporter, super::Error> {
+        build_exporter!(self, OTelSpanExporter)
+    }
 
-            ExporterBuilder::Http {
-                client,
-                headers,
-                protocol,
-                endpoint,
-            } => Ok(OTelSpanExporter::builder()
-                .with_http()
-                .with_http_client(client.clone())
-                .with_protocol(*protocol)
-                .with_headers(headers.clone())
-                .with_endpoint(endpoint.to_string())
-                .build()
-                .map_err(|e| super::bad_endpoint(format!("build HTTP exporter: {e}")))?),
-        }
+    /// Builds a metrics exporter emitting to the same endpoint.
+    pub fn build_metric_exporter(&self) -> Result<MetricExporter, super::Error> {
+        build_exporter!(self, MetricExporter)
+    }
+
+    /// Builds a logs exporter emitting to the same endpoint.
+    pub fn build_log_exporter(&self) -> Result<LogExporter, super::Error> {
+        build_exporter!(self, LogExporter)
     }
 }
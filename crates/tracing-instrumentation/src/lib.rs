@@ -9,9 +9,13 @@
 // by the Apache License, Version 2.0.
 
 mod exporter;
+mod otlp_logs;
+#[cfg(feature = "prometheus")]
+mod otlp_metrics;
 mod pretty;
 #[cfg(feature = "prometheus")]
 pub mod prometheus_metrics;
+mod sampler;
 
 use std::borrow::Cow;
 use std::env;
@@ -19,6 +23,7 @@ use std::fmt::Display;
 use std::sync::OnceLock;
 
 use opentelemetry::global::{BoxedSpan, BoxedTracer};
+use opentelemetry::logs::LoggerProvider;
 use opentelemetry::trace::{SpanContext, Status, TracerProvider};
 use opentelemetry::{
     Context, trace,
@@ -26,6 +31,10 @@ use opentelemetry::{
 };
 use opentelemetry::{InstrumentationScope, KeyValue, global};
 use opentelemetry_contrib::trace::exporter::jaeger_json::JaegerJsonExporter;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::logs::SdkLoggerProvider;
+#[cfg(feature = "prometheus")]
+use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::runtime;
 use opentelemetry_sdk::trace::{SdkTracerProvider, TraceError};
@@ -44,10 +53,13 @@ use restate_types::config::{CommonOptions, LogFormat};
 use restate_types::identifiers::{DeploymentId, InvocationId};
 use restate_types::invocation::SpanRelation;
 use restate_types::invocation::{InvocationTarget, ServiceInvocationSpanContext};
+use restate_types::schema::invocation_target::TracingSampling;
 use restate_types::service_protocol::ServiceProtocolVersion;
 use restate_types::time::MillisSinceEpoch;
 
+use crate::otlp_logs::OtlpLogLayer;
 use crate::pretty::PrettyFields;
+use crate::sampler::ServicesSampler;
 
 pub use exporter::ExporterBuilder;
 pub use exporter::set_global_node_id;
@@ -99,12 +111,32 @@ pub mod semconv {
 
         pub const RESTATE_DEPLOYMENT_SERVICE_PROTOCOL_VERSION: &str =
             "restate.deployment.service_protocol_version";
+
+        /// Ratio with which the invocation is sampled, if configured for its target.
+        /// Set on ingress spans.
+        pub const RESTATE_TRACING_SAMPLE_RATIO: &str = "restate.tracing.sample_ratio";
+
+        /// Set on the failure marker span, emitted for failed invocations which were not sampled
+        /// if their target asks for failure markers.
+        pub const RESTATE_TRACING_FAILURE_MARKER: &str = "restate.tracing.failure_marker";
+
+        /// Prefix of the attributes recording invocation headers.
+        pub const RESTATE_INVOCATION_HEADER_PREFIX: &str = "restate.invocation.header.";
     }
 
     pub mod resource {
         pub use opentelemetry_semantic_conventions::resource::{
             SERVICE_INSTANCE_ID, SERVICE_NAME, SERVICE_NAMESPACE, SERVICE_VERSION,
         };
+
+        /// Name of the cluster the node belongs to.
+        pub const RESTATE_CLUSTER_NAME: &str = "restate.cluster.name";
+
+        /// Name of the node.
+        pub const RESTATE_NODE_NAME: &str = "restate.node.name";
+
+        /// Id of the node, only set if it is configured via `force-node-id`.
+        pub const RESTATE_NODE_ID: &str = "restate.node.id";
     }
 
     pub mod event {
@@ -251,6 +283,7 @@ fn install_opentelemetry_tracer_provider(
     // Reference: https://github.com/open-telemetry/opentelemetry-rust/blob/main/docs/migration_0.28.md#async-runtime-requirements-removed
     let provider = opentelemetry_sdk::trace::TracerProviderBuilder::default()
         .with_resource(resource)
+        .with_sampler(ServicesSampler::from_env())
         .with_span_processor(opentelemetry_sdk::trace::span_processor_with_async_runtime::BatchSpanProcessor::builder(exporter, runtime::Tokio).build())
         .build();
 
@@ -258,6 +291,77 @@ fn install_opentelemetry_tracer_provider(
     Ok(Some(provider))
 }
 
+/// Resource describing this node, attached to exported metrics and logs.
+fn node_resource(common_opts: &CommonOptions, service_name: impl Display) -> Resource {
+    let mut attributes = vec![
+        KeyValue::new(semconv::resource::SERVICE_NAMESPACE, "Restate"),
+        KeyValue::new(
+            semconv::resource::SERVICE_INSTANCE_ID,
+            format!("{}/{}", common_opts.cluster_name(), common_opts.node_name()),
+        ),
+        KeyValue::new(
+            semconv::resource::SERVICE_VERSION,
+            env!("CARGO_PKG_VERSION"),
+        ),
+        KeyValue::new(
+            semconv::resource::RESTATE_CLUSTER_NAME,
+            common_opts.cluster_name().to_owned(),
+        ),
+        KeyValue::new(
+            semconv::resource::RESTATE_NODE_NAME,
+            common_opts.node_name().to_owned(),
+        ),
+    ];
+    if let Some(node_id) = common_opts.force_node_id {
+        attributes.push(KeyValue::new(
+            semconv::resource::RESTATE_NODE_ID,
+            node_id.to_string(),
+        ));
+    }
+
+    Resource::builder_empty()
+        .with_attributes(otel_resource_attributes_from_env())
+        .with_service_name(service_name.to_string())
+        .with_attributes(attributes)
+        .build()
+}
+
+fn instrumentation_scope() -> InstrumentationScope {
+    InstrumentationScope::builder("restate")
+        .with_version(env!("CARGO_PKG_VERSION"))
+        .build()
+}
+
+/// Builds a logger provider exporting logs to the logs-export-endpoint, if set. The endpoint is
+/// parsed the same way as the tracing endpoint, see [`install_opentelemetry_tracer_provider`].
+fn build_opentelemetry_logger_provider(
+    common_opts: &CommonOptions,
+    resource: Resource,
+) -> Result<Option<SdkLoggerProvider>, Error> {
+    let opts = &common_opts.tracing;
+    let Some(endpoint) = &opts.logs_export_endpoint else {
+        return Ok(None);
+    };
+
+    let exporter =
+        ExporterBuilder::new(endpoint, opts.tracing_headers.clone())?.build_log_exporter()?;
+
+    // Like for spans, the exporters need the async runtime variant of the processor.
+    let processor =
+        opentelemetry_sdk::logs::log_processor_with_async_runtime::BatchLogProcessor::builder(
+            exporter,
+            runtime::Tokio,
+        )
+        .build();
+
+    Ok(Some(
+        SdkLoggerProvider::builder()
+            .with_resource(resource)
+            .with_log_processor(processor)
+            .build(),
+    ))
+}
+
 #[allow(clippy::type_complexity, dead_code)]
 fn build_runtime_tracing_layer<S>(
     common_opts: &CommonOptions,
@@ -323,11 +427,7 @@ where
 
     let provider = tracer_provider_builder.build();
 
-    let tracer = provider.tracer_with_scope(
-        InstrumentationScope::builder("restate")
-            .with_version(env!("CARGO_PKG_VERSION"))
-            .build(),
-    );
+    let tracer = provider.tracer_with_scope(instrumentation_scope());
 
     global::set_text_map_propagator(TraceContextPropagator::new());
 
@@ -349,7 +449,7 @@ where
 /// panic if it is executed outside of a Tokio runtime.
 pub fn init_tracing_and_logging(
     common_opts: &CommonOptions,
-    _service_name: impl Display,
+    service_name: impl Display,
 ) -> Result<TracingGuard, Error> {
     let layers = tracing_subscriber::registry();
 
@@ -388,6 +488,13 @@ pub fn init_tracing_and_logging(
     // Service (user) tracing.
     let service_tracer_provider = install_opentelemetry_tracer_provider(common_opts)?;
 
+    // OTLP metrics and logs export.
+    let resource = node_resource(common_opts, &service_name);
+    #[cfg(feature = "prometheus")]
+    let meter_provider =
+        otlp_metrics::install_opentelemetry_meter_provider(common_opts, resource.clone())?;
+    let logger_provider = build_opentelemetry_logger_provider(common_opts, resource)?;
+
     // Runtime Distributed Tracing layer
     // **
     // TEMPORARILY DISABLED DUE TO SIGNIFICANT LOCK CONTENTION
@@ -425,11 +532,24 @@ pub fn init_tracing_and_logging(
 
     let layers = layers.with(log_layer.with_filter(log_filter));
 
+    // OTLP log export layer, sharing the filter of the logging layer.
+    let otlp_log_layer = match &logger_provider {
+        Some(logger_provider) => Some(
+            OtlpLogLayer::new(logger_provider.logger_with_scope(instrumentation_scope()))
+                .with_filter(EnvFilter::try_new(&common_opts.log_filter)?),
+        ),
+        None => None,
+    };
+    let layers = layers.with(otlp_log_layer);
+
     layers.init();
 
     Ok(TracingGuard {
         is_dropped: false,
         service_tracer_provider,
+        #[cfg(feature = "prometheus")]
+        meter_provider,
+        logger_provider,
         _stdout_guard,
         _stderr_guard,
     })
@@ -438,6 +558,9 @@ pub fn init_tracing_and_logging(
 pub struct TracingGuard {
     is_dropped: bool,
     service_tracer_provider: Option<SdkTracerProvider>,
+    #[cfg(feature = "prometheus")]
+    meter_provider: Option<SdkMeterProvider>,
+    logger_provider: Option<SdkLoggerProvider>,
     _stdout_guard: tracing_appender::non_blocking::WorkerGuard,
     _stderr_guard: tracing_appender::non_blocking::WorkerGuard,
 }
@@ -457,6 +580,13 @@ impl TracingGuard {
         if let Some(service_tracer_provider) = self.service_tracer_provider.take() {
             _ = service_tracer_provider.shutdown();
         }
+        #[cfg(feature = "prometheus")]
+        if let Some(meter_provider) = self.meter_provider.take() {
+            _ = meter_provider.shutdown();
+        }
+        if let Some(logger_provider) = self.logger_provider.take() {
+            _ = logger_provider.shutdown();
+        }
     }
 
     pub fn on_config_update(&self) {
@@ -512,21 +642,24 @@ impl Drop for TracingGuard {
 /// id: ref to an instance of [`InvocationId`]
 /// target: ref to an instance of [`InvocationTarget`]
 /// tags: is a list of any extra tags that need to be associated with this span for example `tags = (client.ip = "10.20.30.40")`
+/// attributes [optional]: is a collection of additional [`KeyValue`] attributes, for attributes not known at compile time
 /// fields [optional]: is a list of extra custom span builder fields that can be used to override the default ones for example `fields = (with_span_id = 10)`
 #[macro_export]
 macro_rules! invocation_span {
-    (level= $lvl:expr, relation = $relation:expr, prefix= $prefix:expr, id= $id:expr, target= $target:expr, tags=($($($key:ident).+ = $value:expr),*), fields=($($field:ident = $field_value:expr),*)) => {
+    (level= $lvl:expr, relation = $relation:expr, prefix= $prefix:expr, id= $id:expr, target= $target:expr, tags=($($($key:ident).+ = $value:expr),*), $(attributes= $extra_attributes:expr,)? fields=($($field:ident = $field_value:expr),*)) => {
         {
             use ::opentelemetry::KeyValue;
             use $crate::semconv;
 
-            let attributes = vec![
+            #[allow(unused_mut)]
+            let mut attributes = vec![
                 KeyValue::new(semconv::attribute::RPC_SERVICE, $target.service_name().to_string()),
                 KeyValue::new(semconv::attribute::RPC_METHOD, $target.handler_name().to_string()),
                 KeyValue::new(semconv::attribute::RESTATE_INVOCATION_ID, $id.to_string()),
                 KeyValue::new(semconv::attribute::RESTATE_INVOCATION_TARGET, $target.to_string()),
                 $(KeyValue::new(stringify!($($key).+), $value),)*
             ];
+            $(attributes.extend($extra_attributes);)?
 
             $crate::invocation_span!(
                 level = $lvl,
@@ -620,6 +753,18 @@ macro_rules! info_invocation_span {
             fields = ()
         )
     };
+    (relation=$relation:expr, prefix= $prefix:expr, id= $id:expr, target= $target:expr, tags=($($($key:ident).+ = $value:expr),*), attributes= $extra_attributes:expr) => {
+        $crate::invocation_span!(
+            level = ::tracing::Level::INFO,
+            relation = $relation,
+            prefix = $prefix,
+            id = $id,
+            target = $target,
+            tags = ($($($key).+ = $value),*),
+            attributes = $extra_attributes,
+            fields = ()
+        )
+    };
     (relation=$relation:expr, prefix= $prefix:expr, id= $id:expr, name=$name:expr, tags=($($($key:ident).+ = $value:expr),*), fields=($($field:ident = $field_value:expr),*)) => {
         $crate::invocation_span!(
             level = ::tracing::Level::INFO,
@@ -888,7 +1033,7 @@ pub fn create_invocation_end_span(
     let tracer = get_services_tracer();
 
     tracer
-        .span_builder(format!("invocation-end {}", invocation_target.short()))
+        .span_builder(format!("invocation-failure {}", invocation_target.short()))
         .with_attributes(vec![
             KeyValue::new(
                 semconv::attribute::RPC_SERVICE,
@@ -921,6 +1066,98 @@ pub fn create_invocation_end_span(
         .into()
 }
 
+/// Creates a failure marker span for a failed invocation that was not sampled, as the root of a
+/// new trace. Used for targets configured to emit failure markers.
+///
+/// This is not tail-based sampling: the other spans of the invocation (ingress, attempts) were not
+/// sampled and are not part of the trace. The marker only records that, and how, the invocation
+/// failed.
+pub fn create_invocation_failure_marker_span(
+    invocation_id: &InvocationId,
+    invocation_target: &InvocationTarget,
+) -> ServiceSpan {
+    if !is_service_tracing_enabled() {
+        return ServiceSpan::noop();
+    }
+
+    use crate::semconv;
+
+    let tracer = get_services_tracer();
+
+    tracer
+        .span_builder(format!("invocation-failure {}", invocation_target.short()))
+        // Like for background invocations, use the invocation id as trace id
+        .with_trace_id(invocation_id.invocation_uuid().into())
+        .with_sampling_result(trace::SamplingResult {
+            decision: trace::SamplingDecision::RecordAndSample,
+            attributes: vec![],
+            trace_state: Default::default(),
+        })
+        .with_attributes(vec![
+            KeyValue::new(
+                semconv::attribute::RPC_SERVICE,
+                invocation_target.service_name().to_string(),
+            ),
+            KeyValue::new(
+                semconv::attribute::RPC_METHOD,
+                invocation_target.handler_name().to_string(),
+            ),
+            KeyValue::new(
+                semconv::attribute::RESTATE_INVOCATION_ID,
+                invocation_id.to_string(),
+            ),
+            KeyValue::new(
+                semconv::attribute::RESTATE_INVOCATION_TARGET,
+                invocation_target.to_string(),
+            ),
+            KeyValue::new(semconv::attribute::RESTATE_TRACING_FAILURE_MARKER, true),
+        ])
+        .start_with_context(&tracer, &Context::new())
+        .into()
+}
+
+/// Additional attributes of the ingress span of an invocation: the sample ratio configured for
+/// the target, used by the sampler, and the invocation headers allow-listed in
+/// `tracing-header-attributes`.
+pub fn ingress_span_attributes<'a>(
+    sampling: &TracingSampling,
+    headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<KeyValue> {
+    let mut attributes = Vec::new();
+    if let Some(sample_ratio) = sampling.sample_ratio {
+        attributes.push(KeyValue::new(
+            semconv::attribute::RESTATE_TRACING_SAMPLE_RATIO,
+            sample_ratio,
+        ));
+    }
+
+    let configuration = restate_types::config::Configuration::pinned();
+    let allowed_headers = &configuration.common.tracing.tracing_header_attributes;
+    if !allowed_headers.is_empty() {
+        attributes.extend(
+            headers
+                .into_iter()
+                .filter(|(name, _)| {
+                    allowed_headers
+                        .iter()
+                        .any(|allowed| allowed.eq_ignore_ascii_case(name))
+                })
+                .map(|(name, value)| {
+                    KeyValue::new(
+                        format!(
+                            "{}{}",
+                            semconv::attribute::RESTATE_INVOCATION_HEADER_PREFIX,
+                            name.to_ascii_lowercase()
+                        ),
+                        value.to_owned(),
+                    )
+                }),
+        );
+    }
+
+    attributes
+}
+
 pub fn get_services_tracer() -> BoxedTracer {
     global::tracer_provider().tracer_with_scope(InstrumentationScope::builder("services").build())
 }
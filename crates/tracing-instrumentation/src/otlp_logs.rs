@@ -0,0 +1,131 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+use opentelemetry::logs::{AnyValue, LogRecord, Logger, Severity};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+/// Events of these targets are emitted while exporting and must not be exported themselves.
+const EXPORTER_TARGETS: &[&str] = &["opentelemetry", "tonic", "h2", "hyper", "reqwest", "tower"];
+
+/// Layer forwarding tracing events to an OpenTelemetry [`Logger`].
+pub(crate) struct OtlpLogLayer<L> {
+    logger: L,
+}
+
+impl<L> OtlpLogLayer<L> {
+    pub(crate) fn new(logger: L) -> Self {
+        Self { logger }
+    }
+}
+
+impl<S, L> Layer<S> for OtlpLogLayer<L>
+where
+    S: Subscriber,
+    L: Logger + Send + Sync + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if is_exporter_target(metadata.target()) {
+            return;
+        }
+
+        let mut record = self.logger.create_log_record();
+        record.set_timestamp(SystemTime::now());
+        record.set_target(metadata.target().to_owned());
+        record.set_event_name(metadata.name());
+        record.set_severity_number(severity(metadata.level()));
+        record.set_severity_text(metadata.level().as_str());
+        event.record(&mut LogRecordVisitor(&mut record));
+
+        self.logger.emit(record);
+    }
+}
+
+fn is_exporter_target(target: &str) -> bool {
+    EXPORTER_TARGETS.iter().any(|prefix| {
+        target
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::") || rest.starts_with('_'))
+    })
+}
+
+fn severity(level: &Level) -> Severity {
+    match *level {
+        Level::TRACE => Severity::Trace,
+        Level::DEBUG => Severity::Debug,
+        Level::INFO => Severity::Info,
+        Level::WARN => Severity::Warn,
+        Level::ERROR => Severity::Error,
+    }
+}
+
+/// Sets the `message` field as the body of the record, all other fields become attributes.
+struct LogRecordVisitor<'a, R>(&'a mut R);
+
+impl<R: LogRecord> LogRecordVisitor<'_, R> {
+    fn record_value(&mut self, field: &Field, value: AnyValue) {
+        match field.name() {
+            "message" => self.0.set_body(value),
+            // added by tracing-log for events coming from the log crate
+            name if name.starts_with("log.") => {}
+            name => self.0.add_attribute(name, value),
+        }
+    }
+}
+
+impl<R: LogRecord> Visit for LogRecordVisitor<'_, R> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.record_value(field, value.into()),
+            Err(_) => self.record_value(field, value.to_string().into()),
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record_value(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_value(field, value.to_owned().into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.record_value(field, format!("{value:?}").into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exporter_targets() {
+        assert!(is_exporter_target("opentelemetry_sdk::logs"));
+        assert!(is_exporter_target("opentelemetry"));
+        assert!(is_exporter_target("hyper::proto::h1"));
+        assert!(is_exporter_target("h2::codec"));
+        assert!(!is_exporter_target("restate_worker::partition"));
+        assert!(!is_exporter_target("hyperloglog"));
+    }
+}
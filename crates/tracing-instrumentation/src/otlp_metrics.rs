@@ -0,0 +1,454 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Bridges the metrics recorded through the `metrics` facade to OpenTelemetry instruments so
+//! they can be pushed via OTLP.
+//!
+//! The recorder is installed together with the Prometheus recorder, i.e. before the tokio runtime
+//! and the OpenTelemetry meter provider exist. Counters and gauges are therefore kept in atomics
+//! and reported through observable instruments once the meter is set, so nothing that was
+//! recorded before is lost. Histogram samples recorded before the meter is set are dropped.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use metrics_util::registry::{Registry, Storage};
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{
+    Histogram as OTelHistogram, Meter, MeterProvider, ObservableCounter, ObservableGauge,
+};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::runtime;
+
+use restate_types::config::CommonOptions;
+
+use crate::{Error, ExporterBuilder, instrumentation_scope};
+
+/// Meter used by all handles. Set once the meter provider has been installed.
+static METER: OnceLock<Meter> = OnceLock::new();
+
+/// State shared by the recorder and [`set_meter`].
+static STATE: OnceLock<Arc<State>> = OnceLock::new();
+
+/// Bucket boundaries (in seconds) used for histograms measuring durations.
+const SECONDS_BOUNDARIES: &[f64] = &[
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+    60.0,
+];
+
+struct State {
+    registry: Registry<Key, HandleStorage>,
+    descriptions: Descriptions,
+}
+
+type Descriptions = Arc<RwLock<HashMap<KeyName, Description>>>;
+
+struct Description {
+    unit: Option<Unit>,
+    description: SharedString,
+}
+
+/// Builds a meter provider periodically pushing metrics to the metrics-export-endpoint, if set.
+/// The endpoint is parsed the same way as the tracing endpoint, see
+/// [`install_opentelemetry_tracer_provider`](crate::install_opentelemetry_tracer_provider).
+///
+/// Metrics recorded through the `metrics` facade are only exported if the OTLP recorder has been
+/// installed by `Prometheus::install`.
+pub(crate) fn install_opentelemetry_meter_provider(
+    common_opts: &CommonOptions,
+    resource: Resource,
+) -> Result<Option<SdkMeterProvider>, Error> {
+    let opts = &common_opts.tracing;
+    let Some(endpoint) = &opts.metrics_export_endpoint else {
+        return Ok(None);
+    };
+
+    let exporter =
+        ExporterBuilder::new(endpoint, opts.tracing_headers.clone())?.build_metric_exporter()?;
+
+    // Like for spans, the exporters need the async runtime variant of the reader.
+    let reader =
+        opentelemetry_sdk::metrics::periodic_reader_with_async_runtime::PeriodicReader::builder(
+            exporter,
+            runtime::Tokio,
+        )
+        .with_interval(opts.metrics_export_interval.into())
+        .build();
+
+    let provider = SdkMeterProvider::builder()
+        .with_resource(resource)
+        .with_reader(reader)
+        .build();
+
+    set_meter(provider.meter_with_scope(instrumentation_scope()));
+
+    Ok(Some(provider))
+}
+
+/// Returns the recorder feeding the OpenTelemetry meter.
+///
+/// # Panics
+/// If called more than once.
+pub(crate) fn recorder() -> OtlpRecorder {
+    let state = Arc::new(State {
+        registry: Registry::new(HandleStorage),
+        descriptions: Descriptions::default(),
+    });
+    STATE
+        .set(Arc::clone(&state))
+        .unwrap_or_else(|_| panic!("OTLP metrics recorder is already installed"));
+    OtlpRecorder { state }
+}
+
+/// Sets the meter which receives the recorded metrics.
+pub(crate) fn set_meter(meter: Meter) {
+    if METER.set(meter).is_err() {
+        return;
+    }
+    let meter = METER.get().expect("meter is set");
+    let Some(state) = STATE.get() else {
+        // metrics are not recorded through the OTLP recorder
+        return;
+    };
+
+    // Handles registered with the recorder from now on create their instrument right away.
+    // Create the instruments of the existing ones.
+    let descriptions = &state.descriptions;
+    state
+        .registry
+        .visit_counters(|_, counter| counter.register(meter, descriptions));
+    state
+        .registry
+        .visit_gauges(|_, gauge| gauge.register(meter, descriptions));
+    state
+        .registry
+        .visit_histograms(|_, histogram| histogram.register(meter, descriptions));
+}
+
+pub(crate) struct OtlpRecorder {
+    state: Arc<State>,
+}
+
+impl OtlpRecorder {
+    fn describe(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.state
+            .descriptions
+            .write()
+            .expect("lock is not poisoned")
+            .insert(key, Description { unit, description });
+    }
+}
+
+impl Recorder for OtlpRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description);
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description);
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.describe(key, unit, description);
+    }
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        let handle = self.state.registry.get_or_create_counter(key, Arc::clone);
+        handle.register_if_meter_set(&self.state.descriptions);
+        Counter::from_arc(handle)
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        let handle = self.state.registry.get_or_create_gauge(key, Arc::clone);
+        handle.register_if_meter_set(&self.state.descriptions);
+        Gauge::from_arc(handle)
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        let handle = self.state.registry.get_or_create_histogram(key, Arc::clone);
+        handle.register_if_meter_set(&self.state.descriptions);
+        Histogram::from_arc(handle)
+    }
+}
+
+struct HandleStorage;
+
+impl Storage<Key> for HandleStorage {
+    type Counter = Arc<Handle<AtomicU64, ObservableCounter<u64>>>;
+    type Gauge = Arc<Handle<AtomicU64, ObservableGauge<f64>>>;
+    type Histogram = Arc<Handle<(), OTelHistogram<f64>>>;
+
+    fn counter(&self, key: &Key) -> Self::Counter {
+        Arc::new(Handle::new(key))
+    }
+
+    fn gauge(&self, key: &Key) -> Self::Gauge {
+        Arc::new(Handle::new(key))
+    }
+
+    fn histogram(&self, key: &Key) -> Self::Histogram {
+        Arc::new(Handle::new(key))
+    }
+}
+
+/// A single time series, identified by the metric name and its labels.
+struct Handle<V, I> {
+    name: String,
+    attributes: Arc<[KeyValue]>,
+    value: Arc<V>,
+    instrument: OnceLock<I>,
+}
+
+impl<V: Default, I> Handle<V, I> {
+    fn new(key: &Key) -> Self {
+        Self {
+            name: key.name().to_owned(),
+            attributes: key
+                .labels()
+                .map(|label| KeyValue::new(label.key().to_owned(), label.value().to_owned()))
+                .collect(),
+            value: Arc::default(),
+            instrument: OnceLock::new(),
+        }
+    }
+}
+
+impl<V, I> Handle<V, I>
+where
+    Self: RegisterInstrument,
+{
+    fn register_if_meter_set(&self, descriptions: &Descriptions) {
+        if let Some(meter) = METER.get() {
+            self.register(meter, descriptions);
+        }
+    }
+}
+
+trait RegisterInstrument {
+    fn register(&self, meter: &Meter, descriptions: &Descriptions);
+}
+
+impl RegisterInstrument for Handle<AtomicU64, ObservableCounter<u64>> {
+    fn register(&self, meter: &Meter, descriptions: &Descriptions) {
+        self.instrument.get_or_init(|| {
+            let value = Arc::clone(&self.value);
+            let attributes = Arc::clone(&self.attributes);
+            let mut builder = meter
+                .u64_observable_counter(self.name.clone())
+                .with_callback(move |observer| {
+                    observer.observe(value.load(Ordering::Relaxed), &attributes)
+                });
+            if let Some(description) = descriptions
+                .read()
+                .expect("lock is not poisoned")
+                .get(self.name.as_str())
+            {
+                builder = builder.with_description(description.description.to_string());
+                if let Some(unit) = description.unit {
+                    builder = builder.with_unit(otel_unit(unit));
+                }
+            }
+            builder.build()
+        });
+    }
+}
+
+impl RegisterInstrument for Handle<AtomicU64, ObservableGauge<f64>> {
+    fn register(&self, meter: &Meter, descriptions: &Descriptions) {
+        self.instrument.get_or_init(|| {
+            let value = Arc::clone(&self.value);
+            let attributes = Arc::clone(&self.attributes);
+            let mut builder =
+                meter
+                    .f64_observable_gauge(self.name.clone())
+                    .with_callback(move |observer| {
+                        observer.observe(f64::from_bits(value.load(Ordering::Relaxed)), &attributes)
+                    });
+            if let Some(description) = descriptions
+                .read()
+                .expect("lock is not poisoned")
+                .get(self.name.as_str())
+            {
+                builder = builder.with_description(description.description.to_string());
+                if let Some(unit) = description.unit {
+                    builder = builder.with_unit(otel_unit(unit));
+                }
+            }
+            builder.build()
+        });
+    }
+}
+
+impl RegisterInstrument for Handle<(), OTelHistogram<f64>> {
+    fn register(&self, meter: &Meter, descriptions: &Descriptions) {
+        self.instrument.get_or_init(|| {
+            let mut builder = meter.f64_histogram(self.name.clone());
+            if let Some(description) = descriptions
+                .read()
+                .expect("lock is not poisoned")
+                .get(self.name.as_str())
+            {
+                builder = builder.with_description(description.description.to_string());
+                if let Some(unit) = description.unit {
+                    builder = builder.with_unit(otel_unit(unit));
+                    if unit == Unit::Seconds {
+                        builder = builder.with_boundaries(SECONDS_BOUNDARIES.to_vec());
+                    }
+                }
+            }
+            builder.build()
+        });
+    }
+}
+
+impl CounterFn for Handle<AtomicU64, ObservableCounter<u64>> {
+    fn increment(&self, value: u64) {
+        self.value.fetch_add(value, Ordering::Relaxed);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.value.fetch_max(value, Ordering::Relaxed);
+    }
+}
+
+impl GaugeFn for Handle<AtomicU64, ObservableGauge<f64>> {
+    fn increment(&self, value: f64) {
+        self.update(|current| current + value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|current| current - value);
+    }
+
+    fn set(&self, value: f64) {
+        self.value.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl Handle<AtomicU64, ObservableGauge<f64>> {
+    fn update(&self, f: impl Fn(f64) -> f64) {
+        let _ = self
+            .value
+            .fetch_update(Ordering::AcqRel, Ordering::Relaxed, |current| {
+                Some(f(f64::from_bits(current)).to_bits())
+            });
+    }
+}
+
+impl HistogramFn for Handle<(), OTelHistogram<f64>> {
+    fn record(&self, value: f64) {
+        if let Some(histogram) = self.instrument.get() {
+            histogram.record(value, &self.attributes);
+        }
+    }
+}
+
+/// Maps units to their [UCUM](https://ucum.org/ucum) code as recommended by the OpenTelemetry
+/// semantic conventions.
+fn otel_unit(unit: Unit) -> &'static str {
+    match unit {
+        Unit::Count => "1",
+        Unit::Percent => "%",
+        Unit::Seconds => "s",
+        Unit::Milliseconds => "ms",
+        Unit::Microseconds => "us",
+        Unit::Nanoseconds => "ns",
+        Unit::Bytes => "By",
+        Unit::Kibibytes => "KiBy",
+        Unit::Mebibytes => "MiBy",
+        Unit::Gibibytes => "GiBy",
+        Unit::Tebibytes => "TiBy",
+        Unit::BitsPerSecond => "bit/s",
+        Unit::KilobitsPerSecond => "kbit/s",
+        Unit::MegabitsPerSecond => "Mbit/s",
+        Unit::GigabitsPerSecond => "Gbit/s",
+        Unit::TerabitsPerSecond => "Tbit/s",
+        Unit::CountPerSecond => "1/s",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_otlp::Protocol;
+
+    use super::*;
+
+    fn common_opts(metrics_export_endpoint: Option<&str>) -> CommonOptions {
+        let mut opts = CommonOptions::default();
+        opts.tracing.metrics_export_endpoint = metrics_export_endpoint.map(str::to_owned);
+        opts
+    }
+
+    #[test]
+    fn no_meter_provider_without_endpoint() {
+        let provider =
+            install_opentelemetry_meter_provider(&common_opts(None), Resource::builder().build())
+                .expect("no endpoint is valid");
+        assert!(provider.is_none());
+        assert!(METER.get().is_none());
+    }
+
+    #[test]
+    fn invalid_endpoints_are_rejected() {
+        for endpoint in [
+            "collector:4317",
+            "ftp://collector:4317",
+            "otlp+ftp://collector:4318/v1/metrics",
+            "grpc+json://collector:4317",
+        ] {
+            let result = install_opentelemetry_meter_provider(
+                &common_opts(Some(endpoint)),
+                Resource::builder().build(),
+            );
+            assert!(
+                matches!(result, Err(Error::InvalidTracingEndpoint(_))),
+                "{endpoint} should be rejected"
+            );
+        }
+        assert!(METER.get().is_none());
+    }
+
+    #[tokio::test]
+    async fn metric_exporter_per_scheme() {
+        for (endpoint, expected) in [
+            ("http://collector:4317", Protocol::Grpc),
+            ("grpc://collector:4317", Protocol::Grpc),
+            (
+                "otlp+http://collector:4318/v1/metrics",
+                Protocol::HttpBinary,
+            ),
+            (
+                "otlp+proto+https://collector:4318/v1/metrics",
+                Protocol::HttpBinary,
+            ),
+            (
+                "otlp+json+http://collector:4318/v1/metrics",
+                Protocol::HttpJson,
+            ),
+        ] {
+            let builder = ExporterBuilder::new(endpoint, Default::default())
+                .unwrap_or_else(|e| panic!("{endpoint} should be valid: {e}"));
+            let protocol = match &builder {
+                ExporterBuilder::Tonic { protocol, .. }
+                | ExporterBuilder::Http { protocol, .. } => *protocol,
+            };
+            assert_eq!(protocol, expected, "{endpoint}");
+            builder
+                .build_metric_exporter()
+                .unwrap_or_else(|e| panic!("metric exporter for {endpoint}: {e}"));
+        }
+    }
+}
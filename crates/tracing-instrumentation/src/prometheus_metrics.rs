@@ -11,12 +11,15 @@
 use indexmap::IndexMap;
 use metrics_exporter_prometheus::formatting;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_util::layers::FanoutBuilder;
 use tokio::task::AbortHandle;
 use tokio::time::MissedTickBehavior;
 use tracing::{debug, trace};
 
 use restate_types::config::CommonOptions;
 
+use crate::otlp_metrics;
+
 #[derive(Default)]
 pub struct Prometheus {
     handle: Option<PrometheusHandle>,
@@ -26,12 +29,23 @@ pub struct Prometheus {
 
 impl Prometheus {
     /// Creates and installs a global records unless prometheus is explicitly disabled in
-    /// configuration.
+    /// configuration. If a metrics export endpoint is configured, the recorded metrics are
+    /// additionally handed to the OTLP metrics exporter.
     ///
     /// Note that this *does not* start the upkeep task, the caller should call
     /// `start_upkeep_task()` from within a tokio runtime.
     pub fn install(opts: &CommonOptions) -> Self {
+        let otlp_recorder = opts
+            .tracing
+            .metrics_export_endpoint
+            .is_some()
+            .then(otlp_metrics::recorder);
+
         if opts.disable_prometheus {
+            if let Some(otlp_recorder) = otlp_recorder {
+                metrics::set_global_recorder(otlp_recorder)
+                    .expect("no global metrics recorder should be installed");
+            }
             return Self {
                 handle: None,
                 upkeep_task: None,
@@ -49,8 +63,17 @@ impl Prometheus {
 
         // We do not expect this to fail except due to atomic CAS failure
         // which should never happen in practice.
-        metrics::set_global_recorder(recorder)
-            .expect("no global metrics recorder should be installed");
+        match otlp_recorder {
+            Some(otlp_recorder) => metrics::set_global_recorder(
+                FanoutBuilder::default()
+                    .add_recorder(recorder)
+                    .add_recorder(otlp_recorder)
+                    .build(),
+            )
+            .expect("no global metrics recorder should be installed"),
+            None => metrics::set_global_recorder(recorder)
+                .expect("no global metrics recorder should be installed"),
+        }
         Self {
             handle: Some(prometheus_handle),
             upkeep_task: None,
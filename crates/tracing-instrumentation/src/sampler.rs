@@ -0,0 +1,168 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::env;
+
+use opentelemetry::trace::{Link, SamplingResult, SpanKind, TraceContextExt, TraceId};
+use opentelemetry::{Context, KeyValue, Value};
+use opentelemetry_sdk::trace::{Sampler, ShouldSample};
+
+use crate::semconv;
+
+/// Sampler of the services tracer provider.
+///
+/// Root spans carrying the [`semconv::attribute::RESTATE_TRACING_SAMPLE_RATIO`] attribute are
+/// sampled with that ratio. All other spans are sampled by the sampler configured through the
+/// `OTEL_TRACES_SAMPLER` and `OTEL_TRACES_SAMPLER_ARG` environment variables.
+#[derive(Debug, Clone)]
+pub(crate) struct ServicesSampler {
+    default: Sampler,
+}
+
+impl ServicesSampler {
+    pub(crate) fn from_env() -> Self {
+        Self {
+            default: sampler_from_env(
+                env::var("OTEL_TRACES_SAMPLER").ok().as_deref(),
+                env::var("OTEL_TRACES_SAMPLER_ARG").ok().as_deref(),
+            ),
+        }
+    }
+}
+
+impl ShouldSample for ServicesSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let is_root = !parent_context.is_some_and(|cx| cx.has_active_span());
+        match sample_ratio(attributes) {
+            Some(ratio) if is_root => Sampler::TraceIdRatioBased(ratio).should_sample(
+                parent_context,
+                trace_id,
+                name,
+                span_kind,
+                attributes,
+                links,
+            ),
+            _ => self.default.should_sample(
+                parent_context,
+                trace_id,
+                name,
+                span_kind,
+                attributes,
+                links,
+            ),
+        }
+    }
+}
+
+fn sample_ratio(attributes: &[KeyValue]) -> Option<f64> {
+    attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == semconv::attribute::RESTATE_TRACING_SAMPLE_RATIO)
+        .and_then(|kv| match kv.value {
+            Value::F64(ratio) => Some(ratio),
+            _ => None,
+        })
+}
+
+/// Mirrors the sampler configuration of the OpenTelemetry SDK, which is not applied when setting
+/// a custom sampler:
+/// https://opentelemetry.io/docs/specs/otel/configuration/sdk-environment-variables/#general-sdk-configuration
+fn sampler_from_env(sampler: Option<&str>, arg: Option<&str>) -> Sampler {
+    let ratio = || arg.and_then(|arg| arg.parse::<f64>().ok()).unwrap_or(1.0);
+    match sampler {
+        Some("always_on") => Sampler::AlwaysOn,
+        Some("always_off") => Sampler::AlwaysOff,
+        Some("traceidratio") => Sampler::TraceIdRatioBased(ratio()),
+        Some("parentbased_always_off") => Sampler::ParentBased(Box::new(Sampler::AlwaysOff)),
+        Some("parentbased_traceidratio") => {
+            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio())))
+        }
+        _ => Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use opentelemetry::trace::{SamplingDecision, SpanContext, SpanId, TraceFlags, TraceState};
+
+    fn decision(
+        sampler: &ServicesSampler,
+        parent_context: Option<&Context>,
+        attributes: &[KeyValue],
+    ) -> SamplingDecision {
+        sampler
+            .should_sample(
+                parent_context,
+                TraceId::from(u128::MAX),
+                "span",
+                &SpanKind::Internal,
+                attributes,
+                &[],
+            )
+            .decision
+    }
+
+    #[test]
+    fn sample_ratio_applies_to_root_spans() {
+        let sampler = ServicesSampler {
+            default: Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+        };
+        let never = [KeyValue::new(
+            semconv::attribute::RESTATE_TRACING_SAMPLE_RATIO,
+            0.0,
+        )];
+
+        assert_eq!(
+            decision(&sampler, None, &[]),
+            SamplingDecision::RecordAndSample
+        );
+        assert_eq!(decision(&sampler, None, &never), SamplingDecision::Drop);
+
+        // The decision of the parent takes precedence
+        let sampled_parent = Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from(1),
+            SpanId::from(1),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        ));
+        assert_eq!(
+            decision(&sampler, Some(&sampled_parent), &never),
+            SamplingDecision::RecordAndSample
+        );
+    }
+
+    #[test]
+    fn sampler_env_configuration() {
+        assert!(matches!(
+            sampler_from_env(None, None),
+            Sampler::ParentBased(inner) if matches!(*inner, Sampler::AlwaysOn)
+        ));
+        assert!(matches!(
+            sampler_from_env(Some("traceidratio"), Some("0.25")),
+            Sampler::TraceIdRatioBased(ratio) if ratio == 0.25
+        ));
+        assert!(matches!(
+            sampler_from_env(Some("parentbased_traceidratio"), Some("invalid")),
+            Sampler::ParentBased(inner)
+                if matches!(*inner, Sampler::TraceIdRatioBased(ratio) if ratio == 1.0)
+        ));
+    }
+}
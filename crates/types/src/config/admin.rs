@@ -65,6 +65,12 @@ pub struct AdminOptions {
     /// Controls the interval at which cluster controller polls nodes of the cluster.
     pub heartbeat_interval: NonZeroFriendlyDuration,
 
+    /// # Deployment drain check interval
+    ///
+    /// Interval at which the admin service counts the in-flight invocations pinned to draining
+    /// deployments, and removes the drained deployments which were marked for automatic removal.
+    pub deployment_drain_check_interval: NonZeroFriendlyDuration,
+
     /// Disable serving the Restate Web UI on the admin port. Default is `false`.
     pub disable_web_ui: bool,
 
@@ -129,6 +135,7 @@ impl Default for AdminOptions {
             concurrent_api_requests_limit: None,
             query_engine: Default::default(),
             heartbeat_interval: NonZeroFriendlyDuration::from_millis_unchecked(1500),
+            deployment_drain_check_interval: NonZeroFriendlyDuration::from_secs_unchecked(30),
             #[cfg(any(test, feature = "test-util"))]
             disable_cluster_controller: false,
             disable_web_ui: false,
@@ -418,6 +418,24 @@ pub struct ReplicatedLogletOptions {
     // hide the configuration option by excluding it from the Json schema
     #[cfg_attr(feature = "schemars", schemars(skip))]
     pub default_nodeset_size: NodeSetSize,
+
+    /// # Disable the scrubber
+    ///
+    /// Disables the background anti-entropy scrubber. The scrubber periodically walks the loglets
+    /// sequenced by this node, compares the record digests of all nodes in their nodeset, and
+    /// repairs missing or corrupt copies from healthy ones. Default is `false`.
+    pub disable_scrubber: bool,
+
+    /// # Scrub interval
+    ///
+    /// The time the scrubber waits between two passes over the loglets sequenced by this node.
+    pub scrub_interval: NonZeroFriendlyDuration,
+
+    /// # Scrub rate limit
+    ///
+    /// Maximum number of records per second the scrubber checks. Every checked record is read
+    /// (and its checksum verified) on all nodes of the loglet's nodeset.
+    pub scrub_records_per_second: NonZeroU32,
 }
 
 fn nodeset_size_is_zero(i: &NodeSetSize) -> bool {
@@ -448,6 +466,9 @@ impl Default for ReplicatedLogletOptions {
             readahead_records: NonZeroU16::new(20).unwrap(),
             readahead_trigger_ratio: 0.5,
             default_nodeset_size: NodeSetSize::default(),
+            disable_scrubber: false,
+            scrub_interval: NonZeroFriendlyDuration::from_secs_unchecked(60 * 60),
+            scrub_records_per_second: NonZeroU32::new(1000).unwrap(),
         }
     }
 }
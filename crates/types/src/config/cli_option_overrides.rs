@@ -205,6 +205,20 @@ pub struct CommonOptionCliOverride {
     #[clap(long, global = true)]
     pub tracing_filter: Option<String>,
 
+    /// Metrics Export Endpoint
+    ///
+    /// If set, metrics are periodically pushed to this endpoint using OTLP. Supports the same
+    /// URI schemes as the tracing endpoint.
+    #[clap(long, env = "RESTATE_METRICS_EXPORT_ENDPOINT", global = true)]
+    pub metrics_export_endpoint: Option<String>,
+
+    /// Logs Export Endpoint
+    ///
+    /// If set, logs are exported to this endpoint using OTLP. Supports the same URI schemes as
+    /// the tracing endpoint.
+    #[clap(long, env = "RESTATE_LOGS_EXPORT_ENDPOINT", global = true)]
+    pub logs_export_endpoint: Option<String>,
+
     /// Logging Filter
     ///
     /// Log filter configuration. Can be overridden by the `RUST_LOG` environment variable.
@@ -29,7 +29,9 @@ use super::{
 };
 use crate::PlainNodeId;
 use crate::config::dynamodb_store::DynamoDbOptions;
-use crate::config::{DeprecatedServiceClientOptions, NetworkingOptions};
+use crate::config::{
+    DeprecatedServiceClientOptions, FabricTlsOptions, ListenerTlsOptions, NetworkingOptions,
+};
 use crate::locality::NodeLocation;
 use crate::net::address::{AdvertisedAddress, ListenerPort};
 use crate::net::address::{BindAddress, FabricPort, TokioConsolePort};
@@ -129,6 +131,16 @@ pub struct ListenerOptions<P: ListenerPort + 'static> {
     /// or it'll use the value supplied in `advertised-host` if set.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     advertised_address: Option<AdvertisedAddress<P>>,
+
+    /// # TLS
+    ///
+    /// Serve HTTPS on the TCP socket of this listener; unix domain sockets stay plaintext.
+    /// HTTP/2 is negotiated via ALPN.
+    ///
+    /// Only supported by the HTTP ingress and admin API listeners. Use `networking.tls` to
+    /// secure the node-to-node message fabric.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tls: Option<ListenerTlsOptions>,
 }
 
 impl<P: ListenerPort + 'static> ListenerOptions<P> {
@@ -153,6 +165,7 @@ impl<P: ListenerPort + 'static> ListenerOptions<P> {
         if self.advertised_host.is_none() && self.advertised_address.is_none() {
             self.advertised_host.clone_from(&other.advertised_host);
         }
+        // - We don't inherit TLS, certificates are specific to each listener
     }
 
     pub fn listen_mode(&self) -> ListenMode {
@@ -171,9 +184,18 @@ impl<P: ListenerPort + 'static> ListenerOptions<P> {
 
     pub fn advertised_address(&self, address_book: &AddressBook) -> AdvertisedAddress<P> {
         self.advertised_address.clone().unwrap_or_else(|| {
-            address_book.guess_advertised_address(self.advertised_host.as_deref())
+            let address = address_book.guess_advertised_address(self.advertised_host.as_deref());
+            if self.tls.is_some() {
+                address.with_https_scheme()
+            } else {
+                address
+            }
         })
     }
+
+    pub fn tls(&self) -> Option<&ListenerTlsOptions> {
+        self.tls.as_ref()
+    }
 }
 
 impl<P: ListenerPort> Default for ListenerOptions<P> {
@@ -186,6 +208,7 @@ impl<P: ListenerPort> Default for ListenerOptions<P> {
             bind_port: None,
             bind_address: None,
             advertised_address: None,
+            tls: None,
         }
     }
 }
@@ -753,6 +776,11 @@ impl CommonOptions {
         self.base_dir = Some(path.into());
     }
 
+    #[cfg(feature = "unsafe-mutable-config")]
+    pub fn set_fabric_advertised_address(&mut self, address: AdvertisedAddress<FabricPort>) {
+        self.fabric_listener_options.advertised_address = Some(address);
+    }
+
     pub fn base_dir(&self) -> PathBuf {
         self.base_dir.clone().unwrap_or_else(|| {
             std::env::current_dir()
@@ -980,6 +1008,14 @@ pub struct MetadataClientOptions {
     /// over the cluster internal network.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_size_limit: Option<NonZeroByteCount>,
+
+    /// # Metadata client TLS
+    ///
+    /// Mutual TLS settings for connecting to the replicated metadata servers.
+    ///
+    /// If unset, defaults to `networking.tls`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<FabricTlsOptions>,
 }
 
 impl MetadataClientOptions {
@@ -989,6 +1025,10 @@ impl MetadataClientOptions {
                 .map(|limit| limit.min(network_options.message_size_limit))
                 .unwrap_or(network_options.message_size_limit),
         );
+
+        if self.tls.is_none() {
+            self.tls.clone_from(&network_options.tls);
+        }
     }
 
     pub fn message_size_limit(&self) -> NonZeroUsize {
@@ -1013,6 +1053,7 @@ impl Default for MetadataClientOptions {
                 Some(Duration::from_millis(1000)),
             ),
             message_size_limit: None,
+            tls: None,
         }
     }
 }
@@ -1251,6 +1292,43 @@ pub struct TracingOptions {
     #[serde(skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
     #[serde(default)]
     pub tracing_headers: SerdeableHeaderHashMap,
+
+    /// # Invocation headers recorded as span attributes
+    ///
+    /// Names of the invocation headers which are added as attributes to the ingress span of an
+    /// invocation, as `restate.invocation.header.<name>`. Header names are case-insensitive.
+    ///
+    /// Only add headers which don't contain sensitive information, as the attributes are
+    /// exported to the tracing endpoint.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub tracing_header_attributes: Vec<String>,
+
+    /// # Metrics Export Endpoint
+    ///
+    /// If set, metrics are periodically pushed to this endpoint using OTLP, in addition to being
+    /// served on the Prometheus endpoint. The URI scheme selects the transport and protocol in
+    /// the same way as for [`Self::tracing_endpoint`], e.g. `http://collector:4317` for OTLP
+    /// gRPC or `otlp+http://collector:4318/v1/metrics` for OTLP over HTTP.
+    ///
+    /// The [`Self::tracing_headers`] are sent along with every export request.
+    pub metrics_export_endpoint: Option<String>,
+
+    /// # Metrics Export Interval
+    ///
+    /// Interval between two exports to the [`Self::metrics_export_endpoint`].
+    pub metrics_export_interval: NonZeroFriendlyDuration,
+
+    /// # Logs Export Endpoint
+    ///
+    /// If set, logs are exported to this endpoint using OTLP, in addition to being written to
+    /// stdout/stderr. The same `log-filter` applies to both. The URI scheme selects the transport
+    /// and protocol in the same way as for [`Self::tracing_endpoint`], e.g.
+    /// `http://collector:4317` for OTLP gRPC or `otlp+http://collector:4318/v1/logs` for OTLP over
+    /// HTTP.
+    ///
+    /// The [`Self::tracing_headers`] are sent along with every export request.
+    pub logs_export_endpoint: Option<String>,
 }
 
 impl Default for TracingOptions {
@@ -1262,6 +1340,10 @@ impl Default for TracingOptions {
             tracing_json_path: None,
             tracing_filter: "info".to_owned(),
             tracing_headers: SerdeableHeaderHashMap::default(),
+            tracing_header_attributes: Vec::new(),
+            metrics_export_endpoint: None,
+            metrics_export_interval: NonZeroFriendlyDuration::from_secs_unchecked(30),
+            logs_export_endpoint: None,
         }
     }
 }
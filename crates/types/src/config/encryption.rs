@@ -0,0 +1,82 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// # Encryption at rest
+///
+/// Envelope encryption of user data that Restate persists to disk or uploads to the snapshot
+/// repository: state values, journal entries, Bifrost record payloads stored by log-servers,
+/// and partition snapshot archives. Records of the local loglet, inbox and outbox entries,
+/// invocation status and promise values are not encrypted.
+///
+/// Enabling encryption migrates the partition store to a storage version which tags state
+/// values, which nodes running an older Restate version cannot read.
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, derive_builder::Builder)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "EncryptionOptions", default))]
+#[serde(rename_all = "kebab-case")]
+#[builder(default)]
+pub struct EncryptionOptions {
+    /// # Key file
+    ///
+    /// Path to a TOML file holding the data encryption keys. When unset, encryption at rest
+    /// is disabled and data is written in plaintext. Data that was written while encryption
+    /// was enabled can only be read if its key is still listed in this file.
+    ///
+    /// The file lists one or more keys and names the key that is used to encrypt new data:
+    ///
+    /// ```toml
+    /// active-key = 2
+    ///
+    /// [[keys]]
+    /// id = 1
+    /// key = "<base64 encoded 256-bit key>"
+    ///
+    /// [[keys]]
+    /// id = 2
+    /// wrapped-key = "<base64 encoded key, encrypted by the KMS>"
+    /// ```
+    ///
+    /// To rotate keys, add a new key and point `active-key` to it. Older keys must stay in the
+    /// file for as long as data encrypted with them may still be read. When the configuration
+    /// is reloaded, the keys are reloaded if this section or the key file changed. Setting or
+    /// unsetting the key file only takes effect on restart.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<PathBuf>,
+
+    /// # KMS unwrap command
+    ///
+    /// Command used to unwrap keys listed as `wrapped-key` in the key file. The wrapped key
+    /// bytes are passed on the command's stdin and the raw 256-bit key is expected on its
+    /// stdout. The environment variable `RESTATE_KMS_KEY_ID` is set to the `kms-key-id` of
+    /// the key entry, if present.
+    ///
+    /// Example: `["aws", "kms", "decrypt", "--ciphertext-blob", "fileb:///dev/stdin",
+    /// "--query", "Plaintext", "--output", "text"]` combined with `kms-output-base64 = true`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kms_unwrap_command: Vec<String>,
+
+    /// # KMS output is base64
+    ///
+    /// Whether the output of `kms-unwrap-command` is base64 encoded rather than raw bytes.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub kms_output_base64: bool,
+}
+
+impl EncryptionOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.key_file.is_some()
+    }
+}
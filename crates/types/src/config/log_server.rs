@@ -32,6 +32,29 @@ const COMPACTION_BYTES_MULTIPLIER: u32 = 25;
 const MIN_FILE_SIZE: usize = 16 * 1024 * 1024;
 /// The absolute minimum write buffer size
 const MIN_WRITE_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+/// Segment files smaller than this are not worth rolling
+const MIN_SEGMENT_SIZE: usize = 1024 * 1024;
+
+/// # Log-store backend
+///
+/// The storage engine used by the log-server to persist loglet records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum LogStoreBackend {
+    /// # RocksDB
+    ///
+    /// Stores records and loglet metadata in a dedicated RocksDB database.
+    #[default]
+    #[serde(rename = "rocksdb")]
+    RocksDb,
+    /// # Segment files
+    ///
+    /// [Experimental] Stores records of each loglet in append-only segment files with
+    /// a sparse in-memory offset index. Writes are fsynced once per write batch and
+    /// trimming deletes whole segment files, avoiding compaction write amplification.
+    SegmentFile,
+}
 
 /// # Log server options
 ///
@@ -201,6 +224,50 @@ pub struct LogServerOptions {
     #[cfg_attr(feature = "schemars", schemars(skip))]
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub in_memory: bool,
+
+    /// # Storage backend
+    ///
+    /// The log-store backend used to persist records. Data is not migrated between
+    /// backends, the log-server refuses to start if the data directory of the other
+    /// backend exists. To switch the backend of a log-server, remove it from the cluster
+    /// and delete its data directory first.
+    ///
+    /// The `segment-file` backend does not support `in-memory`.
+    ///
+    /// Since v1.7.3
+    #[serde(default)]
+    pub storage_backend: LogStoreBackend,
+
+    /// # Segment size
+    ///
+    /// Only used by the `segment-file` backend. A loglet's active segment file is rolled
+    /// once it grows beyond this size. Trimming can only reclaim space of whole segments.
+    ///
+    /// The value is automatically sanitized to 1 MiB if set to a smaller value.
+    ///
+    /// [default] is 64 MiB
+    ///
+    /// Since v1.7.3
+    #[serde(default = "default_segment_max_size")]
+    pub segment_max_size: NonZeroByteCount,
+
+    /// # Segment index interval
+    ///
+    /// Only used by the `segment-file` backend. The maximum number of records between two
+    /// entries of the in-memory sparse offset index. Lower values speed up random reads at
+    /// the cost of memory.
+    ///
+    /// Since v1.7.3
+    #[serde(default = "default_segment_index_interval")]
+    pub segment_index_interval: NonZeroU32,
+}
+
+fn default_segment_max_size() -> NonZeroByteCount {
+    NonZeroByteCount::new(NonZeroUsize::new(64 * 1024 * 1024).unwrap())
+}
+
+fn default_segment_index_interval() -> NonZeroU32 {
+    NonZeroU32::new(64).unwrap()
 }
 
 impl LogServerOptions {
@@ -269,6 +336,22 @@ impl LogServerOptions {
         super::data_dir("log-store")
     }
 
+    pub fn segment_data_dir(&self) -> PathBuf {
+        super::data_dir("log-store-segments")
+    }
+
+    /// The backend which isn't configured in `storage_backend` and its data directory.
+    pub fn other_backend_data_dir(&self) -> (LogStoreBackend, PathBuf) {
+        match self.storage_backend {
+            LogStoreBackend::RocksDb => (LogStoreBackend::SegmentFile, self.segment_data_dir()),
+            LogStoreBackend::SegmentFile => (LogStoreBackend::RocksDb, self.data_dir()),
+        }
+    }
+
+    pub fn segment_max_size(&self) -> usize {
+        MIN_SEGMENT_SIZE.max(self.segment_max_size.as_usize())
+    }
+
     pub fn wal_dir(&self) -> PathBuf {
         self.rocksdb_wal_dir
             .clone()
@@ -369,6 +452,9 @@ impl Default for LogServerOptions {
             rocksdb_max_file_size: NonZeroByteCount::new(
                 NonZeroUsize::new(128 * 1024 * 1024).unwrap(),
             ),
+            storage_backend: LogStoreBackend::default(),
+            segment_max_size: default_segment_max_size(),
+            segment_index_interval: default_segment_index_interval(),
         }
     }
 }
@@ -18,6 +18,7 @@ mod bifrost;
 mod cli_option_overrides;
 mod common;
 mod dynamodb_store;
+mod encryption;
 mod gossip;
 mod http;
 mod ingress;
@@ -39,6 +40,7 @@ pub use bifrost::*;
 pub use cli_option_overrides::*;
 pub use common::*;
 pub use dynamodb_store::*;
+pub use encryption::*;
 pub use gossip::*;
 pub use http::*;
 pub use ingress::*;
@@ -301,6 +303,7 @@ pub struct Configuration {
     pub metadata_server: MetadataServerOptions,
     pub networking: NetworkingOptions,
     pub log_server: LogServerOptions,
+    pub encryption: EncryptionOptions,
 }
 
 impl Configuration {
@@ -444,6 +447,12 @@ impl Configuration {
             return Err(InvalidConfigurationError::ForceNodeIdZero);
         }
 
+        if self.common.fabric_listener_options.tls().is_some() {
+            return Err(InvalidConfigurationError::UnsupportedListenerTls(
+                "use 'networking.tls' to secure the node-to-node message fabric",
+            ));
+        }
+
         if self.common.node_name.is_none() {
             // If the node name is not set, we will fallback to use hostname as the node name.
             // So to avoid changing hostname to make data loss, we must validate the directory's entry.
@@ -487,6 +496,8 @@ pub enum InvalidConfigurationError {
     DeriveBindAddress(String),
     #[error("node-name is required: {0}")]
     RequiredNodeName(String),
+    #[error("'tls' is not supported in the common listener options: {0}")]
+    UnsupportedListenerTls(&'static str),
 }
 
 /// Migrates a single field from a deprecated config location to its new one.
@@ -9,6 +9,7 @@
 // by the Apache License, Version 2.0.
 
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use restate_util_bytecount::NonZeroByteCount;
@@ -103,6 +104,141 @@ pub struct NetworkingOptions {
         skip_serializing_if = "is_default_fabric_memory_limit"
     )]
     fabric_memory_limit: NonZeroByteCount,
+
+    /// # Fabric TLS
+    ///
+    /// Enables mutual TLS on the node-to-node message fabric and the metadata server gRPC
+    /// services. When set, every TCP connection to and from this node must present a
+    /// certificate signed by the configured cluster CA. Unix domain sockets are not affected.
+    ///
+    /// Default: unset (plaintext)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<FabricTlsOptions>,
+}
+
+/// # Fabric TLS options
+///
+/// Certificate material used to secure node-to-node connections with mutual TLS.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "FabricTlsOptions"))]
+#[serde(rename_all = "kebab-case")]
+pub struct FabricTlsOptions {
+    /// # Cluster CA certificate
+    ///
+    /// Path to a PEM file containing the certificate(s) of the cluster CA. Peer certificates
+    /// are verified against these roots.
+    pub ca_cert_path: PathBuf,
+
+    /// # Node certificate
+    ///
+    /// Path to a PEM file containing this node's certificate chain. The leaf certificate
+    /// must carry the node name as a DNS subject alternative name, since peers verify it
+    /// against the node identity announced during the handshake. It also needs to be valid
+    /// for the host (or IP address) of the node's advertised address.
+    pub cert_path: PathBuf,
+
+    /// # Node private key
+    ///
+    /// Path to a PEM file containing the private key of the node certificate.
+    pub key_path: PathBuf,
+
+    /// # Reload interval
+    ///
+    /// How often the certificate and key files are checked for changes. Updated files are
+    /// picked up by new connections without restarting the node.
+    ///
+    /// Default: `1m`
+    #[serde(default = "default_tls_reload_interval")]
+    pub reload_interval: NonZeroFriendlyDuration,
+}
+
+impl FabricTlsOptions {
+    pub fn new(
+        ca_cert_path: impl Into<PathBuf>,
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            ca_cert_path: ca_cert_path.into(),
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            reload_interval: default_tls_reload_interval(),
+        }
+    }
+}
+
+/// # Listener TLS options
+///
+/// Serves HTTPS on the TCP socket of a listener.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "ListenerTlsOptions"))]
+#[serde(rename_all = "kebab-case")]
+pub struct ListenerTlsOptions {
+    /// # Certificate
+    ///
+    /// Path to a PEM file containing the server certificate chain.
+    pub cert_path: PathBuf,
+
+    /// # Private key
+    ///
+    /// Path to a PEM file containing the private key of the server certificate.
+    pub key_path: PathBuf,
+
+    /// # Minimum TLS version
+    ///
+    /// The minimum TLS protocol version accepted from clients.
+    ///
+    /// Default: `1.2`
+    #[serde(default)]
+    pub min_version: TlsVersion,
+
+    /// # Client CA certificate
+    ///
+    /// Path to a PEM file containing CA certificate(s) to verify client certificates with. If
+    /// set, clients must present a certificate signed by one of these CAs.
+    ///
+    /// Default: unset (client certificates are not requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca_cert_path: Option<PathBuf>,
+
+    /// # Reload interval
+    ///
+    /// How often the certificate files are checked for changes. Updated files are picked up by
+    /// new connections without restarting the node.
+    ///
+    /// Default: `1m`
+    #[serde(default = "default_tls_reload_interval")]
+    pub reload_interval: NonZeroFriendlyDuration,
+}
+
+impl ListenerTlsOptions {
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            min_version: TlsVersion::default(),
+            client_ca_cert_path: None,
+            reload_interval: default_tls_reload_interval(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TlsVersion {
+    /// TLS 1.2
+    #[default]
+    #[serde(rename = "1.2")]
+    Tls12,
+    /// TLS 1.3
+    #[serde(rename = "1.3")]
+    Tls13,
+}
+
+fn default_tls_reload_interval() -> NonZeroFriendlyDuration {
+    NonZeroFriendlyDuration::from_secs_unchecked(60)
 }
 
 const fn default_message_size_limit() -> NonZeroByteCount {
@@ -159,6 +295,7 @@ impl Default for NetworkingOptions {
             ),
             message_size_limit: default_message_size_limit(),
             fabric_memory_limit: default_fabric_memory_limit(),
+            tls: None,
         }
     }
 }
@@ -167,6 +167,24 @@ pub struct WorkerOptions {
     /// Since v1.7.3
     #[cfg_attr(feature = "schemars", schemars(skip))]
     pub self_proposal_queue_memory_limit: NonZeroByteCount,
+
+    /// # Invocation events
+    ///
+    /// Delivery of the invocation lifecycle events emitted by the services and handlers which
+    /// opt in via the `restate.invocation-events` metadata. Partitions only start to emit events
+    /// once a leader with configured invocation events enabled them. A leader without this option
+    /// keeps the emitted events in the outbox until a leader with it takes over.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation_events: Option<InvocationEventsOptions>,
+
+    /// # Invocation archive
+    ///
+    /// Archive completed invocations to Parquet files on an object store before the cleaner
+    /// removes them at the end of their retention. The archived invocations can be queried
+    /// through the `sys_invocation_history` table. When unset, completed invocations are
+    /// removed without being archived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation_archive: Option<InvocationArchiveOptions>,
 }
 
 impl WorkerOptions {
@@ -233,10 +251,158 @@ impl Default for WorkerOptions {
             self_proposal_queue_memory_limit: NonZeroByteCount::new(
                 NonZeroUsize::new(64 * 1024 * 1024).expect("non zero"),
             ),
+            invocation_events: None,
+            invocation_archive: None,
         }
     }
 }
 
+/// # Invocation events options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct InvocationEventsOptions {
+    /// # Sink
+    ///
+    /// Where the events are delivered to.
+    pub sink: InvocationEventSinkOptions,
+
+    /// # Delivery retry policy
+    ///
+    /// Retry policy for delivering an event to the sink. If the retries are exhausted, the
+    /// delivery starts over after a pause of 10s.
+    ///
+    /// Default: unlimited exponential retries, from 100ms up to 10s.
+    #[serde(default = "InvocationEventsOptions::default_retry_policy")]
+    pub retry_policy: RetryPolicy,
+
+    /// # Concurrency limit
+    ///
+    /// Maximum number of events of this node which are delivered concurrently. Events which are
+    /// delivered concurrently can arrive out of order.
+    ///
+    /// Default: 64
+    #[serde(default = "InvocationEventsOptions::default_concurrency_limit")]
+    pub concurrency_limit: NonZeroUsize,
+
+    /// # Buffer size
+    ///
+    /// Maximum number of events of this node which are waiting to be delivered. Partition leaders
+    /// don't wait for the delivery of the events, so that a slow or unavailable sink doesn't hold
+    /// back the other messages of the outbox. Events which don't fit in the buffer are dropped.
+    ///
+    /// Default: 10000
+    #[serde(default = "InvocationEventsOptions::default_buffer_size")]
+    pub buffer_size: NonZeroUsize,
+}
+
+impl InvocationEventsOptions {
+    fn default_retry_policy() -> RetryPolicy {
+        RetryPolicy::exponential(
+            Duration::from_millis(100),
+            2.0,
+            None,
+            Some(Duration::from_secs(10)),
+        )
+    }
+
+    fn default_concurrency_limit() -> NonZeroUsize {
+        NonZeroUsize::new(64).expect("non zero")
+    }
+
+    fn default_buffer_size() -> NonZeroUsize {
+        NonZeroUsize::new(10_000).expect("non zero")
+    }
+}
+
+/// # Invocation events sink
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum InvocationEventSinkOptions {
+    /// Send every event as JSON in the body of a `POST` request. Any `2xx` response status
+    /// acknowledges the event.
+    #[serde(rename_all = "kebab-case")]
+    Webhook {
+        /// # URL
+        url: String,
+        /// # Headers
+        ///
+        /// Headers added to every request, e.g. for authentication.
+        #[serde(default, skip_serializing_if = "SerdeableHeaderHashMap::is_empty")]
+        headers: SerdeableHeaderHashMap,
+    },
+    /// Produce every event as JSON record to a Kafka topic. The record key is the invocation id,
+    /// so that all events of an invocation end up in the same topic partition.
+    #[serde(rename_all = "kebab-case")]
+    Kafka {
+        /// # Cluster
+        ///
+        /// Name of the Kafka cluster, as registered via the Admin API or configured in
+        /// `ingress.kafka-clusters`.
+        cluster: String,
+        /// # Topic
+        topic: String,
+    },
+}
+
+/// # Invocation archive options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub struct InvocationArchiveOptions {
+    /// # Archive destination URL
+    ///
+    /// Base URL of the archive, e.g. `s3://bucket/restate/archive`. Supports the `s3://`,
+    /// `gs://`, `az://` and `file://` protocol schemes. Invocations are written to
+    /// `<destination>/invocations/date=<completion date>/service=<service name>/`, journals to
+    /// `<destination>/journals/...` using the same layout.
+    pub destination: String,
+
+    /// # Archive journals
+    ///
+    /// Whether to archive the journals of the completed invocations as well. Journals are
+    /// archived when they are removed, i.e. at the end of the journal retention.
+    ///
+    /// Default: `false`
+    #[serde(default)]
+    pub include_journal: bool,
+
+    /// # Maximum invocations per file
+    ///
+    /// The expired invocations of a cleanup run are archived in chunks of at most this many
+    /// invocations. Every chunk is written to its own Parquet file before its invocations are
+    /// purged, which bounds the memory used for archiving.
+    ///
+    /// Default: 10000
+    #[serde(default = "InvocationArchiveOptions::default_max_invocations_per_file")]
+    pub max_invocations_per_file: NonZeroUsize,
+
+    #[serde(flatten)]
+    pub object_store: ObjectStoreOptions,
+
+    /// # Error retry policy
+    ///
+    /// A retry policy for dealing with retryable object store errors.
+    #[serde(default = "InvocationArchiveOptions::default_retry_policy")]
+    pub object_store_retry_policy: RetryPolicy,
+}
+
+impl InvocationArchiveOptions {
+    fn default_max_invocations_per_file() -> NonZeroUsize {
+        NonZeroUsize::new(10_000).expect("non zero")
+    }
+
+    fn default_retry_policy() -> RetryPolicy {
+        RetryPolicy::exponential(
+            Duration::from_millis(100),
+            2.,
+            Some(10),
+            Some(Duration::from_secs(10)),
+        )
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, Serialize, Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
@@ -182,6 +182,38 @@ pub enum PauseInvocationResponse {
     NotRunning,
 }
 
+/// Point-in-time progress of an invocation, as seen by the leader of its partition.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvocationProgress {
+    pub status: InvocationProgressStatus,
+    /// When the invocation is scheduled, the time it will start executing.
+    pub execution_time: Option<MillisSinceEpoch>,
+    /// Number of attempts started so far. Only known while the invocation is running.
+    pub attempts: Option<u32>,
+    /// Failure of the last attempt, if the invocation is being retried.
+    pub last_failure: Option<InvocationError>,
+    /// When the invocation is backing off, the time of the next attempt.
+    pub next_retry_at: Option<MillisSinceEpoch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InvocationProgressStatus {
+    Scheduled,
+    Inboxed,
+    Running,
+    /// The last attempt failed, the invocation is waiting for the next attempt.
+    BackingOff,
+    Suspended,
+    Paused,
+    Completed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GetInvocationProgressResponse {
+    NotFound,
+    Ready(InvocationProgress),
+}
+
 /// This trait provides the functionalities to interact with Restate invocations.
 pub trait InvocationClient {
     /// Append the invocation to the log, waiting for the PP to emit [`SubmittedInvocationNotification`] when the command is processed.
@@ -212,6 +244,13 @@ pub trait InvocationClient {
         invocation_query: InvocationQuery,
     ) -> impl Future<Output = Result<GetInvocationOutputResponse, InvocationClientError>> + Send;
 
+    /// Get the current progress of an invocation, without waiting for its completion.
+    fn get_invocation_progress(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_id: InvocationId,
+    ) -> impl Future<Output = Result<GetInvocationProgressResponse, InvocationClientError>> + Send;
+
     /// **DEPRECATED** Append [`InvocationResponse`] to an existing invocation journal. Only ServiceProtocol <= 3
     fn append_invocation_response(
         &self,
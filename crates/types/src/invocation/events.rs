@@ -0,0 +1,112 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Lifecycle events of invocations, delivered to external sinks.
+
+use enumset::{EnumSet, EnumSetType};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::InvocationError;
+use crate::identifiers::InvocationId;
+use crate::invocation::InvocationTarget;
+use crate::journal_events::EventType;
+use crate::journal_events::raw::RawEvent;
+use crate::time::MillisSinceEpoch;
+
+/// Type of an [`InvocationEvent`]. Services opt in to the types of events they want to emit.
+#[derive(Debug, Hash, EnumSetType, strum::Display, strum::EnumString, Serialize, Deserialize)]
+#[enumset(serialize_repr = "list")]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum InvocationEventType {
+    /// The invocation completed successfully.
+    Completed,
+    /// The invocation completed with a failure. This includes killed and cancelled invocations.
+    Failed,
+    /// The invocation suspended, waiting for a completion.
+    Suspended,
+    /// The invocation was paused, e.g. because it exhausted its retry attempts.
+    Paused,
+    /// An attempt of the invocation failed, the invocation will be retried.
+    TransientError,
+}
+
+impl InvocationEventType {
+    /// Parse a comma separated list of event types, e.g. `failed,paused`.
+    pub fn parse_list(value: &str) -> Result<EnumSet<Self>, strum::ParseError> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|ty| !ty.is_empty())
+            .map(str::parse::<Self>)
+            .collect()
+    }
+}
+
+/// Lifecycle event of an invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvocationEvent {
+    pub invocation_id: InvocationId,
+    pub invocation_target: InvocationTarget,
+    /// Creation time of the log record which caused the event.
+    pub timestamp: MillisSinceEpoch,
+    pub kind: InvocationEventKind,
+}
+
+impl InvocationEvent {
+    /// Returns `None` for journal events unknown to this Restate version.
+    pub fn event_type(&self) -> Option<InvocationEventType> {
+        self.kind.event_type()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InvocationEventKind {
+    Completed,
+    Failed(InvocationError),
+    /// Event recorded in the journal of the invocation, see [`crate::journal_events`].
+    JournalEvent(RawEvent),
+}
+
+impl InvocationEventKind {
+    /// Returns `None` for journal events unknown to this Restate version.
+    pub fn event_type(&self) -> Option<InvocationEventType> {
+        match self {
+            InvocationEventKind::Completed => Some(InvocationEventType::Completed),
+            InvocationEventKind::Failed(_) => Some(InvocationEventType::Failed),
+            InvocationEventKind::JournalEvent(event) => match event.ty() {
+                EventType::TransientError => Some(InvocationEventType::TransientError),
+                EventType::Paused => Some(InvocationEventType::Paused),
+                EventType::Suspended => Some(InvocationEventType::Suspended),
+                EventType::Unknown => None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_event_type_list() {
+        assert_eq!(
+            InvocationEventType::parse_list("failed, paused,,transient-error").unwrap(),
+            InvocationEventType::Failed
+                | InvocationEventType::Paused
+                | InvocationEventType::TransientError
+        );
+        assert_eq!(
+            InvocationEventType::parse_list("").unwrap(),
+            EnumSet::empty()
+        );
+        assert!(InvocationEventType::parse_list("failed,exploded").is_err());
+    }
+}
@@ -11,6 +11,7 @@
 //! This module contains all the core types representing a service invocation.
 
 pub mod client;
+pub mod events;
 
 use std::borrow::Cow;
 use std::hash::Hash;
@@ -20,7 +20,9 @@ use super::metadata::{
 use super::{LogId, Lsn};
 use crate::Version;
 use crate::config::Configuration;
+use crate::nodes_config::NodesConfiguration;
 use crate::replicated_loglet::ReplicatedLogletParams;
+use crate::replication::{NodeSet, NodeSetChecker, ReplicationProperty};
 
 pub type LogsHlcClock<C> = HlcClock<C, LocalStorage>;
 
@@ -50,6 +52,12 @@ pub enum BuilderError {
     SegmentConflict(Lsn),
     #[error(transparent)]
     HlcClock(#[from] ClockError),
+    #[error("log {0} has no replicated segment with index {1}")]
+    UnknownSegment(LogId, SegmentIndex),
+    #[error("segment {1} of log {0} is not sealed")]
+    SegmentNotSealed(LogId, SegmentIndex),
+    #[error("nodeset {2} of segment {1} of log {0} cannot satisfy replication {3}")]
+    InsufficientNodeset(LogId, SegmentIndex, NodeSet, ReplicationProperty),
 }
 
 impl<C: Clock> LogsBuilder<C> {
@@ -347,6 +355,65 @@ impl<C: Clock> ChainBuilder<'_, C> {
             }
         }
     }
+
+    /// Replaces `remove` with `add` in the nodeset of the sealed replicated segment with
+    /// `segment_index`. This is used after the records of the removed nodes were re-replicated to
+    /// the rest of the nodeset, including the added nodes, to make the loglet stop referencing
+    /// them.
+    ///
+    /// Fails if the resulting nodeset cannot satisfy the replication property of the segment.
+    /// Returns the resulting nodeset. The chain is not modified if the nodeset doesn't change.
+    pub fn replace_in_sealed_nodeset(
+        &mut self,
+        segment_index: SegmentIndex,
+        remove: &NodeSet,
+        add: &NodeSet,
+        nodes_config: &NodesConfiguration,
+    ) -> Result<NodeSet, BuilderError> {
+        let last_base_lsn = *self
+            .inner
+            .chain
+            .last_key_value()
+            .expect("chain have at least one segment")
+            .0;
+
+        let Some((base_lsn, config)) = self.inner.chain.iter_mut().find(|(_, config)| {
+            config.index() == segment_index && ProviderKind::Replicated == config.kind
+        }) else {
+            return Err(BuilderError::UnknownSegment(self.log_id, segment_index));
+        };
+
+        if *base_lsn == last_base_lsn {
+            // the tail segment is still open, its nodeset can only change by extending the chain.
+            return Err(BuilderError::SegmentNotSealed(self.log_id, segment_index));
+        }
+
+        let mut params = ReplicatedLogletParams::deserialize_from(config.params.as_bytes())?;
+        let mut nodeset = params.nodeset.clone();
+        nodeset.retain(|node_id| !remove.contains(*node_id));
+        nodeset.extend(add.iter().copied());
+        if nodeset == params.nodeset {
+            return Ok(nodeset);
+        }
+
+        let mut checker = NodeSetChecker::new(&nodeset, nodes_config, &params.replication);
+        checker.fill_with(true);
+        if !checker.check_write_quorum(|attr| *attr) {
+            return Err(BuilderError::InsufficientNodeset(
+                self.log_id,
+                segment_index,
+                nodeset,
+                params.replication,
+            ));
+        }
+
+        params.nodeset = nodeset.clone();
+        config.params = LogletParams::from(params.serialize()?);
+        self.lookup_index.update_replicated_loglet_params(params);
+        *self.modified = true;
+
+        Ok(nodeset)
+    }
 }
 
 impl<C: Clock> Deref for ChainBuilder<'_, C> {
@@ -976,4 +1043,114 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn replace_in_sealed_nodeset() -> googletest::Result<()> {
+        use crate::GenerationalNodeId;
+        use crate::logs::LogletId;
+        use crate::nodes_config::NodesConfiguration;
+        use crate::replication::ReplicationProperty;
+
+        // unknown nodes are considered provisioning, they count towards the replication
+        let nodes_config = NodesConfiguration::new_for_testing();
+
+        let mut builder = LogsBuilder::new(MockClock::new());
+
+        let loglet = |id: u64| ReplicatedLogletParams {
+            loglet_id: LogletId::from(id),
+            sequencer: GenerationalNodeId::new(1, 1),
+            replication: ReplicationProperty::new(NonZeroU8::new(2).unwrap()),
+            nodeset: NodeSet::from([1, 2, 3]),
+        };
+
+        // log-1 -> [replicated-loglet-1, replicated-loglet-2]
+        builder.add_log(
+            LogId::new(1),
+            Chain::new(
+                ProviderKind::Replicated,
+                LogletParams::from(loglet(1).serialize()?),
+            ),
+        )?;
+        let tail_index = builder.chain(LogId::new(1)).unwrap().append_segment(
+            Lsn::from(10),
+            ProviderKind::Replicated,
+            LogletParams::from(loglet(2).serialize()?),
+        )?;
+
+        let mut chain = builder.chain(LogId::new(1)).unwrap();
+        // the tail segment is still open
+        assert_that!(
+            chain.replace_in_sealed_nodeset(
+                tail_index,
+                &NodeSet::from([3]),
+                &NodeSet::default(),
+                &nodes_config
+            ),
+            err(pat!(BuilderError::SegmentNotSealed(
+                eq(LogId::new(1)),
+                eq(tail_index)
+            )))
+        );
+        assert_that!(
+            chain.replace_in_sealed_nodeset(
+                SegmentIndex(5),
+                &NodeSet::from([3]),
+                &NodeSet::default(),
+                &nodes_config
+            ),
+            err(pat!(BuilderError::UnknownSegment(
+                eq(LogId::new(1)),
+                eq(SegmentIndex(5))
+            )))
+        );
+
+        let nodeset = chain.replace_in_sealed_nodeset(
+            SegmentIndex::OLDEST,
+            &NodeSet::from([3]),
+            &NodeSet::from([4]),
+            &nodes_config,
+        )?;
+        assert_that!(nodeset, eq(NodeSet::from([1, 2, 4])));
+
+        let head = ReplicatedLogletParams::deserialize_from(chain.head().config.params.as_bytes())?;
+        assert_that!(head.nodeset, eq(NodeSet::from([1, 2, 4])));
+        let tail = ReplicatedLogletParams::deserialize_from(chain.tail().config.params.as_bytes())?;
+        assert_that!(tail.nodeset, eq(NodeSet::from([1, 2, 3])));
+
+        // replacing it again is a no-op
+        let nodeset = chain.replace_in_sealed_nodeset(
+            SegmentIndex::OLDEST,
+            &NodeSet::from([3]),
+            &NodeSet::from([4]),
+            &nodes_config,
+        )?;
+        assert_that!(nodeset, eq(NodeSet::from([1, 2, 4])));
+
+        // the remaining nodeset must be able to satisfy the replication property
+        assert_that!(
+            chain.replace_in_sealed_nodeset(
+                SegmentIndex::OLDEST,
+                &NodeSet::from([1, 2]),
+                &NodeSet::default(),
+                &nodes_config
+            ),
+            err(pat!(BuilderError::InsufficientNodeset(
+                eq(LogId::new(1)),
+                eq(SegmentIndex::OLDEST),
+                eq(NodeSet::from([4])),
+                anything()
+            )))
+        );
+
+        // the lookup index reflects the new nodeset
+        let found = builder
+            .inner
+            .lookup_index
+            .get_replicated_loglet(&LogletId::from(1))
+            .unwrap();
+        assert_that!(found.params.nodeset, eq(NodeSet::from([1, 2, 4])));
+        assert_that!(found.references.len(), eq(1));
+
+        Ok(())
+    }
 }
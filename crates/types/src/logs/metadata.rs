@@ -117,6 +117,14 @@ impl LookupIndex {
         }
     }
 
+    /// Replaces the params of an already indexed replicated loglet. This is a no-op if the loglet
+    /// is not referenced by any segment.
+    pub fn update_replicated_loglet_params(&mut self, params: ReplicatedLogletParams) {
+        if let Some(loglet_ref) = self.replicated_loglets.get_mut(&params.loglet_id) {
+            loglet_ref.params = params;
+        }
+    }
+
     pub fn get_replicated_loglet(
         &self,
         loglet_id: &LogletId,
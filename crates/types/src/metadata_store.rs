@@ -22,6 +22,9 @@ pub mod keys {
     /// Cluster-global rule book (limiter rules).
     /// *Since v1.7.0*
     pub static RULE_BOOK_KEY: ByteString = ByteString::from_static("rule_book");
+    /// Summaries of the most recently finished bulk invocation operations.
+    /// *Since v1.8.0*
+    pub static BULK_OPERATIONS_KEY: ByteString = ByteString::from_static("bulk_operations");
     // end todo
 
     pub static PARTITION_PROCESSOR_EPOCH_PREFIX: &str = "pp_epoch";
@@ -411,6 +411,22 @@ impl<P: ListenerPort> AdvertisedAddress<P> {
         }
     }
 
+    /// Switches an `http://` address to `https://`. Other addresses are returned unchanged.
+    pub fn with_https_scheme(self) -> Self {
+        let inner = match self.inner {
+            PeerNetAddress::Http(uri) if uri.scheme() == Some(&http::uri::Scheme::HTTP) => {
+                let mut parts = uri.into_parts();
+                parts.scheme = Some(http::uri::Scheme::HTTPS);
+                PeerNetAddress::Http(Uri::from_parts(parts).expect("valid uri"))
+            }
+            inner => inner,
+        };
+        Self {
+            inner,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
     pub fn with_node_base_dir(dir: &Path) -> Self {
         Self {
             inner: PeerNetAddress::Uds(dir.join(P::UDS_NAME)),
@@ -613,6 +629,22 @@ mod tests {
         assert_eq!(addr.inner.port(), 0);
     }
 
+    #[test]
+    fn switch_advertised_address_to_https() {
+        let address = "http://localhost:8080"
+            .parse::<AdvertisedAddress<HttpIngressPort>>()
+            .unwrap()
+            .with_https_scheme();
+        assert_eq!(address.to_string(), "https://localhost:8080/");
+
+        // already secure
+        let address = "https://localhost"
+            .parse::<AdvertisedAddress<HttpIngressPort>>()
+            .unwrap()
+            .with_https_scheme();
+        assert_eq!(address.to_string(), "https://localhost/");
+    }
+
     #[test]
     fn parse_bind_address() {
         let input = "127.0.0.1:8080";
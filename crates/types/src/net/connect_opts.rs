@@ -12,7 +12,7 @@ use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::config::{MetadataClientOptions, NetworkingOptions};
+use crate::config::{FabricTlsOptions, MetadataClientOptions, NetworkingOptions};
 
 /// Overhead added to user-facing max_message_size.
 ///
@@ -33,6 +33,10 @@ pub trait CommonClientConnectionOptions: GrpcConnectionOptions {
     fn keep_alive_interval(&self) -> Duration;
     fn keep_alive_timeout(&self) -> Duration;
     fn http2_adaptive_window(&self) -> bool;
+    /// Mutual TLS settings to use for TCP connections. Connections are plaintext if unset.
+    fn tls(&self) -> Option<&FabricTlsOptions> {
+        None
+    }
 }
 
 impl<T: GrpcConnectionOptions> GrpcConnectionOptions for &T {
@@ -64,6 +68,10 @@ where
     fn http2_adaptive_window(&self) -> bool {
         (*self).http2_adaptive_window()
     }
+
+    fn tls(&self) -> Option<&FabricTlsOptions> {
+        (*self).tls()
+    }
 }
 
 impl<T> GrpcConnectionOptions for Arc<T>
@@ -98,6 +106,10 @@ where
     fn http2_adaptive_window(&self) -> bool {
         (**self).http2_adaptive_window()
     }
+
+    fn tls(&self) -> Option<&FabricTlsOptions> {
+        (**self).tls()
+    }
 }
 
 impl GrpcConnectionOptions for NetworkingOptions {
@@ -129,6 +141,10 @@ impl CommonClientConnectionOptions for NetworkingOptions {
     fn http2_adaptive_window(&self) -> bool {
         self.http2_adaptive_window
     }
+
+    fn tls(&self) -> Option<&FabricTlsOptions> {
+        self.tls.as_ref()
+    }
 }
 
 impl GrpcConnectionOptions for MetadataClientOptions {
@@ -157,4 +173,8 @@ impl CommonClientConnectionOptions for MetadataClientOptions {
     fn http2_adaptive_window(&self) -> bool {
         true
     }
+
+    fn tls(&self) -> Option<&FabricTlsOptions> {
+        self.tls.as_ref()
+    }
 }
@@ -834,6 +834,10 @@ pub struct GetDigest {
     // inclusive
     pub from_offset: LogletOffset,
     pub to_offset: LogletOffset,
+    /// If set, the node verifies the checksum of every record in the range and reports records
+    /// that fail verification as [`RecordStatus::Corrupted`]. Nodes that don't support this
+    /// ignore the flag and report all records as existing.
+    pub verify_checksums: bool,
 }
 
 #[derive(
@@ -878,6 +882,10 @@ pub enum RecordStatus {
     Archived = 2,
     #[display("X")]
     Exists = 3,
+    /// The record exists but failed checksum verification. Only reported if requested via
+    /// [`GetDigest::verify_checksums`].
+    #[display("C")]
+    Corrupted = 4,
 }
 
 impl DigestEntry {
@@ -18,9 +18,9 @@ use crate::identifiers::{
     PartitionProcessorRpcRequestId, WithPartitionKey,
 };
 use crate::invocation::client::{
-    CancelInvocationResponse, InvocationOutput, KillInvocationResponse, PatchDeploymentId,
-    PauseInvocationResponse, PurgeInvocationResponse, RestartAsNewInvocationResponse,
-    ResumeInvocationResponse, SubmittedInvocationNotification,
+    CancelInvocationResponse, InvocationOutput, InvocationProgress, KillInvocationResponse,
+    PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
+    RestartAsNewInvocationResponse, ResumeInvocationResponse, SubmittedInvocationNotification,
 };
 use crate::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
 use crate::journal_v2::Signal;
@@ -141,6 +141,10 @@ pub enum PartitionProcessorRpcRequestInner {
     PauseInvocation {
         invocation_id: InvocationId,
     },
+    // *Since v1.7*, older leaders fail decoding it
+    GetInvocationProgress {
+        invocation_id: InvocationId,
+    },
 }
 
 impl WithPartitionKey for PartitionProcessorRpcRequestInner {
@@ -171,6 +175,9 @@ impl WithPartitionKey for PartitionProcessorRpcRequestInner {
             PartitionProcessorRpcRequestInner::PauseInvocation { invocation_id } => {
                 invocation_id.partition_key()
             }
+            PartitionProcessorRpcRequestInner::GetInvocationProgress { invocation_id } => {
+                invocation_id.partition_key()
+            }
         }
     }
 }
@@ -539,4 +546,5 @@ pub enum PartitionProcessorRpcResponse {
     RestartAsNewInvocation(RestartAsNewInvocationRpcResponse),
     ResumeInvocation(ResumeInvocationRpcResponse),
     PauseInvocation(PauseInvocationRpcResponse),
+    InvocationProgress(InvocationProgress),
 }
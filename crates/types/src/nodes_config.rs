@@ -610,6 +610,23 @@ pub enum StorageState {
     /// - new node sets: **excluded**
     ReadOnly,
 
+    // [authoritative]
+    /// The log-server is being decommissioned. Like `ReadOnly`, it's excluded from new nodesets
+    /// but still serves reads and accepts writes on existing ones. On top of that, the cluster
+    /// controller moves the node's data away: open loglets are reconfigured to nodesets without
+    /// it, and the records of sealed loglets are re-replicated onto the remaining members of
+    /// their nodesets before the node is removed from them. Once no nodeset references the node
+    /// anymore, it's transitioned to `Disabled`.
+    ///
+    /// It's persisted as `ReadOnly` with a separate draining flag (see [`LogServerConfig`]) so
+    /// that nodes running older versions can still read the nodes configuration.
+    ///
+    /// - should read from: **yes**
+    /// - can write to: **yes**
+    /// - should write to: **no**
+    /// - new node sets: **excluded**
+    Draining,
+
     // [authoritative]
     /// Gone is logically equivalent to ReadOnly but it signifies that the node has been
     /// permanently lost along with all the data it might have had.
@@ -644,14 +661,14 @@ impl StorageState {
         use StorageState::*;
         match self {
             Provisioning | Disabled | Gone | DataLoss => false,
-            ReadWrite | ReadOnly => true,
+            ReadWrite | ReadOnly | Draining => true,
         }
     }
 
     pub fn should_write_to(&self) -> bool {
         use StorageState::*;
         match self {
-            Provisioning | Disabled | ReadOnly | Gone | DataLoss => false,
+            Provisioning | Disabled | ReadOnly | Draining | Gone | DataLoss => false,
             ReadWrite => true,
         }
     }
@@ -660,7 +677,7 @@ impl StorageState {
     pub fn can_read_from(&self) -> bool {
         use StorageState::*;
         match self {
-            Provisioning | ReadOnly | Gone | ReadWrite | DataLoss => true,
+            Provisioning | ReadOnly | Draining | Gone | ReadWrite | DataLoss => true,
             Disabled => false,
         }
     }
@@ -669,7 +686,7 @@ impl StorageState {
         use StorageState::*;
         match self {
             DataLoss => false,
-            Disabled | Provisioning | ReadOnly | Gone | ReadWrite => true,
+            Disabled | Provisioning | ReadOnly | Draining | Gone | ReadWrite => true,
         }
     }
 
@@ -738,10 +755,48 @@ pub enum WorkerState {
 }
 
 #[derive(Clone, Default, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "LogServerConfigShadow", into = "LogServerConfigShadow")]
 pub struct LogServerConfig {
     pub storage_state: StorageState,
 }
 
+/// Serialized form of [`LogServerConfig`].
+///
+/// [`StorageState::Draining`] is stored as `ReadOnly` plus the `draining` flag. Nodes that don't
+/// know about draining can still decode the nodes configuration and treat the node as
+/// `ReadOnly`, which restricts it the same way except for moving its data away.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LogServerConfigShadow {
+    storage_state: StorageState,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    draining: bool,
+}
+
+impl From<LogServerConfig> for LogServerConfigShadow {
+    fn from(value: LogServerConfig) -> Self {
+        match value.storage_state {
+            StorageState::Draining => Self {
+                storage_state: StorageState::ReadOnly,
+                draining: true,
+            },
+            storage_state => Self {
+                storage_state,
+                draining: false,
+            },
+        }
+    }
+}
+
+impl From<LogServerConfigShadow> for LogServerConfig {
+    fn from(value: LogServerConfigShadow) -> Self {
+        let storage_state = match value.storage_state {
+            StorageState::ReadOnly if value.draining => StorageState::Draining,
+            storage_state => storage_state,
+        };
+        Self { storage_state }
+    }
+}
+
 #[derive(Clone, Default, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct MetadataServerConfig {
     pub metadata_server_state: MetadataServerState,
@@ -913,4 +968,32 @@ mod tests {
         // really make sure we have removed it from the name lookup table
         assert!(!config.name_lookup.contains_key("node1"));
     }
+
+    #[test]
+    fn draining_is_encoded_as_read_only() {
+        /// The log-server config as known by versions without draining
+        #[derive(serde::Deserialize)]
+        struct OldLogServerConfig {
+            storage_state: StorageState,
+        }
+
+        let draining = LogServerConfig {
+            storage_state: StorageState::Draining,
+        };
+        let encoded = flexbuffers::to_vec(&draining).unwrap();
+
+        let decoded: LogServerConfig = flexbuffers::from_slice(&encoded).unwrap();
+        assert_eq!(draining, decoded);
+        let old: OldLogServerConfig = flexbuffers::from_slice(&encoded).unwrap();
+        assert_eq!(StorageState::ReadOnly, old.storage_state);
+
+        for storage_state in [StorageState::ReadOnly, StorageState::ReadWrite] {
+            let config = LogServerConfig { storage_state };
+            let encoded = flexbuffers::to_vec(&config).unwrap();
+            let old: OldLogServerConfig = flexbuffers::from_slice(&encoded).unwrap();
+            assert_eq!(storage_state, old.storage_state);
+            let decoded: LogServerConfig = flexbuffers::from_slice(&encoded).unwrap();
+            assert_eq!(config, decoded);
+        }
+    }
 }
@@ -16,7 +16,9 @@ use crate::storage::{
     StorageCodecKind, StorageDecode, StorageDecodeError, StorageEncode, StorageEncodeError, decode,
     encode,
 };
-use crate::{RESTATE_VERSION_1_6_0, RESTATE_VERSION_1_7_0, SemanticRestateVersion};
+use crate::{
+    RESTATE_VERSION_1_6_0, RESTATE_VERSION_1_7_0, RESTATE_VERSION_1_7_3, SemanticRestateVersion,
+};
 
 /// A change to the set of state-machine features enabled on a partition.
 ///
@@ -58,6 +60,11 @@ pub enum PartitionFeatureChange {
     ///
     /// *Since v1.7.0*
     EnableUniqueRandomSeeds = 3,
+    /// Enqueue invocation lifecycle events into the outbox. Older leaders can't decode the
+    /// corresponding outbox messages.
+    ///
+    /// *Since v1.7.3*
+    EnableInvocationEvents = 4,
 }
 
 impl PartitionFeatureChange {
@@ -74,6 +81,7 @@ impl PartitionFeatureChange {
             Self::EnableJournalV2 => &RESTATE_VERSION_1_6_0,
             Self::EnableVqueues => &RESTATE_VERSION_1_7_0,
             Self::EnableUniqueRandomSeeds => &RESTATE_VERSION_1_7_0,
+            Self::EnableInvocationEvents => &RESTATE_VERSION_1_7_3,
         }
     }
 
@@ -86,6 +94,9 @@ impl PartitionFeatureChange {
             Self::EnableUniqueRandomSeeds => {
                 !std::mem::replace(&mut features.unique_random_seeds, true)
             }
+            Self::EnableInvocationEvents => {
+                !std::mem::replace(&mut features.invocation_events, true)
+            }
         }
     }
 }
@@ -130,6 +141,11 @@ pub struct PersistedFeatures {
     /// *Since v1.7.0*
     #[bilrost(tag(3))]
     pub unique_random_seeds: bool,
+    /// Invocation lifecycle events are enqueued into the outbox.
+    ///
+    /// *Since v1.7.3*
+    #[bilrost(tag(4))]
+    pub invocation_events: bool,
 }
 
 impl PersistedFeatures {
@@ -141,6 +157,7 @@ impl PersistedFeatures {
             self.journal_v2.then_some("journal_v2"),
             self.vqueues.then_some("vqueues"),
             self.unique_random_seeds.then_some("unique_random_seeds"),
+            self.invocation_events.then_some("invocation_events"),
         ]
         .into_iter()
         .flatten()
@@ -204,6 +221,23 @@ mod tests {
         PartitionFeatureChange::EnableUniqueRandomSeeds.apply_to(&mut features);
         assert!(features.vqueues);
         assert!(features.unique_random_seeds);
+
+        assert!(!features.invocation_events);
+        assert!(PartitionFeatureChange::EnableInvocationEvents.apply_to(&mut features));
+        assert!(features.invocation_events);
+        assert!(!PartitionFeatureChange::EnableInvocationEvents.apply_to(&mut features));
+    }
+
+    #[test]
+    fn invocation_events_require_1_7_3() {
+        let change = PartitionFeatureChange::EnableInvocationEvents;
+        assert_eq!(PartitionFeatureChange::from_repr(4), Some(change));
+        assert!(
+            change
+                .min_required_version()
+                .is_equal_or_newer_than(&RESTATE_VERSION_1_7_0)
+        );
+        assert!(!RESTATE_VERSION_1_7_0.is_equal_or_newer_than(change.min_required_version()));
     }
 
     #[test]
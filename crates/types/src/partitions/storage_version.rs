@@ -52,6 +52,16 @@ pub enum StorageVersion {
     /// Gated by `experimental_enable_migrate_scoped_tables`.
     /// Since v1.7.0
     ScopedStateAndPromise = 2,
+    /// Migrations:
+    /// * state values are tagged with their storage codec, so that sealed values are told
+    ///   apart from plaintext ones
+    ///
+    /// Required to encrypt state values at rest, and only migrated to if encryption at rest is
+    /// configured. Includes the migrations of `ScopedStateAndPromise`. Partition stores at this
+    /// version can't be opened by older versions, so configuring encryption at rest rules out
+    /// downgrades below v1.7.3, even if encryption is disabled again later on.
+    /// Since v1.7.3
+    TaggedStateValues = 3,
 }
 
 impl StorageVersion {
@@ -62,6 +72,12 @@ impl StorageVersion {
     pub fn is_scope_migrated(self) -> bool {
         self >= StorageVersion::ScopedStateAndPromise
     }
+
+    /// Returns `true` once state values start with a storage codec tag. Before, state values
+    /// are stored as the plain user bytes and are never sealed.
+    pub fn has_tagged_state_values(self) -> bool {
+        self >= StorageVersion::TaggedStateValues
+    }
 }
 
 /// Error returned when a discriminant cannot be mapped to a known
@@ -92,6 +108,7 @@ mod tests {
             StorageVersion::None,
             StorageVersion::V1_5,
             StorageVersion::ScopedStateAndPromise,
+            StorageVersion::TaggedStateValues,
         ] {
             assert_eq!(StorageVersion::try_from(known as u16).unwrap(), known);
         }
@@ -110,5 +127,13 @@ mod tests {
         assert!(!StorageVersion::None.is_scope_migrated());
         assert!(!StorageVersion::V1_5.is_scope_migrated());
         assert!(StorageVersion::ScopedStateAndPromise.is_scope_migrated());
+        assert!(StorageVersion::TaggedStateValues.is_scope_migrated());
+    }
+
+    #[test]
+    fn has_tagged_state_values_only_for_tagged_state_values() {
+        assert!(!StorageVersion::V1_5.has_tagged_state_values());
+        assert!(!StorageVersion::ScopedStateAndPromise.has_tagged_state_values());
+        assert!(StorageVersion::TaggedStateValues.has_tagged_state_values());
     }
 }
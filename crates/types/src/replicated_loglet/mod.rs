@@ -45,6 +45,7 @@ pub fn logserver_candidate_filter(_node_id: PlainNodeId, config: &NodeConfig) ->
         StorageState::Provisioning
         | StorageState::Disabled
         | StorageState::ReadOnly
+        | StorageState::Draining
         | StorageState::Gone
         | StorageState::DataLoss => false,
     }
@@ -22,7 +22,7 @@ use crate::config::Configuration;
 use crate::deployment::{
     DeploymentAddress, Headers, HttpDeploymentAddress, LambdaDeploymentAddress,
 };
-use crate::identifiers::{DeploymentId, LambdaARN, ServiceRevision};
+use crate::identifiers::{DeploymentId, InvocationId, LambdaARN, ServiceRevision};
 use crate::schema::info::SchemaInfo;
 use crate::schema::service::ServiceMetadata;
 use crate::time::MillisSinceEpoch;
@@ -59,9 +59,93 @@ pub struct Deployment {
     ///
     /// List of configuration/deprecation information related to this deployment.
     pub info: Vec<SchemaInfo>,
+    /// Set when the deployment is draining, see [`DeploymentDrain`].
+    pub drain: Option<DeploymentDrain>,
+}
+
+/// A draining deployment doesn't serve the latest revision of any service, hence it doesn't
+/// accept new invocations, and waits for the invocations pinned to it to complete.
+///
+/// The number of pinned invocations is not part of the schema, it's computed on demand.
+///
+/// Since v1.7.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentDrain {
+    /// When the deployment started draining.
+    pub started_at: MillisSinceEpoch,
+    /// Whether to remove the deployment once no in-flight invocations are pinned to it anymore.
+    #[serde(default)]
+    pub auto_remove: bool,
+    /// When the drain checks observed that no in-flight invocations are pinned to the deployment
+    /// anymore. `None` while the deployment is still draining.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drained_at: Option<MillisSinceEpoch>,
+}
+
+impl DeploymentDrain {
+    /// Whether the drain checks observed no pinned in-flight invocations.
+    pub fn is_drained(&self) -> bool {
+        self.drained_at.is_some()
+    }
+}
+
+/// Weighted routing of the new invocations of a service between deployments serving it.
+///
+/// When a service has a traffic split, new invocations are routed to one of the deployments of
+/// the split, proportionally to their weights, instead of the deployment serving the latest
+/// revision of the service. Deployments registered after the split was set don't receive any
+/// traffic until they're added to the split.
+///
+/// Since v1.7.3
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficSplit {
+    pub weights: Vec<DeploymentWeight>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentWeight {
+    pub deployment_id: DeploymentId,
+    pub weight: u32,
+}
+
+impl TrafficSplit {
+    pub fn total_weight(&self) -> u64 {
+        self.weights.iter().map(|w| u64::from(w.weight)).sum()
+    }
+
+    pub fn weight_of(&self, deployment_id: &DeploymentId) -> u32 {
+        self.weights
+            .iter()
+            .filter(|w| &w.deployment_id == deployment_id)
+            .map(|w| w.weight)
+            .sum()
+    }
+
+    /// Picks a deployment proportionally to the weights. The same `routing_key` always picks
+    /// the same deployment, as long as the weights don't change.
+    pub fn pick(&self, routing_key: u64) -> Option<DeploymentId> {
+        let total_weight = self.total_weight();
+        if total_weight == 0 {
+            return None;
+        }
+
+        let mut point = routing_key % total_weight;
+        for weight in &self.weights {
+            let weight_value = u64::from(weight.weight);
+            if point < weight_value {
+                return Some(weight.deployment_id);
+            }
+            point -= weight_value;
+        }
+        None
+    }
 }
 
 impl Deployment {
+    pub fn is_draining(&self) -> bool {
+        self.drain.is_some()
+    }
+
     pub fn as_address(&self) -> DeploymentAddress {
         self.ty.as_address()
     }
@@ -233,6 +317,18 @@ pub trait DeploymentResolver {
         service_name: impl AsRef<str>,
     ) -> Option<Deployment>;
 
+    /// Resolves the deployment a new invocation is pinned to, taking the [`TrafficSplit`] of the
+    /// service into account. Falls back to the deployment serving the latest revision of the
+    /// service.
+    fn resolve_deployment_for_new_invocation(
+        &self,
+        service_name: impl AsRef<str>,
+        _handler_name: impl AsRef<str>,
+        _invocation_id: &InvocationId,
+    ) -> Option<Deployment> {
+        self.resolve_latest_deployment_for_service(service_name)
+    }
+
     fn find_deployment(
         &self,
         deployment_address: &DeploymentAddress,
@@ -257,6 +353,15 @@ impl<T: DeploymentResolver> DeploymentResolver for &T {
         (**self).resolve_latest_deployment_for_service(service_name)
     }
 
+    fn resolve_deployment_for_new_invocation(
+        &self,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+        invocation_id: &InvocationId,
+    ) -> Option<Deployment> {
+        (**self).resolve_deployment_for_new_invocation(service_name, handler_name, invocation_id)
+    }
+
     fn find_deployment(
         &self,
         deployment_address: &DeploymentAddress,
@@ -289,6 +394,15 @@ impl<T: DeploymentResolver> DeploymentResolver for Arc<T> {
         (**self).resolve_latest_deployment_for_service(service_name)
     }
 
+    fn resolve_deployment_for_new_invocation(
+        &self,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+        invocation_id: &InvocationId,
+    ) -> Option<Deployment> {
+        (**self).resolve_deployment_for_new_invocation(service_name, handler_name, invocation_id)
+    }
+
     fn find_deployment(
         &self,
         deployment_address: &DeploymentAddress,
@@ -531,6 +645,7 @@ pub mod test_util {
                 metadata: Default::default(),
                 additional_headers: Default::default(),
                 info: vec![],
+                drain: None,
             }
         }
 
@@ -552,6 +667,7 @@ pub mod test_util {
                 metadata: Default::default(),
                 additional_headers: Default::default(),
                 info: vec![],
+                drain: None,
             }
         }
     }
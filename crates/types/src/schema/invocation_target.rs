@@ -12,22 +12,56 @@ use std::str::FromStr;
 use std::time::Duration;
 use std::{cmp, fmt};
 
+use base64::Engine;
 use bytes::Bytes;
 use bytestring::ByteString;
+use enumset::EnumSet;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use restate_util_bytecount::ByteCount;
+use restate_util_time::NonZeroFriendlyDuration;
 
 use crate::identifiers::DeploymentId;
+use crate::invocation::events::InvocationEventType;
 use crate::invocation::{
-    InvocationRetention, InvocationTargetType, ServiceType, WorkflowHandlerType,
+    InvocationRetention, InvocationTargetType, ServiceType, VirtualObjectHandlerType,
+    WorkflowHandlerType,
 };
 use crate::retries::RetryIter;
 
 pub const DEFAULT_IDEMPOTENCY_RETENTION: Duration = Duration::from_secs(60 * 60 * 24);
 pub const DEFAULT_WORKFLOW_COMPLETION_RETENTION: Duration = Duration::from_secs(60 * 60 * 24);
 
+/// Handler metadata key marking a handler as pure, that is its output depends only on its input.
+/// The value is the duration for which responses are cached, e.g. `5 minutes`.
+///
+/// Only supported for service handlers and shared virtual object handlers.
+pub const RESPONSE_CACHE_TTL_METADATA_KEY: &str = "restate.response-cache-ttl";
+
+/// Prefix of the idempotency keys derived from the request input for cached handlers.
+///
+/// This prefix is reserved, user provided idempotency keys must not start with it.
+pub const RESPONSE_CACHE_IDEMPOTENCY_KEY_PREFIX: &str = "restate-response-cache:";
+
+/// Service or handler metadata key setting the ratio of invocations that are traced, between `0`
+/// and `1`. The handler metadata takes precedence over the service metadata.
+///
+/// Only applies to invocations that don't continue the trace of their caller.
+pub const TRACING_SAMPLE_RATIO_METADATA_KEY: &str = "restate.tracing.sample-ratio";
+
+/// Service or handler metadata key which, when set to `true`, emits a failure marker span for
+/// failed invocations that were not sampled. The marker is the root of its own trace, the other
+/// spans of the invocation are not recorded. The handler metadata takes precedence over the
+/// service metadata.
+pub const TRACING_FAILURE_MARKERS_METADATA_KEY: &str = "restate.tracing.failure-markers";
+
+/// Service or handler metadata key listing the types of invocation lifecycle events to emit,
+/// separated by commas, e.g. `failed,paused`. The handler metadata takes precedence over the
+/// service metadata. See [`InvocationEventType`] for the available types.
+pub const INVOCATION_EVENTS_METADATA_KEY: &str = "restate.invocation-events";
+
 /// This API resolves invocation targets.
 ///
 /// This is used by invoker and ingress to resolve metadata required to ingest an invocation and run it.
@@ -97,6 +131,99 @@ pub struct InvocationTargetMetadata {
     pub output_rules: OutputRules,
 
     pub deployment_status: DeploymentStatus,
+
+    /// If set, the handler is pure and its responses can be cached for this duration.
+    /// See [`RESPONSE_CACHE_TTL_METADATA_KEY`].
+    pub response_cache_ttl: Option<Duration>,
+
+    pub tracing_sampling: TracingSampling,
+
+    /// Types of lifecycle events emitted by the invocations of this target.
+    /// See [`INVOCATION_EVENTS_METADATA_KEY`].
+    pub invocation_events: EnumSet<InvocationEventType>,
+}
+
+/// Tracing sampling configured in the service and handler metadata.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TracingSampling {
+    /// Ratio of the invocations to trace. See [`TRACING_SAMPLE_RATIO_METADATA_KEY`].
+    pub sample_ratio: Option<f64>,
+    /// See [`TRACING_FAILURE_MARKERS_METADATA_KEY`].
+    pub failure_markers: bool,
+}
+
+impl TracingSampling {
+    /// Resolve the sampling from the service and handler metadata, ignoring invalid values.
+    pub(crate) fn resolve(
+        service_metadata: &std::collections::HashMap<String, String>,
+        handler_metadata: &std::collections::HashMap<String, String>,
+    ) -> Self {
+        Self {
+            sample_ratio: parse_tracing_sample_ratio(handler_metadata)
+                .ok()
+                .flatten()
+                .or_else(|| parse_tracing_sample_ratio(service_metadata).ok().flatten()),
+            failure_markers: parse_tracing_failure_markers(handler_metadata)
+                .ok()
+                .flatten()
+                .or_else(|| {
+                    parse_tracing_failure_markers(service_metadata)
+                        .ok()
+                        .flatten()
+                })
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Parse the tracing sample ratio configured in the service or handler metadata, if any.
+///
+/// Returns an error message if the ratio is configured, but invalid.
+pub(crate) fn parse_tracing_sample_ratio(
+    metadata: &std::collections::HashMap<String, String>,
+) -> Result<Option<f64>, String> {
+    let Some(value) = metadata.get(TRACING_SAMPLE_RATIO_METADATA_KEY) else {
+        return Ok(None);
+    };
+    match value.trim().parse::<f64>() {
+        Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(Some(ratio)),
+        _ => Err(format!(
+            "The metadata '{TRACING_SAMPLE_RATIO_METADATA_KEY}' is ignored, because '{value}' is not a number between 0 and 1."
+        )),
+    }
+}
+
+/// Parse the failure-markers flag configured in the service or handler metadata, if any.
+///
+/// Returns an error message if the flag is configured, but invalid.
+pub(crate) fn parse_tracing_failure_markers(
+    metadata: &std::collections::HashMap<String, String>,
+) -> Result<Option<bool>, String> {
+    let Some(value) = metadata.get(TRACING_FAILURE_MARKERS_METADATA_KEY) else {
+        return Ok(None);
+    };
+    value.trim().parse::<bool>().map(Some).map_err(|_| {
+        format!(
+            "The metadata '{TRACING_FAILURE_MARKERS_METADATA_KEY}' is ignored, because '{value}' is neither 'true' nor 'false'."
+        )
+    })
+}
+
+/// Parse the invocation event types configured in the service or handler metadata, if any.
+///
+/// Returns an error message if the event types are configured, but invalid.
+pub(crate) fn parse_invocation_events(
+    metadata: &std::collections::HashMap<String, String>,
+) -> Result<Option<EnumSet<InvocationEventType>>, String> {
+    let Some(value) = metadata.get(INVOCATION_EVENTS_METADATA_KEY) else {
+        return Ok(None);
+    };
+    InvocationEventType::parse_list(value).map(Some).map_err(|_| {
+        format!(
+            "The metadata '{INVOCATION_EVENTS_METADATA_KEY}' is ignored, because '{value}' is not a list of invocation event types. Supported types are: {}.",
+            EnumSet::<InvocationEventType>::all().iter().join(", ")
+        )
+    })
 }
 
 impl InvocationTargetMetadata {
@@ -120,6 +247,64 @@ impl InvocationTargetMetadata {
             _ => InvocationRetention::none(),
         }
     }
+
+    /// Retention to use for the invocations whose response is cached, `None` if the response of this target cannot be cached.
+    ///
+    /// The completion is retained for the cache TTL, so that identical requests get the same response until it expires.
+    pub fn compute_response_cache_retention(&self) -> Option<InvocationRetention> {
+        self.response_cache_ttl.map(|ttl| InvocationRetention {
+            completion_retention: ttl,
+            journal_retention: cmp::min(self.journal_retention, ttl),
+        })
+    }
+}
+
+/// Derive the idempotency key identifying the cached response for the given input.
+///
+/// Together with the invocation target, this determines the invocation id,
+/// so requests with the same target and input are deduplicated to the same invocation.
+pub fn response_cache_idempotency_key(input: &[u8]) -> ByteString {
+    let digest = Sha256::digest(input);
+    format!(
+        "{RESPONSE_CACHE_IDEMPOTENCY_KEY_PREFIX}{}",
+        restate_base64_util::URL_SAFE.encode(digest)
+    )
+    .into()
+}
+
+/// Returns true if the idempotency key is reserved for the response cache,
+/// and thus cannot be provided by users.
+pub fn is_reserved_idempotency_key(idempotency_key: &str) -> bool {
+    idempotency_key.starts_with(RESPONSE_CACHE_IDEMPOTENCY_KEY_PREFIX)
+}
+
+/// Parse the response cache TTL configured in the handler metadata, if any.
+///
+/// Returns an error message if the TTL is configured, but invalid for this handler.
+pub(crate) fn parse_response_cache_ttl(
+    target_ty: InvocationTargetType,
+    metadata: &std::collections::HashMap<String, String>,
+) -> Result<Option<Duration>, String> {
+    let Some(value) = metadata.get(RESPONSE_CACHE_TTL_METADATA_KEY) else {
+        return Ok(None);
+    };
+    if !matches!(
+        target_ty,
+        InvocationTargetType::Service
+            | InvocationTargetType::VirtualObject(VirtualObjectHandlerType::Shared)
+    ) {
+        return Err(format!(
+            "The handler metadata '{RESPONSE_CACHE_TTL_METADATA_KEY}' is ignored, because response caching is supported only for service handlers and shared virtual object handlers."
+        ));
+    }
+    value
+        .parse::<NonZeroFriendlyDuration>()
+        .map(|ttl| Some(ttl.to_std()))
+        .map_err(|e| {
+            format!(
+                "The handler metadata '{RESPONSE_CACHE_TTL_METADATA_KEY}' is ignored, because '{value}' is not a valid duration: {e}"
+            )
+        })
 }
 
 #[derive(Debug, Eq, PartialEq, Default)]
@@ -594,6 +779,9 @@ pub mod test_util {
                 input_rules: Default::default(),
                 output_rules: Default::default(),
                 deployment_status: DeploymentStatus::Enabled,
+                response_cache_ttl: None,
+                tracing_sampling: Default::default(),
+                invocation_events: Default::default(),
             }
         }
     }
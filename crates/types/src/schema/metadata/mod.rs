@@ -32,18 +32,21 @@ use crate::config::{Configuration, InvocationRetryPolicyOptions};
 use crate::deployment::{
     DeploymentAddress, Headers, HttpDeploymentAddress, LambdaDeploymentAddress,
 };
-use crate::identifiers::{DeploymentId, SubscriptionId};
+use crate::identifiers::{DeploymentId, InvocationId, SubscriptionId};
 use crate::invocation::{InvocationTargetType, ServiceType, WorkflowHandlerType};
 use crate::live::Pinned;
 use crate::metadata::GlobalMetadata;
 use crate::net::address::{AdvertisedAddress, HttpIngressPort};
 use crate::net::metadata::{MetadataContainer, MetadataKind};
 use crate::retries::{RetryIter, RetryPolicy};
-use crate::schema::deployment::{DeploymentResolver, DeploymentType, ProtocolType};
+use crate::schema::deployment::{
+    DeploymentDrain, DeploymentResolver, DeploymentType, ProtocolType, TrafficSplit,
+};
 use crate::schema::info::SchemaInfo;
+use crate::schema::invocation_target;
 use crate::schema::invocation_target::{
     DeploymentStatus, InputRules, InvocationAttemptOptions, InvocationTargetMetadata,
-    InvocationTargetResolver, OnMaxAttempts, OutputRules,
+    InvocationTargetResolver, OnMaxAttempts, OutputRules, TracingSampling,
 };
 use crate::schema::kafka::{
     DUPLICATED_KAFKA_CLUSTER_INFO_MESSAGE, KafkaCluster, KafkaClusterResolver,
@@ -74,6 +77,8 @@ pub struct Schema {
     active_service_revisions: HashMap<String, ActiveServiceRevision>,
     subscriptions: HashMap<SubscriptionId, Subscription>,
     kafka_clusters: HashMap<String, KafkaCluster>,
+    /// Traffic splits by service name
+    traffic_splits: HashMap<String, TrafficSplit>,
 
     // If legacy is true, it means the schema raw data is
     // still using v1 schema model. Schema should be migrated.
@@ -88,6 +93,7 @@ impl Default for Schema {
             deployments: HashMap::default(),
             subscriptions: HashMap::default(),
             kafka_clusters: HashMap::default(),
+            traffic_splits: HashMap::default(),
             legacy_v1: false,
         }
     }
@@ -105,6 +111,10 @@ impl Schema {
     pub fn touch(&mut self) {
         self.version = self.version.next();
     }
+
+    pub fn traffic_split(&self, service_name: &str) -> Option<&TrafficSplit> {
+        self.traffic_splits.get(service_name)
+    }
 }
 
 impl GlobalMetadata for Schema {
@@ -218,6 +228,10 @@ struct Deployment {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     limits: Option<DeploymentLimits>,
+
+    /// Since v1.7.3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    drain: Option<DeploymentDrain>,
 }
 
 impl MapAsVecItem for Deployment {
@@ -239,6 +253,7 @@ impl Deployment {
             metadata: self.metadata.clone(),
             additional_headers: self.delivery_options.additional_headers.clone(),
             info: vec![],
+            drain: self.drain,
         }
     }
     /// This returns true if the two deployments are to be considered the "same".
@@ -446,6 +461,8 @@ impl ServiceRevision {
             ))
         }
 
+        push_metadata_options_info(&self.metadata, &mut info);
+
         let workflow_completion_retention = if self.ty == ServiceType::Workflow {
             let requested = self
                 .handlers
@@ -590,6 +607,20 @@ struct Handler {
     retry_policy_on_max_attempts: Option<OnMaxAttempts>,
 }
 
+/// Report invalid tracing sampling and invocation events values in the service or handler
+/// metadata.
+fn push_metadata_options_info(metadata: &HashMap<String, String>, info: &mut Vec<SchemaInfo>) {
+    if let Err(message) = invocation_target::parse_tracing_sample_ratio(metadata) {
+        info.push(SchemaInfo::new(message))
+    }
+    if let Err(message) = invocation_target::parse_tracing_failure_markers(metadata) {
+        info.push(SchemaInfo::new(message))
+    }
+    if let Err(message) = invocation_target::parse_invocation_events(metadata) {
+        info.push(SchemaInfo::new(message))
+    }
+}
+
 impl MapAsVecItem for Handler {
     type Key = String;
 
@@ -637,6 +668,13 @@ impl Handler {
             ))
         }
 
+        if let Err(message) =
+            invocation_target::parse_response_cache_ttl(self.target_ty, &self.metadata)
+        {
+            info.push(SchemaInfo::new(message))
+        }
+        push_metadata_options_info(&self.metadata, &mut info);
+
         service::HandlerMetadata {
             name: self.name.clone(),
             ty: self.target_ty.into(),
@@ -683,6 +721,29 @@ impl DeploymentResolver for Schema {
             .map(|dp| dp.to_deployment())
     }
 
+    fn resolve_deployment_for_new_invocation(
+        &self,
+        service_name: impl AsRef<str>,
+        handler_name: impl AsRef<str>,
+        invocation_id: &InvocationId,
+    ) -> Option<deployment::Deployment> {
+        let service_name = service_name.as_ref();
+        if let Some(traffic_split) = self.traffic_splits.get(service_name)
+            && let Some(deployment_id) =
+                traffic_split.pick(u128::from(invocation_id.invocation_uuid()) as u64)
+            && let Some(deployment) = self.deployments.get(&deployment_id)
+            // The handler might not exist in the revision served by the picked deployment
+            && deployment
+                .services
+                .get(service_name)
+                .is_some_and(|service| service.handlers.contains_key(handler_name.as_ref()))
+        {
+            return Some(deployment.to_deployment());
+        }
+
+        self.resolve_latest_deployment_for_service(service_name)
+    }
+
     fn find_deployment(
         &self,
         deployment_address: &DeploymentAddress,
@@ -828,6 +889,13 @@ impl InvocationTargetResolver for Schema {
             // But let's not panic yet, this will fail later on.
             .unwrap_or_default();
 
+        // The completion of cached responses is retained for the TTL, so it's clamped like the idempotency retention.
+        let response_cache_ttl =
+            invocation_target::parse_response_cache_ttl(handler.target_ty, &handler.metadata)
+                .ok()
+                .flatten()
+                .and_then(|ttl| configuration.clamp_idempotency_retention(Some(ttl)).0);
+
         Some(InvocationTargetMetadata {
             public: handler.public.unwrap_or(service_revision.public),
             completion_retention,
@@ -836,6 +904,20 @@ impl InvocationTargetResolver for Schema {
             input_rules: handler.input_rules.clone(),
             output_rules: handler.output_rules.clone(),
             deployment_status,
+            response_cache_ttl,
+            tracing_sampling: TracingSampling::resolve(
+                &service_revision.metadata,
+                &handler.metadata,
+            ),
+            invocation_events: invocation_target::parse_invocation_events(&handler.metadata)
+                .ok()
+                .flatten()
+                .or_else(|| {
+                    invocation_target::parse_invocation_events(&service_revision.metadata)
+                        .ok()
+                        .flatten()
+                })
+                .unwrap_or_default(),
         })
     }
 
@@ -390,6 +390,10 @@ pub struct Schema {
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     #[serde_as(as = "restate_serde_util::MapAsVec")]
     kafka_clusters: HashMap<String, KafkaCluster>,
+
+    /// Since v1.7.3
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    traffic_splits: HashMap<String, TrafficSplit>,
 }
 
 impl restate_serde_util::MapAsVecItem for KafkaCluster {
@@ -407,6 +411,7 @@ impl From<super::Schema> for Schema {
             deployments,
             subscriptions,
             kafka_clusters,
+            traffic_splits,
             ..
         }: super::Schema,
     ) -> Self {
@@ -417,6 +422,7 @@ impl From<super::Schema> for Schema {
             version,
             subscriptions,
             kafka_clusters,
+            traffic_splits,
         }
     }
 }
@@ -430,6 +436,7 @@ impl From<Schema> for super::Schema {
             version,
             subscriptions,
             kafka_clusters,
+            traffic_splits,
         }: Schema,
     ) -> Self {
         if let Some(deployments_v2) = deployments_v2 {
@@ -442,6 +449,7 @@ impl From<Schema> for super::Schema {
                     .collect(),
                 subscriptions,
                 kafka_clusters,
+                traffic_splits,
                 legacy_v1: false,
             }
         } else if let (Some(services), Some(deployments)) = (services, deployments) {
@@ -460,6 +468,7 @@ impl From<Schema> for super::Schema {
                     .collect(),
                 subscriptions,
                 kafka_clusters,
+                traffic_splits,
                 legacy_v1: true,
             }
         } else {
@@ -571,6 +580,7 @@ mod conversions {
                     metadata: Default::default(),
                     services: v2_services,
                     limits: None,
+                    drain: None,
                 };
                 v2_deployments.push(v2_deployment);
             }
@@ -851,6 +861,7 @@ mod conversions {
                         created_at: MillisSinceEpoch::now(),
                         metadata: Default::default(),
                         limits: None,
+                        drain: None,
                         services: HashMap::from([
                             (
                                 "Greeter".to_owned(),
@@ -989,6 +1000,7 @@ mod conversions {
                         created_at: MillisSinceEpoch::now(),
                         metadata: Default::default(),
                         limits: None,
+                        drain: None,
                         services: HashMap::from([(
                             "Greeter".to_owned(),
                             Arc::new(ServiceRevision {
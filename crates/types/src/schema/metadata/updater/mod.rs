@@ -22,10 +22,10 @@ use crate::invocation::{
     InvocationTargetType, ServiceType, VirtualObjectHandlerType, WorkflowHandlerType,
 };
 use crate::schema::Redaction;
-use crate::schema::deployment::DeploymentType;
+use crate::schema::deployment::{DeploymentDrain, DeploymentType, DeploymentWeight, TrafficSplit};
 use crate::schema::invocation_target::{
     BadInputContentType, InputRules, InputValidationRule, OnMaxAttempts, OutputContentTypeRule,
-    OutputRules,
+    OutputRules, TRACING_FAILURE_MARKERS_METADATA_KEY, TRACING_SAMPLE_RATIO_METADATA_KEY,
 };
 use crate::schema::kafka::{KafkaClusterName, KafkaClusterResolver};
 use crate::schema::registry::{DeploymentConnectionParameters, DiscoveryResponse};
@@ -34,9 +34,10 @@ use crate::time::MillisSinceEpoch;
 use crate::{deployment, endpoint_manifest, identifiers};
 use bilrost::encoding::Collection;
 use http::{HeaderValue, Uri};
+use itertools::Itertools;
 use serde_json::Value;
-use std::collections::HashMap;
 use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::num::NonZeroUsize;
 use std::ops::{Deref, Not, RangeInclusive};
@@ -147,6 +148,17 @@ pub(in crate::schema) enum ServiceError {
     #[error("modifying retention time for service type {0} is unsupported")]
     #[code(unknown)]
     CannotModifyRetentionTime(ServiceType),
+    #[error(
+        "the traffic split of service '{0}' must assign a positive weight to at least one deployment"
+    )]
+    #[code(unknown)]
+    EmptyTrafficSplit(String),
+    #[error("the traffic split of service '{0}' contains the deployment '{1}' more than once")]
+    #[code(unknown)]
+    DuplicateTrafficSplitDeployment(String, DeploymentId),
+    #[error("the deployment '{1}' of the traffic split doesn't serve the service '{0}'")]
+    #[code(unknown)]
+    TrafficSplitDeploymentNotServingService(String, DeploymentId),
 }
 
 #[derive(Debug, thiserror::Error, codederror::CodedError)]
@@ -214,6 +226,16 @@ pub(in crate::schema) enum DeploymentError {
     )]
     #[code(restate_errors::META0016)]
     DifferentSupportedProtocolVersions(RangeInclusive<i32>, RangeInclusive<i32>),
+    #[error(
+        "cannot drain deployment '{0}' as it serves the latest revision of the services {1:?}. Register a new deployment for these services first"
+    )]
+    #[code(unknown)]
+    ServesLatestRevision(DeploymentId, Vec<String>),
+    #[error(
+        "cannot drain deployment '{0}' as it receives traffic of the services {1:?} through a traffic split. Remove it from the traffic splits first"
+    )]
+    #[code(unknown)]
+    ReceivesSplitTraffic(DeploymentId, Vec<String>),
 }
 
 /// Behavior when service type changes during update
@@ -274,6 +296,10 @@ pub struct ModifyServiceRequest {
     pub workflow_completion_retention: Option<Duration>,
     pub inactivity_timeout: Option<Duration>,
     pub abort_timeout: Option<Duration>,
+    /// Sets the [`TRACING_SAMPLE_RATIO_METADATA_KEY`] service metadata.
+    pub tracing_sample_ratio: Option<f64>,
+    /// Sets the [`TRACING_FAILURE_MARKERS_METADATA_KEY`] service metadata.
+    pub tracing_failure_markers: Option<bool>,
 }
 
 /// Responsible for updating the provided [`Schema`] with new
@@ -446,6 +472,7 @@ impl SchemaUpdater {
                 metadata,
                 services: computed_services,
                 limits: None,
+                drain: None,
             },
         );
 
@@ -693,6 +720,7 @@ impl SchemaUpdater {
                     // discovery_response once it has it, for now
                     // we keep original value.
                     limits: existing_deployment.limits,
+                    drain: existing_deployment.drain,
                 },
             );
 
@@ -798,6 +826,7 @@ impl SchemaUpdater {
                     // discovery_response once it has it, for now
                     // we keep original value.
                     limits: existing_deployment.limits,
+                    drain: existing_deployment.drain,
                 },
             );
 
@@ -827,11 +856,229 @@ impl SchemaUpdater {
                 }
             }
             self.mark_updated();
+            self.reactivate_draining_deployments_serving_latest_revisions();
+            self.remove_deployment_from_traffic_splits(deployment_id);
             return true;
         }
         false
     }
 
+    /// Marks the deployment as draining. Draining deployments must not serve the latest revision
+    /// of any service, so that no new invocations are routed to them.
+    pub(in crate::schema) fn drain_deployment(
+        &mut self,
+        deployment_id: DeploymentId,
+        auto_remove: bool,
+    ) -> Result<(), SchemaError> {
+        let mut serving_latest_revision: Vec<_> = self
+            .schema
+            .active_service_revisions
+            .iter()
+            .filter(|(_, revision)| revision.deployment_id == deployment_id)
+            .map(|(service_name, _)| service_name.clone())
+            .collect();
+
+        let Some(deployment) = self.schema.deployments.get_mut(&deployment_id) else {
+            return Err(SchemaError::NotFound(format!(
+                "deployment with id '{deployment_id}'"
+            )));
+        };
+
+        if !serving_latest_revision.is_empty() {
+            serving_latest_revision.sort();
+            return Err(DeploymentError::ServesLatestRevision(
+                deployment_id,
+                serving_latest_revision,
+            )
+            .into());
+        }
+
+        let mut receiving_split_traffic: Vec<_> = self
+            .schema
+            .traffic_splits
+            .iter()
+            .filter(|(_, traffic_split)| traffic_split.weight_of(&deployment_id) > 0)
+            .map(|(service_name, _)| service_name.clone())
+            .collect();
+        if !receiving_split_traffic.is_empty() {
+            receiving_split_traffic.sort();
+            return Err(DeploymentError::ReceivesSplitTraffic(
+                deployment_id,
+                receiving_split_traffic,
+            )
+            .into());
+        }
+
+        match &mut deployment.drain {
+            Some(drain) if drain.auto_remove == auto_remove => return Ok(()),
+            Some(drain) => drain.auto_remove = auto_remove,
+            None => {
+                info!(
+                    restate.deployment.id = %deployment_id,
+                    restate.deployment.address = %deployment.ty.address_display(),
+                    "Deployment started draining"
+                );
+                deployment.drain = Some(DeploymentDrain {
+                    started_at: MillisSinceEpoch::now(),
+                    auto_remove,
+                    drained_at: None,
+                });
+            }
+        }
+
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Marks a draining deployment as drained, once no in-flight invocations are pinned to it
+    /// anymore.
+    pub(in crate::schema) fn mark_deployment_drained(
+        &mut self,
+        deployment_id: DeploymentId,
+    ) -> Result<(), SchemaError> {
+        let Some(drain) = self
+            .schema
+            .deployments
+            .get_mut(&deployment_id)
+            .and_then(|deployment| deployment.drain.as_mut())
+        else {
+            return Err(SchemaError::NotFound(format!(
+                "draining deployment with id '{deployment_id}'"
+            )));
+        };
+
+        if drain.drained_at.is_none() {
+            drain.drained_at = Some(MillisSinceEpoch::now());
+            self.modified = true;
+        }
+        Ok(())
+    }
+
+    /// Removing a deployment can turn an older, draining deployment into the one serving the
+    /// latest revision of a service. Such deployments receive new invocations again, hence they
+    /// stop draining.
+    fn reactivate_draining_deployments_serving_latest_revisions(&mut self) {
+        for revision in self.schema.active_service_revisions.values() {
+            if let Some(deployment) = self.schema.deployments.get_mut(&revision.deployment_id)
+                && deployment.drain.take().is_some()
+            {
+                warn!(
+                    restate.deployment.id = %deployment.id,
+                    rpc.service = %revision.service_revision.name,
+                    "Draining deployment serves the latest revision of the service again after a deployment removal, it stopped draining"
+                );
+            }
+        }
+    }
+
+    /// Sets the traffic split of the service, replacing the previous one. Draining deployments
+    /// receiving traffic through the split stop draining.
+    pub(in crate::schema) fn set_traffic_split(
+        &mut self,
+        service_name: &str,
+        traffic_split: TrafficSplit,
+    ) -> Result<(), SchemaError> {
+        if !self
+            .schema
+            .active_service_revisions
+            .contains_key(service_name)
+        {
+            return Err(SchemaError::NotFound(format!(
+                "service with name '{service_name}'"
+            )));
+        }
+        if traffic_split.total_weight() == 0 {
+            return Err(ServiceError::EmptyTrafficSplit(service_name.to_owned()).into());
+        }
+
+        let mut seen = HashSet::with_capacity(traffic_split.weights.len());
+        for DeploymentWeight { deployment_id, .. } in &traffic_split.weights {
+            if !seen.insert(*deployment_id) {
+                return Err(ServiceError::DuplicateTrafficSplitDeployment(
+                    service_name.to_owned(),
+                    *deployment_id,
+                )
+                .into());
+            }
+            if !self
+                .schema
+                .deployments
+                .get(deployment_id)
+                .is_some_and(|deployment| deployment.services.contains_key(service_name))
+            {
+                return Err(ServiceError::TrafficSplitDeploymentNotServingService(
+                    service_name.to_owned(),
+                    *deployment_id,
+                )
+                .into());
+            }
+        }
+
+        for DeploymentWeight {
+            deployment_id,
+            weight,
+        } in &traffic_split.weights
+        {
+            if *weight > 0
+                && let Some(deployment) = self.schema.deployments.get_mut(deployment_id)
+                && deployment.drain.take().is_some()
+            {
+                warn!(
+                    restate.deployment.id = %deployment_id,
+                    rpc.service = %service_name,
+                    "Draining deployment receives traffic through the traffic split of the service, it stopped draining"
+                );
+            }
+        }
+
+        info!(
+            rpc.service = %service_name,
+            "Traffic split set to {}",
+            traffic_split
+                .weights
+                .iter()
+                .map(|w| format!("{}={}", w.deployment_id, w.weight))
+                .join(", ")
+        );
+        self.schema
+            .traffic_splits
+            .insert(service_name.to_owned(), traffic_split);
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Returns true if the service had a traffic split.
+    pub(in crate::schema) fn remove_traffic_split(&mut self, service_name: &str) -> bool {
+        if self.schema.traffic_splits.remove(service_name).is_some() {
+            self.modified = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes the deployment from all the traffic splits. Traffic splits left without any
+    /// weight are removed, so that the service is routed to its latest revision again.
+    fn remove_deployment_from_traffic_splits(&mut self, deployment_id: DeploymentId) {
+        let active_service_revisions = &self.schema.active_service_revisions;
+        self.schema
+            .traffic_splits
+            .retain(|service_name, traffic_split| {
+                traffic_split
+                    .weights
+                    .retain(|w| w.deployment_id != deployment_id);
+                let keep = traffic_split.total_weight() > 0
+                    && active_service_revisions.contains_key(service_name);
+                if !keep {
+                    warn!(
+                        rpc.service = %service_name,
+                        "Traffic split removed after the removal of deployment {deployment_id}, new invocations are routed to the latest revision of the service"
+                    );
+                }
+                keep
+            });
+    }
+
     pub(in crate::schema) fn add_subscription(
         &mut self,
         source: Uri,
@@ -1159,6 +1406,18 @@ impl SchemaUpdater {
             if let Some(new_abort_timeout) = modify_service_request.abort_timeout {
                 svc.abort_timeout = Some(new_abort_timeout);
             }
+            if let Some(new_sample_ratio) = modify_service_request.tracing_sample_ratio {
+                svc.metadata.insert(
+                    TRACING_SAMPLE_RATIO_METADATA_KEY.to_owned(),
+                    new_sample_ratio.to_string(),
+                );
+            }
+            if let Some(new_failure_markers) = modify_service_request.tracing_failure_markers {
+                svc.metadata.insert(
+                    TRACING_FAILURE_MARKERS_METADATA_KEY.to_owned(),
+                    new_failure_markers.to_string(),
+                );
+            }
             Ok(())
         })?;
 
@@ -9,9 +9,11 @@
 // by the Apache License, Version 2.0.
 
 use super::*;
+use std::collections::HashSet;
 use std::convert::Infallible;
 
 use crate::Versioned;
+use crate::identifiers::InvocationId;
 use crate::schema::deployment::DeploymentResolver;
 use crate::schema::deployment::ProtocolType;
 use crate::schema::info::SchemaInfo;
@@ -859,6 +861,286 @@ fn register_two_deployments_then_remove_second() {
     assert!(schemas.get_deployment(&deployment_id_2).is_none());
 }
 
+fn register_two_greeter_deployments() -> (DeploymentId, DeploymentId, Schema) {
+    let ((_, deployment_id_1), schemas) =
+        SchemaUpdater::update_and_return(Schema::default(), |updater| {
+            updater.add_deployment(AddDeploymentRequest {
+                deployment_address: DeploymentAddress::mock_uri("http://localhost:9080"),
+                ..add_deployment_request(vec![greeter_service()])
+            })
+        })
+        .unwrap();
+
+    let ((_, deployment_id_2), schemas) = SchemaUpdater::update_and_return(schemas, |updater| {
+        updater.add_deployment(AddDeploymentRequest {
+            deployment_address: DeploymentAddress::mock_uri("http://localhost:9081"),
+            ..add_deployment_request(vec![greeter_service()])
+        })
+    })
+    .unwrap();
+
+    (deployment_id_1, deployment_id_2, schemas)
+}
+
+#[test]
+fn drain_superseded_deployment() {
+    let (deployment_id_1, deployment_id_2, schemas) = register_two_greeter_deployments();
+
+    let version_before_drain = schemas.version();
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.drain_deployment(deployment_id_1, true)
+    })
+    .unwrap();
+    assert!(version_before_drain < schemas.version());
+
+    let drain = schemas
+        .get_deployment(&deployment_id_1)
+        .unwrap()
+        .drain
+        .unwrap();
+    assert!(drain.auto_remove);
+    assert_eq!(drain.drained_at, None);
+    assert!(
+        schemas
+            .get_deployment(&deployment_id_2)
+            .unwrap()
+            .drain
+            .is_none()
+    );
+    schemas.assert_service_deployment(GREETER_SERVICE_NAME, deployment_id_2);
+
+    // draining again is idempotent
+    let version_before_drain = schemas.version();
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.drain_deployment(deployment_id_1, true)
+    })
+    .unwrap();
+    assert_eq!(version_before_drain, schemas.version());
+
+    // the drained transition is only recorded once
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.mark_deployment_drained(deployment_id_1)
+    })
+    .unwrap();
+    let version_after_drained = schemas.version();
+    assert!(version_before_drain < version_after_drained);
+    assert!(
+        schemas
+            .get_deployment(&deployment_id_1)
+            .unwrap()
+            .drain
+            .unwrap()
+            .is_drained()
+    );
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.mark_deployment_drained(deployment_id_1)
+    })
+    .unwrap();
+    assert_eq!(version_after_drained, schemas.version());
+}
+
+#[test]
+fn drain_deployment_serving_latest_revision_fails() {
+    let (_, deployment_id_2, schemas) = register_two_greeter_deployments();
+
+    let result = SchemaUpdater::update(schemas, |updater| {
+        updater.drain_deployment(deployment_id_2, false)
+    });
+    let Err(SchemaError::Deployment(DeploymentError::ServesLatestRevision(_, services))) = &result
+    else {
+        panic!("unexpected result {result:?}");
+    };
+    assert_eq!(services, &vec![GREETER_SERVICE_NAME.to_owned()]);
+}
+
+#[test]
+fn removing_latest_deployment_stops_draining_the_previous_one() {
+    let (deployment_id_1, deployment_id_2, schemas) = register_two_greeter_deployments();
+
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.drain_deployment(deployment_id_1, true)
+    })
+    .unwrap();
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        assert!(updater.remove_deployment(deployment_id_2));
+        Ok::<(), Infallible>(())
+    })
+    .unwrap();
+
+    schemas.assert_service_deployment(GREETER_SERVICE_NAME, deployment_id_1);
+    assert!(
+        schemas
+            .get_deployment(&deployment_id_1)
+            .unwrap()
+            .drain
+            .is_none()
+    );
+}
+
+fn traffic_split(weights: &[(DeploymentId, u32)]) -> TrafficSplit {
+    TrafficSplit {
+        weights: weights
+            .iter()
+            .map(|(deployment_id, weight)| DeploymentWeight {
+                deployment_id: *deployment_id,
+                weight: *weight,
+            })
+            .collect(),
+    }
+}
+
+fn routed_deployments(schemas: &Schema, handler_name: &str) -> HashSet<DeploymentId> {
+    (0..100)
+        .filter_map(|_| {
+            schemas.resolve_deployment_for_new_invocation(
+                GREETER_SERVICE_NAME,
+                handler_name,
+                &InvocationId::mock_random(),
+            )
+        })
+        .map(|deployment| deployment.id)
+        .collect()
+}
+
+#[test]
+fn traffic_split_routes_new_invocations_by_weight() {
+    let (deployment_id_1, deployment_id_2, schemas) = register_two_greeter_deployments();
+    assert_eq!(
+        routed_deployments(&schemas, GREET_HANDLER_NAME),
+        HashSet::from([deployment_id_2])
+    );
+
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.set_traffic_split(
+            GREETER_SERVICE_NAME,
+            traffic_split(&[(deployment_id_1, 100), (deployment_id_2, 0)]),
+        )
+    })
+    .unwrap();
+    assert_eq!(
+        routed_deployments(&schemas, GREET_HANDLER_NAME),
+        HashSet::from([deployment_id_1])
+    );
+    // the latest revision is still the one of the newest deployment
+    schemas.assert_service_deployment(GREETER_SERVICE_NAME, deployment_id_2);
+
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.set_traffic_split(
+            GREETER_SERVICE_NAME,
+            traffic_split(&[(deployment_id_1, 50), (deployment_id_2, 50)]),
+        )
+    })
+    .unwrap();
+    assert_eq!(
+        routed_deployments(&schemas, GREET_HANDLER_NAME),
+        HashSet::from([deployment_id_1, deployment_id_2])
+    );
+
+    // the same invocation is always routed to the same deployment
+    let invocation_id = InvocationId::mock_random();
+    let routed_to = schemas
+        .resolve_deployment_for_new_invocation(
+            GREETER_SERVICE_NAME,
+            GREET_HANDLER_NAME,
+            &invocation_id,
+        )
+        .unwrap()
+        .id;
+    for _ in 0..10 {
+        assert_eq!(
+            schemas
+                .resolve_deployment_for_new_invocation(
+                    GREETER_SERVICE_NAME,
+                    GREET_HANDLER_NAME,
+                    &invocation_id,
+                )
+                .unwrap()
+                .id,
+            routed_to
+        );
+    }
+
+    // handlers unknown to the picked deployment fall back to the latest revision
+    assert_eq!(
+        routed_deployments(&schemas, "unknown"),
+        HashSet::from([deployment_id_2])
+    );
+
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        assert!(updater.remove_traffic_split(GREETER_SERVICE_NAME));
+        Ok::<(), Infallible>(())
+    })
+    .unwrap();
+    assert_eq!(
+        routed_deployments(&schemas, GREET_HANDLER_NAME),
+        HashSet::from([deployment_id_2])
+    );
+}
+
+#[test]
+fn invalid_traffic_split_is_rejected() {
+    let (deployment_id_1, deployment_id_2, schemas) = register_two_greeter_deployments();
+
+    let mut updater = SchemaUpdater::new(schemas);
+    assert!(let &SchemaError::NotFound(_) = updater.set_traffic_split(
+        ANOTHER_GREETER_SERVICE_NAME,
+        traffic_split(&[(deployment_id_1, 1)])
+    ).unwrap_err());
+    assert!(let &SchemaError::Service(ServiceError::EmptyTrafficSplit(_)) = updater.set_traffic_split(
+        GREETER_SERVICE_NAME,
+        traffic_split(&[(deployment_id_1, 0), (deployment_id_2, 0)])
+    ).unwrap_err());
+    assert!(let &SchemaError::Service(ServiceError::DuplicateTrafficSplitDeployment(_, _)) = updater.set_traffic_split(
+        GREETER_SERVICE_NAME,
+        traffic_split(&[(deployment_id_1, 1), (deployment_id_1, 1)])
+    ).unwrap_err());
+    assert!(let &SchemaError::Service(ServiceError::TrafficSplitDeploymentNotServingService(_, _)) = updater.set_traffic_split(
+        GREETER_SERVICE_NAME,
+        traffic_split(&[(DeploymentId::new(), 1)])
+    ).unwrap_err());
+    assert!(!updater.modified);
+}
+
+#[test]
+fn traffic_split_interacts_with_drain_and_removal() {
+    let (deployment_id_1, deployment_id_2, schemas) = register_two_greeter_deployments();
+
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.drain_deployment(deployment_id_1, true)
+    })
+    .unwrap();
+
+    // rolling back to a draining deployment stops draining it
+    let schemas = SchemaUpdater::update(schemas, |updater| {
+        updater.set_traffic_split(
+            GREETER_SERVICE_NAME,
+            traffic_split(&[(deployment_id_1, 90), (deployment_id_2, 10)]),
+        )
+    })
+    .unwrap();
+    assert!(
+        schemas
+            .get_deployment(&deployment_id_1)
+            .unwrap()
+            .drain
+            .is_none()
+    );
+
+    let mut updater = SchemaUpdater::new(schemas);
+    assert!(let &SchemaError::Deployment(DeploymentError::ReceivesSplitTraffic(_, _)) = updater.drain_deployment(deployment_id_1, true).unwrap_err());
+
+    // removing a deployment removes it from the split
+    assert!(updater.remove_deployment(deployment_id_2));
+    assert_eq!(
+        updater.schema.traffic_split(GREETER_SERVICE_NAME),
+        Some(&traffic_split(&[(deployment_id_1, 90)]))
+    );
+
+    // removing the last deployment with a weight removes the split
+    assert!(updater.remove_deployment(deployment_id_1));
+    assert_eq!(updater.schema.traffic_split(GREETER_SERVICE_NAME), None);
+}
+
 #[test]
 fn update_latest_deployment() {
     let mut updater = SchemaUpdater::default();
@@ -1308,8 +1590,14 @@ mod endpoint_manifest_options_propagation {
 
     use crate::config::Configuration;
     use crate::invocation::InvocationRetention;
-    use crate::schema::invocation_target::{InvocationAttemptOptions, InvocationTargetMetadata};
+    use crate::invocation::events::InvocationEventType;
+    use crate::schema::invocation_target::{
+        INVOCATION_EVENTS_METADATA_KEY, InvocationAttemptOptions, InvocationTargetMetadata,
+        RESPONSE_CACHE_TTL_METADATA_KEY, TRACING_FAILURE_MARKERS_METADATA_KEY,
+        TRACING_SAMPLE_RATIO_METADATA_KEY, TracingSampling,
+    };
     use crate::schema::service::{HandlerMetadata, ServiceMetadata};
+    use enumset::EnumSet;
     use googletest::prelude::*;
     use restate_util_time::FriendlyDuration;
     use std::time::Duration;
@@ -1376,6 +1664,305 @@ mod endpoint_manifest_options_propagation {
         assert_that!(public_handler_target.public, eq(true));
     }
 
+    #[test]
+    fn response_cache_ttl_from_handler_metadata() {
+        let cached_handler = |name: &str, ttl: &str| endpoint_manifest::Handler {
+            name: name.parse().unwrap(),
+            metadata: [(RESPONSE_CACHE_TTL_METADATA_KEY.to_owned(), ttl.to_owned())]
+                .into_iter()
+                .collect(),
+            ..greeter_service_greet_handler()
+        };
+
+        let schema = SchemaUpdater::update(Schema::default(), |updater| {
+            updater
+                .add_deployment(add_deployment_request(vec![endpoint_manifest::Service {
+                    handlers: vec![
+                        cached_handler("cached", "5 minutes"),
+                        cached_handler("invalid", "not a duration"),
+                        greeter_service_greet_handler(),
+                    ],
+                    ..greeter_service()
+                }]))
+                .map(|_| ())
+        })
+        .unwrap();
+
+        let target = schema.assert_invocation_target(GREETER_SERVICE_NAME, "cached");
+        assert_that!(
+            target.response_cache_ttl,
+            some(eq(Duration::from_secs(300)))
+        );
+        assert_that!(
+            target.compute_response_cache_retention(),
+            some(eq(InvocationRetention {
+                completion_retention: Duration::from_secs(300),
+                // The default journal retention is capped by the cache TTL
+                journal_retention: Duration::from_secs(300),
+            }))
+        );
+
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, "invalid")
+                .response_cache_ttl,
+            none()
+        );
+        assert_that!(
+            schema.assert_handler(GREETER_SERVICE_NAME, "invalid").info,
+            contains(predicate(|info: &SchemaInfo| info
+                .message()
+                .contains("is not a valid duration")))
+        );
+
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .response_cache_ttl,
+            none()
+        );
+    }
+
+    #[test]
+    fn response_cache_ttl_ignored_for_exclusive_handlers() {
+        let schema = SchemaUpdater::update(Schema::default(), |updater| {
+            updater
+                .add_deployment(add_deployment_request(vec![endpoint_manifest::Service {
+                    handlers: vec![endpoint_manifest::Handler {
+                        metadata: [(RESPONSE_CACHE_TTL_METADATA_KEY.to_owned(), "5m".to_owned())]
+                            .into_iter()
+                            .collect(),
+                        ..greeter_service_greet_handler()
+                    }],
+                    ..greeter_virtual_object()
+                }]))
+                .map(|_| ())
+        })
+        .unwrap();
+
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .response_cache_ttl,
+            none()
+        );
+        assert_that!(
+            schema
+                .assert_handler(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .info,
+            contains(predicate(|info: &SchemaInfo| info
+                .message()
+                .contains("supported only for service handlers")))
+        );
+    }
+
+    #[test]
+    fn tracing_sampling_from_service_and_handler_metadata() {
+        let schema = SchemaUpdater::update(Schema::default(), |updater| {
+            updater
+                .add_deployment(add_deployment_request(vec![endpoint_manifest::Service {
+                    metadata: [
+                        (
+                            TRACING_SAMPLE_RATIO_METADATA_KEY.to_owned(),
+                            "0.1".to_owned(),
+                        ),
+                        (
+                            TRACING_FAILURE_MARKERS_METADATA_KEY.to_owned(),
+                            "true".to_owned(),
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    handlers: vec![
+                        endpoint_manifest::Handler {
+                            name: "overridden".parse().unwrap(),
+                            metadata: [
+                                (TRACING_SAMPLE_RATIO_METADATA_KEY.to_owned(), "1".to_owned()),
+                                (
+                                    TRACING_FAILURE_MARKERS_METADATA_KEY.to_owned(),
+                                    "false".to_owned(),
+                                ),
+                            ]
+                            .into_iter()
+                            .collect(),
+                            ..greeter_service_greet_handler()
+                        },
+                        endpoint_manifest::Handler {
+                            name: "invalid".parse().unwrap(),
+                            metadata: [(
+                                TRACING_SAMPLE_RATIO_METADATA_KEY.to_owned(),
+                                "1.5".to_owned(),
+                            )]
+                            .into_iter()
+                            .collect(),
+                            ..greeter_service_greet_handler()
+                        },
+                        greeter_service_greet_handler(),
+                    ],
+                    ..greeter_service()
+                }]))
+                .map(|_| ())
+        })
+        .unwrap();
+
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .tracing_sampling,
+            eq(TracingSampling {
+                sample_ratio: Some(0.1),
+                failure_markers: true,
+            })
+        );
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, "overridden")
+                .tracing_sampling,
+            eq(TracingSampling {
+                sample_ratio: Some(1.0),
+                failure_markers: false,
+            })
+        );
+
+        // Invalid handler values fall back to the service metadata
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, "invalid")
+                .tracing_sampling,
+            eq(TracingSampling {
+                sample_ratio: Some(0.1),
+                failure_markers: true,
+            })
+        );
+        assert_that!(
+            schema.assert_handler(GREETER_SERVICE_NAME, "invalid").info,
+            contains(predicate(|info: &SchemaInfo| info
+                .message()
+                .contains("is not a number between 0 and 1")))
+        );
+    }
+
+    #[test]
+    fn tracing_sampling_modified_through_admin_api() {
+        let schema = SchemaUpdater::update(Schema::default(), |updater| {
+            updater
+                .add_deployment(add_deployment_request(vec![endpoint_manifest::Service {
+                    handlers: vec![
+                        endpoint_manifest::Handler {
+                            name: "overridden".parse().unwrap(),
+                            metadata: [(
+                                TRACING_SAMPLE_RATIO_METADATA_KEY.to_owned(),
+                                "1".to_owned(),
+                            )]
+                            .into_iter()
+                            .collect(),
+                            ..greeter_service_greet_handler()
+                        },
+                        greeter_service_greet_handler(),
+                    ],
+                    ..greeter_service()
+                }]))
+                .map(|_| ())?;
+            updater.modify_service(
+                GREETER_SERVICE_NAME,
+                ModifyServiceRequest {
+                    tracing_sample_ratio: Some(0.25),
+                    tracing_failure_markers: Some(true),
+                    ..ModifyServiceRequest::default()
+                },
+            )
+        })
+        .unwrap();
+
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .tracing_sampling,
+            eq(TracingSampling {
+                sample_ratio: Some(0.25),
+                failure_markers: true,
+            })
+        );
+        // handler metadata still takes precedence
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, "overridden")
+                .tracing_sampling,
+            eq(TracingSampling {
+                sample_ratio: Some(1.0),
+                failure_markers: true,
+            })
+        );
+    }
+
+    #[test]
+    fn invocation_events_from_service_and_handler_metadata() {
+        let schema = SchemaUpdater::update(Schema::default(), |updater| {
+            updater
+                .add_deployment(add_deployment_request(vec![endpoint_manifest::Service {
+                    metadata: [(
+                        INVOCATION_EVENTS_METADATA_KEY.to_owned(),
+                        "failed, paused".to_owned(),
+                    )]
+                    .into_iter()
+                    .collect(),
+                    handlers: vec![
+                        endpoint_manifest::Handler {
+                            name: "overridden".parse().unwrap(),
+                            metadata: [(
+                                INVOCATION_EVENTS_METADATA_KEY.to_owned(),
+                                "completed".to_owned(),
+                            )]
+                            .into_iter()
+                            .collect(),
+                            ..greeter_service_greet_handler()
+                        },
+                        endpoint_manifest::Handler {
+                            name: "invalid".parse().unwrap(),
+                            metadata: [(
+                                INVOCATION_EVENTS_METADATA_KEY.to_owned(),
+                                "failed,exploded".to_owned(),
+                            )]
+                            .into_iter()
+                            .collect(),
+                            ..greeter_service_greet_handler()
+                        },
+                        greeter_service_greet_handler(),
+                    ],
+                    ..greeter_service()
+                }]))
+                .map(|_| ())
+        })
+        .unwrap();
+
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, GREET_HANDLER_NAME)
+                .invocation_events,
+            eq(InvocationEventType::Failed | InvocationEventType::Paused)
+        );
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, "overridden")
+                .invocation_events,
+            eq(EnumSet::only(InvocationEventType::Completed))
+        );
+
+        // Invalid handler values fall back to the service metadata
+        assert_that!(
+            schema
+                .assert_invocation_target(GREETER_SERVICE_NAME, "invalid")
+                .invocation_events,
+            eq(InvocationEventType::Failed | InvocationEventType::Paused)
+        );
+        assert_that!(
+            schema.assert_handler(GREETER_SERVICE_NAME, "invalid").info,
+            contains(predicate(|info: &SchemaInfo| info
+                .message()
+                .contains("is not a list of invocation event types")))
+        );
+    }
+
     #[test]
     fn public_handler_in_private_service() {
         let schema_information = Schema::default();
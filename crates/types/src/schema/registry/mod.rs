@@ -28,11 +28,11 @@ use crate::deployment::{
 };
 use crate::identifiers::{DeploymentId, LambdaARN, ServiceRevision, SubscriptionId};
 use crate::net::address::{AdvertisedAddress, HttpIngressPort};
-use crate::schema::deployment::{Deployment, DeploymentResolver, DeploymentType};
+use crate::schema::deployment::{Deployment, DeploymentResolver, DeploymentType, TrafficSplit};
 use crate::schema::kafka::{KafkaCluster, KafkaClusterName, KafkaClusterResolver};
 use crate::schema::metadata::updater;
 use crate::schema::metadata::updater::{
-    KafkaClusterError, SchemaError, SchemaUpdater, ServiceError,
+    DeploymentError, KafkaClusterError, SchemaError, SchemaUpdater, ServiceError,
 };
 use crate::schema::service::{HandlerMetadata, ServiceMetadata, ServiceMetadataResolver};
 use crate::schema::subscriptions::{ListSubscriptionFilter, Subscription, SubscriptionResolver};
@@ -71,7 +71,11 @@ impl SchemaRegistryError {
                 )
                 | SchemaError::KafkaCluster(KafkaClusterError::ConflictsWithStaticConfig {
                     ..
-                }) => StatusCode::CONFLICT,
+                })
+                | SchemaError::Deployment(
+                    DeploymentError::ServesLatestRevision(..)
+                    | DeploymentError::ReceivesSplitTraffic(..),
+                ) => StatusCode::CONFLICT,
                 SchemaError::Service(_) => StatusCode::BAD_REQUEST,
                 SchemaError::KafkaCluster(_) => StatusCode::BAD_REQUEST,
                 _ => StatusCode::BAD_REQUEST,
@@ -617,6 +621,49 @@ impl<Metadata: MetadataService, Discovery, Telemetry>
         Ok(())
     }
 
+    /// Marks the deployment as draining, see [`DeploymentDrain`](crate::schema::deployment::DeploymentDrain).
+    pub async fn drain_deployment(
+        &self,
+        deployment_id: DeploymentId,
+        auto_remove: bool,
+    ) -> Result<(Deployment, Vec<ServiceMetadata>), SchemaRegistryError> {
+        let (_, schemas) = self
+            .metadata_service
+            .update(|schema| {
+                Ok((
+                    (),
+                    SchemaUpdater::update(schema, |updater| {
+                        updater.drain_deployment(deployment_id, auto_remove)
+                    })?,
+                ))
+            })
+            .await?;
+
+        Ok(schemas
+            .get_deployment_and_services(&deployment_id)
+            .expect("deployment was just drained"))
+    }
+
+    /// Marks a draining deployment as drained, once no in-flight invocations are pinned to it
+    /// anymore.
+    pub async fn mark_deployment_drained(
+        &self,
+        deployment_id: DeploymentId,
+    ) -> Result<(), SchemaRegistryError> {
+        self.metadata_service
+            .update(|schema| {
+                Ok((
+                    (),
+                    SchemaUpdater::update(schema, |updater| {
+                        updater.mark_deployment_drained(deployment_id)
+                    })?,
+                ))
+            })
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn modify_service(
         &self,
         service_name: String,
@@ -645,6 +692,52 @@ impl<Metadata: MetadataService, Discovery, Telemetry>
         Ok(response)
     }
 
+    /// Sets the traffic split of the service, see [`TrafficSplit`].
+    pub async fn set_traffic_split(
+        &self,
+        service_name: String,
+        traffic_split: TrafficSplit,
+    ) -> Result<TrafficSplit, SchemaRegistryError> {
+        self.metadata_service
+            .update(|schema| {
+                Ok((
+                    (),
+                    SchemaUpdater::update(schema, |updater| {
+                        updater.set_traffic_split(&service_name, traffic_split.clone())
+                    })?,
+                ))
+            })
+            .await?;
+
+        Ok(traffic_split)
+    }
+
+    /// Removes the traffic split of the service, new invocations are routed to the latest
+    /// revision of the service again.
+    pub async fn remove_traffic_split(
+        &self,
+        service_name: String,
+    ) -> Result<(), SchemaRegistryError> {
+        self.metadata_service
+            .update(|schema| {
+                Ok((
+                    (),
+                    SchemaUpdater::update(schema, |updater| {
+                        if updater.remove_traffic_split(&service_name) {
+                            Ok(())
+                        } else {
+                            Err(SchemaError::NotFound(format!(
+                                "traffic split of service '{service_name}'"
+                            )))
+                        }
+                    })?,
+                ))
+            })
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn delete_subscription(
         &self,
         subscription_id: SubscriptionId,
@@ -711,6 +804,13 @@ impl<Metadata: MetadataService, Discovery, Telemetry>
             .resolve_latest_deployment_for_service(&service_name)
     }
 
+    pub fn get_traffic_split(&self, service_name: impl AsRef<str>) -> Option<TrafficSplit> {
+        self.metadata_service
+            .get()
+            .traffic_split(service_name.as_ref())
+            .cloned()
+    }
+
     pub fn list_deployments(&self) -> Vec<(Deployment, Vec<(String, ServiceRevision)>)> {
         self.metadata_service.get().get_deployments()
     }
@@ -24,8 +24,8 @@ use restate_types::identifiers::{
 };
 use restate_types::invocation::client::{
     AttachInvocationResponse, CancelInvocationResponse, GetInvocationOutputResponse,
-    InvocationClient, InvocationClientError, InvocationOutput, KillInvocationResponse,
-    PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
+    GetInvocationProgressResponse, InvocationClient, InvocationClientError, InvocationOutput,
+    KillInvocationResponse, PatchDeploymentId, PauseInvocationResponse, PurgeInvocationResponse,
     RestartAsNewInvocationResponse, ResumeInvocationResponse, SubmittedInvocationNotification,
 };
 use restate_types::invocation::{InvocationQuery, InvocationRequest, InvocationResponse};
@@ -361,6 +361,32 @@ where
             }
         })
     }
+
+    async fn get_invocation_progress(
+        &self,
+        request_id: PartitionProcessorRpcRequestId,
+        invocation_id: InvocationId,
+    ) -> Result<GetInvocationProgressResponse, InvocationClientError> {
+        let response = self
+            .resolve_partition_id_and_send(
+                request_id,
+                PartitionProcessorRpcRequestInner::GetInvocationProgress { invocation_id },
+            )
+            .await?;
+
+        Ok(match response {
+            PartitionProcessorRpcResponse::NotFound => GetInvocationProgressResponse::NotFound,
+            PartitionProcessorRpcResponse::InvocationProgress(progress) => {
+                GetInvocationProgressResponse::Ready(progress)
+            }
+            _ => {
+                panic!(
+                    "Expecting either PartitionProcessorRpcResponse::InvocationProgress or PartitionProcessorRpcResponse::NotFound"
+                )
+            }
+        })
+    }
+
     async fn append_invocation_response(
         &self,
         request_id: PartitionProcessorRpcRequestId,
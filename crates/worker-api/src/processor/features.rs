@@ -36,6 +36,12 @@ pub trait PartitionFeatures {
     ///
     /// *Since v1.7.0*
     fn is_unique_random_seeds_enabled(&self) -> bool;
+
+    /// Whether invocation lifecycle events are enqueued into the outbox for delivery by the
+    /// leader's invocation events sink.
+    ///
+    /// *Since v1.7.3*
+    fn is_invocation_events_enabled(&self) -> bool;
 }
 
 impl PartitionFeatures for PersistedFeatures {
@@ -45,6 +51,7 @@ impl PartitionFeatures for PersistedFeatures {
             PartitionFeatureChange::EnableJournalV2 => self.journal_v2,
             PartitionFeatureChange::EnableVqueues => self.vqueues,
             PartitionFeatureChange::EnableUniqueRandomSeeds => self.unique_random_seeds,
+            PartitionFeatureChange::EnableInvocationEvents => self.invocation_events,
         }
     }
 
@@ -62,6 +69,11 @@ impl PartitionFeatures for PersistedFeatures {
     fn is_unique_random_seeds_enabled(&self) -> bool {
         self.unique_random_seeds
     }
+
+    #[inline]
+    fn is_invocation_events_enabled(&self) -> bool {
+        self.invocation_events
+    }
 }
 
 // -- Boilerplate --
@@ -82,6 +94,10 @@ impl<T: PartitionFeatures> PartitionFeatures for &T {
     fn is_unique_random_seeds_enabled(&self) -> bool {
         (**self).is_unique_random_seeds_enabled()
     }
+
+    fn is_invocation_events_enabled(&self) -> bool {
+        (**self).is_invocation_events_enabled()
+    }
 }
 
 impl<T: PartitionFeatures> PartitionFeatures for &mut T {
@@ -100,4 +116,8 @@ impl<T: PartitionFeatures> PartitionFeatures for &mut T {
     fn is_unique_random_seeds_enabled(&self) -> bool {
         (**self).is_unique_random_seeds_enabled()
     }
+
+    fn is_invocation_events_enabled(&self) -> bool {
+        (**self).is_invocation_events_enabled()
+    }
 }
@@ -0,0 +1,75 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::Duration;
+
+use anyhow::Context;
+use rdkafka::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use tokio::sync::OnceCell;
+
+use restate_core::Metadata;
+use restate_types::schema::Redaction;
+use restate_types::schema::kafka::KafkaClusterResolver;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Produces every event as JSON record to a Kafka topic, keyed by the invocation id.
+pub(super) struct KafkaSink {
+    cluster: String,
+    topic: String,
+    /// Created on the first delivery, so that the cluster can be registered after startup.
+    producer: OnceCell<FutureProducer>,
+}
+
+impl KafkaSink {
+    pub(super) fn new(cluster: String, topic: String) -> Self {
+        Self {
+            cluster,
+            topic,
+            producer: OnceCell::new(),
+        }
+    }
+
+    pub(super) async fn send(&self, key: &str, payload: &[u8]) -> anyhow::Result<()> {
+        let producer = self
+            .producer
+            .get_or_try_init(|| async { self.create_producer() })
+            .await?;
+
+        producer
+            .send(
+                FutureRecord::to(&self.topic).key(key).payload(payload),
+                SEND_TIMEOUT,
+            )
+            .await
+            .map_err(|(err, _)| err)
+            .with_context(|| format!("failed to produce to topic '{}'", self.topic))?;
+
+        Ok(())
+    }
+
+    fn create_producer(&self) -> anyhow::Result<FutureProducer> {
+        let kafka_cluster = Metadata::with_current(|m| m.schema())
+            .get_kafka_cluster(&self.cluster, Redaction::No)
+            .with_context(|| format!("unknown Kafka cluster '{}'", self.cluster))?;
+
+        let mut client_config = ClientConfig::new();
+        // enabling probing for the ca certificates if the user does not specify anything else
+        client_config.set("https.ca.location", "probe");
+        for (k, v) in &kafka_cluster.properties {
+            client_config.set(k, v);
+        }
+
+        client_config
+            .create()
+            .with_context(|| format!("failed to create producer for cluster '{}'", self.cluster))
+    }
+}
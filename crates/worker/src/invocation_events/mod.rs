@@ -0,0 +1,316 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Delivery of invocation lifecycle events to the sink configured in `worker.invocation-events`.
+//!
+//! Partition processors enqueue the events of the services which opted in into their outbox.
+//! The shuffle of the leader hands them over to the [`InvocationEventSink`] without waiting for
+//! their delivery, so that events never hold back the other outbox messages. The events are
+//! buffered in memory up to `worker.invocation-events.buffer-size`. Events which don't fit in the
+//! buffer, and events of a leader without a configured sink, are dropped.
+
+mod kafka;
+mod webhook;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use metrics::counter;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, warn};
+
+use restate_core::cancellation_token;
+use restate_types::config::{InvocationEventSinkOptions, InvocationEventsOptions};
+use restate_types::identifiers::PartitionId;
+use restate_types::invocation::events::{InvocationEvent, InvocationEventKind};
+use restate_types::message::MessageIndex;
+
+use self::kafka::KafkaSink;
+use self::webhook::WebhookSink;
+use crate::metric_definitions::{
+    DROP_REASON_BUFFER_FULL, DROP_REASON_NO_SINK, INVOCATION_EVENTS_DROPPED, PARTITION_LABEL,
+    REASON_LABEL,
+};
+
+/// Pause before delivering an event again once the retry policy has been exhausted.
+const EXHAUSTED_RETRIES_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Handle to hand over invocation events to the delivery service of this node.
+#[derive(Debug, Clone, Default)]
+pub struct InvocationEventSink {
+    /// `None` if no sink is configured.
+    tx: Option<mpsc::Sender<PendingEvent>>,
+}
+
+impl InvocationEventSink {
+    /// Sink of a node without configured `worker.invocation-events`. Drops every event.
+    pub(crate) fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Sink buffering up to `buffer_size` events in the returned receiver.
+    #[cfg(test)]
+    pub(crate) fn with_buffer(buffer_size: usize) -> (Self, mpsc::Receiver<PendingEvent>) {
+        let (tx, rx) = mpsc::channel(buffer_size);
+        (Self { tx: Some(tx) }, rx)
+    }
+
+    /// Hands the event over to the delivery service, without waiting for its delivery. The event
+    /// is dropped if no sink is configured, or if the buffer of the delivery service is full
+    /// because the sink can't keep up or is unavailable.
+    ///
+    /// The event id is stable across redeliveries and can be used to deduplicate events.
+    pub(crate) fn deliver(
+        &self,
+        partition_id: PartitionId,
+        seq_number: MessageIndex,
+        event: InvocationEvent,
+    ) {
+        let Some(tx) = &self.tx else {
+            debug!(
+                %partition_id,
+                "Dropping invocation event, because no sink is configured in 'worker.invocation-events'"
+            );
+            counter!(
+                INVOCATION_EVENTS_DROPPED,
+                PARTITION_LABEL => partition_id.to_string(),
+                REASON_LABEL => DROP_REASON_NO_SINK,
+            )
+            .increment(1);
+            return;
+        };
+
+        let pending = PendingEvent {
+            id: format!("{partition_id}-{seq_number}"),
+            event,
+        };
+        match tx.try_send(pending) {
+            Ok(()) => {}
+            Err(TrySendError::Full(pending)) => {
+                debug!(
+                    %partition_id,
+                    restate.invocation.id = %pending.event.invocation_id,
+                    "Dropping invocation event {}, because the delivery buffer is full",
+                    pending.id
+                );
+                counter!(
+                    INVOCATION_EVENTS_DROPPED,
+                    PARTITION_LABEL => partition_id.to_string(),
+                    REASON_LABEL => DROP_REASON_BUFFER_FULL,
+                )
+                .increment(1);
+            }
+            Err(TrySendError::Closed(_)) => {
+                // The service is shutting down
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PendingEvent {
+    id: String,
+    event: InvocationEvent,
+}
+
+enum Sink {
+    Webhook(WebhookSink),
+    Kafka(KafkaSink),
+}
+
+impl Sink {
+    async fn send(&self, key: &str, payload: Bytes) -> anyhow::Result<()> {
+        match self {
+            Sink::Webhook(webhook) => webhook.send(payload).await,
+            Sink::Kafka(kafka) => kafka.send(key, &payload).await,
+        }
+    }
+}
+
+/// Delivers the events handed over to the [`InvocationEventSink`] to the configured sink.
+pub(crate) struct InvocationEventsService {
+    options: InvocationEventsOptions,
+    sink: Sink,
+    rx: mpsc::Receiver<PendingEvent>,
+}
+
+impl InvocationEventsService {
+    pub(crate) fn create(
+        options: InvocationEventsOptions,
+    ) -> anyhow::Result<(InvocationEventSink, Self)> {
+        let sink = match &options.sink {
+            InvocationEventSinkOptions::Webhook { url, headers } => {
+                Sink::Webhook(WebhookSink::new(url, headers.clone().into())?)
+            }
+            InvocationEventSinkOptions::Kafka { cluster, topic } => {
+                Sink::Kafka(KafkaSink::new(cluster.clone(), topic.clone()))
+            }
+        };
+        let (tx, rx) = mpsc::channel(options.buffer_size.get());
+
+        Ok((
+            InvocationEventSink { tx: Some(tx) },
+            Self { options, sink, rx },
+        ))
+    }
+
+    pub(crate) async fn run(self) -> anyhow::Result<()> {
+        let Self { options, sink, rx } = self;
+
+        cancellation_token()
+            .run_until_cancelled(
+                ReceiverStream::new(rx)
+                    .for_each_concurrent(options.concurrency_limit.get(), |pending| {
+                        deliver_event(&options, &sink, pending)
+                    }),
+            )
+            .await;
+
+        debug!("Stopping invocation events service");
+        Ok(())
+    }
+}
+
+async fn deliver_event(options: &InvocationEventsOptions, sink: &Sink, pending: PendingEvent) {
+    let PendingEvent { id, event } = pending;
+    let key = event.invocation_id.to_string();
+    let payload = Bytes::from(event_payload(&id, &event).to_string());
+
+    // Once the retry policy is exhausted, it starts over after a pause. Meanwhile, the buffer
+    // fills up and the shuffle drops the events which don't fit anymore.
+    loop {
+        let result = options
+            .retry_policy
+            .clone()
+            .retry_with_inspect(
+                || sink.send(&key, payload.clone()),
+                |attempt, err| {
+                    debug!(
+                        restate.invocation.id = %event.invocation_id,
+                        "Failed to deliver invocation event {id} (attempt {attempt}), retrying: {err}"
+                    )
+                },
+            )
+            .await;
+
+        match result {
+            Ok(()) => return,
+            Err(err) => {
+                warn!(
+                    restate.invocation.id = %event.invocation_id,
+                    "Failed to deliver invocation event {id}, retrying in {:?}: {err}",
+                    EXHAUSTED_RETRIES_BACKOFF
+                );
+                tokio::time::sleep(EXHAUSTED_RETRIES_BACKOFF).await;
+            }
+        }
+    }
+}
+
+/// JSON representation of the event, which is sent to the sink.
+fn event_payload(id: &str, event: &InvocationEvent) -> serde_json::Value {
+    let mut payload = json!({
+        "id": id,
+        "type": event.event_type().map(|ty| ty.to_string()),
+        "invocation_id": event.invocation_id.to_string(),
+        "target": {
+            "service": event.invocation_target.service_name().to_string(),
+            "handler": event.invocation_target.handler_name().to_string(),
+            "key": event.invocation_target.key().map(|key| key.to_string()),
+        },
+        "timestamp": event.timestamp.into_timestamp().to_string(),
+    });
+
+    match &event.kind {
+        InvocationEventKind::Completed => {}
+        InvocationEventKind::Failed(error) => {
+            payload["error"] = json!({
+                "code": u16::from(error.code()),
+                "message": error.message(),
+            });
+        }
+        InvocationEventKind::JournalEvent(raw_event) => {
+            payload["journal_event"] =
+                serde_json::to_value(raw_event.clone().into_event_or_unknown()).unwrap_or_default();
+        }
+    }
+
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use googletest::prelude::*;
+
+    use restate_types::errors::InvocationError;
+    use restate_types::identifiers::InvocationId;
+    use restate_types::invocation::{InvocationTarget, VirtualObjectHandlerType};
+    use restate_types::journal_events::raw::RawEvent;
+    use restate_types::journal_events::{Event, PausedEvent};
+    use restate_types::time::MillisSinceEpoch;
+
+    fn mock_event(kind: InvocationEventKind) -> InvocationEvent {
+        InvocationEvent {
+            invocation_id: InvocationId::mock_random(),
+            invocation_target: InvocationTarget::virtual_object(
+                "Greeter",
+                "bob",
+                "greet",
+                VirtualObjectHandlerType::Exclusive,
+            ),
+            timestamp: MillisSinceEpoch::new(1_700_000_000_000),
+            kind,
+        }
+    }
+
+    #[test]
+    fn failed_event_payload() {
+        let event = mock_event(InvocationEventKind::Failed(InvocationError::new(
+            500u16, "boom",
+        )));
+
+        let payload = event_payload("3-42", &event);
+
+        assert_that!(payload["id"], eq(&json!("3-42")));
+        assert_that!(payload["type"], eq(&json!("failed")));
+        assert_that!(
+            payload["invocation_id"],
+            eq(&json!(event.invocation_id.to_string()))
+        );
+        assert_that!(
+            payload["target"],
+            eq(&json!({"service": "Greeter", "handler": "greet", "key": "bob"}))
+        );
+        assert_that!(payload["timestamp"], eq(&json!("2023-11-14T22:13:20Z")));
+        assert_that!(
+            payload["error"],
+            eq(&json!({"code": 500, "message": "boom"}))
+        );
+    }
+
+    #[test]
+    fn journal_event_payload() {
+        let event = mock_event(InvocationEventKind::JournalEvent(RawEvent::from(
+            Event::Paused(PausedEvent { last_failure: None }),
+        )));
+
+        let payload = event_payload("0-1", &event);
+
+        assert_that!(payload["type"], eq(&json!("paused")));
+        assert_that!(payload["journal_event"], eq(&json!({"ty": "Paused"})));
+        assert_that!(payload.get("error"), none());
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Context;
+use bytes::Bytes;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Sends every event as JSON body of a `POST` request.
+pub(super) struct WebhookSink {
+    client: reqwest::Client,
+    url: reqwest::Url,
+}
+
+impl WebhookSink {
+    pub(super) fn new(
+        url: &str,
+        headers: HashMap<HeaderName, HeaderValue>,
+    ) -> anyhow::Result<Self> {
+        let url = reqwest::Url::parse(url)
+            .with_context(|| format!("invalid invocation events webhook url '{url}'"))?;
+
+        let mut default_headers = HeaderMap::from_iter(headers);
+        default_headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let client = reqwest::Client::builder()
+            .default_headers(default_headers)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed to create the invocation events webhook client")?;
+
+        Ok(Self { client, url })
+    }
+
+    /// Succeeds if the webhook responded with a `2xx` status.
+    pub(super) async fn send(&self, payload: Bytes) -> anyhow::Result<()> {
+        self.client
+            .post(self.url.clone())
+            .body(payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
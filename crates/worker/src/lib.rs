@@ -12,6 +12,7 @@ extern crate core;
 
 mod error;
 mod handle;
+mod invocation_events;
 mod invoker_integration;
 mod metric_definitions;
 #[cfg(feature = "expose-internals")]
@@ -42,7 +43,7 @@ use restate_core::partitions::PartitionRouting;
 use restate_core::{Metadata, TaskKind};
 use restate_core::{MetadataWriter, TaskCenter};
 use restate_ingestion_client::IngestionClient;
-use restate_ingress_kafka::Service as IngressKafkaService;
+use restate_ingress_kafka::{KafkaConsumerStates, Service as IngressKafkaService};
 use restate_partition_store::PartitionStoreManager;
 use restate_partition_store::snapshots::SnapshotRepository;
 use restate_storage_query_datafusion::context::{QueryContext, SelectPartitionsFromMetadata};
@@ -58,6 +59,7 @@ use restate_types::schema::kafka::KafkaClusterResolver;
 use restate_types::schema::subscriptions::SubscriptionResolver;
 use restate_worker_api::ProcessorsManagerHandle;
 
+use crate::invocation_events::{InvocationEventSink, InvocationEventsService};
 use crate::partition_processor_manager::PartitionProcessorManager;
 
 pub use self::error::*;
@@ -93,12 +95,16 @@ pub enum BuildError {
     #[error("failed constructing partition snapshot repository: {0}")]
     #[code(unknown)]
     SnapshotRepository(#[from] anyhow::Error),
+    #[error("failed creating invocation events sink: {0}")]
+    #[code(unknown)]
+    InvocationEvents(anyhow::Error),
 }
 
 pub struct Worker<T> {
     storage_query_context: QueryContext,
     ingress_kafka: IngressKafkaService<T>,
     subscription_controller_handle: SubscriptionControllerHandle,
+    invocation_events_service: Option<InvocationEventsService>,
     partition_processor_manager: PartitionProcessorManager<T>,
 }
 
@@ -166,6 +172,16 @@ where
                 .expect("Ingestion session options to build"),
         );
 
+        let (invocation_event_sink, invocation_events_service) =
+            match config.worker.invocation_events.clone() {
+                Some(options) => {
+                    let (sink, service) = InvocationEventsService::create(options)
+                        .map_err(BuildError::InvocationEvents)?;
+                    (sink, Some(service))
+                }
+                None => (InvocationEventSink::disabled(), None),
+            };
+
         let metadata_store_client = metadata_writer.raw_metadata_store_client().clone();
 
         let partition_processor_manager = PartitionProcessorManager::new(
@@ -183,6 +199,7 @@ where
             .await
             .map_err(BuildError::SnapshotRepository)?,
             ppm_ingestion_client,
+            invocation_event_sink,
         );
 
         let rule_book_cache_handle = partition_processor_manager.rule_book_cache_handle();
@@ -203,6 +220,7 @@ where
             storage_query_context,
             ingress_kafka,
             subscription_controller_handle,
+            invocation_events_service,
             partition_processor_manager,
         })
     }
@@ -219,6 +237,10 @@ where
         self.partition_processor_manager.rule_book_cache_handle()
     }
 
+    pub fn kafka_consumer_states(&self) -> KafkaConsumerStates {
+        self.ingress_kafka.consumer_states().clone()
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         TaskCenter::spawn_child(
             TaskKind::MetadataBackgroundSync,
@@ -233,6 +255,14 @@ where
             self.ingress_kafka.run(),
         )?;
 
+        if let Some(invocation_events_service) = self.invocation_events_service {
+            TaskCenter::spawn_child(
+                TaskKind::SystemService,
+                "invocation-events",
+                invocation_events_service.run(),
+            )?;
+        }
+
         self.partition_processor_manager.run().await?;
         info!("Worker role has stopped");
 
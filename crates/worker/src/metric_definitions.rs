@@ -62,6 +62,11 @@ pub const PARTITION_RECORD_COMMITTED_TO_READ_LATENCY_SECONDS: &str =
 pub const PARTITION_SHUFFLE_MESSAGE_COUNT: &str = "restate.partition.shuffle.message.total";
 pub const PARTITION_SHUFFLE_INFLIGHT_RECORDS: &str = "restate.partition.shuffle.inflight";
 
+// contains `reason` and `partition` labels
+pub const INVOCATION_EVENTS_DROPPED: &str = "restate.invocation_events.dropped.total";
+pub const DROP_REASON_NO_SINK: &str = "no-sink";
+pub const DROP_REASON_BUFFER_FULL: &str = "buffer-full";
+
 pub(crate) fn describe_metrics() {
     describe_gauge!(
         PARTITION_BLOCKED_FLARE,
@@ -156,4 +161,10 @@ pub(crate) fn describe_metrics() {
         Unit::Count,
         "Number of inflight records by source partition"
     );
+
+    describe_counter!(
+        INVOCATION_EVENTS_DROPPED,
+        Unit::Count,
+        "Number of invocation events dropped before delivery, by source partition and reason"
+    );
 }
@@ -8,10 +8,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use anyhow::Context;
 use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
 use tokio::sync::mpsc::{self, Sender};
 use tokio::time::{Instant, MissedTickBehavior};
 use tokio_stream::wrappers::ReceiverStream;
@@ -19,10 +21,13 @@ use tracing::{debug, instrument, warn};
 
 use restate_core::{ShutdownError, TaskCenter, TaskHandle, TaskId, TaskKind, cancellation_watcher};
 use restate_storage_api::invocation_status_table::ScanInvocationStatusTable;
+use restate_storage_api::protobuf_types::v1::lazy::InvocationStatusV2Lazy;
 use restate_types::errors::ConversionError;
 use restate_types::identifiers::{InvocationId, PartitionId};
 use restate_util_time::DurationExt;
 
+use super::invocation_archiver::{InvocationArchiver, PendingArchive};
+
 const CLEANER_EFFECT_QUEUE_SIZE: usize = 10;
 
 #[derive(Debug, Clone)]
@@ -50,6 +55,7 @@ pub(super) struct Cleaner<Storage> {
     partition_id: PartitionId,
     storage: Storage,
     cleanup_interval: Duration,
+    archiver: Option<InvocationArchiver>,
 }
 
 impl<Storage> Cleaner<Storage>
@@ -65,9 +71,16 @@ where
             partition_id,
             storage,
             cleanup_interval,
+            archiver: None,
         }
     }
 
+    /// Archives the invocations and journals before purging them.
+    pub(super) fn with_archiver(mut self, archiver: InvocationArchiver) -> Self {
+        self.archiver = Some(archiver);
+        self
+    }
+
     pub(super) fn start(self) -> Result<CleanerHandle, ShutdownError> {
         let (tx, rx) = mpsc::channel(CLEANER_EFFECT_QUEUE_SIZE);
         let task_id = TaskCenter::spawn_child(TaskKind::Cleaner, "cleaner", self.run(tx))?;
@@ -124,51 +137,31 @@ where
 
         let now = SystemTime::now();
 
-        let effects_stream = self
-            .storage
-            .filter_map_invocation_status_lazy(move |(invocation_id, invocation_status_v2_lazy)| {
-                let restate_storage_api::protobuf_types::v1::invocation_status_v2::Status::Completed =
-                    invocation_status_v2_lazy.inner.status()
-                else {
-                    return Ok(None);
-                };
-
-                let Some(completed_time) = invocation_status_v2_lazy.inner.completed_transition_time else {
-                    // If completed time is unavailable, the invocation is on the old invocation table,
-                    //  thus it will be cleaned up with the old timer.
-                    return Ok(None);
-                };
-                let completed_time = restate_types::time::MillisSinceEpoch::new(completed_time);
-
-                let completion_retention_duration =
-                    invocation_status_v2_lazy.completion_retention_duration()?;
-
-                // Check if the invocation status itself has expired
-                if let Some(status_expiration_time) =
-                    SystemTime::from(completed_time).checked_add(completion_retention_duration)
-                    && now >= status_expiration_time
+        let pending_archive = self
+            .archiver
+            .as_ref()
+            .map(|archiver| Arc::new(Mutex::new(archiver.pending_archive())));
+
+        let effects_stream = self.storage.filter_map_invocation_status_lazy({
+            let pending_archive = pending_archive.clone();
+            move |(invocation_id, invocation_status_v2_lazy)| {
+                let effect = expired_effect(now, invocation_id, invocation_status_v2_lazy)?;
+                if let Some(effect) = &effect
+                    && let Some(pending_archive) = &pending_archive
                 {
-                    return Ok(Some(CleanerEffect::PurgeInvocation(invocation_id)));
-                }
-
-                // We don't cleanup the status yet, let's check if there's a journal to cleanup
-                // When length != 0 it means that the purge journal feature was activated from the SDK side (through annotations and the new manifest),
-                // or from the relative experimental feature in the Admin API. In this case, the user opted-in this feature and it can't go back to 1.3
-                if invocation_status_v2_lazy.inner.journal_length != 0 {
-                    let journal_retention_duration = invocation_status_v2_lazy.journal_retention_duration()?;
-
-                    if let Some(journal_expiration_time) =
-                        SystemTime::from(completed_time).checked_add(journal_retention_duration)
-                        && now >= journal_expiration_time
-                    {
-                        return Ok(Some(CleanerEffect::PurgeJournal(invocation_id)));
-                    }
+                    pending_archive
+                        .lock()
+                        .add(effect, invocation_id, invocation_status_v2_lazy)?;
                 }
-
-                Result::<Option<_>, ConversionError>::Ok(None)
-            })?;
+                Result::<_, ConversionError>::Ok(effect)
+            }
+        })?;
         tokio::pin!(effects_stream);
 
+        // When archiving, the effects are held back until the chunk of the archive they belong
+        // to has been written, so that no invocation is purged before it has been archived.
+        let mut held_back_effects = Vec::new();
+        let mut archived_chunks = 0;
         while let Some(effect) = effects_stream
             .next()
             .await
@@ -179,9 +172,28 @@ where
                 CleanerEffect::PurgeInvocation(_) => purged_invocation_count += 1,
                 CleanerEffect::PurgeJournal(_) => purged_journal_count += 1,
             }
-            tx.send(effect)
-                .await
-                .context("Cannot send cleaner effect")?;
+            if let Some(pending_archive) = &pending_archive {
+                held_back_effects.push(effect);
+                if held_back_effects.len() >= self.max_invocations_per_file() {
+                    self.archive_chunk(
+                        pending_archive,
+                        archived_chunks,
+                        &mut held_back_effects,
+                        tx,
+                    )
+                    .await?;
+                    archived_chunks += 1;
+                }
+            } else {
+                tx.send(effect)
+                    .await
+                    .context("Cannot send cleaner effect")?;
+            }
+        }
+
+        if let Some(pending_archive) = &pending_archive {
+            self.archive_chunk(pending_archive, archived_chunks, &mut held_back_effects, tx)
+                .await?;
         }
 
         debug!(
@@ -194,6 +206,82 @@ where
 
         Ok(())
     }
+
+    fn max_invocations_per_file(&self) -> usize {
+        self.archiver
+            .as_ref()
+            .map_or(usize::MAX, InvocationArchiver::max_invocations_per_file)
+    }
+
+    /// Archives the pending chunk, then sends the held back effects of its invocations.
+    async fn archive_chunk(
+        &self,
+        pending_archive: &Mutex<PendingArchive>,
+        chunk: usize,
+        held_back_effects: &mut Vec<CleanerEffect>,
+        tx: &Sender<CleanerEffect>,
+    ) -> anyhow::Result<()> {
+        let archiver = self.archiver.as_ref().expect("cleaner with archiver");
+        let pending = std::mem::replace(&mut *pending_archive.lock(), archiver.pending_archive());
+        archiver
+            .archive(pending, chunk)
+            .await
+            .context("Cannot archive the expired invocations")?;
+
+        for effect in held_back_effects.drain(..) {
+            tx.send(effect)
+                .await
+                .context("Cannot send cleaner effect")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the effect to apply to the given invocation, if its retention expired.
+fn expired_effect(
+    now: SystemTime,
+    invocation_id: InvocationId,
+    invocation_status_v2_lazy: &InvocationStatusV2Lazy<'_>,
+) -> Result<Option<CleanerEffect>, ConversionError> {
+    let restate_storage_api::protobuf_types::v1::invocation_status_v2::Status::Completed =
+        invocation_status_v2_lazy.inner.status()
+    else {
+        return Ok(None);
+    };
+
+    let Some(completed_time) = invocation_status_v2_lazy.inner.completed_transition_time else {
+        // If completed time is unavailable, the invocation is on the old invocation table,
+        //  thus it will be cleaned up with the old timer.
+        return Ok(None);
+    };
+    let completed_time = restate_types::time::MillisSinceEpoch::new(completed_time);
+
+    let completion_retention_duration =
+        invocation_status_v2_lazy.completion_retention_duration()?;
+
+    // Check if the invocation status itself has expired
+    if let Some(status_expiration_time) =
+        SystemTime::from(completed_time).checked_add(completion_retention_duration)
+        && now >= status_expiration_time
+    {
+        return Ok(Some(CleanerEffect::PurgeInvocation(invocation_id)));
+    }
+
+    // We don't cleanup the status yet, let's check if there's a journal to cleanup
+    // When length != 0 it means that the purge journal feature was activated from the SDK side (through annotations and the new manifest),
+    // or from the relative experimental feature in the Admin API. In this case, the user opted-in this feature and it can't go back to 1.3
+    if invocation_status_v2_lazy.inner.journal_length != 0 {
+        let journal_retention_duration = invocation_status_v2_lazy.journal_retention_duration()?;
+
+        if let Some(journal_expiration_time) =
+            SystemTime::from(completed_time).checked_add(journal_retention_duration)
+            && now >= journal_expiration_time
+        {
+            return Ok(Some(CleanerEffect::PurgeJournal(invocation_id)));
+        }
+    }
+
+    Ok(None)
 }
 
 #[cfg(test)]
@@ -0,0 +1,163 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use anyhow::Context;
+use futures::StreamExt;
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+use restate_partition_store::PartitionStore;
+use restate_storage_api::journal_table_v2::ReadJournalTable;
+use restate_storage_api::protobuf_types::v1::lazy::InvocationStatusV2Lazy;
+use restate_storage_query_datafusion::invocation_history::{
+    ArchivePartition, InvocationArchive, InvocationArchiveBatch,
+};
+use restate_types::config::InvocationArchiveOptions;
+use restate_types::errors::ConversionError;
+use restate_types::identifiers::{EntryIndex, InvocationId, JournalEntryId, PartitionId};
+use restate_types::time::MillisSinceEpoch;
+
+use super::cleaner::CleanerEffect;
+
+/// Archives the invocations and journals which are about to be purged by the [`Cleaner`].
+///
+/// [`Cleaner`]: super::cleaner::Cleaner
+pub(super) struct InvocationArchiver {
+    partition_id: PartitionId,
+    storage: PartitionStore,
+    options: InvocationArchiveOptions,
+    // created on first use, so that an unavailable object store delays the cleanup instead of
+    // failing the leadership transition
+    archive: OnceCell<InvocationArchive>,
+}
+
+impl InvocationArchiver {
+    pub(super) fn new(
+        partition_id: PartitionId,
+        storage: PartitionStore,
+        options: InvocationArchiveOptions,
+    ) -> Self {
+        Self {
+            partition_id,
+            storage,
+            options,
+            archive: OnceCell::new(),
+        }
+    }
+
+    /// Maximum number of expired invocations to archive in a single file.
+    pub(super) fn max_invocations_per_file(&self) -> usize {
+        self.options.max_invocations_per_file.get()
+    }
+
+    pub(super) fn pending_archive(&self) -> PendingArchive {
+        PendingArchive {
+            include_journal: self.options.include_journal,
+            ..Default::default()
+        }
+    }
+
+    /// Writes the pending archive to the object store, as the `chunk`-th file of the current
+    /// cleanup run. The purge effects of the archived invocations must only be applied once
+    /// this returned successfully.
+    pub(super) async fn archive(
+        &self,
+        pending: PendingArchive,
+        chunk: usize,
+    ) -> anyhow::Result<()> {
+        let PendingArchive {
+            mut batch,
+            journals,
+            ..
+        } = pending;
+
+        for (invocation_id, journal_length, partition) in journals {
+            let entries = self
+                .storage
+                .get_journal(invocation_id, journal_length)
+                .context("Cannot read the journal to archive")?;
+            tokio::pin!(entries);
+            while let Some((index, entry)) = entries
+                .next()
+                .await
+                .transpose()
+                .context("Cannot read the journal to archive")?
+            {
+                batch.append_journal_entry(
+                    &partition,
+                    JournalEntryId::from_parts(invocation_id, index),
+                    entry,
+                );
+            }
+        }
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let archive = self
+            .archive
+            .get_or_try_init(|| InvocationArchive::create(&self.options))
+            .await?;
+
+        let archived_invocation_count = batch.num_invocations();
+        let file_name = format!(
+            "{}-{}-{chunk}.parquet",
+            self.partition_id,
+            MillisSinceEpoch::now().as_u64()
+        );
+        archive.write(batch, &file_name).await?;
+
+        debug!(
+            partition_id=%self.partition_id,
+            archived_invocation_count,
+            "Archived expired invocations to {file_name}"
+        );
+
+        Ok(())
+    }
+}
+
+/// Invocations collected during a cleanup run, which are archived before they are purged. Holds
+/// at most one chunk of [`InvocationArchiver::max_invocations_per_file`] invocations, plus the
+/// ones the scan read ahead.
+#[derive(Default)]
+pub(super) struct PendingArchive {
+    include_journal: bool,
+    batch: InvocationArchiveBatch,
+    journals: Vec<(InvocationId, EntryIndex, ArchivePartition)>,
+}
+
+impl PendingArchive {
+    pub(super) fn add(
+        &mut self,
+        effect: &CleanerEffect,
+        invocation_id: InvocationId,
+        invocation_status: &InvocationStatusV2Lazy<'_>,
+    ) -> Result<(), ConversionError> {
+        let Some(partition) = ArchivePartition::from_invocation_status(invocation_status)? else {
+            return Ok(());
+        };
+
+        if let CleanerEffect::PurgeInvocation(_) = effect {
+            self.batch
+                .append_invocation(&partition, invocation_id, invocation_status)?;
+        }
+
+        // both effects remove the journal
+        let journal_length = invocation_status.inner.journal_length;
+        if self.include_journal && journal_length > 0 {
+            self.journals
+                .push((invocation_id, journal_length, partition));
+        }
+
+        Ok(())
+    }
+}
@@ -80,6 +80,7 @@ use self::trim_queue::{HasTrimQueue, LogTrimmer};
 use crate::invoker_integration::EntryEnricher;
 use crate::partition::LeadershipInfo;
 use crate::partition::cleaner::{self, Cleaner};
+use crate::partition::invocation_archiver::InvocationArchiver;
 use crate::partition::invoker_storage_reader::InvokerStorageReader;
 use crate::partition::leadership::leader_state::LeaderState;
 use crate::partition::leadership::self_proposer::SelfProposer;
@@ -588,6 +589,14 @@ where
                 feature_changes.push(PartitionFeatureChange::EnableUniqueRandomSeeds);
             }
 
+            // Enqueue invocation events only once the partition requires a version whose leaders
+            // can decode them from the outbox. Proposed by the first leader with a configured sink.
+            if config.worker.invocation_events.is_some()
+                && !processor.fsm().features().is_invocation_events_enabled()
+            {
+                feature_changes.push(PartitionFeatureChange::EnableInvocationEvents);
+            }
+
             if !feature_changes.is_empty() {
                 // Smallest version that supports every listed feature, but never below
                 // the partition's current min_restate_version.
@@ -738,6 +747,7 @@ where
                 shuffle_tx,
                 config.worker.internal_queue_length(),
                 self.ingestion_client.clone(),
+                node_ctx.invocation_event_sink.clone(),
             );
 
             let shuffle_hint_tx = shuffle.create_hint_sender();
@@ -745,11 +755,18 @@ where
             let shuffle_task_handle =
                 TaskCenter::spawn_unmanaged(TaskKind::Shuffle, "shuffle", shuffle.run())?;
 
-            let cleaner = Cleaner::new(
+            let mut cleaner = Cleaner::new(
                 partition_store.clone(),
                 processor.partition_id(),
                 config.worker.cleanup_interval(),
             );
+            if let Some(archive_options) = &config.worker.invocation_archive {
+                cleaner = cleaner.with_archiver(InvocationArchiver::new(
+                    processor.partition_id(),
+                    partition_store.clone(),
+                    archive_options.clone(),
+                ));
+            }
 
             let cleaner_handle = cleaner.start()?;
 
@@ -1051,6 +1068,7 @@ mod tests {
     use restate_wal_protocol::Envelope;
     use restate_worker_api::invoker::capacity::InvokerCapacity;
 
+    use crate::invocation_events::InvocationEventSink;
     use crate::partition::leadership::{LeadershipState, State};
     use crate::partition::processor::ProcessorRawContext;
     use crate::partition::{LeadershipInfo, NodeContext};
@@ -1091,6 +1109,7 @@ mod tests {
             bifrost.clone(),
             InvokerCapacity::new_unlimited(),
             PartitionLeaderHandlesRegistry::default(),
+            InvocationEventSink::disabled(),
         );
 
         let mut partition_store = partition_store_manager.open(&PARTITION, None).await?;
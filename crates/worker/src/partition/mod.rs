@@ -17,6 +17,7 @@
 )]
 
 mod cleaner;
+mod invocation_archiver;
 pub mod invoker_storage_reader;
 mod leadership;
 pub mod node;
@@ -62,7 +63,7 @@ use restate_storage_api::deduplication_table::{
 use restate_storage_api::{StorageError, Transaction};
 use restate_types::cluster::cluster_state::{PartitionProcessorStatus, RunMode};
 use restate_types::epoch::EpochMetadata;
-use restate_types::identifiers::LeaderEpoch;
+use restate_types::identifiers::{LeaderEpoch, WithPartitionKey};
 use restate_types::logs::{self, Lsn, RecordDecodeError, SequenceNumber};
 use restate_types::net::ingest::{
     DedupSequenceNrQueryRequest, DedupSequenceNrQueryResponse, ReceivedIngestRequest,
@@ -76,6 +77,7 @@ use restate_types::net::{RpcRequest, ingest};
 use restate_types::partitions::PartitionFeatureChange;
 use restate_types::retries::RetryPolicy;
 use restate_types::schema::Schema;
+use restate_types::sharding::KeyRange;
 use restate_types::storage::{
     PolyBytes, StorageCodec, StorageDecode, StorageDecodeError, StorageEncode,
 };
@@ -86,7 +88,7 @@ use restate_vqueues::context::HasVQueues;
 use restate_wal_protocol::control::{CurrentReplicaSetConfiguration, NextReplicaSetConfiguration};
 use restate_wal_protocol::v2::CommandScope;
 use restate_wal_protocol::{Envelope, v2};
-use restate_worker_api::invoker::InvokerHandle;
+use restate_worker_api::invoker::{InvokerHandle, StatusHandle};
 use restate_worker_api::{LeaderQueryCommand, LeaderQueryReceiver};
 
 use self::leadership::RpcProcessingPermit;
@@ -723,6 +725,29 @@ where
                 }
                 response_tx.send(Ok(reply));
             }
+            rpc::Decision::ReplyWithInvokerStatus {
+                invocation_id,
+                progress,
+            } => {
+                // Read the invoker status off the partition processor loop, the invoker task
+                // might be waiting on us to drain its effects.
+                let leader_handles_registry = self.node_ctx.leader_handles_registry.clone();
+                _ = TaskCenter::current().spawn_child(
+                    TaskKind::Disposable,
+                    "read-invoker-status",
+                    async move {
+                        let partition_key = invocation_id.partition_key();
+                        let report = leader_handles_registry
+                            .read_status(KeyRange::new(partition_key, partition_key))
+                            .await
+                            .find(|report| report.invocation_id() == &invocation_id);
+                        response_tx.send(Ok(PartitionProcessorRpcResponse::InvocationProgress(
+                            rpc::merge_invoker_status(progress, report.as_ref()),
+                        )));
+                        Ok(())
+                    },
+                );
+            }
         }
     }
 
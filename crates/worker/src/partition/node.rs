@@ -16,6 +16,7 @@ use restate_types::partitions::state::PartitionReplicaSetStates;
 use restate_worker_api::invoker::capacity::InvokerCapacity;
 
 use crate::RuleBookCacheHandle;
+use crate::invocation_events::InvocationEventSink;
 use crate::partition_processor_manager::PartitionLeaderHandlesRegistry;
 
 #[derive(Clone)]
@@ -32,6 +33,8 @@ pub struct NodeContext {
     pub rule_book_cache: RuleBookCacheHandle,
     pub bifrost: Bifrost,
     pub leader_handles_registry: PartitionLeaderHandlesRegistry,
+    /// Receives the invocation events from the shuffle of the partition leaders.
+    pub invocation_event_sink: InvocationEventSink,
 }
 
 impl NodeContext {
@@ -43,6 +46,7 @@ impl NodeContext {
         bifrost: Bifrost,
         invoker_capacity: InvokerCapacity,
         leader_handles_registry: PartitionLeaderHandlesRegistry,
+        invocation_event_sink: InvocationEventSink,
     ) -> Self {
         Self {
             my_node_id,
@@ -51,6 +55,7 @@ impl NodeContext {
             bifrost,
             invoker_capacity,
             leader_handles_registry,
+            invocation_event_sink,
             config,
         }
     }
@@ -176,6 +176,9 @@ impl<L: LeaderPromotion> ApplyPartitionCommand<VersionBarrierCommand>
                     // point. Pre-existing invocations without a stored random seed keep working via the
                     // `to_random_seed()` fallback in `invoker_storage_reader.rs`.
                     PartitionFeatureChange::EnableUniqueRandomSeeds => {}
+                    // Only invocation events emitted after the apply point are enqueued. The
+                    // barrier keeps leaders that can't decode them from processing the partition.
+                    PartitionFeatureChange::EnableInvocationEvents => {}
                 }
             }
         }
@@ -246,6 +249,7 @@ mod tests {
 
     use super::{ApplyPartitionCommand, VersionBarrierContext};
     use crate::RuleBookCacheHandle;
+    use crate::invocation_events::InvocationEventSink;
     use crate::partition::leadership::trim_queue::HasTrimQueue;
     use crate::partition::processor::ProcessorRawContext;
     use crate::partition::processor::commands::NextStep;
@@ -371,6 +375,7 @@ mod tests {
             bifrost,
             InvokerCapacity::new_unlimited(),
             PartitionLeaderHandlesRegistry::default(),
+            InvocationEventSink::disabled(),
         );
 
         let result = apply_barrier(
@@ -405,6 +410,7 @@ mod tests {
             bifrost,
             InvokerCapacity::new_unlimited(),
             PartitionLeaderHandlesRegistry::default(),
+            InvocationEventSink::disabled(),
         );
 
         apply_barrier(
@@ -464,6 +470,7 @@ mod tests {
             bifrost,
             InvokerCapacity::new_unlimited(),
             PartitionLeaderHandlesRegistry::default(),
+            InvocationEventSink::disabled(),
         );
 
         // PSF starts empty.
@@ -508,6 +515,7 @@ mod tests {
             bifrost,
             InvokerCapacity::new_unlimited(),
             PartitionLeaderHandlesRegistry::default(),
+            InvocationEventSink::disabled(),
         );
 
         let result = apply_barrier(
@@ -0,0 +1,251 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use super::*;
+use restate_storage_api::invocation_status_table::{InvocationStatus, ReadInvocationStatusTable};
+use restate_types::invocation::client::{InvocationProgress, InvocationProgressStatus};
+use restate_types::time::MillisSinceEpoch;
+use restate_worker_api::invoker::InvocationStatusReport;
+
+pub(super) struct Request {
+    pub(super) invocation_id: InvocationId,
+}
+
+impl<'a, TSchemas, TStorage> RpcHandler<Request> for RpcContext<'a, TSchemas, TStorage>
+where
+    TStorage: ReadInvocationStatusTable,
+{
+    async fn handle(self, Request { invocation_id }: Request) -> Decision {
+        // Same as get_invocation_output, only the leader has an authoritative view of the
+        // invocation status. Moreover, the attempts are tracked by the leader's invoker only.
+        if !self.is_leader {
+            return Decision::Reply(Err(PartitionProcessorRpcError::NotLeader(
+                self.partition_id,
+            )));
+        }
+
+        let invocation_status = match self.storage.get_invocation_status(&invocation_id).await {
+            Ok(status) => status,
+            Err(err) => {
+                return Decision::Reply(Err(PartitionProcessorRpcError::Internal(err.to_string())));
+            }
+        };
+
+        let progress = |status, execution_time| InvocationProgress {
+            status,
+            execution_time,
+            attempts: None,
+            last_failure: None,
+            next_retry_at: None,
+        };
+        let reply = match invocation_status {
+            InvocationStatus::Free => PartitionProcessorRpcResponse::NotFound,
+            InvocationStatus::Scheduled(scheduled) => {
+                PartitionProcessorRpcResponse::InvocationProgress(progress(
+                    InvocationProgressStatus::Scheduled,
+                    scheduled.metadata.execution_time,
+                ))
+            }
+            InvocationStatus::Inboxed(_) => PartitionProcessorRpcResponse::InvocationProgress(
+                progress(InvocationProgressStatus::Inboxed, None),
+            ),
+            InvocationStatus::Invoked(_) => {
+                return Decision::ReplyWithInvokerStatus {
+                    invocation_id,
+                    progress: progress(InvocationProgressStatus::Running, None),
+                };
+            }
+            InvocationStatus::Suspended { .. } => {
+                PartitionProcessorRpcResponse::InvocationProgress(progress(
+                    InvocationProgressStatus::Suspended,
+                    None,
+                ))
+            }
+            InvocationStatus::Paused(_) => PartitionProcessorRpcResponse::InvocationProgress(
+                progress(InvocationProgressStatus::Paused, None),
+            ),
+            InvocationStatus::Completed(_) => PartitionProcessorRpcResponse::InvocationProgress(
+                progress(InvocationProgressStatus::Completed, None),
+            ),
+        };
+        Decision::Reply(Ok(reply))
+    }
+}
+
+/// Completes the progress of a running invocation with the attempts tracked by the invoker.
+pub(crate) fn merge_invoker_status(
+    mut progress: InvocationProgress,
+    report: Option<&InvocationStatusReport>,
+) -> InvocationProgress {
+    let Some(report) = report else {
+        // The invoker might not have picked up the invocation yet
+        return progress;
+    };
+
+    progress.attempts = Some(u32::try_from(report.retry_count()).unwrap_or(u32::MAX));
+    progress.last_failure = report
+        .last_retry_attempt_failure()
+        .map(|failure| failure.err.clone());
+    if !report.in_flight()
+        && let Some(next_retry_at) = report.next_retry_at()
+    {
+        progress.status = InvocationProgressStatus::BackingOff;
+        progress.next_retry_at = Some(MillisSinceEpoch::from(next_retry_at));
+    }
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches;
+    use std::future::ready;
+    use std::time::{Duration, SystemTime};
+
+    use restate_storage_api::invocation_status_table::InFlightInvocationMetadata;
+    use restate_types::errors::{InvocationError, codes};
+    use restate_worker_api::invoker::{InvocationErrorReport, InvocationStatusReportInner};
+    use test_log::test;
+
+    use super::*;
+
+    struct MockStorage {
+        status: InvocationStatus,
+    }
+
+    impl ReadInvocationStatusTable for MockStorage {
+        fn get_invocation_status(
+            &mut self,
+            _: &InvocationId,
+        ) -> impl Future<Output = restate_storage_api::Result<InvocationStatus>> + Send {
+            ready(Ok(self.status.clone()))
+        }
+
+        fn any_non_completed_invocation_in_range(
+            &mut self,
+            _: restate_types::sharding::KeyRange,
+        ) -> impl Future<Output = restate_storage_api::Result<bool>> + Send {
+            ready(Ok(false))
+        }
+    }
+
+    #[test(restate_core::test)]
+    async fn reply_not_leader_when_not_leader() {
+        let mut storage = MockStorage {
+            status: InvocationStatus::Free,
+        };
+
+        let decision = RpcHandler::handle(
+            RpcContext::new(false, PartitionId::MIN, &(), &mut storage),
+            Request {
+                invocation_id: InvocationId::mock_random(),
+            },
+        )
+        .await;
+
+        assert_matches!(
+            decision,
+            Decision::Reply(Err(PartitionProcessorRpcError::NotLeader(_)))
+        );
+    }
+
+    #[test(restate_core::test)]
+    async fn paused_invocation_replies_immediately() {
+        let mut storage = MockStorage {
+            status: InvocationStatus::Paused(InFlightInvocationMetadata::mock()),
+        };
+
+        let decision = RpcHandler::handle(
+            RpcContext::new(true, PartitionId::MIN, &(), &mut storage),
+            Request {
+                invocation_id: InvocationId::mock_random(),
+            },
+        )
+        .await;
+
+        assert_matches!(
+            decision,
+            Decision::Reply(Ok(PartitionProcessorRpcResponse::InvocationProgress(
+                InvocationProgress {
+                    status: InvocationProgressStatus::Paused,
+                    attempts: None,
+                    ..
+                }
+            )))
+        );
+    }
+
+    #[test(restate_core::test)]
+    async fn invoked_invocation_asks_the_invoker() {
+        let invocation_id = InvocationId::mock_random();
+        let mut storage = MockStorage {
+            status: InvocationStatus::Invoked(InFlightInvocationMetadata::mock()),
+        };
+
+        let decision = RpcHandler::handle(
+            RpcContext::new(true, PartitionId::MIN, &(), &mut storage),
+            Request { invocation_id },
+        )
+        .await;
+
+        assert_matches!(
+            decision,
+            Decision::ReplyWithInvokerStatus {
+                invocation_id: actual_invocation_id,
+                progress: InvocationProgress {
+                    status: InvocationProgressStatus::Running,
+                    ..
+                },
+            } if actual_invocation_id == invocation_id
+        );
+    }
+
+    #[test]
+    fn merge_backing_off_invoker_status() {
+        let next_retry_at = SystemTime::now() + Duration::from_secs(10);
+        let report = InvocationStatusReport::new(
+            InvocationId::mock_random(),
+            InvocationStatusReportInner {
+                in_flight: false,
+                start_count: 3,
+                next_retry_at: Some(next_retry_at),
+                last_retry_attempt_failure: Some(InvocationErrorReport {
+                    err: InvocationError::new(codes::INTERNAL, "boom"),
+                    doc_error_code: None,
+                    related_entry_index: None,
+                    related_entry_name: None,
+                    related_entry_type: None,
+                }),
+                ..Default::default()
+            },
+        );
+
+        let progress = merge_invoker_status(
+            InvocationProgress {
+                status: InvocationProgressStatus::Running,
+                execution_time: None,
+                attempts: None,
+                last_failure: None,
+                next_retry_at: None,
+            },
+            Some(&report),
+        );
+
+        assert_eq!(progress.status, InvocationProgressStatus::BackingOff);
+        assert_eq!(progress.attempts, Some(3));
+        assert_eq!(
+            progress.last_failure,
+            Some(InvocationError::new(codes::INTERNAL, "boom"))
+        );
+        assert_eq!(
+            progress.next_retry_at,
+            Some(MillisSinceEpoch::from(next_retry_at))
+        );
+    }
+}
@@ -13,6 +13,7 @@ mod append_invocation_response;
 mod append_signal;
 mod cancel_invocation;
 mod get_invocation_output;
+mod get_invocation_progress;
 mod kill_invocation;
 mod pause_invocation;
 mod purge_invocation;
@@ -20,6 +21,8 @@ mod purge_journal;
 mod restart_as_new_invocation;
 mod resume_invocation;
 
+pub(crate) use get_invocation_progress::merge_invoker_status;
+
 use std::sync::Arc;
 
 use restate_storage_api::invocation_status_table::ReadInvocationStatusTable;
@@ -29,6 +32,7 @@ use restate_types::identifiers::{
     InvocationId, PartitionId, PartitionKey, PartitionProcessorRpcRequestId,
 };
 use restate_types::invocation::InvocationRequest;
+use restate_types::invocation::client::InvocationProgress;
 use restate_types::net::partition_processor::{
     AppendInvocationReplyOn, PartitionProcessorRpcError, PartitionProcessorRpcRequest,
     PartitionProcessorRpcRequestInner, PartitionProcessorRpcResponse,
@@ -55,6 +59,12 @@ pub(crate) enum Decision {
         notification: InvokerNotification,
         reply: PartitionProcessorRpcResponse,
     },
+    /// Complete the progress of a running invocation with the status tracked by the invoker,
+    /// then reply.
+    ReplyWithInvokerStatus {
+        invocation_id: InvocationId,
+        progress: InvocationProgress,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -217,6 +227,10 @@ where
                 })
                 .await
             }
+            PartitionProcessorRpcRequestInner::GetInvocationProgress { invocation_id } => {
+                self.handle(get_invocation_progress::Request { invocation_id })
+                    .await
+            }
         }
     }
 }
@@ -770,6 +770,9 @@ mod tests {
             Decision::NotifyInvokerAndReply { .. } => {
                 panic!("unexpected invoker notification")
             }
+            Decision::ReplyWithInvokerStatus { .. } => {
+                panic!("unexpected invoker status read")
+            }
         };
         assert_that!(
             service_invocation,
@@ -9,25 +9,29 @@
 // by the Apache License, Version 2.0.
 
 use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 use async_channel::{TryRecvError, TrySendError};
+use futures::FutureExt;
 use metrics::{counter, gauge};
 use tokio::sync::watch;
 use tracing::debug;
 
 use restate_core::cancellation_token;
 use restate_core::network::TransportConnect;
-use restate_ingestion_client::IngestionClient;
+use restate_ingestion_client::{IngestionClient, RecordCommit};
 use restate_storage_api::deduplication_table::DedupInformation;
 use restate_storage_api::outbox_table::OutboxMessage;
 use restate_types::identifiers::{LeaderEpoch, PartitionId, PartitionKey, WithPartitionKey};
 use restate_types::message::MessageIndex;
 use restate_wal_protocol::{Destination, Envelope, Header, Source};
 
+use crate::invocation_events::InvocationEventSink;
 use crate::metric_definitions::{
     PARTITION_LABEL, PARTITION_SHUFFLE_INFLIGHT_RECORDS, PARTITION_SHUFFLE_MESSAGE_COUNT,
 };
-use crate::partition::types::OutboxMessageExt;
+use crate::partition::types::{NotACommandError, OutboxMessageExt};
 
 #[derive(Debug)]
 pub(crate) struct NewOutboxMessage {
@@ -57,15 +61,33 @@ impl OutboxTruncation {
     }
 }
 
+/// Resolves to the sequence number of an outbox message once it has been committed to its
+/// destination partition, or handed over to the invocation events sink.
+pub(crate) enum OutboxCommit {
+    Ingested(RecordCommit<MessageIndex>),
+    HandedOver(MessageIndex),
+}
+
+impl Future for OutboxCommit {
+    type Output = anyhow::Result<MessageIndex>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut() {
+            OutboxCommit::Ingested(commit) => commit.poll_unpin(cx).map_err(Into::into),
+            OutboxCommit::HandedOver(seq_number) => Poll::Ready(Ok(*seq_number)),
+        }
+    }
+}
+
 pub(crate) fn wrap_outbox_message_in_envelope(
     message: OutboxMessage,
     seq_number: MessageIndex,
     shuffle_metadata: &ShuffleMetadata,
-) -> Envelope {
-    Envelope::new(
+) -> Result<Envelope, NotACommandError> {
+    Ok(Envelope::new(
         create_header(message.partition_key(), seq_number, shuffle_metadata),
-        message.to_command(),
-    )
+        message.to_command()?,
+    ))
 }
 
 fn create_header(
@@ -167,6 +189,7 @@ pub(crate) struct Shuffle<T, OR> {
     metadata: ShuffleMetadata,
     outbox_reader: OR,
     ingestion_client: IngestionClient<T, Envelope>,
+    event_sink: InvocationEventSink,
     // used to tell partition processor about outbox truncations
     truncation_tx: watch::Sender<Option<OutboxTruncation>>,
     hint_rx: async_channel::Receiver<NewOutboxMessage>,
@@ -185,6 +208,7 @@ where
         truncation_tx: watch::Sender<Option<OutboxTruncation>>,
         channel_size: usize,
         ingestion_client: IngestionClient<T, Envelope>,
+        event_sink: InvocationEventSink,
     ) -> Self {
         let (hint_tx, hint_rx) = async_channel::bounded(channel_size);
 
@@ -195,6 +219,7 @@ where
             hint_rx,
             hint_tx,
             ingestion_client,
+            event_sink,
         }
     }
 
@@ -223,13 +248,19 @@ where
             outbox_reader,
             truncation_tx,
             ingestion_client,
+            event_sink,
             ..
         } = self;
 
         debug!(restate.partition.id = %metadata.partition_id, "Running shuffle");
 
-        let mut state_machine =
-            state_machine::StateMachine::new(metadata, ingestion_client, outbox_reader, hint_rx);
+        let mut state_machine = state_machine::StateMachine::new(
+            metadata,
+            ingestion_client,
+            event_sink,
+            outbox_reader,
+            hint_rx,
+        );
 
         let mut inflight = VecDeque::new();
 
@@ -275,13 +306,15 @@ mod state_machine {
     use tokio_util::sync::ReusableBoxFuture;
 
     use restate_core::network::TransportConnect;
-    use restate_ingestion_client::{IngestFuture, IngestionClient, RecordCommit};
+    use restate_ingestion_client::{IngestFuture, IngestionClient};
     use restate_storage_api::outbox_table::OutboxMessage;
     use restate_types::{identifiers::WithPartitionKey, message::MessageIndex};
     use restate_wal_protocol::Envelope;
 
+    use crate::invocation_events::InvocationEventSink;
     use crate::partition::shuffle::{
-        NewOutboxMessage, OutboxReaderError, ShuffleMetadata, wrap_outbox_message_in_envelope,
+        NewOutboxMessage, OutboxCommit, OutboxReaderError, ShuffleMetadata,
+        wrap_outbox_message_in_envelope,
     };
 
     type ReadFuture<OutboxReader> = ReusableBoxFuture<
@@ -307,11 +340,13 @@ mod state_machine {
         Idle,
         ReadingOutbox,
         Ingesting { ingest: IngestFuture, sn: u64 },
+        HandedOver { sn: u64 },
     }
 
     pub struct StateMachine<T, R> {
         metadata: ShuffleMetadata,
         ingestion: IngestionClient<T, Envelope>,
+        event_sink: InvocationEventSink,
         hint_rx: async_channel::Receiver<NewOutboxMessage>,
         reader: Option<R>,
         read_fut: ReadFuture<R>,
@@ -327,12 +362,14 @@ mod state_machine {
         pub fn new(
             metadata: ShuffleMetadata,
             ingestion: IngestionClient<T, Envelope>,
+            event_sink: InvocationEventSink,
             reader: R,
             hint_rx: async_channel::Receiver<NewOutboxMessage>,
         ) -> Self {
             Self {
                 metadata,
                 ingestion,
+                event_sink,
                 hint_rx,
                 reader: None,
                 read_fut: ReusableBoxFuture::new(get_next_message(reader, 0)),
@@ -341,7 +378,7 @@ mod state_machine {
             }
         }
 
-        pub async fn shuffle_next_message(&mut self) -> anyhow::Result<RecordCommit<u64>> {
+        pub async fn shuffle_next_message(&mut self) -> anyhow::Result<OutboxCommit> {
             loop {
                 match &mut self.state {
                     State::Idle => {
@@ -356,14 +393,7 @@ mod state_machine {
 
                         match sn.cmp(&self.next_sequence_number) {
                             Ordering::Equal => {
-                                let envelope =
-                                    wrap_outbox_message_in_envelope(message, sn, &self.metadata);
-                                self.state = State::Ingesting {
-                                    ingest: self
-                                        .ingestion
-                                        .ingest(envelope.partition_key(), envelope),
-                                    sn,
-                                };
+                                self.state = self.send(message, sn)?;
                             }
                             Ordering::Greater => {
                                 // Missed hints; we need to do an outbox scan
@@ -382,15 +412,14 @@ mod state_machine {
                         let sn = *sn;
                         let commit_token = ingest.await?.map(|_| sn);
 
-                        // read next message
-                        self.next_sequence_number = sn + 1;
-                        self.read_fut.set(get_next_message(
-                            self.reader.take().unwrap(),
-                            self.next_sequence_number,
-                        ));
-                        self.state = State::ReadingOutbox;
+                        self.read_next_message(sn);
+                        return Ok(OutboxCommit::Ingested(commit_token));
+                    }
+                    State::HandedOver { sn } => {
+                        let sn = *sn;
 
-                        return Ok(commit_token);
+                        self.read_next_message(sn);
+                        return Ok(OutboxCommit::HandedOver(sn));
                     }
                     State::ReadingOutbox => {
                         let (result, reader) = self.read_fut.get_pin().await;
@@ -405,20 +434,41 @@ mod state_machine {
                                     sn >= self.next_sequence_number,
                                     "message sequence numbers must not decrease"
                                 );
-                                let envelope =
-                                    wrap_outbox_message_in_envelope(message, sn, &self.metadata);
-                                self.state = State::Ingesting {
-                                    ingest: self
-                                        .ingestion
-                                        .ingest(envelope.partition_key(), envelope),
-                                    sn,
-                                };
+                                self.state = self.send(message, sn)?;
                             }
                         }
                     }
                 }
             }
         }
+
+        /// Invocation events are handed over to the event sink without waiting for their
+        /// delivery, all other messages are ingested into the partition of their destination.
+        fn send(&self, message: OutboxMessage, sn: MessageIndex) -> anyhow::Result<State> {
+            Ok(match message {
+                OutboxMessage::InvocationEvent(event) => {
+                    self.event_sink
+                        .deliver(self.metadata.partition_id, sn, *event);
+                    State::HandedOver { sn }
+                }
+                message => {
+                    let envelope = wrap_outbox_message_in_envelope(message, sn, &self.metadata)?;
+                    State::Ingesting {
+                        ingest: self.ingestion.ingest(envelope.partition_key(), envelope),
+                        sn,
+                    }
+                }
+            })
+        }
+
+        fn read_next_message(&mut self, sent_sn: MessageIndex) {
+            self.next_sequence_number = sent_sn + 1;
+            self.read_fut.set(get_next_message(
+                self.reader.take().unwrap(),
+                self.next_sequence_number,
+            ));
+            self.state = State::ReadingOutbox;
+        }
     }
 }
 
@@ -451,11 +501,14 @@ mod ingestion_client_tests {
     use restate_storage_api::outbox_table::OutboxMessage;
     use restate_types::Version;
     use restate_types::identifiers::{InvocationId, LeaderEpoch, PartitionId};
-    use restate_types::invocation::ServiceInvocation;
+    use restate_types::invocation::events::{InvocationEvent, InvocationEventKind};
+    use restate_types::invocation::{InvocationTarget, ServiceInvocation};
     use restate_types::message::MessageIndex;
     use restate_types::partition_table::PartitionTable;
+    use restate_types::time::MillisSinceEpoch;
     use restate_wal_protocol::{Command, Envelope};
 
+    use crate::invocation_events::InvocationEventSink;
     use crate::partition::shuffle::{OutboxReader, OutboxReaderError, Shuffle, ShuffleMetadata};
 
     struct MockOutboxReader {
@@ -511,6 +564,21 @@ mod ingestion_client_tests {
         }
     }
 
+    /// Outbox reader over consecutive messages, starting at sequence number 0.
+    struct MessagesOutboxReader(Vec<OutboxMessage>);
+
+    impl OutboxReader for MessagesOutboxReader {
+        async fn get_next_message(
+            &mut self,
+            next_sequence_number: MessageIndex,
+        ) -> Result<Option<(MessageIndex, OutboxMessage)>, OutboxReaderError> {
+            Ok(self
+                .0
+                .get(usize::try_from(next_sequence_number).expect("index should fit in usize"))
+                .map(|message| (next_sequence_number, message.clone())))
+        }
+    }
+
     /// Outbox reader which is used to let the shuffler fail in a controlled manner so that we
     /// can simulate restarts.
     struct FailingOutboxReader {
@@ -628,6 +696,13 @@ mod ingestion_client_tests {
 
     async fn create_shuffle_env<OR: OutboxReader + Send + Sync + 'static>(
         outbox_reader: OR,
+    ) -> ShuffleEnv<OR> {
+        create_shuffle_env_with_event_sink(outbox_reader, InvocationEventSink::disabled()).await
+    }
+
+    async fn create_shuffle_env_with_event_sink<OR: OutboxReader + Send + Sync + 'static>(
+        outbox_reader: OR,
+        event_sink: InvocationEventSink,
     ) -> ShuffleEnv<OR> {
         // set numbers of partitions to 1 to easily find all sent messages by the shuffle
         let mut builder = TestCoreEnvBuilder::with_incoming_only_connector().set_partition_table(
@@ -664,7 +739,14 @@ mod ingestion_client_tests {
 
         let (truncation_tx, _truncation_rx) = watch::channel(None);
 
-        let shuffle = Shuffle::new(metadata, outbox_reader, truncation_tx, 1, ingestion.clone());
+        let shuffle = Shuffle::new(
+            metadata,
+            outbox_reader,
+            truncation_tx,
+            1,
+            ingestion.clone(),
+            event_sink,
+        );
 
         ShuffleEnv {
             env,
@@ -732,6 +814,39 @@ mod ingestion_client_tests {
         Ok(())
     }
 
+    fn mock_invocation_event() -> OutboxMessage {
+        OutboxMessage::InvocationEvent(Box::new(InvocationEvent {
+            invocation_id: InvocationId::mock_random(),
+            invocation_target: InvocationTarget::mock_service(),
+            timestamp: MillisSinceEpoch::now(),
+            kind: InvocationEventKind::Completed,
+        }))
+    }
+
+    #[test(restate_core::test)]
+    async fn unavailable_event_sink_does_not_block_outbox() -> anyhow::Result<()> {
+        let service_invocation = ServiceInvocation::mock();
+        let last_invocation_id = service_invocation.invocation_id;
+
+        let outbox_reader = MessagesOutboxReader(vec![
+            mock_invocation_event(),
+            mock_invocation_event(),
+            OutboxMessage::ServiceInvocation(Box::new(service_invocation.clone())),
+        ]);
+        // the buffer is never drained, so the second event doesn't fit anymore
+        let (event_sink, _pending_events) = InvocationEventSink::with_buffer(1);
+        let mut shuffle_env = create_shuffle_env_with_event_sink(outbox_reader, event_sink).await;
+
+        TaskCenter::spawn_child(TaskKind::Shuffle, "shuffle", shuffle_env.shuffle.run())?;
+
+        let messages =
+            collect_invoke_commands_until(&mut shuffle_env.stream, last_invocation_id).await?;
+
+        assert_received_invoke_commands(messages, vec![Some(service_invocation)]);
+
+        Ok(())
+    }
+
     #[test(restate_core::test)]
     async fn shuffle_with_restarts() -> anyhow::Result<()> {
         let expected_messages: Vec<_> = iter::repeat_with(|| Some(ServiceInvocation::mock()))
@@ -783,6 +898,7 @@ mod ingestion_client_tests {
                         truncation_tx.clone(),
                         1,
                         shuffle_env.ingestion.clone(),
+                        InvocationEventSink::disabled(),
                     );
                 }
 
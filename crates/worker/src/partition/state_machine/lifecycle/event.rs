@@ -8,12 +8,15 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::invocation_status_table::InvocationStatus;
 use restate_storage_api::journal_events::{EventView, WriteJournalEventsTable};
+use restate_storage_api::outbox_table::WriteOutboxTable;
 use restate_types::identifiers::InvocationId;
+use restate_types::invocation::events::InvocationEventKind;
 use restate_types::journal_events::raw::RawEvent;
 
-use crate::partition::processor::Processor;
+use crate::partition::processor::ProcessorContext;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
 
 pub struct ApplyEventCommand<'e> {
@@ -22,8 +25,11 @@ pub struct ApplyEventCommand<'e> {
     pub event: RawEvent,
 }
 
-impl<'e, 'ctx: 'e, 's: 'ctx, S: WriteJournalEventsTable, P: Processor>
-    CommandHandler<&'ctx mut StateMachineApplyContext<'s, S, P>> for ApplyEventCommand<'e>
+impl<'e, 'ctx: 'e, 's: 'ctx, S, P> CommandHandler<&'ctx mut StateMachineApplyContext<'s, S, P>>
+    for ApplyEventCommand<'e>
+where
+    S: WriteJournalEventsTable + WriteOutboxTable + WriteFsmTable,
+    P: ProcessorContext,
 {
     async fn apply(self, ctx: &'ctx mut StateMachineApplyContext<'s, S, P>) -> Result<(), Error> {
         let Some(journal_metadata) = self.invocation_status.get_journal_metadata() else {
@@ -34,6 +40,14 @@ impl<'e, 'ctx: 'e, 's: 'ctx, S: WriteJournalEventsTable, P: Processor>
         // To store the event, we need to give it a total order wrt journal.
         let after_journal_entry_index = journal_metadata.length.saturating_sub(1);
 
+        if let Some(invocation_target) = self.invocation_status.invocation_target() {
+            ctx.emit_invocation_event(
+                *self.invocation_id,
+                invocation_target,
+                InvocationEventKind::JournalEvent(self.event.clone()),
+            )?;
+        }
+
         // Store event
         ctx.storage.put_journal_event(
             self.invocation_id,
@@ -8,11 +8,13 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::invocation_status_table::{
     InvocationStatus, ReadInvocationStatusTable, WriteInvocationStatusTable,
 };
 use restate_storage_api::journal_events::WriteJournalEventsTable;
 use restate_storage_api::lock_table::WriteLockTable;
+use restate_storage_api::outbox_table::WriteOutboxTable;
 use restate_storage_api::vqueue_table::{ReadVQueueTable, WriteVQueueTable};
 use restate_types::identifiers::InvocationId;
 use restate_types::invocation::InvocationMutationResponseSink;
@@ -42,6 +44,8 @@ where
     S: ReadInvocationStatusTable
         + WriteInvocationStatusTable
         + WriteJournalEventsTable
+        + WriteOutboxTable
+        + WriteFsmTable
         + WriteVQueueTable
         + ReadVQueueTable
         + WriteLockTable,
@@ -11,21 +11,22 @@
 use tracing::debug;
 
 use restate_clock::UniqueTimestamp;
+use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::invocation_status_table::{
     InFlightInvocationMetadata, InvocationStatus, ReadInvocationStatusTable,
     WriteInvocationStatusTable,
 };
 use restate_storage_api::journal_events::WriteJournalEventsTable;
 use restate_storage_api::lock_table::WriteLockTable;
+use restate_storage_api::outbox_table::WriteOutboxTable;
 use restate_storage_api::vqueue_table::{EntryStatusHeader, ReadVQueueTable, WriteVQueueTable};
 use restate_types::identifiers::{InvocationId, WithPartitionKey as _};
 use restate_types::journal_events::raw::RawEvent;
 use restate_types::vqueues::EntryId;
 use restate_vqueues::VQueue;
-use restate_vqueues::context::HasVQueuesMut;
 
 use crate::debug_if_leader;
-use crate::partition::processor::{Processor, ProcessorContext};
+use crate::partition::processor::ProcessorContext;
 use crate::partition::state_machine::lifecycle::event::ApplyEventCommand;
 use crate::partition::state_machine::{CommandHandler, Error, StateMachineApplyContext};
 
@@ -40,6 +41,8 @@ where
     S: ReadInvocationStatusTable
         + WriteInvocationStatusTable
         + WriteJournalEventsTable
+        + WriteOutboxTable
+        + WriteFsmTable
         + WriteVQueueTable
         + WriteLockTable
         + ReadVQueueTable,
@@ -85,10 +88,12 @@ pub(crate) async fn pause_invocation<'ctx, 's: 'ctx, S, P>(
 where
     S: WriteInvocationStatusTable
         + WriteJournalEventsTable
+        + WriteOutboxTable
+        + WriteFsmTable
         + WriteVQueueTable
         + WriteLockTable
         + ReadVQueueTable,
-    P: Processor + HasVQueuesMut,
+    P: ProcessorContext,
 {
     debug_if_leader!(ctx.is_leader, "Paused the invocation");
 
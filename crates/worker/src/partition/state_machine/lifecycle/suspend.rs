@@ -13,10 +13,12 @@ use std::collections::HashMap;
 use tracing::{debug, trace};
 
 use restate_clock::UniqueTimestamp;
+use restate_storage_api::fsm_table::WriteFsmTable;
 use restate_storage_api::invocation_status_table::{InvocationStatus, WriteInvocationStatusTable};
 use restate_storage_api::journal_events::WriteJournalEventsTable;
 use restate_storage_api::journal_table_v2::ReadJournalTable;
 use restate_storage_api::lock_table::WriteLockTable;
+use restate_storage_api::outbox_table::WriteOutboxTable;
 use restate_storage_api::vqueue_table::{EntryStatusHeader, ReadVQueueTable, WriteVQueueTable};
 use restate_types::identifiers::{InvocationId, WithPartitionKey};
 use restate_types::journal_events::raw::RawEvent;
@@ -43,6 +45,8 @@ where
     S: ReadJournalTable
         + WriteInvocationStatusTable
         + WriteJournalEventsTable
+        + WriteOutboxTable
+        + WriteFsmTable
         + WriteVQueueTable
         + ReadVQueueTable
         + WriteLockTable,
@@ -11,6 +11,8 @@
 mod actions;
 mod entries;
 mod lifecycle;
+#[cfg(any(test, feature = "test-util"))]
+pub mod simulation;
 mod utils;
 
 pub use actions::{Action, ActionCollector};
@@ -83,6 +85,9 @@ use restate_types::invocation::client::{
     CancelInvocationResponse, InvocationOutputResponse, KillInvocationResponse,
     PauseInvocationResponse, PurgeInvocationResponse, ResumeInvocationResponse,
 };
+use restate_types::invocation::events::{
+    InvocationEvent, InvocationEventKind, InvocationEventType,
+};
 use restate_types::invocation::{
     AttachInvocationRequest, IngressInvocationResponseSink, InvocationInput,
     InvocationMutationResponseSink, InvocationQuery, InvocationResponse, InvocationTarget,
@@ -109,6 +114,7 @@ use restate_types::journal_v2::{
 };
 use restate_types::logs::Lsn;
 use restate_types::message::MessageIndex;
+use restate_types::schema::invocation_target::InvocationTargetResolver;
 use restate_types::service_protocol::ServiceProtocolVersion;
 use restate_types::state_mut::ExternalStateMutation;
 use restate_types::state_mut::StateMutationVersion;
@@ -2724,6 +2730,7 @@ impl<S, P: ProcessorContext> StateMachineApplyContext<'_, S, P> {
 
         let vqueue_id = invocation_metadata.vqueue_id.clone();
         let mut end_status = vqueue_table::Status::Succeeded;
+        let emit_end_event = self.emits_invocation_end_event(&invocation_target);
         // If there are any response sinks, or we need to store back the completed status,
         //  we need to find the latest output entry
         if !invocation_metadata.response_sinks.is_empty() || !completion_retention.is_zero() {
@@ -2781,6 +2788,13 @@ impl<S, P: ProcessorContext> StateMachineApplyContext<'_, S, P> {
                     ResponseResult::Failure(err) => Err(err),
                 },
             );
+            if emit_end_event {
+                self.emit_invocation_end_event(
+                    invocation_id,
+                    &invocation_target,
+                    &response_result,
+                )?;
+            }
 
             // Store the completed status, if needed
             if !completion_retention.is_zero() {
@@ -2804,6 +2818,27 @@ impl<S, P: ProcessorContext> StateMachineApplyContext<'_, S, P> {
                 &invocation_metadata.journal_metadata.span_context,
                 Ok(()),
             );
+
+            // The output entry is only needed for the invocation end event
+            if emit_end_event
+                && let Some(response_result) = match response_result_override {
+                    Some(response_result) => Some(response_result),
+                    None => {
+                        self.read_last_output_entry_result(
+                            &invocation_id,
+                            journal_length,
+                            pinned_service_protocol_version.unwrap_or_default(),
+                        )
+                        .await?
+                    }
+                }
+            {
+                self.emit_invocation_end_event(
+                    invocation_id,
+                    &invocation_target,
+                    &response_result,
+                )?;
+            }
         }
 
         // If no retention, immediately cleanup the invocation status
@@ -4167,6 +4202,18 @@ impl<S, P: ProcessorContext> StateMachineApplyContext<'_, S, P> {
             span_context,
         );
 
+        // Failures of unsampled invocations get a standalone marker span if their target asks
+        // for it.
+        if invocation_result.is_err()
+            && !end_span.is_recording()
+            && self.emits_failure_markers(invocation_target)
+        {
+            end_span = instrumentation::create_invocation_failure_marker_span(
+                invocation_id,
+                invocation_target,
+            );
+        }
+
         if end_span.is_recording() {
             match invocation_result {
                 Err(err) => {
@@ -4199,6 +4246,92 @@ impl<S, P: ProcessorContext> StateMachineApplyContext<'_, S, P> {
         }
     }
 
+    fn emits_failure_markers(&self, invocation_target: &InvocationTarget) -> bool {
+        self.processor
+            .fsm()
+            .schema()
+            .and_then(|schema| {
+                schema.resolve_latest_invocation_target(
+                    invocation_target.service_name(),
+                    invocation_target.handler_name(),
+                )
+            })
+            .is_some_and(|target| target.tracing_sampling.failure_markers)
+    }
+
+    fn emits_invocation_event(
+        &self,
+        invocation_target: &InvocationTarget,
+        event_type: InvocationEventType,
+    ) -> bool {
+        self.processor
+            .fsm()
+            .features()
+            .is_invocation_events_enabled()
+            && self
+                .processor
+                .fsm()
+                .schema()
+                .and_then(|schema| {
+                    schema.resolve_latest_invocation_target(
+                        invocation_target.service_name(),
+                        invocation_target.handler_name(),
+                    )
+                })
+                .is_some_and(|target| target.invocation_events.contains(event_type))
+    }
+
+    /// Enqueues the event into the outbox, if the partition enabled invocation events and the
+    /// target of the invocation opted in to its type. The shuffle of the leader hands it over to
+    /// the configured invocation events sink.
+    fn emit_invocation_event(
+        &mut self,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        kind: InvocationEventKind,
+    ) -> Result<(), Error>
+    where
+        S: WriteOutboxTable + WriteFsmTable,
+    {
+        let Some(event_type) = kind.event_type() else {
+            return Ok(());
+        };
+        if !self.emits_invocation_event(invocation_target, event_type) {
+            return Ok(());
+        }
+
+        self.do_enqueue_into_outbox(OutboxMessage::InvocationEvent(Box::new(InvocationEvent {
+            invocation_id,
+            invocation_target: invocation_target.clone(),
+            timestamp: self.record_created_at,
+            kind,
+        })))
+    }
+
+    fn emits_invocation_end_event(&self, invocation_target: &InvocationTarget) -> bool {
+        self.emits_invocation_event(invocation_target, InvocationEventType::Completed)
+            || self.emits_invocation_event(invocation_target, InvocationEventType::Failed)
+    }
+
+    fn emit_invocation_end_event(
+        &mut self,
+        invocation_id: InvocationId,
+        invocation_target: &InvocationTarget,
+        response_result: &ResponseResult,
+    ) -> Result<(), Error>
+    where
+        S: WriteOutboxTable + WriteFsmTable,
+    {
+        self.emit_invocation_event(
+            invocation_id,
+            invocation_target,
+            match response_result {
+                ResponseResult::Success(_) => InvocationEventKind::Completed,
+                ResponseResult::Failure(err) => InvocationEventKind::Failed(err.clone()),
+            },
+        )
+    }
+
     async fn handle_attach_invocation_request(
         &mut self,
         attach_invocation_request: AttachInvocationRequest,
@@ -4702,6 +4835,14 @@ impl<S, P: ProcessorContext> StateMachineApplyContext<'_, S, P> {
                         "Notifying signal to {invocation_id} with signal id {:?}", signal.id,
                     )
                 }
+                OutboxMessage::InvocationEvent(event) => {
+                    debug!(
+                        restate.invocation.id = %event.invocation_id,
+                        restate.outbox.seq = seq_number,
+                        "Effect: Enqueuing invocation event '{:?}'",
+                        event.event_type(),
+                    )
+                }
             }
         }
 
@@ -0,0 +1,1060 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Deterministic simulation of the partition processor state machine.
+//!
+//! [`Simulation`] drives [`StateMachine::apply`] with randomized interleavings of new
+//! invocations, invoker effects, timers, messages delivered through the outbox, cancellations
+//! and leadership changes, playing the role of the invoker, the timer service, the shuffle and
+//! (with vqueues enabled) the scheduler. All decisions are derived from a single seed and the
+//! records are stamped by a simulated clock, so running the same seed again reproduces the same
+//! interleaving. After every step, the partition store is checked against the [`Violation`]
+//! invariants.
+//!
+//! Failing seeds can be reproduced by setting [`SEED_ENV`] for the simulation tests.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{RngExt, SeedableRng};
+
+use restate_partition_store::PartitionStore;
+use restate_service_protocol_v4::entry_codec::ServiceProtocolV4Codec;
+use restate_storage_api::inbox_table::{InboxEntry, ReadInboxTable};
+use restate_storage_api::invocation_status_table::{
+    InvocationStatus, InvocationStatusDiscriminants, ReadInvocationStatusTable,
+};
+use restate_storage_api::outbox_table::{OutboxMessage, ReadOutboxTable};
+use restate_storage_api::service_status_table::{
+    ReadVirtualObjectStatusTable, VirtualObjectStatus,
+};
+use restate_storage_api::timer_table::ReadTimerTable;
+use restate_storage_api::vqueue_table::metadata::VQueueMeta;
+use restate_storage_api::vqueue_table::scheduler::{
+    RunAction, SchedulerAction, SchedulerDecisionsCommand,
+};
+use restate_storage_api::vqueue_table::{EntryKey, ReadVQueueTable, ScanVQueueEntries, Stage};
+use restate_storage_api::{StorageError, Transaction};
+use restate_types::SemanticRestateVersion;
+use restate_types::deployment::PinnedDeployment;
+use restate_types::errors::{InvocationError, codes};
+use restate_types::identifiers::{
+    DeploymentId, InvocationId, InvocationUuid, PartitionProcessorRpcRequestId, ServiceId,
+    WithPartitionKey,
+};
+use restate_types::invocation::{
+    InvocationTarget, InvocationTermination, ServiceInvocation, Source, TerminationFlavor,
+    VirtualObjectHandlerType,
+};
+use restate_types::journal_v2::{
+    CallCommand, CallRequest, CompletionId, Entry, NotificationId, SleepCommand,
+};
+use restate_types::logs::{Keys, Lsn, SequenceNumber};
+use restate_types::message::MessageIndex;
+use restate_types::partitions::PersistedFeatures;
+use restate_types::service_protocol::ServiceProtocolVersion;
+use restate_types::sharding::KeyRange;
+use restate_types::time::MillisSinceEpoch;
+use restate_types::vqueues::VQueueId;
+use restate_vqueues::context::HasVQueues;
+use restate_wal_protocol::timer::TimerKeyValue;
+use restate_wal_protocol::v2::{Dedup, Envelope, Raw, commands};
+use restate_worker_api::invoker::Effect;
+
+use super::{Action, ActionCollector, DataRecord, Error, StateMachine};
+use crate::partition::ProcessorError;
+use crate::partition::processor::{
+    FsmMut, HasFsmMut, HasOutboxMut, OutboxMut, ProcessorRawContext,
+};
+use crate::partition::types::InvokerEffectKind;
+
+/// Environment variable to run the simulation tests with a single seed only, e.g. to reproduce
+/// a failure.
+pub const SEED_ENV: &str = "RESTATE_SIMULATION_SEED";
+
+/// Number of steps kept in the trace of a [`SimulationFailure`].
+const TRACE_LEN: usize = 64;
+/// Upper bound for the number of invocations which are not completed yet.
+const MAX_INVOCATIONS: usize = 16;
+const START_TIME: MillisSinceEpoch = MillisSinceEpoch::new(1_700_000_000_000);
+
+const SERVICE: &str = "Greeter";
+const OBJECT: &str = "Counter";
+const OBJECT_KEYS: [&str; 2] = ["a", "b"];
+
+/// Returns the seed set in [`SEED_ENV`], if any.
+pub fn seed_from_env() -> Option<u64> {
+    let seed = std::env::var(SEED_ENV).ok()?;
+    Some(
+        seed.parse()
+            .unwrap_or_else(|_| panic!("{SEED_ENV} must be an unsigned integer, got {seed}")),
+    )
+}
+
+/// Invariant of the partition store that the state machine must uphold after every command.
+#[derive(Debug, thiserror::Error)]
+pub enum Violation {
+    #[error("invocation {invocation_id} is in the inbox of {service_id} with status {status:?}")]
+    InboxedInvocationNotInboxed {
+        service_id: ServiceId,
+        invocation_id: InvocationId,
+        status: Option<InvocationStatusDiscriminants>,
+    },
+    #[error("{service_id} is unlocked but its inbox is not empty")]
+    UnlockedWithInbox { service_id: ServiceId },
+    #[error("{service_id} is locked by invocation {invocation_id} with status {status:?}")]
+    LockedByInvocationNotInFlight {
+        service_id: ServiceId,
+        invocation_id: InvocationId,
+        status: Option<InvocationStatusDiscriminants>,
+    },
+    #[error("invocation {invocation_id} is in flight without holding the lock of {service_id}")]
+    InFlightWithoutLock {
+        service_id: ServiceId,
+        invocation_id: InvocationId,
+    },
+    #[error("the invoker was asked to run invocation {invocation_id} with status {status:?}")]
+    InvokedInvocationNotInvoked {
+        invocation_id: InvocationId,
+        status: Option<InvocationStatusDiscriminants>,
+    },
+    #[error(
+        "invocation {invocation_id} is in stage {stage} of vqueue {qid} with status {status:?}"
+    )]
+    VQueueEntryNotInFlight {
+        qid: VQueueId,
+        stage: Stage,
+        invocation_id: InvocationId,
+        status: Option<InvocationStatusDiscriminants>,
+    },
+    #[error("the {origin} of vqueue {qid} counts {counted:?} but it has {actual:?} entries")]
+    VQueueCounts {
+        qid: VQueueId,
+        origin: &'static str,
+        counted: StageCounts,
+        actual: StageCounts,
+    },
+}
+
+/// Number of entries of a vqueue per stage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageCounts {
+    pub inbox: u64,
+    pub running: u64,
+    pub suspended: u64,
+    pub paused: u64,
+}
+
+impl StageCounts {
+    fn of(meta: &VQueueMeta) -> Self {
+        let stats = meta.stats();
+        Self {
+            inbox: stats.num_inbox(),
+            running: stats.num_running(),
+            suspended: stats.num_suspended(),
+            paused: stats.num_paused(),
+        }
+    }
+
+    fn increment(&mut self, stage: Stage) {
+        match stage {
+            Stage::Inbox => self.inbox += 1,
+            Stage::Running => self.running += 1,
+            Stage::Suspended => self.suspended += 1,
+            Stage::Paused => self.paused += 1,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FailureCause {
+    #[error("state machine failed: {0}")]
+    StateMachine(#[from] Error),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("invariant violated: {0}")]
+    Violation(#[from] Violation),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "simulation with seed {seed} failed in step {step}: {cause}\nrerun with {SEED_ENV}={seed} \
+    to reproduce, last steps:\n{}",
+    .trace.join("\n")
+)]
+pub struct SimulationFailure {
+    pub seed: u64,
+    pub step: usize,
+    pub cause: FailureCause,
+    pub trace: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StepKind {
+    NewInvocation,
+    InvokerEffect,
+    FireTimer,
+    DeliverOutbox,
+    RunVQueueEntry,
+    Terminate,
+    ChangeLeadership,
+}
+
+/// What the simulated invoker knows about an invocation.
+struct InvocationModel {
+    /// Whether the invoker is currently executing the invocation.
+    running: bool,
+    pinned: bool,
+    next_completion_id: CompletionId,
+    /// Completion ids of the journal commands the invocation can await.
+    awaitable: Vec<CompletionId>,
+}
+
+impl InvocationModel {
+    fn new() -> Self {
+        Self {
+            running: false,
+            pinned: false,
+            next_completion_id: 1,
+            awaitable: Vec::new(),
+        }
+    }
+}
+
+/// Drives the state machine of a single partition covering the full key range.
+pub struct Simulation {
+    seed: u64,
+    rng: StdRng,
+    clock: MillisSinceEpoch,
+    lsn: Lsn,
+    is_leader: bool,
+    processor: ProcessorRawContext,
+    storage: PartitionStore,
+    vqueues: bool,
+    /// Next outbox message to deliver.
+    outbox_head: MessageIndex,
+    /// Invocations which are not completed yet.
+    invocations: BTreeMap<InvocationId, InvocationModel>,
+    trace: VecDeque<String>,
+}
+
+impl Simulation {
+    /// Creates a simulation on top of an empty partition store, with the given features enabled.
+    pub async fn new(
+        seed: u64,
+        features: PersistedFeatures,
+        mut storage: PartitionStore,
+    ) -> Result<Self, ProcessorError> {
+        let mut processor =
+            ProcessorRawContext::create(SemanticRestateVersion::current(), &mut storage).await?;
+
+        let mut txn = storage.transaction();
+        processor.fsm_mut().set_enabled_features(&mut txn, features);
+        txn.commit().await?;
+
+        Ok(Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            clock: START_TIME,
+            lsn: Lsn::OLDEST,
+            is_leader: true,
+            processor,
+            storage,
+            vqueues: features.vqueues,
+            outbox_head: 0,
+            invocations: BTreeMap::new(),
+            trace: VecDeque::with_capacity(TRACE_LEN),
+        })
+    }
+
+    /// The last steps of the simulation, oldest first.
+    pub fn trace(&self) -> impl Iterator<Item = &str> {
+        self.trace.iter().map(String::as_str)
+    }
+
+    /// Runs the given number of steps, checking the invariants after each of them.
+    pub async fn run(&mut self, steps: usize) -> Result<(), SimulationFailure> {
+        for step in 0..steps {
+            let result = match self.step().await {
+                Ok(description) => {
+                    self.record(format!(
+                        "{step:>5} @ {}: {description}",
+                        self.clock.as_u64()
+                    ));
+                    self.check_invariants().await
+                }
+                Err(cause) => Err(cause),
+            };
+
+            if let Err(cause) = result {
+                return Err(SimulationFailure {
+                    seed: self.seed,
+                    step,
+                    cause,
+                    trace: self.trace.drain(..).collect(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record(&mut self, description: String) {
+        if self.trace.len() == TRACE_LEN {
+            self.trace.pop_front();
+        }
+        self.trace.push_back(description);
+    }
+
+    async fn step(&mut self) -> Result<String, FailureCause> {
+        self.clock = self.clock + Duration::from_millis(self.rng.random_range(0..200));
+
+        let mut candidates = vec![(StepKind::ChangeLeadership, 2)];
+        if self.invocations.len() < MAX_INVOCATIONS {
+            candidates.push((StepKind::NewInvocation, 15));
+        }
+        if !self.invocations.is_empty() {
+            candidates.push((StepKind::Terminate, 3));
+        }
+        // the invoker, the timer service, the shuffle and the scheduler only run on the leader
+        if self.is_leader {
+            if self
+                .invocations
+                .values()
+                .any(|invocation| invocation.running)
+            {
+                candidates.push((StepKind::InvokerEffect, 40));
+            }
+            if self.next_timer().await?.is_some() {
+                candidates.push((StepKind::FireTimer, 10));
+            }
+            if self.next_outbox_message().await?.is_some() {
+                candidates.push((StepKind::DeliverOutbox, 15));
+            }
+            if self.vqueues && !self.runnable_vqueue_entries().await?.is_empty() {
+                candidates.push((StepKind::RunVQueueEntry, 15));
+            }
+        }
+
+        let (kind, _) = *candidates
+            .choose_weighted(&mut self.rng, |(_, weight)| *weight)
+            .expect("there is always a candidate");
+
+        match kind {
+            StepKind::NewInvocation => self.new_invocation().await,
+            StepKind::InvokerEffect => self.invoker_effect().await,
+            StepKind::FireTimer => self.fire_timer().await,
+            StepKind::DeliverOutbox => self.deliver_outbox().await,
+            StepKind::RunVQueueEntry => self.run_vqueue_entry().await,
+            StepKind::Terminate => self.terminate().await,
+            StepKind::ChangeLeadership => self.change_leadership().await,
+        }
+    }
+
+    async fn apply(&mut self, envelope: Envelope<Raw>) -> Result<(), FailureCause> {
+        self.lsn = self.lsn.next();
+
+        let mut action_collector = ActionCollector::default();
+        let mut txn = self.storage.transaction();
+        StateMachine::apply(
+            &mut self.processor,
+            &mut txn,
+            DataRecord::new(self.clock.into(), Keys::None, self.lsn, envelope),
+            &mut action_collector,
+            self.is_leader,
+        )
+        .await?;
+        txn.commit().await?;
+
+        for action in action_collector {
+            match action {
+                Action::Invoke { invocation_id, .. } => self.on_invoke(invocation_id).await?,
+                Action::VQInvoke { vq_handle, key, .. } => {
+                    let vqueues = self.processor.vqueues();
+                    let slot = vqueues
+                        .get(vq_handle)
+                        .expect("vqueue of invoked entry is cached");
+                    let invocation_id = key
+                        .entry_id()
+                        .to_invocation_id(slot.partition_key())
+                        .expect("only invocations are invoked");
+                    self.on_invoke(invocation_id).await?;
+                }
+                Action::AbortInvocation { invocation_id } => {
+                    if let Some(invocation) = self.invocations.get_mut(&invocation_id) {
+                        invocation.running = false;
+                    }
+                }
+                // the outbox and the timers are read from storage
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn on_invoke(&mut self, invocation_id: InvocationId) -> Result<(), FailureCause> {
+        let status = self.storage.get_invocation_status(&invocation_id).await?;
+        if !matches!(status, InvocationStatus::Invoked(_)) {
+            return Err(Violation::InvokedInvocationNotInvoked {
+                invocation_id,
+                status: status.discriminant(),
+            }
+            .into());
+        }
+
+        self.invocations
+            .entry(invocation_id)
+            .or_insert_with(InvocationModel::new)
+            .running = true;
+        Ok(())
+    }
+
+    fn random_target(&mut self) -> InvocationTarget {
+        match self.rng.random_range(0..3) {
+            0 => InvocationTarget::service(SERVICE, "greet"),
+            1 => InvocationTarget::virtual_object(
+                OBJECT,
+                *OBJECT_KEYS.choose(&mut self.rng).unwrap(),
+                "get",
+                VirtualObjectHandlerType::Shared,
+            ),
+            _ => InvocationTarget::virtual_object(
+                OBJECT,
+                *OBJECT_KEYS.choose(&mut self.rng).unwrap(),
+                "add",
+                VirtualObjectHandlerType::Exclusive,
+            ),
+        }
+    }
+
+    /// Generates the id from the seeded rng instead of [`InvocationId::generate`].
+    fn random_invocation_id(&mut self, target: &InvocationTarget) -> InvocationId {
+        let partition_key = match target.as_keyed_service_id() {
+            Some(service_id) => service_id.partition_key(),
+            None => self.rng.random(),
+        };
+        InvocationId::from_parts(
+            partition_key,
+            InvocationUuid::from(self.rng.random::<u128>()),
+        )
+    }
+
+    async fn new_invocation(&mut self) -> Result<String, FailureCause> {
+        let target = self.random_target();
+        let invocation_id = self.random_invocation_id(&target);
+        let request_id =
+            PartitionProcessorRpcRequestId::from_parts(self.clock.as_u64(), self.rng.random());
+
+        self.invocations
+            .insert(invocation_id, InvocationModel::new());
+        self.apply(
+            Envelope::new(
+                Dedup::None,
+                commands::InvokeCommand::from(ServiceInvocation::initialize(
+                    invocation_id,
+                    target.clone(),
+                    Source::Ingress(request_id),
+                )),
+            )
+            .into_raw(),
+        )
+        .await?;
+
+        Ok(format!("invoke {invocation_id} on {target}"))
+    }
+
+    async fn invoker_effect(&mut self) -> Result<String, FailureCause> {
+        let running: Vec<_> = self
+            .invocations
+            .iter()
+            .filter(|(_, invocation)| invocation.running)
+            .map(|(invocation_id, _)| *invocation_id)
+            .collect();
+        let invocation_id = *running.choose(&mut self.rng).unwrap();
+        let callee_target = self.random_target();
+        let callee_id = self.random_invocation_id(&callee_target);
+        let choice = self.rng.random_range(0..100);
+        let wake_up_time = self.clock + Duration::from_millis(self.rng.random_range(1..5_000));
+
+        let invocation = self.invocations.get_mut(&invocation_id).unwrap();
+        let (kind, description) = if !invocation.pinned {
+            invocation.pinned = true;
+            (
+                InvokerEffectKind::PinnedDeployment(PinnedDeployment {
+                    deployment_id: DeploymentId::default(),
+                    service_protocol_version: ServiceProtocolVersion::V5,
+                }),
+                "pin deployment".to_owned(),
+            )
+        } else if choice < 30 {
+            let completion_id = invocation.next_completion_id;
+            invocation.next_completion_id += 1;
+            invocation.awaitable.push(completion_id);
+            (
+                journal_entry(SleepCommand {
+                    wake_up_time,
+                    completion_id,
+                    name: Default::default(),
+                }),
+                format!("sleep until {}", wake_up_time.as_u64()),
+            )
+        } else if choice < 50 {
+            let invocation_id_completion_id = invocation.next_completion_id;
+            let result_completion_id = invocation_id_completion_id + 1;
+            invocation.next_completion_id += 2;
+            invocation.awaitable.push(result_completion_id);
+            let description = format!("call {callee_id} on {callee_target}");
+            (
+                journal_entry(CallCommand {
+                    request: CallRequest {
+                        invocation_id: callee_id,
+                        invocation_target: callee_target,
+                        span_context: Default::default(),
+                        parameter: Bytes::new(),
+                        headers: vec![],
+                        idempotency_key: None,
+                        completion_retention_duration: Duration::ZERO,
+                        journal_retention_duration: Duration::ZERO,
+                        limit_key: Default::default(),
+                    },
+                    invocation_id_completion_id,
+                    result_completion_id,
+                    name: Default::default(),
+                }),
+                description,
+            )
+        } else if choice < 70
+            && let Some(completion_id) = invocation.awaitable.choose(&mut self.rng).copied()
+        {
+            invocation.running = false;
+            (
+                InvokerEffectKind::SuspendedV3 {
+                    awaiting_on: NotificationId::for_completion(completion_id).into(),
+                },
+                format!("suspend awaiting completion {completion_id}"),
+            )
+        } else if choice < 95 {
+            invocation.running = false;
+            (InvokerEffectKind::End, "end".to_owned())
+        } else {
+            invocation.running = false;
+            (
+                InvokerEffectKind::Failed(InvocationError::new(
+                    codes::INTERNAL,
+                    "simulated failure",
+                )),
+                "fail".to_owned(),
+            )
+        };
+
+        self.apply(
+            Envelope::new(
+                Dedup::None,
+                commands::InvokerEffectCommand::from(Effect {
+                    invocation_id,
+                    kind,
+                }),
+            )
+            .into_raw(),
+        )
+        .await?;
+
+        Ok(format!("invoker effect of {invocation_id}: {description}"))
+    }
+
+    async fn next_timer(&mut self) -> Result<Option<TimerKeyValue>, StorageError> {
+        let timers: Vec<_> = self
+            .storage
+            .next_timers_greater_than(None, 1)?
+            .try_collect()
+            .await?;
+        Ok(timers
+            .into_iter()
+            .next()
+            .map(|(key, timer)| TimerKeyValue::new(key, timer)))
+    }
+
+    /// Fires the earliest timer, advancing the clock to its wake-up time if needed.
+    async fn fire_timer(&mut self) -> Result<String, FailureCause> {
+        let timer = self.next_timer().await?.expect("timer to fire");
+        self.clock = self.clock.max(timer.wake_up_time());
+        let description = format!("fire timer of {}", timer.invocation_id());
+
+        self.apply(Envelope::new(Dedup::None, commands::TimerCommand::from(timer)).into_raw())
+            .await?;
+
+        Ok(description)
+    }
+
+    async fn next_outbox_message(
+        &mut self,
+    ) -> Result<Option<(MessageIndex, OutboxMessage)>, StorageError> {
+        self.storage.get_next_outbox_message(self.outbox_head).await
+    }
+
+    /// Delivers the head of the outbox back to this partition and truncates it, like the
+    /// shuffle does for partitions covering the full key range.
+    async fn deliver_outbox(&mut self) -> Result<String, FailureCause> {
+        let (seq, message) = self
+            .next_outbox_message()
+            .await?
+            .expect("outbox message to deliver");
+
+        let (envelope, description) = match message {
+            OutboxMessage::ServiceInvocation(invocation) => {
+                self.invocations
+                    .entry(invocation.invocation_id)
+                    .or_insert_with(InvocationModel::new);
+                let description = format!(
+                    "deliver invocation {} on {}",
+                    invocation.invocation_id, invocation.invocation_target
+                );
+                (
+                    Some(
+                        Envelope::new(Dedup::None, commands::InvokeCommand::from(*invocation))
+                            .into_raw(),
+                    ),
+                    description,
+                )
+            }
+            OutboxMessage::ServiceResponse(response) => (
+                Some(
+                    Envelope::new(
+                        Dedup::None,
+                        commands::InvocationResponseCommand::from(response.clone()),
+                    )
+                    .into_raw(),
+                ),
+                format!(
+                    "deliver response for completion {} of {}",
+                    response.target.caller_completion_id, response.target.caller_id
+                ),
+            ),
+            OutboxMessage::InvocationTermination(termination) => (
+                Some(
+                    Envelope::new(
+                        Dedup::None,
+                        commands::TerminateInvocationCommand::from(termination.clone()),
+                    )
+                    .into_raw(),
+                ),
+                format!("deliver termination of {}", termination.invocation_id),
+            ),
+            OutboxMessage::AttachInvocation(request) => (
+                Some(
+                    Envelope::new(
+                        Dedup::None,
+                        commands::AttachInvocationCommand::from(request),
+                    )
+                    .into_raw(),
+                ),
+                "deliver attach invocation request".to_owned(),
+            ),
+            OutboxMessage::NotifySignal(request) => (
+                Some(
+                    Envelope::new(Dedup::None, commands::NotifySignalCommand::from(request))
+                        .into_raw(),
+                ),
+                "deliver signal".to_owned(),
+            ),
+            // invocation events leave the partition through the invocation events sink
+            OutboxMessage::InvocationEvent(_) => (None, "skip invocation event".to_owned()),
+        };
+
+        if let Some(envelope) = envelope {
+            self.apply(envelope).await?;
+        }
+
+        let mut txn = self.storage.transaction();
+        self.processor
+            .outbox_mut()
+            .truncate_outbox_to(&mut txn, seq)?;
+        txn.commit().await?;
+        self.outbox_head = seq + 1;
+
+        Ok(description)
+    }
+
+    async fn terminate(&mut self) -> Result<String, FailureCause> {
+        let invocation_ids: Vec<_> = self.invocations.keys().copied().collect();
+        let invocation_id = *invocation_ids.choose(&mut self.rng).unwrap();
+        let flavor = if self.rng.random_bool(0.5) {
+            TerminationFlavor::Cancel
+        } else {
+            TerminationFlavor::Kill
+        };
+
+        self.apply(
+            Envelope::new(
+                Dedup::None,
+                commands::TerminateInvocationCommand::from(InvocationTermination {
+                    invocation_id,
+                    flavor,
+                    response_sink: None,
+                }),
+            )
+            .into_raw(),
+        )
+        .await?;
+
+        Ok(format!("{flavor:?} {invocation_id}"))
+    }
+
+    async fn change_leadership(&mut self) -> Result<String, FailureCause> {
+        self.is_leader = !self.is_leader;
+
+        if !self.is_leader {
+            // the invoker of the previous leader stops executing invocations
+            for invocation in self.invocations.values_mut() {
+                invocation.running = false;
+            }
+            return Ok("lose leadership".to_owned());
+        }
+
+        // the invoker of the new leader resumes all invoked invocations
+        for (invocation_id, invocation) in &mut self.invocations {
+            let status = self.storage.get_invocation_status(invocation_id).await?;
+            invocation.running = matches!(status, InvocationStatus::Invoked(_));
+        }
+        Ok("gain leadership".to_owned())
+    }
+
+    async fn vqueue_entries(
+        &self,
+        stage: Stage,
+    ) -> Result<Vec<(VQueueId, EntryKey)>, StorageError> {
+        let entries = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let collector = Arc::clone(&entries);
+        // scan a single stage at a time to keep the order of the entries deterministic
+        self.storage
+            .for_each_vqueue_entry(KeyRange::FULL, [stage], move |(qid, _, key, _)| {
+                collector.lock().push((qid.clone(), *key));
+                ControlFlow::Continue(())
+            })?
+            .await?;
+
+        let entries = std::mem::take(&mut *entries.lock());
+        Ok(entries)
+    }
+
+    /// Inbox entries the scheduler may run: entries which hold their lock already and entries
+    /// whose lock is not held by any other entry.
+    async fn runnable_vqueue_entries(&mut self) -> Result<Vec<(VQueueId, EntryKey)>, StorageError> {
+        let mut holders = Vec::new();
+        for stage in [Stage::Running, Stage::Suspended, Stage::Paused] {
+            holders.extend(self.vqueue_entries(stage).await?);
+        }
+        let inbox = self.vqueue_entries(Stage::Inbox).await?;
+        holders.extend(inbox.iter().filter(|(_, key)| key.has_lock()).cloned());
+
+        let txn = self.storage.transaction();
+        let mut held_locks = Vec::new();
+        for (qid, _) in holders {
+            if let Some(meta) = txn.get_vqueue(&qid).await?
+                && let Some(lock_name) = meta.lock_name()
+            {
+                held_locks.push((meta.scope().clone(), lock_name.to_string()));
+            }
+        }
+
+        let mut runnable = Vec::new();
+        for (qid, key) in inbox {
+            let Some(meta) = txn.get_vqueue(&qid).await? else {
+                continue;
+            };
+            let is_free = meta.lock_name().is_none_or(|lock_name| {
+                !held_locks.contains(&(meta.scope().clone(), lock_name.to_string()))
+            });
+            if key.has_lock() || is_free {
+                runnable.push((qid, key));
+            }
+        }
+
+        Ok(runnable)
+    }
+
+    async fn run_vqueue_entry(&mut self) -> Result<String, FailureCause> {
+        let runnable = self.runnable_vqueue_entries().await?;
+        let (qid, key) = runnable.choose(&mut self.rng).cloned().unwrap();
+        let description = format!("run entry {:?} of vqueue {qid}", key.entry_id());
+
+        self.apply(
+            Envelope::new(
+                Dedup::None,
+                SchedulerDecisionsCommand {
+                    qids: vec![(
+                        qid,
+                        vec![SchedulerAction::Run(RunAction {
+                            key,
+                            wait_stats: Default::default(),
+                        })],
+                    )],
+                },
+            )
+            .into_raw(),
+        )
+        .await?;
+
+        Ok(description)
+    }
+
+    async fn check_invariants(&mut self) -> Result<(), FailureCause> {
+        let mut statuses = BTreeMap::new();
+        for invocation_id in self.invocations.keys() {
+            let status = self.storage.get_invocation_status(invocation_id).await?;
+            statuses.insert(*invocation_id, status);
+        }
+        self.invocations.retain(|invocation_id, _| {
+            !matches!(
+                statuses[invocation_id],
+                InvocationStatus::Completed(_) | InvocationStatus::Free
+            )
+        });
+
+        if self.vqueues {
+            self.check_vqueues().await
+        } else {
+            self.check_inboxes(&statuses).await
+        }
+    }
+
+    /// Without vqueues, the inbox of a virtual object holds the invocations which wait for its
+    /// lock, and the lock is held by the in-flight exclusive invocation.
+    async fn check_inboxes(
+        &mut self,
+        statuses: &BTreeMap<InvocationId, InvocationStatus>,
+    ) -> Result<(), FailureCause> {
+        let mut locks = BTreeMap::new();
+        for key in OBJECT_KEYS {
+            let service_id = ServiceId::new(None, OBJECT, key);
+            let inbox: Vec<_> = self.storage.inbox(&service_id)?.try_collect().await?;
+
+            for entry in &inbox {
+                if let InboxEntry::Invocation(_, invocation_id) = entry.inbox_entry {
+                    let status = self.storage.get_invocation_status(&invocation_id).await?;
+                    if !matches!(status, InvocationStatus::Inboxed(_)) {
+                        return Err(Violation::InboxedInvocationNotInboxed {
+                            service_id,
+                            invocation_id,
+                            status: status.discriminant(),
+                        }
+                        .into());
+                    }
+                }
+            }
+
+            let lock = self.storage.get_virtual_object_status(&service_id).await?;
+            match lock {
+                VirtualObjectStatus::Unlocked if !inbox.is_empty() => {
+                    return Err(Violation::UnlockedWithInbox { service_id }.into());
+                }
+                VirtualObjectStatus::Unlocked => {}
+                VirtualObjectStatus::Locked(invocation_id) => {
+                    let status = self.storage.get_invocation_status(&invocation_id).await?;
+                    if status.get_invocation_metadata().is_none() {
+                        return Err(Violation::LockedByInvocationNotInFlight {
+                            service_id,
+                            invocation_id,
+                            status: status.discriminant(),
+                        }
+                        .into());
+                    }
+                }
+            }
+            locks.insert(service_id, lock);
+        }
+
+        for (invocation_id, status) in statuses {
+            let Some(metadata) = status.get_invocation_metadata() else {
+                continue;
+            };
+            if let InvocationTarget::VirtualObject {
+                handler_ty: VirtualObjectHandlerType::Exclusive,
+                ..
+            } = &metadata.invocation_target
+            {
+                let service_id = metadata.invocation_target.as_keyed_service_id().unwrap();
+                if locks.get(&service_id) != Some(&VirtualObjectStatus::Locked(*invocation_id)) {
+                    return Err(Violation::InFlightWithoutLock {
+                        service_id,
+                        invocation_id: *invocation_id,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// With vqueues, the entries of unfinished invocations are in the inbox, running, suspended
+    /// or paused stage, and the statistics of every vqueue match its entries.
+    async fn check_vqueues(&mut self) -> Result<(), FailureCause> {
+        let mut counts: BTreeMap<VQueueId, StageCounts> = BTreeMap::new();
+        for stage in [
+            Stage::Inbox,
+            Stage::Running,
+            Stage::Suspended,
+            Stage::Paused,
+        ] {
+            for (qid, key) in self.vqueue_entries(stage).await? {
+                counts.entry(qid.clone()).or_default().increment(stage);
+
+                let Some(invocation_id) = key.entry_id().to_invocation_id(qid.partition_key())
+                else {
+                    continue;
+                };
+                let status = self.storage.get_invocation_status(&invocation_id).await?;
+                if matches!(
+                    status,
+                    InvocationStatus::Completed(_) | InvocationStatus::Free
+                ) {
+                    return Err(Violation::VQueueEntryNotInFlight {
+                        qid,
+                        stage,
+                        invocation_id,
+                        status: status.discriminant(),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        // vqueues without entries must not count any either
+        for (_, qid, _) in self.processor.vqueues().iter_active_vqueues() {
+            counts.entry(qid.clone()).or_default();
+        }
+
+        let txn = self.storage.transaction();
+        for (qid, actual) in counts {
+            let stored = txn
+                .get_vqueue(&qid)
+                .await?
+                .map(|meta| StageCounts::of(&meta))
+                .unwrap_or_default();
+            if stored != actual {
+                return Err(Violation::VQueueCounts {
+                    qid,
+                    origin: "stored metadata",
+                    counted: stored,
+                    actual,
+                }
+                .into());
+            }
+
+            let vqueues = self.processor.vqueues();
+            if let Some(cached) = vqueues.get_vqueue(&qid).map(StageCounts::of)
+                && cached != actual
+            {
+                return Err(Violation::VQueueCounts {
+                    qid,
+                    origin: "cached metadata",
+                    counted: cached,
+                    actual,
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn journal_entry(entry: impl Into<Entry>) -> InvokerEffectKind {
+    InvokerEffectKind::journal_entry(entry.into().encode::<ServiceProtocolV4Codec>(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use restate_core::TaskCenter;
+    use restate_partition_store::PartitionStoreManager;
+    use restate_rocksdb::RocksDbManager;
+    use restate_types::partitions::{Partition, PartitionFeatureChange};
+    use restate_types::sharding::PartitionId;
+
+    const SEEDS: u64 = 8;
+    const STEPS: usize = 300;
+
+    async fn partition_store_manager() -> Arc<PartitionStoreManager> {
+        RocksDbManager::init();
+        TaskCenter::set_on_shutdown(Box::pin(async {
+            RocksDbManager::get().shutdown().await;
+        }));
+        PartitionStoreManager::create(true).await.unwrap()
+    }
+
+    /// Every simulation runs on its own partition store.
+    async fn simulation(
+        manager: &PartitionStoreManager,
+        partition_id: u16,
+        seed: u64,
+        features: PersistedFeatures,
+    ) -> Simulation {
+        let storage = manager
+            .open(
+                &Partition::new(PartitionId::from(partition_id), KeyRange::FULL),
+                None,
+            )
+            .await
+            .unwrap();
+        Simulation::new(seed, features, storage).await.unwrap()
+    }
+
+    async fn run_seeds(features: PersistedFeatures) {
+        let manager = partition_store_manager().await;
+        let seeds: Vec<_> = match seed_from_env() {
+            Some(seed) => vec![seed],
+            None => (0..SEEDS).collect(),
+        };
+
+        for (partition_id, seed) in seeds.into_iter().enumerate() {
+            let mut simulation = simulation(&manager, partition_id as u16, seed, features).await;
+            if let Err(failure) = simulation.run(STEPS).await {
+                panic!("{failure}");
+            }
+        }
+    }
+
+    #[restate_core::test]
+    async fn simulate_inbox() {
+        run_seeds(PersistedFeatures::default()).await;
+    }
+
+    #[restate_core::test]
+    async fn simulate_vqueues() {
+        run_seeds(PersistedFeatures::from_iter([
+            PartitionFeatureChange::EnableVqueues,
+        ]))
+        .await;
+    }
+
+    #[restate_core::test]
+    async fn same_seed_same_steps() {
+        let manager = partition_store_manager().await;
+        let seed = seed_from_env().unwrap_or_default();
+
+        let mut first = simulation(&manager, 0, seed, PersistedFeatures::default()).await;
+        first.run(STEPS).await.unwrap();
+        let mut second = simulation(&manager, 1, seed, PersistedFeatures::default()).await;
+        second.run(STEPS).await.unwrap();
+
+        assert!(first.trace().eq(second.trace()));
+    }
+}
@@ -24,9 +24,14 @@ pub(crate) trait OutboxMessageExt {
         result: ResponseResult,
     ) -> OutboxMessage;
 
-    fn to_command(self) -> Command;
+    fn to_command(self) -> Result<Command, NotACommandError>;
 }
 
+/// Error returned for outbox messages which aren't ingested into a partition.
+#[derive(Debug, thiserror::Error)]
+#[error("outbox message '{0}' is not a command, it is delivered to the invocation events sink")]
+pub(crate) struct NotACommandError(&'static str);
+
 impl OutboxMessageExt for OutboxMessage {
     fn from_awakeable_completion(
         invocation_id: InvocationId,
@@ -42,13 +47,16 @@ impl OutboxMessageExt for OutboxMessage {
         })
     }
 
-    fn to_command(self) -> Command {
-        match self {
+    fn to_command(self) -> Result<Command, NotACommandError> {
+        Ok(match self {
             OutboxMessage::ServiceInvocation(si) => Command::Invoke(si),
             OutboxMessage::ServiceResponse(sr) => Command::InvocationResponse(sr),
             OutboxMessage::InvocationTermination(it) => Command::TerminateInvocation(it),
             OutboxMessage::AttachInvocation(ai) => Command::AttachInvocation(ai),
             OutboxMessage::NotifySignal(notify_signal) => Command::NotifySignal(notify_signal),
-        }
+            OutboxMessage::InvocationEvent(_) => {
+                return Err(NotACommandError("InvocationEvent"));
+            }
+        })
     }
 }
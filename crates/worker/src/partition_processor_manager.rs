@@ -84,6 +84,7 @@ use restate_wal_protocol::Envelope;
 use restate_worker_api::invoker::capacity::InvokerCapacity;
 use restate_worker_api::{ProcessorsManagerCommand, ProcessorsManagerHandle};
 
+use crate::invocation_events::InvocationEventSink;
 use crate::metric_definitions::{
     ERROR_STOP, FLARE_REASON_MIGRATION_BARRIER, FLARE_REASON_SNAPSHOT_UNAVAILABLE,
     FLARE_REASON_VERSION_BARRIER, GAP_STOP, PARTITION_BLOCKED_FLARE, PARTITION_IS_EFFECTIVE_LEADER,
@@ -133,6 +134,7 @@ pub struct PartitionProcessorManager<T> {
     invoker_capacity: InvokerCapacity,
 
     ingestion_client: IngestionClient<T, Envelope>,
+    invocation_event_sink: InvocationEventSink,
 
     /// Built in `new`; the polling task is spawned at the start of `run`.
     rule_book_cache_task: Option<RuleBookCache>,
@@ -210,6 +212,7 @@ where
         bifrost: Bifrost,
         snapshot_repository: Option<SnapshotRepository>,
         ingestion_client: IngestionClient<T, Envelope>,
+        invocation_event_sink: InvocationEventSink,
     ) -> Self {
         let config = updateable_config.pinned();
         let ppm_svc_rx = router_builder.register_service(BackPressureMode::Lossy);
@@ -273,6 +276,7 @@ where
             wait_for_partition_table_update: false,
             invoker_capacity,
             ingestion_client,
+            invocation_event_sink,
             rule_book_cache_task: Some(rule_book_cache_task),
             rule_book_cache,
         }
@@ -1394,6 +1398,7 @@ where
             self.bifrost.clone(),
             self.invoker_capacity.clone(),
             self.leader_handles_registry.clone(),
+            self.invocation_event_sink.clone(),
         );
 
         let starting_task = SpawnPartitionProcessorTask::new(
@@ -1559,6 +1564,7 @@ enum EventKind {
 
 #[cfg(test)]
 mod tests {
+    use crate::invocation_events::InvocationEventSink;
     use crate::partition_processor_manager::PartitionProcessorManager;
     use googletest::IntoTestResult;
     use restate_bifrost::BifrostService;
@@ -1633,6 +1639,7 @@ mod tests {
             bifrost,
             None,
             ingestion_client,
+            InvocationEventSink::disabled(),
         );
 
         // only needed for setting up the metadata
@@ -296,6 +296,13 @@ fn main() {
                     _ = config_update_watcher.changed(), if !shutdown => {
                         TaskCenter::with_current(|tc| tc.memory_controller().notify_config_update());
                         tracing_guard.on_config_update();
+                        let encryption = Configuration::pinned().encryption.clone();
+                        let _ = TaskCenter::spawn(TaskKind::Disposable, "reload-encryption-keys", async move {
+                            if let Err(err) = restate_encryption::reload_from_options(&encryption).await {
+                                warn!(%err, "Failed to reload encryption keys, keeping the previous keys");
+                            }
+                            Ok(())
+                        });
                     },
                     _ = signal::sighup_compact(), if !shutdown => {},
                     _ = signal::sigusr1_dump_config() => {},
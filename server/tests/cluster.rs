@@ -10,9 +10,12 @@
 
 #![allow(clippy::large_futures)]
 
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::io;
 use std::net::SocketAddr;
 use std::num::{NonZeroU8, NonZeroUsize};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use anyhow::anyhow;
@@ -20,13 +23,20 @@ use enumset::EnumSet;
 use futures_util::StreamExt;
 use googletest::IntoTestResult;
 use http::header::CONTENT_TYPE;
+use rand::RngExt;
 use rand::prelude::IndexedMutRandom;
 use rand::seq::IndexedRandom;
 use regex::Regex;
 use tokio::net::TcpListener;
 use tokio::sync::{oneshot, watch};
+use tonic::transport::Channel;
 use tracing::{debug, info};
 
+use restate_core::network::net_util::{DNSResolution, create_tonic_channel};
+use restate_core::protobuf::cluster_ctrl_svc::{
+    SealAndExtendChainRequest, cluster_ctrl_svc_client::ClusterCtrlSvcClient,
+    new_cluster_ctrl_client,
+};
 use restate_core::{TaskCenter, TaskKind, cancellation_token};
 use restate_local_cluster_runner::cluster::StartedCluster;
 use restate_local_cluster_runner::node::TerminationSignal;
@@ -35,7 +45,7 @@ use restate_local_cluster_runner::{
     node::{BinarySource, NodeSpec},
 };
 use restate_types::config::Configuration;
-use restate_types::config::RaftOptions;
+use restate_types::config::{NetworkingOptions, RaftOptions};
 use restate_types::logs::metadata::{
     NodeSetSize, ProviderConfiguration, ProviderKind, ReplicatedLogletConfig,
 };
@@ -343,3 +353,425 @@ async fn cluster_chaos_test() -> googletest::Result<()> {
 
     Ok(())
 }
+
+/// Adds to a set of counters with idempotent invocations while nodes get isolated, paused and
+/// slowed down, which seals loglets and fails the partition leadership over to other nodes.
+/// Checks that every add took effect exactly once and that the state of the counters reflects
+/// all of them.
+///
+/// Ignored by default since it runs for more than a minute, run it with `cargo test -- --ignored`.
+#[ignore]
+#[test_log::test(restate_core::test)]
+async fn cluster_failover_exactly_once_test() -> googletest::Result<()> {
+    exactly_once_under_faults("cluster_failover_exactly_once_test", false).await
+}
+
+/// Same workload as [`cluster_failover_exactly_once_test`], while the logs of the partitions are
+/// additionally sealed and reconfigured by the cluster controller every few seconds, the way
+/// `restatectl logs reconfigure` does it.
+///
+/// Ignored by default since it runs for more than a minute, run it with `cargo test -- --ignored`.
+#[ignore]
+#[test_log::test(restate_core::test)]
+async fn cluster_bifrost_reconfiguration_exactly_once_test() -> googletest::Result<()> {
+    exactly_once_under_faults("cluster_bifrost_reconfiguration_exactly_once_test", true).await
+}
+
+async fn exactly_once_under_faults(
+    test_name: &str,
+    reconfigure_logs: bool,
+) -> googletest::Result<()> {
+    let num_nodes = 3;
+    let num_partitions = 4;
+    let num_workers = 4;
+    let test_duration = Duration::from_secs(60);
+    let request_timeout = Duration::from_secs(5);
+    let counters = ["1", "2", "3"];
+    let mut base_config = Configuration::new_unix_sockets();
+    base_config.metadata_server.set_raft_options(RaftOptions {
+        raft_election_tick: NonZeroUsize::new(5).expect("5 to be non zero"),
+        raft_heartbeat_tick: NonZeroUsize::new(2).expect("2 to be non zero"),
+        ..RaftOptions::default()
+    });
+    base_config.common.default_num_partitions = num_partitions;
+    base_config.bifrost.default_provider = ProviderKind::Replicated;
+    base_config.common.log_filter = "warn,restate=debug".to_owned();
+    base_config.common.gossip.gossip_suspect_interval = Duration::from_secs(1).into();
+
+    let nodes = NodeSpec::new_test_nodes(
+        base_config,
+        BinarySource::CargoTest,
+        EnumSet::all(),
+        num_nodes,
+        false,
+    );
+    let cluster = Cluster::builder()
+        .cluster_name(test_name)
+        .nodes(nodes)
+        .temp_base_dir(test_name)
+        .chaos(true)
+        .build()
+        .start()
+        .await?;
+
+    let replicated_loglet_config = ReplicatedLogletConfig {
+        target_nodeset_size: NodeSetSize::default(),
+        replication_property: ReplicationProperty::new(NonZeroU8::new(2).expect("to be non-zero")),
+    };
+
+    cluster.nodes[0]
+        .provision_cluster(
+            None,
+            ReplicationProperty::new_unchecked(3),
+            Some(ProviderConfiguration::Replicated(replicated_loglet_config)),
+            EnumSet::empty(),
+        )
+        .await
+        .into_test_result()?;
+
+    let ingress_clients: Vec<reqwest::Client> = cluster
+        .nodes
+        .iter()
+        .flat_map(|node| node.ingress_address().clone())
+        .map(|addr| {
+            let PeerNetAddress::Uds(uds) =
+                addr.into_address().expect("ingress address must be set")
+            else {
+                panic!("ingress address must be a unix domain socket");
+            };
+            reqwest::Client::builder()
+                .unix_socket(uds)
+                .timeout(request_timeout)
+                .build()
+                .expect("reqwest client should build")
+        })
+        .collect();
+    let admin_address = cluster
+        .nodes
+        .iter()
+        .flat_map(|node| node.admin_address().clone())
+        .next()
+        .expect("at least one admin node to be present");
+
+    cluster.wait_healthy(Duration::from_secs(30)).await?;
+
+    let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+    let mock_svc_port = listener.local_addr()?.port();
+    let (running_tx, running_rx) = oneshot::channel();
+    let service_handle = TaskCenter::spawn_unmanaged(TaskKind::TestRunner, "mock-service", {
+        async move {
+            cancellation_token()
+                .run_until_cancelled(mock_service_endpoint::listener::run_listener(
+                    listener,
+                    move || {
+                        let _ = running_tx.send(());
+                    },
+                ))
+                .await
+        }
+    })?;
+    running_rx.await?;
+
+    let PeerNetAddress::Uds(admin_uds) = admin_address.into_address().unwrap() else {
+        panic!("admin address must be a unix domain socket");
+    };
+    let admin_client = reqwest::Client::builder()
+        .unix_socket(admin_uds)
+        .build()
+        .expect("reqwest client should build");
+    let discovery_response = admin_client
+        .post("http://localhost/deployments")
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::json!({"uri": format!("http://127.0.0.1:{mock_svc_port}")}).to_string())
+        .send()
+        .await?;
+    assert!(
+        discovery_response.status().is_success(),
+        "discovery should be successful"
+    );
+
+    let sealed_loglets: Regex = r"seal\(\) has completed successfully".parse()?;
+    let won_leaderships: Regex = "Won the leadership campaign".parse()?;
+    let seals_before = count_log_lines(&cluster, &sealed_loglets)?;
+    let leaderships_before = count_log_lines(&cluster, &won_leaderships)?;
+
+    // the nodes are addressed directly, bypassing the fault injection proxies
+    let cluster_ctrl_clients: Vec<_> = cluster
+        .nodes
+        .iter()
+        .map(|node| {
+            new_cluster_ctrl_client(
+                create_tonic_channel(
+                    node.advertised_address().clone(),
+                    &NetworkingOptions::default(),
+                    DNSResolution::Gai,
+                ),
+                &NetworkingOptions::default(),
+            )
+        })
+        .collect();
+    let reconfigurer_handle = reconfigure_logs
+        .then(|| {
+            TaskCenter::spawn_unmanaged(
+                TaskKind::TestRunner,
+                "log-reconfigurer",
+                reconfigure_logs_periodically(
+                    cluster_ctrl_clients,
+                    num_partitions,
+                    Duration::from_secs(3),
+                ),
+            )
+        })
+        .transpose()?;
+
+    let nemesis_handle =
+        TaskCenter::spawn_unmanaged(TaskKind::TestRunner, "nemesis", async move {
+            async fn inject_faults(cluster: &StartedCluster) -> anyhow::Result<Infallible> {
+                let chaos = cluster.chaos().expect("chaos to be enabled");
+                // long enough for the cluster to detect the failed node and to fail over
+                let fault_duration = Duration::from_secs(10);
+
+                loop {
+                    let node = cluster
+                        .nodes
+                        .choose(&mut rand::rng())
+                        .expect("at least one node being present");
+
+                    match rand::rng().random_range(0..3) {
+                        0 => {
+                            chaos.isolate(node.node_name());
+                            tokio::time::sleep(fault_duration).await;
+                            chaos.heal();
+                        }
+                        1 => {
+                            node.pause()?;
+                            tokio::time::sleep(fault_duration).await;
+                            node.resume()?;
+                        }
+                        _ => {
+                            for other in cluster
+                                .nodes
+                                .iter()
+                                .filter(|other| other.node_name() != node.node_name())
+                            {
+                                chaos.delay(
+                                    node.node_name(),
+                                    other.node_name(),
+                                    Duration::from_millis(200),
+                                );
+                            }
+                            tokio::time::sleep(fault_duration).await;
+                            chaos.heal();
+                        }
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+
+            if let Some(result) = cancellation_token()
+                .run_until_cancelled(inject_faults(&cluster))
+                .await
+            {
+                result?;
+            }
+
+            // leave the cluster without faults
+            cluster.chaos().expect("chaos to be enabled").heal();
+            for node in &cluster.nodes {
+                node.resume()?;
+            }
+
+            Ok::<_, anyhow::Error>(cluster)
+        })?;
+
+    info!("Starting the cluster failover exactly-once test");
+
+    // results of the adds per counter and idempotency key, `None` if unknown
+    let adds: Mutex<HashMap<&str, HashMap<String, Option<i32>>>> = Mutex::default();
+
+    let start = Instant::now();
+    futures_util::future::join_all((0..num_workers).map(|worker| {
+        let ingress_clients = &ingress_clients;
+        let adds = &adds;
+        async move {
+            let mut next_add = 0;
+            while start.elapsed() < test_duration {
+                let counter = *counters
+                    .choose(&mut rand::rng())
+                    .expect("at least one counter");
+                let idempotency_key = format!("{worker}-{next_add}");
+                next_add += 1;
+
+                let client = ingress_clients
+                    .choose(&mut rand::rng())
+                    .expect("at least one ingress client");
+                let result = add(client, counter, &idempotency_key).await;
+                if result.is_none() {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                adds.lock()
+                    .unwrap()
+                    .entry(counter)
+                    .or_default()
+                    .insert(idempotency_key, result);
+            }
+        }
+    }))
+    .await;
+
+    let reconfigurations = match reconfigurer_handle {
+        Some(handle) => {
+            handle.cancel();
+            handle.await?
+        }
+        None => 0,
+    };
+    nemesis_handle.cancel();
+    let mut cluster = nemesis_handle.await?.into_test_result()?;
+    cluster.wait_healthy(Duration::from_secs(30)).await?;
+
+    let mut adds = adds.into_inner().unwrap();
+    let num_adds: usize = adds.values().map(HashMap::len).sum();
+    info!("Checking the outcome of {num_adds} adds");
+
+    for (counter, results) in &mut adds {
+        // resolve the unknown outcomes by retrying the adds with the same idempotency keys
+        for (idempotency_key, result) in results.iter_mut().filter(|(_, r)| r.is_none()) {
+            let retry_start = Instant::now();
+            while result.is_none() {
+                if retry_start.elapsed() > Duration::from_secs(60) {
+                    return Err(anyhow!(
+                        "Add {idempotency_key} to counter {counter} did not complete"
+                    ))
+                    .into_test_result();
+                }
+                *result = add(&ingress_clients[0], counter, idempotency_key).await;
+            }
+        }
+
+        // every add took effect exactly once, so the adds observed all values in between
+        let mut observed: Vec<_> = results.values().map(|r| r.expect("resolved")).collect();
+        observed.sort_unstable();
+        let expected: Vec<_> = (1..=i32::try_from(observed.len())?).collect();
+        assert_eq!(
+            observed, expected,
+            "adds to counter {counter} did not take effect exactly once"
+        );
+
+        let value: i32 = ingress_clients[0]
+            .post(format!("http://localhost/Counter/{counter}/get"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        assert_eq!(
+            value,
+            *expected.last().unwrap_or(&0),
+            "counter {counter} lost adds"
+        );
+    }
+
+    let seals = count_log_lines(&cluster, &sealed_loglets)? - seals_before;
+    let leaderships = count_log_lines(&cluster, &won_leaderships)? - leaderships_before;
+    info!("Observed {seals} sealed loglets and {leaderships} won leaderships during the test");
+    assert!(seals > 0, "no loglet was sealed during the test");
+    assert!(leaderships > 0, "no partition failed over during the test");
+    if reconfigure_logs {
+        info!("Reconfigured logs {reconfigurations} times during the test");
+        assert!(
+            reconfigurations > 0,
+            "no log was reconfigured during the test"
+        );
+    }
+
+    service_handle.cancel();
+    cluster.graceful_shutdown(Duration::from_secs(3)).await?;
+
+    Ok(())
+}
+
+/// Seals the tail segment of a random log and lets the cluster controller choose the
+/// configuration of the next segment, until cancelled. Returns the number of successful
+/// reconfigurations. Requests fail while the contacted node is paused or isn't the leader, these
+/// failures are ignored.
+async fn reconfigure_logs_periodically(
+    mut clients: Vec<ClusterCtrlSvcClient<Channel>>,
+    num_logs: u16,
+    period: Duration,
+) -> usize {
+    let mut reconfigurations = 0;
+    loop {
+        let log_id = rand::rng().random_range(0..u32::from(num_logs));
+        let client = clients
+            .choose_mut(&mut rand::rng())
+            .expect("at least one cluster controller client");
+
+        // requests to paused nodes don't complete until the node is resumed
+        let Some(result) = cancellation_token()
+            .run_until_cancelled(async {
+                tokio::time::sleep(period).await;
+                client
+                    .seal_and_extend_chain(SealAndExtendChainRequest {
+                        log_id,
+                        min_version: None,
+                        extension: None,
+                    })
+                    .await
+            })
+            .await
+        else {
+            break;
+        };
+
+        match result {
+            Ok(response) => {
+                debug!(
+                    "Reconfigured log {log_id}, new segment index is {}",
+                    response.into_inner().new_segment_index
+                );
+                reconfigurations += 1;
+            }
+            Err(status) => {
+                debug!(%status, "Failed to reconfigure log {log_id}");
+            }
+        }
+    }
+    reconfigurations
+}
+
+/// Adds 1 to the counter, returns its new value or `None` if the outcome is unknown.
+async fn add(client: &reqwest::Client, counter: &str, idempotency_key: &str) -> Option<i32> {
+    let response = client
+        .post(format!("http://localhost/Counter/{counter}/add"))
+        .header(CONTENT_TYPE, "application/json")
+        .header("idempotency-key", idempotency_key)
+        .body("1")
+        .send()
+        .await
+        .inspect_err(|err| debug!(%err, "Failed to add {idempotency_key} to counter {counter}"))
+        .ok()?;
+
+    if !response.status().is_success() {
+        return None;
+    }
+    serde_json::from_slice(response.bytes().await.ok()?.as_ref()).ok()
+}
+
+/// Number of lines in the logs of all nodes which match the pattern.
+fn count_log_lines(cluster: &StartedCluster, pattern: &Regex) -> io::Result<usize> {
+    cluster
+        .nodes
+        .iter()
+        .map(|node| {
+            let log = std::fs::read_to_string(
+                cluster
+                    .base_dir()
+                    .join(node.node_name())
+                    .join("restate.log"),
+            )?;
+            Ok(log.lines().filter(|line| pattern.is_match(line)).count())
+        })
+        .sum()
+}
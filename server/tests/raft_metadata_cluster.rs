@@ -17,8 +17,9 @@ use futures_util::never::Never;
 use googletest::prelude::err;
 use googletest::{IntoTestResult, assert_that, pat};
 use rand::RngExt;
-use rand::seq::IndexedMutRandom;
+use rand::seq::{IndexedMutRandom, IndexedRandom};
 use restate_core::{TaskCenter, TaskKind, cancellation_token};
+use restate_local_cluster_runner::chaos::linearizability::{History, Operation, Outcome};
 use restate_local_cluster_runner::cluster::{Cluster, StartedCluster};
 use restate_local_cluster_runner::node::{BinarySource, HealthCheck, NodeSpec, TerminationSignal};
 use restate_metadata_providers::create_client;
@@ -340,6 +341,185 @@ enum State {
     Reconcile,
 }
 
+/// Reads and writes a set of registers in the metadata store while nodes get paused, isolated
+/// and slowed down, and checks that the observed history is linearizable.
+///
+/// Ignored by default since it runs for more than 20s, run it with `cargo test -- --ignored`.
+#[ignore]
+#[test_log::test(restate_core::test)]
+async fn raft_metadata_cluster_linearizability_test() -> googletest::Result<()> {
+    let num_nodes = 3;
+    let num_workers = 4;
+    let test_duration = Duration::from_secs(20);
+    let operation_timeout = Duration::from_secs(1);
+    let registers = ["register-1", "register-2"];
+    let mut base_config = Configuration::new_unix_sockets();
+    base_config.metadata_server.set_raft_options(RaftOptions {
+        raft_election_tick: NonZeroUsize::new(5).expect("5 to be non zero"),
+        raft_heartbeat_tick: NonZeroUsize::new(2).expect("2 to be non zero"),
+        ..RaftOptions::default()
+    });
+
+    let nodes = NodeSpec::new_test_nodes(
+        base_config,
+        BinarySource::CargoTest,
+        enum_set!(Role::MetadataServer),
+        num_nodes,
+        true,
+    );
+    let cluster = Cluster::builder()
+        .cluster_name("raft_metadata_cluster_linearizability_test")
+        .nodes(nodes)
+        .temp_base_dir("raft_metadata_cluster_linearizability_test")
+        .chaos(true)
+        .build()
+        .start()
+        .await?;
+
+    cluster.wait_healthy(Duration::from_secs(30)).await?;
+
+    let addresses = cluster
+        .nodes
+        .iter()
+        .map(|node| node.advertised_address().clone())
+        .collect();
+
+    // Set a valid configuration with the cluster name for the GrpcMetadataServerClient to pick it up
+    let mut configuration = Configuration::default();
+    configuration
+        .common
+        .set_cluster_name(cluster.cluster_name().to_owned());
+    set_current_config(configuration);
+
+    let metadata_store_client_options = MetadataClientOptions {
+        kind: MetadataClientKind::Replicated { addresses },
+        ..MetadataClientOptions::default()
+    };
+    let client = create_client(metadata_store_client_options)
+        .await
+        .expect("to not fail");
+
+    let nemesis_handle =
+        TaskCenter::spawn_unmanaged(TaskKind::TestRunner, "nemesis", async move {
+            async fn inject_faults(cluster: &StartedCluster) -> anyhow::Result<Infallible> {
+                let chaos = cluster.chaos().expect("chaos to be enabled");
+                let fault_duration = Duration::from_secs(2);
+
+                loop {
+                    let node = cluster
+                        .nodes
+                        .choose(&mut rand::rng())
+                        .expect("at least one node being present");
+
+                    match rand::rng().random_range(0..3) {
+                        0 => {
+                            node.pause()?;
+                            tokio::time::sleep(fault_duration).await;
+                            node.resume()?;
+                        }
+                        1 => {
+                            chaos.isolate(node.node_name());
+                            tokio::time::sleep(fault_duration).await;
+                            chaos.heal();
+                        }
+                        _ => {
+                            for other in cluster
+                                .nodes
+                                .iter()
+                                .filter(|other| other.node_name() != node.node_name())
+                            {
+                                chaos.delay(
+                                    node.node_name(),
+                                    other.node_name(),
+                                    Duration::from_millis(100),
+                                );
+                            }
+                            tokio::time::sleep(fault_duration).await;
+                            chaos.heal();
+                        }
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+
+            if let Some(result) = cancellation_token()
+                .run_until_cancelled(inject_faults(&cluster))
+                .await
+            {
+                result?;
+            }
+
+            // leave the cluster without faults
+            cluster.chaos().expect("chaos to be enabled").heal();
+            for node in &cluster.nodes {
+                node.resume()?;
+            }
+
+            Ok::<_, anyhow::Error>(cluster)
+        })?;
+
+    let history = History::default();
+
+    info!("Starting the metadata cluster linearizability test");
+
+    let start = Instant::now();
+    futures_util::future::join_all((0..num_workers).map(|worker: u32| {
+        let client = client.clone();
+        let history = &history;
+        async move {
+            let mut next_value = worker * 1_000_000;
+
+            while start.elapsed() < test_duration {
+                let register = *registers
+                    .choose(&mut rand::rng())
+                    .expect("at least one register");
+                let key = ByteString::from_static(register);
+
+                if rand::rng().random_bool(0.5) {
+                    let id = history.invoke(register, Operation::Read);
+                    let outcome =
+                        match tokio::time::timeout(operation_timeout, client.get::<Value>(key))
+                            .await
+                        {
+                            Ok(Ok(value)) => Outcome::Read(value.map(|value| value.value)),
+                            _ => Outcome::Unknown,
+                        };
+                    history.complete(id, outcome);
+                } else {
+                    next_value += 1;
+                    let id = history.invoke(register, Operation::Write(next_value));
+                    let outcome = match tokio::time::timeout(
+                        operation_timeout,
+                        client.put(key, &Value::new(next_value), Precondition::None),
+                    )
+                    .await
+                    {
+                        Ok(Ok(())) => Outcome::Written,
+                        // the write might have been committed even if it failed
+                        _ => Outcome::Unknown,
+                    };
+                    history.complete(id, outcome);
+                }
+            }
+        }
+    }))
+    .await;
+
+    nemesis_handle.cancel();
+    let mut cluster = nemesis_handle.await?.into_test_result()?;
+
+    info!(
+        "Checking the linearizability of {} operations",
+        history.len()
+    );
+    history.check()?;
+
+    cluster.graceful_shutdown(Duration::from_secs(3)).await?;
+
+    Ok(())
+}
+
 #[test_log::test(restate_core::test)]
 async fn raft_metadata_cluster_reconfiguration() -> googletest::Result<()> {
     let num_nodes = 3;
@@ -20,11 +20,13 @@ use restate_cli_util::{CliContext, c_eprintln, c_println, c_tip, c_warn};
 use restate_core::task_center::TaskCenterMonitoring;
 use restate_core::{MetadataBuilder, TaskCenter, TaskCenterBuilder, TaskKind, task_center};
 use restate_errors::fmt::RestateCode;
+use restate_log_server::logstore::LogStore;
 use restate_log_server::metadata::LogletStateMap;
-use restate_log_server::rocksdb_logstore::RocksDbLogStoreBuilder;
+use restate_log_server::rocksdb_logstore::{RocksDbLogStore, RocksDbLogStoreBuilder};
+use restate_log_server::segment_logstore::{SegmentLogStore, SegmentLogStoreBuilder};
 use restate_rocksdb::RocksDbManager;
 use restate_tracing_instrumentation::init_tracing_and_logging;
-use restate_types::config::Configuration;
+use restate_types::config::{Configuration, LogStoreBackend};
 use restate_types::config_loader::ConfigLoaderBuilder;
 
 use logserver_bench::{Arguments, Command, metrics_server, mixed_workload, write_throughput};
@@ -117,29 +119,12 @@ fn main() -> anyhow::Result<()> {
         // thread). This better reflects real-world scheduling behaviour.
         let bench_handle =
             TaskCenter::spawn_unmanaged(TaskKind::Disposable, "benchmark", async move {
-                match args.command {
-                    Command::WriteThroughput(ref opts) => {
-                        write_throughput::run(
-                            opts,
-                            log_store.clone(),
-                            &state_map,
-                            args.report_interval,
-                            args.raw_rocksdb_stats,
-                        )
-                        .await
+                match log_store {
+                    BenchLogStore::RocksDb(log_store) => {
+                        run_benchmark(&args, log_store, &state_map).await
                     }
-                    Command::MixedWorkload(ref opts) => {
-                        mixed_workload::run(
-                            opts,
-                            log_store.clone(),
-                            &state_map,
-                            args.report_interval,
-                            args.raw_rocksdb_stats,
-                        )
-                        .await
-                    }
-                    Command::GeneratePayload(_) => {
-                        unreachable!("handled above before TaskCenter setup")
+                    BenchLogStore::SegmentFile(log_store) => {
+                        run_benchmark(&args, log_store, &state_map).await
                     }
                 }
             })?;
@@ -168,13 +153,47 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_benchmark<S: LogStore + Sync>(
+    args: &Arguments,
+    log_store: S,
+    state_map: &LogletStateMap,
+) -> anyhow::Result<()> {
+    match args.command {
+        Command::WriteThroughput(ref opts) => {
+            write_throughput::run(
+                opts,
+                log_store,
+                state_map,
+                args.report_interval,
+                args.raw_rocksdb_stats,
+            )
+            .await
+        }
+        Command::MixedWorkload(ref opts) => {
+            mixed_workload::run(
+                opts,
+                log_store,
+                state_map,
+                args.report_interval,
+                args.raw_rocksdb_stats,
+            )
+            .await
+        }
+        Command::GeneratePayload(_) => {
+            unreachable!("handled above before TaskCenter setup")
+        }
+    }
+}
+
+/// The log-store selected by `log-server.storage-backend`
+enum BenchLogStore {
+    RocksDb(RocksDbLogStore),
+    SegmentFile(SegmentLogStore),
+}
+
 fn spawn_environment(
     config: restate_types::live::Live<Configuration>,
-) -> (
-    task_center::Handle,
-    restate_log_server::rocksdb_logstore::RocksDbLogStore,
-    LogletStateMap,
-) {
+) -> (task_center::Handle, BenchLogStore, LogletStateMap) {
     let tc = TaskCenterBuilder::default()
         .options(config.pinned().common.clone())
         .build()
@@ -187,19 +206,38 @@ fn spawn_environment(
 
         RocksDbManager::init();
 
-        let builder = RocksDbLogStoreBuilder::create()
-            .await
-            .expect("Failed to create RocksDB log store");
-        let log_store = builder
-            .start(Default::default())
-            .await
-            .expect("Failed to start RocksDB log store");
-
-        let state_map = LogletStateMap::load_all(&log_store)
-            .await
-            .expect("Failed to load loglet state map");
-
-        (log_store, state_map)
+        match Configuration::pinned().log_server.storage_backend {
+            LogStoreBackend::RocksDb => {
+                let builder = RocksDbLogStoreBuilder::create()
+                    .await
+                    .expect("Failed to create RocksDB log store");
+                let log_store = builder
+                    .start(Default::default())
+                    .await
+                    .expect("Failed to start RocksDB log store");
+
+                let state_map = LogletStateMap::load_all(&log_store)
+                    .await
+                    .expect("Failed to load loglet state map");
+
+                (BenchLogStore::RocksDb(log_store), state_map)
+            }
+            LogStoreBackend::SegmentFile => {
+                let builder = SegmentLogStoreBuilder::create()
+                    .await
+                    .expect("Failed to create segment-file log store");
+                let log_store = builder
+                    .start(Default::default())
+                    .await
+                    .expect("Failed to start segment-file log store");
+
+                let state_map = LogletStateMap::load_all(&log_store)
+                    .await
+                    .expect("Failed to load loglet state map");
+
+                (BenchLogStore::SegmentFile(log_store), state_map)
+            }
+        }
     });
 
     (tc, log_store, state_map)
@@ -9,7 +9,9 @@
 // by the Apache License, Version 2.0.
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use anyhow::Context;
 use cling::prelude::*;
 
 use restate_cli_util::{CliContext, CommonOpts};
@@ -49,6 +51,11 @@ pub struct GlobalOpts {
     /// Limit max open files for RocksDB (use if you hit "too many open files" errors)
     #[arg(long, global = true)]
     pub limit_open_files: Option<i32>,
+
+    /// Key file used to decrypt data written with encryption at rest enabled. Keys wrapped by
+    /// a KMS are not supported here, use a key file with unwrapped keys instead.
+    #[arg(long, global = true, env = "RESTATE_ENCRYPTION__KEY_FILE")]
+    pub encryption_key_file: Option<PathBuf>,
 }
 
 #[derive(Run, Subcommand, Clone)]
@@ -72,7 +79,15 @@ pub enum Command {
     Completions(Completions),
 }
 
-fn init(common_opts: &CommonOpts) {
+async fn init(common_opts: &CommonOpts, global_opts: &GlobalOpts) -> anyhow::Result<()> {
     // Initialize CLI context (handles colors, logging, etc.)
     CliContext::new(common_opts.clone()).set_as_global();
+
+    if let Some(key_file) = &global_opts.encryption_key_file {
+        let key_ring = restate_encryption::load_key_ring(key_file, None)
+            .await
+            .with_context(|| format!("failed to load encryption keys from {key_file:?}"))?;
+        restate_encryption::install_key_ring(Some(Arc::new(key_ring)));
+    }
+    Ok(())
 }
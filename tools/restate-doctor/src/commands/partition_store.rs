@@ -11,6 +11,7 @@
 mod get;
 pub(crate) mod info;
 mod scan;
+mod sql;
 mod sst;
 
 use std::path::PathBuf;
@@ -20,6 +21,7 @@ use cling::prelude::*;
 pub use get::Get;
 pub use info::Info;
 pub use scan::Scan;
+pub use sql::Sql;
 pub use sst::Sst;
 
 use crate::util::rocksdb::OpenMode;
@@ -35,6 +37,8 @@ pub enum PartitionStoreCommand {
     Info(Info),
     /// Inspect SST files in detail (decoded keys, colorized hex)
     Sst(Sst),
+    /// Run SQL queries against the local partition store of a stopped node
+    Sql(Sql),
 }
 
 /// Common options for partition store commands
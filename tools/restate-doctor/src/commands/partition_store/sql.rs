@@ -0,0 +1,309 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! SQL queries over a stopped node's local partition store
+
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, bail};
+use cling::prelude::*;
+use rocksdb::{DB, Options};
+
+use restate_cli_util::ui::console::confirm_or_exit;
+use restate_cli_util::{c_println, c_warn};
+use restate_core::task_center::TaskCenterFutureExt;
+use restate_core::{MetadataBuilder, MetadataManager, TaskCenterBuilder, spawn_metadata_manager};
+use restate_metadata_store::MetadataStoreClient;
+use restate_partition_store::PartitionStoreManager;
+use restate_rocksdb::{OpenMode, RocksDbManager};
+use restate_storage_query_datafusion::context::{PartitionTables, QueryContext};
+use restate_storage_query_datafusion::remote_query_scanner_manager::RemoteScannerManager;
+use restate_types::clock::ClockUpkeep;
+use restate_types::config::{Configuration, set_current_config};
+use restate_types::identifiers::PartitionId;
+use restate_types::memory::NonZeroByteCount;
+use restate_types::partition_table::Partition;
+use restate_types::protobuf::common::DatabaseKind;
+use restate_types::sharding::KeyRange;
+
+use crate::app::GlobalOpts;
+use crate::commands::snapshot::{LocalPartitions, OfflineMetadataStore, repl, server};
+
+/// Prefix of the column families holding a partition's data (`data-<partition_id>`)
+const PARTITION_CF_PREFIX: &str = "data-";
+
+/// Run SQL queries against the local partition store of a stopped node
+///
+/// Opens the partition store databases found in a node's data directory in RocksDB read-only
+/// mode and exposes the same tables as a live worker (`sys_invocation`, `state`, `sys_journal`,
+/// ...) through an interactive SQL terminal, a single `--query`, or the admin-compatible query
+/// API with `--listen`. Both the single-database and the per-partition database layouts are
+/// supported.
+///
+/// The Restate server owning the data directory MUST be stopped.
+#[derive(Run, Parser, Collect, Clone)]
+#[cling(run = "run_sql")]
+pub struct Sql {
+    /// Restate node data directory, the one containing `db/` (default: --data-dir)
+    pub data_dir: Option<PathBuf>,
+
+    /// Inspect only this partition id instead of all partitions found in the data directory
+    #[arg(long)]
+    pub partition_id: Option<u16>,
+
+    /// Run a single SQL query, print the result, and exit (instead of starting the REPL)
+    #[arg(long)]
+    pub query: Option<String>,
+
+    /// Serve the admin-compatible SQL query API (POST /query) on this address instead of starting
+    /// the REPL. Defaults to 127.0.0.1:9078 when given without a value.
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        default_missing_value = "127.0.0.1:9078",
+        conflicts_with = "query"
+    )]
+    pub listen: Option<SocketAddr>,
+
+    #[clap(long, default_value = "2147483648")]
+    pub rocksdb_memory_budget: NonZeroUsize,
+}
+
+pub async fn run_sql(global_opts: &GlobalOpts, cmd: &Sql) -> anyhow::Result<()> {
+    let data_dir = cmd
+        .data_dir
+        .as_deref()
+        .or(global_opts.data_dir.as_deref())
+        .context("Either <DATA_DIR> or --data-dir must be specified")?;
+    let node_dir = data_dir
+        .canonicalize()
+        .with_context(|| format!("Data directory does not exist: {}", data_dir.display()))?;
+    let (Some(base_dir), Some(node_name)) = (node_dir.parent(), node_dir.file_name()) else {
+        bail!("Invalid data directory: {}", node_dir.display());
+    };
+
+    let (use_multi_db_layout, mut partition_ids) = discover_partitions(&node_dir)?;
+    if let Some(id) = cmd.partition_id {
+        let partition_id = PartitionId::new_unchecked(id);
+        if !partition_ids.contains(&partition_id) {
+            bail!(
+                "Partition {partition_id} not found in the data directory {}",
+                node_dir.display()
+            );
+        }
+        partition_ids = vec![partition_id];
+    }
+    if partition_ids.is_empty() {
+        bail!(
+            "No partition stores found in the data directory {}",
+            node_dir.display()
+        );
+    }
+
+    c_warn!(
+        "Opening the partition store in READ-ONLY mode. If a Restate server currently has this \
+         data directory open, the queries only see the data as of opening it and miss whatever \
+         the server has not flushed yet. Use this command when the server is fully stopped."
+    );
+    confirm_or_exit("Is the Restate server owning this data directory stopped?")?;
+
+    // The partition store resolves its databases relative to the node directory,
+    // i.e. `<base-dir>/<node-name>`.
+    let mut config = Configuration::default();
+    config.common.set_base_dir(base_dir);
+    config.common.set_node_name(node_name.to_string_lossy());
+    config
+        .worker
+        .storage
+        .set_rocksdb_memory_budget(NonZeroByteCount::new(cmd.rocksdb_memory_budget));
+
+    set_current_config(config.clone());
+
+    let tc = TaskCenterBuilder::default()
+        .default_runtime_handle(tokio::runtime::Handle::current())
+        .options(config.common.clone())
+        .build()
+        .expect("task center builds")
+        .into_handle();
+
+    let metadata_builder = MetadataBuilder::default();
+    let metadata = metadata_builder.to_metadata();
+    let metadata_manager = MetadataManager::new(
+        metadata_builder,
+        MetadataStoreClient::new(OfflineMetadataStore, None),
+    );
+    tc.try_set_global_metadata(metadata);
+
+    let task_center = tc.clone();
+    async move {
+        let _upkeep = ClockUpkeep::start().expect("clock upkeep starts");
+        spawn_metadata_manager(metadata_manager)?;
+
+        let result = run(cmd, use_multi_db_layout, partition_ids).await;
+
+        task_center.shutdown_node("completed", 0).await;
+        result
+    }
+    .in_tc(&tc)
+    .await
+}
+
+async fn run(
+    cmd: &Sql,
+    use_multi_db_layout: bool,
+    partition_ids: Vec<PartitionId>,
+) -> anyhow::Result<()> {
+    c_println!(
+        "Found {} partition(s): {}",
+        partition_ids.len(),
+        partition_ids
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    RocksDbManager::init();
+    let manager =
+        PartitionStoreManager::create_with_open_mode(use_multi_db_layout, OpenMode::ReadOnly)
+            .await
+            .context("failed to create the partition store manager")?;
+
+    let mut partitions = Vec::with_capacity(partition_ids.len());
+    for partition_id in partition_ids {
+        // The key range argument is irrelevant: the column family exists already, so the store is
+        // opened as-is and the real range is read back from it.
+        let store = manager
+            .open(&Partition::new(partition_id, KeyRange::FULL), None)
+            .await
+            .with_context(|| format!("failed to open partition {partition_id}"))?;
+        let range = store.partition_key_range();
+        partitions.push((partition_id, Partition::new(partition_id, range)));
+    }
+
+    let config = Configuration::pinned();
+    let query_context = QueryContext::create(
+        &config.admin.query_engine,
+        PartitionTables::new(
+            LocalPartitions(Arc::new(partitions)),
+            manager,
+            RemoteScannerManager::local_only(restate_core::Metadata::current()),
+        ),
+    )
+    .await
+    .context("failed to build the query context")?;
+
+    match (&cmd.query, cmd.listen) {
+        (Some(query), _) => repl::run_query(&query_context, query).await,
+        (None, Some(addr)) => server::run_server(query_context, addr).await,
+        // Never write the history into the data directory of the node
+        (None, None) => repl::run_repl(&query_context, None).await,
+    }
+}
+
+/// Finds the partitions stored in the node directory and whether they use the per-partition
+/// database layout (`db-<partition_id>/`) or the single database layout (`db/`).
+fn discover_partitions(node_dir: &Path) -> anyhow::Result<(bool, Vec<PartitionId>)> {
+    let db_name = DatabaseKind::PartitionStore.db_name();
+
+    let mut partitions: Vec<PartitionId> = std::fs::read_dir(node_dir)
+        .with_context(|| format!("Failed to read data directory {}", node_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_str()?
+                .strip_prefix(db_name)?
+                .strip_prefix('-')?
+                .parse::<u16>()
+                .ok()
+        })
+        .map(PartitionId::new_unchecked)
+        .collect();
+    if !partitions.is_empty() {
+        partitions.sort();
+        return Ok((true, partitions));
+    }
+
+    let db_path = node_dir.join(db_name);
+    if !db_path.exists() {
+        bail!(
+            "Could not find a partition store in {}. Is this a Restate node data directory?",
+            node_dir.display()
+        );
+    }
+    let cf_names = DB::list_cf(&Options::default(), &db_path)
+        .context("Failed to list column families. Is this a valid RocksDB database?")?;
+    partitions = cf_names
+        .iter()
+        .filter_map(|name| name.strip_prefix(PARTITION_CF_PREFIX)?.parse::<u16>().ok())
+        .map(PartitionId::new_unchecked)
+        .collect();
+    partitions.sort();
+    Ok((false, partitions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovers_per_partition_databases() {
+        let node_dir = tempfile::tempdir().unwrap();
+        for dir in ["db-3", "db-0", "db-invalid", "local-metadata-store"] {
+            std::fs::create_dir(node_dir.path().join(dir)).unwrap();
+        }
+        // Only directories are databases
+        std::fs::write(node_dir.path().join("db-5"), b"").unwrap();
+
+        let (use_multi_db_layout, partitions) = discover_partitions(node_dir.path()).unwrap();
+
+        assert!(use_multi_db_layout);
+        assert_eq!(
+            partitions,
+            vec![PartitionId::new_unchecked(0), PartitionId::new_unchecked(3)]
+        );
+    }
+
+    #[test]
+    fn discovers_partitions_of_single_database() {
+        let node_dir = tempfile::tempdir().unwrap();
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(
+            &opts,
+            node_dir.path().join(DatabaseKind::PartitionStore.db_name()),
+            ["data-2", "data-1", "data-invalid", "other"],
+        )
+        .unwrap();
+        drop(db);
+
+        let (use_multi_db_layout, partitions) = discover_partitions(node_dir.path()).unwrap();
+
+        assert!(!use_multi_db_layout);
+        assert_eq!(
+            partitions,
+            vec![PartitionId::new_unchecked(1), PartitionId::new_unchecked(2)]
+        );
+    }
+
+    #[test]
+    fn fails_without_partition_store() {
+        let node_dir = tempfile::tempdir().unwrap();
+
+        assert!(discover_partitions(node_dir.path()).is_err());
+    }
+}
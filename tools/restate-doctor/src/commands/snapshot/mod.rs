@@ -54,8 +54,8 @@ use restate_types::nodes_config::{ClusterFingerprint, NodesConfiguration};
 use restate_types::partition_table::Partition;
 use restate_types::sharding::KeyRange;
 
-mod repl;
-mod server;
+pub(crate) mod repl;
+pub(crate) mod server;
 
 /// Run SQL queries against Restate partition snapshots from a repository.
 #[derive(Run, Parser, Collect, Clone)]
@@ -139,7 +139,7 @@ impl SnapshotArgs {
 
 /// A fixed set of locally-opened partitions handed to the DataFusion query layer.
 #[derive(Clone, Debug)]
-struct LocalPartitions(Arc<Vec<(PartitionId, Partition)>>);
+pub(crate) struct LocalPartitions(pub(crate) Arc<Vec<(PartitionId, Partition)>>);
 
 #[async_trait]
 impl SelectPartitions for LocalPartitions {
@@ -152,7 +152,7 @@ impl SelectPartitions for LocalPartitions {
 /// directly into the in-process metadata manager and never needs durable metadata, so reads are
 /// always empty and writes are accepted as no-ops.
 #[derive(Debug, Default)]
-struct OfflineMetadataStore;
+pub(crate) struct OfflineMetadataStore;
 
 #[async_trait]
 impl MetadataStore for OfflineMetadataStore {
@@ -334,7 +334,10 @@ async fn run(
     match (&args.query, args.listen) {
         (Some(query), _) => repl::run_query(&query_context, query).await,
         (None, Some(addr)) => server::run_server(query_context, addr).await,
-        (None, None) => repl::run_repl(&query_context, &args.cache_dir).await,
+        (None, None) => {
+            let history_path = args.cache_dir.join(".snapshot-debugger-history");
+            repl::run_repl(&query_context, Some(&history_path)).await
+        }
     }
 }
 
@@ -69,11 +69,12 @@ pub(crate) async fn run_query(ctx: &QueryContext, query: &str) -> Result<()> {
     Ok(())
 }
 
-/// Starts the interactive SQL terminal, persisting history under the data directory.
-pub(crate) async fn run_repl(ctx: &QueryContext, data_dir: &Path) -> Result<()> {
-    let history_path = data_dir.join(".snapshot-debugger-history");
+/// Starts the interactive SQL terminal, persisting history to `history_path` if given.
+pub(crate) async fn run_repl(ctx: &QueryContext, history_path: Option<&Path>) -> Result<()> {
     let mut editor = DefaultEditor::new()?;
-    let _ = editor.load_history(&history_path);
+    if let Some(history_path) = history_path {
+        let _ = editor.load_history(history_path);
+    }
 
     c_println!("Connected. Enter SQL queries terminated by ';'. Type \\q or Ctrl-D to exit.");
     c_println!("Tip: `SELECT table_name FROM information_schema.tables` lists available tables.");
@@ -115,6 +116,8 @@ pub(crate) async fn run_repl(ctx: &QueryContext, data_dir: &Path) -> Result<()>
         }
     }
 
-    let _ = editor.save_history(&history_path);
+    if let Some(history_path) = history_path {
+        let _ = editor.save_history(history_path);
+    }
     Ok(())
 }
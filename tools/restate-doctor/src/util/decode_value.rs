@@ -13,6 +13,8 @@
 //! This module provides utilities to decode values stored in the partition-store
 //! using the appropriate decoder for each table type.
 
+use std::borrow::Cow;
+
 use bilrost::OwnedMessage;
 
 use restate_limiter::RuleBook;
@@ -56,6 +58,8 @@ mod fsm_variable {
     pub const RULE_BOOK: u64 = 9;
     /// *Since v1.7.0*
     pub const STATE_MACHINE_FEATURES: u64 = 10;
+    /// *Since v1.7.0*
+    pub const TAGGED_STATE_VALUES_CURSOR: u64 = 11;
 }
 
 /// Result of decoding a value, including codec metadata
@@ -145,7 +149,8 @@ impl DecodedValue {
 ///
 /// Different tables use different encoding schemes:
 /// - Most tables use protobuf with a StorageCodec wrapper (1-byte codec discriminant + protobuf)
-/// - State table stores raw user bytes (no codec)
+/// - State table stores raw user bytes, tagged with a codec discriminant since
+///   `StorageVersion::TaggedStateValues`
 /// - VQueue tables use bilrost encoding (no codec discriminant)
 /// - FSM table uses different types based on the state_id in the key
 pub fn decode_value(key_kind: KeyKind, key: &[u8], value: &[u8]) -> DecodedValue {
@@ -153,6 +158,27 @@ pub fn decode_value(key_kind: KeyKind, key: &[u8], value: &[u8]) -> DecodedValue
         return DecodedValue::empty();
     }
 
+    // Journal entries sealed by encryption at rest carry the `Encrypted` codec tag. Whether a
+    // state value is tagged depends on the partition's storage version, so they are shown as-is.
+    let opened = match (key_kind, value.split_first()) {
+        (KeyKind::JournalV2, Some((&tag, sealed)))
+            if tag == u8::from(StorageCodecKind::Encrypted) =>
+        {
+            match restate_encryption::open(sealed) {
+                Ok(opened) => Cow::Owned(opened),
+                Err(err) => {
+                    return DecodedValue::error(
+                        Some(StorageCodecKind::Encrypted),
+                        value.len() - 1,
+                        format!("cannot decrypt value: {err}"),
+                    );
+                }
+            }
+        }
+        _ => Cow::Borrowed(value),
+    };
+    let value = opened.as_ref();
+
     match key_kind {
         // Raw bytes - user state, no decoding
         KeyKind::State | KeyKind::ScopedState => DecodedValue::raw_bytes(value.len()),
@@ -446,6 +472,9 @@ fn decode_fsm_value(key: &[u8], value: &[u8]) -> DecodedValue {
         fsm_variable::STATE_MACHINE_FEATURES => {
             decode_fsm_storage_codec::<PersistedFeatures>(value, codec, payload_size)
         }
+        fsm_variable::TAGGED_STATE_VALUES_CURSOR => {
+            decode_fsm_sequence_number(value, codec, payload_size, "TaggedStateValuesCursor")
+        }
         unknown => DecodedValue::decoded(
             codec,
             payload_size,
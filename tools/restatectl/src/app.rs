@@ -27,6 +27,7 @@ use crate::commands::sql::SqlOpts;
 use crate::commands::status::ClusterStatusOpts;
 use crate::commands::storage::Storage;
 use crate::connection::ConnectionInfo;
+use crate::util::FabricTlsOpts;
 
 /// Restate Cluster Administration Tool
 ///
@@ -44,6 +45,8 @@ pub struct CliApp {
     pub connection: ConnectionInfo,
     #[clap(flatten)]
     pub common_opts: CommonOpts,
+    #[clap(flatten)]
+    pub fabric_tls: FabricTlsOpts,
     #[clap(subcommand)]
     pub cmd: Command,
 }
@@ -90,6 +93,7 @@ pub enum Command {
     Storage(Storage),
 }
 
-fn init(common_opts: &CommonOpts) {
+fn init(common_opts: &CommonOpts, fabric_tls: &FabricTlsOpts) {
     CliContext::new(common_opts.clone()).set_as_global();
+    fabric_tls.set_as_global();
 }
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 - 2026 Restate Software, Inc., Restate GmbH.
+// All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use cling::prelude::*;
+
+use restate_cli_util::c_println;
+use restate_types::PlainNodeId;
+use restate_types::nodes_config::StorageState;
+
+use crate::commands::node::storage_state::update_storage_state;
+use crate::connection::ConnectionInfo;
+
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "decommission")]
+pub struct DecommissionOpts {
+    /// A node-id or a list of node-ids (comma-separated) to decommission
+    #[arg(long, required = true, visible_alias = "node", value_delimiter = ',')]
+    nodes: Vec<PlainNodeId>,
+}
+
+async fn decommission(connection: &ConnectionInfo, opts: &DecommissionOpts) -> anyhow::Result<()> {
+    update_storage_state(connection, &opts.nodes, StorageState::Draining, false).await?;
+
+    c_println!(
+        "The cluster controller is moving the data off the decommissioned log-servers. Once they \
+        are not part of any nodeset anymore, their storage-state changes to `disabled`. Use \
+        `restatectl node storage-state` to follow the progress."
+    );
+    Ok(())
+}
@@ -93,6 +93,7 @@ impl<'a, 'b> DisableNodeChecker<'a, 'b> {
             StorageState::Disabled => return Ok(()),
             // we need to check whether this node is no longer part of any known node sets
             StorageState::ReadOnly
+            | StorageState::Draining
             | StorageState::Gone
             | StorageState::DataLoss
             | StorageState::Provisioning => {}
@@ -8,6 +8,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+mod decommission;
 pub mod disable_node_checker;
 pub mod list_nodes;
 mod remove_nodes;
@@ -35,6 +36,11 @@ pub enum Nodes {
     /// certain that the specified nodes are no longer part of any node sets, not members of the
     /// metadata cluster nor required to run partition processors.
     Remove(remove_nodes::RemoveNodesOpts),
+    /// Decommissions the given log-server/s. Their data is moved to other log-servers, after
+    /// which they are disabled.
+    Decommission(decommission::DecommissionOpts),
+    /// Print the storage-state of the log-servers and the progress of their decommissioning
+    StorageState(storage_state::ListOpts),
     /// [dangerous] low-level unprotected log-server's storage-state manipulation
     SetStorageState(storage_state::SetOpts),
     /// Sets the state of a worker
@@ -12,19 +12,28 @@ use std::collections::HashMap;
 
 use anyhow::Context;
 use cling::prelude::*;
+use itertools::Itertools;
 
 use crate::commands::node::update_state;
+use crate::commands::replicated_loglet::render_storage_state;
 use crate::connection::ConnectionInfo;
+use restate_cli_util::_comfy_table::{Cell, Table};
+use restate_cli_util::ui::console::StyledTable;
 use restate_cli_util::{CliContext, c_println};
 use restate_metadata_store::MetadataStoreClient;
 use restate_metadata_store::protobuf::metadata_proxy_svc::client::MetadataStoreProxy;
+use restate_types::Versioned;
 use restate_types::config::MetadataClientOptions;
 use restate_types::logs::metadata::ProviderKind;
 use restate_types::nodes_config::{Role, StorageState};
-use restate_types::replicated_loglet::logserver_candidate_filter;
+use restate_types::replicated_loglet::{ReplicatedLogletParams, logserver_candidate_filter};
 use restate_types::replication::{NodeSetChecker, NodeSetSelector, NodeSetSelectorOptions};
 use restate_types::{GenerationalNodeId, PlainNodeId};
 
+#[derive(Run, Parser, Collect, Clone, Debug)]
+#[cling(run = "list_storage_states")]
+pub struct ListOpts;
+
 #[derive(Run, Parser, Collect, Clone, Debug)]
 #[cling(run = "set_storage_state")]
 pub struct SetOpts {
@@ -38,22 +47,96 @@ pub struct SetOpts {
     force: bool,
 }
 
+async fn list_storage_states(connection: &ConnectionInfo) -> anyhow::Result<()> {
+    let nodes_config = connection.get_nodes_configuration().await?;
+    let logs = connection.get_logs().await?;
+
+    // (open, sealed) replicated loglets whose nodeset includes the node
+    let mut loglets: HashMap<PlainNodeId, (usize, usize)> = HashMap::new();
+    for (_, chain) in logs.iter() {
+        for segment in chain.iter() {
+            if segment.config.kind != ProviderKind::Replicated {
+                continue;
+            }
+            let params = ReplicatedLogletParams::deserialize_from(segment.config.params.as_bytes())
+                .expect("loglet config is deserializable");
+            for node_id in params.nodeset.iter() {
+                let (open, sealed) = loglets.entry(*node_id).or_default();
+                if segment.tail_lsn.is_none() {
+                    *open += 1;
+                } else {
+                    *sealed += 1;
+                }
+            }
+        }
+    }
+
+    let mut servers_table = Table::new_styled();
+    let header = vec![
+        "NODE",
+        "GEN",
+        "STORAGE-STATE",
+        "OPEN-LOGLETS",
+        "SEALED-LOGLETS",
+        "DECOMMISSION",
+    ];
+    servers_table.set_styled_header(header);
+    for (node_id, config) in nodes_config.iter().sorted_by(|a, b| Ord::cmp(&a.0, &b.0)) {
+        if !config.has_role(Role::LogServer)
+            && config.log_server_config.storage_state.is_provisioning()
+        {
+            continue;
+        }
+        let (open, sealed) = loglets.get(&node_id).copied().unwrap_or_default();
+        let progress = match config.log_server_config.storage_state {
+            StorageState::Draining if open > 0 => "reconfiguring open loglets",
+            StorageState::Draining if sealed > 0 => "evacuating sealed loglets",
+            StorageState::Draining => "waiting to be disabled",
+            _ => "-",
+        };
+        servers_table.add_row(vec![
+            Cell::new(node_id.to_string()),
+            Cell::new(config.current_generation.to_string()),
+            render_storage_state(config.log_server_config.storage_state),
+            Cell::new(open),
+            Cell::new(sealed),
+            Cell::new(progress),
+        ]);
+    }
+    c_println!("Node configuration {}", nodes_config.version());
+    c_println!("Log chain {}", logs.version());
+    c_println!("{}", servers_table);
+
+    Ok(())
+}
+
 async fn set_storage_state(connection: &ConnectionInfo, opts: &SetOpts) -> anyhow::Result<()> {
-    if opts.nodes.is_empty() {
+    update_storage_state(connection, &opts.nodes, opts.storage_state, opts.force).await
+}
+
+/// Transitions the log-servers `nodes` into `target_state` after checking that the transition is
+/// safe, unless `force` is set.
+pub(super) async fn update_storage_state(
+    connection: &ConnectionInfo,
+    nodes: &[PlainNodeId],
+    target_state: StorageState,
+    force: bool,
+) -> anyhow::Result<()> {
+    if nodes.is_empty() {
         return Err(anyhow::anyhow!("--node/--nodes is required"));
     }
 
     let nodes_config = connection.get_nodes_configuration().await?;
 
     let mut current_states: HashMap<GenerationalNodeId, StorageState> =
-        HashMap::with_capacity(opts.nodes.len());
-    for node_id in opts.nodes.iter().cloned() {
+        HashMap::with_capacity(nodes.len());
+    for node_id in nodes.iter().cloned() {
         let node = nodes_config.find_node_by_id(node_id)?;
         let current_state = node.log_server_config.storage_state;
         let current_generation = node.current_generation;
         current_states.insert(current_generation, current_state);
 
-        if !opts.force {
+        if !force {
             if !node.has_role(Role::LogServer) {
                 return Err(anyhow::anyhow!(
                     "Node {} doesn't have `log-server` role. Its last-observed generation {} has roles: [{}]",
@@ -63,7 +146,7 @@ async fn set_storage_state(connection: &ConnectionInfo, opts: &SetOpts) -> anyho
                 ));
             }
 
-            let safe = match (current_state, opts.storage_state) {
+            let safe = match (current_state, target_state) {
                 (from, to) if from == to => true,
                 (StorageState::Provisioning, StorageState::Disabled) => {
                     // one can do that, but the node will never be a log-server in the future
@@ -89,7 +172,21 @@ async fn set_storage_state(connection: &ConnectionInfo, opts: &SetOpts) -> anyho
                     // - removed from all nodesets (chains trimmed)
                     false
                 }
+                (StorageState::ReadWrite | StorageState::ReadOnly, StorageState::Draining) => {
+                    // decommission. Same as ReadOnly, but the cluster controller moves the data of
+                    // this node elsewhere.
+                    true
+                }
                 (StorageState::ReadOnly, _) => false,
+                (StorageState::Draining, StorageState::ReadWrite | StorageState::ReadOnly) => {
+                    // abort decommissioning, nodesets that dropped this node stay as they are.
+                    true
+                }
+                (StorageState::Draining, StorageState::Disabled) => {
+                    // the cluster controller disables the node once it's removed from all nodesets
+                    false
+                }
+                (StorageState::Draining, _) => false,
                 (StorageState::ReadWrite, StorageState::Provisioning) => false,
                 (StorageState::ReadWrite, _) => false,
                 (StorageState::DataLoss, StorageState::ReadWrite) => {
@@ -113,14 +210,14 @@ async fn set_storage_state(connection: &ConnectionInfo, opts: &SetOpts) -> anyho
             if !safe {
                 return Err(anyhow::anyhow!(
                     "This node is currently in `{current_state}` storage-state. Transitioning into `{}` is unsafe.",
-                    opts.storage_state
+                    target_state
                 ));
             }
         }
     }
 
     // run safety checks
-    if !opts.force && !opts.storage_state.should_write_to() {
+    if !force && !target_state.should_write_to() {
         let logs = connection.get_logs().await?;
         let provider = &logs.configuration().default_provider;
         if let ProviderKind::Replicated = provider.kind() {
@@ -133,7 +230,7 @@ async fn set_storage_state(connection: &ConnectionInfo, opts: &SetOpts) -> anyho
                 replication_property,
                 |node_id, config| {
                     // skip our selection of nodes from being candidate
-                    if opts.nodes.contains(&node_id) {
+                    if nodes.contains(&node_id) {
                         false
                     } else {
                         logserver_candidate_filter(node_id, config)
@@ -170,7 +267,7 @@ async fn set_storage_state(connection: &ConnectionInfo, opts: &SetOpts) -> anyho
             update_state(
                 &metadata_store_client,
                 &current_states,
-                opts.storage_state,
+                target_state,
                 |node_config| &mut node_config.log_server_config.storage_state,
             )
             .await
@@ -182,7 +279,7 @@ async fn set_storage_state(connection: &ConnectionInfo, opts: &SetOpts) -> anyho
             "{} storage-state has been updated from {} to {}",
             node.as_plain(),
             old_state,
-            opts.storage_state,
+            target_state,
         );
     }
     Ok(())
@@ -45,6 +45,9 @@ pub struct DigestOpts {
     /// Only print under-replicated offsets
     #[arg(long, short)]
     under_replicated_only: bool,
+    /// Verify record checksums on the log servers and report corrupt copies as `C`
+    #[arg(long)]
+    verify_checksums: bool,
 }
 
 async fn get_digest(connection: &ConnectionInfo, opts: &DigestOpts) -> anyhow::Result<()> {
@@ -130,6 +133,7 @@ async fn get_digest(connection: &ConnectionInfo, opts: &DigestOpts) -> anyhow::R
             loglet_id: opts.loglet_id.into(),
             from_offset,
             to_offset,
+            verify_checksums: opts.verify_checksums,
         };
         let mut client = new_log_server_client(channel.clone(), &CliContext::get().network);
         let digest = match client.get_digest(req).await {
@@ -170,9 +174,15 @@ async fn get_digest(connection: &ConnectionInfo, opts: &DigestOpts) -> anyhow::R
         status_row.push(Cell::new(offset.to_string()));
         for node in nodeset.iter() {
             if let Some(status) = responses.get(node) {
-                status_row.push(Cell::new(status.to_string()));
-                if let RecordStatus::Exists = status {
-                    checker.set_attribute(*node, true);
+                match status {
+                    RecordStatus::Exists => {
+                        status_row.push(Cell::new(status.to_string()));
+                        checker.set_attribute(*node, true);
+                    }
+                    RecordStatus::Corrupted => {
+                        status_row.push(Cell::new(status.to_string()).fg(Color::Red));
+                    }
+                    _ => status_row.push(Cell::new(status.to_string())),
                 }
             } else if digests.is_known(node) {
                 // record doesn't exist on this node
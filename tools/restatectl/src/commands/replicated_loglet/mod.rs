@@ -28,11 +28,12 @@ pub enum ReplicatedLoglet {
     ListServers(list_servers::ListServersOpts),
 }
 
-fn render_storage_state(state: StorageState) -> Cell {
+pub(crate) fn render_storage_state(state: StorageState) -> Cell {
     let cell = Cell::new(state);
     match state {
         StorageState::ReadWrite => cell.fg(Color::Green),
         StorageState::ReadOnly => cell.fg(Color::Yellow),
+        StorageState::Draining => cell.fg(Color::Yellow),
         StorageState::Gone => cell.fg(Color::Yellow),
         StorageState::DataLoss => cell.fg(Color::Red),
         StorageState::Provisioning => cell.fg(Color::Reset),
@@ -10,20 +10,119 @@
 
 use std::{
     fmt::{self, Display},
+    num::NonZeroUsize,
+    path::PathBuf,
     str::FromStr,
+    sync::OnceLock,
+    time::Duration,
 };
+
+use cling::{Collect, prelude::Parser};
 use tonic::transport::Channel;
 
 use restate_cli_util::CliContext;
 use restate_core::network::net_util::{DNSResolution, create_tonic_channel};
 use restate_types::{
+    config::FabricTlsOptions,
     logs::metadata::ProviderConfiguration,
     net::address::{AdvertisedAddress, GrpcPort, ListenerPort},
+    net::connect_opts::{CommonClientConnectionOptions, GrpcConnectionOptions},
 };
 
+static FABRIC_TLS: OnceLock<FabricTlsOptions> = OnceLock::new();
+
+/// Mutual TLS options for connecting to clusters that run the node-to-node fabric with TLS.
+#[derive(Clone, Parser, Collect, Debug, Default)]
+pub struct FabricTlsOpts {
+    /// Path to the PEM encoded cluster CA certificate used to verify the nodes.
+    ///
+    /// Enables mutual TLS for all connections to nodes. Requires `--tls-cert` and `--tls-key`.
+    #[clap(
+        long,
+        global = true,
+        env = "RESTATECTL_TLS_CA_CERT",
+        requires_all = ["tls_cert", "tls_key"],
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    pub tls_ca_cert: Option<PathBuf>,
+
+    /// Path to the PEM encoded client certificate presented to the nodes.
+    #[clap(
+        long,
+        global = true,
+        env = "RESTATECTL_TLS_CERT",
+        requires = "tls_ca_cert",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    pub tls_cert: Option<PathBuf>,
+
+    /// Path to the PEM encoded private key of the client certificate.
+    #[clap(
+        long,
+        global = true,
+        env = "RESTATECTL_TLS_KEY",
+        requires = "tls_ca_cert",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    pub tls_key: Option<PathBuf>,
+}
+
+impl FabricTlsOpts {
+    /// Use these options for all channels created via [`grpc_channel`].
+    pub fn set_as_global(&self) {
+        if let (Some(ca_cert), Some(cert), Some(key)) =
+            (&self.tls_ca_cert, &self.tls_cert, &self.tls_key)
+        {
+            let _ = FABRIC_TLS.set(FabricTlsOptions::new(ca_cert, cert, key));
+        }
+    }
+}
+
 pub fn grpc_channel<P: ListenerPort + GrpcPort>(address: AdvertisedAddress<P>) -> Channel {
     let ctx = CliContext::get();
-    create_tonic_channel(address, &ctx.network, DNSResolution::Gai)
+    let options = ConnectionOptions {
+        network: &ctx.network,
+        tls: FABRIC_TLS.get(),
+    };
+    create_tonic_channel(address, &options, DNSResolution::Gai)
+}
+
+/// The CLI network options extended with the fabric TLS settings.
+struct ConnectionOptions<'a, N> {
+    network: &'a N,
+    tls: Option<&'a FabricTlsOptions>,
+}
+
+impl<N: GrpcConnectionOptions> GrpcConnectionOptions for ConnectionOptions<'_, N> {
+    fn message_size_limit(&self) -> NonZeroUsize {
+        self.network.message_size_limit()
+    }
+}
+
+impl<N: CommonClientConnectionOptions> CommonClientConnectionOptions for ConnectionOptions<'_, N> {
+    fn connect_timeout(&self) -> Duration {
+        self.network.connect_timeout()
+    }
+
+    fn request_timeout(&self) -> Option<Duration> {
+        self.network.request_timeout()
+    }
+
+    fn keep_alive_interval(&self) -> Duration {
+        self.network.keep_alive_interval()
+    }
+
+    fn keep_alive_timeout(&self) -> Duration {
+        self.network.keep_alive_timeout()
+    }
+
+    fn http2_adaptive_window(&self) -> bool {
+        self.network.http2_adaptive_window()
+    }
+
+    fn tls(&self) -> Option<&FabricTlsOptions> {
+        self.tls
+    }
 }
 
 pub fn write_default_provider<W: fmt::Write>(